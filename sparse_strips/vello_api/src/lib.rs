@@ -114,7 +114,7 @@
 //!
 //! - Masks
 //! - Filter effects
-//! - Non-isolated blending (this is "supported" by Vello Hybrid, but currently silently ignored)
+//! - Non-isolated compositing (Vello Hybrid supports non-isolated mix modes, but currently silently ignores compose modes other than source-over)
 //! - Blurred rounded rectangles (note that currently this is actually included in the abstraction, despite this status)
 //!
 //! There are currently no such features the other way around (i.e. which only Vello Hybrid supports).
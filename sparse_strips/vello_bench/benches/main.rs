@@ -5,7 +5,7 @@
 #![allow(dead_code, reason = "Might be unused on platforms not supporting SIMD")]
 
 use criterion::{criterion_group, criterion_main};
-use vello_bench::{fine, flatten, glyph, integration, strip, tile};
+use vello_bench::{fine, flatten, glyph, integration, scene, strip, tile};
 
 criterion_group!(fine_solid, fine::fill);
 criterion_group!(fine_strip, fine::strip);
@@ -15,17 +15,23 @@ criterion_group!(fine_rounded_blurred_rect, fine::rounded_blurred_rect);
 criterion_group!(fine_blend, fine::blend);
 criterion_group!(fine_image, fine::image);
 criterion_group!(tile, tile::tile);
+criterion_group!(tile_sort, tile::sort);
 criterion_group!(flatten, flatten::flatten);
 criterion_group!(strokes, flatten::strokes);
 criterion_group!(render_strips, strip::render_strips);
 criterion_group!(glyph, glyph::glyph);
+criterion_group!(paragraph, glyph::paragraph);
 criterion_group!(integration_bench, integration::images);
+criterion_group!(fill_rects, scene::fill_rects);
+criterion_group!(wide_tile_widths, scene::wide_tile_widths);
 criterion_main!(
     tile,
+    tile_sort,
     render_strips,
     flatten,
     strokes,
     glyph,
+    paragraph,
     fine_solid,
     fine_strip,
     fine_pack,
@@ -33,5 +39,7 @@ criterion_main!(
     fine_rounded_blurred_rect,
     fine_blend,
     fine_image,
-    integration_bench
+    integration_bench,
+    fill_rects,
+    wide_tile_widths
 );
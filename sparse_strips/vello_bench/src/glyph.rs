@@ -12,8 +12,11 @@ use vello_common::fearless_simd::Level;
 use vello_common::glyph::{Glyph, GlyphCaches, GlyphRunBuilder};
 use vello_common::glyph::{GlyphRenderer, GlyphType};
 use vello_common::kurbo::Affine;
+use vello_common::paint::ImageId;
 use vello_common::peniko::Fill;
+use vello_common::peniko::color::palette::css::BLACK;
 use vello_common::strip_generator::{StripGenerator, StripStorage};
+use vello_hybrid::{GlyphAtlas, Scene};
 
 pub fn glyph(c: &mut Criterion) {
     let mut g = c.benchmark_group("glyph");
@@ -33,17 +36,7 @@ pub fn glyph(c: &mut Criterion) {
 
     const TEXT: &str = "The quick brown fox jumps over the lazy dog 0123456789";
 
-    let layout_for = |text: &str, scale: f32| {
-        let mut layout_cx = LayoutContext::new();
-        let mut font_cx = FontContext::new();
-        let mut builder = layout_cx.ranged_builder(&mut font_cx, text, scale, true);
-        builder.push_default(FontFamily::parse("Roboto").unwrap());
-        let mut layout: Layout<Brush> = builder.build(text);
-        let max_advance = Some(WIDTH as f32);
-        layout.break_all_lines(max_advance);
-        layout.align(max_advance, Alignment::Start, AlignmentOptions::default());
-        layout
-    };
+    let layout_for = |text: &str, scale: f32| layout_text(text, scale, WIDTH);
 
     for (hint_name, hint) in [("hinted", true), ("unhinted", false)] {
         g.bench_function(format!("cached_{hint_name}"), |b| {
@@ -106,6 +99,77 @@ pub fn glyph(c: &mut Criterion) {
     });
 }
 
+/// Draw a paragraph into a `vello_hybrid` scene for many frames, with and without a glyph
+/// atlas. With the atlas, the cost per frame stays flat after the first frame, as the glyphs
+/// are no longer rasterized.
+pub fn paragraph(c: &mut Criterion) {
+    let mut g = c.benchmark_group("paragraph");
+
+    const WIDTH: u16 = 512;
+    const HEIGHT: u16 = 256;
+    const TEXT: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod \
+        tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis \
+        nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute \
+        irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla \
+        pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia \
+        deserunt mollit anim id est laborum.";
+
+    let layout = layout_text(TEXT, 1.0, WIDTH);
+    let draw = |scene: &mut Scene| {
+        scene.set_paint(BLACK);
+        for line in layout.lines() {
+            for item in line.items() {
+                if let PositionedLayoutItem::GlyphRun(glyph_run) = item {
+                    let run = glyph_run.run();
+                    scene
+                        .glyph_run(run.font())
+                        .font_size(run.font_size())
+                        .hint(true)
+                        .fill_glyphs(positioned_glyphs(&glyph_run));
+                }
+            }
+        }
+    };
+
+    for (name, glyph_atlas) in [("paths", false), ("atlas", true)] {
+        g.bench_function(name, |b| {
+            let mut scene = Scene::new(WIDTH, HEIGHT);
+            if glyph_atlas {
+                scene.set_glyph_atlas(Some(GlyphAtlas::new()));
+            }
+            // Warm up the caches, and pretend to upload the rasterized glyphs.
+            draw(&mut scene);
+            let mut next_id = 0;
+            if let Some(glyph_atlas) = scene.glyph_atlas_mut() {
+                glyph_atlas.upload_pending(|_| {
+                    next_id += 1;
+                    ImageId::new(next_id)
+                });
+            }
+
+            b.iter(|| {
+                scene.reset();
+                draw(&mut scene);
+                std::hint::black_box(&scene);
+            });
+        });
+    }
+
+    g.finish();
+}
+
+fn layout_text(text: &str, scale: f32, width: u16) -> Layout<Brush> {
+    let mut layout_cx = LayoutContext::new();
+    let mut font_cx = FontContext::new();
+    let mut builder = layout_cx.ranged_builder(&mut font_cx, text, scale, true);
+    builder.push_default(FontFamily::parse("Roboto").unwrap());
+    let mut layout: Layout<Brush> = builder.build(text);
+    let max_advance = Some(width as f32);
+    layout.break_all_lines(max_advance);
+    layout.align(max_advance, Alignment::Start, AlignmentOptions::default());
+    layout
+}
+
 #[derive(Clone, Copy, Default, Debug, PartialEq)]
 struct Brush {}
 
@@ -145,6 +209,29 @@ impl GlyphRenderer for GlyphBenchRenderer {
         unimplemented!()
     }
 
+    fn stroke_glyph_with(
+        &mut self,
+        glyph: vello_common::glyph::PreparedGlyph<'_>,
+        stroke: &vello_common::kurbo::Stroke,
+        _paint: &vello_common::paint::PaintType,
+    ) {
+        // The paint doesn't affect the generated strips.
+        match glyph.glyph_type {
+            GlyphType::Outline(outline_glyph) => {
+                self.strip_generator.generate_stroked_path(
+                    outline_glyph.path,
+                    stroke,
+                    glyph.transform,
+                    Some(128),
+                    &mut self.strip_storage,
+                    None,
+                );
+            }
+            GlyphType::Bitmap(_) => {}
+            GlyphType::Colr(_) => {}
+        }
+    }
+
     fn take_glyph_caches(&mut self) -> GlyphCaches {
         self.glyph_caches.take().unwrap_or_default()
     }
@@ -168,9 +255,20 @@ fn render_glyph_run(
     glyph_run: &GlyphRun<'_, Brush>,
     hint: bool,
 ) {
+    let run = glyph_run.run();
+    renderer
+        .glyph_run(run.font())
+        .font_size(run.font_size())
+        .hint(hint)
+        .fill_glyphs(positioned_glyphs(glyph_run));
+}
+
+fn positioned_glyphs<'a>(
+    glyph_run: &'a GlyphRun<'_, Brush>,
+) -> impl Iterator<Item = Glyph> + Clone + 'a {
     let mut run_x = glyph_run.offset();
     let run_y = glyph_run.baseline();
-    let glyphs = glyph_run.glyphs().map(|glyph| {
+    glyph_run.glyphs().map(move |glyph| {
         let glyph_x = run_x + glyph.x;
         let glyph_y = run_y - glyph.y;
         run_x += glyph.advance;
@@ -180,12 +278,5 @@ fn render_glyph_run(
             x: glyph_x,
             y: glyph_y,
         }
-    });
-
-    let run = glyph_run.run();
-    renderer
-        .glyph_run(run.font())
-        .font_size(run.font_size())
-        .hint(hint)
-        .fill_glyphs(glyphs);
+    })
 }
@@ -12,6 +12,7 @@ pub mod fine;
 pub mod flatten;
 pub mod glyph;
 pub mod integration;
+pub mod scene;
 pub mod strip;
 pub mod tile;
 
@@ -0,0 +1,130 @@
+// Copyright 2025 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Benchmarks for encoding hybrid scenes.
+
+use criterion::Criterion;
+use vello_common::color::palette::css::{BLUE, RED, WHITE};
+use vello_common::kurbo::{Affine, Circle, Point, Rect, Shape};
+use vello_common::peniko::{BlendMode, Compose, Mix};
+use vello_hybrid::{
+    GpuSlotClear, GpuStrip, LoadOp, RenderSettings, RendererBackend, Scene, StripScheduler,
+};
+
+const WIDTH: u16 = 1000;
+const HEIGHT: u16 = 1000;
+
+pub fn fill_rects(c: &mut Criterion) {
+    let mut g = c.benchmark_group("fill_rects");
+
+    // 10,000 small rectangles with fractional edges that don't share any pixels. They are
+    // slightly rotated, since axis-aligned rectangles don't need to be batched.
+    let rects = (0..10_000)
+        .map(|i| {
+            let x = f64::from(i % 100) * 10.0 + 0.5;
+            let y = f64::from(i / 100) * 10.0 + 0.25;
+            Rect::new(x, y, x + 6.0, y + 6.5)
+        })
+        .collect::<Vec<_>>();
+    let transform = Affine::rotate_about(0.02, Point::new(500.0, 500.0));
+
+    g.bench_function("individual", |b| {
+        let mut scene = Scene::new(WIDTH, HEIGHT);
+
+        b.iter(|| {
+            scene.reset();
+            scene.set_transform(transform);
+            for rect in &rects {
+                scene.fill_rect(rect);
+            }
+            std::hint::black_box(&scene);
+        });
+    });
+
+    g.bench_function("batched", |b| {
+        let mut scene = Scene::new(WIDTH, HEIGHT);
+
+        b.iter(|| {
+            scene.reset();
+            scene.set_transform(transform);
+            scene.fill_rects(&rects);
+            std::hint::black_box(&scene);
+        });
+    });
+
+    g.finish();
+}
+
+/// A backend that only counts the scheduled draws.
+#[derive(Default)]
+struct CountingBackend {
+    strips: usize,
+}
+
+impl RendererBackend for CountingBackend {
+    fn clear_slots(&mut self, _: usize, _: &[GpuSlotClear]) {}
+
+    fn render_strips(&mut self, strips: &[GpuStrip], _: usize, _: LoadOp) {
+        self.strips += strips.len();
+    }
+}
+
+pub fn wide_tile_widths(c: &mut Criterion) {
+    let mut g = c.benchmark_group("wide_tile_width");
+
+    // A grid of overlapping circles, every other one of which is drawn into a clipped and
+    // blended layer.
+    let draw = |scene: &mut Scene| {
+        scene.set_paint(WHITE);
+        scene.fill_rect(&Rect::new(0.0, 0.0, f64::from(WIDTH), f64::from(HEIGHT)));
+        for i in 0..400 {
+            let center = (
+                f64::from(i % 20) * 50.0 + 25.0,
+                f64::from(i / 20) * 50.0 + 25.0,
+            );
+            let circle = Circle::new(center, 40.0).to_path(0.1);
+            if i % 2 == 0 {
+                scene.set_paint(BLUE.with_alpha(0.5));
+                scene.fill_path(&circle);
+            } else {
+                scene.push_clip_layer(&circle);
+                scene.push_blend_layer(BlendMode::new(Mix::Multiply, Compose::SrcOver));
+                scene.set_paint(RED);
+                scene.fill_rect(&Rect::from_center_size(center, (60.0, 60.0)));
+                scene.pop_layer();
+                scene.pop_layer();
+            }
+        }
+    };
+
+    for wide_tile_width in [64, 128, 256, 512, 1024] {
+        let settings = RenderSettings {
+            wide_tile_width,
+            ..RenderSettings::default()
+        };
+
+        g.bench_function(format!("encode_{wide_tile_width}"), |b| {
+            let mut scene = Scene::new_with(WIDTH, HEIGHT, settings);
+
+            b.iter(|| {
+                scene.reset();
+                draw(&mut scene);
+                std::hint::black_box(&scene);
+            });
+        });
+
+        g.bench_function(format!("schedule_{wide_tile_width}"), |b| {
+            let mut scene = Scene::new_with(WIDTH, HEIGHT, settings);
+            draw(&mut scene);
+            let mut scheduler = StripScheduler::new(512, &settings);
+
+            b.iter(|| {
+                let mut backend = CountingBackend::default();
+                scheduler.schedule(&scene, &[], &mut backend).unwrap();
+                std::hint::black_box(backend.strips);
+            });
+        });
+    }
+
+    g.finish();
+}
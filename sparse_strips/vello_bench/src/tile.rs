@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use crate::data::get_data_items;
-use criterion::{BenchmarkId, Criterion};
+use criterion::{BatchSize, BenchmarkId, Criterion};
 use vello_common::flatten::Line;
 use vello_common::tile::Tiles;
 use vello_cpu::Level;
@@ -30,3 +30,27 @@ pub fn tile(c: &mut Criterion) {
     run_tile_benchmark(c, "tile_aaa", Tiles::make_tiles_analytic_aa);
     run_tile_benchmark(c, "tile_msaa", Tiles::make_tiles_msaa);
 }
+
+pub fn sort(c: &mut Criterion) {
+    let mut g = c.benchmark_group("tile_sort");
+    g.sample_size(50);
+
+    for item in get_data_items() {
+        let tiles = item.unsorted_tiles();
+        g.bench_with_input(
+            BenchmarkId::from_parameter(&item.name),
+            &tiles,
+            |b, tiles| {
+                b.iter_batched(
+                    || tiles.clone(),
+                    |mut tiles| {
+                        tiles.sort_tiles();
+                        tiles
+                    },
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+    g.finish();
+}
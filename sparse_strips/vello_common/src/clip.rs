@@ -3,11 +3,11 @@
 
 //! Managing clipping state.
 
-use crate::kurbo::{Affine, BezPath};
+use crate::kurbo::{Affine, BezPath, Rect};
 use crate::strip::Strip;
 use crate::strip_generator::{GenerationMode, StripGenerator, StripStorage};
 use crate::tile::Tile;
-use crate::util::normalized_mul_u8x16;
+use crate::util::{normalized_mul_u8x16, preserves_axis_alignment};
 use alloc::vec;
 use alloc::vec::Vec;
 use fearless_simd::{Level, Simd, SimdBase, dispatch, u8x16};
@@ -17,6 +17,9 @@ use peniko::Fill;
 struct ClipData {
     alpha_start: u32,
     strip_start: u32,
+    /// The clip in device space and the aliasing threshold it was generated with, if it is an
+    /// axis-aligned rectangle.
+    rect: Option<(Rect, Option<u8>)>,
 }
 
 impl ClipData {
@@ -69,6 +72,13 @@ impl ClipContext {
         self.temp_storage.clear();
     }
 
+    /// Release the capacity that is not used by the current clip paths.
+    pub fn shrink_to_fit(&mut self) {
+        self.clip_stack.shrink_to_fit();
+        self.storage.shrink_to_fit();
+        self.temp_storage.shrink_to_fit();
+    }
+
     /// Get the data of the current clip path.
     #[inline]
     pub fn get(&self) -> Option<PathDataRef<'_>> {
@@ -86,6 +96,81 @@ impl ClipContext {
         fill_rule: Fill,
         transform: Affine,
         aliasing_threshold: Option<u8>,
+    ) {
+        self.push_with(None, |storage, existing_clip| {
+            strip_generator.generate_filled_path(
+                clip_path,
+                fill_rule,
+                transform,
+                aliasing_threshold,
+                storage,
+                existing_clip,
+            );
+        });
+    }
+
+    /// Push a new clip rectangle to the stack.
+    ///
+    /// This is the same as pushing the outline of the rectangle with
+    /// [`ClipContext::push_clip`], but uses [`StripGenerator::generate_filled_rect`], so it is
+    /// considerably cheaper if the rectangle stays axis-aligned under `transform`.
+    ///
+    /// Nested axis-aligned clip rectangles are intersected as rectangles, so the strips of the
+    /// intersection are generated directly instead of intersecting the strips of each level.
+    /// This is also more accurate where the edges of several rectangles share a pixel, as its
+    /// coverage is the area of the intersection rather than the product of the coverages. A
+    /// clip path, or a rectangle that doesn't stay axis-aligned, ends the chain of rectangles.
+    #[inline]
+    pub fn push_clip_rect(
+        &mut self,
+        clip_rect: &Rect,
+        strip_generator: &mut StripGenerator,
+        transform: Affine,
+        aliasing_threshold: Option<u8>,
+    ) {
+        let device_rect = if preserves_axis_alignment(&transform) {
+            let device_rect = transform.transform_rect_bbox(*clip_rect);
+            match self.clip_stack.last() {
+                None => Some(device_rect),
+                Some(ClipData {
+                    rect: Some((parent, threshold)),
+                    ..
+                }) if *threshold == aliasing_threshold => Some(device_rect.intersect(*parent)),
+                Some(_) => None,
+            }
+        } else {
+            None
+        };
+
+        if let Some(device_rect) = device_rect {
+            self.push_with(Some((device_rect, aliasing_threshold)), |storage, _| {
+                strip_generator.generate_filled_rect(
+                    &device_rect,
+                    Affine::IDENTITY,
+                    aliasing_threshold,
+                    storage,
+                    None,
+                );
+            });
+        } else {
+            self.push_with(None, |storage, existing_clip| {
+                strip_generator.generate_filled_rect(
+                    clip_rect,
+                    transform,
+                    aliasing_threshold,
+                    storage,
+                    existing_clip,
+                );
+            });
+        }
+    }
+
+    /// Push the clip generated by `generate`, which is passed the storage to generate the strips
+    /// into and the current clip to intersect them with.
+    fn push_with(
+        &mut self,
+        rect: Option<(Rect, Option<u8>)>,
+        generate: impl FnOnce(&mut StripStorage, Option<PathDataRef<'_>>),
     ) {
         self.temp_storage.clear();
 
@@ -95,6 +180,7 @@ impl ClipContext {
         let clip_data = ClipData {
             alpha_start,
             strip_start,
+            rect,
         };
 
         let existing_clip = self
@@ -102,14 +188,7 @@ impl ClipContext {
             .last()
             .map(|c| c.to_path_data_ref(&self.storage));
 
-        strip_generator.generate_filled_path(
-            clip_path,
-            fill_rule,
-            transform,
-            aliasing_threshold,
-            &mut self.temp_storage,
-            existing_clip,
-        );
+        generate(&mut self.temp_storage, existing_clip);
 
         self.storage.extend(&self.temp_storage);
         self.clip_stack.push(clip_data);
@@ -537,12 +616,102 @@ fn should_create_new_strip(
 
 #[cfg(test)]
 mod tests {
-    use crate::clip::{PathDataRef, RowIterator, intersect};
+    use crate::clip::{ClipContext, PathDataRef, RowIterator, intersect};
+    use crate::kurbo::{Affine, Circle, Rect, Shape};
+    use crate::peniko::Fill;
     use crate::strip::Strip;
-    use crate::strip_generator::StripStorage;
+    use crate::strip_generator::{StripGenerator, StripStorage};
     use crate::tile::Tile;
+    use alloc::vec;
     use fearless_simd::Level;
-    use std::vec;
+
+    /// Generate the strips of `rect`, clipped by `clip` if there is one.
+    fn rect_strips(
+        generator: &mut StripGenerator,
+        rect: Rect,
+        clip: Option<PathDataRef<'_>>,
+    ) -> StripStorage {
+        let mut storage = StripStorage::default();
+        generator.generate_filled_rect(&rect, Affine::IDENTITY, None, &mut storage, clip);
+        storage
+    }
+
+    fn assert_clip_eq(clip_context: &ClipContext, expected: &StripStorage) {
+        let clip = clip_context.get().unwrap();
+        assert_eq!(clip.strips, expected.strips.as_slice());
+        assert_eq!(clip.alphas, expected.alphas.as_slice());
+    }
+
+    #[test]
+    fn nested_clip_rects_are_intersected_as_rects() {
+        let mut generator = StripGenerator::new(200, 200, Level::fallback());
+        let mut clip_context = ClipContext::new();
+
+        let mut intersections = vec![];
+        let mut intersection = Rect::new(0.0, 0.0, 200.0, 200.0);
+        for i in 0..10 {
+            let offset = f64::from(i) * 3.25;
+            let rect = Rect::new(5.0 + offset, 3.5, 180.0 - offset, 190.0 - offset * 2.0);
+            // Scaling doesn't break the chain.
+            clip_context.push_clip_rect(
+                &rect.scale_from_origin(0.5),
+                &mut generator,
+                Affine::scale(2.0),
+                None,
+            );
+            intersection = intersection.intersect(rect);
+            intersections.push(intersection);
+
+            // The strips of the clip are those of the intersection, without any further
+            // intersection with the strips of the previous levels.
+            assert_clip_eq(
+                &clip_context,
+                &rect_strips(&mut generator, intersection, None),
+            );
+        }
+
+        // Popping returns to the previous intersections.
+        for intersection in intersections.iter().rev().skip(1) {
+            clip_context.pop_clip();
+            assert_clip_eq(
+                &clip_context,
+                &rect_strips(&mut generator, *intersection, None),
+            );
+        }
+    }
+
+    #[test]
+    fn clip_path_ends_chain_of_clip_rects() {
+        let mut generator = StripGenerator::new(200, 200, Level::fallback());
+        let mut clip_context = ClipContext::new();
+        let outer = Rect::new(10.0, 10.0, 190.0, 190.0);
+        let circle = Circle::new((100.0, 100.0), 70.0).to_path(0.1);
+        let inner = Rect::new(50.5, 20.0, 150.5, 180.0);
+
+        clip_context.push_clip_rect(&outer, &mut generator, Affine::IDENTITY, None);
+        clip_context.push_clip(
+            &circle,
+            &mut generator,
+            Fill::NonZero,
+            Affine::IDENTITY,
+            None,
+        );
+        let mut circle_clip = StripStorage::default();
+        let clip = clip_context.get().unwrap();
+        circle_clip.strips.extend_from_slice(clip.strips);
+        circle_clip.alphas.extend_from_slice(clip.alphas);
+
+        // The inner rectangle has to be intersected with the strips of the circle.
+        clip_context.push_clip_rect(&inner, &mut generator, Affine::IDENTITY, None);
+        let circle_clip = PathDataRef {
+            strips: &circle_clip.strips,
+            alphas: &circle_clip.alphas,
+        };
+        assert_clip_eq(
+            &clip_context,
+            &rect_strips(&mut generator, inner, Some(circle_clip)),
+        );
+    }
 
     #[test]
     fn intersect_partly_overlapping_strips() {
@@ -658,7 +827,12 @@ mod tests {
             alphas: &path_2.alphas,
         };
 
-        intersect(Level::new(), path_1, path_2, &mut write_target);
+        intersect(
+            Level::try_detect().unwrap_or(Level::fallback()),
+            path_1,
+            path_2,
+            &mut write_target,
+        );
 
         assert_eq!(write_target, expected);
     }
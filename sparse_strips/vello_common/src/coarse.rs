@@ -8,7 +8,8 @@ use crate::encode::EncodedPaint;
 use crate::filter_effects::Filter;
 use crate::kurbo::{Affine, Rect};
 use crate::mask::Mask;
-use crate::paint::{Paint, PremulColor};
+use crate::paint::{IndexedPaint, Paint, PremulColor};
+use crate::peniko::color::PremulRgba8;
 use crate::peniko::{BlendMode, Compose, Mix};
 use crate::render_graph::{DependencyKind, LayerId, RenderGraph, RenderNodeKind};
 use crate::{strip::Strip, tile::Tile};
@@ -38,6 +39,9 @@ struct Layer {
     mask: Option<Mask>,
     /// A filter effect to apply to the layer before other operations.
     filter: Option<Filter>,
+    /// The color the layer starts out with instead of being transparent, see
+    /// [`Wide::push_backdrop_layer`].
+    backdrop: Option<PremulRgba8>,
     /// Bounding box of wide tiles containing geometry.
     /// Starts with inverted bounds, shrinks to actual content during drawing.
     wtile_bbox: WideTilesBbox,
@@ -70,6 +74,8 @@ pub struct Wide<const MODE: u8 = MODE_CPU> {
     pub width: u16,
     /// The height of the container.
     pub height: u16,
+    /// The width of a wide tile in pixels.
+    tile_width: u16,
     /// The wide tiles in the container.
     pub tiles: Vec<WideTile<MODE>>,
     /// Shared command properties, referenced by index from fill and clip commands.
@@ -146,10 +152,11 @@ impl WideTilesBbox {
         self.x1().saturating_sub(self.x0())
     }
 
-    /// Get the width of the bounding box in pixels.
+    /// Get the width of the bounding box in pixels, for wide tiles that are `tile_width` pixels
+    /// wide.
     #[inline(always)]
-    pub fn width_px(&self) -> u16 {
-        self.width_tiles() * WideTile::WIDTH
+    pub fn width_px(&self, tile_width: u16) -> u16 {
+        self.width_tiles() * tile_width
     }
 
     /// Get the height of the bounding box (y1 - y0).
@@ -244,10 +251,16 @@ impl WideTilesBbox {
 
     /// Expands the bounding box outward by the given pixel amounts in each direction.
     ///
-    /// Pixel values are converted to tile coordinates (rounding up) and clamped to the
-    /// valid range `[0, max_x)` × `[0, max_y)`. The result is a new bounding box in
-    /// wide tile coordinates.
-    pub fn expand_by_pixels(&self, expansion: Rect, max_x: u16, max_y: u16) -> Self {
+    /// Pixel values are converted to the coordinates of wide tiles that are `tile_width` pixels
+    /// wide (rounding up) and clamped to the valid range `[0, max_x)` × `[0, max_y)`. The result
+    /// is a new bounding box in wide tile coordinates.
+    pub fn expand_by_pixels(
+        &self,
+        expansion: Rect,
+        tile_width: u16,
+        max_x: u16,
+        max_y: u16,
+    ) -> Self {
         // The expansion rect is centered at origin:
         // - Negative coordinates (x0, y0) represent left/top expansion
         // - Positive coordinates (x1, y1) represent right/bottom expansion
@@ -257,9 +270,9 @@ impl WideTilesBbox {
         let bottom_px = expansion.y1.max(0.0).ceil() as u16;
 
         // Convert pixel expansion to tile expansion (round up)
-        let left_tiles = left_px.div_ceil(WideTile::WIDTH);
+        let left_tiles = left_px.div_ceil(tile_width);
         let top_tiles = top_px.div_ceil(Tile::HEIGHT);
-        let right_tiles = right_px.div_ceil(WideTile::WIDTH);
+        let right_tiles = right_px.div_ceil(tile_width);
         let bottom_tiles = bottom_px.div_ceil(Tile::HEIGHT);
 
         Self::new([
@@ -269,34 +282,249 @@ impl WideTilesBbox {
             (self.y1() + bottom_tiles).min(max_y),
         ])
     }
+
+    /// Create the smallest bounding box covering the given rectangle in pixel coordinates, for
+    /// wide tiles that are `tile_width` pixels wide.
+    ///
+    /// The result is clamped to the valid range `[0, max_x)` × `[0, max_y)` in wide tile
+    /// coordinates.
+    pub fn from_pixel_rect(rect: Rect, tile_width: u16, max_x: u16, max_y: u16) -> Self {
+        let tile_width = f64::from(tile_width);
+        let tile_height = f64::from(Tile::HEIGHT);
+
+        Self::new([
+            ((rect.x0.max(0.0) / tile_width).floor() as u16).min(max_x),
+            ((rect.y0.max(0.0) / tile_height).floor() as u16).min(max_y),
+            ((rect.x1.max(0.0) / tile_width).ceil() as u16).min(max_x),
+            ((rect.y1.max(0.0) / tile_height).ceil() as u16).min(max_y),
+        ])
+    }
 }
 
 impl Wide<MODE_CPU> {
     /// Create a new container for wide tiles.
     pub fn new(width: u16, height: u16) -> Self {
-        Self::new_internal(width, height)
+        Self::new_internal(width, height, WideTile::WIDTH)
     }
 }
 
 impl Wide<MODE_HYBRID> {
     /// Create a new container for wide tiles.
     pub fn new(width: u16, height: u16) -> Self {
-        Self::new_internal(width, height)
+        Self::new_internal(width, height, WideTile::WIDTH)
+    }
+
+    /// Create a new container for wide tiles that are `tile_width` pixels wide, instead of
+    /// [`WideTile::WIDTH`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tile_width` isn't a non-zero multiple of [`Tile::WIDTH`].
+    pub fn new_with_tile_width(width: u16, height: u16, tile_width: u16) -> Self {
+        assert!(
+            tile_width > 0 && tile_width.is_multiple_of(Tile::WIDTH),
+            "wide tile width must be a non-zero multiple of the tile width"
+        );
+        Self::new_internal(width, height, tile_width)
+    }
+
+    /// Append the commands of `other` on top of the commands of this container, offset by
+    /// `dx` pixels horizontally and `dy` pixels vertically.
+    ///
+    /// The indexed paints of the appended commands are offset by `paint_offset` and their alpha
+    /// indices by `alpha_offset`, so that they can refer to paints and alphas that are appended
+    /// to the ones of this container.
+    /// Commands are split at the wide tile boundaries they end up crossing, and the parts that
+    /// end up outside of the container are dropped.
+    ///
+    /// Masks and filters can't be appended, since they are tied to the absolute position of
+    /// the layer and to the render graph of the container they were recorded in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dy` isn't a multiple of the tile height, if the containers have different
+    /// wide tile widths, if either container has layers that haven't been popped yet, if
+    /// `other` contains masks or filter layers, or if `other` contains destructive blends that
+    /// would need to be split across wide tiles.
+    pub fn append(
+        &mut self,
+        other: &Self,
+        dx: i32,
+        dy: i32,
+        paint_offset: usize,
+        alpha_offset: u32,
+    ) {
+        assert!(
+            !self.has_layers() && !other.has_layers(),
+            "cannot append containers with unpopped layers"
+        );
+        assert!(
+            !other.has_masks_or_filters(),
+            "cannot append containers with masks or filter layers"
+        );
+        assert_eq!(
+            dy.rem_euclid(i32::from(Tile::HEIGHT)),
+            0,
+            "vertical offset must be a multiple of the tile height"
+        );
+        assert_eq!(
+            self.tile_width, other.tile_width,
+            "cannot append containers with different wide tile widths"
+        );
+
+        let fill_attrs_offset = u32::try_from(self.attrs.fill.len()).unwrap();
+        let clip_attrs_offset = u32::try_from(self.attrs.clip.len()).unwrap();
+        self.attrs
+            .fill
+            .extend(other.attrs.fill.iter().map(|attrs| FillAttrs {
+                paint: match &attrs.paint {
+                    Paint::Indexed(paint) => {
+                        Paint::Indexed(IndexedPaint::new(paint.index() + paint_offset))
+                    }
+                    paint => paint.clone(),
+                },
+                alpha_base_idx: attrs.alpha_base_idx + alpha_offset,
+                ..attrs.clone()
+            }));
+        self.attrs
+            .clip
+            .extend(other.attrs.clip.iter().map(|attrs| ClipAttrs {
+                thread_idx: attrs.thread_idx,
+                alpha_base_idx: attrs.alpha_base_idx + alpha_offset,
+            }));
+
+        let tile_width = i32::from(self.tile_width);
+        let width_tiles = i32::from(self.width_tiles());
+        let height_tiles = i32::from(self.height_tiles());
+
+        for src in other.tiles.iter().filter(|tile| !tile.cmds.is_empty()) {
+            let row = (i32::from(src.y) + dy) / i32::from(Tile::HEIGHT);
+            if !(0..height_tiles).contains(&row) {
+                continue;
+            }
+
+            // The horizontal position of the source tile in this container.
+            let start = i32::from(src.x) + dx;
+            let first_col = start.div_euclid(tile_width).max(0);
+            let last_col = (start + tile_width - 1)
+                .div_euclid(tile_width)
+                .min(width_tiles - 1);
+
+            for col in first_col..=last_col {
+                let shift = start - col * tile_width;
+                // The part of the source tile that ends up in this tile, relative to the source
+                // tile.
+                let lo = (-shift).max(0);
+                let hi = (tile_width - shift).min(tile_width);
+                let split = lo != 0 || hi != tile_width;
+
+                // Clip the span `x..x + width` of the source tile to the part that ends up in this
+                // tile. Returns the clipped span relative to this tile, as well as the number of
+                // columns that were clipped at its start.
+                let clip = |x: u16, width: u16| {
+                    let x0 = i32::from(x).max(lo);
+                    let x1 = (i32::from(x) + i32::from(width)).min(hi);
+                    (x0 < x1).then(|| {
+                        (
+                            (x0 + shift) as u16,
+                            (x1 - x0) as u16,
+                            (x0 - i32::from(x)) as u32 * u32::from(Tile::HEIGHT),
+                        )
+                    })
+                };
+
+                let dst = self.get_mut(col as u16, row as u16);
+                let len = dst.cmds.len();
+                let mut has_draw_commands = false;
+
+                for cmd in &src.cmds {
+                    let cmd = match cmd {
+                        Cmd::Fill(fill) => {
+                            let Some((x, width, _)) = clip(fill.x, fill.width) else {
+                                continue;
+                            };
+                            Cmd::Fill(CmdFill {
+                                x,
+                                width,
+                                attrs_idx: fill.attrs_idx + fill_attrs_offset,
+                            })
+                        }
+                        Cmd::AlphaFill(fill) => {
+                            let Some((x, width, skipped)) = clip(fill.x, fill.width) else {
+                                continue;
+                            };
+                            Cmd::AlphaFill(CmdAlphaFill {
+                                x,
+                                width,
+                                alpha_offset: fill.alpha_offset + skipped,
+                                attrs_idx: fill.attrs_idx + fill_attrs_offset,
+                            })
+                        }
+                        Cmd::ClipFill(fill) => {
+                            let Some((x, width, _)) = clip(fill.x, fill.width) else {
+                                continue;
+                            };
+                            Cmd::ClipFill(CmdClipFill { x, width })
+                        }
+                        Cmd::ClipStrip(fill) => {
+                            let Some((x, width, skipped)) = clip(fill.x, fill.width) else {
+                                continue;
+                            };
+                            Cmd::ClipStrip(CmdClipAlphaFill {
+                                x,
+                                width,
+                                alpha_offset: fill.alpha_offset + skipped,
+                                attrs_idx: fill.attrs_idx + clip_attrs_offset,
+                            })
+                        }
+                        Cmd::PushBuf(layer_kind) => Cmd::PushBuf(*layer_kind),
+                        Cmd::PopBuf => Cmd::PopBuf,
+                        Cmd::Opacity(opacity) => Cmd::Opacity(*opacity),
+                        Cmd::Blend(blend_mode) => {
+                            assert!(
+                                !split || !blend_mode.is_destructive(),
+                                "cannot split destructive blends across wide tiles"
+                            );
+                            Cmd::Blend(*blend_mode)
+                        }
+                        Cmd::PushZeroClip(_)
+                        | Cmd::PopZeroClip
+                        | Cmd::Filter(..)
+                        | Cmd::Mask(_) => {
+                            unreachable!("masks and filters are rejected above")
+                        }
+                    };
+
+                    has_draw_commands |= matches!(
+                        cmd,
+                        Cmd::Fill(_) | Cmd::AlphaFill(_) | Cmd::ClipFill(_) | Cmd::ClipStrip(_)
+                    );
+                    dst.cmds.push(cmd);
+                }
+
+                // Without any drawing, the remaining commands of a split tile only blend empty
+                // buffers non-destructively, so they can be dropped.
+                if split && !has_draw_commands {
+                    dst.cmds.truncate(len);
+                }
+            }
+        }
     }
 }
 
 impl<const MODE: u8> Wide<MODE> {
     /// Create a new container for wide tiles.
-    fn new_internal(width: u16, height: u16) -> Self {
-        let width_tiles = width.div_ceil(WideTile::WIDTH);
+    fn new_internal(width: u16, height: u16, tile_width: u16) -> Self {
+        let width_tiles = width.div_ceil(tile_width);
         let height_tiles = height.div_ceil(Tile::HEIGHT);
         let mut tiles = Vec::with_capacity(usize::from(width_tiles) * usize::from(height_tiles));
 
         for h in 0..height_tiles {
             for w in 0..width_tiles {
                 tiles.push(WideTile::<MODE>::new_internal(
-                    w * WideTile::WIDTH,
+                    w * tile_width,
                     h * Tile::HEIGHT,
+                    tile_width,
                 ));
             }
         }
@@ -305,6 +533,7 @@ impl<const MODE: u8> Wide<MODE> {
             tiles,
             width,
             height,
+            tile_width,
             attrs: CommandAttrs::default(),
             layer_stack: vec![],
             clip_stack: vec![],
@@ -319,6 +548,20 @@ impl<const MODE: u8> Wide<MODE> {
         !self.layer_stack.is_empty()
     }
 
+    /// Whether any of the tiles contain commands for masks or filter layers.
+    fn has_masks_or_filters(&self) -> bool {
+        self.tiles.iter().flat_map(|tile| &tile.cmds).any(|cmd| {
+            matches!(
+                cmd,
+                Cmd::PushBuf(LayerKind::Filtered(_))
+                    | Cmd::PushZeroClip(_)
+                    | Cmd::PopZeroClip
+                    | Cmd::Filter(..)
+                    | Cmd::Mask(_)
+            )
+        })
+    }
+
     /// Reset all tiles in the container.
     pub fn reset(&mut self) {
         for tile in &mut self.tiles {
@@ -340,9 +583,27 @@ impl<const MODE: u8> Wide<MODE> {
         self.clipped_filter_layer_depth = 0;
     }
 
+    /// Release the capacity that is not used by the current commands.
+    pub fn shrink_to_fit(&mut self) {
+        for tile in &mut self.tiles {
+            tile.cmds.shrink_to_fit();
+            tile.layer_ids.shrink_to_fit();
+            tile.layer_cmd_ranges.shrink_to_fit();
+        }
+        self.attrs.shrink_to_fit();
+        self.layer_stack.shrink_to_fit();
+        self.clip_stack.shrink_to_fit();
+        self.filter_node_stack.shrink_to_fit();
+    }
+
+    /// Return the width of a wide tile in pixels.
+    pub fn tile_width(&self) -> u16 {
+        self.tile_width
+    }
+
     /// Return the number of horizontal tiles.
     pub fn width_tiles(&self) -> u16 {
-        self.width.div_ceil(WideTile::WIDTH)
+        self.width.div_ceil(self.tile_width)
     }
 
     /// Return the number of vertical tiles.
@@ -437,6 +698,8 @@ impl<const MODE: u8> Wide<MODE> {
 
         // Save current_layer_id to avoid borrowing issues
         let current_layer_id = self.get_current_layer_id();
+        let tile_width = self.tile_width;
+        let max_wide_tile_coord = u16::MAX / tile_width;
 
         for i in 0..strip_buf.len() - 1 {
             let strip = &strip_buf[i];
@@ -472,18 +735,18 @@ impl<const MODE: u8> Wide<MODE> {
             let x1 = x0.saturating_add(strip_width);
 
             // Calculate which wide tiles this strip intersects
-            let wtile_x0 = (x0 / WideTile::WIDTH).max(bbox.x0());
+            let wtile_x0 = (x0 / tile_width).max(bbox.x0());
             // It's possible that a strip extends into a new wide tile, but we don't actually
             // have as many wide tiles (e.g. because the pixmap width is only 512, but
             // strip ends at 513), so take the minimum between the rounded values and `width_tiles`.
             let wtile_x1 = x1
-                .div_ceil(WideTile::WIDTH)
+                .div_ceil(tile_width)
                 .min(bbox.x1())
-                .min(WideTile::MAX_WIDE_TILE_COORD);
+                .min(max_wide_tile_coord);
 
             // Adjust column starting position if needed to respect clip boundaries
             let mut x = x0;
-            let clip_x = bbox.x0() * WideTile::WIDTH;
+            let clip_x = bbox.x0() * tile_width;
             if clip_x > x {
                 col += u32::from(clip_x - x);
                 x = clip_x;
@@ -491,9 +754,9 @@ impl<const MODE: u8> Wide<MODE> {
 
             // Generate alpha fill commands for each wide tile intersected by this strip
             for wtile_x in wtile_x0..wtile_x1 {
-                let x_wtile_rel = x % WideTile::WIDTH;
+                let x_wtile_rel = x % tile_width;
                 // Restrict the width of the fill to the width of the wide tile
-                let width = x1.min((wtile_x + 1) * WideTile::WIDTH) - x;
+                let width = x1.min((wtile_x + 1) * tile_width) - x;
                 let cmd = CmdAlphaFill {
                     x: x_wtile_rel,
                     width,
@@ -502,7 +765,9 @@ impl<const MODE: u8> Wide<MODE> {
                 };
                 x += width;
                 col += u32::from(width);
-                self.get_mut(wtile_x, strip_y).strip(cmd, current_layer_id);
+                let idx =
+                    usize::from(strip_y) * usize::from(self.width_tiles()) + usize::from(wtile_x);
+                self.tiles[idx].strip(cmd, &self.attrs.fill, current_layer_id);
                 self.update_current_layer_bbox(wtile_x, strip_y);
             }
 
@@ -513,21 +778,25 @@ impl<const MODE: u8> Wide<MODE> {
             // generate fill commands for the region between them
             if active_fill && strip_y == next_strip.strip_y() {
                 // Clamp the fill to the clip bounding box
-                x = x1.max(bbox.x0() * WideTile::WIDTH);
+                x = x1.max(bbox.x0() * tile_width);
                 let x2 = next_strip.x.min(
                     self.width
-                        .checked_next_multiple_of(WideTile::WIDTH)
+                        .checked_next_multiple_of(tile_width)
                         .unwrap_or(u16::MAX),
                 );
-                let wfxt0 = (x1 / WideTile::WIDTH).max(bbox.x0());
+                let wfxt0 = (x1 / tile_width).max(bbox.x0());
                 let wfxt1 = x2
-                    .div_ceil(WideTile::WIDTH)
+                    .div_ceil(tile_width)
                     .min(bbox.x1())
-                    .min(WideTile::MAX_WIDE_TILE_COORD);
+                    .min(max_wide_tile_coord);
 
                 // Compute fill hint based on paint type
                 let fill_attrs = &self.attrs.fill[attrs_idx as usize];
-                let fill_hint = if fill_attrs.mask.is_none() {
+                // Only plain source-over fills fully replace what is below them.
+                let fill_hint = if fill_attrs.mask.is_none()
+                    && fill_attrs.blend_mode.mix == Mix::Normal
+                    && fill_attrs.blend_mode.compose == Compose::SrcOver
+                {
                     match &fill_attrs.paint {
                         Paint::Solid(s) if s.is_opaque() => FillHint::OpaqueSolid(*s),
                         Paint::Indexed(idx) => {
@@ -548,13 +817,10 @@ impl<const MODE: u8> Wide<MODE> {
 
                 // Generate fill commands for each wide tile in the fill region
                 for wtile_x in wfxt0..wfxt1 {
-                    let x_wtile_rel = x % WideTile::WIDTH;
-                    let width = x2.min(
-                        (wtile_x
-                            .checked_add(1)
-                            .unwrap_or(WideTile::MAX_WIDE_TILE_COORD))
-                            * WideTile::WIDTH,
-                    ) - x;
+                    let x_wtile_rel = x % tile_width;
+                    let width = x2
+                        .min((wtile_x.checked_add(1).unwrap_or(max_wide_tile_coord)) * tile_width)
+                        - x;
                     x += width;
                     self.get_mut(wtile_x, strip_y).fill(
                         x_wtile_rel,
@@ -663,6 +929,7 @@ impl<const MODE: u8> Wide<MODE> {
             opacity,
             mask,
             filter,
+            backdrop: None,
             wtile_bbox: WideTilesBbox::inverted(),
         };
 
@@ -699,6 +966,34 @@ impl<const MODE: u8> Wide<MODE> {
         self.layer_stack.push(layer);
     }
 
+    /// Push a new layer that starts out with `backdrop` instead of being transparent.
+    ///
+    /// The layer has no clip and is composited using source-over once it is popped. Its
+    /// buffers are pushed as [`LayerKind::Backdrop`], so it is up to the renderer to fill them
+    /// with the backdrop. Unlike other layers, they are composited into the backdrop of the
+    /// layer even in wide tiles in which nothing is drawn into it, unless they are clipped out.
+    pub fn push_backdrop_layer(&mut self, layer_id: LayerId, backdrop: PremulRgba8) {
+        let layer_kind = LayerKind::Backdrop(layer_id, backdrop);
+        for x in 0..self.width_tiles() {
+            for y in 0..self.height_tiles() {
+                let tile = self.get_mut(x, y);
+                tile.push_buf(layer_kind);
+                tile.in_clipped_filter_layer = false;
+            }
+        }
+
+        self.layer_stack.push(Layer {
+            layer_id,
+            clip: false,
+            blend_mode: BlendMode::default(),
+            opacity: 1.0,
+            mask: None,
+            filter: None,
+            backdrop: Some(backdrop),
+            wtile_bbox: WideTilesBbox::inverted(),
+        });
+    }
+
     /// Pop a previously pushed layer.
     ///
     /// This method finalizes the layer by:
@@ -728,11 +1023,22 @@ impl<const MODE: u8> Wide<MODE> {
                     let expansion = filter.bounds_expansion(transform);
                     let expanded_bbox = layer.wtile_bbox.expand_by_pixels(
                         expansion,
+                        self.tile_width(),
                         self.width_tiles(),
                         self.height_tiles(),
                     );
                     let clip_bbox = self.active_bbox();
-                    let final_bbox = expanded_bbox.intersect(clip_bbox);
+                    let mut final_bbox = expanded_bbox.intersect(clip_bbox);
+
+                    // Don't evaluate the filter outside of its bounds.
+                    if let Some(bounds) = filter.device_bounds(transform) {
+                        final_bbox = final_bbox.intersect(WideTilesBbox::from_pixel_rect(
+                            bounds,
+                            self.tile_width(),
+                            self.width_tiles(),
+                            self.height_tiles(),
+                        ));
+                    }
 
                     // Update both the local layer and the render graph node
                     layer.wtile_bbox = final_bbox;
@@ -765,14 +1071,19 @@ impl<const MODE: u8> Wide<MODE> {
         let needs_buf = layer.needs_buf();
 
         if needs_buf {
+            let active_bbox = self.active_bbox();
             for x in 0..self.width_tiles() {
                 for y in 0..self.height_tiles() {
                     let t = self.get_mut(x, y);
 
                     // Optimization: If no drawing happened since the last `PushBuf`, then we don't
                     // need to do any masking or buffer-wide opacity work. The same holds for
-                    // blending, unless it is destructive blending.
-                    let has_draw_commands = !matches!(t.cmds.last().unwrap(), &Cmd::PushBuf(_));
+                    // blending, unless it is destructive blending. Layers with a backdrop are
+                    // never empty, unless they are clipped out.
+                    let has_draw_commands = !matches!(t.cmds.last().unwrap(), &Cmd::PushBuf(_))
+                        || (layer.backdrop.is_some()
+                            && active_bbox.contains(x, y)
+                            && !t.is_zero_clip());
                     if has_draw_commands {
                         if let Some(mask) = layer.mask.clone() {
                             t.mask(mask);
@@ -825,7 +1136,7 @@ impl<const MODE: u8> Wide<MODE> {
             let wtile_y1 = strips[n_strips.saturating_sub(1)].strip_y() + 1;
 
             // Calculate the x range by examining all strips in wide tile coordinates
-            let mut wtile_x0 = strips[0].x / WideTile::WIDTH;
+            let mut wtile_x0 = strips[0].x / self.tile_width;
             let mut wtile_x1 = wtile_x0;
             for i in 0..n_strips.saturating_sub(1) {
                 let strip = &strips[i];
@@ -833,8 +1144,8 @@ impl<const MODE: u8> Wide<MODE> {
                 let width =
                     ((next_strip.alpha_idx() - strip.alpha_idx()) / u32::from(Tile::HEIGHT)) as u16;
                 let x = strip.x;
-                wtile_x0 = wtile_x0.min(x / WideTile::WIDTH);
-                wtile_x1 = wtile_x1.max((x + width).div_ceil(WideTile::WIDTH));
+                wtile_x0 = wtile_x0.min(x / self.tile_width);
+                wtile_x1 = wtile_x1.max((x + width).div_ceil(self.tile_width));
             }
             WideTilesBbox::new([wtile_x0, wtile_y0, wtile_x1, wtile_y1])
         };
@@ -889,7 +1200,7 @@ impl<const MODE: u8> Wide<MODE> {
 
             // Process wide tiles to the left of this strip in the same row
             let x = strip.x;
-            let wtile_x_clamped = (x / WideTile::WIDTH).min(clip_bbox.x1());
+            let wtile_x_clamped = (x / self.tile_width).min(clip_bbox.x1());
             if cur_wtile_x < wtile_x_clamped {
                 // If winding is zero or doesn't match fill rule, these wide tiles are outside the path
                 let is_inside = strip.fill_gap();
@@ -907,7 +1218,7 @@ impl<const MODE: u8> Wide<MODE> {
             let next_strip = &strips[i + 1];
             let width =
                 ((next_strip.alpha_idx() - strip.alpha_idx()) / u32::from(Tile::HEIGHT)) as u16;
-            let wtile_x1 = (x + width).div_ceil(WideTile::WIDTH).min(clip_bbox.x1());
+            let wtile_x1 = (x + width).div_ceil(self.tile_width).min(clip_bbox.x1());
             if cur_wtile_x < wtile_x1 {
                 for wtile_x in cur_wtile_x..wtile_x1 {
                     self.get_mut(wtile_x, cur_wtile_y).push_clip(layer_id);
@@ -1028,7 +1339,7 @@ impl<const MODE: u8> Wide<MODE> {
 
             // Process tiles to the left of this strip in the same row
             let x0 = strip.x;
-            let wtile_x_clamped = (x0 / WideTile::WIDTH).min(clip_bbox.x1());
+            let wtile_x_clamped = (x0 / self.tile_width).min(clip_bbox.x1());
             if cur_wtile_x < wtile_x_clamped {
                 // Handle any pending clip pop from previous iteration
                 if core::mem::take(&mut pop_pending) {
@@ -1053,13 +1364,13 @@ impl<const MODE: u8> Wide<MODE> {
             let strip_width =
                 ((next_strip.alpha_idx() - strip.alpha_idx()) / u32::from(Tile::HEIGHT)) as u16;
             let mut clipped_x1 = x0 + strip_width;
-            let wtile_x0 = (x0 / WideTile::WIDTH).max(clip_bbox.x0());
-            let wtile_x1 = clipped_x1.div_ceil(WideTile::WIDTH).min(clip_bbox.x1());
+            let wtile_x0 = (x0 / self.tile_width).max(clip_bbox.x0());
+            let wtile_x1 = clipped_x1.div_ceil(self.tile_width).min(clip_bbox.x1());
 
             // Calculate starting position and column for alpha mask
             let mut x = x0;
             let mut col = strip.alpha_idx() / u32::from(Tile::HEIGHT);
-            let clip_x = clip_bbox.x0() * WideTile::WIDTH;
+            let clip_x = clip_bbox.x0() * self.tile_width;
             if clip_x > x {
                 col += u32::from(clip_x - x);
                 x = clip_x;
@@ -1074,8 +1385,8 @@ impl<const MODE: u8> Wide<MODE> {
                 }
 
                 // Calculate the portion of the strip that affects this tile
-                let x_rel = x % WideTile::WIDTH;
-                let width = clipped_x1.min((wtile_x + 1) * WideTile::WIDTH) - x;
+                let x_rel = x % self.tile_width;
+                let width = clipped_x1.min((wtile_x + 1) * self.tile_width) - x;
 
                 // Create clip strip command for rendering the partial coverage
                 let cmd = CmdClipAlphaFill {
@@ -1105,14 +1416,14 @@ impl<const MODE: u8> Wide<MODE> {
                 }
 
                 let x2 = next_strip.x;
-                let clipped_x2 = x2.min((cur_wtile_x + 1) * WideTile::WIDTH);
+                let clipped_x2 = x2.min((cur_wtile_x + 1) * self.tile_width);
                 let width = clipped_x2.saturating_sub(clipped_x1);
 
                 // If there's a gap, fill it. Only do this if the fill wouldn't cover the
                 // whole tile, as such clips are skipped by the `push_clip` function. See
                 // <https://github.com/linebender/vello/blob/de0659e4df9842c8857153841a2b4ba6f1020bb0/sparse_strips/vello_common/src/coarse.rs#L504-L516>
-                if width > 0 && width < WideTile::WIDTH {
-                    let x_rel = clipped_x1 % WideTile::WIDTH;
+                if width > 0 && width < self.tile_width {
+                    let x_rel = clipped_x1 % self.tile_width;
                     self.get_mut(cur_wtile_x, cur_wtile_y)
                         .clip_fill(x_rel, width);
                 }
@@ -1125,13 +1436,13 @@ impl<const MODE: u8> Wide<MODE> {
                 }
 
                 // If fill extends to next tile, pop current and handle next
-                if x2 > (cur_wtile_x + 1) * WideTile::WIDTH {
+                if x2 > (cur_wtile_x + 1) * self.tile_width {
                     if core::mem::take(&mut pop_pending) {
                         self.get_mut(cur_wtile_x, cur_wtile_y).pop_clip();
                     }
 
-                    let width2 = x2 % WideTile::WIDTH;
-                    cur_wtile_x = x2 / WideTile::WIDTH;
+                    let width2 = x2 % self.tile_width;
+                    cur_wtile_x = x2 / self.tile_width;
 
                     // If the strip is outside the clipping box, we don't need to do any
                     // filling, so we continue (also to prevent out-of-bounds access).
@@ -1178,6 +1489,8 @@ pub struct WideTile<const MODE: u8 = MODE_CPU> {
     pub x: u16,
     /// The y coordinate of the wide tile.
     pub y: u16,
+    /// The width of the wide tile in pixels.
+    width: u16,
     /// The background of the tile.
     pub bg: PremulColor,
     /// The draw commands of the tile.
@@ -1208,25 +1521,26 @@ impl WideTile {
 impl WideTile<MODE_CPU> {
     /// Create a new wide tile.
     pub fn new(width: u16, height: u16) -> Self {
-        Self::new_internal(width, height)
+        Self::new_internal(width, height, WideTile::WIDTH)
     }
 }
 
 impl WideTile<MODE_HYBRID> {
     /// Create a new wide tile.
     pub fn new(width: u16, height: u16) -> Self {
-        Self::new_internal(width, height)
+        Self::new_internal(width, height, WideTile::WIDTH)
     }
 }
 
 impl<const MODE: u8> WideTile<MODE> {
     /// Create a new wide tile.
-    fn new_internal(x: u16, y: u16) -> Self {
+    fn new_internal(x: u16, y: u16, width: u16) -> Self {
         let mut layer_cmd_ranges = HashMap::new();
         layer_cmd_ranges.insert(0, LayerCommandRanges::default());
         Self {
             x,
             y,
+            width,
             bg: PremulColor::from_alpha_color(TRANSPARENT),
             cmds: vec![],
             n_zero_clip: 0,
@@ -1245,7 +1559,8 @@ impl<const MODE: u8> WideTile<MODE> {
     /// layer content rendered before applying the clip as a mask.
     ///
     /// The `fill_hint` parameter is pre-computed by the caller based on paint type:
-    /// - `OpaqueSolid(color)`: Paint is an opaque solid color, can replace background
+    /// - `OpaqueSolid(color)`: Paint is an opaque solid color, can replace background (in hybrid
+    ///   mode, can clear previous commands)
     /// - `OpaqueImage`: Paint is an opaque image, can clear previous commands
     /// - `None`: No optimization available
     pub(crate) fn fill(
@@ -1257,69 +1572,54 @@ impl<const MODE: u8> WideTile<MODE> {
         fill_hint: FillHint,
     ) {
         if !self.is_zero_clip() || self.in_clipped_filter_layer {
-            match MODE {
-                MODE_CPU => {
-                    // Check if we can apply overdraw elimination optimization.
-                    // This requires filling the entire tile width with no clip/buffer stack.
-                    //
-                    // Note that we could be more aggressive in optimizing a whole-tile opaque fill
-                    // even with a clip stack. It would be valid to elide all drawing commands from
-                    // the enclosing clip push up to the fill. Further, we could extend the clip
-                    // push command to include a background color, rather than always starting with
-                    // a transparent buffer. Lastly, a sequence of push(bg); strip/fill; pop could
-                    // be replaced with strip/fill with the color (the latter is true even with a
-                    // non-opaque color).
-                    //
-                    // However, the extra cost of tracking such optimizations may outweigh the
-                    // benefit, especially in hybrid mode with GPU painting.
-                    let can_override =
-                        x == 0 && width == WideTile::WIDTH && self.n_clip == 0 && self.n_bufs == 0;
-
-                    if can_override {
-                        match fill_hint {
-                            FillHint::OpaqueSolid(color) => {
-                                self.cmds.clear();
-                                self.bg = color;
-                                if let Some(ranges) =
-                                    self.layer_cmd_ranges.get_mut(&current_layer_id)
-                                {
-                                    ranges.clear();
-                                }
-                                return;
-                            }
-                            FillHint::OpaqueImage => {
-                                // Opaque image: clear previous commands but still emit the fill.
-                                self.cmds.clear();
-                                self.bg = PremulColor::from_alpha_color(TRANSPARENT);
-                                if let Some(ranges) =
-                                    self.layer_cmd_ranges.get_mut(&current_layer_id)
-                                {
-                                    ranges.clear();
-                                }
-                                // Fall through to emit the fill command below, as opposed to
-                                // solid paints where we have a return statement.
-                            }
-                            FillHint::None => {}
+            // Check if we can apply overdraw elimination optimization.
+            // This requires filling the entire tile width with no clip/buffer stack.
+            //
+            // Note that we could be more aggressive in optimizing a whole-tile opaque fill
+            // even with a clip stack. It would be valid to elide all drawing commands from
+            // the enclosing clip push up to the fill. Further, we could extend the clip
+            // push command to include a background color, rather than always starting with
+            // a transparent buffer. Lastly, a sequence of push(bg); strip/fill; pop could
+            // be replaced with strip/fill with the color (the latter is true even with a
+            // non-opaque color).
+            //
+            // However, the extra cost of tracking such optimizations may outweigh the
+            // benefit, especially in hybrid mode with GPU painting.
+            let can_override =
+                x == 0 && width == self.width && self.n_clip == 0 && self.n_bufs == 0;
+
+            if can_override {
+                match fill_hint {
+                    FillHint::OpaqueSolid(color) if MODE == MODE_CPU => {
+                        self.cmds.clear();
+                        self.bg = color;
+                        if let Some(ranges) = self.layer_cmd_ranges.get_mut(&current_layer_id) {
+                            ranges.clear();
                         }
+                        return;
                     }
-
-                    self.record_fill_cmd(current_layer_id, self.cmds.len());
-                    self.cmds.push(Cmd::Fill(CmdFill {
-                        x,
-                        width,
-                        attrs_idx,
-                    }));
-                }
-                MODE_HYBRID => {
-                    self.record_fill_cmd(current_layer_id, self.cmds.len());
-                    self.cmds.push(Cmd::Fill(CmdFill {
-                        x,
-                        width,
-                        attrs_idx,
-                    }));
+                    // In hybrid mode, opaque solid fills are still emitted so that they are
+                    // painted (and batched) like any other fill.
+                    FillHint::OpaqueSolid(_) | FillHint::OpaqueImage => {
+                        // Opaque paint: clear previous commands but still emit the fill.
+                        self.cmds.clear();
+                        self.bg = PremulColor::from_alpha_color(TRANSPARENT);
+                        if let Some(ranges) = self.layer_cmd_ranges.get_mut(&current_layer_id) {
+                            ranges.clear();
+                        }
+                        // Fall through to emit the fill command below, as opposed to
+                        // solid paints in CPU mode where we have a return statement.
+                    }
+                    FillHint::None => {}
                 }
-                _ => unreachable!(),
             }
+
+            self.record_fill_cmd(current_layer_id, self.cmds.len());
+            self.cmds.push(Cmd::Fill(CmdFill {
+                x,
+                width,
+                attrs_idx,
+            }));
         }
     }
 
@@ -1328,8 +1628,24 @@ impl<const MODE: u8> WideTile<MODE> {
     /// Generates alpha fill commands unless the tile is in a zero-clip region (fully clipped out).
     /// For clipped filter layers, commands are always generated since filters need the full
     /// layer content rendered before applying the clip as a mask.
-    pub(crate) fn strip(&mut self, cmd_strip: CmdAlphaFill, current_layer_id: LayerId) {
+    ///
+    /// If the previous command is an alpha fill with equivalent attributes that ends where the
+    /// new one starts, both horizontally and in the alpha buffer, it is extended instead, so
+    /// that adjacent strips (possibly of different paths) become a single command.
+    pub(crate) fn strip(
+        &mut self,
+        cmd_strip: CmdAlphaFill,
+        fill_attrs: &[FillAttrs],
+        current_layer_id: LayerId,
+    ) {
         if !self.is_zero_clip() || self.in_clipped_filter_layer {
+            if let Some(Cmd::AlphaFill(last)) = self.cmds.last_mut()
+                && last.continues_with(&cmd_strip, fill_attrs)
+            {
+                last.width += cmd_strip.width;
+                return;
+            }
+
             self.record_fill_cmd(current_layer_id, self.cmds.len());
             self.cmds.push(Cmd::AlphaFill(cmd_strip));
         }
@@ -1562,6 +1878,9 @@ pub enum FillHint {
 pub enum LayerKind {
     /// Regular layer using local `blend_buf` stack for temporary storage.
     Regular(LayerId),
+    /// Regular layer whose buffer starts out filled with the given color instead of being
+    /// transparent.
+    Backdrop(LayerId, PremulRgba8),
     /// Filtered layer materialized in persistent `layer_manager` storage.
     Filtered(LayerId),
     /// Clip layer for clipping operations.
@@ -1574,7 +1893,7 @@ impl LayerKind {
     /// All layer kinds contain a layer ID that uniquely identifies the layer.
     pub fn id(&self) -> LayerId {
         match self {
-            Self::Regular(id) | Self::Filtered(id) | Self::Clip(id) => *id,
+            Self::Regular(id) | Self::Backdrop(id, _) | Self::Filtered(id) | Self::Clip(id) => *id,
         }
     }
 }
@@ -1651,6 +1970,7 @@ impl Cmd {
             Self::AlphaFill(_) => "AlphaFillPath",
             Self::PushBuf(layer_kind) => match layer_kind {
                 LayerKind::Regular(_) => "PushBuf(Regular)",
+                LayerKind::Backdrop(..) => "PushBuf(Backdrop)",
                 LayerKind::Filtered(_) => "PushBuf(Filtered)",
                 LayerKind::Clip(_) => "PushBuf(Clip)",
             },
@@ -1794,6 +2114,12 @@ impl CommandAttrs {
         self.fill.clear();
         self.clip.clear();
     }
+
+    /// Release the capacity that is not used by the current attributes.
+    pub fn shrink_to_fit(&mut self) {
+        self.fill.shrink_to_fit();
+        self.clip.shrink_to_fit();
+    }
 }
 
 /// Fill a consecutive horizontal region of a wide tile.
@@ -1829,6 +2155,25 @@ pub struct CmdAlphaFill {
     pub attrs_idx: u32,
 }
 
+impl CmdAlphaFill {
+    /// Whether `next` directly continues this command, i.e. it starts where this command ends,
+    /// reads the alphas following the ones of this command and has equivalent attributes.
+    fn continues_with(&self, next: &Self, fill_attrs: &[FillAttrs]) -> bool {
+        if self.x + self.width != next.x {
+            return false;
+        }
+
+        let attrs = &fill_attrs[self.attrs_idx as usize];
+        let next_attrs = &fill_attrs[next.attrs_idx as usize];
+        attrs.thread_idx == next_attrs.thread_idx
+            && attrs.paint == next_attrs.paint
+            && attrs.blend_mode == next_attrs.blend_mode
+            && attrs.mask == next_attrs.mask
+            && attrs.alpha_idx(self.alpha_offset) + u32::from(self.width) * u32::from(Tile::HEIGHT)
+                == next_attrs.alpha_idx(next.alpha_offset)
+    }
+}
+
 /// Fill operation within a clipping region.
 ///
 /// This command copies a horizontal region from the top of the clip buffer stack
@@ -1906,11 +2251,18 @@ impl LayerCommandRanges {
 
 #[cfg(test)]
 mod tests {
-    use crate::coarse::{FillHint, LayerKind, MODE_CPU, Wide, WideTile};
-    use crate::kurbo::Affine;
+    use crate::coarse::{
+        Cmd, CmdAlphaFill, CmdFill, FillHint, LayerKind, MODE_CPU, MODE_HYBRID, Wide, WideTile,
+        WideTilesBbox,
+    };
+    use crate::color::palette::css::{BLUE, GREEN, RED};
+    use crate::color::{AlphaColor, PremulRgba8, Srgb};
+    use crate::kurbo::{Affine, Rect};
+    use crate::paint::{Paint, PremulColor};
     use crate::peniko::{BlendMode, Compose, Mix};
     use crate::render_graph::RenderGraph;
     use crate::strip::Strip;
+    use crate::tile::Tile;
     use alloc::{boxed::Box, vec};
 
     #[test]
@@ -1960,6 +2312,106 @@ mod tests {
         assert_eq!(wide.cmds.len(), 4);
     }
 
+    #[test]
+    fn empty_layers_with_backdrop_are_kept() {
+        let mut wide = Wide::<MODE_HYBRID>::new(512, 4);
+        let mut render_graph = RenderGraph::new();
+        let backdrop = PremulRgba8 {
+            r: 0,
+            g: 0,
+            b: 128,
+            a: 128,
+        };
+        wide.push_layer(
+            1,
+            None::<Box<[Strip]>>,
+            BlendMode::default(),
+            None,
+            1.0,
+            None,
+            Affine::IDENTITY,
+            &mut render_graph,
+            0,
+        );
+        wide.pop_layer(&mut render_graph);
+        wide.push_backdrop_layer(2, backdrop);
+        wide.pop_layer(&mut render_graph);
+
+        for tile in &wide.tiles {
+            assert_eq!(
+                tile.cmds,
+                [
+                    Cmd::PushBuf(LayerKind::Backdrop(2, backdrop)),
+                    Cmd::Blend(BlendMode::default()),
+                    Cmd::PopBuf,
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn backdrop_layers_are_kept_only_inside_their_clip() {
+        let mut wide = Wide::<MODE_HYBRID>::new(512, 4);
+        let mut render_graph = RenderGraph::new();
+        let backdrop = PremulRgba8 {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255,
+        };
+        wide.push_layer(
+            1,
+            Some(alpha_strips(300, 310, 0)),
+            BlendMode::default(),
+            None,
+            1.0,
+            None,
+            Affine::IDENTITY,
+            &mut render_graph,
+            0,
+        );
+        wide.push_backdrop_layer(2, backdrop);
+        wide.pop_layer(&mut render_graph);
+        wide.pop_layer(&mut render_graph);
+
+        // The clip doesn't reach the first wide tile, so neither does the backdrop.
+        assert!(wide.get(0, 0).cmds.is_empty());
+        assert!(
+            wide.get(1, 0)
+                .cmds
+                .contains(&Cmd::PushBuf(LayerKind::Backdrop(2, backdrop)))
+        );
+    }
+
+    #[test]
+    fn bbox_pixels_use_tile_width() {
+        let bbox = WideTilesBbox::new([2, 1, 3, 2]);
+        assert_eq!(bbox.width_px(WideTile::WIDTH), WideTile::WIDTH);
+        assert_eq!(bbox.width_px(64), 64);
+
+        // 100 pixels are a single wide tile of the default width, but two of 64 pixels.
+        let expansion = Rect::new(-100.0, -5.0, 100.0, 5.0);
+        assert_eq!(
+            bbox.expand_by_pixels(expansion, WideTile::WIDTH, 10, 10)
+                .bbox,
+            [1, 0, 4, 4]
+        );
+        assert_eq!(
+            bbox.expand_by_pixels(expansion, 64, 10, 10).bbox,
+            [0, 0, 5, 4]
+        );
+
+        let rect = Rect::new(100.0, 6.0, 300.0, 9.0);
+        assert_eq!(
+            WideTilesBbox::from_pixel_rect(rect, WideTile::WIDTH, 10, 10).bbox,
+            [0, 1, 2, 3]
+        );
+        assert_eq!(
+            WideTilesBbox::from_pixel_rect(rect, 64, 10, 10).bbox,
+            [1, 1, 5, 3]
+        );
+    }
+
     #[test]
     fn tile_coordinates() {
         let wide = Wide::<MODE_CPU>::new(1000, 258);
@@ -2019,4 +2471,199 @@ mod tests {
         assert_eq!(wide.clip_stack.len(), 0);
         assert_eq!(wide.tiles[0].n_bufs, 0);
     }
+
+    /// Strips covering the two wide tiles of the first row with a solid fill.
+    fn full_row_strips() -> [Strip; 2] {
+        [
+            Strip::new(0, 0, 0, false),
+            Strip::new(2 * WideTile::WIDTH, 0, 0, true),
+        ]
+    }
+
+    fn solid(color: AlphaColor<Srgb>) -> Paint {
+        Paint::Solid(PremulColor::from_alpha_color(color))
+    }
+
+    #[test]
+    fn opaque_fill_culls_covered_commands_in_hybrid_mode() {
+        let mut wide = Wide::<MODE_HYBRID>::new(2 * WideTile::WIDTH, 4);
+        let strips = full_row_strips();
+        for i in 0..9 {
+            let color = if i % 2 == 0 { RED } else { GREEN };
+            wide.generate(&strips, solid(color), BlendMode::default(), 0, None, &[]);
+        }
+        wide.generate(&strips, solid(BLUE), BlendMode::default(), 0, None, &[]);
+
+        for tile in &wide.tiles {
+            assert_eq!(
+                tile.cmds,
+                [Cmd::Fill(CmdFill {
+                    x: 0,
+                    width: WideTile::WIDTH,
+                    attrs_idx: 9,
+                })]
+            );
+        }
+    }
+
+    #[test]
+    fn narrow_wide_tiles_split_commands() {
+        let mut wide = Wide::<MODE_HYBRID>::new_with_tile_width(2 * WideTile::WIDTH, 4, 64);
+        assert_eq!(wide.tile_width(), 64);
+        assert_eq!(wide.width_tiles(), 8);
+
+        // A fill spanning the whole row is split into one fill per wide tile, each of which
+        // replaces the commands below it.
+        wide.generate(
+            &full_row_strips(),
+            solid(RED),
+            BlendMode::default(),
+            0,
+            None,
+            &[],
+        );
+        wide.generate(
+            &full_row_strips(),
+            solid(BLUE),
+            BlendMode::default(),
+            0,
+            None,
+            &[],
+        );
+        for (i, tile) in wide.tiles.iter().enumerate() {
+            assert_eq!(tile.x, 64 * i as u16);
+            assert_eq!(
+                tile.cmds,
+                [Cmd::Fill(CmdFill {
+                    x: 0,
+                    width: 64,
+                    attrs_idx: 1,
+                })]
+            );
+        }
+    }
+
+    /// Strips of a path covering `x0..x1` of the first row, with alphas starting at `alpha_idx`.
+    fn alpha_strips(x0: u16, x1: u16, alpha_idx: u32) -> [Strip; 2] {
+        let width = u32::from(x1 - x0);
+        [
+            Strip::new(x0, 0, alpha_idx, false),
+            Strip::new(x1, 0, alpha_idx + width * u32::from(Tile::HEIGHT), false),
+        ]
+    }
+
+    #[test]
+    fn adjacent_alpha_fills_are_coalesced() {
+        let mut wide = Wide::<MODE_HYBRID>::new(2 * WideTile::WIDTH, 4);
+        // Three abutting paths with consecutive alphas, the last one crossing into the next
+        // wide tile.
+        wide.generate(
+            &alpha_strips(10, 30, 0),
+            solid(RED),
+            BlendMode::default(),
+            0,
+            None,
+            &[],
+        );
+        wide.generate(
+            &alpha_strips(30, 200, 80),
+            solid(RED),
+            BlendMode::default(),
+            0,
+            None,
+            &[],
+        );
+        wide.generate(
+            &alpha_strips(200, 300, 760),
+            solid(RED),
+            BlendMode::default(),
+            0,
+            None,
+            &[],
+        );
+
+        assert_eq!(
+            wide.tiles[0].cmds,
+            [Cmd::AlphaFill(CmdAlphaFill {
+                x: 10,
+                width: 246,
+                alpha_offset: 0,
+                attrs_idx: 0,
+            })]
+        );
+        // The part in the second wide tile still reads the alphas following those of the first.
+        let [Cmd::AlphaFill(second)] = wide.tiles[1].cmds.as_slice() else {
+            panic!("expected a single alpha fill, got {:?}", wide.tiles[1].cmds);
+        };
+        let attrs = &wide.attrs.fill[second.attrs_idx as usize];
+        assert_eq!((second.x, second.width), (0, 44));
+        assert_eq!(
+            attrs.alpha_idx(second.alpha_offset),
+            246 * u32::from(Tile::HEIGHT)
+        );
+    }
+
+    #[test]
+    fn alpha_fills_with_gaps_or_different_paints_are_kept() {
+        let mut wide = Wide::<MODE_HYBRID>::new(WideTile::WIDTH, 4);
+        wide.generate(
+            &alpha_strips(0, 10, 0),
+            solid(RED),
+            BlendMode::default(),
+            0,
+            None,
+            &[],
+        );
+        // Different paint.
+        wide.generate(
+            &alpha_strips(10, 20, 40),
+            solid(BLUE),
+            BlendMode::default(),
+            0,
+            None,
+            &[],
+        );
+        // Gap on screen.
+        wide.generate(
+            &alpha_strips(21, 30, 80),
+            solid(BLUE),
+            BlendMode::default(),
+            0,
+            None,
+            &[],
+        );
+        // Gap in the alpha buffer.
+        wide.generate(
+            &alpha_strips(30, 40, 200),
+            solid(BLUE),
+            BlendMode::default(),
+            0,
+            None,
+            &[],
+        );
+        // Different thread.
+        wide.generate(
+            &alpha_strips(40, 50, 240),
+            solid(BLUE),
+            BlendMode::default(),
+            1,
+            None,
+            &[],
+        );
+
+        assert_eq!(wide.tiles[0].cmds.len(), 5);
+    }
+
+    #[test]
+    fn opaque_fill_with_blend_mode_keeps_covered_commands() {
+        let mut wide = Wide::<MODE_HYBRID>::new(2 * WideTile::WIDTH, 4);
+        let strips = full_row_strips();
+        wide.generate(&strips, solid(RED), BlendMode::default(), 0, None, &[]);
+        let multiply = BlendMode::new(Mix::Multiply, Compose::SrcOver);
+        wide.generate(&strips, solid(BLUE), multiply, 0, None, &[]);
+
+        for tile in &wide.tiles {
+            assert_eq!(tile.cmds.len(), 2);
+        }
+    }
 }
@@ -8,6 +8,7 @@ use crate::color::{AlphaColor, DynamicColor};
 use crate::glyph::{ColorGlyph, OutlinePath};
 use crate::kurbo::{Affine, BezPath, Point, Rect, Shape};
 use crate::math::FloatExt;
+use crate::paint::PaintType;
 use crate::peniko::{self, BlendMode, ColorStops, Compose, Extend, Gradient, Mix};
 use alloc::boxed::Box;
 use alloc::vec;
@@ -37,6 +38,16 @@ pub trait ColrRenderer {
     fn pop_layer(&mut self);
 }
 
+/// The color used for COLR layers that refer to the foreground color, given the current paint.
+///
+/// Non-solid paints fall back to black.
+pub fn context_color(paint: &PaintType) -> AlphaColor<Srgb> {
+    match paint {
+        PaintType::Solid(s) => *s,
+        _ => AlphaColor::BLACK,
+    }
+}
+
 /// An abstraction for painting COLR glyphs.
 pub struct ColrPainter<'a> {
     transforms: Vec<Affine>,
@@ -54,6 +65,9 @@ impl Debug for ColrPainter<'_> {
 
 impl<'a> ColrPainter<'a> {
     /// Create a new COLR painter.
+    ///
+    /// `context_color` is used for layers that refer to the foreground color, unless the glyph
+    /// run specified its own foreground color.
     pub fn new(
         color_glyph: Box<ColorGlyph<'a>>,
         context_color: AlphaColor<Srgb>,
@@ -61,8 +75,8 @@ impl<'a> ColrPainter<'a> {
     ) -> Self {
         Self {
             transforms: vec![color_glyph.draw_transform],
+            context_color: color_glyph.foreground_color.unwrap_or(context_color),
             color_glyph,
-            context_color,
             painter,
             layer_count: 0,
         }
@@ -88,13 +102,18 @@ impl<'a> ColrPainter<'a> {
 
     fn palette_index_to_color(&self, palette_index: u16, alpha: f32) -> Option<AlphaColor<Srgb>> {
         if palette_index != u16::MAX {
-            let color = self
-                .color_glyph
-                .font_ref
-                .cpal()
-                .ok()?
-                .color_records_array()?
-                .ok()?[palette_index as usize];
+            let cpal = self.color_glyph.font_ref.cpal().ok()?;
+            let records = cpal.color_records_array()?.ok()?;
+            let entry = |palette: u16| {
+                if palette_index >= cpal.num_palette_entries() {
+                    return None;
+                }
+                let first = cpal.color_record_indices().get(usize::from(palette))?.get();
+                records.get(usize::from(first) + usize::from(palette_index))
+            };
+            // Fall back to the default palette if the selected one doesn't exist or is
+            // missing the entry.
+            let color = entry(self.color_glyph.palette).or_else(|| entry(0))?;
 
             Some(
                 AlphaColor::from_rgba8(color.red, color.green, color.blue, color.alpha)
@@ -5,16 +5,16 @@
 
 use crate::blurred_rounded_rect::BlurredRoundedRectangle;
 use crate::color::palette::css::BLACK;
-use crate::color::{ColorSpaceTag, HueDirection, Srgb, gradient};
+use crate::color::{AlphaColor, ColorSpaceTag, HueDirection, Srgb, gradient};
 use crate::kurbo::{Affine, Point, Vec2};
 use crate::math::{FloatExt, compute_erf7};
-use crate::paint::{Image, ImageSource, IndexedPaint, Paint, PremulColor};
+use crate::paint::{Image, ImageSource, IndexedPaint, Paint, PremulColor, clip_to_gamut};
 use crate::peniko::{ColorStop, ColorStops, Extend, Gradient, GradientKind, ImageQuality};
 use alloc::borrow::Cow;
 use alloc::fmt::Debug;
 use alloc::vec;
 use alloc::vec::Vec;
-#[cfg(not(feature = "multithreading"))]
+#[cfg(not(feature = "std"))]
 use core::cell::OnceCell;
 use core::hash::{Hash, Hasher};
 use fearless_simd::{Simd, SimdBase, SimdFloat, f32x4, f32x16, mask32x4, mask32x16};
@@ -25,8 +25,9 @@ use peniko::{
     SweepGradientPosition,
 };
 use smallvec::ToSmallVec;
-// So we can just use `OnceCell` regardless of which feature is activated.
-#[cfg(feature = "multithreading")]
+// So we can just use `OnceCell` regardless of which feature is activated. Encoded paints are
+// only `Sync` with `OnceLock`, which e.g. allows frozen hybrid scenes to be shared across threads.
+#[cfg(feature = "std")]
 use std::sync::OnceLock as OnceCell;
 
 use crate::simd::{Splat4thExt, element_wise_splat};
@@ -327,32 +328,26 @@ fn encode_stops(
     #[derive(Debug)]
     struct EncodedColorStop {
         offset: f32,
-        color: crate::color::AlphaColor<Srgb>,
+        color: AlphaColor<Srgb>,
     }
 
     let create_range = |left_stop: &EncodedColorStop, right_stop: &EncodedColorStop| {
-        let clamp = |mut color: [f32; 4]| {
-            // The linear approximation of the gradient can produce values slightly outside of
-            // [0.0, 1.0], so clamp them.
-            for c in &mut color {
-                *c = c.clamp(0.0, 1.0);
+        let components = |color: AlphaColor<Srgb>| {
+            // Stops can be outside of the sRGB gamut, and the linear approximation of the
+            // gradient can produce values slightly outside of [0.0, 1.0], so clip them. This
+            // happens before premultiplying, so that the components never exceed the alpha.
+            let color = clip_to_gamut(color);
+            if interpolation_alpha_space == InterpolationAlphaSpace::Unpremultiplied {
+                color.components
+            } else {
+                color.premultiply().components
             }
-
-            color
         };
 
         let x0 = left_stop.offset;
         let x1 = right_stop.offset;
-        let c0 = if interpolation_alpha_space == InterpolationAlphaSpace::Unpremultiplied {
-            clamp(left_stop.color.components)
-        } else {
-            clamp(left_stop.color.premultiply().components)
-        };
-        let c1 = if interpolation_alpha_space == InterpolationAlphaSpace::Unpremultiplied {
-            clamp(right_stop.color.components)
-        } else {
-            clamp(right_stop.color.premultiply().components)
-        };
+        let c0 = components(left_stop.color);
+        let c1 = components(right_stop.color);
 
         // We calculate a bias and scale factor, such that we can simply calculate
         // bias + x * scale to get the interpolated color, where x is between x0 and x1,
@@ -526,7 +521,7 @@ impl EncodeExt for Image {
 }
 
 /// An encoded paint.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum EncodedPaint {
     /// An encoded gradient.
     Gradient(EncodedGradient),
@@ -536,6 +531,18 @@ pub enum EncodedPaint {
     BlurredRoundedRect(EncodedBlurredRoundedRectangle),
 }
 
+impl EncodedPaint {
+    /// Move the paint by `offset` in device space.
+    pub fn translate(&mut self, offset: Vec2) {
+        let transform = match self {
+            Self::Gradient(gradient) => &mut gradient.transform,
+            Self::Image(image) => &mut image.transform,
+            Self::BlurredRoundedRect(rect) => &mut rect.transform,
+        };
+        *transform *= Affine::translate(-offset);
+    }
+}
+
 impl From<EncodedGradient> for EncodedPaint {
     fn from(value: EncodedGradient) -> Self {
         Self::Gradient(value)
@@ -549,7 +556,7 @@ impl From<EncodedBlurredRoundedRectangle> for EncodedPaint {
 }
 
 /// An encoded image.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EncodedImage {
     /// The underlying pixmap of the image.
     pub source: ImageSource,
@@ -688,7 +695,7 @@ impl RadialKind {
 }
 
 /// Computed properties of a sweep gradient.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SweepKind {
     /// The start angle of the sweep gradient.
     pub start_angle: f32,
@@ -697,7 +704,7 @@ pub struct SweepKind {
 }
 
 /// A kind of encoded gradient.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum EncodedKind {
     /// An encoded linear gradient.
     Linear(LinearKind),
@@ -718,7 +725,7 @@ impl EncodedKind {
 }
 
 /// An encoded gradient.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EncodedGradient {
     /// The cache key for the gradient.
     pub cache_key: CacheKey<GradientCacheKey>,
@@ -797,7 +804,7 @@ pub struct GradientRange {
 }
 
 /// An encoded blurred, rounded rectangle.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EncodedBlurredRoundedRectangle {
     /// An component for computing the blur effect.
     pub exponent: f32,
@@ -966,7 +973,7 @@ impl FromF32Color for u8 {
 }
 
 /// A lookup table for sampled gradient values.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct GradientLut<T: FromF32Color> {
     lut: Vec<[T; 4]>,
     scale: f32,
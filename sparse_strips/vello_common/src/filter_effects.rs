@@ -16,30 +16,30 @@
 //! **Filter Functions:**
 //! - `Blur` - Gaussian blur effect
 //!
-//! **Filter Primitives (Single Use Only):**
+//! **Filter Primitives:**
 //! - `Flood` - Solid color fill
 //! - `GaussianBlur` - Gaussian blur filter
 //! - `DropShadow` - Drop shadow effect (compound primitive)
-//! - `Offset` - Translation/shift (single primitive)
+//! - `Offset` - Translation/shift
+//! - `ColorMatrix` - Matrix-based color transformation
+//! - `Morphology` - Dilate/erode operations
 //!
-//! **Note:** Currently only single primitive filters are supported. Filter graphs with
-//! multiple connected primitives are not yet implemented.
+//! **Core Features:**
+//! - `FilterGraph` execution - Chaining filter primitives in sequence, with each
+//!   primitive consuming the result of the previous one
 //!
 //! ### 🚧 Not Yet Implemented
 //!
 //! **Core Features:**
-//! - `FilterGraph` execution - Chaining multiple filter primitives together
-//! - `FilterInputs` - Connecting primitives to create complex effects
+//! - `FilterInputs` - Connecting primitives to create complex (non-linear) effects
 //!
 //! **Filter Functions:**
 //! - `Brightness`, `Contrast`, `Grayscale`, `HueRotate`, `Invert`,
 //!   `Opacity`, `Saturate`, `Sepia`
 //!
 //! **Filter Primitives:**
-//! - `ColorMatrix` - Matrix-based color transformation
 //! - `Composite` - Porter-Duff compositing operations
 //! - `Blend` - Blend mode operations
-//! - `Morphology` - Dilate/erode operations
 //! - `ConvolveMatrix` - Custom convolution kernels
 //! - `Turbulence` - Perlin noise generation
 //! - `DisplacementMap` - Pixel displacement
@@ -49,6 +49,8 @@
 //! - `DiffuseLighting`, `SpecularLighting` - Lighting effects
 
 use crate::color::{AlphaColor, Srgb};
+#[cfg(not(feature = "std"))]
+use crate::kurbo::common::FloatFuncs as _;
 use crate::kurbo::{Affine, Rect};
 use alloc::sync::Arc;
 use alloc::vec::Vec;
@@ -62,10 +64,6 @@ use smallvec::SmallVec;
 pub struct Filter {
     /// Filter graph defining the effect pipeline.
     pub graph: Arc<FilterGraph>,
-    // TODO: Add bounds restricting where the filter applies.
-    // Optional bounds restricting where the filter applies.
-    // If `None`, the filter applies to the entire filtered element.
-    // pub bounds: Option<Rect>,
 }
 
 impl Filter {
@@ -77,7 +75,7 @@ impl Filter {
         // Convert function to primitive
         let primitive = match function {
             FilterFunction::Blur { radius } => FilterPrimitive::GaussianBlur {
-                std_deviation: radius,
+                std_deviation: (radius, radius),
                 edge_mode: EdgeMode::default(),
             },
             _ => unimplemented!("Filter function {:?} not supported", function),
@@ -100,6 +98,48 @@ impl Filter {
         }
     }
 
+    /// Create a filter system from an ordered chain of filter primitives.
+    ///
+    /// The primitives are applied in sequence, with each one consuming the result of
+    /// the previous one, like a stack of primitives in an SVG `<filter>` element.
+    pub fn from_primitives(primitives: impl IntoIterator<Item = FilterPrimitive>) -> Self {
+        let mut graph = FilterGraph::new();
+        for primitive in primitives {
+            let filter_id = graph.add(primitive, None);
+            graph.set_output(filter_id);
+        }
+
+        Self {
+            graph: Arc::new(graph),
+        }
+    }
+
+    /// Restrict the filter to the given bounds.
+    ///
+    /// See [`FilterGraph::bounds`] for details.
+    pub fn with_bounds(mut self, bounds: Rect) -> Self {
+        Arc::make_mut(&mut self.graph).bounds = Some(bounds);
+        self
+    }
+
+    /// The bounds restricting where the filter applies, if any.
+    pub fn bounds(&self) -> Option<Rect> {
+        self.graph.bounds
+    }
+
+    /// Calculate the bounds of this filter in pixel/device space, if any.
+    ///
+    /// For transforms that rotate or skew, this is the axis-aligned bounding box of
+    /// the transformed bounds.
+    ///
+    /// # Arguments
+    /// * `transform` - The transform applied to this filter layer
+    pub fn device_bounds(&self, transform: &Affine) -> Option<Rect> {
+        self.graph
+            .bounds
+            .map(|bounds| transform.transform_rect_bbox(bounds))
+    }
+
     /// Calculate the bounds expansion for this filter in pixel/device space.
     ///
     /// Returns a `Rect` representing how many extra pixels are needed around the
@@ -135,6 +175,14 @@ pub struct FilterGraph {
     pub primitives: SmallVec<[FilterPrimitive; 1]>,
     /// The final output filter ID whose result is the output of this graph.
     pub output: FilterId,
+    /// Optional bounds restricting where the filter applies, similar to the filter region
+    /// of an SVG `<filter>` element.
+    ///
+    /// The bounds are given in the coordinate space of the filter layer, i.e. they are
+    /// affected by the transform in place when the layer is pushed. Both the input and the
+    /// output of the filter are cropped to the bounds, so content outside of them is clipped.
+    /// If `None`, the filter applies to the entire filtered element.
+    pub bounds: Option<Rect>,
     /// Next available filter ID (monotonically increasing counter).
     next_id: u16,
     /// Accumulated bounds expansion from all primitives in the graph, cached in user space.
@@ -155,6 +203,7 @@ impl FilterGraph {
         Self {
             primitives: SmallVec::new(),
             output: FilterId(0),
+            bounds: None,
             next_id: 0,
             expansion_rect: Rect::ZERO,
         }
@@ -164,13 +213,22 @@ impl FilterGraph {
     ///
     /// Returns a `FilterId` that can be referenced by other primitives.
     /// Automatically updates the accumulated bounds expansion based on the primitive's requirements.
+    ///
+    /// Since explicit inputs are not yet supported, the primitive consumes the result
+    /// of the previously added primitive.
     pub fn add(&mut self, primitive: FilterPrimitive, _inputs: Option<FilterInputs>) -> FilterId {
         let id = FilterId(self.next_id);
         self.next_id += 1;
 
-        // Update accumulated expansion by taking the union of rects
+        // Each primitive operates on the output of the previous one, so the expansions
+        // accumulate: a blur followed by an offset needs room for both.
         let primitive_rect = primitive.expansion_rect();
-        self.expansion_rect = self.expansion_rect.union(primitive_rect);
+        self.expansion_rect = Rect::new(
+            self.expansion_rect.x0 + primitive_rect.x0,
+            self.expansion_rect.y0 + primitive_rect.y0,
+            self.expansion_rect.x1 + primitive_rect.x1,
+            self.expansion_rect.y1 + primitive_rect.y1,
+        );
 
         self.primitives.push(primitive);
 
@@ -366,17 +424,14 @@ pub enum FilterPrimitive {
     /// approximately 3 × `std_deviation`, as this captures ~99.7% of the
     /// Gaussian distribution.
     GaussianBlur {
-        /// Standard deviation for the blur kernel. Larger values create more blur.
-        /// Must be non-negative. A value of 0 means no blur.
+        /// Standard deviations for the blur kernel along the x and y axes.
+        /// Larger values create more blur. Must be non-negative.
         ///
         /// This directly corresponds to the σ (sigma) parameter in the Gaussian
         /// function. The visible blur effect extends approximately 3σ in each direction.
-        ///
-        /// TODO: Per the W3C specification, this should support separate x and y values.
-        /// The spec allows `stdDeviation` to be either one number (applied to both axes)
-        /// or two numbers (first for x-axis, second for y-axis). Currently only uniform
-        /// blur is supported. Consider changing to `(f32, f32)` or a dedicated type.
-        std_deviation: f32,
+        /// A value of 0 on one axis means the blur is only applied along the other axis;
+        /// if both are 0, no blur is applied.
+        std_deviation: (f32, f32),
         /// Edge mode determining how pixels beyond the input bounds are handled.
         edge_mode: EdgeMode,
     },
@@ -392,23 +447,26 @@ pub enum FilterPrimitive {
         dx: f32,
         /// Vertical offset of the shadow in pixels. Positive values shift down.
         dy: f32,
-        /// Blur standard deviation for the shadow. Larger values create softer shadows.
-        std_deviation: f32,
+        /// Blur standard deviations for the shadow along the x and y axes.
+        /// Larger values create softer shadows.
+        std_deviation: (f32, f32),
         /// Shadow color with alpha channel. Alpha controls shadow opacity.
         color: AlphaColor<Srgb>,
         /// Edge mode for handling boundaries during blur operation.
         /// Default is `EdgeMode::None` per SVG spec.
         edge_mode: EdgeMode,
     },
-    //
-    // ============================================================
-    // TODO: The following filter primitives are not yet implemented
-    // ============================================================
-    //
     /// Matrix-based color transformation.
     ///
     /// Applies a 4x5 matrix transformation to colors, allowing arbitrary
     /// color space transformations, hue shifts, and color adjustments.
+    /// The matrix operates on un-premultiplied color values in the range `[0, 1]`,
+    /// and the results are clamped to that range.
+    ///
+    /// See [`FilterPrimitive::saturate`], [`FilterPrimitive::hue_rotate`] and
+    /// [`FilterPrimitive::luminance_to_alpha`] for the shorthands defined by `feColorMatrix`.
+    ///
+    /// See: <https://drafts.fxtf.org/filter-effects/#feColorMatrixElement>
     ColorMatrix {
         /// 4x5 color transformation matrix: 4 rows (R,G,B,A) × 5 columns (R,G,B,A,offset).
         /// Each output channel is computed as a linear combination of input channels plus offset.
@@ -425,6 +483,25 @@ pub enum FilterPrimitive {
         dy: f32,
     },
 
+    /// Morphological operations (dilate/erode).
+    ///
+    /// Expands (dilate) or contracts (erode) the shapes in the input image.
+    /// Useful for creating outline effects or cleaning up edges.
+    /// Pixels outside of the input image are treated as transparent black.
+    ///
+    /// See: <https://drafts.fxtf.org/filter-effects/#feMorphologyElement>
+    Morphology {
+        /// Morphological operator determining whether to erode or dilate.
+        operator: MorphologyOperator,
+        /// Operation radius `(x, y)` in pixels. Larger values create stronger effects.
+        /// A negative or zero value on either axis disables the effect.
+        radius: (f32, f32),
+    },
+    //
+    // ============================================================
+    // TODO: The following filter primitives are not yet implemented
+    // ============================================================
+    //
     /// Composite two inputs using Porter-Duff compositing operations.
     ///
     /// Combines two input images using standard compositing operators
@@ -441,16 +518,6 @@ pub enum FilterPrimitive {
         /// Blend mode determining how colors are combined.
         mode: BlendMode,
     },
-    /// Morphological operations (dilate/erode).
-    ///
-    /// Expands (dilate) or contracts (erode) the shapes in the input image.
-    /// Useful for creating outline effects or cleaning up edges.
-    Morphology {
-        /// Morphological operator determining whether to erode or dilate.
-        operator: MorphologyOperator,
-        /// Operation radius in pixels. Larger values create stronger effects.
-        radius: f32,
-    },
     /// Custom convolution kernel for image processing.
     ///
     /// Applies a custom convolution matrix to the input image, enabling
@@ -548,6 +615,94 @@ pub enum FilterPrimitive {
 }
 
 impl FilterPrimitive {
+    /// The identity color matrix, which leaves colors unchanged.
+    pub const IDENTITY_COLOR_MATRIX: [f32; 20] = [
+        1.0, 0.0, 0.0, 0.0, 0.0, //
+        0.0, 1.0, 0.0, 0.0, 0.0, //
+        0.0, 0.0, 1.0, 0.0, 0.0, //
+        0.0, 0.0, 0.0, 1.0, 0.0, //
+    ];
+
+    /// Create a [`ColorMatrix`](Self::ColorMatrix) that adjusts the color saturation.
+    ///
+    /// An amount of 0 fully desaturates (producing the luma of the color), 1 leaves
+    /// colors unchanged and values greater than 1 oversaturate.
+    ///
+    /// This corresponds to `feColorMatrix` with `type="saturate"`.
+    pub fn saturate(amount: f32) -> Self {
+        let s = amount;
+        Self::ColorMatrix {
+            matrix: [
+                0.213 + 0.787 * s,
+                0.715 - 0.715 * s,
+                0.072 - 0.072 * s,
+                0.0,
+                0.0, //
+                0.213 - 0.213 * s,
+                0.715 + 0.285 * s,
+                0.072 - 0.072 * s,
+                0.0,
+                0.0, //
+                0.213 - 0.213 * s,
+                0.715 - 0.715 * s,
+                0.072 + 0.928 * s,
+                0.0,
+                0.0, //
+                0.0,
+                0.0,
+                0.0,
+                1.0,
+                0.0, //
+            ],
+        }
+    }
+
+    /// Create a [`ColorMatrix`](Self::ColorMatrix) that rotates the hue by `angle` degrees.
+    ///
+    /// This corresponds to `feColorMatrix` with `type="hueRotate"`.
+    pub fn hue_rotate(angle: f32) -> Self {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        Self::ColorMatrix {
+            matrix: [
+                0.213 + cos * 0.787 - sin * 0.213,
+                0.715 - cos * 0.715 - sin * 0.715,
+                0.072 - cos * 0.072 + sin * 0.928,
+                0.0,
+                0.0,
+                0.213 - cos * 0.213 + sin * 0.143,
+                0.715 + cos * 0.285 + sin * 0.140,
+                0.072 - cos * 0.072 - sin * 0.283,
+                0.0,
+                0.0,
+                0.213 - cos * 0.213 - sin * 0.787,
+                0.715 - cos * 0.715 + sin * 0.715,
+                0.072 + cos * 0.928 + sin * 0.072,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                1.0,
+                0.0,
+            ],
+        }
+    }
+
+    /// Create a [`ColorMatrix`](Self::ColorMatrix) that converts the luminance of each
+    /// pixel into its alpha, leaving the color channels black.
+    ///
+    /// This corresponds to `feColorMatrix` with `type="luminanceToAlpha"`.
+    pub fn luminance_to_alpha() -> Self {
+        Self::ColorMatrix {
+            matrix: [
+                0.0, 0.0, 0.0, 0.0, 0.0, //
+                0.0, 0.0, 0.0, 0.0, 0.0, //
+                0.0, 0.0, 0.0, 0.0, 0.0, //
+                0.2125, 0.7154, 0.0721, 0.0, 0.0, //
+            ],
+        }
+    }
+
     /// Calculate the bounds expansion as a `Rect` in user space.
     ///
     /// Returns a rectangle centered at the origin representing how much the filter
@@ -565,9 +720,10 @@ impl FilterPrimitive {
     pub fn expansion_rect(&self) -> Rect {
         match self {
             Self::GaussianBlur { std_deviation, .. } => {
-                // Gaussian blur expands uniformly by 3*sigma (covers 99.7% of distribution)
-                let radius = (*std_deviation * 3.0) as f64;
-                Rect::new(-radius, -radius, radius, radius)
+                // Gaussian blur expands by 3*sigma on each axis (covers 99.7% of distribution)
+                let radius_x = (std_deviation.0 * 3.0) as f64;
+                let radius_y = (std_deviation.1 * 3.0) as f64;
+                Rect::new(-radius_x, -radius_y, radius_x, radius_y)
             }
             Self::Offset { dx, dy } => {
                 // Offset shifts pixels; expand bounds asymmetrically so shifted content isn't cut.
@@ -583,17 +739,27 @@ impl FilterPrimitive {
             } => {
                 // Drop shadow = blur + offset + composite with original
                 // The expansion rect encompasses both the blur and the offset
-                let blur_radius = (*std_deviation * 3.0) as f64;
+                let blur_radius_x = (std_deviation.0 * 3.0) as f64;
+                let blur_radius_y = (std_deviation.1 * 3.0) as f64;
                 let dx = *dx as f64;
                 let dy = *dy as f64;
 
                 Rect::new(
-                    -(blur_radius + (-dx).max(0.0)),
-                    -(blur_radius + (-dy).max(0.0)),
-                    blur_radius + dx.max(0.0),
-                    blur_radius + dy.max(0.0),
+                    -(blur_radius_x + (-dx).max(0.0)),
+                    -(blur_radius_y + (-dy).max(0.0)),
+                    blur_radius_x + dx.max(0.0),
+                    blur_radius_y + dy.max(0.0),
                 )
             }
+            Self::Morphology {
+                operator: MorphologyOperator::Dilate,
+                radius,
+            } if radius.0 > 0.0 && radius.1 > 0.0 => {
+                // Dilation grows shapes by the radius on each axis. Erosion only shrinks them.
+                let radius_x = radius.0 as f64;
+                let radius_y = radius.1 as f64;
+                Rect::new(-radius_x, -radius_y, radius_x, radius_y)
+            }
             // Most other filters don't expand bounds
             _ => Rect::ZERO,
         }
@@ -614,6 +780,22 @@ mod offset_expansion_tests {
             "Offset expansion should be asymmetric and include the shift vector"
         );
     }
+
+    #[test]
+    fn chained_offset_expansion_accumulates() {
+        let filter = super::Filter::from_primitives([
+            FilterPrimitive::GaussianBlur {
+                std_deviation: (2.0, 2.0),
+                edge_mode: super::EdgeMode::None,
+            },
+            FilterPrimitive::Offset { dx: 5.0, dy: 0.0 },
+        ]);
+        assert_eq!(
+            filter.bounds_expansion(&crate::kurbo::Affine::IDENTITY),
+            Rect::new(-6.0, -6.0, 11.0, 6.0),
+            "Offset should shift the region already expanded by the blur"
+        );
+    }
 }
 
 /// Unique identifier for a filter primitive in the graph.
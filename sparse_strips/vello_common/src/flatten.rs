@@ -2,20 +2,57 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 //! Flattening filled and stroked paths.
+//!
+//! [`fill`] and [`stroke`] approximate a path by the [`Line`]s of its outline, after applying a
+//! transform. They're what the renderers use to turn paths into strips, but they don't depend
+//! on a scene or any other rendering state, so they can also be used on their own, for example
+//! to build collision meshes or to inspect the geometry that a path is rendered with.
+//!
+//! ```
+//! use vello_common::fearless_simd::Level;
+//! use vello_common::flatten::{self, FlattenCtx, Line};
+//! use vello_common::kurbo::{Affine, Circle, Shape};
+//!
+//! let circle = Circle::new((50.0, 50.0), 40.0);
+//! let mut ctx = FlattenCtx::default();
+//! ctx.set_tolerance(0.1);
+//! let mut lines: Vec<Line> = Vec::new();
+//! flatten::fill(
+//!     Level::try_detect().unwrap_or(Level::fallback()),
+//!     circle.path_elements(0.1),
+//!     Affine::IDENTITY,
+//!     &mut lines,
+//!     &mut ctx,
+//! );
+//!
+//! // The lines form a closed polygon whose corners are on the circle.
+//! assert_eq!(lines.first().unwrap().p0, lines.last().unwrap().p1);
+//! for line in &lines {
+//!     let (dx, dy) = (line.p0.x - 50.0, line.p0.y - 50.0);
+//!     assert!(((dx * dx + dy * dy).sqrt() - 40.0).abs() <= 0.1);
+//! }
+//! ```
+//!
+//! The buffer of lines and the contexts can be reused across paths to avoid allocations.
 
 use crate::flatten_simd::{Callback, LinePathEl};
 use crate::kurbo::{self, Affine, PathEl, Stroke, StrokeCtx, StrokeOpts};
+use crate::util::extract_scales;
 use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash, Hasher};
 use fearless_simd::{Level, Simd, dispatch};
-use log::warn;
+use hashbrown::HashMap;
+use hashbrown::hash_map::Entry;
+use log::debug;
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
 
 pub use crate::flatten_simd::FlattenCtx;
 
-// The current tolerance is set to 0.25. Since `sqrt` doesn't work in const contexts, we instead
-// hardcode the squared tolerance and derive the others from that.
-pub(crate) const SQRT_TOL: f64 = 0.5;
-pub(crate) const TOL: f64 = SQRT_TOL * SQRT_TOL;
-pub(crate) const TOL_2: f64 = TOL * TOL;
+/// The default flattening tolerance, in device space (pixels).
+///
+/// See [`FlattenCtx::set_tolerance`].
+pub const DEFAULT_TOLERANCE: f64 = 0.25;
 
 /// A point.
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -60,7 +97,10 @@ impl core::ops::Mul<f32> for Point {
     }
 }
 
-/// A line.
+/// A line of a flattened path, directed from `p0` to `p1`.
+///
+/// The direction of the lines follows the direction of the path, which determines the winding
+/// numbers used by the fill rule.
 #[derive(Clone, Copy, Debug)]
 pub struct Line {
     /// The start point of the line.
@@ -77,6 +117,17 @@ impl Line {
 }
 
 /// Flatten a filled bezier path into line segments.
+///
+/// `line_buf` is cleared and then filled with the lines of the outline of `path`, transformed
+/// by `affine`, in the order of the path. Every subpath is closed, even if it doesn't end with
+/// a `ClosePath`, so that the lines enclose the filled area. Zero-length lines are skipped, and
+/// so are segments with non-finite coordinates.
+///
+/// The path is transformed before being flattened, so the tolerance of `ctx` applies
+/// in device space, independently of the scale of the transform. Curves are approximated by
+/// as few lines as possible while staying within that distance of the curve.
+///
+/// See the [module documentation](self) for an example.
 pub fn fill(
     level: Level,
     path: impl IntoIterator<Item = PathEl>,
@@ -97,28 +148,99 @@ pub fn fill_impl<S: Simd>(
     flatten_ctx: &mut FlattenCtx,
 ) {
     line_buf.clear();
-    let iter = path.into_iter().map(
-        #[inline(always)]
-        |el| affine * el,
-    );
+    let mut has_non_finite = false;
+    let iter = finite_elements(path, affine, &mut has_non_finite);
 
     let mut lb = FlattenerCallback {
         line_buf,
-        start: Point::ZERO,
         p0: Point::ZERO,
-        is_nan: false,
+        last: kurbo::Point::ZERO,
     };
 
     crate::flatten_simd::flatten(simd, iter, &mut lb, flatten_ctx);
 
-    // A path that contains NaN is ill-defined, so ignore it.
-    if lb.is_nan {
-        warn!("A path contains NaN, ignoring it.");
-
-        line_buf.clear();
+    if has_non_finite {
+        debug!("A path contains non-finite coordinates, ignoring the affected segments.");
     }
 }
+
+/// Transform the elements of `path` by `affine`, and drop the parts of the result that aren't
+/// finite.
+///
+/// A segment with a non-finite control point is replaced by a line to its end point, and a
+/// segment with a non-finite end point is dropped, so that the path continues from the last
+/// finite point. A subpath that starts at a non-finite point instead starts at the end point
+/// of its first finite segment. `has_non_finite` is set if anything was replaced or dropped.
+fn finite_elements(
+    path: impl IntoIterator<Item = PathEl>,
+    affine: Affine,
+    has_non_finite: &mut bool,
+) -> impl Iterator<Item = PathEl> {
+    let mut needs_move_to = false;
+    path.into_iter().filter_map(
+        #[inline(always)]
+        move |el| {
+            let el = affine * el;
+            if el.is_finite() {
+                if needs_move_to && let Some(p) = el.end_point() {
+                    needs_move_to = false;
+                    return Some(PathEl::MoveTo(p));
+                }
+                return (!needs_move_to || !matches!(el, PathEl::ClosePath)).then_some(el);
+            }
+
+            *has_non_finite = true;
+            match el.end_point() {
+                Some(p) if p.is_finite() => {
+                    if needs_move_to {
+                        needs_move_to = false;
+                        Some(PathEl::MoveTo(p))
+                    } else {
+                        Some(PathEl::LineTo(p))
+                    }
+                }
+                _ => {
+                    needs_move_to |= matches!(el, PathEl::MoveTo(_));
+                    None
+                }
+            }
+        },
+    )
+}
+
 /// Flatten a stroked bezier path into line segments.
+///
+/// The stroke of `path` with `style` is expanded into the outline of the area that it covers,
+/// which is then flattened like with [`fill`]: `line_buf` is cleared and filled with the lines
+/// of the outline, transformed by `affine`. The outline has to be filled with the non-zero
+/// fill rule. The width and dashes of `style` are in the coordinate space of `path`, so they
+/// are transformed as well.
+///
+/// Like for [`fill`], the tolerance of `flatten_ctx` applies in device space.
+///
+/// ```
+/// use vello_common::fearless_simd::Level;
+/// use vello_common::flatten::{self, FlattenCtx, Line};
+/// use vello_common::kurbo::{Affine, Cap, Line as KurboLine, Shape, Stroke, StrokeCtx};
+///
+/// let mut lines: Vec<Line> = Vec::new();
+/// flatten::stroke(
+///     Level::fallback(),
+///     KurboLine::new((10.0, 10.0), (90.0, 10.0)).path_elements(0.1),
+///     &Stroke::new(4.0).with_caps(Cap::Butt),
+///     Affine::IDENTITY,
+///     &mut lines,
+///     &mut FlattenCtx::default(),
+///     &mut StrokeCtx::default(),
+/// );
+///
+/// // A line with butt caps is stroked as a rectangle around it.
+/// assert_eq!(lines.len(), 4);
+/// for line in &lines {
+///     assert!((10.0..=90.0).contains(&line.p0.x));
+///     assert!((8.0..=12.0).contains(&line.p0.y));
+/// }
+/// ```
 pub fn stroke(
     level: Level,
     path: impl IntoIterator<Item = PathEl>,
@@ -128,32 +250,356 @@ pub fn stroke(
     flatten_ctx: &mut FlattenCtx,
     stroke_ctx: &mut StrokeCtx,
 ) {
-    // TODO: Temporary hack to ensure that strokes are scaled properly by the transform.
-    let tolerance = TOL
-        / affine.as_coeffs()[0]
-            .abs()
-            .max(affine.as_coeffs()[3].abs())
-            .max(1.);
+    let tolerance = stroke_tolerance(affine, flatten_ctx);
 
+    // Non-finite coordinates would propagate through the whole expanded stroke, so they are
+    // already dropped before expanding it.
+    let mut has_non_finite = false;
+    let path = finite_elements(path, Affine::IDENTITY, &mut has_non_finite);
     expand_stroke(path, style, tolerance, stroke_ctx);
+    if has_non_finite {
+        debug!("A stroked path contains non-finite coordinates, ignoring the affected segments.");
+    }
+
     fill(level, stroke_ctx.output(), affine, line_buf, flatten_ctx);
 }
 
+/// Flatten a stroked bezier path into line segments, reusing its expansion from `stroke_cache`
+/// if the same path was already stroked with the same style and tolerance.
+///
+/// Apart from the caching, this is the same as [`stroke`].
+pub fn stroke_cached(
+    level: Level,
+    path: impl IntoIterator<Item = PathEl>,
+    style: &Stroke,
+    affine: Affine,
+    line_buf: &mut Vec<Line>,
+    flatten_ctx: &mut FlattenCtx,
+    stroke_ctx: &mut StrokeCtx,
+    stroke_cache: &mut StrokeCache,
+) {
+    let tolerance = stroke_tolerance(affine, flatten_ctx);
+    let expanded = stroke_cache.get_or_expand(path, style, tolerance, stroke_ctx);
+
+    fill(level, expanded, affine, line_buf, flatten_ctx);
+}
+
+/// The tolerance for expanding a stroke that is drawn with `affine`.
+fn stroke_tolerance(affine: Affine, flatten_ctx: &FlattenCtx) -> f64 {
+    // Strokes are expanded before applying the transform, so the expansion tolerance needs
+    // to be scaled down by the largest amount the transform can stretch a distance, which is
+    // its largest singular value. This also covers rotations, skews and non-uniform scales.
+    let (max_scale, _) = extract_scales(&affine);
+    flatten_ctx.tolerance() / f64::from(max_scale).max(1.)
+}
+
+/// The number of calls to [`StrokeCache::maintain`] for which an unused expanded stroke is
+/// retained.
+const MAX_STROKE_AGE: u32 = 8;
+
+/// A cache of expanded strokes.
+///
+/// Expanding a stroke is much more expensive than flattening the expanded path, so a path that
+/// is stroked repeatedly, for example an outline that is animated by moving it around, only
+/// needs to be expanded once. Expanded strokes are keyed by the path, the stroke style and the
+/// expansion tolerance. As the tolerance depends on how much the transform scales the path,
+/// a transform that only translates or rotates it reuses the expanded stroke, while a change of
+/// scale expands it again.
+#[derive(Debug, Default)]
+pub struct StrokeCache {
+    entries: HashMap<u64, StrokeCacheEntry>,
+    path_buf: Vec<PathEl>,
+    serial: u32,
+    expansions: u32,
+}
+
+#[derive(Debug)]
+struct StrokeCacheEntry {
+    path: Vec<PathEl>,
+    style: Stroke,
+    tolerance: f64,
+    expanded: kurbo::BezPath,
+    serial: u32,
+}
+
+impl StrokeCache {
+    /// Create a new, empty stroke cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the expansion of `path` stroked with `style`, expanding it with `stroke_ctx` if
+    /// it isn't cached yet.
+    pub fn get_or_expand(
+        &mut self,
+        path: impl IntoIterator<Item = PathEl>,
+        style: &Stroke,
+        tolerance: f64,
+        stroke_ctx: &mut StrokeCtx,
+    ) -> &kurbo::BezPath {
+        self.path_buf.clear();
+        self.path_buf.extend(path);
+        let path = &self.path_buf;
+
+        let mut hasher = self.entries.hasher().build_hasher();
+        hash_path(path, &mut hasher);
+        hash_stroke(style, &mut hasher);
+        tolerance.to_bits().hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let serial = self.serial;
+        let is_match = |entry: &StrokeCacheEntry| {
+            entry.path == *path
+                && entry.style == *style
+                && entry.tolerance.to_bits() == tolerance.to_bits()
+        };
+        let entry = match self.entries.entry(hash) {
+            Entry::Occupied(entry) if is_match(entry.get()) => {
+                let entry = entry.into_mut();
+                entry.serial = serial;
+                return &entry.expanded;
+            }
+            // A hash collision, replace the other stroke.
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(StrokeCacheEntry {
+                path: Vec::new(),
+                style: Stroke::default(),
+                tolerance,
+                expanded: kurbo::BezPath::new(),
+                serial,
+            }),
+        };
+
+        // Non-finite coordinates would propagate through the whole expanded stroke, so they are
+        // already dropped before expanding it.
+        let mut has_non_finite = false;
+        let finite = finite_elements(path.iter().copied(), Affine::IDENTITY, &mut has_non_finite);
+        expand_stroke(finite, style, tolerance, stroke_ctx);
+        if has_non_finite {
+            debug!(
+                "A stroked path contains non-finite coordinates, ignoring the affected segments."
+            );
+        }
+        self.expansions += 1;
+
+        entry.path.clone_from(path);
+        entry.style.clone_from(style);
+        entry.tolerance = tolerance;
+        entry.serial = serial;
+        entry.expanded.truncate(0);
+        entry.expanded.extend(stroke_ctx.output().iter());
+        &entry.expanded
+    }
+
+    /// Return how many strokes were expanded since the last [`maintain`](Self::maintain),
+    /// i.e. how many strokes weren't found in the cache.
+    pub fn expansions(&self) -> u32 {
+        self.expansions
+    }
+
+    /// Return the number of cached expanded strokes.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Evict the expanded strokes that weren't used recently.
+    ///
+    /// This should be called once per frame. Strokes that weren't used during the last
+    /// 8 frames are evicted.
+    pub fn maintain(&mut self) {
+        let serial = self.serial;
+        self.serial = self.serial.wrapping_add(1);
+        self.expansions = 0;
+        self.entries
+            .retain(|_, entry| serial.wrapping_sub(entry.serial) < MAX_STROKE_AGE);
+    }
+
+    /// Remove all expanded strokes from the cache.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.expansions = 0;
+    }
+}
+
+fn hash_path(path: &[PathEl], hasher: &mut impl Hasher) {
+    let mut hash_point = |p: kurbo::Point| {
+        hasher.write_u64(p.x.to_bits());
+        hasher.write_u64(p.y.to_bits());
+    };
+    for el in path {
+        match *el {
+            PathEl::MoveTo(p) => {
+                hash_point(p);
+            }
+            PathEl::LineTo(p) => {
+                hash_point(p);
+            }
+            PathEl::QuadTo(p1, p2) => {
+                hash_point(p1);
+                hash_point(p2);
+            }
+            PathEl::CurveTo(p1, p2, p3) => {
+                hash_point(p1);
+                hash_point(p2);
+                hash_point(p3);
+            }
+            PathEl::ClosePath => {}
+        }
+    }
+    hasher.write_usize(path.len());
+}
+
+fn hash_stroke(style: &Stroke, hasher: &mut impl Hasher) {
+    hasher.write_u64(style.width.to_bits());
+    hasher.write_u8(style.join as u8);
+    hasher.write_u64(style.miter_limit.to_bits());
+    hasher.write_u8(style.start_cap as u8);
+    hasher.write_u8(style.end_cap as u8);
+    for dash in &style.dash_pattern {
+        hasher.write_u64(dash.to_bits());
+    }
+    hasher.write_u64(style.dash_offset.to_bits());
+}
+
 /// Expand a stroked path to a filled path.
+///
+/// Like in SVG, a dash pattern with negative or non-finite lengths, or whose lengths sum up to
+/// zero, is ignored, so the path is stroked without dashes.
 pub fn expand_stroke(
     path: impl IntoIterator<Item = PathEl>,
     style: &Stroke,
     tolerance: f64,
     stroke_ctx: &mut StrokeCtx,
 ) {
+    if !style.dash_pattern.is_empty()
+        && (dash_period(&style.dash_pattern).is_none() || !style.dash_offset.is_finite())
+    {
+        debug!("Ignoring the invalid dash pattern of a stroke.");
+        let style = Stroke {
+            dash_pattern: kurbo::Dashes::default(),
+            dash_offset: 0.0,
+            ..style.clone()
+        };
+        kurbo::stroke_with(path, &style, &StrokeOpts::default(), tolerance, stroke_ctx);
+        return;
+    }
+
     kurbo::stroke_with(path, style, &StrokeOpts::default(), tolerance, stroke_ctx);
 }
 
+/// Wrap `dash_offset` into a single period of `dash_pattern`.
+///
+/// Offsets that differ by a multiple of the period produce the same dashes, so an animation
+/// like "marching ants" can keep increasing the offset and wrap it with this function, which
+/// keeps it from losing precision as it grows. Like in SVG, a pattern with an odd number of
+/// lengths is repeated twice to form a period. The offset is returned unchanged if the
+/// pattern is empty or invalid.
+pub fn wrap_dash_offset(dash_offset: f64, dash_pattern: &[f64]) -> f64 {
+    match dash_period(dash_pattern) {
+        Some(period) if dash_offset.is_finite() => {
+            let wrapped = dash_offset.rem_euclid(period);
+            // The remainder of a tiny negative offset rounds up to the period itself.
+            if wrapped < period { wrapped } else { 0.0 }
+        }
+        _ => dash_offset,
+    }
+}
+
+/// The length of a period of `dash_pattern`, or `None` if the pattern is empty or invalid.
+fn dash_period(dash_pattern: &[f64]) -> Option<f64> {
+    if dash_pattern
+        .iter()
+        .any(|length| !length.is_finite() || *length < 0.0)
+    {
+        return None;
+    }
+
+    let sum: f64 = dash_pattern.iter().sum();
+    let period = if dash_pattern.len() % 2 == 1 {
+        2.0 * sum
+    } else {
+        sum
+    };
+    (period > 0.0 && period.is_finite()).then_some(period)
+}
+
+/// The largest absolute value of the coordinates of flattened lines.
+///
+/// Geometry beyond this is far outside of any viewport, but its coordinates could overflow
+/// when being converted to `f32` or packed into tiles, so it is clamped to this range.
+const MAX_COORDINATE: f64 = 1e9;
+
 struct FlattenerCallback<'a> {
     line_buf: &'a mut Vec<Line>,
-    start: Point,
+    /// The end point of the last line, clamped to [`MAX_COORDINATE`].
     p0: Point,
-    is_nan: bool,
+    /// The end point of the last line, before clamping.
+    last: kurbo::Point,
+}
+
+impl FlattenerCallback<'_> {
+    /// Push a line from the current point to `p`, which is already clamped.
+    #[inline(always)]
+    fn line_to(&mut self, p: kurbo::Point) {
+        let p = Point::new(p.x as f32, p.y as f32);
+        // Zero-length lines don't contribute to the coverage of a path, but they would
+        // still generate (empty) tiles.
+        if p != self.p0 {
+            self.line_buf.push(Line::new(self.p0, p));
+            self.p0 = p;
+        }
+    }
+
+    /// Push the lines of the line from the current point to `p`, clamped to [`MAX_COORDINATE`].
+    ///
+    /// Clamping only the end points would change the direction of lines that reach into the
+    /// viewport. Instead, the line is split where it leaves the allowed range, and the parts
+    /// outside of it are moved onto its border. This doesn't change the winding number of any
+    /// point within the range.
+    #[cold]
+    fn clamped_line_to(&mut self, p: kurbo::Point) {
+        // Halve the coordinates so that their differences can't overflow.
+        let (a, b) = (self.last.to_vec2() * 0.5, p.to_vec2() * 0.5);
+        let bound = MAX_COORDINATE * 0.5;
+
+        let mut splits = [0.0; 4];
+        let mut num_splits = 0;
+        for (a, b) in [(a.x, b.x), (a.y, b.y)] {
+            for bound in [-bound, bound] {
+                let t = (bound - a) / (b - a);
+                if t > 0.0 && t < 1.0 {
+                    splits[num_splits] = t;
+                    num_splits += 1;
+                }
+            }
+        }
+        splits[..num_splits].sort_unstable_by(f64::total_cmp);
+
+        for t in &splits[..num_splits] {
+            self.line_to(clamp_point((a * (1.0 - t) + b * *t) * 2.0));
+        }
+        self.line_to(clamp_point(p.to_vec2()));
+    }
+}
+
+/// Clamp the coordinates of a point to [`MAX_COORDINATE`].
+#[inline(always)]
+fn clamp_point(p: kurbo::Vec2) -> kurbo::Point {
+    kurbo::Point::new(
+        p.x.clamp(-MAX_COORDINATE, MAX_COORDINATE),
+        p.y.clamp(-MAX_COORDINATE, MAX_COORDINATE),
+    )
+}
+
+/// Whether both coordinates of a point are within [`MAX_COORDINATE`].
+#[inline(always)]
+fn is_in_range(p: kurbo::Point) -> bool {
+    p.x.abs() <= MAX_COORDINATE && p.y.abs() <= MAX_COORDINATE
 }
 
 impl Callback for FlattenerCallback<'_> {
@@ -161,18 +607,410 @@ impl Callback for FlattenerCallback<'_> {
     fn callback(&mut self, el: LinePathEl) {
         match el {
             LinePathEl::MoveTo(p) => {
-                self.is_nan |= p.is_nan();
-
-                self.start = Point::new(p.x as f32, p.y as f32);
-                self.p0 = self.start;
+                let clamped = clamp_point(p.to_vec2());
+                self.p0 = Point::new(clamped.x as f32, clamped.y as f32);
+                self.last = p;
             }
             LinePathEl::LineTo(p) => {
-                self.is_nan |= p.is_nan();
+                if is_in_range(self.last) && is_in_range(p) {
+                    self.line_to(p);
+                } else {
+                    self.clamped_line_to(p);
+                }
+                self.last = p;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DEFAULT_TOLERANCE, FlattenCtx, Line, MAX_COORDINATE, Point, fill, stroke, wrap_dash_offset,
+    };
+    use crate::kurbo::{self, Affine, BezPath, Circle, Shape, Stroke, StrokeCtx};
+    use crate::util::extract_scales;
+    use alloc::vec::Vec;
+    use fearless_simd::Level;
+
+    fn fill_lines(circle: Circle, transform: Affine, tolerance: f64) -> usize {
+        let mut ctx = FlattenCtx::default();
+        ctx.set_tolerance(tolerance);
+        let mut lines: Vec<Line> = Vec::new();
+        fill(
+            Level::fallback(),
+            circle.path_elements(0.1),
+            transform,
+            &mut lines,
+            &mut ctx,
+        );
+        lines.len()
+    }
+
+    #[test]
+    fn finer_tolerance_generates_more_lines() {
+        let circle = Circle::new((500.0, 500.0), 480.0);
+        let coarse = fill_lines(circle, Affine::IDENTITY, 1.0);
+        let default = fill_lines(circle, Affine::IDENTITY, DEFAULT_TOLERANCE);
+        let fine = fill_lines(circle, Affine::IDENTITY, 0.01);
+
+        assert!(coarse < default, "{coarse} >= {default}");
+        assert!(default < fine, "{default} >= {fine}");
+    }
+
+    #[test]
+    fn tolerance_is_in_device_space() {
+        let small = fill_lines(Circle::new((10.0, 10.0), 9.6), Affine::scale(50.0), 0.1);
+        let large = fill_lines(Circle::new((500.0, 500.0), 480.0), Affine::IDENTITY, 0.1);
+
+        assert_eq!(small, large);
+    }
+
+    #[test]
+    fn circle_line_count_matches_tolerance() {
+        for (radius, tolerance) in [(40.0, 0.25), (40.0, 0.01), (480.0, 0.25), (480.0, 1.0)] {
+            let lines = fill_lines(
+                Circle::new((500.0, 500.0), radius),
+                Affine::IDENTITY,
+                tolerance,
+            );
+            // The fewest chords of a circle that stay within the tolerance of it.
+            let optimal = (core::f64::consts::PI / (1.0 - tolerance / radius).acos()).ceil();
+            let count = lines as f64;
+
+            assert!(
+                count >= optimal && count <= optimal * 1.2,
+                "{count} lines for radius {radius} and tolerance {tolerance}, optimal is {optimal}"
+            );
+        }
+    }
+
+    #[test]
+    fn dash_offsets_wrap_into_one_period() {
+        assert_eq!(wrap_dash_offset(12.5, &[6.0, 4.0]), 2.5);
+        assert_eq!(wrap_dash_offset(-2.5, &[6.0, 4.0]), 7.5);
+        assert_eq!(wrap_dash_offset(1e6 + 2.5, &[6.0, 4.0]), 2.5);
+        assert_eq!(wrap_dash_offset(-1e-20, &[6.0, 4.0]), 0.0);
+        // Odd patterns are repeated twice.
+        assert_eq!(wrap_dash_offset(12.5, &[6.0, 4.0, 2.0]), 12.5);
+        assert_eq!(wrap_dash_offset(26.5, &[6.0, 4.0, 2.0]), 2.5);
+        // Invalid patterns don't dash, so the offset doesn't matter.
+        assert_eq!(wrap_dash_offset(12.5, &[]), 12.5);
+        assert_eq!(wrap_dash_offset(12.5, &[0.0, 0.0]), 12.5);
+        assert_eq!(wrap_dash_offset(12.5, &[6.0, -4.0]), 12.5);
+    }
+
+    #[test]
+    fn invalid_dash_patterns_are_ignored() {
+        let stroke_lines = |style: &Stroke| {
+            let mut lines: Vec<Line> = Vec::new();
+            stroke(
+                Level::fallback(),
+                Circle::new((50.0, 50.0), 40.0).path_elements(0.1),
+                style,
+                Affine::IDENTITY,
+                &mut lines,
+                &mut FlattenCtx::default(),
+                &mut StrokeCtx::default(),
+            );
+            lines.len()
+        };
+
+        let solid = stroke_lines(&Stroke::new(4.0));
+        for pattern in [[0.0, 0.0], [6.0, -4.0], [6.0, f64::NAN]] {
+            let dashed = Stroke::new(4.0).with_dashes(0.0, pattern);
+            assert_eq!(stroke_lines(&dashed), solid, "{pattern:?}");
+        }
+        let dashed = Stroke::new(4.0).with_dashes(f64::INFINITY, [6.0, 4.0]);
+        assert_eq!(stroke_lines(&dashed), solid);
+    }
+
+    #[test]
+    fn stroke_respects_tolerance() {
+        let stroke_lines = |tolerance: f64| {
+            let mut ctx = FlattenCtx::default();
+            ctx.set_tolerance(tolerance);
+            let mut lines: Vec<Line> = Vec::new();
+            stroke(
+                Level::fallback(),
+                Circle::new((500.0, 500.0), 480.0).path_elements(0.1),
+                &Stroke::new(4.0),
+                Affine::IDENTITY,
+                &mut lines,
+                &mut ctx,
+                &mut StrokeCtx::default(),
+            );
+            lines.len()
+        };
 
-                let p = Point::new(p.x as f32, p.y as f32);
-                self.line_buf.push(Line::new(self.p0, p));
-                self.p0 = p;
+        assert!(stroke_lines(1.0) < stroke_lines(0.01));
+    }
+
+    /// Assert that all lines, including their midpoints, lie within `tolerance` of a circle
+    /// around `center` with one of the given radii.
+    fn assert_on_circles(lines: &[Line], center: Point, radii: &[f32], tolerance: f32) {
+        let distance = |p: Point| {
+            let d = p - center;
+            let r = (d.x * d.x + d.y * d.y).sqrt();
+            radii
+                .iter()
+                .map(|radius| (r - radius).abs())
+                .fold(f32::MAX, f32::min)
+        };
+        for line in lines {
+            let mid = (line.p0 + line.p1) * 0.5;
+            for p in [line.p0, mid, line.p1] {
+                assert!(
+                    distance(p) <= tolerance,
+                    "point {p:?} deviates by {} from the circle",
+                    distance(p)
+                );
             }
         }
     }
+
+    #[test]
+    fn zoomed_fill_has_no_facets() {
+        // Zoom into a small circle so that it covers several thousand pixels.
+        let transform = Affine::scale(100.0);
+        let mut ctx = FlattenCtx::default();
+        let mut lines: Vec<Line> = Vec::new();
+        fill(
+            Level::fallback(),
+            Circle::new((0.0, 0.0), 20.0).path_elements(1e-9),
+            transform,
+            &mut lines,
+            &mut ctx,
+        );
+
+        // Allow for some slack from f32 precision at this magnitude.
+        assert_on_circles(
+            &lines,
+            Point::ZERO,
+            &[2000.0],
+            DEFAULT_TOLERANCE as f32 + 0.01,
+        );
+    }
+
+    #[test]
+    fn zoomed_stroke_has_no_facets() {
+        // With a rotation, the diagonal of the transform is zero, so the scale factor
+        // needs to be derived from the singular values.
+        for transform in [
+            Affine::scale(20.0),
+            Affine::rotate(core::f64::consts::FRAC_PI_2).then_scale(20.0),
+            Affine::scale_non_uniform(5.0, 20.0),
+            Affine::skew(0.5, 0.0).then_scale(20.0),
+        ] {
+            let mut ctx = FlattenCtx::default();
+            let mut lines: Vec<Line> = Vec::new();
+            stroke(
+                Level::fallback(),
+                Circle::new((0.0, 0.0), 40.0).path_elements(1e-9),
+                &Stroke::new(2.0),
+                transform,
+                &mut lines,
+                &mut ctx,
+                &mut StrokeCtx::default(),
+            );
+
+            // Check the deviation in user space, where the stroke outlines are circles.
+            // Both stroke expansion and flattening stay within the tolerance in device
+            // space, which maps to at most `tolerance / min_scale` in user space.
+            let inverse = transform.inverse();
+            let (_, min_scale) = extract_scales(&transform);
+            let user_lines: Vec<Line> = lines
+                .iter()
+                .map(|line| {
+                    let map = |p: Point| {
+                        let p = inverse * kurbo::Point::new(f64::from(p.x), f64::from(p.y));
+                        Point::new(p.x as f32, p.y as f32)
+                    };
+                    Line::new(map(line.p0), map(line.p1))
+                })
+                .collect();
+            assert_on_circles(
+                &user_lines,
+                Point::ZERO,
+                &[39.0, 41.0],
+                2.0 * (DEFAULT_TOLERANCE as f32 + 0.01) / min_scale,
+            );
+        }
+    }
+
+    #[test]
+    fn stroke_tolerance_is_rotation_invariant() {
+        let stroke_lines = |transform: Affine| {
+            let mut path = BezPath::new();
+            path.move_to((0.0, 0.0));
+            path.curve_to((30.0, -40.0), (10.0, 60.0), (50.0, 20.0));
+            let mut ctx = FlattenCtx::default();
+            let mut lines: Vec<Line> = Vec::new();
+            stroke(
+                Level::fallback(),
+                &path,
+                &Stroke::new(6.0),
+                transform,
+                &mut lines,
+                &mut ctx,
+                &mut StrokeCtx::default(),
+            );
+            lines.len()
+        };
+
+        // Rotating the stroke by a quarter turn should not make it coarser.
+        let scaled = stroke_lines(Affine::scale(20.0));
+        let rotated = stroke_lines(Affine::rotate(core::f64::consts::FRAC_PI_2).then_scale(20.0));
+        assert_eq!(scaled, rotated);
+    }
+
+    #[test]
+    fn invalid_tolerance_is_ignored() {
+        let mut ctx = FlattenCtx::default();
+        for tolerance in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            ctx.set_tolerance(tolerance);
+            assert_eq!(ctx.tolerance(), DEFAULT_TOLERANCE);
+        }
+    }
+
+    fn flattened(path: &BezPath) -> Vec<[f32; 4]> {
+        let mut lines = Vec::new();
+        fill(
+            Level::fallback(),
+            path,
+            Affine::IDENTITY,
+            &mut lines,
+            &mut FlattenCtx::default(),
+        );
+        lines
+            .iter()
+            .map(|line| [line.p0.x, line.p0.y, line.p1.x, line.p1.y])
+            .collect()
+    }
+
+    fn square() -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to((10.0, 10.0));
+        path.line_to((50.0, 10.0));
+        path.line_to((50.0, 50.0));
+        path.line_to((10.0, 50.0));
+        path.close_path();
+        path
+    }
+
+    #[test]
+    fn non_finite_control_points_are_replaced_by_lines() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let mut path = BezPath::new();
+            path.move_to((10.0, 10.0));
+            path.line_to((50.0, 10.0));
+            path.curve_to((value, 20.0), (60.0, 40.0), (50.0, 50.0));
+            path.quad_to((30.0, value), (10.0, 50.0));
+            path.close_path();
+
+            assert_eq!(flattened(&path), flattened(&square()), "{value}");
+        }
+    }
+
+    #[test]
+    fn non_finite_end_points_are_dropped() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let mut path = BezPath::new();
+            path.move_to((10.0, 10.0));
+            path.line_to((50.0, 10.0));
+            path.line_to((value, 30.0));
+            path.curve_to((60.0, 20.0), (60.0, 40.0), (30.0, value));
+            path.line_to((50.0, 50.0));
+            path.line_to((10.0, 50.0));
+            path.close_path();
+
+            assert_eq!(flattened(&path), flattened(&square()), "{value}");
+        }
+    }
+
+    #[test]
+    fn subpaths_starting_at_non_finite_points_start_at_next_point() {
+        let mut path = BezPath::new();
+        path.move_to((f64::NAN, 0.0));
+        path.close_path();
+        path.move_to((0.0, f64::INFINITY));
+        path.line_to((f64::NAN, 10.0));
+        path.extend(square().into_iter().skip(1));
+        path.move_to((f64::NAN, f64::NAN));
+
+        let mut expected = BezPath::new();
+        expected.move_to((50.0, 10.0));
+        expected.line_to((50.0, 50.0));
+        expected.line_to((10.0, 50.0));
+        expected.close_path();
+        assert_eq!(flattened(&path), flattened(&expected));
+    }
+
+    #[test]
+    fn non_finite_transforms_are_ignored() {
+        let mut lines = Vec::new();
+        fill(
+            Level::fallback(),
+            square(),
+            Affine::scale(f64::INFINITY),
+            &mut lines,
+            &mut FlattenCtx::default(),
+        );
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn non_finite_points_of_strokes_are_dropped() {
+        let stroke_lines = |path: &BezPath| {
+            let mut lines = Vec::new();
+            stroke(
+                Level::fallback(),
+                path,
+                &Stroke::new(4.0),
+                Affine::IDENTITY,
+                &mut lines,
+                &mut FlattenCtx::default(),
+                &mut StrokeCtx::default(),
+            );
+            lines
+                .iter()
+                .map(|line| [line.p0.x, line.p0.y, line.p1.x, line.p1.y])
+                .collect::<Vec<_>>()
+        };
+
+        let mut path = BezPath::new();
+        path.move_to((10.0, 10.0));
+        path.line_to((50.0, 10.0));
+        path.line_to((f64::NAN, 30.0));
+        path.quad_to((f64::INFINITY, 30.0), (50.0, 50.0));
+        path.line_to((10.0, 50.0));
+        path.close_path();
+
+        assert_eq!(stroke_lines(&path), stroke_lines(&square()));
+    }
+
+    #[test]
+    fn huge_coordinates_are_clamped_along_lines() {
+        let mut path = BezPath::new();
+        path.move_to((10.0, 10.0));
+        path.line_to((2e40, 1e40));
+        path.line_to((-1e300, f64::MAX));
+        path.close_path();
+
+        let lines = flattened(&path);
+        let max = MAX_COORDINATE as f32;
+        assert!(lines.iter().flatten().all(|c| c.abs() <= max), "{lines:?}");
+        // The line leaving the viewport keeps its direction until it reaches the border.
+        let [x0, y0, x1, y1] = lines[0];
+        assert_eq!([x0, y0, x1], [10.0, 10.0, max]);
+        assert!((y1 - max / 2.0).abs() <= max * 1e-6, "{y1}");
+        // The rest of the path runs along the border, so it is closed again.
+        assert_eq!(
+            lines.last().map(|line| [line[2], line[3]]),
+            Some([10.0, 10.0])
+        );
+        for pair in lines.windows(2) {
+            assert_eq!([pair[0][2], pair[0][3]], [pair[1][0], pair[1][1]]);
+        }
+    }
 }
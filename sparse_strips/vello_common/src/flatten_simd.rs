@@ -5,7 +5,7 @@
 //! well as some code that was copied from kurbo, which is needed to reimplement the
 //! full `flatten` method.
 
-use crate::flatten::{SQRT_TOL, TOL, TOL_2};
+use crate::flatten::DEFAULT_TOLERANCE;
 #[cfg(not(feature = "std"))]
 use crate::kurbo::common::FloatFuncs as _;
 use crate::kurbo::{CubicBez, Line, ParamCurve, ParamCurveNearest, PathEl, Point, QuadBez};
@@ -49,6 +49,10 @@ pub(crate) fn flatten<S: Simd>(
 ) {
     flatten_ctx.flattened_cubics.clear();
 
+    let tol = flatten_ctx.tolerance;
+    let sqrt_tol = tol.sqrt();
+    let tol_2 = tol * tol;
+
     let mut closed = true;
     let mut start_pt = Point::ZERO;
     let mut last_pt = Point::ZERO;
@@ -89,12 +93,12 @@ pub(crate) fn flatten<S: Simd>(
                 // The following takes the square to elide the square root of the Euclidean
                 // distance.
                 let line = Line::new(p0, p2);
-                if line.nearest(p1, 0.).distance_sq <= 4. * TOL_2 {
+                if line.nearest(p1, 0.).distance_sq <= 4. * tol_2 {
                     callback.callback(LinePathEl::LineTo(p2));
                 } else {
                     let q = QuadBez::new(p0, p1, p2);
-                    let params = q.estimate_subdiv(SQRT_TOL);
-                    let n = ((0.5 / SQRT_TOL * params.val).ceil() as usize).max(1);
+                    let params = q.estimate_subdiv(sqrt_tol);
+                    let n = ((0.5 / sqrt_tol * params.val).ceil() as usize).max(1);
                     let step = 1.0 / (n as f64);
                     for i in 1..n {
                         let u = (i as f64) * step;
@@ -132,7 +136,7 @@ pub(crate) fn flatten<S: Simd>(
                 if f64::max(
                     line.nearest(p1, 0.).distance_sq,
                     line.nearest(p2, 0.).distance_sq,
-                ) <= 16. / 9. * TOL_2
+                ) <= 16. / 9. * tol_2
                 {
                     callback.callback(LinePathEl::LineTo(p3));
                 } else {
@@ -256,7 +260,7 @@ struct FlattenParams {
 const MAX_QUADS: usize = 16;
 
 /// The context needed for flattening curves.
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct FlattenCtx {
     // The +4 is to encourage alignment; might be better to be explicit
     even_pts: [Point32; MAX_QUADS + 4],
@@ -269,6 +273,45 @@ pub struct FlattenCtx {
     n_quads: usize,
     /// Reusable buffer for flattened cubic points.
     flattened_cubics: Vec<Point32>,
+    /// The maximum distance between a curve and its flattened lines, in device pixels.
+    tolerance: f64,
+}
+
+impl Default for FlattenCtx {
+    fn default() -> Self {
+        Self {
+            even_pts: [Point32::default(); MAX_QUADS + 4],
+            odd_pts: [Point32::default(); MAX_QUADS],
+            a0: [0.0; MAX_QUADS],
+            da: [0.0; MAX_QUADS],
+            u0: [0.0; MAX_QUADS],
+            uscale: [0.0; MAX_QUADS],
+            val: [0.0; MAX_QUADS],
+            n_quads: 0,
+            flattened_cubics: Vec::new(),
+            tolerance: DEFAULT_TOLERANCE,
+        }
+    }
+}
+
+impl FlattenCtx {
+    /// Set the flattening tolerance.
+    ///
+    /// This is the maximum distance between a curve and the lines approximating it.
+    /// Since paths are flattened after applying their transform, the tolerance is
+    /// interpreted in device space (pixels), so zooming in automatically refines curves.
+    ///
+    /// Non-positive or non-finite values are ignored.
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        if tolerance > 0.0 && tolerance.is_finite() {
+            self.tolerance = tolerance;
+        }
+    }
+
+    /// Get the flattening tolerance, in device space.
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
 }
 
 #[inline(always)]
@@ -517,9 +560,9 @@ fn output_lines_simd<S: Simd>(
 
 #[inline(always)]
 fn flatten_cubic_simd<S: Simd>(simd: S, c: CubicBez, ctx: &mut FlattenCtx) -> usize {
-    let n_quads = estimate_num_quads(c, TOL as f32);
+    let n_quads = estimate_num_quads(c, ctx.tolerance as f32);
     eval_cubics_simd(simd, &c, n_quads, ctx);
-    let tol = (TOL as f32) * (1.0 - TO_QUAD_TOL);
+    let tol = (ctx.tolerance as f32) * (1.0 - TO_QUAD_TOL);
     let sqrt_tol = tol.sqrt();
     estimate_subdiv_simd(simd, sqrt_tol, ctx);
     let sum: f32 = ctx.val[..n_quads].iter().sum();
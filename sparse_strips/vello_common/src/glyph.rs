@@ -3,16 +3,21 @@
 
 //! Processing and drawing glyphs.
 
-use crate::kurbo::{Affine, BezPath, Vec2};
+use crate::color::{AlphaColor, Srgb};
+use crate::kurbo::{Affine, BezPath, PathEl, Shape, Stroke, Vec2};
+use crate::paint::PaintType;
 use crate::peniko::FontData;
+use alloc::borrow::Cow;
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 use core::fmt::{Debug, Formatter};
 use hashbrown::hash_map::{Entry, RawEntryMut};
 use hashbrown::{Equivalent, HashMap};
 use skrifa::instance::{LocationRef, Size};
+use skrifa::metrics::GlyphMetrics;
 use skrifa::outline::{DrawSettings, OutlineGlyphFormat};
 use skrifa::raw::TableProvider;
+use skrifa::raw::tables::{vmtx::Vmtx, vorg::Vorg};
 use skrifa::{FontRef, OutlineGlyphCollection};
 use skrifa::{
     GlyphId, MetadataProvider,
@@ -67,6 +72,20 @@ pub struct PreparedGlyph<'a> {
 pub struct OutlineGlyph<'a> {
     /// The path of the glyph.
     pub path: &'a BezPath,
+    /// A key that identifies the outline, see [`OutlineGlyphKey`].
+    pub key: OutlineGlyphKey,
+}
+
+/// A key that identifies the outline of a glyph.
+///
+/// Two outline glyphs with the same key have the same path. The key covers the font, the glyph
+/// id, the font size, hinting, synthetic emboldening and the variation coordinates, so
+/// renderers can use it to cache what they derive from the outline, like rasterized coverage.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct OutlineGlyphKey {
+    outline: OutlineKey,
+    /// A hash of the variation coordinates, or 0 for the default location.
+    coords_hash: u64,
 }
 
 /// A glyph defined by a bitmap.
@@ -82,12 +101,15 @@ pub struct BitmapGlyph {
 ///
 /// Clients are supposed to first draw the glyph into an intermediate image texture/pixmap
 /// and then render that into the actual scene, in a similar fashion to
-/// bitmap glyphs.
+/// bitmap glyphs. Alternatively, the layers can be painted directly into the scene inside an
+/// isolated layer, with fills covering [`ColorGlyph::area`].
 pub struct ColorGlyph<'a> {
     pub(crate) skrifa_glyph: skrifa::color::ColorGlyph<'a>,
     pub(crate) location: LocationRef<'a>,
     pub(crate) font_ref: &'a FontRef<'a>,
     pub(crate) draw_transform: Affine,
+    pub(crate) palette: u16,
+    pub(crate) foreground_color: Option<AlphaColor<Srgb>>,
     /// The rectangular area that should be filled with the rendered representation of the
     /// COLR glyph when painting.
     pub area: Rect,
@@ -111,6 +133,33 @@ pub trait GlyphRenderer {
     /// Stroke glyphs with the current paint and stroke settings.
     fn stroke_glyph(&mut self, glyph: PreparedGlyph<'_>);
 
+    /// Stroke glyphs with the given paint and stroke settings, leaving the current ones
+    /// unchanged.
+    ///
+    /// By default, this sets the stroke and paint with `replace_stroke_and_paint` for the
+    /// duration of `stroke_glyph`. Renderers that don't support replacing them stroke with their
+    /// current settings instead.
+    fn stroke_glyph_with(&mut self, glyph: PreparedGlyph<'_>, stroke: &Stroke, paint: &PaintType) {
+        let previous = self.replace_stroke_and_paint(stroke.clone(), paint.clone());
+        self.stroke_glyph(glyph);
+        if let Some((stroke, paint)) = previous {
+            self.replace_stroke_and_paint(stroke, paint);
+        }
+    }
+
+    /// Replaces the current stroke settings and paint, returning the previous ones.
+    ///
+    /// This is used by the default implementation of `stroke_glyph_with`. Returns `None` without
+    /// changing anything if the renderer doesn't support it, which is the default.
+    fn replace_stroke_and_paint(
+        &mut self,
+        stroke: Stroke,
+        paint: PaintType,
+    ) -> Option<(Stroke, PaintType)> {
+        let _ = (stroke, paint);
+        None
+    }
+
     /// Takes the glyph caches from the renderer for use in a glyph run.
     ///
     /// NOTE: The caller must restore the caches after the glyph run is done.
@@ -140,7 +189,13 @@ impl<'a, T: GlyphRenderer + 'a> GlyphRunBuilder<'a, T> {
                 transform,
                 glyph_transform: None,
                 hint: true,
-                normalized_coords: &[],
+                normalized_coords: Cow::Borrowed(&[]),
+                palette: 0,
+                foreground_color: None,
+                y_axis: YAxis::Down,
+                writing_mode: WritingMode::Horizontal,
+                synthetic_oblique: 0.0,
+                synthetic_bold: 0.0,
             },
             renderer,
         }
@@ -159,6 +214,31 @@ impl<'a, T: GlyphRenderer + 'a> GlyphRunBuilder<'a, T> {
         self
     }
 
+    /// Slant the glyphs by `angle` (in radians) to simulate an oblique style for fonts without
+    /// an italic. Positive angles lean the glyphs to the right, common values are around 12
+    /// degrees.
+    ///
+    /// Each glyph is sheared about its baseline before the
+    /// [per-glyph transform](Self::glyph_transform) is applied, so the glyph positions don't
+    /// change.
+    pub fn synthetic_oblique(mut self, angle: f64) -> Self {
+        self.run.synthetic_oblique = angle;
+        self
+    }
+
+    /// Embolden the outlines of the glyphs to simulate a bold style for fonts without one.
+    ///
+    /// The outlines are grown so that each stem gets wider by `strength` times the font size,
+    /// while the glyphs keep their baseline and left edge. Values around 0.02 to 0.04 work well.
+    /// Bitmap and COLR glyphs are not affected.
+    ///
+    /// The advances of the glyphs are not adjusted: callers positioning glyphs by the advances
+    /// of the regular weight should add the extra width themselves to keep the spacing.
+    pub fn synthetic_bold(mut self, strength: f32) -> Self {
+        self.run.synthetic_bold = strength;
+        self
+    }
+
     /// Set whether font hinting is enabled.
     ///
     /// This performs vertical hinting only. Hinting is performed only if the combined `transform`
@@ -168,9 +248,62 @@ impl<'a, T: GlyphRenderer + 'a> GlyphRunBuilder<'a, T> {
         self
     }
 
+    /// Set the direction of the y-axis of the coordinate system the run is drawn in.
+    ///
+    /// This applies to the transform of the run, the per-glyph transform and the glyph
+    /// positions. By default, the y-axis points down.
+    pub fn y_axis(mut self, y_axis: YAxis) -> Self {
+        self.run.y_axis = y_axis;
+        self
+    }
+
+    /// Set the writing mode of the run, which determines the origin the glyph positions refer
+    /// to.
+    ///
+    /// In [`WritingMode::Vertical`], each position is the vertical origin of its glyph, so the
+    /// caller advances positions along the y-axis. All glyphs of a run share its writing mode,
+    /// so text mixing horizontal and vertical segments (for example rotated Latin text in a
+    /// vertical CJK line) must be drawn as separate runs.
+    pub fn writing_mode(mut self, writing_mode: WritingMode) -> Self {
+        self.run.writing_mode = writing_mode;
+        self
+    }
+
     /// Set normalized variation coordinates for variable fonts.
     pub fn normalized_coords(mut self, coords: &'a [NormalizedCoord]) -> Self {
-        self.run.normalized_coords = bytemuck::cast_slice(coords);
+        self.run.normalized_coords = Cow::Borrowed(bytemuck::cast_slice(coords));
+        self
+    }
+
+    /// Set the variation of variable fonts using axis tag and value pairs in user space, for
+    /// example `&[("wght", 700.0), ("wdth", 75.0)]`.
+    ///
+    /// The values are normalized against the axes of the font. Tags that don't match an axis
+    /// are ignored, values outside of the range of their axis are clamped, and axes without a
+    /// value keep their default. This replaces any previously set
+    /// [normalized coordinates](Self::normalized_coords).
+    pub fn variations(mut self, settings: &[(&str, f32)]) -> Self {
+        let coords = FontRef::from_index(self.run.font.data.as_ref(), self.run.font.index)
+            .map(|font_ref| font_ref.axes().location(settings).coords().to_vec())
+            .unwrap_or_default();
+        self.run.normalized_coords = Cow::Owned(coords);
+        self
+    }
+
+    /// Set the index of the color palette used by COLR glyphs.
+    ///
+    /// Fonts can define multiple palettes, for example for light and dark themes. If the font
+    /// has no palette with this index, the default palette (index 0) is used.
+    pub fn palette(mut self, palette: u16) -> Self {
+        self.run.palette = palette;
+        self
+    }
+
+    /// Set the color used by COLR glyphs for layers that refer to the foreground color.
+    ///
+    /// By default, the current paint is used if it is a solid color, and black otherwise.
+    pub fn foreground_color(mut self, color: AlphaColor<Srgb>) -> Self {
+        self.run.foreground_color = Some(color);
         self
     }
 
@@ -184,9 +317,70 @@ impl<'a, T: GlyphRenderer + 'a> GlyphRunBuilder<'a, T> {
         self.render(glyphs, Style::Stroke);
     }
 
-    fn render(self, glyphs: impl Iterator<Item = Glyph>, style: Style) {
-        let font_ref =
-            FontRef::from_index(self.run.font.data.as_ref(), self.run.font.index).unwrap();
+    /// Consumes the builder and strokes the glyphs with the given stroke and paint, instead of
+    /// the current stroke settings and paint of the renderer.
+    ///
+    /// This is useful for outlined text, by stroking the glyphs before filling them. The stroke
+    /// follows every contour of a glyph, including the overlapping contours that some fonts
+    /// (notably variable fonts) use, and is centered on the outline, so strokes wider than the
+    /// thin stems of a glyph will close its counters. Filling the glyphs on top of the stroke
+    /// hides both.
+    pub fn stroke_glyphs_with(
+        mut self,
+        stroke: &Stroke,
+        paint: impl Into<PaintType>,
+        glyphs: impl Iterator<Item = Glyph>,
+    ) {
+        let paint = paint.into();
+        self.prepare_glyphs(glyphs, |renderer, glyph| {
+            renderer.stroke_glyph_with(glyph, stroke, &paint);
+        });
+    }
+
+    /// Computes the device-space bounding box of the glyphs with the current configuration,
+    /// without drawing them. Returns `None` if none of the glyphs has any visible geometry.
+    ///
+    /// The bounds are the union of the bounding boxes of the glyph outlines as they would be
+    /// drawn, so they use hinted outlines if hinting applies to the run. For bitmap and COLR
+    /// glyphs, the area covered by their image is used. Strokes are not accounted for.
+    pub fn bounds(&mut self, glyphs: impl Iterator<Item = Glyph>) -> Option<Rect> {
+        let mut bounds: Option<Rect> = None;
+        self.prepare_glyphs(glyphs, |_, glyph| {
+            let area = match &glyph.glyph_type {
+                GlyphType::Outline(outline) if outline.path.is_empty() => return,
+                GlyphType::Outline(outline) => outline.path.bounding_box(),
+                GlyphType::Bitmap(bitmap) => bitmap.area,
+                GlyphType::Colr(color) => color.area,
+            };
+            let area = glyph.transform.transform_rect_bbox(area);
+            bounds = Some(bounds.map_or(area, |bounds| bounds.union(area)));
+        });
+        bounds
+    }
+
+    fn render(mut self, glyphs: impl Iterator<Item = Glyph>, style: Style) {
+        let render_glyph = match style {
+            Style::Fill => GlyphRenderer::fill_glyph,
+            Style::Stroke => GlyphRenderer::stroke_glyph,
+        };
+        self.prepare_glyphs(glyphs, render_glyph);
+    }
+
+    /// Prepares each of the glyphs for rendering and passes it to `f`.
+    fn prepare_glyphs(
+        &mut self,
+        glyphs: impl Iterator<Item = Glyph>,
+        mut f: impl FnMut(&mut T, PreparedGlyph<'_>),
+    ) {
+        // Glyphs are prepared for a y-down coordinate system, so express y-up runs in it.
+        let y_up = self.run.y_axis == YAxis::Up;
+        let run = if y_up {
+            Cow::Owned(self.run.to_y_down())
+        } else {
+            Cow::Borrowed(&self.run)
+        };
+
+        let font_ref = FontRef::from_index(run.font.data.as_ref(), run.font.index).unwrap();
 
         let upem: f32 = font_ref.head().map(|h| h.units_per_em()).unwrap().into();
 
@@ -200,22 +394,29 @@ impl<'a, T: GlyphRenderer + 'a> GlyphRunBuilder<'a, T> {
             mut outline_cache,
         } = self.renderer.take_glyph_caches();
         let mut outline_cache_session =
-            OutlineCacheSession::new(&mut outline_cache, VarLookupKey(self.run.normalized_coords));
+            OutlineCacheSession::new(&mut outline_cache, VarLookupKey(&run.normalized_coords));
         let PreparedGlyphRun {
             transform: initial_transform,
             size,
             normalized_coords,
             hinting_instance,
-        } = prepare_glyph_run(&self.run, &outlines, &mut hinting_cache);
-
-        let render_glyph = match style {
-            Style::Fill => GlyphRenderer::fill_glyph,
-            Style::Stroke => GlyphRenderer::stroke_glyph,
-        };
-
-        for glyph in glyphs {
+        } = prepare_glyph_run(&run, &outlines, &mut hinting_cache);
+        // The outlines are generated at `size`, so scale the strength accordingly.
+        let embolden = run.synthetic_bold.max(0.0) * size.ppem().unwrap();
+        let vertical_origins = (run.writing_mode == WritingMode::Vertical)
+            .then(|| VerticalOrigins::new(&font_ref, &run, upem));
+
+        for mut glyph in glyphs {
+            if y_up {
+                glyph.y = -glyph.y;
+            }
+            if let Some(vertical_origins) = &vertical_origins {
+                let (x, y) = vertical_origins.offset(GlyphId::new(glyph.id));
+                glyph.x += x;
+                glyph.y += y;
+            }
             let bitmap_data = bitmaps
-                .glyph_for_size(Size::new(self.run.font_size), GlyphId::new(glyph.id))
+                .glyph_for_size(Size::new(run.font_size), GlyphId::new(glyph.id))
                 .and_then(|g| match g.data {
                     #[cfg(feature = "png")]
                     BitmapData::Png(data) => Pixmap::from_png(data).ok().map(|d| (g, d)),
@@ -232,18 +433,20 @@ impl<'a, T: GlyphRenderer + 'a> GlyphRunBuilder<'a, T> {
                     prepare_colr_glyph(
                         &font_ref,
                         glyph,
-                        self.run.font_size,
+                        run.font_size,
                         upem,
                         initial_transform,
                         color_glyph,
                         normalized_coords,
+                        run.palette,
+                        run.foreground_color,
                     )
                 } else if let Some((bitmap_glyph, pixmap)) = bitmap_data {
                     prepare_bitmap_glyph(
                         &bitmaps,
                         glyph,
                         pixmap,
-                        self.run.font_size,
+                        run.font_size,
                         upem,
                         initial_transform,
                         bitmap_glyph,
@@ -255,15 +458,16 @@ impl<'a, T: GlyphRenderer + 'a> GlyphRunBuilder<'a, T> {
 
                     prepare_outline_glyph(
                         glyph,
-                        self.run.font.data.id(),
-                        self.run.font.index,
+                        run.font.data.id(),
+                        run.font.index,
                         &mut outline_cache_session,
                         size,
                         initial_transform,
-                        self.run.transform,
+                        run.transform,
                         &outline,
                         hinting_instance,
                         normalized_coords,
+                        embolden,
                     )
                 };
 
@@ -272,7 +476,7 @@ impl<'a, T: GlyphRenderer + 'a> GlyphRunBuilder<'a, T> {
                 transform,
             };
 
-            render_glyph(self.renderer, prepared_glyph);
+            f(self.renderer, prepared_glyph);
         }
 
         self.renderer.restore_glyph_caches(GlyphCaches {
@@ -295,8 +499,9 @@ fn prepare_outline_glyph<'a>(
     outline_glyph: &skrifa::outline::OutlineGlyph<'a>,
     hinting_instance: Option<&HintingInstance>,
     normalized_coords: &[skrifa::instance::NormalizedCoord],
+    embolden: f32,
 ) -> (GlyphType<'a>, Affine) {
-    let path = outline_cache.get_or_insert(
+    let (key, path) = outline_cache.get_or_insert(
         glyph.id,
         font_id,
         font_index,
@@ -304,6 +509,7 @@ fn prepare_outline_glyph<'a>(
         VarLookupKey(normalized_coords),
         outline_glyph,
         hinting_instance,
+        embolden,
     );
 
     // Calculate the global glyph translation based on the glyph's local position within
@@ -332,7 +538,7 @@ fn prepare_outline_glyph<'a>(
     }
 
     (
-        GlyphType::Outline(OutlineGlyph { path: &path.0 }),
+        GlyphType::Outline(OutlineGlyph { path: &path.0, key }),
         Affine::new(final_transform),
     )
 }
@@ -406,6 +612,8 @@ fn prepare_colr_glyph<'a>(
     run_transform: Affine,
     color_glyph: skrifa::color::ColorGlyph<'a>,
     normalized_coords: &'a [skrifa::instance::NormalizedCoord],
+    palette: u16,
+    foreground_color: Option<AlphaColor<Srgb>>,
 ) -> (GlyphType<'a>, Affine) {
     // A couple of notes on the implementation here:
     //
@@ -501,11 +709,45 @@ fn prepare_colr_glyph<'a>(
             pix_width,
             pix_height,
             draw_transform,
+            palette,
+            foreground_color,
         })),
         glyph_transform,
     )
 }
 
+/// The direction of the y-axis of the coordinate system that glyphs are drawn in.
+///
+/// Fonts are defined with the y-axis pointing up, so glyphs are flipped when drawing them into
+/// a coordinate system whose y-axis points down (the default), but not when it points up.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum YAxis {
+    /// The y-axis points down, as in screen coordinates.
+    #[default]
+    Down,
+    /// The y-axis points up, as is common in game engines and in fonts themselves.
+    Up,
+}
+
+/// The writing mode of a glyph run.
+///
+/// Glyphs are drawn upright in both modes, only the origin that glyph positions refer to
+/// changes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WritingMode {
+    /// Horizontal text, as with CSS `horizontal-tb`. Glyph positions refer to the glyph origin
+    /// on the baseline.
+    #[default]
+    Horizontal,
+    /// Vertical text, as with CSS `vertical-rl` and `vertical-lr`. Glyph positions refer to
+    /// the vertical origin of the glyph, which is horizontally centered on its advance and
+    /// on the top edge of its em box.
+    ///
+    /// The vertical origin is taken from the `VORG` table, or from the `vmtx` table and the
+    /// glyph outline. Without either, the ascender of the font is used.
+    Vertical,
+}
+
 /// Rendering style for glyphs.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Style {
@@ -528,9 +770,91 @@ struct GlyphRun<'a> {
     /// text.
     glyph_transform: Option<Affine>,
     /// Normalized variation coordinates for variable fonts.
-    normalized_coords: &'a [skrifa::instance::NormalizedCoord],
+    normalized_coords: Cow<'a, [skrifa::instance::NormalizedCoord]>,
     /// Controls whether font hinting is enabled.
     hint: bool,
+    /// Index of the color palette used by COLR glyphs.
+    palette: u16,
+    /// Color of COLR layers that refer to the foreground color, overriding the current paint.
+    foreground_color: Option<AlphaColor<Srgb>>,
+    /// Direction of the y-axis of the coordinate system the run is drawn in.
+    y_axis: YAxis,
+    /// The writing mode, which determines the origin the glyph positions refer to.
+    writing_mode: WritingMode,
+    /// The angle in radians by which glyphs are slanted to simulate an oblique style.
+    synthetic_oblique: f64,
+    /// The width of the stroke that emboldens glyph outlines, relative to the font size.
+    synthetic_bold: f32,
+}
+
+impl GlyphRun<'_> {
+    /// The per-glyph transform, including the shear of the synthetic oblique style.
+    fn total_glyph_transform(&self) -> Affine {
+        let glyph_transform = self.glyph_transform.unwrap_or(Affine::IDENTITY);
+        if self.synthetic_oblique == 0.0 {
+            return glyph_transform;
+        }
+
+        // The y-axis points down, so points above the baseline have a negative y-coordinate
+        // and need to be moved to the right.
+        glyph_transform * Affine::skew(-self.synthetic_oblique.tan(), 0.0)
+    }
+
+    /// Expresses a run in a y-up coordinate system as a run in a y-down coordinate system, in
+    /// which the glyph positions have to be flipped as well.
+    fn to_y_down(&self) -> Self {
+        let flip = Affine::FLIP_Y;
+        Self {
+            transform: self.transform * flip,
+            glyph_transform: self.glyph_transform.map(|t| flip * t * flip),
+            y_axis: YAxis::Down,
+            ..self.clone()
+        }
+    }
+}
+
+/// Computes the offsets from the vertical origins of glyphs to their horizontal origins, which
+/// are what glyphs are prepared relative to.
+struct VerticalOrigins<'a> {
+    metrics: GlyphMetrics<'a>,
+    vorg: Option<Vorg<'a>>,
+    vmtx: Option<Vmtx<'a>>,
+    ascent: f32,
+    font_units_to_size: f32,
+}
+
+impl<'a> VerticalOrigins<'a> {
+    fn new(font_ref: &FontRef<'a>, run: &'a GlyphRun<'_>, upem: f32) -> Self {
+        let size = Size::new(run.font_size);
+        let location = LocationRef::new(&run.normalized_coords);
+        Self {
+            metrics: font_ref.glyph_metrics(size, location),
+            vorg: font_ref.vorg().ok(),
+            vmtx: font_ref.vmtx().ok(),
+            ascent: font_ref.metrics(size, location).ascent,
+            font_units_to_size: run.font_size / upem,
+        }
+    }
+
+    /// Returns the offset from the vertical origin of the glyph to its horizontal origin, in a
+    /// y-down coordinate system.
+    fn offset(&self, id: GlyphId) -> (f32, f32) {
+        let advance = self.metrics.advance_width(id).unwrap_or_default();
+        let origin_y = if let Some(vorg) = &self.vorg {
+            f32::from(vorg.vertical_origin_y(id)) * self.font_units_to_size
+        } else if let Some((top_side_bearing, bounds)) = self
+            .vmtx
+            .as_ref()
+            .and_then(|vmtx| vmtx.side_bearing(id))
+            .zip(self.metrics.bounds(id))
+        {
+            f32::from(top_side_bearing) * self.font_units_to_size + bounds.y_max
+        } else {
+            self.ascent
+        };
+
+        (-advance / 2.0, origin_y)
+    }
 }
 
 struct PreparedGlyphRun<'a> {
@@ -548,15 +872,15 @@ struct PreparedGlyphRun<'a> {
 /// This function calculates the appropriate transform, size, and scaling parameters
 /// for proper font hinting when enabled and possible.
 fn prepare_glyph_run<'a>(
-    run: &GlyphRun<'a>,
+    run: &'a GlyphRun<'_>,
     outlines: &OutlineGlyphCollection<'_>,
     hint_cache: &'a mut HintCache,
 ) -> PreparedGlyphRun<'a> {
     if !run.hint {
         return PreparedGlyphRun {
-            transform: run.transform * run.glyph_transform.unwrap_or(Affine::IDENTITY),
+            transform: run.transform * run.total_glyph_transform(),
             size: Size::new(run.font_size),
-            normalized_coords: run.normalized_coords,
+            normalized_coords: &run.normalized_coords,
             hinting_instance: None,
         };
     }
@@ -571,7 +895,7 @@ fn prepare_glyph_run<'a>(
     //
     // As the hinting is vertical-only, we can handle horizontal skew, but not vertical skew or
     // rotations.
-    let total_transform = run.transform * run.glyph_transform.unwrap_or(Affine::IDENTITY);
+    let total_transform = run.transform * run.total_glyph_transform();
     let [t_a, t_b, t_c, t_d, t_e, t_f] = total_transform.as_coeffs();
 
     let uniform_scale = t_a == t_d;
@@ -586,25 +910,155 @@ fn prepare_glyph_run<'a>(
             font_index: run.font.index,
             outlines,
             size,
-            coords: run.normalized_coords,
+            coords: &run.normalized_coords,
         });
 
         PreparedGlyphRun {
             transform: Affine::new([1., 0., t_c, 1., t_e, t_f]),
             size,
-            normalized_coords: run.normalized_coords,
+            normalized_coords: &run.normalized_coords,
             hinting_instance,
         }
     } else {
         PreparedGlyphRun {
             transform: total_transform,
             size: Size::new(run.font_size),
-            normalized_coords: run.normalized_coords,
+            normalized_coords: &run.normalized_coords,
             hinting_instance: None,
         }
     }
 }
 
+/// Grow the outline of a glyph in font coordinates, so that its stems get wider by `strength`.
+///
+/// This is a port of `FT_Outline_EmboldenXY`: every point, including control points, is moved
+/// outwards along the bisector of its adjacent segments, and the outline is shifted by half the
+/// strength so that it keeps its left edge and baseline.
+fn embolden_outline(path: &mut BezPath, strength: f64) {
+    let strength = strength / 2.0;
+
+    let mut points = Vec::new();
+    let mut contours = Vec::new();
+    let mut start = 0;
+    for el in path.elements() {
+        match el {
+            PathEl::MoveTo(p) => {
+                if points.len() > start {
+                    contours.push(start..points.len());
+                }
+                start = points.len();
+                points.push(*p);
+            }
+            PathEl::LineTo(p) => points.push(*p),
+            PathEl::QuadTo(p1, p2) => points.extend([*p1, *p2]),
+            PathEl::CurveTo(p1, p2, p3) => points.extend([*p1, *p2, *p3]),
+            PathEl::ClosePath => {}
+        }
+    }
+    if points.len() > start {
+        contours.push(start..points.len());
+    }
+
+    // Twice the signed area of all contours, which is positive if the outer contours are
+    // counter-clockwise (as in CFF fonts) and negative if they are clockwise (as in TrueType).
+    let area: f64 = contours
+        .iter()
+        .map(|contour| {
+            let mut prev = points[contour.end - 1];
+            points[contour.clone()]
+                .iter()
+                .map(|&cur| {
+                    let area = (cur.y - prev.y) * (cur.x + prev.x);
+                    prev = cur;
+                    area
+                })
+                .sum::<f64>()
+        })
+        .sum();
+    if area == 0.0 {
+        return;
+    }
+    let clockwise = area < 0.0;
+
+    for contour in contours {
+        let (first, last) = (contour.start, contour.end - 1);
+        let next = |idx: usize| if idx < last { idx + 1 } else { first };
+
+        let (mut in_dir, mut in_len) = (Vec2::ZERO, 0.0);
+        let (mut anchor_dir, mut anchor_len) = (Vec2::ZERO, 0.0);
+        // `j` cycles through the points, `i` advances only when points are moved and `anchor`
+        // marks the first moved point.
+        let (mut i, mut j, mut anchor) = (last, first, None);
+        while j != i && Some(i) != anchor {
+            let (out_dir, out_len) = if Some(j) == anchor {
+                (anchor_dir, anchor_len)
+            } else {
+                let out = points[j] - points[i];
+                let out_len = out.hypot();
+                if out_len == 0.0 {
+                    j = next(j);
+                    continue;
+                }
+                (out / out_len, out_len)
+            };
+
+            if in_len == 0.0 {
+                i = j;
+            } else {
+                if anchor.is_none() {
+                    anchor = Some(i);
+                    (anchor_dir, anchor_len) = (in_dir, in_len);
+                }
+
+                let cos = in_dir.dot(out_dir);
+                // Only shift if the turn is less than ~160 degrees.
+                let shift = if cos > -0.9375 {
+                    let d = cos + 1.0;
+                    // Shift along the lateral bisector, towards the outside of the contour.
+                    let mut shift = Vec2::new(in_dir.y + out_dir.y, in_dir.x + out_dir.x);
+                    let mut sin = out_dir.x * in_dir.y - out_dir.y * in_dir.x;
+                    if clockwise {
+                        shift.x = -shift.x;
+                        sin = -sin;
+                    } else {
+                        shift.y = -shift.y;
+                    }
+
+                    // Restrict the shift to better handle collapsing segments.
+                    let len = in_len.min(out_len);
+                    if strength * sin <= len * d {
+                        shift * (strength / d)
+                    } else {
+                        shift * (len / sin)
+                    }
+                } else {
+                    Vec2::ZERO
+                };
+
+                while i != j {
+                    points[i] += Vec2::new(strength, strength) + shift;
+                    i = next(i);
+                }
+            }
+
+            (in_dir, in_len) = (out_dir, out_len);
+            j = next(j);
+        }
+    }
+
+    let mut points = points.into_iter();
+    let mut next_point = || points.next().unwrap();
+    for el in path.elements_mut() {
+        *el = match el {
+            PathEl::MoveTo(_) => PathEl::MoveTo(next_point()),
+            PathEl::LineTo(_) => PathEl::LineTo(next_point()),
+            PathEl::QuadTo(..) => PathEl::QuadTo(next_point(), next_point()),
+            PathEl::CurveTo(..) => PathEl::CurveTo(next_point(), next_point(), next_point()),
+            PathEl::ClosePath => PathEl::ClosePath,
+        };
+    }
+}
+
 // TODO: Although these are sane defaults, we might want to make them
 // configurable.
 const HINTING_OPTIONS: HintingOptions = HintingOptions {
@@ -625,7 +1079,7 @@ impl OutlinePath {
     }
 }
 
-// Note that we flip the y-axis to match our coordinate system.
+// Note that the y-axis is not flipped here, but in the transform of the glyph instead.
 impl OutlinePen for OutlinePath {
     #[inline]
     fn move_to(&mut self, x: f32, y: f32) {
@@ -670,6 +1124,220 @@ mod tests {
 
     const _NORMALISED_COORD_SIZE_MATCHES: () =
         assert!(size_of::<skrifa::instance::NormalizedCoord>() == size_of::<NormalizedCoord>());
+
+    /// Records the bounds of the outline glyphs it is asked to fill.
+    #[derive(Default)]
+    struct BoundsRenderer {
+        bounds: Vec<Rect>,
+        caches: Option<GlyphCaches>,
+    }
+
+    impl GlyphRenderer for BoundsRenderer {
+        fn fill_glyph(&mut self, glyph: PreparedGlyph<'_>) {
+            if let GlyphType::Outline(outline) = glyph.glyph_type {
+                self.bounds.push(outline.path.bounding_box());
+            }
+        }
+
+        fn stroke_glyph(&mut self, glyph: PreparedGlyph<'_>) {
+            self.fill_glyph(glyph);
+        }
+
+        fn take_glyph_caches(&mut self) -> GlyphCaches {
+            self.caches.take().unwrap_or_default()
+        }
+
+        fn restore_glyph_caches(&mut self, caches: GlyphCaches) {
+            self.caches = Some(caches);
+        }
+    }
+
+    /// Outline bounds of the glyph for `m` in Inconsolata at the given variation.
+    fn inconsolata_m_bounds(settings: &[(&str, f32)]) -> Rect {
+        const FONT: &[u8] = include_bytes!("../../../examples/assets/inconsolata/Inconsolata.ttf");
+        let font = FontData::new(peniko::Blob::new(alloc::sync::Arc::new(FONT)), 0);
+        let id = FontRef::new(FONT)
+            .unwrap()
+            .charmap()
+            .map('m')
+            .unwrap()
+            .to_u32();
+
+        let mut renderer = BoundsRenderer::default();
+        GlyphRunBuilder::new(font, Affine::IDENTITY, &mut renderer)
+            .font_size(100.0)
+            .hint(false)
+            .variations(settings)
+            .fill_glyphs(core::iter::once(Glyph { id, x: 0.0, y: 0.0 }));
+        renderer.bounds[0]
+    }
+
+    #[test]
+    fn variations_change_outlines() {
+        let default = inconsolata_m_bounds(&[]);
+        let bold = inconsolata_m_bounds(&[("wght", 900.0)]);
+        let wide = inconsolata_m_bounds(&[("wght", 900.0), ("wdth", 200.0)]);
+
+        assert_eq!(inconsolata_m_bounds(&[("wght", 400.0)]), default);
+        assert_ne!(bold, default);
+        assert!(wide.width() > bold.width());
+    }
+
+    #[test]
+    fn bounds_span_sum_of_advances() {
+        const FONT: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+        let font = FontData::new(peniko::Blob::new(alloc::sync::Arc::new(FONT)), 0);
+        let font_ref = FontRef::new(FONT).unwrap();
+        let font_size = 50.0;
+        let metrics = font_ref.glyph_metrics(Size::new(font_size), LocationRef::default());
+
+        let mut x = 0.0;
+        let mut glyphs = Vec::new();
+        for c in "MINIMUM".chars() {
+            let id = font_ref.charmap().map(c).unwrap();
+            glyphs.push(Glyph {
+                id: id.to_u32(),
+                x,
+                y: 0.0,
+            });
+            x += metrics.advance_width(id).unwrap();
+        }
+
+        let mut renderer = BoundsRenderer::default();
+        let bounds = GlyphRunBuilder::new(font, Affine::translate((10.0, 60.0)), &mut renderer)
+            .font_size(font_size)
+            .bounds(glyphs.into_iter())
+            .unwrap();
+
+        // The outlines are inset from the advances by the side bearings of the outer glyphs.
+        assert!((bounds.width() - f64::from(x)).abs() < f64::from(font_size) * 0.2);
+        assert!(bounds.x0 > 10.0 && bounds.x0 < 20.0);
+        // Capital letters sit on the baseline and reach up to about 0.7 em.
+        assert!((bounds.y1 - 60.0).abs() < 1.0);
+        assert!((bounds.height() - 0.711 * f64::from(font_size)).abs() < 2.0);
+        assert!(renderer.bounds.is_empty(), "computing bounds must not draw");
+    }
+
+    /// Bounds of the glyphs for `text`, stacked vertically one em apart with their vertical
+    /// origins on the line `x = 0`.
+    fn vertical_column_bounds(font: &'static [u8], text: &str, font_size: f32) -> Rect {
+        let font_ref = FontRef::new(font).unwrap();
+        let glyphs = text
+            .chars()
+            .enumerate()
+            .map(|(i, c)| Glyph {
+                id: font_ref.charmap().map(c).unwrap().to_u32(),
+                x: 0.0,
+                y: i as f32 * font_size,
+            })
+            .collect::<Vec<_>>();
+        let font = FontData::new(peniko::Blob::new(alloc::sync::Arc::new(font)), 0);
+
+        let mut renderer = BoundsRenderer::default();
+        GlyphRunBuilder::new(font, Affine::IDENTITY, &mut renderer)
+            .font_size(font_size)
+            .hint(false)
+            .writing_mode(WritingMode::Vertical)
+            .bounds(glyphs.into_iter())
+            .unwrap()
+    }
+
+    #[test]
+    fn vertical_glyphs_hang_below_their_origin() {
+        const ROBOTO: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+        const NOTO_CBTF: &[u8] = include_bytes!(
+            "../../../examples/assets/noto_color_emoji/NotoColorEmoji-CBTF-Subset.ttf"
+        );
+        let font_size = 40.0;
+        for bounds in [
+            vertical_column_bounds(ROBOTO, "HOH", 40.0),
+            vertical_column_bounds(NOTO_CBTF, "✅👀🎉", 40.0),
+        ] {
+            // Glyphs are centered on their vertical origin and hang from it into the em box
+            // below, so the column spans two advances plus the height of the last glyph.
+            assert!((bounds.x0 + bounds.x1).abs() < 0.5, "{bounds:?}");
+            assert!(bounds.width() < font_size * 1.5, "{bounds:?}");
+            assert!(
+                bounds.y0 >= 0.0 && bounds.y0 < font_size * 0.25,
+                "{bounds:?}"
+            );
+            assert!(
+                bounds.y1 > 2.0 * font_size && bounds.y1 < 3.5 * font_size,
+                "{bounds:?}"
+            );
+        }
+    }
+
+    /// Device-space bounds of the glyph for `c` in Roboto, drawn at the origin with a font size
+    /// of 100 by a run configured with `configure`.
+    fn roboto_glyph_bounds(
+        c: char,
+        configure: impl FnOnce(
+            GlyphRunBuilder<'_, BoundsRenderer>,
+        ) -> GlyphRunBuilder<'_, BoundsRenderer>,
+    ) -> Rect {
+        const FONT: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+        let font = FontData::new(peniko::Blob::new(alloc::sync::Arc::new(FONT)), 0);
+        let id = FontRef::new(FONT)
+            .unwrap()
+            .charmap()
+            .map(c)
+            .unwrap()
+            .to_u32();
+
+        let mut renderer = BoundsRenderer::default();
+        configure(
+            GlyphRunBuilder::new(font, Affine::IDENTITY, &mut renderer)
+                .font_size(100.0)
+                .hint(false),
+        )
+        .bounds(core::iter::once(Glyph { id, x: 0.0, y: 0.0 }))
+        .unwrap()
+    }
+
+    #[test]
+    fn synthetic_oblique_shears_about_the_baseline() {
+        let angle = 12_f64.to_radians();
+        let upright = roboto_glyph_bounds('H', |run| run);
+        let oblique = roboto_glyph_bounds('H', |run| run.synthetic_oblique(angle));
+
+        assert_eq!(
+            oblique,
+            roboto_glyph_bounds('H', |run| run
+                .glyph_transform(Affine::skew(-angle.tan(), 0.0)))
+        );
+        // The bottom of the glyph stays in place, its top moves to the right.
+        assert!((oblique.x0 - upright.x0).abs() < 1e-3);
+        assert!((oblique.x1 - upright.x1 - angle.tan() * upright.height()).abs() < 1e-3);
+        assert_eq!((oblique.y0, oblique.y1), (upright.y0, upright.y1));
+    }
+
+    #[test]
+    fn synthetic_bold_widens_stems() {
+        let regular = roboto_glyph_bounds('l', |run| run);
+        let bold = roboto_glyph_bounds('l', |run| run.synthetic_bold(0.05));
+
+        // The stem gets wider by 5 pixels, the left edge and the baseline stay in place.
+        assert!(
+            (bold.width() - regular.width() - 5.0).abs() < 0.1,
+            "{bold:?}"
+        );
+        assert!((bold.x0 - regular.x0).abs() < 0.1, "{bold:?}");
+        assert!((bold.y1 - regular.y1).abs() < 0.1, "{bold:?}");
+        assert!(bold.y0 < regular.y0);
+    }
+
+    #[test]
+    fn variations_clamp_values_and_ignore_unknown_axes() {
+        assert_eq!(
+            inconsolata_m_bounds(&[("wght", 2000.0)]),
+            inconsolata_m_bounds(&[("wght", 900.0)])
+        );
+        assert_eq!(
+            inconsolata_m_bounds(&[("abcd", 900.0), ("not a tag", 1.0)]),
+            inconsolata_m_bounds(&[])
+        );
+    }
 }
 
 /// Caches used for glyph rendering.
@@ -698,6 +1366,31 @@ impl GlyphCaches {
     pub fn maintain(&mut self) {
         self.outline_cache.maintain();
     }
+
+    /// Returns the number of cached entries and an estimate of the memory used by them.
+    pub fn stats(&self) -> GlyphCacheStats {
+        let hinting_instances = self.hinting_cache.len();
+        GlyphCacheStats {
+            outlines: self.outline_cache.cached_count,
+            hinting_instances,
+            approx_bytes: self.outline_cache.approx_bytes()
+                + hinting_instances * size_of::<HintEntry>(),
+        }
+    }
+}
+
+/// Statistics about the contents of [`GlyphCaches`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GlyphCacheStats {
+    /// The number of cached glyph outlines.
+    pub outlines: usize,
+    /// The number of cached hinting instances.
+    pub hinting_instances: usize,
+    /// The approximate number of bytes used by the caches.
+    ///
+    /// This includes the cached outlines and the outline buffers that are kept for reuse, but
+    /// not the memory that hinting instances allocate internally.
+    pub approx_bytes: usize,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
@@ -707,6 +1400,7 @@ struct OutlineKey {
     glyph_id: u32,
     size_bits: u32,
     hint: bool,
+    embolden_bits: u32,
 }
 
 struct OutlineEntry {
@@ -793,6 +1487,26 @@ impl OutlineCache {
         });
     }
 
+    /// Estimate the number of bytes used by the cached and free outlines.
+    fn approx_bytes(&self) -> usize {
+        let path_bytes = |path: &OutlinePath| size_of_val(path.0.elements());
+        let map_bytes = |map: &HashMap<OutlineKey, OutlineEntry>| {
+            map.values()
+                .map(|entry| size_of::<(OutlineKey, OutlineEntry)>() + path_bytes(&entry.path))
+                .sum::<usize>()
+        };
+
+        self.free_list.iter().map(path_bytes).sum::<usize>()
+            + map_bytes(&self.static_map)
+            + self
+                .variable_map
+                .iter()
+                .map(|(key, map)| {
+                    size_of::<VarKey>() + size_of_val(key.as_slice()) + map_bytes(map)
+                })
+                .sum::<usize>()
+    }
+
     fn clear(&mut self) {
         self.free_list.clear();
         self.static_map.clear();
@@ -808,6 +1522,7 @@ struct OutlineCacheSession<'a> {
     free_list: &'a mut Vec<OutlinePath>,
     serial: u32,
     cached_count: &'a mut usize,
+    coords_hash: u64,
 }
 
 impl<'a> OutlineCacheSession<'a> {
@@ -829,6 +1544,7 @@ impl<'a> OutlineCacheSession<'a> {
             free_list: &mut outline_cache.free_list,
             serial: outline_cache.serial,
             cached_count: &mut outline_cache.cached_count,
+            coords_hash: hash_coords(var_key.0),
         }
     }
 
@@ -841,19 +1557,26 @@ impl<'a> OutlineCacheSession<'a> {
         var_key: VarLookupKey<'_>,
         outline_glyph: &skrifa::outline::OutlineGlyph<'_>,
         hinting_instance: Option<&HintingInstance>,
-    ) -> &OutlinePath {
+        embolden: f32,
+    ) -> (OutlineGlyphKey, &OutlinePath) {
         let key = OutlineKey {
             glyph_id,
             font_id,
             font_index,
             size_bits: size.ppem().unwrap().to_bits(),
             hint: hinting_instance.is_some(),
+            embolden_bits: embolden.to_bits(),
+        };
+
+        let glyph_key = OutlineGlyphKey {
+            outline: key,
+            coords_hash: self.coords_hash,
         };
 
         match self.map.entry(key) {
             Entry::Occupied(mut entry) => {
                 entry.get_mut().serial = self.serial;
-                &entry.into_mut().path
+                (glyph_key, &entry.into_mut().path)
             }
             Entry::Vacant(entry) => {
                 let mut path = self.free_list.pop().unwrap_or_default();
@@ -866,10 +1589,13 @@ impl<'a> OutlineCacheSession<'a> {
 
                 path.0.truncate(0);
                 outline_glyph.draw(draw_settings, &mut path).unwrap();
+                if embolden > 0.0 {
+                    embolden_outline(&mut path.0, f64::from(embolden));
+                }
 
                 let entry = entry.insert(OutlineEntry::new(path, self.serial));
                 *self.cached_count += 1;
-                &entry.path
+                (glyph_key, &entry.path)
             }
         }
     }
@@ -894,6 +1620,24 @@ impl From<VarLookupKey<'_>> for VarKey {
     }
 }
 
+/// Hash variation coordinates with FNV-1a, which (unlike the hasher of the maps) gives the
+/// same result in every run, so that keys stay stable across frames.
+fn hash_coords(coords: &[skrifa::instance::NormalizedCoord]) -> u64 {
+    if coords.is_empty() {
+        return 0;
+    }
+
+    coords.iter().fold(0xcbf2_9ce4_8422_2325, |hash, coord| {
+        coord
+            .to_bits()
+            .to_le_bytes()
+            .iter()
+            .fold(hash, |hash, byte| {
+                (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+            })
+    })
+}
+
 /// We keep this small to enable a simple LRU cache with a linear
 /// search. Regenerating hinting data is low to medium cost so it's fine
 /// to redo it occasionally.
@@ -956,6 +1700,10 @@ impl HintCache {
         Some(&entry.instance)
     }
 
+    fn len(&self) -> usize {
+        self.glyf_entries.len() + self.cff_entries.len()
+    }
+
     fn clear(&mut self) {
         self.glyf_entries.clear();
         self.cff_entries.clear();
@@ -0,0 +1,354 @@
+// Copyright 2025 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Testing whether points are inside filled paths, without rendering them.
+//!
+//! The winding numbers are computed the same way as the coverage in [`strip`](crate::strip):
+//! lines directed upwards add to the winding of the points to their right, and lines directed
+//! downwards subtract from it. A point is inside a path if its winding number is non-zero, or
+//! odd with [`Fill::EvenOdd`], so the result agrees with the rendered coverage.
+//!
+//! ```
+//! use vello_common::hit_test;
+//! use vello_common::kurbo::{Circle, Point, Shape};
+//! use vello_common::peniko::Fill;
+//!
+//! let circle = Circle::new((50.0, 50.0), 40.0).to_path(0.1);
+//! assert!(hit_test::path_contains(&circle, Fill::NonZero, Point::new(50.0, 80.0)));
+//! assert!(!hit_test::path_contains(&circle, Fill::NonZero, Point::new(85.0, 85.0)));
+//! ```
+//!
+//! Stroked paths are tested with [`stroke_contains`], which also takes a tolerance for picking
+//! points near the stroke.
+
+use crate::flatten::{self, FlattenCtx, Line};
+use crate::kurbo::{Affine, PathEl, Point, Stroke, StrokeCtx};
+use crate::peniko::Fill;
+use alloc::vec::Vec;
+use fearless_simd::Level;
+
+/// The winding number of `point` with respect to the closed outline formed by `lines`, as
+/// produced by [`flatten::fill`].
+///
+/// Points that lie exactly on one of the lines get the winding number of the area just to their
+/// right (and, on horizontal lines, just below them), like pixels do when rendering.
+pub fn winding_number(lines: &[Line], point: Point) -> i32 {
+    let mut winding = 0;
+    for line in lines {
+        let (x0, y0) = (f64::from(line.p0.x), f64::from(line.p0.y));
+        let (x1, y1) = (f64::from(line.p1.x), f64::from(line.p1.y));
+        // Lines own the rows from their top end up to, but excluding, their bottom end, so
+        // that a point level with the joint of two lines is only counted once.
+        let (top, bottom) = if y0 < y1 { (y0, y1) } else { (y1, y0) };
+        if !(top <= point.y && point.y < bottom) {
+            continue;
+        }
+
+        // The sign of the cross product tells on which side of the line the point is. It is
+        // oriented such that it's positive if the point is to the right of the line.
+        let cross = (x1 - x0) * (point.y - y0) - (y1 - y0) * (point.x - x0);
+        let side = if y0 < y1 { -cross } else { cross };
+        if side >= 0.0 {
+            // Lines moving upwards (in a y-down coordinate system) add to winding; lines moving
+            // downwards subtract from winding.
+            winding += if y0 > y1 { 1 } else { -1 };
+        }
+    }
+    winding
+}
+
+/// Whether `point` is inside the closed outline formed by `lines` under `fill_rule`.
+///
+/// Points that lie exactly on one of the lines are always inside, so that the edges of a path
+/// can be hit.
+pub fn contains(lines: &[Line], point: Point, fill_rule: Fill) -> bool {
+    if lines.iter().any(|line| is_on_line(*line, point)) {
+        return true;
+    }
+
+    let winding = winding_number(lines, point);
+    match fill_rule {
+        Fill::NonZero => winding != 0,
+        Fill::EvenOdd => winding % 2 != 0,
+    }
+}
+
+/// Whether `point` is inside the filled `path` under `fill_rule`.
+///
+/// This flattens the path with the [default tolerance](flatten::DEFAULT_TOLERANCE) in the
+/// coordinate space of the path and passes the lines to [`contains`]. To test many points
+/// against the same path, flatten it once with [`flatten::fill`] instead.
+pub fn path_contains(
+    path: impl IntoIterator<Item = PathEl>,
+    fill_rule: Fill,
+    point: Point,
+) -> bool {
+    let mut lines = Vec::new();
+    flatten::fill(
+        Level::fallback(),
+        path,
+        Affine::IDENTITY,
+        &mut lines,
+        &mut FlattenCtx::default(),
+    );
+    contains(&lines, point, fill_rule)
+}
+
+/// Whether `point` is within `tolerance` of the area covered by stroking `path` with `stroke`.
+///
+/// The stroke is widened by `tolerance` on both sides before its outline is expanded and
+/// flattened like with [`flatten::stroke`], so the caps and joins extend the hit region by
+/// `tolerance` as well. Dashes are kept as they are, so points in the gaps between dashes are
+/// only hit if they are within `tolerance` of a dash. The outline is tested with the non-zero
+/// fill rule, like it is rendered. Negative tolerances are treated as 0.
+///
+/// ```
+/// use vello_common::hit_test::stroke_contains;
+/// use vello_common::kurbo::{Cap, Line, Point, Shape, Stroke};
+///
+/// let line = Line::new((10.0, 50.0), (90.0, 50.0)).to_path(0.1);
+/// let stroke = Stroke::new(20.0).with_caps(Cap::Round);
+/// // The round cap extends the line by half the stroke width.
+/// assert!(stroke_contains(&line, &stroke, Point::new(99.0, 50.0), 0.0));
+/// assert!(!stroke_contains(&line, &stroke, Point::new(102.0, 50.0), 0.0));
+/// assert!(stroke_contains(&line, &stroke, Point::new(102.0, 50.0), 3.0));
+/// ```
+pub fn stroke_contains(
+    path: impl IntoIterator<Item = PathEl>,
+    stroke: &Stroke,
+    point: Point,
+    tolerance: f64,
+) -> bool {
+    let mut style = stroke.clone();
+    style.width += 2.0 * tolerance.max(0.0);
+
+    let mut lines = Vec::new();
+    flatten::stroke(
+        Level::fallback(),
+        path,
+        &style,
+        Affine::IDENTITY,
+        &mut lines,
+        &mut FlattenCtx::default(),
+        &mut StrokeCtx::default(),
+    );
+    contains(&lines, point, Fill::NonZero)
+}
+
+/// Whether `point` lies exactly on `line`.
+fn is_on_line(line: Line, point: Point) -> bool {
+    let (x0, y0) = (f64::from(line.p0.x), f64::from(line.p0.y));
+    let (x1, y1) = (f64::from(line.p1.x), f64::from(line.p1.y));
+    let cross = (x1 - x0) * (point.y - y0) - (y1 - y0) * (point.x - x0);
+    cross == 0.0
+        && x0.min(x1) <= point.x
+        && point.x <= x0.max(x1)
+        && y0.min(y1) <= point.y
+        && point.y <= y0.max(y1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contains, stroke_contains, winding_number};
+    use crate::fearless_simd::Level;
+    use crate::flatten::{self, FlattenCtx, Line};
+    use crate::kurbo::{Affine, BezPath, Cap, Join, Line as KurboLine, Point, Shape, Stroke};
+    use crate::peniko::Fill;
+    use crate::strip;
+    use crate::tile::{Tile, Tiles};
+    use alloc::vec::Vec;
+
+    /// Two overlapping squares with the same orientation and a bowtie whose lobes to the left
+    /// and right of `(90, 30)` meet there.
+    fn self_intersecting_path() -> Vec<Line> {
+        let mut path = BezPath::new();
+        for [x0, y0, x1, y1] in [[10.0, 10.0, 50.0, 50.0], [30.0, 30.0, 70.0, 70.0]] {
+            path.move_to((x0, y0));
+            path.line_to((x1, y0));
+            path.line_to((x1, y1));
+            path.line_to((x0, y1));
+            path.close_path();
+        }
+        path.move_to((80.0, 10.0));
+        path.line_to((100.0, 50.0));
+        path.line_to((100.0, 10.0));
+        path.line_to((80.0, 50.0));
+        path.close_path();
+
+        let mut lines = Vec::new();
+        flatten::fill(
+            Level::fallback(),
+            &path,
+            Affine::IDENTITY,
+            &mut lines,
+            &mut FlattenCtx::default(),
+        );
+        lines
+    }
+
+    #[test]
+    fn points_inside_outside_and_on_the_boundary() {
+        let lines = self_intersecting_path();
+        let hit = |x: f64, y: f64| {
+            [Fill::NonZero, Fill::EvenOdd]
+                .map(|fill_rule| contains(&lines, Point::new(x, y), fill_rule))
+        };
+
+        // Inside one of the squares.
+        assert_eq!(winding_number(&lines, Point::new(20.0, 20.0)).abs(), 1);
+        assert_eq!(hit(20.0, 20.0), [true, true]);
+        assert_eq!(hit(60.0, 60.0), [true, true]);
+        // Where the squares overlap, the winding number is 2.
+        assert_eq!(winding_number(&lines, Point::new(40.0, 40.0)).abs(), 2);
+        assert_eq!(hit(40.0, 40.0), [true, false]);
+        // The two halves of the bowtie wind in opposite directions.
+        assert_eq!(
+            winding_number(&lines, Point::new(85.0, 30.0)),
+            -winding_number(&lines, Point::new(95.0, 30.0))
+        );
+        assert_eq!(hit(85.0, 30.0), [true, true]);
+        assert_eq!(hit(95.0, 30.0), [true, true]);
+        // Outside of the path.
+        assert_eq!(hit(5.0, 5.0), [false, false]);
+        assert_eq!(hit(60.0, 20.0), [false, false]);
+        assert_eq!(hit(20.0, 60.0), [false, false]);
+        assert_eq!(hit(90.0, 20.0), [false, false]);
+        // On the boundary, including edges on the right and bottom, corners, the edges of
+        // the overlap, and the crossing of the bowtie.
+        for (x, y) in [
+            (10.0, 30.0),
+            (50.0, 20.0),
+            (30.0, 50.0),
+            (70.0, 70.0),
+            (30.0, 40.0),
+            (40.0, 50.0),
+            (90.0, 30.0),
+        ] {
+            assert_eq!(hit(x, y), [true, true], "({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn agrees_with_rendered_coverage() {
+        const SIZE: u16 = 112;
+        let lines = self_intersecting_path();
+        let mut tiles = Tiles::new(Level::fallback());
+        tiles.make_tiles_analytic_aa(&lines, SIZE, SIZE);
+        tiles.sort_tiles();
+
+        for fill_rule in [Fill::NonZero, Fill::EvenOdd] {
+            let mut strips = Vec::new();
+            let mut alphas = Vec::new();
+            strip::render(
+                Level::fallback(),
+                &tiles,
+                &mut strips,
+                &mut alphas,
+                fill_rule,
+                None,
+                &lines,
+            );
+
+            let mut checked = 0;
+            for (strip, next) in strips.iter().zip(&strips[1..]) {
+                if strip.is_sentinel() {
+                    continue;
+                }
+                let strip_alphas = &alphas[strip.alpha_idx() as usize..next.alpha_idx() as usize];
+                for (column, column_alphas) in
+                    strip_alphas.chunks(usize::from(Tile::HEIGHT)).enumerate()
+                {
+                    for (row, alpha) in column_alphas.iter().enumerate() {
+                        let center = Point::new(
+                            f64::from(strip.x) + column as f64 + 0.5,
+                            f64::from(strip.y) + row as f64 + 0.5,
+                        );
+                        // Partially covered pixels can go either way.
+                        if *alpha == 0 || *alpha == 255 {
+                            assert_eq!(
+                                contains(&lines, center, fill_rule),
+                                *alpha == 255,
+                                "{center:?} with {fill_rule:?}"
+                            );
+                            checked += 1;
+                        }
+                    }
+                }
+            }
+            assert!(checked > 100, "only {checked} pixels checked");
+        }
+    }
+
+    #[test]
+    fn stroke_hits_include_caps_and_tolerance() {
+        let line = KurboLine::new((10.0, 50.0), (90.0, 50.0)).to_path(0.1);
+        let round = Stroke::new(20.0).with_caps(Cap::Round);
+        let butt = Stroke::new(20.0).with_caps(Cap::Butt);
+        let hit = |stroke: &Stroke, x: f64, y: f64, tolerance: f64| {
+            stroke_contains(&line, stroke, Point::new(x, y), tolerance)
+        };
+
+        // Along the line, within half the stroke width of the centerline.
+        assert!(hit(&butt, 50.0, 59.5, 0.0));
+        assert!(!hit(&butt, 50.0, 60.5, 0.0));
+        assert!(hit(&butt, 50.0, 61.5, 2.0));
+        assert!(!hit(&butt, 50.0, 62.5, 2.0));
+
+        // The round cap at `(90, 50)` has a radius of 10, which the tolerance extends.
+        for (dx, dy) in [(9.5, 0.0), (6.5, 6.5), (0.0, 9.5)] {
+            assert!(hit(&round, 90.0 + dx, 50.0 + dy, 0.0), "({dx}, {dy})");
+            // Butt caps end at the end of the line.
+            assert_eq!(
+                hit(&butt, 90.0 + dx, 50.0 + dy, 0.0),
+                dx == 0.0,
+                "({dx}, {dy})"
+            );
+        }
+        for (dx, dy) in [(10.5, 0.0), (7.5, 7.5)] {
+            assert!(!hit(&round, 90.0 + dx, 50.0 + dy, 0.0), "({dx}, {dy})");
+            assert!(hit(&round, 90.0 + dx, 50.0 + dy, 2.0), "({dx}, {dy})");
+        }
+        for (dx, dy) in [(12.5, 0.0), (9.0, 9.0)] {
+            assert!(!hit(&round, 90.0 + dx, 50.0 + dy, 2.0), "({dx}, {dy})");
+        }
+
+        // Negative tolerances don't shrink the stroke.
+        assert!(hit(&round, 99.5, 50.0, -5.0));
+    }
+
+    #[test]
+    fn stroke_hits_include_joins() {
+        let mut corner = BezPath::new();
+        corner.move_to((10.0, 50.0));
+        corner.line_to((50.0, 50.0));
+        corner.line_to((50.0, 10.0));
+        let miter = Stroke::new(10.0).with_join(Join::Miter);
+        let bevel = Stroke::new(10.0).with_join(Join::Bevel);
+
+        // The miter join fills the outer corner at `(55, 55)`, the bevel join cuts it off.
+        assert!(stroke_contains(
+            &corner,
+            &miter,
+            Point::new(54.5, 54.5),
+            0.0
+        ));
+        assert!(!stroke_contains(
+            &corner,
+            &bevel,
+            Point::new(54.5, 54.5),
+            0.0
+        ));
+        assert!(!stroke_contains(
+            &corner,
+            &miter,
+            Point::new(56.0, 56.0),
+            0.0
+        ));
+        assert!(stroke_contains(
+            &corner,
+            &miter,
+            Point::new(56.0, 56.0),
+            2.0
+        ));
+    }
+}
@@ -23,10 +23,21 @@
 //! - `png` (enabled by default): Allow loading [`Pixmap`][crate::pixmap::Pixmap]s from PNG images.
 //!   Also required for rendering glyphs with an embedded PNG.
 //!   Implies `std`.
+//! - `image`: Allow converting [`Pixmap`][crate::pixmap::Pixmap]s from and to images of the
+//!   [image][] crate. Implies `std`.
 //! - `text` (enabled by default): Enables glyph rendering (see the [`glyph`][] module).
+//! - `multithreading`: Enables parallel strip generation using [rayon][] (see `strip::render_par`).
+//!   Implies `std`.
 //!
 //! At least one of `std` and `libm` is required; `std` overrides `libm`.
 //!
+//! # `no_std` support
+//!
+//! This crate is `no_std` and only requires an allocator, so it can be used on bare-metal targets
+//! such as `thumbv7em-none-eabihf` by disabling the default features and enabling `libm`.
+//! The `text` feature is also available without `std`.
+//! The features which imply `std` (`png`, `image` and `multithreading`) are not available on such targets.
+//!
 //! # Contents
 //!
 //! - Shared data structures for paths, tiles, and strips
@@ -36,6 +47,8 @@
 //! This crate acts as a foundation for `vello_cpu` and `vello_hybrid`, providing essential components to minimize duplication.
 //!
 //! [vello_cpu]: https://crates.io/crates/vello_cpu
+//! [rayon]: https://crates.io/crates/rayon
+//! [image]: https://crates.io/crates/image
 #![cfg_attr(feature = "libm", doc = "[libm]: libm")]
 #![cfg_attr(not(feature = "libm"), doc = "[libm]: https://crates.io/crates/libm")]
 // LINEBENDER LINT SET - lib.rs - v3
@@ -75,6 +88,7 @@ pub mod flatten;
 pub(crate) mod flatten_simd;
 #[cfg(feature = "text")]
 pub mod glyph;
+pub mod hit_test;
 pub mod mask;
 pub mod math;
 pub mod paint;
@@ -87,6 +101,7 @@ pub mod render_graph;
 pub mod simd;
 pub mod strip;
 pub mod strip_generator;
+pub mod svg_export;
 pub mod tile;
 pub mod util;
 
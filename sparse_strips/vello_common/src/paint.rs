@@ -7,7 +7,7 @@ use crate::pixmap::Pixmap;
 use alloc::sync::Arc;
 use peniko::{
     Gradient,
-    color::{AlphaColor, PremulRgba8, Srgb},
+    color::{AlphaColor, ColorSpace, PremulRgba8, Srgb},
 };
 
 /// A paint that needs to be resolved via its index.
@@ -148,8 +148,11 @@ pub struct PremulColor {
 
 impl PremulColor {
     /// Create a new premultiplied color.
+    ///
+    /// Colors outside of the sRGB gamut, like wide-gamut colors converted from Display P3, are
+    /// clipped to it, so that the premultiplied components never exceed the alpha.
     pub fn from_alpha_color(color: AlphaColor<Srgb>) -> Self {
-        Self::from_premul_color(color.premultiply())
+        Self::from_premul_color(clip_to_gamut(color).premultiply())
     }
 
     /// Create a new premultiplied color from `peniko::PremulColor`.
@@ -176,5 +179,12 @@ impl PremulColor {
     }
 }
 
+/// Clip the color components of `color` to the sRGB gamut, and its alpha to [0, 1].
+pub(crate) fn clip_to_gamut(color: AlphaColor<Srgb>) -> AlphaColor<Srgb> {
+    let [r, g, b, a] = color.components;
+    let [r, g, b] = Srgb::clip([r, g, b]);
+    AlphaColor::new([r, g, b, a.clamp(0.0, 1.0)])
+}
+
 /// A kind of paint that can be used for filling and stroking shapes.
 pub type PaintType = peniko::Brush<Image, Gradient>;
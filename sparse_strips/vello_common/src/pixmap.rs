@@ -7,11 +7,15 @@ use alloc::vec;
 use alloc::vec::Vec;
 use peniko::color::Rgba8;
 
-use crate::peniko::color::PremulRgba8;
+use crate::kurbo::Rect;
+use crate::peniko::color::{AlphaColor, PremulRgba8, Srgb};
 
-#[cfg(feature = "png")]
+#[cfg(any(feature = "png", feature = "image"))]
 extern crate std;
 
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
+
 /// A pixmap of premultiplied RGBA8 values backed by [`u8`][core::u8].
 #[derive(Debug, Clone)]
 pub struct Pixmap {
@@ -252,8 +256,56 @@ impl Pixmap {
             }
         };
 
+        pixmap.premultiply();
+
+        Ok(pixmap)
+    }
+
+    /// Create a pixmap from an image of the [image] crate.
+    ///
+    /// Images without an alpha channel or with fewer color channels are expanded to RGBA, and
+    /// images with more than 8 bits per channel are converted to 8 bits.
+    #[cfg(feature = "image")]
+    pub fn from_image(image: &image::DynamicImage) -> Result<Self, image::ImageError> {
+        let dimension_error = || {
+            image::ImageError::Limits(image::error::LimitError::from_kind(
+                image::error::LimitErrorKind::DimensionError,
+            ))
+        };
+        let width: u16 = image.width().try_into().map_err(|_| dimension_error())?;
+        let height: u16 = image.height().try_into().map_err(|_| dimension_error())?;
+
+        let data = bytemuck::cast_vec(image.to_rgba8().into_raw());
+        let mut pixmap = Self::from_parts(data, width, height);
+        pixmap.premultiply();
+
+        Ok(pixmap)
+    }
+
+    /// Return the current content of the pixmap as an (unpremultiplied) image of the
+    /// [image] crate.
+    #[cfg(feature = "image")]
+    pub fn to_image(&self) -> image::RgbaImage {
+        let data = self
+            .buf
+            .iter()
+            .copied()
+            .map(unpremultiply)
+            .collect::<Vec<_>>();
+        image::RgbaImage::from_raw(
+            self.width.into(),
+            self.height.into(),
+            bytemuck::cast_vec(data),
+        )
+        .expect("the buffer has the size of the pixmap")
+    }
+
+    /// Premultiply the color channels of the pixmap, which are assumed to hold unpremultiplied
+    /// RGBA8, by their alpha and update [`may_have_opacities`](Self::may_have_opacities).
+    #[cfg(any(feature = "png", feature = "image"))]
+    fn premultiply(&mut self) {
         let mut may_have_opacities = false;
-        for pixel in pixmap.data_mut() {
+        for pixel in self.data_mut() {
             let alpha = pixel.a;
             if alpha != 255 {
                 may_have_opacities = true;
@@ -268,9 +320,7 @@ impl Pixmap {
             pixel.g = premultiply(pixel.g);
             pixel.b = premultiply(pixel.b);
         }
-        pixmap.may_have_opacities = may_have_opacities;
-
-        Ok(pixmap)
+        self.may_have_opacities = may_have_opacities;
     }
 
     /// Return the current content of the pixmap as a PNG.
@@ -342,6 +392,103 @@ impl Pixmap {
         self.buf[idx] = pixel;
     }
 
+    /// Composite `other` over this pixmap using source-over, with the top-left corner of `other`
+    /// placed at `(x, y)`.
+    ///
+    /// Parts of `other` that fall outside of this pixmap are clipped. Both pixmaps are
+    /// premultiplied, and compositing over an opaque pixel keeps it opaque, so
+    /// [`may_have_opacities`](Self::may_have_opacities) doesn't change.
+    pub fn blend_over(&mut self, other: &Self, x: i32, y: i32) {
+        let width = i32::from(self.width);
+        let height = i32::from(self.height);
+        let x0 = x.clamp(0, width);
+        let y0 = y.clamp(0, height);
+        let x1 = (x + i32::from(other.width)).clamp(0, width);
+        let y1 = (y + i32::from(other.height)).clamp(0, height);
+
+        for dst_y in y0..y1 {
+            for dst_x in x0..x1 {
+                #[expect(
+                    clippy::cast_sign_loss,
+                    clippy::cast_possible_truncation,
+                    reason = "clamped to the bounds of both pixmaps"
+                )]
+                let (src_x, src_y) = ((dst_x - x) as u16, (dst_y - y) as u16);
+                let idx = dst_y as usize * self.width as usize + dst_x as usize;
+                self.buf[idx] = src_over(other.sample(src_x, src_y), self.buf[idx]);
+            }
+        }
+    }
+
+    /// Composite `color` over the pixels of this pixmap within `rect` using source-over.
+    ///
+    /// The rectangle is snapped to whole pixels by rounding its edges and clipped to the
+    /// pixmap, there is no anti-aliasing. As with [`blend_over`](Self::blend_over),
+    /// [`may_have_opacities`](Self::may_have_opacities) doesn't change.
+    pub fn fill_rect(&mut self, rect: Rect, color: AlphaColor<Srgb>) {
+        let src = color.premultiply().to_rgba8();
+        let [x0, y0, x1, y1] = self.pixel_bounds(rect).map(usize::from);
+
+        for y in y0..y1 {
+            let row = y * self.width as usize;
+            for pixel in &mut self.buf[row + x0..row + x1] {
+                *pixel = src_over(src, *pixel);
+            }
+        }
+    }
+
+    /// Borrow the pixels of this pixmap within `rect` without copying them.
+    ///
+    /// The rectangle is snapped to whole pixels by rounding its edges and clamped to the pixmap,
+    /// so the view can be smaller than `rect` (or even empty).
+    pub fn view(&self, rect: Rect) -> PixmapView<'_> {
+        let [x0, y0, x1, y1] = self.pixel_bounds(rect);
+        let (width, height) = (x1 - x0, y1 - y0);
+        let data = if width == 0 || height == 0 {
+            &[]
+        } else {
+            let start = usize::from(y0) * usize::from(self.width) + usize::from(x0);
+            let len = usize::from(height - 1) * usize::from(self.width) + usize::from(width);
+            &self.buf[start..start + len]
+        };
+
+        PixmapView {
+            data,
+            width,
+            height,
+            stride: self.width,
+            may_have_opacities: Some(self.may_have_opacities),
+        }
+    }
+
+    /// Copy the pixels of this pixmap within `rect` into a new pixmap.
+    ///
+    /// The rectangle is snapped and clamped in the same way as in [`view`](Self::view).
+    pub fn crop(&self, rect: Rect) -> Self {
+        self.view(rect).to_pixmap()
+    }
+
+    /// Snap `rect` to whole pixels by rounding its edges and clamp it to the pixmap, returning
+    /// `[x0, y0, x1, y1]`.
+    fn pixel_bounds(&self, rect: Rect) -> [u16; 4] {
+        let clamp = |v: f64, max: u16| {
+            #[expect(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                reason = "clamped to the bounds of the pixmap"
+            )]
+            let v = v.round().clamp(0.0, f64::from(max)) as u16;
+            v
+        };
+        let rect = rect.abs();
+        [
+            clamp(rect.x0, self.width),
+            clamp(rect.y0, self.height),
+            clamp(rect.x1, self.width),
+            clamp(rect.y1, self.height),
+        ]
+    }
+
     /// Consume the pixmap, returning the data as the underlying [`Vec`] of premultiplied RGBA8.
     ///
     /// The pixels are in row-major order.
@@ -355,23 +502,230 @@ impl Pixmap {
     ///
     /// The pixels are in row-major order.
     pub fn take_unpremultiplied(self) -> Vec<Rgba8> {
-        self.buf
-            .into_iter()
-            .map(|PremulRgba8 { r, g, b, a }| {
-                let alpha = 255.0 / f32::from(a);
-                if a != 0 {
-                    #[expect(clippy::cast_possible_truncation, reason = "deliberate quantization")]
-                    let unpremultiply = |component| (f32::from(component) * alpha + 0.5) as u8;
-                    Rgba8 {
-                        r: unpremultiply(r),
-                        g: unpremultiply(g),
-                        b: unpremultiply(b),
-                        a,
-                    }
-                } else {
-                    Rgba8 { r, g, b, a }
-                }
-            })
-            .collect()
+        self.buf.into_iter().map(unpremultiply).collect()
+    }
+}
+
+/// A borrowed, read-only view of a rectangular region of premultiplied RGBA8 pixels.
+///
+/// Rows are `stride` pixels apart in the underlying data, so a view can reference a sub-region of
+/// a [`Pixmap`] (see [`Pixmap::view`]) or of a padded buffer, such as one read back from a GPU
+/// texture, without copying.
+#[derive(Debug, Clone, Copy)]
+pub struct PixmapView<'a> {
+    data: &'a [PremulRgba8],
+    width: u16,
+    height: u16,
+    stride: u16,
+    /// Known when the view was created from a [`Pixmap`], otherwise computed when copying.
+    may_have_opacities: Option<bool>,
+}
+
+impl<'a> PixmapView<'a> {
+    /// Create a view of `width` by `height` pixels, whose rows start `stride` pixels apart
+    /// in `data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is smaller than `width` or if `data` is too short to hold all rows.
+    pub fn new(data: &'a [PremulRgba8], width: u16, height: u16, stride: u16) -> Self {
+        assert!(stride >= width, "stride must be at least the width");
+        if width != 0 && height != 0 {
+            let required = usize::from(height - 1) * usize::from(stride) + usize::from(width);
+            assert!(data.len() >= required, "data is too short for the view");
+        }
+
+        Self {
+            data,
+            width,
+            height,
+            stride,
+            may_have_opacities: None,
+        }
+    }
+
+    /// Return the width of the view.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Return the height of the view.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Return the distance between the starts of consecutive rows, in pixels.
+    pub fn stride(&self) -> u16 {
+        self.stride
+    }
+
+    /// Return the pixels of row `y`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is not smaller than the height of the view.
+    pub fn row(&self, y: u16) -> &'a [PremulRgba8] {
+        assert!(y < self.height, "row out of bounds");
+        let start = usize::from(y) * usize::from(self.stride);
+        &self.data[start..start + usize::from(self.width)]
+    }
+
+    /// Iterate over the rows of the view, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [PremulRgba8]> + 'a {
+        let view = *self;
+        (0..self.height).map(move |y| view.row(y))
+    }
+
+    /// Sample a pixel from the view.
+    #[inline(always)]
+    pub fn sample(&self, x: u16, y: u16) -> PremulRgba8 {
+        self.row(y)[usize::from(x)]
+    }
+
+    /// Copy the pixels of the view into a new, tightly packed pixmap.
+    pub fn to_pixmap(&self) -> Pixmap {
+        let mut buf = Vec::with_capacity(usize::from(self.width) * usize::from(self.height));
+        for row in self.rows() {
+            buf.extend_from_slice(row);
+        }
+        match self.may_have_opacities {
+            Some(may_have_opacities) => {
+                Pixmap::from_parts_with_opacity(buf, self.width, self.height, may_have_opacities)
+            }
+            None => Pixmap::from_parts(buf, self.width, self.height),
+        }
+    }
+}
+
+/// Convert a premultiplied pixel to an unpremultiplied one.
+fn unpremultiply(PremulRgba8 { r, g, b, a }: PremulRgba8) -> Rgba8 {
+    let alpha = 255.0 / f32::from(a);
+    if a != 0 {
+        #[expect(clippy::cast_possible_truncation, reason = "deliberate quantization")]
+        let unpremultiply = |component| (f32::from(component) * alpha + 0.5) as u8;
+        Rgba8 {
+            r: unpremultiply(r),
+            g: unpremultiply(g),
+            b: unpremultiply(b),
+            a,
+        }
+    } else {
+        Rgba8 { r, g, b, a }
+    }
+}
+
+/// Composite the premultiplied `src` over `dst`.
+fn src_over(src: PremulRgba8, dst: PremulRgba8) -> PremulRgba8 {
+    let inv_alpha = 255 - u16::from(src.a);
+    let blend =
+        |s: u8, d: u8| (u16::from(s) + (u16::from(d) * inv_alpha + 127) / 255).min(255) as u8;
+    PremulRgba8 {
+        r: blend(src.r, dst.r),
+        g: blend(src.g, dst.g),
+        b: blend(src.b, dst.b),
+        a: blend(src.a, dst.a),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peniko::color::palette::css::{BLUE, RED};
+
+    #[test]
+    fn blend_over_composites_premultiplied_pixels() {
+        let mut dst = Pixmap::new(4, 4);
+        dst.fill_rect(Rect::new(0.0, 0.0, 4.0, 4.0), BLUE);
+        let mut src = Pixmap::new(2, 2);
+        src.fill_rect(Rect::new(0.0, 0.0, 2.0, 2.0), RED.with_alpha(0.5));
+
+        // Only the bottom-right pixel of `src` overlaps `dst`.
+        dst.blend_over(&src, -1, -1);
+        // Only the top-left pixel of `src` overlaps `dst`.
+        dst.blend_over(&src, 3, 3);
+
+        let purple = PremulRgba8 {
+            r: 128,
+            g: 0,
+            b: 127,
+            a: 255,
+        };
+        let blue = PremulRgba8 {
+            r: 0,
+            g: 0,
+            b: 255,
+            a: 255,
+        };
+        assert_eq!(dst.sample(0, 0), purple);
+        assert_eq!(dst.sample(3, 3), purple);
+        assert_eq!(dst.sample(1, 0), blue);
+        assert_eq!(dst.sample(0, 1), blue);
+        assert_eq!(dst.sample(2, 2), blue);
+    }
+
+    #[test]
+    fn fill_rect_snaps_and_clips_to_pixels() {
+        let mut pixmap = Pixmap::new(4, 4);
+        pixmap.fill_rect(Rect::new(2.6, -3.0, 1.4, 1.2), RED.with_alpha(0.5));
+
+        let half_red = PremulRgba8 {
+            r: 128,
+            g: 0,
+            b: 0,
+            a: 128,
+        };
+        let transparent = PremulRgba8::from_u32(0);
+        assert_eq!(pixmap.sample(1, 0), half_red);
+        assert_eq!(pixmap.sample(2, 0), half_red);
+        assert_eq!(pixmap.sample(0, 0), transparent);
+        assert_eq!(pixmap.sample(3, 0), transparent);
+        assert_eq!(pixmap.sample(1, 1), transparent);
+    }
+
+    /// A 16x16 pixmap whose red channel increases along x and green channel along y.
+    fn gradient() -> Pixmap {
+        let mut pixmap = Pixmap::new(16, 16);
+        for y in 0..16 {
+            for x in 0..16 {
+                let pixel = PremulRgba8 {
+                    r: x as u8 * 16,
+                    g: y as u8 * 16,
+                    b: 0,
+                    a: 255,
+                };
+                pixmap.set_pixel(x, y, pixel);
+            }
+        }
+        pixmap
+    }
+
+    #[test]
+    fn crop_and_view_reference_the_same_pixels() {
+        let pixmap = gradient();
+        let rect = Rect::new(3.0, 5.0, 11.0, 9.0);
+        let view = pixmap.view(rect);
+        let cropped = pixmap.crop(rect);
+
+        assert_eq!((view.width(), view.height(), view.stride()), (8, 4, 16));
+        assert_eq!((cropped.width(), cropped.height()), (8, 4));
+        for (x, y) in [(0, 0), (7, 0), (0, 3), (7, 3)] {
+            let expected = pixmap.sample(x + 3, y + 5);
+            assert_eq!(view.sample(x, y), expected);
+            assert_eq!(cropped.sample(x, y), expected);
+        }
+    }
+
+    #[test]
+    fn crop_clamps_to_pixmap() {
+        let pixmap = gradient();
+        let cropped = pixmap.crop(Rect::new(12.0, -4.0, 20.0, 2.0));
+
+        assert_eq!((cropped.width(), cropped.height()), (4, 2));
+        assert_eq!(cropped.sample(0, 0), pixmap.sample(12, 0));
+        assert_eq!(cropped.sample(3, 1), pixmap.sample(15, 1));
+
+        let empty = pixmap.view(Rect::new(20.0, 20.0, 30.0, 30.0));
+        assert_eq!((empty.width(), empty.height()), (0, 0));
+        assert_eq!(empty.rows().count(), 0);
     }
 }
@@ -123,6 +123,12 @@ pub enum RenderCommand {
     SetStroke(Stroke),
     /// Push a new layer with optional clipping and effects.
     PushLayer(PushLayerCommand),
+    /// Push a new layer clipped to a path.
+    ///
+    /// Unlike the clip path of [`RenderCommand::PushLayer`], the strips of the clip path are
+    /// cached along with the geometry when preparing the recording, and the layer is still
+    /// pushed as a clip layer when executing it.
+    PushClip(BezPath),
     /// Pop the current layer.
     PopLayer,
     /// Set the current paint.
@@ -141,6 +147,10 @@ pub enum RenderCommand {
     /// Render a stroke outline glyph.
     #[cfg(feature = "text")]
     StrokeOutlineGlyph((BezPath, Affine)),
+    /// Render a stroke outline glyph with the given stroke and paint, instead of the current
+    /// ones.
+    #[cfg(feature = "text")]
+    StrokeOutlineGlyphWith((BezPath, Affine, Stroke, PaintType)),
 }
 
 impl Recording {
@@ -226,6 +236,59 @@ impl Default for Recording {
     }
 }
 
+/// A retained list of rendering commands that can be replayed with different root transforms.
+///
+/// Unlike a [`Recording`], a display list doesn't cache any strips, since those are only
+/// valid for the transform they were generated with. Instead, all transforms in the list
+/// are composed with the root transform when replaying it, which makes it possible to
+/// build a scene once and to draw it at a different position or scale in every frame.
+///
+/// A display list is built by recording commands and converting the recording:
+/// ```ignore
+/// let mut recording = Recording::new();
+/// scene.record(&mut recording, |ctx| { ... });
+/// let display_list = DisplayList::from(recording);
+///
+/// // Every frame
+/// scene.replay(&display_list, root_transform);
+/// ```
+#[derive(Debug, Default)]
+pub struct DisplayList {
+    /// The retained commands.
+    commands: Vec<RenderCommand>,
+}
+
+impl DisplayList {
+    /// Create a new empty display list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get commands as a slice.
+    pub fn commands(&self) -> &[RenderCommand] {
+        &self.commands
+    }
+
+    /// Check if the display list contains no commands.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Clear the display list contents.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+}
+
+impl From<Recording> for DisplayList {
+    /// Retain the commands of a recording, dropping its cached strips.
+    fn from(recording: Recording) -> Self {
+        Self {
+            commands: recording.commands,
+        }
+    }
+}
+
 /// Trait for rendering contexts that support recording and replaying operations.
 ///
 /// # State Modification During Replay
@@ -440,8 +503,12 @@ impl<'a> Recorder<'a> {
     }
 
     /// Push a new clip layer.
+    ///
+    /// This records a [`RenderCommand::PushClip`], which needs to be balanced by a call to
+    /// [`Recorder::pop_layer`].
     pub fn push_clip_layer(&mut self, clip_path: &BezPath) {
-        self.push_layer(Some(clip_path), None, None, None, None);
+        self.recording
+            .add_command(RenderCommand::PushClip(clip_path.clone()));
     }
 
     /// Push a new filter layer.
@@ -499,6 +566,27 @@ impl GlyphRenderer for Recorder<'_> {
         }
     }
 
+    fn stroke_glyph_with(&mut self, glyph: PreparedGlyph<'_>, stroke: &Stroke, paint: &PaintType) {
+        // The recorder doesn't know the stroke and paint that will be current when the
+        // recording is executed, so the glyph carries its own instead of changing them.
+        match glyph.glyph_type {
+            GlyphType::Outline(outline_glyph) => {
+                if !outline_glyph.path.is_empty() {
+                    self.recording
+                        .add_command(RenderCommand::StrokeOutlineGlyphWith((
+                            outline_glyph.path.clone(),
+                            glyph.transform,
+                            stroke.clone(),
+                            paint.clone(),
+                        )));
+                }
+            }
+            _ => {
+                unimplemented!("Recording glyphs of type {:?}", glyph.glyph_type);
+            }
+        }
+    }
+
     fn restore_glyph_caches(&mut self, caches: crate::glyph::GlyphCaches) {
         self.glyph_caches = Some(caches);
     }
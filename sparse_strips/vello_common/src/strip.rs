@@ -4,11 +4,16 @@
 //! Rendering strips.
 
 use crate::flatten::Line;
+use crate::kurbo::Rect;
 use crate::peniko::Fill;
 use crate::tile::{Tile, Tiles};
 use crate::util::f32_to_u8;
+#[cfg(feature = "multithreading")]
+use alloc::vec;
 use alloc::vec::Vec;
 use fearless_simd::*;
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
 
 /// A strip.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,6 +34,15 @@ impl Strip {
     /// The bit mask for `fill_gap` packed into `packed_alpha_idx_fill_gap`.
     const FILL_GAP_MASK: u32 = 1 << 31;
 
+    /// The largest alpha index that can be stored in a strip.
+    ///
+    /// Since strips index into an alpha buffer that is shared by a whole scene, this also caps
+    /// the length of that buffer. Paths whose alphas would not fit are skipped during strip
+    /// generation, see [`StripStorage::has_alpha_capacity`].
+    ///
+    /// [`StripStorage::has_alpha_capacity`]: crate::strip_generator::StripStorage::has_alpha_capacity
+    pub const MAX_ALPHA_IDX: u32 = !Self::FILL_GAP_MASK;
+
     /// Creates a new strip.
     pub fn new(x: u16, y: u16, alpha_idx: u32, fill_gap: bool) -> Self {
         // Ensure `alpha_idx` does not collide with the fill flag bit.
@@ -62,8 +76,8 @@ impl Strip {
 
     /// Sets the alpha index.
     ///
-    /// Note that the largest value that can be stored in the alpha index is `u32::MAX << 1`, as the
-    /// highest bit is reserved for `fill_gap`.
+    /// Note that the largest value that can be stored in the alpha index is
+    /// [`Strip::MAX_ALPHA_IDX`], as the highest bit is reserved for `fill_gap`.
     #[inline(always)]
     pub fn set_alpha_idx(&mut self, alpha_idx: u32) {
         // Ensure `alpha_idx` does not collide with the fill flag bit.
@@ -91,6 +105,10 @@ impl Strip {
 }
 
 /// Render the tiles stored in `tiles` into the strip and alpha buffer.
+///
+/// The strips are the same for every SIMD level. The alpha values may differ by at most 1
+/// between levels, as some levels (like AVX2 and NEON) use fused multiply-adds, which round
+/// differently. For bit-identical output across machines, use [`Level::fallback`].
 pub fn render(
     level: Level,
     tiles: &Tiles,
@@ -100,23 +118,280 @@ pub fn render(
     aliasing_threshold: Option<u8>,
     lines: &[Line],
 ) {
-    dispatch!(level, simd => render_impl(simd, tiles, strip_buf, alpha_buf, fill_rule, aliasing_threshold, lines));
+    if tiles.is_empty() {
+        return;
+    }
+
+    let mode = CoverageMode::from_aliasing_threshold(aliasing_threshold);
+    dispatch!(level, simd => render_impl(simd, tiles.as_slice(), tiles, strip_buf, alpha_buf, fill_rule, mode, lines, true));
 }
 
-#[inline(always)]
-fn render_impl<S: Simd>(
-    s: S,
+/// Render the tiles stored in `tiles` into the strip and alpha buffer conservatively, such that
+/// every pixel that the path covers at all is fully opaque, and every other pixel transparent.
+///
+/// Unlike an aliasing threshold in [`render`], which drops pixels that are covered less than
+/// the threshold, this includes pixels with arbitrarily small coverage, for example where a thin
+/// sliver of the path just touches them. The resulting 1-bit mask contains every pixel a path
+/// could possibly touch, which is useful as a coarse region for hit-testing.
+pub fn render_conservative(
+    level: Level,
     tiles: &Tiles,
     strip_buf: &mut Vec<Strip>,
     alpha_buf: &mut Vec<u8>,
     fill_rule: Fill,
-    aliasing_threshold: Option<u8>,
     lines: &[Line],
 ) {
     if tiles.is_empty() {
         return;
     }
 
+    dispatch!(level, simd => render_impl(simd, tiles.as_slice(), tiles, strip_buf, alpha_buf, fill_rule, CoverageMode::Conservative, lines, true));
+}
+
+/// How the coverage of pixels is converted into alphas.
+#[derive(Clone, Copy, Debug)]
+enum CoverageMode {
+    /// The alpha is proportional to the coverage.
+    AntiAliased,
+    /// Pixels are opaque if their alpha is at least the threshold, and transparent otherwise.
+    Aliased(u8),
+    /// Pixels are opaque if they're covered at all, and transparent otherwise.
+    Conservative,
+}
+
+impl CoverageMode {
+    fn from_aliasing_threshold(aliasing_threshold: Option<u8>) -> Self {
+        aliasing_threshold.map_or(Self::AntiAliased, Self::Aliased)
+    }
+}
+
+/// The minimum number of tiles for each partition of [`render_par`].
+#[cfg(feature = "multithreading")]
+const MIN_TILES_PER_PARTITION: usize = 4096;
+
+/// Render the tiles stored in `tiles` into the strip and alpha buffer, in parallel in the
+/// current thread pool.
+///
+/// The output is identical to that of [`render`]. The tiles are partitioned into ranges of
+/// rows, which are rendered independently and merged afterwards. This only pays off for paths
+/// with many tiles; smaller paths are rendered serially.
+#[cfg(feature = "multithreading")]
+pub fn render_par(
+    level: Level,
+    tiles: &Tiles,
+    strip_buf: &mut Vec<Strip>,
+    alpha_buf: &mut Vec<u8>,
+    fill_rule: Fill,
+    aliasing_threshold: Option<u8>,
+    lines: &[Line],
+) {
+    let num_partitions = rayon::current_num_threads()
+        .min(tiles.len() as usize / MIN_TILES_PER_PARTITION)
+        .max(1);
+
+    render_partitioned(
+        level,
+        tiles,
+        strip_buf,
+        alpha_buf,
+        fill_rule,
+        aliasing_threshold,
+        lines,
+        num_partitions,
+    );
+}
+
+#[cfg(feature = "multithreading")]
+fn render_partitioned(
+    level: Level,
+    tiles: &Tiles,
+    strip_buf: &mut Vec<Strip>,
+    alpha_buf: &mut Vec<u8>,
+    fill_rule: Fill,
+    aliasing_threshold: Option<u8>,
+    lines: &[Line],
+    num_partitions: usize,
+) {
+    use rayon::prelude::*;
+
+    if num_partitions <= 1 || tiles.is_empty() {
+        render(
+            level,
+            tiles,
+            strip_buf,
+            alpha_buf,
+            fill_rule,
+            aliasing_threshold,
+            lines,
+        );
+        return;
+    }
+
+    let tile_slice = tiles.as_slice();
+
+    // Windings are reset at the start of each row, so partitioning at row boundaries allows
+    // rendering each partition independently.
+    let mut bounds = vec![0];
+    for i in 1..num_partitions {
+        let row = tile_slice[tile_slice.len() * i / num_partitions].y;
+        let start = tile_slice.partition_point(|tile| tile.y < row);
+        if start > *bounds.last().unwrap() {
+            bounds.push(start);
+        }
+    }
+    bounds.push(tile_slice.len());
+
+    let last = bounds.len() - 2;
+    let mode = CoverageMode::from_aliasing_threshold(aliasing_threshold);
+    let partitions: Vec<(Vec<Strip>, Vec<u8>)> = bounds
+        .par_windows(2)
+        .enumerate()
+        .map(|(i, range)| {
+            let mut strips = Vec::new();
+            let mut alphas = Vec::new();
+            // Only the last partition terminates the path.
+            dispatch!(level, simd => render_impl(
+                simd,
+                &tile_slice[range[0]..range[1]],
+                tiles,
+                &mut strips,
+                &mut alphas,
+                fill_rule,
+                mode,
+                lines,
+                i == last,
+            ));
+            (strips, alphas)
+        })
+        .collect();
+
+    for (strips, alphas) in partitions {
+        // Each partition indexes into its own alpha buffer, so rebase its indices.
+        let alpha_offset = alpha_buf.len() as u32;
+        strip_buf.extend(strips.into_iter().map(|mut strip| {
+            strip.set_alpha_idx(strip.alpha_idx() + alpha_offset);
+            strip
+        }));
+        alpha_buf.extend(alphas);
+    }
+}
+
+/// Render an axis-aligned rectangle, given in device coordinates, into the strip and alpha
+/// buffer.
+///
+/// This produces the same coverage as flattening the outline of the rectangle and passing it to
+/// [`render`], but computes it directly instead of tiling the lines, so the cost only depends
+/// on the number of tiles along the edges. The alphas are identical for coordinates with few
+/// fractional bits (like multiples of 1/16), and may otherwise differ by at most 1, like the
+/// alphas of different SIMD levels.
+///
+/// Parts of the rectangle that lie outside a viewport of the given size are discarded. The fill
+/// rule doesn't matter, as a rectangle never overlaps itself.
+pub fn render_rect(
+    rect: Rect,
+    width: u16,
+    height: u16,
+    strip_buf: &mut Vec<Strip>,
+    alpha_buf: &mut Vec<u8>,
+    aliasing_threshold: Option<u8>,
+) {
+    // Like flattened lines, the rectangle is rounded to `f32`.
+    let x0 = (rect.min_x() as f32).max(0.0);
+    let x1 = (rect.max_x() as f32).min(f32::from(width));
+    let y0 = (rect.min_y() as f32).max(0.0);
+    let y1 = (rect.max_y() as f32).min(f32::from(height));
+    // This also rejects rectangles with NaN coordinates.
+    if !(x0 < x1 && y0 < y1) {
+        return;
+    }
+
+    // The length of `start..end` that lies within the pixel at `px`.
+    let coverage = |start: f32, end: f32, px: u16| {
+        let px = f32::from(px);
+        (end.min(px + 1.0) - start.max(px)).max(0.0)
+    };
+    let to_alpha = |coverage: f32| {
+        let alpha = (coverage * 255.0 + 0.5) as u8;
+        match aliasing_threshold {
+            Some(threshold) if alpha >= threshold => 255,
+            Some(_) => 0,
+            None => alpha,
+        }
+    };
+
+    let strips_start = strip_buf.len();
+    // The (inclusive) ranges of tiles touched by the rectangle.
+    let tile_x0 = x0 as u16 / Tile::WIDTH;
+    let tile_x1 = (x1.ceil() as u16 - 1) / Tile::WIDTH;
+    let tile_y0 = y0 as u16 / Tile::HEIGHT;
+    let tile_y1 = (y1.ceil() as u16 - 1) / Tile::HEIGHT;
+
+    for tile_y in tile_y0..=tile_y1 {
+        let y = tile_y * Tile::HEIGHT;
+        let row_coverage: [f32; Tile::HEIGHT as usize] =
+            core::array::from_fn(|i| coverage(y0, y1, y + i as u16));
+
+        let mut push_strip = |tile_start: u16, tile_end: u16, fill_gap: bool| {
+            strip_buf.push(Strip::new(
+                tile_start * Tile::WIDTH,
+                y,
+                alpha_buf.len() as u32,
+                fill_gap,
+            ));
+            for x in tile_start * Tile::WIDTH..=tile_end * Tile::WIDTH + (Tile::WIDTH - 1) {
+                let column_coverage = coverage(x0, x1, x);
+                alpha_buf.extend(row_coverage.map(|c| to_alpha(column_coverage * c)));
+            }
+        };
+
+        // In rows that are covered completely, only the tiles with the left and right edges
+        // need alphas, the ones in between are filled sparsely.
+        if tile_x1 > tile_x0 + 1 && row_coverage.iter().all(|c| *c == 1.0) {
+            push_strip(tile_x0, tile_x0, false);
+            push_strip(tile_x1, tile_x1, true);
+        } else {
+            push_strip(tile_x0, tile_x1, false);
+        }
+    }
+
+    strip_buf.push(Strip::new(
+        u16::MAX,
+        tile_y1 * Tile::HEIGHT,
+        alpha_buf.len() as u32,
+        false,
+    ));
+    drop_opaque_tiles(strip_buf, alpha_buf, strips_start);
+}
+
+// `render_impl` stores the coverage of a tile column in a single `f32x4`, and packs the four
+// columns of a tile into one `u8x16` of alphas. Changing the tile height requires generalizing
+// it over the vector width first.
+const _: () = const {
+    if Tile::HEIGHT != 4 {
+        panic!("strip rendering assumes that a tile column fits exactly into an `f32x4`");
+    }
+    if Tile::WIDTH != 4 {
+        panic!("strip rendering assumes that the alphas of a tile fit exactly into a `u8x16`");
+    }
+};
+
+/// Render a range of sorted tiles, starting at the beginning of a row.
+///
+/// `tiles` provides the winding of geometry left of the viewport. If `terminate` is `true`, a
+/// final sentinel strip is always emitted to end the path.
+#[inline(always)]
+#[expect(clippy::too_many_arguments, reason = "internal function")]
+fn render_impl<S: Simd>(
+    s: S,
+    tile_slice: &[Tile],
+    tiles: &Tiles,
+    strip_buf: &mut Vec<Strip>,
+    alpha_buf: &mut Vec<u8>,
+    fill_rule: Fill,
+    mode: CoverageMode,
+    lines: &[Line],
+    terminate: bool,
+) {
     let should_fill = |winding: i32| match fill_rule {
         Fill::NonZero => winding != 0,
         Fill::EvenOdd => winding % 2 != 0,
@@ -128,11 +403,11 @@ fn render_impl<S: Simd>(
     let mut winding_delta: i32 = 0;
 
     // The previous tile visited.
-    let mut prev_tile = *tiles.get(0);
+    let mut prev_tile = tile_slice[0];
     // The accumulated (fractional) winding of the tile-sized location we're currently at.
     // Note multiple tiles can be at the same location.
-    // Note that we are also implicitly assuming here that the tile height exactly fits into a
-    // SIMD vector (i.e. 128 bits).
+    // Note that we are assuming here that the tile height exactly fits into a SIMD vector (i.e.
+    // 128 bits), which is checked at compile time above.
     let mut location_winding = [f32x4::splat(s, 0.0); Tile::WIDTH as usize];
     // The accumulated (fractional) windings at this location's right edge. When we move to the
     // next location, this is splatted to that location's starting winding.
@@ -141,6 +416,8 @@ fn render_impl<S: Simd>(
     /// A special tile to keep the logic below simple.
     const SENTINEL: Tile = Tile::new(u16::MAX, u16::MAX, 0, 0);
 
+    let strips_start = strip_buf.len();
+
     // The strip we're building.
     let mut strip = Strip::new(
         prev_tile.x * Tile::WIDTH,
@@ -149,7 +426,7 @@ fn render_impl<S: Simd>(
         false,
     );
 
-    for (tile_idx, tile) in tiles.iter().copied().chain([SENTINEL]).enumerate() {
+    for (tile_idx, tile) in tile_slice.iter().copied().chain([SENTINEL]).enumerate() {
         let line = lines[tile.line_idx() as usize];
         let tile_left_x = f32::from(tile.x) * f32::from(Tile::WIDTH);
         let tile_top_y = f32::from(tile.y) * f32::from(Tile::HEIGHT);
@@ -161,6 +438,14 @@ fn render_impl<S: Simd>(
         // Push out the winding as an alpha mask when we move to the next location (i.e., a tile
         // without the same location).
         if !prev_tile.same_loc(&tile) {
+            // In conservative mode, any coverage is turned into a fully opaque pixel. This is
+            // decided on the coverage itself, as tiny coverage would round to an alpha of 0.
+            let conservative = |coverage: f32x4<S>| {
+                let opaque = f32x4::splat(s, 255.0);
+                let zero = f32x4::splat(s, 0.0);
+                s.select_f32x4(coverage.simd_gt(zero), opaque, zero)
+            };
+
             match fill_rule {
                 Fill::NonZero => {
                     let p1 = f32x4::splat(s, 0.5);
@@ -170,6 +455,10 @@ fn render_impl<S: Simd>(
                     for x in 0..Tile::WIDTH as usize {
                         let area = location_winding[x];
                         let coverage = area.abs();
+                        if matches!(mode, CoverageMode::Conservative) {
+                            location_winding[x] = conservative(coverage);
+                            continue;
+                        }
                         let mulled = coverage.madd(p2, p1);
                         // Note that we are not storing the location winding here but the actual
                         // alpha value as f32, so we reuse the variable as a temporary storage.
@@ -188,6 +477,10 @@ fn render_impl<S: Simd>(
                         let area = location_winding[x];
                         let im1 = area.madd(p1, p1).floor();
                         let coverage = p2.madd(im1, area).abs();
+                        if matches!(mode, CoverageMode::Conservative) {
+                            location_winding[x] = conservative(coverage);
+                            continue;
+                        }
                         let mulled = p3.madd(coverage, p1);
                         // TODO: It is possible that, unlike for `NonZero`, we don't need the `min`
                         // here.
@@ -201,7 +494,7 @@ fn render_impl<S: Simd>(
 
             let mut u8_vals = f32_to_u8(s.combine_f32x8(p1, p2));
 
-            if let Some(aliasing_threshold) = aliasing_threshold {
+            if let CoverageMode::Aliased(aliasing_threshold) = mode {
                 u8_vals = s.select_u8x16(
                     u8_vals.simd_ge(u8x16::splat(s, aliasing_threshold)),
                     u8x16::splat(s, 255),
@@ -226,12 +519,12 @@ fn render_impl<S: Simd>(
             );
             strip_buf.push(strip);
 
-            let is_sentinel = tile_idx == tiles.len() as usize;
+            let is_sentinel = tile_idx == tile_slice.len();
             if !prev_tile.same_row(&tile) {
                 // Emit a final strip in the row if there is non-zero winding for the sparse fill,
                 // or unconditionally if we've reached the sentinel tile to end the path (the
                 // `alpha_idx` field is used for width calculations).
-                if winding_delta != 0 || is_sentinel {
+                if winding_delta != 0 || (is_sentinel && terminate) {
                     strip_buf.push(Strip::new(
                         u16::MAX,
                         prev_tile.y * Tile::HEIGHT,
@@ -265,6 +558,19 @@ fn render_impl<S: Simd>(
         }
         prev_tile = tile;
 
+        // Geometry left of the viewport covers the whole row to its right.
+        if tile.is_left_winding() {
+            let left_winding = tiles.left_winding(tile.y);
+            let coverage: f32x4<_> = left_winding.coverage.simd_into(s);
+            accumulated_winding += coverage;
+            #[expect(clippy::needless_range_loop, reason = "dimension clarity")]
+            for x in 0..Tile::WIDTH as usize {
+                location_winding[x] += coverage;
+            }
+            winding_delta += left_winding.winding;
+            continue;
+        }
+
         // TODO: horizontal geometry has no impact on winding. This branch will be removed when
         // horizontal geometry is culled at the tile-generation stage.
         if p0_y == p1_y {
@@ -311,53 +617,11 @@ fn render_impl<S: Simd>(
             (p1_y, p1_x, p0_y, p0_x)
         };
 
-        let (line_left_x, line_left_y, line_right_x) = if p0_x < p1_x {
-            (p0_x, p0_y, p1_x)
-        } else {
-            (p1_x, p1_y, p0_x)
-        };
-
         let y_slope = (line_bottom_y - line_top_y) / (line_bottom_x - line_top_x);
         let x_slope = 1. / y_slope;
 
         winding_delta += sign as i32 * i32::from(tile.winding());
 
-        // TODO: this should be removed when out-of-viewport tiles are culled at the
-        // tile-generation stage. That requires calculating and forwarding winding to strip
-        // generation.
-        if tile.x == 0 && line_left_x < 0. {
-            let (ymin, ymax) = if line.p0.x == line.p1.x {
-                (line_top_y, line_bottom_y)
-            } else {
-                let line_viewport_left_y = (line_top_y - line_top_x * y_slope)
-                    .max(line_top_y)
-                    .min(line_bottom_y);
-
-                (
-                    f32::min(line_left_y, line_viewport_left_y),
-                    f32::max(line_left_y, line_viewport_left_y),
-                )
-            };
-
-            let ymin: f32x4<_> = ymin.simd_into(s);
-            let ymax: f32x4<_> = ymax.simd_into(s);
-
-            let px_top_y: f32x4<_> = [0.0, 1.0, 2.0, 3.0].simd_into(s);
-            let px_bottom_y = 1.0 + px_top_y;
-            let ymin = px_top_y.max(ymin);
-            let ymax = px_bottom_y.min(ymax);
-            let h = (ymax - ymin).max(0.0);
-            accumulated_winding = h.madd(sign, accumulated_winding);
-            for x_idx in 0..Tile::WIDTH {
-                location_winding[x_idx as usize] = h.madd(sign, location_winding[x_idx as usize]);
-            }
-
-            if line_right_x < 0. {
-                // Early exit, as no part of the line is inside the tile.
-                continue;
-            }
-        }
-
         let line_top_y = f32x4::splat(s, line_top_y);
         let line_bottom_y = f32x4::splat(s, line_bottom_y);
 
@@ -430,4 +694,175 @@ fn render_impl<S: Simd>(
 
         accumulated_winding += acc;
     }
+
+    drop_opaque_tiles(strip_buf, alpha_buf, strips_start);
+}
+
+/// Remove fully opaque tiles from the ends of strips whose adjacent gap is filled anyway.
+///
+/// Covering a fully opaque tile with a sparse fill instead of an alpha mask gives the same
+/// result, but reduces the size of the alpha buffer and allows the coarse rasterizer to emit
+/// cheaper fill commands, e.g. for the edges of pixel-aligned rectangles. Strips are only
+/// shrunk by whole tiles, and at least one tile is kept unless the gaps on both sides of a
+/// strip are filled, in which case the strip is removed altogether.
+///
+/// Only the strips starting at `strips_start` are processed. Their alphas must be stored
+/// contiguously at the end of `alpha_buf`.
+fn drop_opaque_tiles(strip_buf: &mut Vec<Strip>, alpha_buf: &mut Vec<u8>, strips_start: usize) {
+    const TILE_ALPHAS: usize = Tile::WIDTH as usize * Tile::HEIGHT as usize;
+
+    let Some(first_strip) = strip_buf.get(strips_start) else {
+        return;
+    };
+    let mut write_alpha = first_strip.alpha_idx() as usize;
+    let mut write_strip = strips_start;
+
+    for read_strip in strips_start..strip_buf.len() {
+        let mut strip = strip_buf[read_strip];
+        let next = strip_buf.get(read_strip + 1).copied();
+        let alpha_start = strip.alpha_idx() as usize;
+        let alpha_end = next.map_or(alpha_buf.len(), |next| next.alpha_idx() as usize);
+
+        let (mut first, mut last) = (0, (alpha_end - alpha_start) / TILE_ALPHAS);
+        if !strip.is_sentinel() {
+            let is_opaque = |tile: usize| {
+                alpha_buf[alpha_start + tile * TILE_ALPHAS..][..TILE_ALPHAS]
+                    .iter()
+                    .all(|alpha| *alpha == 255)
+            };
+            let fill_before = strip.fill_gap()
+                && write_strip > strips_start
+                && strip_buf[write_strip - 1].strip_y() == strip.strip_y();
+            let fill_after =
+                next.is_some_and(|next| next.fill_gap() && next.strip_y() == strip.strip_y());
+
+            if fill_before {
+                while first < last && is_opaque(first) {
+                    first += 1;
+                }
+            }
+            if fill_after {
+                while last > first && is_opaque(last - 1) {
+                    last -= 1;
+                }
+            }
+            if first == last {
+                if fill_before && fill_after {
+                    // The gap before this strip is merged with the one after it.
+                    continue;
+                } else if fill_before {
+                    first -= 1;
+                } else {
+                    last += 1;
+                }
+            }
+            strip.x += first as u16 * Tile::WIDTH;
+        }
+
+        let src = alpha_start + first * TILE_ALPHAS..alpha_start + last * TILE_ALPHAS;
+        if src.start != write_alpha {
+            alpha_buf.copy_within(src.clone(), write_alpha);
+        }
+        strip.set_alpha_idx(write_alpha as u32);
+        write_alpha += src.len();
+        strip_buf[write_strip] = strip;
+        write_strip += 1;
+    }
+
+    strip_buf.truncate(write_strip);
+    alpha_buf.truncate(write_alpha);
+}
+
+#[cfg(all(test, feature = "multithreading"))]
+mod tests {
+    use super::{render, render_partitioned};
+    use crate::flatten::{FlattenCtx, fill};
+    use crate::kurbo::{Affine, BezPath, Circle, Shape};
+    use crate::peniko::Fill;
+    use crate::tile::Tiles;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use fearless_simd::Level;
+
+    const SIZE: u16 = 200;
+
+    fn star(center: (f64, f64), radius: f64, points: usize) -> BezPath {
+        let mut path = BezPath::new();
+        for i in 0..points {
+            // Connect every second outer point, so the star is self-intersecting.
+            let angle = (i * 2) as f64 * core::f64::consts::TAU / points as f64;
+            let point = (
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            );
+            if i == 0 {
+                path.move_to(point);
+            } else {
+                path.line_to(point);
+            }
+        }
+        path.close_path();
+        path
+    }
+
+    fn assert_par_matches_serial(path: &BezPath, fill_rule: Fill, aliasing_threshold: Option<u8>) {
+        let level = Level::try_detect().unwrap_or(Level::fallback());
+        let mut lines = Vec::new();
+        fill(
+            level,
+            path,
+            Affine::IDENTITY,
+            &mut lines,
+            &mut FlattenCtx::default(),
+        );
+        let mut tiles = Tiles::new(level);
+        tiles.make_tiles_analytic_aa(&lines, SIZE, SIZE);
+        tiles.sort_tiles();
+
+        // Start with non-empty buffers, like when appending to the buffers of a scene.
+        let mut serial_strips = Vec::new();
+        let mut serial_alphas = vec![7; 32];
+        render(
+            level,
+            &tiles,
+            &mut serial_strips,
+            &mut serial_alphas,
+            fill_rule,
+            aliasing_threshold,
+            &lines,
+        );
+
+        for num_partitions in [2, 3, 7, 16, 1000] {
+            let mut strips = Vec::new();
+            let mut alphas = vec![7; 32];
+            render_partitioned(
+                level,
+                &tiles,
+                &mut strips,
+                &mut alphas,
+                fill_rule,
+                aliasing_threshold,
+                &lines,
+                num_partitions,
+            );
+            assert_eq!(strips, serial_strips, "{num_partitions} partitions");
+            assert_eq!(alphas, serial_alphas, "{num_partitions} partitions");
+        }
+    }
+
+    #[test]
+    fn par_matches_serial() {
+        let circle = Circle::new((100.0, 100.0), 90.0).to_path(0.1);
+        let centered_star = star((100.0, 100.0), 95.0, 7);
+        // Extends far left of and below the viewport, so rows carry winding from culled geometry
+        // and the last row of tiles is fully covered.
+        let offscreen = star((-50.0, 150.0), 180.0, 5);
+
+        for path in [&circle, &centered_star, &offscreen] {
+            for fill_rule in [Fill::NonZero, Fill::EvenOdd] {
+                assert_par_matches_serial(path, fill_rule, None);
+            }
+        }
+        assert_par_matches_serial(&centered_star, Fill::NonZero, Some(128));
+    }
 }
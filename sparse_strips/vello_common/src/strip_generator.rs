@@ -5,13 +5,15 @@
 
 use crate::clip::{PathDataRef, intersect};
 use crate::fearless_simd::Level;
-use crate::flatten::{FlattenCtx, Line};
-use crate::kurbo::{Affine, PathEl, Stroke};
+use crate::flatten::{FlattenCtx, Line, StrokeCache};
+use crate::kurbo::{Affine, PathEl, Rect, Shape, Stroke};
 use crate::peniko::Fill;
 use crate::strip::Strip;
-use crate::tile::Tiles;
+use crate::tile::{Tile, Tiles};
+use crate::util::preserves_axis_alignment;
 use crate::{flatten, strip};
 use alloc::vec::Vec;
+use log::warn;
 use peniko::kurbo::StrokeCtx;
 
 /// A storage for storing strip-related data.
@@ -21,6 +23,12 @@ pub struct StripStorage {
     pub strips: Vec<Strip>,
     /// The alphas in the storage.
     pub alphas: Vec<u8>,
+    /// Whether geometry was dropped since the storage was last cleared, because its alphas
+    /// would have exceeded [`Strip::MAX_ALPHA_IDX`].
+    ///
+    /// Renderers should check this before rendering the strips, since they are missing parts
+    /// of the scene in that case.
+    pub alphas_exhausted: bool,
     generation_mode: GenerationMode,
 }
 
@@ -36,9 +44,24 @@ pub enum GenerationMode {
 
 impl StripStorage {
     /// Reset the storage.
+    ///
+    /// The allocated capacity is retained.
     pub fn clear(&mut self) {
         self.strips.clear();
         self.alphas.clear();
+        self.alphas_exhausted = false;
+    }
+
+    /// Reserve capacity for at least `strips` more strips and `alphas` more alphas.
+    pub fn reserve(&mut self, strips: usize, alphas: usize) {
+        self.strips.reserve(strips);
+        self.alphas.reserve(alphas);
+    }
+
+    /// Release the capacity that is not used by the current strips and alphas.
+    pub fn shrink_to_fit(&mut self) {
+        self.strips.shrink_to_fit();
+        self.alphas.shrink_to_fit();
     }
 
     /// Set the generation mode of the storage.
@@ -51,13 +74,108 @@ impl StripStorage {
         self.strips.is_empty() && self.alphas.is_empty()
     }
 
+    /// Whether `additional` alphas can be added to the storage without the alpha indices
+    /// exceeding [`Strip::MAX_ALPHA_IDX`].
+    pub fn has_alpha_capacity(&self, additional: usize) -> bool {
+        self.alphas.len().saturating_add(additional) <= Strip::MAX_ALPHA_IDX as usize
+    }
+
+    /// Append `alphas` to the alpha buffer, returning the index of the first appended alpha.
+    ///
+    /// Returns `None` and sets [`StripStorage::alphas_exhausted`] without appending anything
+    /// if the alphas don't fit, see [`StripStorage::has_alpha_capacity`].
+    pub fn extend_alphas(&mut self, alphas: &[u8]) -> Option<u32> {
+        if !self.has_alpha_capacity(alphas.len()) {
+            warn!(
+                "The alpha buffer is full, ignoring {} alphas.",
+                alphas.len()
+            );
+            self.alphas_exhausted = true;
+            return None;
+        }
+
+        let offset = self.alphas.len() as u32;
+        self.alphas.extend_from_slice(alphas);
+        Some(offset)
+    }
+
     /// Extend the current strip storage with the data from another storage.
     pub fn extend(&mut self, other: &Self) {
         self.strips.extend(&other.strips);
         self.alphas.extend(&other.alphas);
+        self.alphas_exhausted |= other.alphas_exhausted;
     }
 }
 
+/// The capacities to reserve for the buffers used to generate strips.
+///
+/// The fields correspond to the buffers tracked by the allocation statistics of a
+/// [`StripGenerator`]. See [`StripGenerator::reserve`] and [`StripStorage::reserve`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BufferCapacities {
+    /// The number of flattened lines of the most complex path.
+    pub line_buf: usize,
+    /// The number of tiles of the most complex path.
+    pub tiles: usize,
+    /// The number of strips.
+    pub strip_buf: usize,
+    /// The number of alpha bytes.
+    pub alphas: usize,
+}
+
+/// How often the buffers of a [`StripGenerator`] had to grow since it was last reset.
+///
+/// Each count is the number of times generating a path increased the capacity of the
+/// buffer, i.e. reallocated it. Once the buffers have grown to fit the paths of a frame,
+/// generating a frame of similar complexity after [`StripGenerator::reset`] shouldn't
+/// allocate anymore, so all counts should stay zero.
+///
+/// This is only tracked in debug builds (`debug_assertions`).
+#[cfg(debug_assertions)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Growths of the buffer of flattened lines.
+    pub line_buf: u32,
+    /// Growths of the strip buffers.
+    pub strip_buf: u32,
+    /// Growths of the alpha buffers.
+    pub alphas: u32,
+    /// Growths of the tile buffer.
+    pub tiles: u32,
+}
+
+/// How much work a [`StripGenerator`] did since it was last reset.
+///
+/// This is useful for understanding why a scene is slow to render, for example because a
+/// path with many segments covers a large number of tiles.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TileStats {
+    /// The number of tiles generated from the flattened paths.
+    pub tiles: u32,
+    /// The number of strips produced.
+    pub strips: u32,
+    /// The number of alpha bytes produced.
+    pub alpha_bytes: u32,
+    /// The number of wide tiles that received draw commands.
+    ///
+    /// The strip generator doesn't know about wide tiles, so this is always zero in the stats
+    /// returned by [`StripGenerator::tile_stats`]. It is filled in by renderers that do.
+    pub wide_tiles: u32,
+    /// The number of strokes that had to be expanded, i.e. that weren't found in the stroke
+    /// cache.
+    pub stroke_expansions: u32,
+}
+
+/// The capacities of the buffers tracked by [`AllocStats`].
+#[cfg(debug_assertions)]
+#[derive(Clone, Copy)]
+struct Capacities {
+    line_buf: usize,
+    strip_buf: [usize; 2],
+    alphas: [usize; 2],
+    tiles: usize,
+}
+
 /// An object for easily generating strips for a filled/stroked path.
 #[derive(Debug)]
 pub struct StripGenerator {
@@ -65,10 +183,14 @@ pub struct StripGenerator {
     line_buf: Vec<Line>,
     flatten_ctx: FlattenCtx,
     stroke_ctx: StrokeCtx,
+    stroke_cache: StrokeCache,
     temp_storage: StripStorage,
     tiles: Tiles,
     width: u16,
     height: u16,
+    tile_stats: TileStats,
+    #[cfg(debug_assertions)]
+    alloc_stats: AllocStats,
 }
 
 impl StripGenerator {
@@ -80,12 +202,47 @@ impl StripGenerator {
             tiles: Tiles::new(level),
             flatten_ctx: FlattenCtx::default(),
             stroke_ctx: StrokeCtx::default(),
+            stroke_cache: StrokeCache::default(),
             temp_storage: StripStorage::default(),
             width,
             height,
+            tile_stats: TileStats::default(),
+            #[cfg(debug_assertions)]
+            alloc_stats: AllocStats::default(),
         }
     }
 
+    /// Set the SIMD level used for generating strips.
+    pub fn set_level(&mut self, level: Level) {
+        self.level = level;
+        self.tiles.set_level(level);
+    }
+
+    /// Get the SIMD level used for generating strips.
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Reserve capacity in the intermediate buffers of the generator.
+    ///
+    /// The strips and alphas are reserved in the buffer used for intersecting paths with clip
+    /// paths; the [`StripStorage`] that the strips are generated into has to be reserved
+    /// separately.
+    pub fn reserve(&mut self, capacities: BufferCapacities) {
+        let rows = usize::from(self.height.div_ceil(Tile::HEIGHT));
+        self.line_buf.reserve(capacities.line_buf);
+        self.tiles.reserve(capacities.tiles, rows);
+        self.temp_storage
+            .reserve(capacities.strip_buf, capacities.alphas);
+    }
+
+    /// Set the tolerance used for flattening curves, in device space (pixels).
+    ///
+    /// See [`FlattenCtx::set_tolerance`].
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        self.flatten_ctx.set_tolerance(tolerance);
+    }
+
     /// Generate the strips for a filled path.
     pub fn generate_filled_path(
         &mut self,
@@ -96,6 +253,9 @@ impl StripGenerator {
         strip_storage: &mut StripStorage,
         clip_path: Option<PathDataRef<'_>>,
     ) {
+        #[cfg(debug_assertions)]
+        let capacities = self.capacities(strip_storage);
+
         flatten::fill(
             self.level,
             path,
@@ -105,9 +265,16 @@ impl StripGenerator {
         );
 
         self.generate_with_clip(aliasing_threshold, strip_storage, fill_rule, clip_path);
+
+        #[cfg(debug_assertions)]
+        self.record_growth(capacities, strip_storage);
     }
 
     /// Generate the strips for a stroked path.
+    ///
+    /// The expanded stroke is cached, so stroking the same path with the same style again,
+    /// for example at a different position, only needs to flatten and place it. See
+    /// [`StrokeCache`] for when the cached stroke can be reused.
     pub fn generate_stroked_path(
         &mut self,
         path: impl IntoIterator<Item = PathEl>,
@@ -117,7 +284,10 @@ impl StripGenerator {
         strip_storage: &mut StripStorage,
         clip_path: Option<PathDataRef<'_>>,
     ) {
-        flatten::stroke(
+        #[cfg(debug_assertions)]
+        let capacities = self.capacities(strip_storage);
+
+        flatten::stroke_cached(
             self.level,
             path,
             stroke,
@@ -125,70 +295,255 @@ impl StripGenerator {
             &mut self.line_buf,
             &mut self.flatten_ctx,
             &mut self.stroke_ctx,
+            &mut self.stroke_cache,
         );
         self.generate_with_clip(aliasing_threshold, strip_storage, Fill::NonZero, clip_path);
+
+        #[cfg(debug_assertions)]
+        self.record_growth(capacities, strip_storage);
     }
 
-    fn generate_with_clip(
+    /// Generate the strips for a filled rectangle.
+    ///
+    /// If `transform` keeps the rectangle axis-aligned, i.e. it only scales and translates it
+    /// or rotates it by a multiple of 90 degrees, the strips are computed directly from the
+    /// rectangle with [`strip::render_rect`], which is much cheaper than flattening and tiling
+    /// its outline. Otherwise, this is the same as [`StripGenerator::generate_filled_path`].
+    pub fn generate_filled_rect(
         &mut self,
+        rect: &Rect,
+        transform: Affine,
         aliasing_threshold: Option<u8>,
         strip_storage: &mut StripStorage,
-        fill_rule: Fill,
         clip_path: Option<PathDataRef<'_>>,
     ) {
-        if strip_storage.generation_mode == GenerationMode::Replace {
-            strip_storage.strips.clear();
+        if !preserves_axis_alignment(&transform) {
+            self.generate_filled_path(
+                rect.path_elements(0.1),
+                Fill::NonZero,
+                transform,
+                aliasing_threshold,
+                strip_storage,
+                clip_path,
+            );
+            return;
         }
 
+        #[cfg(debug_assertions)]
+        let capacities = self.capacities(strip_storage);
+
+        // A rectangle has at most two partially covered rows of tiles, all other rows only
+        // store the alphas of the tiles with its left and right edge.
+        let tile_columns = usize::from(self.width.div_ceil(Tile::WIDTH));
+        let tile_rows = usize::from(self.height.div_ceil(Tile::HEIGHT));
+        let max_alphas = 2 * (tile_columns + tile_rows) * usize::from(Tile::WIDTH * Tile::HEIGHT);
+
+        let rect = transform.transform_rect_bbox(*rect);
+        let (width, height) = (self.width, self.height);
+        render_with_clip(
+            self.level,
+            &mut self.temp_storage,
+            &mut self.tile_stats,
+            max_alphas,
+            strip_storage,
+            clip_path,
+            |strip_buf, alpha_buf| {
+                strip::render_rect(
+                    rect,
+                    width,
+                    height,
+                    strip_buf,
+                    alpha_buf,
+                    aliasing_threshold,
+                );
+            },
+        );
+
+        #[cfg(debug_assertions)]
+        self.record_growth(capacities, strip_storage);
+    }
+
+    fn generate_with_clip(
+        &mut self,
+        aliasing_threshold: Option<u8>,
+        strip_storage: &mut StripStorage,
+        fill_rule: Fill,
+        clip_path: Option<PathDataRef<'_>>,
+    ) {
         self.tiles
             .make_tiles_analytic_aa(&self.line_buf, self.width, self.height);
         self.tiles.sort_tiles();
+        self.tile_stats.tiles = self.tile_stats.tiles.saturating_add(self.tiles.len());
 
-        if let Some(clip_path) = clip_path {
-            self.temp_storage.clear();
-
-            strip::render(
-                self.level,
-                &self.tiles,
-                &mut self.temp_storage.strips,
-                &mut self.temp_storage.alphas,
-                fill_rule,
-                aliasing_threshold,
-                &self.line_buf,
-            );
-            let path_data = PathDataRef {
-                strips: &self.temp_storage.strips,
-                alphas: &self.temp_storage.alphas,
-            };
-
-            intersect(self.level, clip_path, path_data, strip_storage);
-        } else {
-            strip::render(
-                self.level,
-                &self.tiles,
-                &mut strip_storage.strips,
-                &mut strip_storage.alphas,
-                fill_rule,
-                aliasing_threshold,
-                &self.line_buf,
-            );
-        }
+        // Each tile location produces at most one column of alphas.
+        let max_alphas =
+            (self.tiles.len() as usize).saturating_mul(usize::from(Tile::WIDTH * Tile::HEIGHT));
+        let (level, tiles, line_buf) = (self.level, &self.tiles, &self.line_buf);
+        render_with_clip(
+            level,
+            &mut self.temp_storage,
+            &mut self.tile_stats,
+            max_alphas,
+            strip_storage,
+            clip_path,
+            |strip_buf, alpha_buf| {
+                strip::render(
+                    level,
+                    tiles,
+                    strip_buf,
+                    alpha_buf,
+                    fill_rule,
+                    aliasing_threshold,
+                    line_buf,
+                );
+            },
+        );
     }
 
     /// Reset the strip generator.
+    ///
+    /// The allocated capacity of all intermediate buffers is retained. This also resets the
+    /// [tile statistics](Self::tile_stats) and, in debug builds, the
+    /// [allocation statistics](Self::alloc_stats). Expanded strokes that weren't used during
+    /// the last few frames are evicted from the stroke cache.
     pub fn reset(&mut self) {
         self.line_buf.clear();
         self.tiles.reset();
         self.temp_storage.clear();
+        self.stroke_cache.maintain();
+        self.tile_stats = TileStats::default();
+        #[cfg(debug_assertions)]
+        {
+            self.alloc_stats = AllocStats::default();
+        }
+    }
+
+    /// Return how many tiles, strips and alphas were generated since the last
+    /// [`reset`](Self::reset).
+    ///
+    /// Strips and alphas are counted after intersecting them with the clip path, if any.
+    pub fn tile_stats(&self) -> TileStats {
+        TileStats {
+            stroke_expansions: self.stroke_cache.expansions(),
+            ..self.tile_stats
+        }
+    }
+
+    /// Return how often the buffers used for generating strips had to grow since the last
+    /// [`reset`](Self::reset).
+    ///
+    /// The strip and alpha buffers include both the internal buffers and the [`StripStorage`]s
+    /// passed to the generator.
+    #[cfg(debug_assertions)]
+    pub fn alloc_stats(&self) -> AllocStats {
+        self.alloc_stats
+    }
+
+    #[cfg(debug_assertions)]
+    fn capacities(&self, strip_storage: &StripStorage) -> Capacities {
+        Capacities {
+            line_buf: self.line_buf.capacity(),
+            strip_buf: [
+                strip_storage.strips.capacity(),
+                self.temp_storage.strips.capacity(),
+            ],
+            alphas: [
+                strip_storage.alphas.capacity(),
+                self.temp_storage.alphas.capacity(),
+            ],
+            tiles: self.tiles.capacity(),
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    fn record_growth(&mut self, before: Capacities, strip_storage: &StripStorage) {
+        let after = self.capacities(strip_storage);
+        let grown = |before: usize, after: usize| u32::from(after > before);
+        let stats = &mut self.alloc_stats;
+        stats.line_buf += grown(before.line_buf, after.line_buf);
+        stats.tiles += grown(before.tiles, after.tiles);
+        for i in 0..2 {
+            stats.strip_buf += grown(before.strip_buf[i], after.strip_buf[i]);
+            stats.alphas += grown(before.alphas[i], after.alphas[i]);
+        }
+    }
+
+    /// Release the capacity that is not used by the intermediate buffers.
+    pub fn shrink_to_fit(&mut self) {
+        self.line_buf.shrink_to_fit();
+        self.tiles.shrink_to_fit();
+        self.temp_storage.shrink_to_fit();
+        self.stroke_ctx = StrokeCtx::default();
+        self.stroke_cache = StrokeCache::default();
     }
 }
 
+/// Render strips with `render` into `strip_storage`, intersecting them with `clip_path` if
+/// there is one.
+///
+/// `max_alphas` is an upper bound of the number of alphas that `render` produces. If they
+/// could exceed the capacity of the alpha buffer, nothing is rendered and
+/// [`StripStorage::alphas_exhausted`] is set.
+fn render_with_clip(
+    level: Level,
+    temp_storage: &mut StripStorage,
+    tile_stats: &mut TileStats,
+    max_alphas: usize,
+    strip_storage: &mut StripStorage,
+    clip_path: Option<PathDataRef<'_>>,
+    render: impl FnOnce(&mut Vec<Strip>, &mut Vec<u8>),
+) {
+    if strip_storage.generation_mode == GenerationMode::Replace {
+        strip_storage.strips.clear();
+    }
+
+    // Intersecting with a clip path can additionally produce columns where the path is filled
+    // but the clip path isn't.
+    let max_alphas =
+        max_alphas.saturating_add(clip_path.map_or(0, |clip_path| clip_path.alphas.len()));
+    if !strip_storage.has_alpha_capacity(max_alphas) {
+        warn!("The alpha buffer is full, ignoring path.");
+        strip_storage.alphas_exhausted = true;
+        return;
+    }
+
+    let (strips_before, alphas_before) = (strip_storage.strips.len(), strip_storage.alphas.len());
+
+    if let Some(clip_path) = clip_path {
+        temp_storage.clear();
+        render(&mut temp_storage.strips, &mut temp_storage.alphas);
+        let path_data = PathDataRef {
+            strips: &temp_storage.strips,
+            alphas: &temp_storage.alphas,
+        };
+
+        intersect(level, clip_path, path_data, strip_storage);
+    } else {
+        render(&mut strip_storage.strips, &mut strip_storage.alphas);
+    }
+
+    let produced = |before: usize, after: usize| u32::try_from(after - before).unwrap_or(u32::MAX);
+    tile_stats.strips = tile_stats
+        .strips
+        .saturating_add(produced(strips_before, strip_storage.strips.len()));
+    tile_stats.alpha_bytes = tile_stats
+        .alpha_bytes
+        .saturating_add(produced(alphas_before, strip_storage.alphas.len()));
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::clip::PathDataRef;
     use crate::fearless_simd::Level;
-    use crate::kurbo::{Affine, Rect, Shape};
+    use crate::flatten::{self, FlattenCtx};
+    use crate::kurbo::{Affine, BezPath, Circle, Rect, Shape, Stroke};
     use crate::peniko::Fill;
-    use crate::strip_generator::{StripGenerator, StripStorage};
+    use crate::strip::{self, Strip};
+    use crate::strip_generator::{StripGenerator, StripStorage, TileStats};
+    use crate::tile::{Tile, Tiles};
+    use alloc::format;
+    use alloc::vec;
+    use alloc::vec::Vec;
 
     #[test]
     fn reset() {
@@ -207,11 +562,477 @@ mod tests {
 
         assert!(!generator.line_buf.is_empty());
         assert!(!storage.is_empty());
+        assert_ne!(generator.tile_stats(), TileStats::default());
 
         generator.reset();
         storage.clear();
 
         assert!(generator.line_buf.is_empty());
         assert!(storage.is_empty());
+        assert_eq!(generator.tile_stats(), TileStats::default());
+    }
+
+    #[test]
+    fn stroke_expansion_is_cached_across_translations() {
+        let mut generator = StripGenerator::new(100, 100, Level::fallback());
+        let path = Circle::new((20.0, 20.0), 15.0).to_path(0.1);
+        let stroke = Stroke::new(3.0);
+        let stroke_at = |generator: &mut StripGenerator, transform: Affine| {
+            let mut storage = StripStorage::default();
+            generator.generate_stroked_path(&path, &stroke, transform, None, &mut storage, None);
+            storage
+        };
+
+        for i in 0..50 {
+            let transform = Affine::translate((f64::from(i) * 1.3, f64::from(i) * 0.7));
+            let cached = stroke_at(&mut generator, transform);
+            let uncached = stroke_at(
+                &mut StripGenerator::new(100, 100, Level::fallback()),
+                transform,
+            );
+            assert_eq!(cached, uncached, "translation {i}");
+        }
+        assert_eq!(generator.tile_stats().stroke_expansions, 1);
+
+        // The expansion tolerance depends on the scale, so scaling the path expands it again.
+        stroke_at(&mut generator, Affine::scale(2.0));
+        assert_eq!(generator.tile_stats().stroke_expansions, 2);
+
+        // Rotating it doesn't, and neither does stroking it in the next frame.
+        generator.reset();
+        stroke_at(&mut generator, Affine::rotate(0.5));
+        stroke_at(&mut generator, Affine::translate((10.0, 10.0)));
+        assert_eq!(generator.tile_stats().stroke_expansions, 0);
+
+        // A different style does.
+        let mut storage = StripStorage::default();
+        generator.generate_stroked_path(
+            &path,
+            &Stroke::new(4.0),
+            Affine::IDENTITY,
+            None,
+            &mut storage,
+            None,
+        );
+        assert_eq!(generator.tile_stats().stroke_expansions, 1);
+    }
+
+    #[test]
+    fn alpha_capacity_boundary() {
+        let max = Strip::MAX_ALPHA_IDX as usize;
+        let mut storage = StripStorage::default();
+        assert!(storage.has_alpha_capacity(max));
+        assert!(!storage.has_alpha_capacity(max + 1));
+
+        assert_eq!(storage.extend_alphas(&[0; 16]), Some(0));
+        assert_eq!(storage.extend_alphas(&[0; 16]), Some(16));
+        assert!(storage.has_alpha_capacity(max - 32));
+        assert!(!storage.has_alpha_capacity(max - 31));
+        assert!(!storage.has_alpha_capacity(usize::MAX));
+    }
+
+    // The zeroed alpha buffer is allocated lazily, so this doesn't actually use 2 GiB.
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn full_alpha_buffer_drops_geometry() {
+        let mut generator = StripGenerator::new(100, 100, Level::fallback());
+        let mut storage = StripStorage {
+            alphas: vec![0; Strip::MAX_ALPHA_IDX as usize - 8],
+            ..StripStorage::default()
+        };
+
+        // A rectangle with fractional edges needs more than 8 alphas.
+        generator.generate_filled_rect(
+            &Rect::new(10.5, 10.5, 50.5, 50.5),
+            Affine::IDENTITY,
+            None,
+            &mut storage,
+            None,
+        );
+        assert!(storage.strips.is_empty());
+        assert_eq!(storage.alphas.len(), Strip::MAX_ALPHA_IDX as usize - 8);
+        assert!(storage.alphas_exhausted);
+
+        storage.alphas_exhausted = false;
+        assert_eq!(storage.extend_alphas(&[0; 16]), None);
+        assert!(storage.alphas_exhausted);
+
+        storage.clear();
+        assert!(!storage.alphas_exhausted);
+    }
+
+    #[test]
+    fn strip_at_max_alpha_idx() {
+        let mut strip = Strip::new(0, 0, Strip::MAX_ALPHA_IDX, true);
+        assert_eq!(strip.alpha_idx(), Strip::MAX_ALPHA_IDX);
+        assert!(strip.fill_gap());
+
+        strip.set_fill_gap(false);
+        assert_eq!(strip.alpha_idx(), Strip::MAX_ALPHA_IDX);
+
+        strip.set_alpha_idx(Strip::MAX_ALPHA_IDX - 1);
+        assert_eq!(strip.alpha_idx(), Strip::MAX_ALPHA_IDX - 1);
+        assert!(!strip.fill_gap());
+    }
+
+    /// Expand the strips in `storage` into a dense coverage mask.
+    fn coverage_mask(storage: &StripStorage, width: u16, height: u16) -> Vec<u8> {
+        let mut mask = vec![0; usize::from(width) * usize::from(height)];
+        let mut set = |x: u16, y: u16, alpha: u8| {
+            if x < width && y < height {
+                mask[usize::from(y) * usize::from(width) + usize::from(x)] = alpha;
+            }
+        };
+
+        let strips = &storage.strips;
+        let mut prev_end = None;
+        for (i, strip) in strips.iter().enumerate() {
+            let alpha_idx = strip.alpha_idx() as usize;
+            let alpha_end = strips
+                .get(i + 1)
+                .map_or(storage.alphas.len(), |next| next.alpha_idx() as usize);
+            if strip.fill_gap()
+                && let Some((end, y)) = prev_end
+                && y == strip.y
+            {
+                for x in end..strip.x.min(width) {
+                    for row in 0..Tile::HEIGHT {
+                        set(x, y + row, 255);
+                    }
+                }
+            }
+            if strip.is_sentinel() {
+                prev_end = None;
+                continue;
+            }
+
+            let alphas = &storage.alphas[alpha_idx..alpha_end];
+            for (column, alphas) in alphas.chunks(usize::from(Tile::HEIGHT)).enumerate() {
+                for (row, alpha) in alphas.iter().enumerate() {
+                    set(strip.x + column as u16, strip.y + row as u16, *alpha);
+                }
+            }
+            let columns = (alphas.len() / usize::from(Tile::HEIGHT)) as u16;
+            prev_end = Some((strip.x + columns, strip.y));
+        }
+
+        mask
+    }
+
+    #[test]
+    fn filled_rect_matches_filled_path() {
+        const WIDTH: u16 = 101;
+        const HEIGHT: u16 = 61;
+
+        let rects = [
+            Rect::new(8.0, 4.0, 40.0, 20.0),
+            Rect::new(10.0, 3.0, 90.0, 57.0),
+            Rect::new(10.25, 3.5, 89.75, 56.0625),
+            Rect::new(20.5, 20.5, 21.0, 21.25),
+            Rect::new(30.25, 10.0, 30.75, 50.0),
+            Rect::new(-20.5, -10.25, 50.5, 30.75),
+            Rect::new(60.5, 40.0, 150.0, 80.5),
+            Rect::new(-10.0, -10.0, 200.0, 100.0),
+            Rect::new(40.0, 20.0, 12.5, 4.5),
+            Rect::new(30.0, 10.0, 30.0, 50.0),
+            Rect::new(110.0, 10.0, 120.0, 20.0),
+        ];
+        let transforms = [
+            Affine::IDENTITY,
+            Affine::translate((0.5, -0.25)),
+            Affine::scale_non_uniform(1.5, 0.5),
+            Affine::scale_non_uniform(-1.0, 1.0).then_translate((101.0, 0.0).into()),
+            Affine::new([0.0, 1.0, 1.0, 0.0, 0.0, 0.0]),
+        ];
+        let clip = Circle::new((50.0, 30.0), 25.0).to_path(0.1);
+
+        let mut generator = StripGenerator::new(WIDTH, HEIGHT, Level::fallback());
+        let mut clip_storage = StripStorage::default();
+        generator.generate_filled_path(
+            &clip,
+            Fill::NonZero,
+            Affine::IDENTITY,
+            None,
+            &mut clip_storage,
+            None,
+        );
+        let clip_path = PathDataRef {
+            strips: &clip_storage.strips,
+            alphas: &clip_storage.alphas,
+        };
+
+        for rect in rects {
+            for transform in transforms {
+                for aliasing_threshold in [None, Some(128)] {
+                    for clip_path in [None, Some(clip_path)] {
+                        let mut expected = StripStorage::default();
+                        generator.generate_filled_path(
+                            rect.path_elements(0.1),
+                            Fill::NonZero,
+                            transform,
+                            aliasing_threshold,
+                            &mut expected,
+                            clip_path,
+                        );
+                        let mut storage = StripStorage::default();
+                        generator.generate_filled_rect(
+                            &rect,
+                            transform,
+                            aliasing_threshold,
+                            &mut storage,
+                            clip_path,
+                        );
+
+                        assert_eq!(
+                            coverage_mask(&storage, WIDTH, HEIGHT),
+                            coverage_mask(&expected, WIDTH, HEIGHT),
+                            "{rect:?} with {transform:?}, {aliasing_threshold:?}, clipped: {}",
+                            clip_path.is_some()
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn filled_rect_falls_back_for_rotations() {
+        let mut generator = StripGenerator::new(100, 100, Level::fallback());
+        let rect = Rect::new(20.0, 20.0, 80.0, 60.0);
+        let transform = Affine::rotate_about(0.3, (50.0, 50.0));
+
+        let mut expected = StripStorage::default();
+        generator.generate_filled_path(
+            rect.path_elements(0.1),
+            Fill::NonZero,
+            transform,
+            None,
+            &mut expected,
+            None,
+        );
+        let mut storage = StripStorage::default();
+        generator.generate_filled_rect(&rect, transform, None, &mut storage, None);
+
+        assert_eq!(storage, expected);
+    }
+
+    #[test]
+    fn filled_rect_with_fractional_edges_is_close_to_filled_path() {
+        let mut generator = StripGenerator::new(100, 100, Level::fallback());
+        let rect = Rect::new(10.3, 7.1, 77.7, 91.9);
+
+        let mut expected = StripStorage::default();
+        generator.generate_filled_path(
+            rect.path_elements(0.1),
+            Fill::NonZero,
+            Affine::IDENTITY,
+            None,
+            &mut expected,
+            None,
+        );
+        let mut storage = StripStorage::default();
+        generator.generate_filled_rect(&rect, Affine::IDENTITY, None, &mut storage, None);
+
+        let max_diff = coverage_mask(&storage, 100, 100)
+            .iter()
+            .zip(coverage_mask(&expected, 100, 100))
+            .map(|(a, b)| a.abs_diff(b))
+            .max();
+        assert!(max_diff <= Some(1), "alphas differ by {max_diff:?}");
+    }
+
+    #[test]
+    fn degenerate_paths_fill_nothing() {
+        const WIDTH: u16 = 40;
+        const HEIGHT: u16 = 20;
+
+        let point = |x: f64, y: f64| {
+            let mut path = BezPath::new();
+            path.move_to((x, y));
+            path
+        };
+        let line = |x: f64, y: f64| {
+            let mut path = point(x, y);
+            path.line_to((x, y));
+            path
+        };
+        let move_tos = || {
+            let mut path = BezPath::new();
+            path.move_to((1.0, 1.0));
+            path.move_to((10.5, 7.25));
+            path.move_to((30.0, 12.0));
+            path
+        };
+        let coincident = |x: f64, y: f64| {
+            let mut path = line(x, y);
+            path.quad_to((x, y), (x, y));
+            path.curve_to((x, y), (x, y), (x, y));
+            path.close_path();
+            path
+        };
+        let collinear = || {
+            let mut path = BezPath::new();
+            path.move_to((2.0, 3.0));
+            path.line_to((30.5, 17.25));
+            path.line_to((16.25, 10.125));
+            path.close_path();
+            path
+        };
+
+        // Paths that collapse to points don't generate any strips at all, while the other paths
+        // may generate strips as long as they don't cover any pixels.
+        let points = [
+            BezPath::new(),
+            point(10.5, 7.25),
+            point(8.0, 4.0),
+            line(10.5, 7.25),
+            line(8.0, 4.0),
+            line(0.0, 0.0),
+            line(40.0, 20.0),
+            move_tos(),
+            coincident(10.5, 7.25),
+            coincident(8.0, 4.0),
+            coincident(-5.0, 30.0),
+        ];
+        let zero_area = [
+            collinear(),
+            Rect::new(10.0, 4.0, 30.0, 4.0).to_path(0.1),
+            Rect::new(12.0, 2.0, 12.0, 18.0).to_path(0.1),
+        ];
+        let paths = points
+            .iter()
+            .map(|path| (path, true))
+            .chain(zero_area.iter().map(|path| (path, false)));
+        let transforms = [
+            Affine::IDENTITY,
+            Affine::translate((0.5, -0.25)),
+            Affine::scale(0.0),
+        ];
+        let clip = Circle::new((20.0, 10.0), 8.0).to_path(0.1);
+
+        let mut generator = StripGenerator::new(WIDTH, HEIGHT, Level::fallback());
+        let mut clip_storage = StripStorage::default();
+        generator.generate_filled_path(
+            &clip,
+            Fill::NonZero,
+            Affine::IDENTITY,
+            None,
+            &mut clip_storage,
+            None,
+        );
+        let clip_path = PathDataRef {
+            strips: &clip_storage.strips,
+            alphas: &clip_storage.alphas,
+        };
+
+        for (path, is_point) in paths {
+            for transform in transforms {
+                for fill_rule in [Fill::NonZero, Fill::EvenOdd] {
+                    for clip_path in [None, Some(clip_path)] {
+                        let mut storage = StripStorage::default();
+                        generator.generate_filled_path(
+                            path,
+                            fill_rule,
+                            transform,
+                            None,
+                            &mut storage,
+                            clip_path,
+                        );
+
+                        let message = format!(
+                            "{path:?} with {transform:?}, {fill_rule:?}, clipped: {}",
+                            clip_path.is_some()
+                        );
+                        if is_point || transform == Affine::scale(0.0) {
+                            assert!(storage.is_empty(), "{message}");
+                        }
+                        assert!(
+                            coverage_mask(&storage, WIDTH, HEIGHT)
+                                .iter()
+                                .all(|alpha| *alpha == 0),
+                            "{message}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn conservative_rendering_includes_thin_features() {
+        const SIZE: u16 = 64;
+        // A diagonal sliver so thin that no pixel is covered enough for a non-zero alpha.
+        let (start, end, width) = ((4.0, 6.0), (58.0, 50.0), 0.001);
+        let mut path = BezPath::new();
+        path.move_to(start);
+        path.line_to(end);
+        path.line_to((end.0 + width, end.1));
+        path.line_to((start.0 + width, start.1));
+        path.close_path();
+
+        let render = |conservative: bool| {
+            let mut lines = Vec::new();
+            flatten::fill(
+                Level::fallback(),
+                &path,
+                Affine::IDENTITY,
+                &mut lines,
+                &mut FlattenCtx::default(),
+            );
+            let mut tiles = Tiles::new(Level::fallback());
+            tiles.make_tiles_analytic_aa(&lines, SIZE, SIZE);
+            tiles.sort_tiles();
+
+            let mut storage = StripStorage::default();
+            if conservative {
+                strip::render_conservative(
+                    Level::fallback(),
+                    &tiles,
+                    &mut storage.strips,
+                    &mut storage.alphas,
+                    Fill::NonZero,
+                    &lines,
+                );
+            } else {
+                strip::render(
+                    Level::fallback(),
+                    &tiles,
+                    &mut storage.strips,
+                    &mut storage.alphas,
+                    Fill::NonZero,
+                    Some(1),
+                    &lines,
+                );
+            }
+            coverage_mask(&storage, SIZE, SIZE)
+        };
+        // Whether the line from `start` to `end`, shifted by `dx`, passes through a pixel.
+        let touches = |x: u16, y: u16, dx: f64| {
+            let (x, y) = (f64::from(x), f64::from(y));
+            let range = |min: f64, start: f64, end: f64| {
+                let (t0, t1) = (
+                    (min - start) / (end - start),
+                    (min + 1.0 - start) / (end - start),
+                );
+                (t0.min(t1), t0.max(t1))
+            };
+            let (tx0, tx1) = range(x, start.0 + dx, end.0 + dx);
+            let (ty0, ty1) = range(y, start.1, end.1);
+            tx0.max(ty0).max(0.0) < tx1.min(ty1).min(1.0)
+        };
+
+        let mask = render(true);
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let alpha = mask[usize::from(y) * usize::from(SIZE) + usize::from(x)];
+                if touches(x, y, 0.0) {
+                    assert_eq!(alpha, 255, "touched pixel ({x}, {y}) isn't set");
+                } else if !touches(x, y, width) {
+                    assert_eq!(alpha, 0, "untouched pixel ({x}, {y}) is set");
+                }
+            }
+        }
+        // Thresholding the coverage instead drops the sliver entirely.
+        assert!(render(false).iter().all(|alpha| *alpha == 0));
     }
 }
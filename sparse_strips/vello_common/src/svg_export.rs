@@ -0,0 +1,518 @@
+// Copyright 2025 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Export of recorded scenes to SVG.
+//!
+//! Reconstructing vector geometry from sparse strips or wide tile commands is lossy, so
+//! the exporter instead operates on the high-level operations captured in a [`Recording`].
+//! A scene is exported by recording its drawing operations and converting the recording:
+//!
+//! ```
+//! use vello_common::color::palette::css::RED;
+//! use vello_common::glyph::GlyphCaches;
+//! use vello_common::kurbo::{Affine, Rect, Shape};
+//! use vello_common::recording::{Recorder, Recording};
+//! use vello_common::svg_export;
+//!
+//! let clip_rect = Rect::new(10.0, 10.0, 60.0, 40.0);
+//! let rect = Rect::new(0.0, 0.0, 50.0, 50.0);
+//!
+//! let mut recording = Recording::new();
+//! let mut ctx = Recorder::new(&mut recording, Affine::IDENTITY, GlyphCaches::default());
+//! ctx.push_clip_layer(&clip_rect.to_path(0.1));
+//! ctx.set_paint(RED);
+//! ctx.fill_rect(&rect);
+//! ctx.pop_layer();
+//!
+//! let svg = svg_export::to_svg(&recording, 100, 100);
+//! assert!(svg.contains("<clipPath"));
+//! ```
+//!
+//! With a renderer, the recording is usually obtained through [`Recordable::record`](crate::recording::Recordable::record) instead.
+//!
+//! The recording is interpreted starting from the default render state (a black paint, the
+//! non-zero fill rule, a stroke of width 1 with bevel joins and butt caps, and no paint
+//! transform), as the state of the
+//! rendering context at the time of recording isn't captured.
+//!
+//! # Limitations
+//!
+//! The following parts of a recording are not exported:
+//! - Image paints and sweep gradients, which have no SVG counterpart. Geometry using them is
+//!   skipped.
+//! - Masks, filters and compose operators of layers.
+//! - The interpolation color space of gradients, they are always interpolated in sRGB.
+//! - Separate start and end caps of strokes, the start cap is used for both ends.
+
+use crate::color::{AlphaColor, Srgb};
+use crate::kurbo::{Affine, BezPath, Cap, Join, PathEl, Shape, Stroke};
+use crate::paint::PaintType;
+use crate::peniko::{BlendMode, Extend, Fill, Gradient, GradientKind, Mix};
+use crate::recording::{PushLayerCommand, Recording, RenderCommand};
+use alloc::string::String;
+use core::fmt::Write;
+
+/// Convert a recording into an SVG document with the given size in pixels.
+pub fn to_svg(recording: &Recording, width: u16, height: u16) -> String {
+    let mut writer = SvgWriter::new();
+    let _ = write!(
+        writer.out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    writer.out.push('\n');
+
+    for command in recording.commands() {
+        writer.command(command);
+    }
+    while writer.depth > 0 {
+        writer.pop_layer();
+    }
+
+    writer.out.push_str("</svg>\n");
+    writer.out
+}
+
+/// The render state tracked while interpreting the recorded commands.
+#[derive(Debug)]
+struct SvgWriter {
+    out: String,
+    transform: Affine,
+    paint: PaintType,
+    paint_transform: Affine,
+    fill_rule: Fill,
+    stroke: Stroke,
+    /// The number of currently open groups.
+    depth: usize,
+    /// The number of ids handed out to gradients and clip paths so far.
+    next_id: usize,
+}
+
+impl SvgWriter {
+    fn new() -> Self {
+        Self {
+            out: String::new(),
+            transform: Affine::IDENTITY,
+            paint: AlphaColor::<Srgb>::BLACK.into(),
+            paint_transform: Affine::IDENTITY,
+            fill_rule: Fill::NonZero,
+            stroke: Stroke {
+                width: 1.0,
+                join: Join::Bevel,
+                start_cap: Cap::Butt,
+                end_cap: Cap::Butt,
+                ..Default::default()
+            },
+            depth: 0,
+            next_id: 0,
+        }
+    }
+
+    fn command(&mut self, command: &RenderCommand) {
+        match command {
+            RenderCommand::FillPath(path) => self.draw(path, self.transform, false),
+            RenderCommand::StrokePath(path) => self.draw(path, self.transform, true),
+            RenderCommand::FillRect(rect) => self.draw(&rect.to_path(0.1), self.transform, false),
+            RenderCommand::StrokeRect(rect) => self.draw(&rect.to_path(0.1), self.transform, true),
+            RenderCommand::SetTransform(transform) => self.transform = *transform,
+            RenderCommand::SetFillRule(fill_rule) => self.fill_rule = *fill_rule,
+            RenderCommand::SetStroke(stroke) => self.stroke = stroke.clone(),
+            RenderCommand::PushLayer(layer) => self.push_layer(layer),
+            RenderCommand::PushClip(clip_path) => self.push_layer(&PushLayerCommand {
+                clip_path: Some(clip_path.clone()),
+                blend_mode: None,
+                opacity: None,
+                mask: None,
+                filter: None,
+            }),
+            RenderCommand::PopLayer => {
+                if self.depth > 0 {
+                    self.pop_layer();
+                }
+            }
+            RenderCommand::SetPaint(paint) => self.paint = paint.clone(),
+            RenderCommand::SetPaintTransform(paint_transform) => {
+                self.paint_transform = *paint_transform;
+            }
+            RenderCommand::ResetPaintTransform => self.paint_transform = Affine::IDENTITY,
+            RenderCommand::SetFilterEffect(_) | RenderCommand::ResetFilterEffect => {}
+            #[cfg(feature = "text")]
+            RenderCommand::FillOutlineGlyph((path, transform)) => {
+                self.draw(path, *transform, false);
+            }
+            #[cfg(feature = "text")]
+            RenderCommand::StrokeOutlineGlyph((path, transform)) => {
+                self.draw(path, *transform, true);
+            }
+            #[cfg(feature = "text")]
+            RenderCommand::StrokeOutlineGlyphWith((path, transform, stroke, paint)) => {
+                let old_stroke = core::mem::replace(&mut self.stroke, stroke.clone());
+                let old_paint = core::mem::replace(&mut self.paint, paint.clone());
+                self.draw(path, *transform, true);
+                self.paint = old_paint;
+                self.stroke = old_stroke;
+            }
+        }
+    }
+
+    /// Emit a `path` element, drawn with the current paint in the coordinate system
+    /// given by `transform`.
+    fn draw(&mut self, path: &BezPath, transform: Affine, stroke: bool) {
+        let paint = match self.paint.clone() {
+            PaintType::Solid(color) => Paint::Solid(color),
+            PaintType::Gradient(gradient) => {
+                if matches!(gradient.kind, GradientKind::Sweep(_)) {
+                    return;
+                }
+                // The paint is positioned relative to the current transform, not to the
+                // coordinate system of the path (which differs for glyphs).
+                let gradient_transform =
+                    transform.inverse() * self.transform * self.paint_transform;
+                Paint::Gradient(self.gradient(&gradient, gradient_transform))
+            }
+            PaintType::Image(_) => return,
+        };
+
+        self.indent();
+        self.out.push_str("<path d=\"");
+        write_path(&mut self.out, path);
+        self.out.push('"');
+        write_transform(&mut self.out, "transform", transform);
+
+        let (target, opacity) = if stroke {
+            self.out.push_str(r#" fill="none""#);
+            ("stroke", "stroke-opacity")
+        } else {
+            ("fill", "fill-opacity")
+        };
+        match paint {
+            Paint::Solid(color) => write_color(&mut self.out, target, opacity, color),
+            Paint::Gradient(id) => {
+                let _ = write!(self.out, r#" {target}="url(#gradient{id})""#);
+            }
+        }
+
+        if stroke {
+            self.write_stroke();
+        } else if self.fill_rule == Fill::EvenOdd {
+            self.out.push_str(r#" fill-rule="evenodd""#);
+        }
+        self.out.push_str("/>\n");
+    }
+
+    fn write_stroke(&mut self) {
+        let stroke = &self.stroke;
+        let _ = write!(self.out, r#" stroke-width="{}""#, stroke.width);
+        let join = match stroke.join {
+            Join::Bevel => "bevel",
+            Join::Miter => "miter",
+            Join::Round => "round",
+        };
+        let _ = write!(self.out, r#" stroke-linejoin="{join}""#);
+        if stroke.join == Join::Miter {
+            let _ = write!(self.out, r#" stroke-miterlimit="{}""#, stroke.miter_limit);
+        }
+        let cap = match stroke.start_cap {
+            Cap::Butt => "butt",
+            Cap::Square => "square",
+            Cap::Round => "round",
+        };
+        let _ = write!(self.out, r#" stroke-linecap="{cap}""#);
+        if !stroke.dash_pattern.is_empty() {
+            self.out.push_str(r#" stroke-dasharray=""#);
+            for (i, dash) in stroke.dash_pattern.iter().enumerate() {
+                if i > 0 {
+                    self.out.push(' ');
+                }
+                let _ = write!(self.out, "{dash}");
+            }
+            let _ = write!(self.out, r#"" stroke-dashoffset="{}""#, stroke.dash_offset);
+        }
+    }
+
+    /// Emit the definition of a gradient and return its id.
+    fn gradient(&mut self, gradient: &Gradient, transform: Affine) -> usize {
+        let id = self.next_id();
+
+        self.indent();
+        self.out.push_str("<defs>");
+        match gradient.kind {
+            GradientKind::Linear(line) => {
+                let _ = write!(
+                    self.out,
+                    r#"<linearGradient id="gradient{id}" x1="{}" y1="{}" x2="{}" y2="{}""#,
+                    line.start.x, line.start.y, line.end.x, line.end.y
+                );
+            }
+            GradientKind::Radial(radial) => {
+                let _ = write!(
+                    self.out,
+                    r#"<radialGradient id="gradient{id}" fx="{}" fy="{}" fr="{}" cx="{}" cy="{}" r="{}""#,
+                    radial.start_center.x,
+                    radial.start_center.y,
+                    radial.start_radius,
+                    radial.end_center.x,
+                    radial.end_center.y,
+                    radial.end_radius
+                );
+            }
+            GradientKind::Sweep(_) => unreachable!("sweep gradients are not exported"),
+        }
+        self.out.push_str(r#" gradientUnits="userSpaceOnUse""#);
+        write_transform(&mut self.out, "gradientTransform", transform);
+        let spread = match gradient.extend {
+            Extend::Pad => "pad",
+            Extend::Repeat => "repeat",
+            Extend::Reflect => "reflect",
+        };
+        let _ = write!(self.out, r#" spreadMethod="{spread}">"#);
+
+        for stop in gradient.stops.iter() {
+            let _ = write!(self.out, r#"<stop offset="{}""#, stop.offset);
+            write_color(
+                &mut self.out,
+                "stop-color",
+                "stop-opacity",
+                stop.color.to_alpha_color(),
+            );
+            self.out.push_str("/>");
+        }
+
+        match gradient.kind {
+            GradientKind::Linear(_) => self.out.push_str("</linearGradient>"),
+            _ => self.out.push_str("</radialGradient>"),
+        }
+        self.out.push_str("</defs>\n");
+
+        id
+    }
+
+    /// Open a group for a layer, clipped to the layer's clip path.
+    fn push_layer(&mut self, layer: &PushLayerCommand) {
+        let clip_id = layer.clip_path.as_ref().map(|clip_path| {
+            let id = self.next_id();
+            self.indent();
+            let _ = write!(
+                self.out,
+                r#"<defs><clipPath id="clip{id}" clipPathUnits="userSpaceOnUse"><path d=""#
+            );
+            write_path(&mut self.out, clip_path);
+            self.out.push('"');
+            write_transform(&mut self.out, "transform", self.transform);
+            if self.fill_rule == Fill::EvenOdd {
+                self.out.push_str(r#" clip-rule="evenodd""#);
+            }
+            self.out.push_str("/></clipPath></defs>\n");
+            id
+        });
+
+        self.indent();
+        self.out.push_str("<g");
+        if let Some(id) = clip_id {
+            let _ = write!(self.out, r#" clip-path="url(#clip{id})""#);
+        }
+        if let Some(opacity) = layer.opacity
+            && opacity != 1.0
+        {
+            let _ = write!(self.out, r#" opacity="{opacity}""#);
+        }
+        if let Some(blend_mode) = layer.blend_mode
+            && blend_mode.mix != Mix::Normal
+        {
+            let _ = write!(
+                self.out,
+                r#" style="mix-blend-mode: {}""#,
+                mix_blend_mode(blend_mode)
+            );
+        }
+        self.out.push_str(">\n");
+        self.depth += 1;
+    }
+
+    fn pop_layer(&mut self) {
+        self.depth -= 1;
+        self.indent();
+        self.out.push_str("</g>\n");
+    }
+
+    fn indent(&mut self) {
+        for _ in 0..=self.depth {
+            self.out.push_str("  ");
+        }
+    }
+
+    fn next_id(&mut self) -> usize {
+        self.next_id += 1;
+        self.next_id - 1
+    }
+}
+
+/// The paint of an emitted element.
+enum Paint {
+    Solid(AlphaColor<Srgb>),
+    /// A reference to the gradient with the given id.
+    Gradient(usize),
+}
+
+fn write_path(out: &mut String, path: &BezPath) {
+    for (i, el) in path.elements().iter().enumerate() {
+        if i > 0 {
+            out.push(' ');
+        }
+        let _ = match *el {
+            PathEl::MoveTo(p) => write!(out, "M{},{}", p.x, p.y),
+            PathEl::LineTo(p) => write!(out, "L{},{}", p.x, p.y),
+            PathEl::QuadTo(p1, p2) => write!(out, "Q{},{} {},{}", p1.x, p1.y, p2.x, p2.y),
+            PathEl::CurveTo(p1, p2, p3) => write!(
+                out,
+                "C{},{} {},{} {},{}",
+                p1.x, p1.y, p2.x, p2.y, p3.x, p3.y
+            ),
+            PathEl::ClosePath => write!(out, "Z"),
+        };
+    }
+}
+
+fn write_transform(out: &mut String, name: &str, transform: Affine) {
+    if transform != Affine::IDENTITY {
+        let [a, b, c, d, e, f] = transform.as_coeffs();
+        let _ = write!(out, r#" {name}="matrix({a} {b} {c} {d} {e} {f})""#);
+    }
+}
+
+fn write_color(out: &mut String, name: &str, opacity_name: &str, color: AlphaColor<Srgb>) {
+    let rgba = color.to_rgba8();
+    let _ = write!(
+        out,
+        r##" {name}="#{:02x}{:02x}{:02x}""##,
+        rgba.r, rgba.g, rgba.b
+    );
+    let alpha = color.components[3];
+    if alpha != 1.0 {
+        let _ = write!(out, r#" {opacity_name}="{alpha}""#);
+    }
+}
+
+fn mix_blend_mode(blend_mode: BlendMode) -> &'static str {
+    match blend_mode.mix {
+        Mix::Normal => "normal",
+        Mix::Multiply => "multiply",
+        Mix::Screen => "screen",
+        Mix::Overlay => "overlay",
+        Mix::Darken => "darken",
+        Mix::Lighten => "lighten",
+        Mix::ColorDodge => "color-dodge",
+        Mix::ColorBurn => "color-burn",
+        Mix::HardLight => "hard-light",
+        Mix::SoftLight => "soft-light",
+        Mix::Difference => "difference",
+        Mix::Exclusion => "exclusion",
+        Mix::Hue => "hue",
+        Mix::Saturation => "saturation",
+        Mix::Color => "color",
+        Mix::Luminosity => "luminosity",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_svg;
+    use crate::color::palette::css::{BLUE, RED};
+    use crate::kurbo::{Affine, BezPath, Rect, Shape};
+    use crate::peniko::Fill;
+    use crate::recording::{Recorder, Recording};
+    use alloc::vec::Vec;
+    use roxmltree::{Document, Node};
+
+    fn record(f: impl FnOnce(&mut Recorder<'_>)) -> Recording {
+        let mut recording = Recording::new();
+        let mut recorder = Recorder::new(
+            &mut recording,
+            Affine::IDENTITY,
+            #[cfg(feature = "text")]
+            crate::glyph::GlyphCaches::default(),
+        );
+        f(&mut recorder);
+        recording
+    }
+
+    fn elements<'a, 'input>(
+        node: Node<'a, 'input>,
+        name: &'a str,
+    ) -> impl Iterator<Item = Node<'a, 'input>> {
+        node.descendants().filter(move |n| n.has_tag_name(name))
+    }
+
+    fn path_of(node: Node<'_, '_>) -> BezPath {
+        BezPath::from_svg(node.attribute("d").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn two_rect_clip_round_trip() {
+        let clip = Rect::new(10.0, 10.0, 60.0, 40.0);
+        let inner = Rect::new(0.0, 0.0, 50.0, 50.0);
+        let outer = Rect::new(30.0, 20.0, 90.0, 70.5);
+
+        let recording = record(|ctx| {
+            ctx.set_fill_rule(Fill::EvenOdd);
+            ctx.push_clip_layer(&clip.to_path(0.1));
+            ctx.set_paint(RED);
+            ctx.fill_rect(&inner);
+            ctx.pop_layer();
+            ctx.set_transform(Affine::translate((5.0, 0.0)));
+            ctx.set_paint(BLUE.with_alpha(0.5));
+            ctx.fill_rect(&outer);
+        });
+        let svg = to_svg(&recording, 100, 80);
+
+        let doc = Document::parse(&svg).unwrap();
+        let root = doc.root_element();
+        assert_eq!(root.attribute("width"), Some("100"));
+        assert_eq!(root.attribute("height"), Some("80"));
+
+        let clip_paths = elements(root, "clipPath").collect::<Vec<_>>();
+        assert_eq!(clip_paths.len(), 1);
+        let clip_path = elements(clip_paths[0], "path").next().unwrap();
+        assert_eq!(path_of(clip_path), clip.to_path(0.1));
+        assert_eq!(clip_path.attribute("clip-rule"), Some("evenodd"));
+
+        let group = elements(root, "g").next().unwrap();
+        let clip_id = clip_paths[0].attribute("id").unwrap();
+        assert_eq!(
+            group.attribute("clip-path"),
+            Some(alloc::format!("url(#{clip_id})").as_str())
+        );
+        let clipped = group
+            .children()
+            .filter(Node::is_element)
+            .collect::<Vec<_>>();
+        assert_eq!(clipped.len(), 1);
+        assert_eq!(path_of(clipped[0]), inner.to_path(0.1));
+        assert_eq!(clipped[0].attribute("fill"), Some("#ff0000"));
+        assert_eq!(clipped[0].attribute("fill-rule"), Some("evenodd"));
+
+        let unclipped = root
+            .children()
+            .filter(|n| n.has_tag_name("path"))
+            .collect::<Vec<_>>();
+        assert_eq!(unclipped.len(), 1);
+        assert_eq!(path_of(unclipped[0]), outer.to_path(0.1));
+        assert_eq!(unclipped[0].attribute("fill"), Some("#0000ff"));
+        assert_eq!(unclipped[0].attribute("fill-opacity"), Some("0.5"));
+        assert_eq!(
+            unclipped[0].attribute("transform"),
+            Some("matrix(1 0 0 1 5 0)")
+        );
+    }
+
+    #[test]
+    fn unbalanced_layers_are_closed() {
+        let recording = record(|ctx| {
+            ctx.push_clip_layer(&Rect::new(0.0, 0.0, 10.0, 10.0).to_path(0.1));
+            ctx.fill_rect(&Rect::new(0.0, 0.0, 20.0, 20.0));
+        });
+        let svg = to_svg(&recording, 20, 20);
+
+        let doc = Document::parse(&svg).unwrap();
+        assert_eq!(elements(doc.root_element(), "g").count(), 1);
+    }
+}
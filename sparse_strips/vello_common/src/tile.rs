@@ -98,6 +98,9 @@ impl Tile {
     pub const WIDTH: u16 = 4;
 
     /// The height of a tile in pixels.
+    ///
+    /// Strip rendering stores a column of a tile in a single 128-bit SIMD vector, so this
+    /// can't currently be changed on its own.
     pub const HEIGHT: u16 = 4;
 
     /// Create a new tile.
@@ -136,6 +139,24 @@ impl Tile {
         }
     }
 
+    /// Create a tile carrying the winding of geometry culled to the left of the viewport
+    /// in row `y`. See [`Tiles::left_winding`].
+    ///
+    /// Analytic AA tiles never set the intersection bits, so the left bit is used to mark
+    /// this tile. It does not refer to a line.
+    #[inline]
+    pub const fn new_left_winding(y: u16) -> Self {
+        Self::new(0, y, 0, L)
+    }
+
+    /// Whether this tile carries the winding of geometry culled to the left of the viewport.
+    ///
+    /// Only meaningful for tiles generated by [`Tiles::make_tiles_analytic_aa`].
+    #[inline]
+    pub const fn is_left_winding(&self) -> bool {
+        self.x == 0 && self.intersects_left()
+    }
+
     /// Check whether two tiles are at the same location.
     #[inline]
     pub const fn same_loc(&self, other: &Self) -> bool {
@@ -236,10 +257,31 @@ impl PartialOrd for Tile {
 
 impl Eq for Tile {}
 
+/// The winding contributed to a row of tiles by geometry left of the viewport.
+///
+/// Such geometry covers every pixel of the row to its right, so its contribution does not
+/// depend on the x coordinate.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LeftWinding {
+    /// The signed, fractional coverage accumulated in each pixel row of the tile row.
+    pub coverage: [f32; Tile::HEIGHT as usize],
+    /// The coarse winding delta of lines crossing the top edge of the tile row.
+    pub winding: i32,
+}
+
+/// The number of tiles from which on the tiles are bucketed by row before sorting.
+const BUCKET_SORT_THRESHOLD: usize = 512;
+
 /// Handles the tiling of paths.
 #[derive(Clone, Debug)]
 pub struct Tiles {
     tile_buf: Vec<Tile>,
+    /// The winding of culled geometry left of the viewport, per tile row. Empty if there
+    /// is no such geometry.
+    left_winding: Vec<LeftWinding>,
+    /// Scratch buffers for sorting the tiles.
+    sort_buf: Vec<Tile>,
+    row_ends: Vec<u32>,
     level: Level,
     sorted: bool,
 }
@@ -249,32 +291,145 @@ impl Tiles {
     pub fn new(level: Level) -> Self {
         Self {
             tile_buf: vec![],
+            left_winding: vec![],
+            sort_buf: vec![],
+            row_ends: vec![],
             level,
             sorted: false,
         }
     }
 
+    /// Set the SIMD level used for sorting the tiles.
+    pub fn set_level(&mut self, level: Level) {
+        self.level = level;
+    }
+
     /// Get the number of tiles in the container.
     pub fn len(&self) -> u32 {
         self.tile_buf.len() as u32
     }
 
+    /// Get the number of tiles the container can hold without reallocating.
+    #[cfg(debug_assertions)]
+    pub(crate) fn capacity(&self) -> usize {
+        self.tile_buf.capacity()
+    }
+
     /// Returns `true` if the container has no tiles.
     pub fn is_empty(&self) -> bool {
         self.tile_buf.is_empty()
     }
 
     /// Reset the tiles' container.
+    ///
+    /// The allocated capacity is retained, so that tiling paths of similar complexity
+    /// doesn't need to allocate again.
     pub fn reset(&mut self) {
         self.tile_buf.clear();
+        self.left_winding.clear();
         self.sorted = false;
     }
 
+    /// Reserve capacity for at least `tiles` more tiles in `rows` rows of tiles.
+    pub fn reserve(&mut self, tiles: usize, rows: usize) {
+        self.tile_buf.reserve(tiles);
+        self.sort_buf.reserve(tiles);
+        self.left_winding.reserve(rows);
+        self.row_ends.reserve(rows + 1);
+    }
+
+    /// Release the capacity that is not used by the current tiles.
+    pub fn shrink_to_fit(&mut self) {
+        self.tile_buf.shrink_to_fit();
+        self.left_winding.shrink_to_fit();
+        self.sort_buf = vec![];
+        self.row_ends = vec![];
+    }
+
     /// Sort the tiles in the container.
+    ///
+    /// Tiles are sorted by row, then by column, then by their packed line index and
+    /// intersection mask, which is the order of [`Tile`]'s [`Ord`] implementation.
     pub fn sort_tiles(&mut self) {
         self.sorted = true;
-        // To enable auto-vectorization.
-        self.level.dispatch(|_| self.tile_buf.sort_unstable());
+
+        if self.tile_buf.len() < BUCKET_SORT_THRESHOLD {
+            // To enable auto-vectorization.
+            self.level.dispatch(|_| self.tile_buf.sort_unstable());
+            return;
+        }
+
+        self.bucket_by_row();
+        let sort_buf = &mut self.sort_buf;
+        let row_ends = &self.row_ends;
+        self.level.dispatch(|_| {
+            let mut start = 0;
+            for &end in row_ends {
+                sort_buf[start..end as usize].sort_unstable();
+                start = end as usize;
+            }
+        });
+        core::mem::swap(&mut self.tile_buf, &mut self.sort_buf);
+    }
+
+    /// Sort the tiles in the container, sorting the rows in parallel.
+    ///
+    /// The resulting order is the same as for [`Tiles::sort_tiles`].
+    #[cfg(feature = "multithreading")]
+    pub fn par_sort_tiles(&mut self) {
+        use rayon::prelude::*;
+
+        if self.tile_buf.len() < BUCKET_SORT_THRESHOLD {
+            self.sort_tiles();
+            return;
+        }
+
+        self.sorted = true;
+        self.bucket_by_row();
+        let level = self.level;
+        self.sort_buf
+            .par_chunk_by_mut(|a, b| a.y == b.y)
+            .for_each(|row| level.dispatch(|_| row.sort_unstable()));
+        core::mem::swap(&mut self.tile_buf, &mut self.sort_buf);
+    }
+
+    /// Distribute the tiles into `sort_buf`, grouped by row in ascending order, and store
+    /// the end of each row in `row_ends`.
+    ///
+    /// Distributing the tiles is a single linear pass, and since a row only contains a
+    /// fraction of all tiles, sorting the rows afterwards is considerably cheaper than
+    /// sorting the whole buffer. Tiles that compare equal are identical, so the result is
+    /// exactly the same as that of sorting the whole buffer at once.
+    fn bucket_by_row(&mut self) {
+        let num_rows = self
+            .tile_buf
+            .iter()
+            .map(|tile| usize::from(tile.y) + 1)
+            .max()
+            .unwrap_or(0);
+
+        // Count the tiles per row. The count of row `y` is stored at index `y + 1`, so that
+        // the prefix sum yields the start of each row.
+        let row_ends = &mut self.row_ends;
+        row_ends.clear();
+        row_ends.resize(num_rows + 1, 0);
+        for tile in &self.tile_buf {
+            row_ends[usize::from(tile.y) + 1] += 1;
+        }
+        for y in 1..=num_rows {
+            row_ends[y] += row_ends[y - 1];
+        }
+
+        // Advancing the start of each row while distributing leaves its end behind.
+        self.sort_buf.clear();
+        self.sort_buf
+            .resize(self.tile_buf.len(), Tile::new(0, 0, 0, 0));
+        for tile in &self.tile_buf {
+            let offset = &mut row_ends[usize::from(tile.y)];
+            self.sort_buf[*offset as usize] = *tile;
+            *offset += 1;
+        }
+        row_ends.truncate(num_rows);
     }
 
     /// Get the tile at a certain index.
@@ -303,14 +458,69 @@ impl Tiles {
         self.tile_buf.iter()
     }
 
+    /// The tiles in sorted order.
+    ///
+    /// Panics if the container hasn't been sorted before.
+    #[inline]
+    pub fn as_slice(&self) -> &[Tile] {
+        assert!(
+            self.sorted,
+            "attempted to call `as_slice` before sorting the tile container."
+        );
+
+        &self.tile_buf
+    }
+
+    /// The winding contributed to tile row `y` by geometry left of the viewport.
+    ///
+    /// Rows with a non-zero contribution are marked by a tile at `x = 0` for which
+    /// [`Tile::is_left_winding`] holds.
+    #[inline]
+    pub fn left_winding(&self, y: u16) -> LeftWinding {
+        self.left_winding
+            .get(usize::from(y))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Accumulate the coverage of the part of `line` lying left of the viewport.
+    ///
+    /// `line` must extend to the left of the viewport.
+    fn add_left_coverage(&mut self, line: Line, height: u16) {
+        let Line { p0, p1 } = line;
+        let (y_start, y_end) = if p0.x <= 0.0 && p1.x <= 0.0 {
+            (p0.y, p1.y)
+        } else {
+            // The y coordinate where the line crosses the left edge of the viewport.
+            let y_cross = p0.y + (p1.y - p0.y) * (-p0.x / (p1.x - p0.x));
+            if p0.x < 0.0 {
+                (p0.y, y_cross)
+            } else {
+                (y_cross, p1.y)
+            }
+        };
+
+        // Lines moving upwards add to the winding, lines moving downwards subtract from it.
+        let sign = if p0.y > p1.y { 1.0 } else { -1.0 };
+        let ymin = y_start.min(y_end).max(0.0);
+        let ymax = y_start.max(y_end).min(f32::from(height));
+
+        // The `as u16` casts are in range due to the clamping above.
+        for px_y in (ymin as u16)..(ymax.ceil() as u16) {
+            let px_top_y = f32::from(px_y);
+            let h = ymax.min(px_top_y + 1.0) - ymin.max(px_top_y);
+            let row = &mut self.left_winding[usize::from(px_y / Tile::HEIGHT)];
+            row.coverage[usize::from(px_y % Tile::HEIGHT)] += sign * h;
+        }
+    }
+
     /// Generates tile commands for Analytic Anti-Aliasing rasterization. Unlike the MSAA path, this
     /// function performs "coarse binning" to simply identify every tile a line segment traverses.
     /// It encodes the line index and winding direction, delegating the precise calculation of pixel
     /// coverage to `strip::render`.
-    //
-    // TODO: Tiles are clamped to the left edge of the viewport, but lines fully to the left of the
-    // viewport are not culled yet. These lines impact winding, and would need forwarding of
-    // winding to the strip generation stage.
+    ///
+    /// Lines, or the parts of them, left of the viewport don't produce tiles. Their winding is
+    /// accumulated per tile row instead, see [`Tiles::left_winding`].
     pub fn make_tiles_analytic_aa(&mut self, lines: &[Line], width: u16, height: u16) {
         self.reset();
 
@@ -367,6 +577,28 @@ impl Tiles {
                 continue;
             }
 
+            // Lines moving upwards add to the winding, lines moving downwards subtract from it.
+            let winding_sign = if p0_y > p1_y { 1 } else { -1 };
+
+            if line_left_x < 0.0 {
+                if self.left_winding.is_empty() {
+                    self.left_winding
+                        .resize(usize::from(tile_rows), LeftWinding::default());
+                }
+                self.add_left_coverage(*line, height);
+
+                // Lines fully left of the viewport only contribute to the winding of the rows
+                // whose top edge they cross.
+                if line_right_x <= 0.0 {
+                    for y_idx in y_top_tiles..y_bottom_tiles {
+                        if f32::from(y_idx) >= line_top_y {
+                            self.left_winding[usize::from(y_idx)].winding += winding_sign;
+                        }
+                    }
+                    continue;
+                }
+            }
+
             // Get tile coordinates for start/end points, use i32 to preserve negative coordinates
             let p0_tile_x = line_top_x.floor() as i32;
             let p0_tile_y = line_top_y.floor() as i32;
@@ -433,6 +665,15 @@ impl Tiles {
                         let row_left_x = f32::min(row_top_x, row_bottom_x).max(line_left_x);
                         let row_right_x = f32::max(row_top_x, row_bottom_x).min(line_right_x);
 
+                        // The part of the line in this row is left of the viewport, so only
+                        // forward its winding.
+                        if row_right_x < 0.0 {
+                            if w_single != 0 {
+                                self.left_winding[usize::from(y_idx)].winding += winding_sign;
+                            }
+                            return;
+                        }
+
                         let x_start = row_left_x as u16;
                         let x_end = (row_right_x as u16).min(tile_columns.saturating_sub(1));
 
@@ -501,6 +742,12 @@ impl Tiles {
                 self.tile_buf.push(tile);
             }
         }
+
+        for (y_idx, row) in self.left_winding.iter().enumerate() {
+            if *row != LeftWinding::default() {
+                self.tile_buf.push(Tile::new_left_winding(y_idx as u16));
+            }
+        }
     }
 
     /// Generates tile commands for MSAA (Multisample Anti-Aliasing) rasterization.
@@ -867,9 +1114,13 @@ impl Tiles {
 mod tests {
     use crate::flatten::{FlattenCtx, Line, Point, fill};
     use crate::kurbo::{Affine, BezPath};
-    use crate::tile::{B, L, R, T, Tile, Tiles, W};
+    use crate::tile::{
+        B, BUCKET_SORT_THRESHOLD, INTERSECTION_MASK, L, LeftWinding, MAX_LINES_PER_PATH, R, T,
+        Tile, Tiles, W,
+    };
+    use alloc::vec;
+    use alloc::vec::Vec;
     use fearless_simd::Level;
-    use std::vec;
 
     const VIEW_DIM: u16 = 100;
     const F_V_DIM: f32 = VIEW_DIM as f32;
@@ -891,6 +1142,13 @@ mod tests {
     }
 
     fn check_analytic_aa_matches(actual: &[Tile], expected: &[Tile]) {
+        // Tiles forwarding the winding of geometry left of the viewport have no MSAA equivalent.
+        let actual: Vec<Tile> = actual
+            .iter()
+            .filter(|tile| !tile.is_left_winding())
+            .copied()
+            .collect();
+
         assert_eq!(
             actual.len(),
             expected.len(),
@@ -967,6 +1225,77 @@ mod tests {
         tiles.assert_tiles_match(&lines, VIEW_DIM, VIEW_DIM, &[]);
     }
 
+    #[test]
+    fn cull_lines_left_of_viewport() {
+        let lines = [Line {
+            p0: Point { x: -20.0, y: 10.0 },
+            p1: Point { x: -5.0, y: 2.0 },
+        }];
+
+        let mut tiles = Tiles::new(Level::try_detect().unwrap_or(Level::fallback()));
+        tiles.make_tiles_analytic_aa(&lines, VIEW_DIM, VIEW_DIM);
+
+        // Only the winding of the line is forwarded, for each row it spans.
+        let expected = [
+            Tile::new_left_winding(0),
+            Tile::new_left_winding(1),
+            Tile::new_left_winding(2),
+        ];
+        assert_eq!(tiles.tile_buf, expected);
+
+        let expected_winding = [
+            LeftWinding {
+                coverage: [0.0, 0.0, 1.0, 1.0],
+                winding: 0,
+            },
+            LeftWinding {
+                coverage: [1.0; 4],
+                winding: 1,
+            },
+            LeftWinding {
+                coverage: [1.0, 1.0, 0.0, 0.0],
+                winding: 1,
+            },
+            LeftWinding::default(),
+        ];
+        for (y, expected) in expected_winding.iter().enumerate() {
+            assert_eq!(tiles.left_winding(y as u16), *expected, "row {y}");
+        }
+    }
+
+    #[test]
+    fn forward_winding_of_line_crossing_left() {
+        let lines = [Line {
+            p0: Point { x: -4.0, y: 1.0 },
+            p1: Point { x: 12.0, y: 9.0 },
+        }];
+
+        let mut tiles = Tiles::new(Level::try_detect().unwrap_or(Level::fallback()));
+        tiles.make_tiles_msaa(&lines, VIEW_DIM, VIEW_DIM);
+        let msaa_tiles = tiles.tile_buf.clone();
+        tiles.make_tiles_analytic_aa(&lines, VIEW_DIM, VIEW_DIM);
+
+        // The part of the line inside the viewport still generates tiles.
+        check_analytic_aa_matches(&tiles.tile_buf, &msaa_tiles);
+
+        // The part left of the viewport, between `y = 1` and `y = 3`, is forwarded.
+        assert_eq!(
+            tiles
+                .tile_buf
+                .iter()
+                .filter(|t| t.is_left_winding())
+                .count(),
+            1
+        );
+        assert_eq!(
+            tiles.left_winding(0),
+            LeftWinding {
+                coverage: [0.0, -1.0, -1.0, 0.0],
+                winding: 0,
+            }
+        );
+    }
+
     #[test]
     fn sloped_line_crossing_top() {
         let lines = [
@@ -1958,6 +2287,85 @@ mod tests {
         check_sorted(&tiles.tile_buf);
     }
 
+    #[test]
+    fn bucket_sort_matches_comparison_sort() {
+        // A simple linear congruential generator, to get reproducible pseudo-random tiles.
+        let mut state = 0x2545_f491_u32;
+        let mut next = |max: u32| {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 8) % max
+        };
+
+        let mut tiles = Tiles::new(Level::fallback());
+        for (len, max_coord, max_line) in [
+            (600, 4, 2),
+            (5000, 300, 10_000),
+            (5000, 1, 1),
+            (20_000, 16_000, MAX_LINES_PER_PATH),
+        ] {
+            tiles.reset();
+            tiles.tile_buf.extend((0..len).map(|_| {
+                Tile::new(
+                    next(max_coord) as u16,
+                    next(max_coord) as u16,
+                    next(max_line),
+                    next(INTERSECTION_MASK + 1),
+                )
+            }));
+            let mut expected = tiles.tile_buf.clone();
+            expected.sort_unstable();
+
+            tiles.sort_tiles();
+            assert_eq!(tiles.tile_buf, expected);
+        }
+    }
+
+    #[test]
+    fn sort_tiles_matches_comparison_sort() {
+        let mut star = BezPath::new();
+        for i in 0..37 {
+            let angle = f64::from(i * 17) * core::f64::consts::TAU / 37.0;
+            let point = (200.0 + 190.0 * angle.cos(), 200.0 + 190.0 * angle.sin());
+            if i == 0 {
+                star.move_to(point);
+            } else {
+                star.line_to(point);
+            }
+        }
+        star.close_path();
+
+        let mut lines = vec![];
+        fill(
+            Level::fallback(),
+            &star,
+            Affine::IDENTITY,
+            &mut lines,
+            &mut FlattenCtx::default(),
+        );
+
+        let sorts: &[fn(&mut Tiles)] = &[
+            Tiles::sort_tiles,
+            #[cfg(feature = "multithreading")]
+            Tiles::par_sort_tiles,
+        ];
+        let mut tiles = Tiles::new(Level::fallback());
+        for sort in sorts {
+            for msaa in [false, true] {
+                if msaa {
+                    tiles.make_tiles_msaa(&lines, 400, 400);
+                } else {
+                    tiles.make_tiles_analytic_aa(&lines, 400, 400);
+                }
+                assert!(tiles.tile_buf.len() > BUCKET_SORT_THRESHOLD);
+
+                let mut expected = tiles.tile_buf.clone();
+                expected.sort_unstable();
+                sort(&mut tiles);
+                assert_eq!(tiles.tile_buf, expected);
+            }
+        }
+    }
+
     fn check_sorted(buf: &[Tile]) {
         for i in 0..buf.len() - 1 {
             let current = buf[i];
@@ -10,6 +10,14 @@ use peniko::kurbo::Affine;
 #[cfg(not(feature = "std"))]
 use peniko::kurbo::common::FloatFuncs as _;
 
+/// Whether `transform` maps axis-aligned rectangles to axis-aligned rectangles, i.e. it only
+/// scales and translates, or rotates by a multiple of 90 degrees.
+#[inline]
+pub fn preserves_axis_alignment(transform: &Affine) -> bool {
+    let [a, b, c, d, _, _] = transform.as_coeffs();
+    (b == 0.0 && c == 0.0) || (a == 0.0 && d == 0.0)
+}
+
 /// Convert f32x16 to u8x16.
 #[inline(always)]
 pub fn f32_to_u8<S: Simd>(val: f32x16<S>) -> u8x16<S> {
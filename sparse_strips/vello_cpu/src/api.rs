@@ -105,6 +105,7 @@ impl PaintScene for CPUScenePainter {
                         push_layer_command.opacity,
                         None,
                         None,
+                        false,
                     );
                 }
                 RenderCommand::PopLayer => self.render_context.pop_layer(),
@@ -228,6 +229,7 @@ impl PaintScene for CPUScenePainter {
             opacity,
             None,
             None,
+            false,
         );
     }
 
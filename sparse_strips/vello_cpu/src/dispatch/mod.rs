@@ -6,7 +6,7 @@ pub(crate) mod multi_threaded;
 pub(crate) mod single_threaded;
 
 use crate::RenderMode;
-use crate::kurbo::{Affine, BezPath, Stroke};
+use crate::kurbo::{Affine, BezPath, Rect, Stroke};
 use crate::peniko::{BlendMode, Fill};
 use core::fmt::Debug;
 use vello_common::coarse::Wide;
@@ -37,6 +37,16 @@ pub(crate) trait Dispatcher: Debug + Send + Sync {
         mask: Option<Mask>,
         encoded_paints: &[EncodedPaint],
     );
+    fn fill_rect(
+        &mut self,
+        rect: &Rect,
+        transform: Affine,
+        paint: Paint,
+        blend_mode: BlendMode,
+        aliasing_threshold: Option<u8>,
+        mask: Option<Mask>,
+        encoded_paints: &[EncodedPaint],
+    );
     fn stroke_path(
         &mut self,
         path: &BezPath,
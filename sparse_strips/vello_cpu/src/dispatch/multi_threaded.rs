@@ -6,7 +6,7 @@ use crate::dispatch::Dispatcher;
 use crate::dispatch::multi_threaded::cost::{COST_THRESHOLD, estimate_render_task_cost};
 use crate::dispatch::multi_threaded::worker::Worker;
 use crate::fine::{Fine, FineKernel};
-use crate::kurbo::{Affine, BezPath, PathEl, Stroke};
+use crate::kurbo::{Affine, BezPath, PathEl, Rect, Stroke};
 use crate::peniko::{BlendMode, Fill};
 use crate::region::Regions;
 use alloc::boxed::Box;
@@ -451,6 +451,26 @@ impl Dispatcher for MultiThreadedDispatcher {
         });
     }
 
+    fn fill_rect(
+        &mut self,
+        rect: &Rect,
+        transform: Affine,
+        paint: Paint,
+        blend_mode: BlendMode,
+        aliasing_threshold: Option<u8>,
+        mask: Option<Mask>,
+        _encoded_paints: &[EncodedPaint],
+    ) {
+        self.register_task(RenderTaskType::FillRect {
+            rect: *rect,
+            transform,
+            paint,
+            blend_mode,
+            aliasing_threshold,
+            mask,
+        });
+    }
+
     fn stroke_path(
         &mut self,
         path: &BezPath,
@@ -787,6 +807,14 @@ pub(crate) enum RenderTaskType {
         aliasing_threshold: Option<u8>,
         mask: Option<Mask>,
     },
+    FillRect {
+        rect: Rect,
+        transform: Affine,
+        paint: Paint,
+        blend_mode: BlendMode,
+        aliasing_threshold: Option<u8>,
+        mask: Option<Mask>,
+    },
     WideCommand {
         strip_buf: Box<[Strip]>,
         thread_idx: u8,
@@ -7,7 +7,7 @@
 pub(crate) const COST_THRESHOLD: f32 = 250.0;
 
 use crate::dispatch::multi_threaded::RenderTaskType;
-use crate::kurbo::{Affine, PathEl, PathSeg, Point, segments};
+use crate::kurbo::{Affine, PathEl, PathSeg, Point, Shape, segments};
 
 /// Try to estimate the cost of the render task.
 pub(crate) fn estimate_render_task_cost(task: &RenderTaskType, paths: &[PathEl]) -> f32 {
@@ -22,6 +22,13 @@ pub(crate) fn estimate_render_task_cost(task: &RenderTaskType, paths: &[PathEl])
             let path = &paths[path_range.start as usize..path_range.end as usize];
             estimate_path_cost(segments(path.iter().copied()), *transform, false)
         }
+        RenderTaskType::FillRect {
+            rect, transform, ..
+        } => {
+            // This overestimates the cost of rectangles that stay axis-aligned, which don't
+            // need to be tiled.
+            estimate_path_cost(rect.path_segments(0.1), *transform, false)
+        }
         RenderTaskType::StrokePath {
             path_range,
             transform,
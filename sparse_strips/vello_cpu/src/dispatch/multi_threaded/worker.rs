@@ -97,6 +97,38 @@ impl Worker {
                         .coarse_tasks
                         .push(coarse_command);
                 }
+                RenderTaskType::FillRect {
+                    rect,
+                    transform,
+                    paint,
+                    blend_mode,
+                    aliasing_threshold,
+                    mask,
+                } => {
+                    let start = self.strip_storage.strips.len() as u32;
+
+                    self.strip_generator.generate_filled_rect(
+                        &rect,
+                        transform,
+                        aliasing_threshold,
+                        &mut self.strip_storage,
+                        path_clip,
+                    );
+                    let end = self.strip_storage.strips.len() as u32;
+
+                    let coarse_command = CoarseTaskType::RenderPath {
+                        thread_id: self.thread_id,
+                        strips: start..end,
+                        blend_mode,
+                        paint,
+                        mask,
+                    };
+
+                    render_task
+                        .allocation_group
+                        .coarse_tasks
+                        .push(coarse_command);
+                }
                 RenderTaskType::StrokePath {
                     path_range,
                     transform,
@@ -3,8 +3,9 @@
 
 use crate::RenderMode;
 use crate::dispatch::Dispatcher;
+use crate::filter::crop_to_region;
 use crate::fine::{Fine, FineKernel};
-use crate::kurbo::{Affine, BezPath, Stroke};
+use crate::kurbo::{Affine, BezPath, Rect, Stroke, Vec2};
 use crate::layer_manager::LayerManager;
 use crate::peniko::{BlendMode, Fill};
 use crate::region::Regions;
@@ -20,6 +21,7 @@ use vello_common::pixmap::Pixmap;
 use vello_common::render_graph::{RenderGraph, RenderNodeKind};
 use vello_common::strip::Strip;
 use vello_common::strip_generator::{StripGenerator, StripStorage};
+use vello_common::tile::Tile;
 
 /// Single-threaded implementation of the rendering dispatcher.
 ///
@@ -183,9 +185,18 @@ impl SingleThreadedDispatcher {
                     wtile_bbox,
                     transform,
                 } => {
-                    // Allocate intermediate buffer for this filtered layer.
-                    let bbox_width = wtile_bbox.width_px();
-                    let bbox_height = wtile_bbox.height_px();
+                    // Allocate intermediate buffer for this filtered layer. Wide tiles at the
+                    // right and bottom edges can extend past the render target, so clamp the
+                    // buffer to the visible area. This way, the filter's edge mode applies at
+                    // the actual boundary of the filtered region instead of at transparent
+                    // padding that will never be visible.
+                    let tile_width = self.wide.tile_width();
+                    let bbox_width = wtile_bbox
+                        .width_px(tile_width)
+                        .min(width.saturating_sub(wtile_bbox.x0().saturating_mul(tile_width)));
+                    let bbox_height = wtile_bbox
+                        .height_px()
+                        .min(height.saturating_sub(wtile_bbox.y0().saturating_mul(Tile::HEIGHT)));
                     let mut pixmap = Pixmap::new(bbox_width, bbox_height);
                     // TODO: Re-use this allocation by adding a .configure() or similar method
                     // to avoid allocating the internal Vec<Region> on every filtered layer.
@@ -217,9 +228,25 @@ impl SingleThreadedDispatcher {
                         fine.pack(region);
                     });
 
+                    // Crop the filter input and output to the bounds of the filter, if any.
+                    let region = filter.device_bounds(transform).map(|bounds| {
+                        let origin = Vec2::new(
+                            f64::from(wtile_bbox.x0()) * f64::from(tile_width),
+                            f64::from(wtile_bbox.y0()) * f64::from(Tile::HEIGHT),
+                        );
+                        bounds - origin
+                    });
+                    if let Some(region) = region {
+                        crop_to_region(&mut pixmap, region);
+                    }
+
                     // Apply the filter effect to the completed layer.
                     fine.filter_layer(&mut pixmap, filter, layer_manager, *transform);
 
+                    if let Some(region) = region {
+                        crop_to_region(&mut pixmap, region);
+                    }
+
                     // Save the filtered pixmap to disk for debugging.
                     // #[cfg(all(debug_assertions, feature = "std", feature = "png"))]
                     // save_filtered_layer_debug(&pixmap, *layer_id);
@@ -532,6 +559,36 @@ impl Dispatcher for SingleThreadedDispatcher {
         );
     }
 
+    fn fill_rect(
+        &mut self,
+        rect: &Rect,
+        transform: Affine,
+        paint: Paint,
+        blend_mode: BlendMode,
+        aliasing_threshold: Option<u8>,
+        mask: Option<Mask>,
+        encoded_paints: &[EncodedPaint],
+    ) {
+        let wide = &mut self.wide;
+
+        self.strip_generator.generate_filled_rect(
+            rect,
+            transform,
+            aliasing_threshold,
+            &mut self.strip_storage,
+            self.clip_context.get(),
+        );
+
+        wide.generate(
+            &self.strip_storage.strips,
+            paint,
+            blend_mode,
+            0,
+            mask,
+            encoded_paints,
+        );
+    }
+
     fn stroke_path(
         &mut self,
         path: &BezPath,
@@ -0,0 +1,193 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! `feColorMatrix` filter primitive implementation.
+//!
+//! Each output channel is a linear combination of the un-premultiplied input channels
+//! plus a constant offset. Results are clamped to `[0, 1]` and premultiplied again.
+//!
+//! @see <https://drafts.fxtf.org/filter-effects/#feColorMatrixElement>
+
+use super::FilterEffect;
+use crate::layer_manager::LayerManager;
+use vello_common::peniko::color::PremulRgba8;
+#[cfg(not(feature = "std"))]
+use vello_common::peniko::kurbo::common::FloatFuncs as _;
+use vello_common::pixmap::Pixmap;
+
+/// Color matrix filter.
+///
+/// The matrix is stored row-major as 4 rows (R, G, B, A) × 5 columns (R, G, B, A, offset).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ColorMatrix {
+    matrix: [f32; 20],
+}
+
+impl ColorMatrix {
+    pub(crate) fn new(matrix: [f32; 20]) -> Self {
+        Self { matrix }
+    }
+
+    fn execute(&self, pixmap: &mut Pixmap, _layer_manager: &mut LayerManager) {
+        for pixel in pixmap.data_mut() {
+            *pixel = self.apply(*pixel);
+        }
+    }
+
+    /// Apply the matrix to a single premultiplied pixel.
+    #[inline]
+    fn apply(&self, pixel: PremulRgba8) -> PremulRgba8 {
+        // Un-premultiply. Fully transparent pixels are treated as transparent black.
+        let alpha = f32::from(pixel.a) / 255.0;
+        let unpremultiply = |c: u8| {
+            if pixel.a == 0 {
+                0.0
+            } else {
+                (f32::from(c) / f32::from(pixel.a)).min(1.0)
+            }
+        };
+        let src = [
+            unpremultiply(pixel.r),
+            unpremultiply(pixel.g),
+            unpremultiply(pixel.b),
+            alpha,
+        ];
+
+        let mut dst = [0.0_f32; 4];
+        for (out, row) in dst.iter_mut().zip(self.matrix.chunks_exact(5)) {
+            let value =
+                row[0] * src[0] + row[1] * src[1] + row[2] * src[2] + row[3] * src[3] + row[4];
+            *out = value.clamp(0.0, 1.0);
+        }
+
+        // Premultiply and convert back to u8.
+        let a = dst[3];
+        let to_u8 = |c: f32| (c * a * 255.0).round() as u8;
+        PremulRgba8 {
+            r: to_u8(dst[0]),
+            g: to_u8(dst[1]),
+            b: to_u8(dst[2]),
+            a: (a * 255.0).round() as u8,
+        }
+    }
+}
+
+impl FilterEffect for ColorMatrix {
+    fn execute_lowp(&self, pixmap: &mut Pixmap, layer_manager: &mut LayerManager) {
+        self.execute(pixmap, layer_manager);
+    }
+
+    fn execute_highp(&self, pixmap: &mut Pixmap, layer_manager: &mut LayerManager) {
+        self.execute(pixmap, layer_manager);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColorMatrix;
+    use vello_common::filter_effects::FilterPrimitive;
+    use vello_common::peniko::color::PremulRgba8;
+
+    fn matrix_of(primitive: FilterPrimitive) -> ColorMatrix {
+        match primitive {
+            FilterPrimitive::ColorMatrix { matrix } => ColorMatrix::new(matrix),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn identity_preserves_pixels() {
+        let filter = ColorMatrix::new(FilterPrimitive::IDENTITY_COLOR_MATRIX);
+        for pixel in [
+            PremulRgba8::from_u32(0),
+            PremulRgba8 {
+                r: 200,
+                g: 100,
+                b: 0,
+                a: 255,
+            },
+            PremulRgba8 {
+                r: 64,
+                g: 32,
+                b: 16,
+                a: 128,
+            },
+        ] {
+            assert_eq!(filter.apply(pixel), pixel);
+        }
+    }
+
+    #[test]
+    fn saturate_zero_produces_luma() {
+        let filter = matrix_of(FilterPrimitive::saturate(0.0));
+        let result = filter.apply(PremulRgba8 {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255,
+        });
+        // 0.213 × 255 ≈ 54
+        let luma = 54;
+        assert_eq!(
+            result,
+            PremulRgba8 {
+                r: luma,
+                g: luma,
+                b: luma,
+                a: 255,
+            }
+        );
+    }
+
+    #[test]
+    fn luminance_to_alpha() {
+        let filter = matrix_of(FilterPrimitive::luminance_to_alpha());
+        let result = filter.apply(PremulRgba8 {
+            r: 255,
+            g: 255,
+            b: 0,
+            a: 255,
+        });
+        // (0.2125 + 0.7154) × 255 ≈ 237, with all color channels black.
+        assert_eq!(
+            result,
+            PremulRgba8 {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 237,
+            }
+        );
+        // Transparent pixels have no luminance.
+        assert_eq!(
+            filter.apply(PremulRgba8::from_u32(0)),
+            PremulRgba8::from_u32(0)
+        );
+    }
+
+    #[test]
+    fn out_of_range_results_are_clamped() {
+        let mut matrix = FilterPrimitive::IDENTITY_COLOR_MATRIX;
+        // Double red, subtract one from green, and add a large offset to alpha.
+        matrix[0] = 2.0;
+        matrix[9] = -1.0;
+        matrix[19] = 5.0;
+        let result = ColorMatrix::new(matrix).apply(PremulRgba8 {
+            r: 64,
+            g: 64,
+            b: 64,
+            a: 128,
+        });
+        // Un-premultiplied input is (0.5, 0.5, 0.5, 0.5): red clamps to 1 and green to 0,
+        // while alpha clamps to 1.
+        assert_eq!(
+            result,
+            PremulRgba8 {
+                r: 255,
+                g: 0,
+                b: 128,
+                a: 255,
+            }
+        );
+    }
+}
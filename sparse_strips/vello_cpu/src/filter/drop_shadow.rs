@@ -14,7 +14,7 @@
 //! @see <https://drafts.fxtf.org/filter-effects-2/#feDropShadowElement>
 
 use super::FilterEffect;
-use super::gaussian_blur::{MAX_KERNEL_SIZE, apply_blur, plan_decimated_blur};
+use super::gaussian_blur::{BlurPlan, apply_blur};
 use super::shift::offset_pixels;
 use crate::layer_manager::LayerManager;
 use vello_common::color::{AlphaColor, Srgb};
@@ -28,17 +28,14 @@ pub(crate) struct DropShadow {
     pub dx: f32,
     pub dy: f32,
     pub color: AlphaColor<Srgb>,
-    /// Standard deviation for the blur (for reference/debugging).
-    std_deviation: f32,
+    /// Standard deviations for the blur along the x and y axes.
+    std_deviation: (f32, f32),
     /// Edge mode for blur sampling.
     edge_mode: EdgeMode,
-    /// Number of 2x2 decimation levels to use (0 means direct convolution).
-    n_decimations: usize,
-    /// Pre-computed Gaussian kernel weights for the reduced blur.
-    /// Only the first `kernel_size` elements are valid.
-    kernel: [f32; MAX_KERNEL_SIZE],
-    /// Actual length of the kernel (kernel is padded to `MAX_KERNEL_SIZE`).
-    kernel_size: u8,
+    /// Pre-computed blur plan for the horizontal axis.
+    plan_x: BlurPlan,
+    /// Pre-computed blur plan for the vertical axis.
+    plan_y: BlurPlan,
 }
 
 impl DropShadow {
@@ -48,22 +45,19 @@ impl DropShadow {
     pub(crate) fn new(
         dx: f32,
         dy: f32,
-        std_deviation: f32,
+        std_deviation: (f32, f32),
         edge_mode: EdgeMode,
         color: AlphaColor<Srgb>,
     ) -> Self {
-        // Precompute blur plan (same logic as GaussianBlur::new)
-        let (n_decimations, kernel, kernel_size) = plan_decimated_blur(std_deviation);
-
         Self {
             dx,
             dy,
             color,
             std_deviation,
             edge_mode,
-            n_decimations,
-            kernel,
-            kernel_size,
+            // Precompute blur plans (same logic as GaussianBlur::new)
+            plan_x: BlurPlan::new(std_deviation.0),
+            plan_y: BlurPlan::new(std_deviation.1),
         }
     }
 }
@@ -75,8 +69,8 @@ impl FilterEffect for DropShadow {
             self.dx,
             self.dy,
             self.std_deviation,
-            self.n_decimations,
-            &self.kernel[..usize::from(self.kernel_size)],
+            &self.plan_x,
+            &self.plan_y,
             self.color,
             self.edge_mode,
             layer_manager,
@@ -100,9 +94,9 @@ fn apply_drop_shadow(
     pixmap: &mut Pixmap,
     dx: f32,
     dy: f32,
-    std_deviation: f32,
-    n_decimations: usize,
-    kernel: &[f32],
+    std_deviation: (f32, f32),
+    plan_x: &BlurPlan,
+    plan_y: &BlurPlan,
     color: AlphaColor<Srgb>,
     edge_mode: EdgeMode,
     layer_manager: &mut LayerManager,
@@ -114,16 +108,10 @@ fn apply_drop_shadow(
     offset_pixels(&mut shadow_pixmap, dx, dy);
 
     // Step 2: Blur the already-offset shadow
-    if std_deviation > 0.0 {
+    if std_deviation.0 > 0.0 || std_deviation.1 > 0.0 {
         let scratch =
             layer_manager.get_scratch_buffer(shadow_pixmap.width(), shadow_pixmap.height());
-        apply_blur(
-            &mut shadow_pixmap,
-            scratch,
-            n_decimations,
-            kernel,
-            edge_mode,
-        );
+        apply_blur(&mut shadow_pixmap, scratch, plan_x, plan_y, edge_mode);
     }
 
     // Step 3: Apply shadow color and composite with original
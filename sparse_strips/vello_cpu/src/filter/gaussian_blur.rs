@@ -46,50 +46,39 @@ const _: () = const {
 };
 
 pub(crate) struct GaussianBlur {
-    std_deviation: f32,
-    /// Number of 2× decimation levels to use (0 means no decimation, direct convolution).
-    n_decimations: usize,
-    /// Pre-computed Gaussian kernel weights for the reduced blur.
-    /// Only the first `kernel_size` elements are valid.
-    kernel: [f32; MAX_KERNEL_SIZE],
-    /// Actual length of the kernel (rest is padding up to `MAX_KERNEL_SIZE`).
-    kernel_size: u8,
+    /// Standard deviations along the x and y axes.
+    std_deviation: (f32, f32),
+    /// Pre-computed blur plan for the horizontal axis.
+    plan_x: BlurPlan,
+    /// Pre-computed blur plan for the vertical axis.
+    plan_y: BlurPlan,
     /// Edge mode for handling out-of-bounds sampling.
     edge_mode: EdgeMode,
 }
 
 impl GaussianBlur {
-    /// Create a new Gaussian blur filter with the specified standard deviation.
+    /// Create a new Gaussian blur filter with the specified per-axis standard deviations.
     ///
-    /// This precomputes the decimation plan, kernel, and radius for optimal performance.
-    pub(crate) fn new(std_deviation: f32, edge_mode: EdgeMode) -> Self {
-        let (n_decimations, kernel, kernel_size) = plan_decimated_blur(std_deviation);
-
+    /// This precomputes the decimation plan, kernel, and radius for each axis.
+    pub(crate) fn new(std_deviation: (f32, f32), edge_mode: EdgeMode) -> Self {
         Self {
             std_deviation,
             edge_mode,
-            n_decimations,
-            kernel,
-            kernel_size,
+            plan_x: BlurPlan::new(std_deviation.0),
+            plan_y: BlurPlan::new(std_deviation.1),
         }
     }
 }
 
 impl FilterEffect for GaussianBlur {
     fn execute_lowp(&self, pixmap: &mut Pixmap, layer_manager: &mut LayerManager) {
-        // No blur if std_deviation is zero or negative
-        if self.std_deviation <= 0.0 {
+        // No blur if std_deviation is zero or negative on both axes
+        if self.std_deviation.0 <= 0.0 && self.std_deviation.1 <= 0.0 {
             return;
         }
 
         let scratch = layer_manager.get_scratch_buffer(pixmap.width(), pixmap.height());
-        apply_blur(
-            pixmap,
-            scratch,
-            self.n_decimations,
-            &self.kernel[..usize::from(self.kernel_size)],
-            self.edge_mode,
-        );
+        apply_blur(pixmap, scratch, &self.plan_x, &self.plan_y, self.edge_mode);
     }
 
     fn execute_highp(&self, pixmap: &mut Pixmap, layer_manager: &mut LayerManager) {
@@ -99,6 +88,43 @@ impl FilterEffect for GaussianBlur {
     }
 }
 
+/// Pre-computed blur plan for a single axis.
+///
+/// A standard deviation of zero (or less) results in an identity plan, which leaves
+/// the axis untouched.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct BlurPlan {
+    /// Number of 2× decimation levels to use (0 means no decimation, direct convolution).
+    n_decimations: usize,
+    /// Pre-computed Gaussian kernel weights for the reduced blur.
+    /// Only the first `kernel_size` elements are valid.
+    kernel: [f32; MAX_KERNEL_SIZE],
+    /// Actual length of the kernel (rest is padding up to `MAX_KERNEL_SIZE`).
+    kernel_size: u8,
+}
+
+impl BlurPlan {
+    /// Plan a blur with the given standard deviation along one axis.
+    pub(crate) fn new(std_deviation: f32) -> Self {
+        let (n_decimations, kernel, kernel_size) = plan_decimated_blur(std_deviation);
+        Self {
+            n_decimations,
+            kernel,
+            kernel_size,
+        }
+    }
+
+    /// The valid portion of the kernel.
+    fn kernel(&self) -> &[f32] {
+        &self.kernel[..usize::from(self.kernel_size)]
+    }
+
+    /// The kernel radius (`kernel_size / 2`).
+    fn radius(&self) -> u8 {
+        self.kernel_size / 2
+    }
+}
+
 /// Compute the blur execution plan based on standard deviation.
 ///
 /// Returns (`n_decimations`, `kernel`, `kernel_size`):
@@ -178,26 +204,28 @@ pub(crate) fn compute_gaussian_kernel(std_deviation: f32) -> ([f32; MAX_KERNEL_S
 
 /// Apply Gaussian blur using multi-scale decimation and upsampling.
 ///
-/// Uses a precomputed decimation plan and kernel for optimal performance.
-/// Operates in-place using a single pixmap buffer with logical dimension tracking
-/// to minimize memory allocations. For `n_decimations=0`, applies direct convolution.
+/// Uses precomputed per-axis decimation plans and kernels. Each axis is decimated
+/// independently, so an axis with a smaller (or zero) standard deviation is not
+/// downsampled more than it needs to be. Operates in-place using a single pixmap
+/// buffer with logical dimension tracking to minimize memory allocations. If neither
+/// axis needs decimation, applies direct convolution.
 ///
 /// The `scratch` buffer is used for separable convolution and must be at least as
 /// large as the source pixmap.
 pub(crate) fn apply_blur(
     pixmap: &mut Pixmap,
     scratch: &mut Pixmap,
-    n_decimations: usize,
-    kernel: &[f32],
+    plan_x: &BlurPlan,
+    plan_y: &BlurPlan,
     edge_mode: EdgeMode,
 ) {
-    let radius = (kernel.len() / 2) as u8;
     let width = pixmap.width();
     let height = pixmap.height();
+    let n_levels = plan_x.n_decimations.max(plan_y.n_decimations);
 
     // Small blur: apply direct convolution at full resolution
-    if n_decimations == 0 {
-        convolve(pixmap, scratch, width, height, kernel, radius, edge_mode);
+    if n_levels == 0 {
+        convolve(pixmap, scratch, width, height, plan_x, plan_y, edge_mode);
         return;
     }
 
@@ -206,22 +234,36 @@ pub(crate) fn apply_blur(
     let mut src_height = height;
     let mut dimensions_stack = vec![(width, height)];
 
-    // Downsample n times (each step reduces resolution by 2×)
-    for _ in 0..n_decimations {
-        (src_width, src_height) = downscale(pixmap, src_width, src_height, edge_mode);
+    // Downsample up to n times per axis (each step reduces resolution by 2×)
+    for level in 0..n_levels {
+        (src_width, src_height) = downscale(
+            pixmap,
+            src_width,
+            src_height,
+            level < plan_x.n_decimations,
+            level < plan_y.n_decimations,
+            edge_mode,
+        );
         dimensions_stack.push((src_width, src_height));
     }
 
     // Apply the reduced blur at the coarsest resolution
     convolve(
-        pixmap, scratch, src_width, src_height, kernel, radius, edge_mode,
+        pixmap, scratch, src_width, src_height, plan_x, plan_y, edge_mode,
     );
 
     // Upsample back to original resolution (each step doubles resolution by 2×)
-    for _ in 0..n_decimations {
+    for level in (0..n_levels).rev() {
         dimensions_stack.pop();
         if let Some(&(target_width, target_height)) = dimensions_stack.last() {
-            (src_width, src_height) = upscale(pixmap, src_width, src_height, edge_mode);
+            (src_width, src_height) = upscale(
+                pixmap,
+                src_width,
+                src_height,
+                level < plan_x.n_decimations,
+                level < plan_y.n_decimations,
+                edge_mode,
+            );
             // Clamp because upscale can exceed target on odd dimensions (e.g., 5→3→6 > 5)
             src_width = src_width.min(target_width);
             src_height = src_height.min(target_height);
@@ -237,20 +279,37 @@ pub(crate) fn apply_blur(
 
 /// Apply separable Gaussian convolution with logical dimensions.
 ///
-/// Performs horizontal blur followed by vertical blur. Works with a logical view
-/// of the pixmap, using only the top-left region defined by width × height.
-/// The `temp` buffer is provided by the caller to avoid allocations.
+/// Performs horizontal blur followed by vertical blur, each with the kernel of the
+/// corresponding axis plan. Works with a logical view of the pixmap, using only the
+/// top-left region defined by width × height.
+/// The `scratch` buffer is provided by the caller to avoid allocations.
 pub(crate) fn convolve(
     src: &mut Pixmap,
     scratch: &mut Pixmap,
     width: u16,
     height: u16,
-    kernel: &[f32],
-    radius: u8,
+    plan_x: &BlurPlan,
+    plan_y: &BlurPlan,
     edge_mode: EdgeMode,
 ) {
-    convolve_x(src, scratch, width, height, kernel, radius, edge_mode);
-    convolve_y(scratch, src, width, height, kernel, radius, edge_mode);
+    convolve_x(
+        src,
+        scratch,
+        width,
+        height,
+        plan_x.kernel(),
+        plan_x.radius(),
+        edge_mode,
+    );
+    convolve_y(
+        scratch,
+        src,
+        width,
+        height,
+        plan_y.kernel(),
+        plan_y.radius(),
+        edge_mode,
+    );
 }
 
 /// Apply horizontal blur pass (1D convolution along x-axis).
@@ -353,18 +412,27 @@ pub(crate) fn convolve_y(
 
 /// Downsample image by 2x using separable \[1,3,3,1\]/8 binomial filter.
 ///
-/// Performs horizontal and vertical decimation in sequence. Returns the new
-/// logical dimensions (ceil(width/2), ceil(height/2)).
+/// Performs horizontal and vertical decimation in sequence, skipping the axes for
+/// which `x` or `y` is `false`. Returns the new logical dimensions (ceil(width/2),
+/// ceil(height/2) for decimated axes).
 pub(crate) fn downscale(
     src: &mut Pixmap,
     src_width: u16,
     src_height: u16,
+    x: bool,
+    y: bool,
     edge_mode: EdgeMode,
 ) -> (u16, u16) {
-    let dst_width = src_width.div_ceil(2);
-    let dst_height = src_height.div_ceil(2);
-    downscale_x(src, src_width, src_height, dst_width, edge_mode);
-    downscale_y(src, src_width, src_height, dst_height, edge_mode);
+    let mut dst_width = src_width;
+    let mut dst_height = src_height;
+    if x {
+        dst_width = src_width.div_ceil(2);
+        downscale_x(src, src_width, src_height, dst_width, edge_mode);
+    }
+    if y {
+        dst_height = src_height.div_ceil(2);
+        downscale_y(src, dst_width, src_height, dst_height, edge_mode);
+    }
     (dst_width, dst_height)
 }
 
@@ -438,7 +506,8 @@ fn downscale_y(
 
 /// Upsample a pixmap by 2x using linear interpolation with [0.75, 0.25] weights.
 ///
-/// Uses separable passes: horizontal doubling followed by vertical doubling.
+/// Uses separable passes: horizontal doubling followed by vertical doubling, skipping
+/// the axes for which `x` or `y` is `false`.
 ///
 /// ## Phase Alignment Theory
 ///
@@ -458,12 +527,21 @@ pub(crate) fn upscale(
     src: &mut Pixmap,
     src_width: u16,
     src_height: u16,
+    x: bool,
+    y: bool,
     edge_mode: EdgeMode,
 ) -> (u16, u16) {
-    let dst_width = src_width * 2;
-    let dst_height = src_height * 2;
-    upscale_x(src, src_width, src_height, edge_mode);
-    upscale_y(src, dst_width, src_height, edge_mode);
+    let mut dst_width = src_width;
+    let mut dst_height = src_height;
+    if x {
+        upscale_x(src, src_width, src_height, edge_mode);
+        dst_width = src_width * 2;
+    }
+    if y {
+        // The doubled width can exceed the physical width by one column (see `upscale_x`).
+        upscale_y(src, dst_width.min(src.width()), src_height, edge_mode);
+        dst_height = src_height * 2;
+    }
     (dst_width, dst_height)
 }
 
@@ -490,7 +568,11 @@ fn upscale_x(src: &mut Pixmap, src_width: u16, src_height: u16, edge_mode: EdgeM
             // output[2x+1] = 0.75×p1 + 0.25×p0  (position 2x+1 is 0.5 from center at 2x+0.5)
             let dst_x = x * 2;
             src.set_pixel(dst_x, y, interpolate_25_75(p2, p1));
-            src.set_pixel(dst_x + 1, y, interpolate_75_25(p1, p0));
+            // When decimating an odd physical width, the last output column lies outside the
+            // pixmap. It would be clamped away afterwards anyway, so just skip it.
+            if dst_x + 1 < src.width() {
+                src.set_pixel(dst_x + 1, y, interpolate_75_25(p1, p0));
+            }
 
             // Advance sliding window for next iteration
             p0 = p1;
@@ -522,7 +604,10 @@ fn upscale_y(src: &mut Pixmap, src_width: u16, src_height: u16, edge_mode: EdgeM
             // output[2y+1] = 0.75×p1 + 0.25×p0  (position 2y+1 is 0.5 from center at 2y+0.5)
             let dst_y = y * 2;
             src.set_pixel(x, dst_y, interpolate_25_75(p2, p1));
-            src.set_pixel(x, dst_y + 1, interpolate_75_25(p1, p0));
+            // Same as in `upscale_x`: skip the row outside of an odd-height pixmap.
+            if dst_y + 1 < src.height() {
+                src.set_pixel(x, dst_y + 1, interpolate_75_25(p1, p0));
+            }
 
             // Advance sliding window for next iteration
             p0 = p1;
@@ -946,23 +1031,63 @@ mod tests {
     #[test]
     fn test_small_image_sizes() {
         let mut pixmap = Pixmap::new(1, 1);
-        let (n_decimations, kernel, kernel_size) = plan_decimated_blur(2.0);
+        let plan = BlurPlan::new(2.0);
 
         // Should not panic
         let result = std::panic::catch_unwind(move || {
             let mut scratch = Pixmap::new(1, 1);
-            apply_blur(
-                &mut pixmap,
-                &mut scratch,
-                n_decimations,
-                &kernel[..usize::from(kernel_size)],
-                EdgeMode::None,
-            );
+            apply_blur(&mut pixmap, &mut scratch, &plan, &plan, EdgeMode::None);
         });
 
         assert!(result.is_ok());
     }
 
+    /// Test that a zero standard deviation on one axis reduces to a 1D blur.
+    #[test]
+    fn test_blur_horizontal_only() {
+        const SIZE: u16 = 64;
+        const WHITE: PremulRgba8 = PremulRgba8 {
+            r: 255,
+            g: 255,
+            b: 255,
+            a: 255,
+        };
+        let mut pixmap = Pixmap::new(SIZE, SIZE);
+        let mut scratch = Pixmap::new(SIZE, SIZE);
+        // Opaque square spanning [24, 40) on both axes.
+        for y in 24..40 {
+            for x in 24..40 {
+                pixmap.set_pixel(x, y, WHITE);
+            }
+        }
+
+        apply_blur(
+            &mut pixmap,
+            &mut scratch,
+            &BlurPlan::new(8.0),
+            &BlurPlan::new(0.0),
+            EdgeMode::None,
+        );
+
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let p = pixmap.sample(x, y);
+                if (24..40).contains(&y) {
+                    // Rows inside the square are smeared horizontally: every row is identical.
+                    assert_eq!(p, pixmap.sample(x, 24), "({x}, {y})");
+                } else {
+                    // Nothing bleeds vertically outside the square.
+                    assert_eq!(p.a, 0, "({x}, {y})");
+                }
+            }
+        }
+        // The smear extends well beyond the square's horizontal bounds...
+        assert!(pixmap.sample(16, 32).a > 0);
+        assert!(pixmap.sample(47, 32).a > 0);
+        // ...and softens its edges.
+        assert!(pixmap.sample(24, 32).a < 255);
+    }
+
     /// Test downscale with odd dimensions.
     #[test]
     fn test_downscale_odd_dimensions() {
@@ -983,17 +1108,31 @@ mod tests {
             }
         }
 
-        let (new_width, new_height) = downscale(&mut pixmap, 5, 5, EdgeMode::Duplicate);
+        let (new_width, new_height) = downscale(&mut pixmap, 5, 5, true, true, EdgeMode::Duplicate);
         // 5 / 2 = 2.5 → ceil = 3
         assert_eq!(new_width, 3);
         assert_eq!(new_height, 3);
     }
 
+    /// Test that downscaling a single axis leaves the other dimension untouched.
+    #[test]
+    fn test_downscale_single_axis() {
+        let mut pixmap = Pixmap::new(5, 5);
+        assert_eq!(
+            downscale(&mut pixmap, 5, 5, true, false, EdgeMode::Duplicate),
+            (3, 5)
+        );
+        assert_eq!(
+            downscale(&mut pixmap, 3, 5, false, true, EdgeMode::Duplicate),
+            (3, 3)
+        );
+    }
+
     /// Test upscale dimensions.
     #[test]
     fn test_upscale_dimensions() {
         let mut pixmap = Pixmap::new(6, 6);
-        let (new_width, new_height) = upscale(&mut pixmap, 3, 3, EdgeMode::Duplicate);
+        let (new_width, new_height) = upscale(&mut pixmap, 3, 3, true, true, EdgeMode::Duplicate);
         // 3 * 2 = 6
         assert_eq!(new_width, 6);
         assert_eq!(new_height, 6);
@@ -1248,11 +1387,11 @@ mod tests {
             a: 255,
         });
 
-        let (w1, h1) = downscale(&mut pixmap, 8, 8, EdgeMode::Duplicate);
+        let (w1, h1) = downscale(&mut pixmap, 8, 8, true, true, EdgeMode::Duplicate);
         assert_eq!(w1, 4);
         assert_eq!(h1, 4);
 
-        let (w2, h2) = upscale(&mut pixmap, w1, h1, EdgeMode::Duplicate);
+        let (w2, h2) = upscale(&mut pixmap, w1, h1, true, true, EdgeMode::Duplicate);
         assert_eq!(w2, 8);
         assert_eq!(h2, 8);
     }
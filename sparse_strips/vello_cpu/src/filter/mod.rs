@@ -8,22 +8,28 @@
 //! Filters are applied to layers through the layer manager, which handles
 //! intermediate storage.
 
+mod color_matrix;
 mod drop_shadow;
 mod flood;
 mod gaussian_blur;
+mod morphology;
 mod offset;
 mod shift;
 
+pub(crate) use color_matrix::ColorMatrix;
 pub(crate) use drop_shadow::DropShadow;
 pub(crate) use flood::Flood;
 pub(crate) use gaussian_blur::GaussianBlur;
+pub(crate) use morphology::Morphology;
 pub(crate) use offset::Offset;
 
 use crate::layer_manager::LayerManager;
 use vello_common::filter_effects::{Filter, FilterPrimitive};
-use vello_common::kurbo::{Affine, Vec2};
+#[cfg(not(feature = "std"))]
+use vello_common::kurbo::common::FloatFuncs as _;
+use vello_common::kurbo::{Affine, Rect, Vec2};
+use vello_common::peniko::color::PremulRgba8;
 use vello_common::pixmap::Pixmap;
-use vello_common::util::extract_scales;
 
 /// Trait for filter effects that can be applied to layers.
 ///
@@ -52,28 +58,33 @@ pub(crate) trait FilterEffect {
 ///
 /// This function dispatches filter primitives from a filter graph to their
 /// corresponding CPU implementations using 8-bit color channels.
+/// Primitives are applied in order, each one operating in place on the result of
+/// the previous one, so no intermediate buffers are needed between stages.
 ///
 /// # Arguments
 /// * `filter` - The filter containing the graph of primitives to apply
 /// * `pixmap` - The target pixmap containing rendering metadata
 /// * `layer_manager` - Manager for allocating and accessing intermediate layers
 /// * `transform` - The transformation matrix to extract scale from for filter parameters
-///
-/// # Limitations
-/// Currently only supports filter graphs with a single primitive.
-/// Multi-primitive filter graphs are not yet implemented.
 pub(crate) fn filter_lowp(
     filter: &Filter,
     pixmap: &mut Pixmap,
     layer_manager: &mut LayerManager,
     transform: Affine,
 ) {
-    // Multi-primitive filter graphs are not yet implemented.
-    if filter.graph.primitives.len() != 1 {
-        unimplemented!("Multi-primitive filter graphs are not yet supported");
+    for primitive in &filter.graph.primitives {
+        primitive_lowp(primitive, pixmap, layer_manager, transform);
     }
+}
 
-    match &filter.graph.primitives[0] {
+/// Apply a single filter primitive using the low-precision (u8) path.
+fn primitive_lowp(
+    primitive: &FilterPrimitive,
+    pixmap: &mut Pixmap,
+    layer_manager: &mut LayerManager,
+    transform: Affine,
+) {
+    match primitive {
         FilterPrimitive::Flood { color } => {
             let flood = Flood::new(*color);
             flood.execute_lowp(pixmap, layer_manager);
@@ -103,8 +114,15 @@ pub(crate) fn filter_lowp(
             let (scaled_dx, scaled_dy) = transform_offset_params(*dx, *dy, &transform);
             Offset::new(scaled_dx, scaled_dy).execute_lowp(pixmap, layer_manager);
         }
+        FilterPrimitive::ColorMatrix { matrix } => {
+            ColorMatrix::new(*matrix).execute_lowp(pixmap, layer_manager);
+        }
+        FilterPrimitive::Morphology { operator, radius } => {
+            let scaled_radius = transform_morphology_radius(*radius, &transform);
+            Morphology::new(*operator, scaled_radius).execute_lowp(pixmap, layer_manager);
+        }
         _ => {
-            // Other primitives like Blend, ColorMatrix, ComponentTransfer, etc.
+            // Other primitives like Blend, ComponentTransfer, etc.
             // are not yet implemented
             unimplemented!("Other filter primitives not yet implemented");
         }
@@ -115,28 +133,33 @@ pub(crate) fn filter_lowp(
 ///
 /// This function dispatches filter primitives from a filter graph to their
 /// corresponding CPU implementations using 32-bit floating-point color channels.
+/// Primitives are applied in order, each one operating in place on the result of
+/// the previous one, so no intermediate buffers are needed between stages.
 ///
 /// # Arguments
 /// * `filter` - The filter containing the graph of primitives to apply
 /// * `pixmap` - The target pixmap containing rendering metadata
 /// * `layer_manager` - Manager for allocating and accessing intermediate layers
 /// * `transform` - The transformation matrix to extract scale from for filter parameters
-///
-/// # Limitations
-/// Currently only supports filter graphs with a single primitive.
-/// Multi-primitive filter graphs are not yet implemented.
 pub(crate) fn filter_highp(
     filter: &Filter,
     pixmap: &mut Pixmap,
     layer_manager: &mut LayerManager,
     transform: Affine,
 ) {
-    // Multi-primitive filter graphs are not yet implemented.
-    if filter.graph.primitives.len() != 1 {
-        unimplemented!("Multi-primitive filter graphs are not yet supported");
+    for primitive in &filter.graph.primitives {
+        primitive_highp(primitive, pixmap, layer_manager, transform);
     }
+}
 
-    match &filter.graph.primitives[0] {
+/// Apply a single filter primitive using the high-precision (f32) path.
+fn primitive_highp(
+    primitive: &FilterPrimitive,
+    pixmap: &mut Pixmap,
+    layer_manager: &mut LayerManager,
+    transform: Affine,
+) {
+    match primitive {
         FilterPrimitive::Flood { color } => {
             let flood = Flood::new(*color);
             flood.execute_highp(pixmap, layer_manager);
@@ -166,14 +189,49 @@ pub(crate) fn filter_highp(
             let (scaled_dx, scaled_dy) = transform_offset_params(*dx, *dy, &transform);
             Offset::new(scaled_dx, scaled_dy).execute_highp(pixmap, layer_manager);
         }
+        FilterPrimitive::ColorMatrix { matrix } => {
+            ColorMatrix::new(*matrix).execute_highp(pixmap, layer_manager);
+        }
+        FilterPrimitive::Morphology { operator, radius } => {
+            let scaled_radius = transform_morphology_radius(*radius, &transform);
+            Morphology::new(*operator, scaled_radius).execute_highp(pixmap, layer_manager);
+        }
         _ => {
-            // Other primitives like Blend, ColorMatrix, ComponentTransfer, etc.
+            // Other primitives like Blend, ComponentTransfer, etc.
             // are not yet implemented
             unimplemented!("Other filter primitives not yet implemented");
         }
     }
 }
 
+/// Clear all pixels of the pixmap outside of the given region.
+///
+/// The region is given in pixel coordinates relative to the top-left corner of the pixmap,
+/// and its edges are rounded to the nearest pixel boundary.
+pub(crate) fn crop_to_region(pixmap: &mut Pixmap, region: Rect) {
+    let width = usize::from(pixmap.width());
+    let height = usize::from(pixmap.height());
+    if width == 0 {
+        return;
+    }
+
+    let clamp = |value: f64, max: usize| (value.round().max(0.0) as usize).min(max);
+    let x0 = clamp(region.x0, width);
+    let x1 = clamp(region.x1, width).max(x0);
+    let y0 = clamp(region.y0, height);
+    let y1 = clamp(region.y1, height).max(y0);
+
+    let transparent = PremulRgba8::from_u32(0);
+    for (y, row) in pixmap.data_mut().chunks_exact_mut(width).enumerate() {
+        if (y0..y1).contains(&y) {
+            row[..x0].fill(transparent);
+            row[x1..].fill(transparent);
+        } else {
+            row.fill(transparent);
+        }
+    }
+}
+
 /// Transform an offset's dx/dy using the affine transformation's linear part.
 ///
 /// # Returns
@@ -188,12 +246,12 @@ fn transform_offset_params(dx: f32, dy: f32, transform: &Affine) -> (f32, f32) {
 /// Transform a drop shadow's offset and standard deviation using the affine transformation.
 ///
 /// Applies the full linear transformation (rotation, scale, and shear) to the offset vector,
-/// and scales the blur standard deviation uniformly.
+/// and maps the per-axis blur standard deviation into device space.
 ///
 /// # Arguments
 /// * `dx` - Horizontal offset in user space
 /// * `dy` - Vertical offset in user space
-/// * `std_deviation` - Blur standard deviation in user space
+/// * `std_deviation` - Blur standard deviations `(x, y)` in user space
 /// * `transform` - The transformation matrix to apply
 ///
 /// # Returns
@@ -201,9 +259,9 @@ fn transform_offset_params(dx: f32, dy: f32, transform: &Affine) -> (f32, f32) {
 fn transform_shadow_params(
     dx: f32,
     dy: f32,
-    std_deviation: f32,
+    std_deviation: (f32, f32),
     transform: &Affine,
-) -> (f32, f32, f32) {
+) -> (f32, f32, (f32, f32)) {
     // Transform the offset vector by the full transformation matrix
     // to correctly handle rotation, scale, and shear.
     // We use the linear part only (no translation) since this is a vector offset.
@@ -213,28 +271,46 @@ fn transform_shadow_params(
     let scaled_dx = transformed_offset.x as f32;
     let scaled_dy = transformed_offset.y as f32;
 
-    // Scale the blur radius uniformly
+    // Map the per-axis blur radius into device space
     let scaled_std_dev = transform_blur_params(std_deviation, transform);
 
     (scaled_dx, scaled_dy, scaled_std_dev)
 }
 
-/// Scale a blur's standard deviation uniformly based on the transformation.
+/// Map a blur's per-axis standard deviation from user space to device space.
 ///
-/// Extracts the scale factors from the transformation matrix using SVD and
-/// averages them to get a uniform scale factor for the blur radius.
+/// The blur is treated as a Gaussian with covariance `diag(σx², σy²)` in user space.
+/// Under the linear part `M` of the transformation, its covariance becomes `M·Σ·Mᵀ`.
+/// Since the blur is executed as separable passes along the device axes, only the
+/// diagonal of that matrix is kept, which is exact for axis-aligned scales and
+/// quarter-turn rotations.
 ///
 /// # Arguments
-/// * `std_deviation` - The blur standard deviation in user space
+/// * `std_deviation` - The blur standard deviations `(x, y)` in user space
 /// * `transform` - The transformation matrix to extract scale from
 ///
 /// # Returns
-/// The scaled standard deviation in device space
-fn transform_blur_params(std_deviation: f32, transform: &Affine) -> f32 {
-    let (scale_x, scale_y) = extract_scales(transform);
-    let uniform_scale = (scale_x + scale_y) / 2.0;
-    // TODO: Support separate std_deviation for x and y axes (std_deviation_x, std_deviation_y)
-    // to properly handle non-uniform scaling. This would eliminate the need for uniform_scale
-    // and allow blur to scale independently along each axis.
-    std_deviation * uniform_scale
+/// The scaled standard deviations `(x, y)` in device space
+fn transform_blur_params(std_deviation: (f32, f32), transform: &Affine) -> (f32, f32) {
+    let [a, b, c, d, _, _] = transform.as_coeffs();
+    let (a, b, c, d) = (a as f32, b as f32, c as f32, d as f32);
+    let var_x = std_deviation.0 * std_deviation.0;
+    let var_y = std_deviation.1 * std_deviation.1;
+    (
+        (a * a * var_x + c * c * var_y).sqrt(),
+        (b * b * var_x + d * d * var_y).sqrt(),
+    )
+}
+
+/// Map a morphology radius from user space to device space.
+///
+/// The radius scales in the same way as a blur's standard deviation. A radius that
+/// disables the filter in user space stays disabled, even if the transform would mix
+/// the axes.
+fn transform_morphology_radius(radius: (f32, f32), transform: &Affine) -> (f32, f32) {
+    if radius.0 > 0.0 && radius.1 > 0.0 {
+        transform_blur_params(radius, transform)
+    } else {
+        (0.0, 0.0)
+    }
 }
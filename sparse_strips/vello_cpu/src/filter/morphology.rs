@@ -0,0 +1,225 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! `feMorphology` filter primitive implementation.
+//!
+//! Dilation takes the per-channel maximum and erosion the per-channel minimum over a
+//! rectangular neighborhood of `(2 * radius_x + 1) × (2 * radius_y + 1)` pixels. Since the
+//! neighborhood is a rectangle, the operation is separable and is applied as a horizontal
+//! pass followed by a vertical pass. Pixels outside the pixmap are transparent black.
+//!
+//! @see <https://drafts.fxtf.org/filter-effects/#feMorphologyElement>
+
+use super::FilterEffect;
+use crate::layer_manager::LayerManager;
+use alloc::vec;
+use alloc::vec::Vec;
+use vello_common::filter_effects::MorphologyOperator;
+#[cfg(not(feature = "std"))]
+use vello_common::kurbo::common::FloatFuncs as _;
+use vello_common::peniko::color::PremulRgba8;
+use vello_common::pixmap::Pixmap;
+
+/// Morphology (dilate/erode) filter.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Morphology {
+    operator: MorphologyOperator,
+    /// Horizontal radius in device pixels.
+    radius_x: usize,
+    /// Vertical radius in device pixels.
+    radius_y: usize,
+}
+
+impl Morphology {
+    /// Create a new morphology filter with the given radius `(x, y)` in device space.
+    ///
+    /// Following the spec, a negative or zero radius on either axis disables the filter.
+    pub(crate) fn new(operator: MorphologyOperator, radius: (f32, f32)) -> Self {
+        let (radius_x, radius_y) = if radius.0 > 0.0 && radius.1 > 0.0 {
+            (radius.0.round() as usize, radius.1.round() as usize)
+        } else {
+            (0, 0)
+        };
+
+        Self {
+            operator,
+            radius_x,
+            radius_y,
+        }
+    }
+
+    fn execute(&self, pixmap: &mut Pixmap, _layer_manager: &mut LayerManager) {
+        let width = usize::from(pixmap.width());
+        let height = usize::from(pixmap.height());
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        if self.radius_x > 0 {
+            let mut line = Vec::with_capacity(width);
+            for row in pixmap.data_mut().chunks_exact_mut(width) {
+                line.clear();
+                line.extend_from_slice(row);
+                self.apply_line(&line, row, self.radius_x);
+            }
+        }
+
+        if self.radius_y > 0 {
+            let mut line = Vec::with_capacity(height);
+            let mut out = vec![PremulRgba8::from_u32(0); height];
+            let data = pixmap.data_mut();
+            for x in 0..width {
+                line.clear();
+                line.extend((0..height).map(|y| data[y * width + x]));
+                self.apply_line(&line, &mut out, self.radius_y);
+                for (y, pixel) in out.iter().enumerate() {
+                    data[y * width + x] = *pixel;
+                }
+            }
+        }
+    }
+
+    /// Apply the operator along a single row or column.
+    fn apply_line(&self, src: &[PremulRgba8], dst: &mut [PremulRgba8], radius: usize) {
+        let len = src.len();
+        for (i, out) in dst.iter_mut().enumerate() {
+            let start = i.saturating_sub(radius);
+            let end = (i + radius + 1).min(len);
+            let window = src[start..end].iter().copied();
+
+            *out = match self.operator {
+                MorphologyOperator::Dilate => window.fold(PremulRgba8::from_u32(0), max_pixel),
+                MorphologyOperator::Erode => {
+                    // A window reaching beyond the edge includes transparent black pixels.
+                    if end - start < 2 * radius + 1 {
+                        PremulRgba8::from_u32(0)
+                    } else {
+                        window.fold(PremulRgba8::from_u32(u32::MAX), min_pixel)
+                    }
+                }
+            };
+        }
+    }
+}
+
+#[inline]
+fn max_pixel(a: PremulRgba8, b: PremulRgba8) -> PremulRgba8 {
+    PremulRgba8 {
+        r: a.r.max(b.r),
+        g: a.g.max(b.g),
+        b: a.b.max(b.b),
+        a: a.a.max(b.a),
+    }
+}
+
+#[inline]
+fn min_pixel(a: PremulRgba8, b: PremulRgba8) -> PremulRgba8 {
+    PremulRgba8 {
+        r: a.r.min(b.r),
+        g: a.g.min(b.g),
+        b: a.b.min(b.b),
+        a: a.a.min(b.a),
+    }
+}
+
+impl FilterEffect for Morphology {
+    fn execute_lowp(&self, pixmap: &mut Pixmap, layer_manager: &mut LayerManager) {
+        self.execute(pixmap, layer_manager);
+    }
+
+    fn execute_highp(&self, pixmap: &mut Pixmap, layer_manager: &mut LayerManager) {
+        self.execute(pixmap, layer_manager);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Morphology;
+    use crate::filter::FilterEffect;
+    use crate::layer_manager::LayerManager;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use vello_common::filter_effects::MorphologyOperator;
+    use vello_common::peniko::color::PremulRgba8;
+    use vello_common::pixmap::Pixmap;
+
+    const OPAQUE_RED: PremulRgba8 = PremulRgba8 {
+        r: 255,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+
+    /// Create a 7x7 pixmap with an opaque 3x3 block at its center.
+    fn block_pixmap() -> Pixmap {
+        let mut pixmap = Pixmap::new(7, 7);
+        for y in 2..5 {
+            for x in 2..5 {
+                pixmap.set_pixel(x, y, OPAQUE_RED);
+            }
+        }
+        pixmap
+    }
+
+    fn opaque_pixels(pixmap: &Pixmap) -> Vec<(u16, u16)> {
+        let mut pixels = Vec::new();
+        for y in 0..pixmap.height() {
+            for x in 0..pixmap.width() {
+                if pixmap.sample(x, y).a == 255 {
+                    pixels.push((x, y));
+                }
+            }
+        }
+        pixels
+    }
+
+    #[test]
+    fn dilate_grows_block_per_axis() {
+        let mut pixmap = block_pixmap();
+        Morphology::new(MorphologyOperator::Dilate, (2.0, 1.0))
+            .execute_lowp(&mut pixmap, &mut LayerManager::new());
+
+        let expected: Vec<_> = (1..6).flat_map(|y| (0..7).map(move |x| (x, y))).collect();
+        assert_eq!(opaque_pixels(&pixmap), expected);
+        assert_eq!(pixmap.sample(0, 0), PremulRgba8::from_u32(0));
+    }
+
+    #[test]
+    fn erode_shrinks_block() {
+        let mut pixmap = block_pixmap();
+        Morphology::new(MorphologyOperator::Erode, (1.0, 1.0))
+            .execute_lowp(&mut pixmap, &mut LayerManager::new());
+
+        assert_eq!(opaque_pixels(&pixmap), vec![(3, 3)]);
+    }
+
+    #[test]
+    fn erode_treats_outside_as_transparent() {
+        let mut pixmap = Pixmap::new(3, 3);
+        for y in 0..3 {
+            for x in 0..3 {
+                pixmap.set_pixel(x, y, OPAQUE_RED);
+            }
+        }
+        Morphology::new(MorphologyOperator::Erode, (1.0, 1.0))
+            .execute_lowp(&mut pixmap, &mut LayerManager::new());
+
+        assert_eq!(opaque_pixels(&pixmap), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn zero_radius_is_noop() {
+        for operator in [MorphologyOperator::Dilate, MorphologyOperator::Erode] {
+            for radius in [(0.0, 0.0), (3.0, 0.0), (-1.0, 2.0)] {
+                let mut pixmap = block_pixmap();
+                Morphology::new(operator, radius)
+                    .execute_lowp(&mut pixmap, &mut LayerManager::new());
+                assert_eq!(
+                    pixmap.data(),
+                    block_pixmap().data(),
+                    "{operator:?} with radius {radius:?} should not modify the pixmap"
+                );
+            }
+        }
+    }
+}
@@ -11,7 +11,8 @@ use crate::layer_manager::LayerManager;
 
 /// Translation/shift filter.
 ///
-/// This shifts the input image by `(dx, dy)` in device pixel space.
+/// This shifts the input image by `(dx, dy)` in device pixel space. Fractional
+/// offsets are snapped to the nearest pixel, like the shift of a drop shadow.
 #[derive(Clone, Copy, Debug)]
 pub(crate) struct Offset {
     dx: f32,
@@ -67,6 +67,7 @@
 //! - `libm`: Use floating point implementations from [libm][].
 //! - `png`(enabled by default): Allow loading [`Pixmap`]s from PNG images.
 //!   Also required for rendering glyphs with an embedded PNG. Implies `std`.
+//! - `image`: Allow converting [`Pixmap`]s from and to images of the [image][] crate. Implies `std`.
 //! - `multithreading`: Enable multi-threaded rendering. Implies `std`.
 //! - `text` (enabled by default): Enables glyph rendering ([`glyph_run`][RenderContext::glyph_run]).
 //! - `u8_pipeline` (enabled by default): Enable the u8 pipeline, for speed focused rendering using u8 math.
@@ -114,6 +115,7 @@
 //!
 //! <!-- We can't directly link to the libm crate built locally, because our feature is only a pass-through  -->
 //! [libm]: https://crates.io/crates/libm
+//! [image]: https://crates.io/crates/image
 // LINEBENDER LINT SET - lib.rs - v3
 // See https://linebender.org/wiki/canonical-lints/
 // These lints shouldn't apply to examples or tests.
@@ -156,7 +158,7 @@ pub mod region;
 pub use render::{RenderContext, RenderSettings};
 pub use vello_common::fearless_simd::Level;
 #[cfg(feature = "text")]
-pub use vello_common::glyph::Glyph;
+pub use vello_common::glyph::{Glyph, GlyphCacheStats, WritingMode, YAxis};
 pub use vello_common::mask::Mask;
 pub use vello_common::paint::{Image, ImageSource, Paint, PaintType};
 pub use vello_common::pixmap::Pixmap;
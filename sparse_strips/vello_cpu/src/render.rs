@@ -19,6 +19,7 @@ use vello_common::blurred_rounded_rect::BlurredRoundedRectangle;
 use vello_common::encode::{EncodeExt, EncodedPaint};
 use vello_common::fearless_simd::Level;
 use vello_common::filter_effects::Filter;
+use vello_common::flatten::wrap_dash_offset;
 use vello_common::kurbo::{Affine, BezPath, Cap, Join, Rect, Stroke};
 use vello_common::mask::Mask;
 use vello_common::paint::{Paint, PaintType};
@@ -31,8 +32,10 @@ use vello_common::strip_generator::{GenerationMode, StripGenerator, StripStorage
 #[cfg(feature = "text")]
 use vello_common::{
     color::{AlphaColor, Srgb},
-    colr::{ColrPainter, ColrRenderer},
-    glyph::{GlyphCaches, GlyphRenderer, GlyphRunBuilder, GlyphType, PreparedGlyph},
+    colr::{ColrPainter, ColrRenderer, context_color},
+    glyph::{
+        GlyphCacheStats, GlyphCaches, GlyphRenderer, GlyphRunBuilder, GlyphType, PreparedGlyph,
+    },
     paint::ImageSource,
 };
 
@@ -72,6 +75,9 @@ pub struct RenderContext {
         allow(dead_code, reason = "used when the `text` feature is enabled")
     )]
     pub(crate) render_settings: RenderSettings,
+    /// For each pushed layer, the blend mode to restore when it is popped, if it is an isolated
+    /// layer pushed with [`RenderContext::push_isolated_layer`].
+    layer_blend_modes: Vec<Option<BlendMode>>,
     dispatcher: Box<dyn Dispatcher>,
     #[cfg(feature = "text")]
     pub(crate) glyph_caches: Option<GlyphCaches>,
@@ -81,6 +87,10 @@ pub struct RenderContext {
 #[derive(Copy, Clone, Debug)]
 pub struct RenderSettings {
     /// The SIMD level that should be used for rendering operations.
+    ///
+    /// Different levels can produce slightly different anti-aliasing results, as some of them
+    /// use fused multiply-adds. Use [`Level::fallback`] if the output needs to be reproducible
+    /// across machines.
     pub level: Level,
     /// The number of worker threads that should be used for rendering. Only has an effect
     /// if the `multithreading` feature is active.
@@ -161,6 +171,7 @@ impl RenderContext {
             blend_mode: BlendMode::default(),
             paint,
             render_settings: settings,
+            layer_blend_modes: vec![],
             mask: None,
             paint_transform,
             fill_rule,
@@ -225,13 +236,14 @@ impl RenderContext {
     }
 
     /// Fill a rectangle.
+    ///
+    /// Rectangles that stay axis-aligned under the current transform are rasterized directly,
+    /// which is considerably cheaper than filling them as a path.
     pub fn fill_rect(&mut self, rect: &Rect) {
         self.with_optional_filter(|ctx| {
-            ctx.rect_to_temp_path(rect);
             let paint = ctx.encode_current_paint();
-            ctx.dispatcher.fill_path(
-                &ctx.temp_path,
-                ctx.fill_rule,
+            ctx.dispatcher.fill_rect(
+                rect,
                 ctx.transform,
                 paint,
                 ctx.blend_mode,
@@ -320,11 +332,38 @@ impl RenderContext {
         GlyphRunBuilder::new(font.clone(), self.transform, self)
     }
 
+    /// Clear the caches of glyph outlines and hinting instances, releasing their memory.
+    ///
+    /// This is useful for reclaiming memory, for example after the used fonts changed. The
+    /// caches are populated again by the following glyph runs, so it is safe to call this at any
+    /// point between drawing commands.
+    #[cfg(feature = "text")]
+    pub fn clear_glyph_caches(&mut self) {
+        self.glyph_caches = Some(GlyphCaches::default());
+    }
+
+    /// Return the number of entries in the caches of glyph outlines and hinting instances, and
+    /// an estimate of the memory used by them.
+    #[cfg(feature = "text")]
+    pub fn glyph_cache_stats(&self) -> GlyphCacheStats {
+        self.glyph_caches
+            .as_ref()
+            .map(GlyphCaches::stats)
+            .unwrap_or_default()
+    }
+
     /// Push a new layer with the given properties.
     ///
     /// Note that the mask, if provided, needs to have the same size as the render context. Otherwise,
     /// it will be ignored. In addition to that, the mask will not be affected by the current
     /// transformation matrix in place.
+    ///
+    /// By default, the contents of the layer are blended as a whole with the backdrop using
+    /// `blend_mode`. If `isolate` is set, the contents are instead blended with each other
+    /// using `blend_mode` on a transparent background, like with `isolation: isolate` in CSS,
+    /// and the layer is composited onto the backdrop using source-over. The blend mode set with
+    /// [`RenderContext::set_blend_mode`] is then replaced by `blend_mode` until the layer is
+    /// popped.
     pub fn push_layer(
         &mut self,
         clip_path: Option<&BezPath>,
@@ -332,6 +371,7 @@ impl RenderContext {
         opacity: Option<f32>,
         mask: Option<Mask>,
         filter: Option<Filter>,
+        isolate: bool,
     ) {
         let mask = mask.and_then(|m| {
             if m.width() != self.width || m.height() != self.height {
@@ -341,8 +381,15 @@ impl RenderContext {
             }
         });
 
-        let blend_mode = blend_mode.unwrap_or_default();
+        let mut blend_mode = blend_mode.unwrap_or_default();
         let opacity = opacity.unwrap_or(1.0);
+        if isolate {
+            let outer = core::mem::replace(&mut self.blend_mode, blend_mode);
+            self.layer_blend_modes.push(Some(outer));
+            blend_mode = BlendMode::default();
+        } else {
+            self.layer_blend_modes.push(None);
+        }
 
         self.dispatcher.push_layer(
             clip_path,
@@ -361,17 +408,26 @@ impl RenderContext {
     /// See the explanation in the [clipping](https://github.com/linebender/vello/tree/main/sparse_strips/vello_cpu/examples)
     /// example for how this method differs from `push_clip_path`.
     pub fn push_clip_layer(&mut self, path: &BezPath) {
-        self.push_layer(Some(path), None, None, None, None);
+        self.push_layer(Some(path), None, None, None, None, false);
     }
 
     /// Push a new blend layer.
     pub fn push_blend_layer(&mut self, blend_mode: BlendMode) {
-        self.push_layer(None, Some(blend_mode), None, None, None);
+        self.push_layer(None, Some(blend_mode), None, None, None, false);
     }
 
     /// Push a new opacity layer.
     pub fn push_opacity_layer(&mut self, opacity: f32) {
-        self.push_layer(None, None, Some(opacity), None, None);
+        self.push_layer(None, None, Some(opacity), None, None, false);
+    }
+
+    /// Push a new isolated layer, whose contents are blended with each other using
+    /// `blend_mode`.
+    ///
+    /// See [`RenderContext::push_layer`] for how this differs from
+    /// [`RenderContext::push_blend_layer`].
+    pub fn push_isolated_layer(&mut self, blend_mode: BlendMode) {
+        self.push_layer(None, Some(blend_mode), None, None, None, true);
     }
 
     /// Push a new mask layer. The mask needs to have the same dimensions as the
@@ -381,16 +437,19 @@ impl RenderContext {
     /// See the explanation in the [masking](https://github.com/linebender/vello/tree/main/sparse_strips/masking/examples)
     /// example for how this method differs from `set_mask`.
     pub fn push_mask_layer(&mut self, mask: Mask) {
-        self.push_layer(None, None, None, Some(mask), None);
+        self.push_layer(None, None, None, Some(mask), None, false);
     }
 
     /// Push a filter layer that affects all subsequent drawing operations.
     ///
+    /// Use [`Filter::with_bounds`] to restrict the region in which the filter is evaluated.
+    /// Content outside of that region is clipped.
+    ///
     /// WARNING: Note that filters are currently incomplete and experimental. In
     /// particular, they will lead to a panic when used in combination with
     /// multi-threaded rendering.
     pub fn push_filter_layer(&mut self, filter: Filter) {
-        self.push_layer(None, None, None, None, Some(filter));
+        self.push_layer(None, None, None, None, Some(filter), false);
     }
 
     /// Set the aliasing threshold.
@@ -402,6 +461,10 @@ impl RenderContext {
     /// the coverage is bigger than the threshold (between 0 and 255), otherwise
     /// it will not be painted at all.
     ///
+    /// Like the paint, the threshold applies to all following fills, strokes and glyphs until it
+    /// is changed again, so aliased and anti-aliased paths can be mixed in the same scene. Clip
+    /// paths use the threshold that is set when they are pushed, independently of their content.
+    ///
     /// Note that there is no performance benefit to disabling anti-aliasing and
     /// this functionality is simply provided for compatibility.
     pub fn set_aliasing_threshold(&mut self, aliasing_threshold: Option<u8>) {
@@ -410,6 +473,9 @@ impl RenderContext {
 
     /// Pop the last-pushed layer.
     pub fn pop_layer(&mut self) {
+        if let Some(Some(blend_mode)) = self.layer_blend_modes.pop() {
+            self.blend_mode = blend_mode;
+        }
         self.dispatcher.pop_layer();
     }
 
@@ -418,6 +484,17 @@ impl RenderContext {
         self.stroke = stroke;
     }
 
+    /// Set the dash offset of the current stroke, wrapped into a single period of its dash
+    /// pattern.
+    ///
+    /// This animates the dashes along a path, for example for "marching ants", without
+    /// rebuilding the path: keep increasing `dash_offset` from frame to frame, and the dashes
+    /// move continuously, also when the offset wraps around. The start and end caps of the
+    /// stroke are applied to both ends of every dash.
+    pub fn set_dash_offset(&mut self, dash_offset: f64) {
+        self.stroke.dash_offset = wrap_dash_offset(dash_offset, &self.stroke.dash_pattern);
+    }
+
     /// Get the current stroke
     pub fn stroke(&self) -> &Stroke {
         &self.stroke
@@ -525,6 +602,7 @@ impl RenderContext {
         #[cfg(feature = "text")]
         self.glyph_caches.as_mut().unwrap().maintain();
         self.blend_mode = BlendMode::default();
+        self.layer_blend_modes.clear();
     }
 
     /// Push a new clip path to the clip stack.
@@ -716,10 +794,7 @@ impl GlyphRenderer for RenderContext {
                 use vello_common::peniko::ImageSampler;
                 let old_transform = self.transform;
                 let old_paint = self.paint.clone();
-                let context_color = match old_paint {
-                    PaintType::Solid(s) => s,
-                    _ => BLACK,
-                };
+                let context_color = context_color(&old_paint);
 
                 let area = glyph.area;
 
@@ -801,6 +876,20 @@ impl GlyphRenderer for RenderContext {
         }
     }
 
+    fn stroke_glyph_with(
+        &mut self,
+        prepared_glyph: PreparedGlyph<'_>,
+        stroke: &Stroke,
+        paint: &PaintType,
+    ) {
+        // Simulate a `save` and `restore` of the stroke and paint around the glyph.
+        let old_stroke = core::mem::replace(&mut self.stroke, stroke.clone());
+        let old_paint = core::mem::replace(&mut self.paint, paint.clone());
+        self.stroke_glyph(prepared_glyph);
+        self.paint = old_paint;
+        self.stroke = old_stroke;
+    }
+
     fn take_glyph_caches(&mut self) -> GlyphCaches {
         self.glyph_caches.take().unwrap()
     }
@@ -876,7 +965,9 @@ impl Recordable for RenderContext {
 
     fn execute_recording(&mut self, recording: &Recording) {
         let (cached_strips, cached_alphas) = recording.get_cached_strips();
-        let adjusted_strips = self.prepare_cached_strips(cached_strips, cached_alphas);
+        let Some(adjusted_strips) = self.prepare_cached_strips(cached_strips, cached_alphas) else {
+            return;
+        };
 
         // Use pre-calculated strip start indices from when we generated the cache.
         let strip_start_indices = recording.get_strip_start_indices();
@@ -905,6 +996,18 @@ impl Recordable for RenderContext {
                     );
                     range_index += 1;
                 }
+                #[cfg(feature = "text")]
+                RenderCommand::StrokeOutlineGlyphWith((_, _, _, paint)) => {
+                    // Simulate a `save` and `restore` of the paint around the glyph.
+                    let old_paint = core::mem::replace(&mut self.paint, paint.clone());
+                    self.process_geometry_command(
+                        strip_start_indices,
+                        range_index,
+                        &adjusted_strips,
+                    );
+                    self.paint = old_paint;
+                    range_index += 1;
+                }
                 RenderCommand::SetPaint(paint) => {
                     self.set_paint(paint.clone());
                 }
@@ -942,8 +1045,13 @@ impl Recordable for RenderContext {
                         *opacity,
                         mask.clone(),
                         filter.clone(),
+                        false,
                     );
                 }
+                // The strips of clip paths are not cached yet, so generate them here.
+                RenderCommand::PushClip(clip_path) => {
+                    self.push_clip_layer(clip_path);
+                }
                 RenderCommand::PopLayer => {
                     self.pop_layer();
                 }
@@ -1011,10 +1119,8 @@ impl RenderContext {
                     strip_start_indices.push(start_index);
                 }
                 RenderCommand::FillRect(rect) => {
-                    self.rect_to_temp_path(rect);
-                    strip_generator.generate_filled_path(
-                        &self.temp_path,
-                        self.fill_rule,
+                    strip_generator.generate_filled_rect(
+                        rect,
                         self.transform,
                         self.aliasing_threshold,
                         &mut strip_storage,
@@ -1058,6 +1164,18 @@ impl RenderContext {
                     );
                     strip_start_indices.push(start_index);
                 }
+                #[cfg(feature = "text")]
+                RenderCommand::StrokeOutlineGlyphWith((path, glyph_transform, stroke, _)) => {
+                    strip_generator.generate_stroked_path(
+                        path,
+                        stroke,
+                        *glyph_transform,
+                        self.aliasing_threshold,
+                        &mut strip_storage,
+                        None,
+                    );
+                    strip_start_indices.push(start_index);
+                }
                 RenderCommand::SetTransform(transform) => {
                     self.transform = *transform;
                 }
@@ -1114,29 +1232,30 @@ impl RenderContext {
     }
 
     /// Prepare cached strips for rendering by adjusting indices.
+    ///
+    /// Returns `None` if the cached alphas don't fit into the alpha buffer.
     fn prepare_cached_strips(
         &mut self,
         cached_strips: &[Strip],
         cached_alphas: &[u8],
-    ) -> Vec<Strip> {
-        // Calculate offset for alpha indices based on current dispatcher's alpha buffer size.
-        let alpha_offset = {
-            let storage = self.dispatcher.strip_storage_mut();
-            let offset = storage.alphas.len() as u32;
-            // Extend the dispatcher's alpha buffer with cached alphas.
-            storage.alphas.extend(cached_alphas);
-
-            offset
-        };
+    ) -> Option<Vec<Strip>> {
+        // Extend the dispatcher's alpha buffer with cached alphas, and calculate the offset for
+        // alpha indices based on its previous size.
+        let alpha_offset = self
+            .dispatcher
+            .strip_storage_mut()
+            .extend_alphas(cached_alphas)?;
         // Create adjusted strips with corrected alpha indices.
-        cached_strips
-            .iter()
-            .map(move |strip| {
-                let mut adjusted_strip = *strip;
-                adjusted_strip.set_alpha_idx(adjusted_strip.alpha_idx() + alpha_offset);
-                adjusted_strip
-            })
-            .collect()
+        Some(
+            cached_strips
+                .iter()
+                .map(move |strip| {
+                    let mut adjusted_strip = *strip;
+                    adjusted_strip.set_alpha_idx(adjusted_strip.alpha_idx() + alpha_offset);
+                    adjusted_strip
+                })
+                .collect(),
+        )
     }
 
     /// Save the current rendering state.
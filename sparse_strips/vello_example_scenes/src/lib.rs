@@ -146,7 +146,7 @@ impl RenderingContext for RenderContext {
         mask: Option<Mask>,
         filter: Option<Filter>,
     ) {
-        self.push_layer(clip, blend_mode, alpha, mask, filter);
+        self.push_layer(clip, blend_mode, alpha, mask, filter, false);
     }
 
     fn pop_layer(&mut self) {
@@ -237,7 +237,7 @@ impl RenderingContext for Scene {
         mask: Option<Mask>,
         filter: Option<Filter>,
     ) {
-        self.push_layer(clip, blend_mode, alpha, mask, filter);
+        self.push_layer(clip, blend_mode, alpha, mask, filter, false);
     }
 
     fn pop_layer(&mut self) {
@@ -5,12 +5,15 @@
 //!
 //! This example demonstrates rendering an SVG file without a window or display.
 //! It takes an input SVG file and renders it to a PNG file using the hybrid CPU/GPU renderer.
+//! If no GPU adapter is available, for example on a headless server, it falls back to rendering
+//! with Vello CPU through [`FallbackRenderer`].
 
 use std::io::BufWriter;
 use vello_common::kurbo::{Affine, Stroke};
 use vello_common::pico_svg::{Item, PicoSvg};
-use vello_common::pixmap::Pixmap;
-use vello_hybrid::{DimensionConstraints, Scene};
+use vello_common::recording::{Recordable, Recorder, Recording};
+use vello_hybrid::DimensionConstraints;
+use vello_hybrid::fallback::FallbackRenderer;
 
 /// Main entry point for the headless rendering example.
 /// Takes two command line arguments:
@@ -30,132 +33,36 @@ async fn run() {
     let render_scale = 5.0;
     let parsed = PicoSvg::load(&svg, 1.0).expect("error parsing SVG");
 
-    let constraints = DimensionConstraints::default();
+    let instance = wgpu::Instance::default();
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::default(),
+            force_fallback_adapter: false,
+            compatible_surface: None,
+        })
+        .await;
+
+    // Large SVGs are scaled down to fit within the texture size the GPU supports.
+    let max_texture_dimension = adapter.as_ref().map_or(u32::from(u16::MAX), |adapter| {
+        adapter.limits().max_texture_dimension_2d
+    });
+    let constraints =
+        DimensionConstraints::default().with_max_texture_dimension(max_texture_dimension);
     let svg_width = parsed.size.width * render_scale;
     let svg_height = parsed.size.height * render_scale;
-    let (width, height) = constraints.calculate_dimensions(svg_width, svg_height);
+    let (width, height, scale) = constraints.calculate_dimensions_and_scale(svg_width, svg_height);
 
     let width = DimensionConstraints::convert_dimension(width);
     let height = DimensionConstraints::convert_dimension(height);
 
-    let mut scene = Scene::new(width, height);
-    render_svg(&mut scene, &parsed.items, Affine::scale(render_scale));
-
-    // Initialize wgpu device and queue for GPU rendering
-    let instance = wgpu::Instance::default();
-    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-        power_preference: wgpu::PowerPreference::default(),
-        force_fallback_adapter: false,
-        compatible_surface: None,
-    }))
-    .expect("Failed to find an appropriate adapter");
-    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
-        label: Some("Device"),
-        required_features: wgpu::Features::empty(),
-        ..Default::default()
-    }))
-    .expect("Failed to create device");
-
-    // Create a render target texture
-    let texture = device.create_texture(&wgpu::TextureDescriptor {
-        label: Some("Render Target"),
-        size: wgpu::Extent3d {
-            width: width.into(),
-            height: height.into(),
-            depth_or_array_layers: 1,
-        },
-        mip_level_count: 1,
-        sample_count: 1,
-        dimension: wgpu::TextureDimension::D2,
-        format: wgpu::TextureFormat::Rgba8Unorm,
-        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
-        view_formats: &[],
-    });
-    let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-    // Create renderer and render the scene to the texture
-    let mut renderer = vello_hybrid::Renderer::new(
-        &device,
-        &vello_hybrid::RenderTargetConfig {
-            format: texture.format(),
-            width: width.into(),
-            height: height.into(),
-        },
-    );
-    let render_size = vello_hybrid::RenderSize {
-        width: width.into(),
-        height: height.into(),
-    };
-    // Copy texture to buffer
-    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-        label: Some("Vello Render To Buffer"),
-    });
-    renderer
-        .render(
-            &scene,
-            &device,
-            &queue,
-            &mut encoder,
-            &render_size,
-            &texture_view,
-        )
-        .unwrap();
-
-    // Create a buffer to copy the texture data
-    let bytes_per_row = (u32::from(width) * 4).next_multiple_of(256);
-    let texture_copy_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-        label: Some("Output Buffer"),
-        size: u64::from(bytes_per_row) * u64::from(height),
-        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-        mapped_at_creation: false,
-    });
-
-    encoder.copy_texture_to_buffer(
-        wgpu::TexelCopyTextureInfo {
-            texture: &texture,
-            mip_level: 0,
-            origin: wgpu::Origin3d::ZERO,
-            aspect: wgpu::TextureAspect::All,
-        },
-        wgpu::TexelCopyBufferInfo {
-            buffer: &texture_copy_buffer,
-            layout: wgpu::TexelCopyBufferLayout {
-                offset: 0,
-                bytes_per_row: Some(bytes_per_row),
-                rows_per_image: None,
-            },
-        },
-        wgpu::Extent3d {
-            width: width.into(),
-            height: height.into(),
-            depth_or_array_layers: 1,
-        },
-    );
-    queue.submit([encoder.finish()]);
-
-    // Map the buffer for reading
-    texture_copy_buffer
-        .slice(..)
-        .map_async(wgpu::MapMode::Read, move |result| {
-            if result.is_err() {
-                panic!("Failed to map texture for reading");
-            }
-        });
-    device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
-
-    // Read back the pixel data
-    let mut img_data = Vec::with_capacity(usize::from(width) * usize::from(height));
-    for row in texture_copy_buffer
-        .slice(..)
-        .get_mapped_range()
-        .chunks_exact(bytes_per_row as usize)
-    {
-        img_data.extend_from_slice(bytemuck::cast_slice(&row[0..usize::from(width) * 4]));
+    let transform = Affine::scale(render_scale * scale);
+    if let Err(err) = &adapter {
+        eprintln!("Failed to find a GPU adapter ({err}), rendering with Vello CPU instead");
     }
-    texture_copy_buffer.unmap();
-
-    // Create the pixmap from the image data
-    let pixmap = Pixmap::from_parts(img_data, width, height);
+    let mut renderer = FallbackRenderer::new(adapter.as_ref().ok()).await;
+    let mut scene = renderer.create_scene(width, height);
+    draw_svg(&mut scene, &parsed.items, transform);
+    let pixmap = renderer.render_to_pixmap(&mut scene).unwrap();
 
     // Write the pixmap to a file
     let file = std::fs::File::create(output_filename).unwrap();
@@ -168,9 +75,19 @@ async fn run() {
         .unwrap();
 }
 
-fn render_svg(ctx: &mut Scene, items: &[Item], transform: Affine) {
-    ctx.set_transform(transform);
+/// Draw the SVG items into `ctx`.
+fn draw_svg(ctx: &mut impl Recordable, items: &[Item], transform: Affine) {
+    let mut recording = Recording::new();
+    ctx.record(&mut recording, |recorder| {
+        record_svg(recorder, items, transform);
+    });
+    ctx.prepare_recording(&mut recording);
+    ctx.execute_recording(&recording);
+}
+
+fn record_svg(ctx: &mut Recorder<'_>, items: &[Item], transform: Affine) {
     for item in items {
+        ctx.set_transform(transform);
         match item {
             Item::Fill(fill_item) => {
                 ctx.set_paint(fill_item.color);
@@ -183,8 +100,7 @@ fn render_svg(ctx: &mut Scene, items: &[Item], transform: Affine) {
                 ctx.stroke_path(&stroke_item.path);
             }
             Item::Group(group_item) => {
-                render_svg(ctx, &group_item.children, transform * group_item.affine);
-                ctx.set_transform(transform);
+                record_svg(ctx, &group_item.children, transform * group_item.affine);
             }
         }
     }
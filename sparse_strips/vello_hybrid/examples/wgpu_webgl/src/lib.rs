@@ -12,6 +12,7 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 use vello_common::{
+    coarse::WideTile,
     fearless_simd::Level,
     kurbo::{Affine, Point},
     paint::{ImageId, ImageSource},
@@ -90,6 +91,14 @@ impl RendererWrapper {
                     atlas_size: (max_texture_dimension_2d, max_texture_dimension_2d),
                     ..AtlasConfig::default()
                 },
+                lazy_pipelines: false,
+                linear_blending: false,
+                max_layer_depth: None,
+                lenient_slot_allocation: false,
+                deterministic_slot_allocation: false,
+                record_clip_slots: false,
+                wide_tile_width: WideTile::WIDTH,
+                max_anisotropy: 16,
             },
         );
 
@@ -594,3 +603,133 @@ pub async fn render_scene(scene: Scene, width: u16, height: u16) {
     queue.submit([encoder.finish()]);
     surface_texture.present();
 }
+
+/// Renders `scene` into an offscreen texture with the given `backends`, and reads back its
+/// pixels as premultiplied RGBA8.
+///
+/// Unlike [`render_scene`], this uses the device's full limits, so that with
+/// [`wgpu::Backends::BROWSER_WEBGPU`] the renderer can use storage buffers and compute shaders.
+pub async fn render_scene_to_pixels(
+    scene: &Scene,
+    width: u16,
+    height: u16,
+    backends: wgpu::Backends,
+) -> Vec<u8> {
+    let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+        backends,
+        ..Default::default()
+    });
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions::default())
+        .await
+        .expect("Adapter to be valid");
+    let required_limits = if backends == wgpu::Backends::GL {
+        wgpu::Limits {
+            max_texture_dimension_2d: adapter.limits().max_texture_dimension_2d,
+            max_buffer_size: adapter.limits().max_buffer_size,
+            ..wgpu::Limits::downlevel_webgl2_defaults()
+        }
+    } else {
+        adapter.limits()
+    };
+    let (device, queue) = adapter
+        .request_device(&wgpu::DeviceDescriptor {
+            label: None,
+            required_features: wgpu::Features::empty(),
+            required_limits,
+            ..Default::default()
+        })
+        .await
+        .expect("Device to be valid");
+
+    let (width, height) = (width as u32, height as u32);
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Target Texture"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let mut renderer = Renderer::new(
+        &device,
+        &RenderTargetConfig {
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            width,
+            height,
+        },
+    );
+
+    let mut encoder =
+        device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+    renderer
+        .render(
+            scene,
+            &device,
+            &queue,
+            &mut encoder,
+            &vello_hybrid::RenderSize { width, height },
+            &view,
+        )
+        .unwrap();
+
+    let bytes_per_row = (width * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Readback Buffer"),
+        size: u64::from(bytes_per_row * height),
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: None,
+            },
+        },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit([encoder.finish()]);
+
+    // On the web, mapping can't block, so wait for the callback through a promise.
+    let mut resolve = None;
+    let mapped = js_sys::Promise::new(&mut |resolve_fn, _| resolve = Some(resolve_fn));
+    let resolve = resolve.unwrap();
+    buffer
+        .slice(..)
+        .map_async(wgpu::MapMode::Read, move |result| {
+            result.expect("Failed to map texture for reading");
+            resolve.call0(&JsValue::NULL).unwrap();
+        });
+    wasm_bindgen_futures::JsFuture::from(mapped).await.unwrap();
+
+    let pixels = buffer
+        .slice(..)
+        .get_mapped_range()
+        .chunks_exact(bytes_per_row as usize)
+        .flat_map(|row| &row[..width as usize * 4])
+        .copied()
+        .collect();
+    buffer.unmap();
+
+    pixels
+}
@@ -8,7 +8,7 @@ mod wasm {
     use vello_common::peniko::{color::palette, kurbo::BezPath};
     use vello_hybrid::Scene;
     use wasm_bindgen_test::*;
-    use wgpu_webgl::render_scene;
+    use wgpu_webgl::{render_scene, render_scene_to_pixels};
 
     wasm_bindgen_test_configure!(run_in_browser);
 
@@ -30,4 +30,34 @@ mod wasm {
 
         render_scene(scene, 100, 100).await;
     }
+
+    #[wasm_bindgen_test]
+    async fn test_webgpu_matches_webgl2() {
+        console_error_panic_hook::set_once();
+
+        if !wgpu::util::is_browser_webgpu_supported().await {
+            log::warn!("WebGPU is not supported by this browser, skipping test");
+            return;
+        }
+
+        let mut scene = Scene::new(100, 100);
+
+        // Draw a blue triangle
+        let mut path = BezPath::new();
+        path.move_to((30.0, 40.0));
+        path.line_to((50.0, 20.0));
+        path.line_to((70.0, 40.0));
+        path.close_path();
+        scene.set_paint(palette::css::BLUE);
+        scene.fill_path(&path);
+
+        let webgl2 = render_scene_to_pixels(&scene, 100, 100, wgpu::Backends::GL).await;
+        let webgpu = render_scene_to_pixels(&scene, 100, 100, wgpu::Backends::BROWSER_WEBGPU).await;
+
+        assert!(
+            webgl2.iter().any(|&value| value != 0),
+            "triangle was not drawn"
+        );
+        assert_eq!(webgpu, webgl2, "WebGPU and WebGL2 output differ");
+    }
 }
@@ -175,6 +175,10 @@ impl ApplicationHandler for App<'_> {
             WindowEvent::Resized(size) => {
                 self.context
                     .resize_surface(surface, size.width, size.height);
+                if let Some(renderer) = &mut self.renderers[surface.dev_id] {
+                    let queue = &self.context.devices[surface.dev_id].queue;
+                    renderer.resize(queue, size.width, size.height);
+                }
                 self.scene = Scene::new(
                     u16::try_from(size.width).unwrap(),
                     u16::try_from(size.height).unwrap(),
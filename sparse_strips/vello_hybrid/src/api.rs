@@ -103,6 +103,7 @@ impl PaintScene for HybridScenePainter {
                         push_layer_command.opacity,
                         None,
                         None,
+                        false,
                     );
                 }
                 RenderCommand::PopLayer => self.scene.pop_layer(),
@@ -190,6 +191,19 @@ impl PaintScene for HybridScenePainter {
         )
     }
 
+    fn fill_blurred_rounded_rect(
+        &mut self,
+        transform: Affine,
+        color: Color,
+        rect: &kurbo::Rect,
+        radius: f32,
+        std_dev: f32,
+    ) {
+        self.scene.set_paint(color);
+        self.scene.set_transform(transform);
+        self.scene.fill_blurred_rounded_rect(rect, radius, std_dev);
+    }
+
     fn push_layer(
         &mut self,
         clip_transform: Affine,
@@ -211,6 +225,7 @@ impl PaintScene for HybridScenePainter {
             opacity,
             None,
             None,
+            false,
         );
     }
 
@@ -0,0 +1,263 @@
+// Copyright 2025 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering to pixmaps with a fallback to Vello CPU if no GPU is available.
+//!
+//! This is useful for headless environments such as servers or CI, which often don't have a GPU.
+//! Create a [`FallbackRenderer`] from the adapter `wgpu` found, if any, and draw into the
+//! [`FallbackScene`]s it creates through [`Recordable`], which both renderers implement.
+//!
+//! Images are referenced by the id they were uploaded to a GPU renderer with, so scenes drawing
+//! images aren't supported.
+
+use log::warn;
+use vello_common::pixmap::{Pixmap, PixmapView};
+use vello_common::recording::{Recordable, Recorder, Recording};
+use vello_cpu::{RenderContext, RenderMode};
+use wgpu::{Adapter, Device, Queue};
+
+use crate::{RenderError, RenderSize, RenderTargetConfig, Renderer, Scene};
+
+/// A scene created by a [`FallbackRenderer`], for the backend it renders with.
+#[derive(Debug)]
+pub enum FallbackScene {
+    /// A scene for the `wgpu` renderer.
+    Gpu(Scene),
+    /// A render context of Vello CPU.
+    Cpu(RenderContext),
+}
+
+impl FallbackScene {
+    /// Width of the scene in pixels.
+    pub fn width(&self) -> u16 {
+        match self {
+            Self::Gpu(scene) => scene.width(),
+            Self::Cpu(ctx) => ctx.width(),
+        }
+    }
+
+    /// Height of the scene in pixels.
+    pub fn height(&self) -> u16 {
+        match self {
+            Self::Gpu(scene) => scene.height(),
+            Self::Cpu(ctx) => ctx.height(),
+        }
+    }
+
+    /// Reset the scene to its initial state, so that it can be drawn again.
+    pub fn reset(&mut self) {
+        match self {
+            Self::Gpu(scene) => scene.reset(),
+            Self::Cpu(ctx) => ctx.reset(),
+        }
+    }
+}
+
+impl Recordable for FallbackScene {
+    fn record<F>(&mut self, recording: &mut Recording, f: F)
+    where
+        F: FnOnce(&mut Recorder<'_>),
+    {
+        match self {
+            Self::Gpu(scene) => scene.record(recording, f),
+            Self::Cpu(ctx) => ctx.record(recording, f),
+        }
+    }
+
+    fn prepare_recording(&mut self, recording: &mut Recording) {
+        match self {
+            Self::Gpu(scene) => scene.prepare_recording(recording),
+            Self::Cpu(ctx) => ctx.prepare_recording(recording),
+        }
+    }
+
+    fn execute_recording(&mut self, recording: &Recording) {
+        match self {
+            Self::Gpu(scene) => scene.execute_recording(recording),
+            Self::Cpu(ctx) => ctx.execute_recording(recording),
+        }
+    }
+}
+
+/// Renders scenes to pixmaps with the `wgpu` renderer, or with Vello CPU if no GPU is available.
+///
+/// Vello CPU renders with its `f32` pipeline, so both backends produce the same image up to
+/// rounding differences of one per channel.
+#[derive(Debug)]
+pub struct FallbackRenderer {
+    gpu: Option<GpuBackend>,
+}
+
+#[derive(Debug)]
+struct GpuBackend {
+    device: Device,
+    queue: Queue,
+    /// Created for the size of the first rendered scene.
+    renderer: Option<Renderer>,
+}
+
+impl FallbackRenderer {
+    /// Create a renderer that renders on `adapter`.
+    ///
+    /// Falls back to Vello CPU if `adapter` is `None`, for example because
+    /// [`wgpu::Instance::request_adapter`] failed, or if no device can be created on it.
+    pub async fn new(adapter: Option<&Adapter>) -> Self {
+        let Some(adapter) = adapter else {
+            return Self::new_cpu();
+        };
+
+        match adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: Some("Vello Hybrid Fallback Renderer"),
+                required_features: wgpu::Features::empty(),
+                ..Default::default()
+            })
+            .await
+        {
+            Ok((device, queue)) => Self {
+                gpu: Some(GpuBackend {
+                    device,
+                    queue,
+                    renderer: None,
+                }),
+            },
+            Err(err) => {
+                warn!("failed to create a device ({err}), rendering with Vello CPU instead");
+                Self::new_cpu()
+            }
+        }
+    }
+
+    /// Create a renderer that always renders with Vello CPU.
+    pub fn new_cpu() -> Self {
+        Self { gpu: None }
+    }
+
+    /// Whether this renderer renders with Vello CPU.
+    pub fn is_cpu(&self) -> bool {
+        self.gpu.is_none()
+    }
+
+    /// Create a scene to draw into, for the backend this renderer renders with.
+    pub fn create_scene(&self, width: u16, height: u16) -> FallbackScene {
+        if self.is_cpu() {
+            let settings = vello_cpu::RenderSettings {
+                render_mode: RenderMode::OptimizeQuality,
+                ..Default::default()
+            };
+            FallbackScene::Cpu(RenderContext::new_with(width, height, settings))
+        } else {
+            FallbackScene::Gpu(Scene::new(width, height))
+        }
+    }
+
+    /// Render `scene` into a new pixmap of the same size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scene` wasn't created by [`FallbackRenderer::create_scene`] of this renderer.
+    pub fn render_to_pixmap(&mut self, scene: &mut FallbackScene) -> Result<Pixmap, RenderError> {
+        match (scene, &mut self.gpu) {
+            (FallbackScene::Cpu(ctx), None) => {
+                ctx.flush();
+                let mut pixmap = Pixmap::new(ctx.width(), ctx.height());
+                ctx.render_to_pixmap(&mut pixmap);
+                Ok(pixmap)
+            }
+            (FallbackScene::Gpu(scene), Some(gpu)) => gpu.render_to_pixmap(scene),
+            _ => panic!("the scene was created for a different backend than the renderer's"),
+        }
+    }
+}
+
+impl GpuBackend {
+    fn render_to_pixmap(&mut self, scene: &Scene) -> Result<Pixmap, RenderError> {
+        let (width, height) = (scene.width(), scene.height());
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Fallback Render Target"),
+            size: wgpu::Extent3d {
+                width: width.into(),
+                height: height.into(),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let device = &self.device;
+        let renderer = self.renderer.get_or_insert_with(|| {
+            Renderer::new(
+                device,
+                &RenderTargetConfig {
+                    format: texture.format(),
+                    width: width.into(),
+                    height: height.into(),
+                },
+            )
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Fallback Render To Pixmap"),
+        });
+        renderer.render(
+            scene,
+            device,
+            &self.queue,
+            &mut encoder,
+            &RenderSize {
+                width: width.into(),
+                height: height.into(),
+            },
+            &texture_view,
+        )?;
+
+        let bytes_per_row =
+            (u32::from(width) * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Fallback Readback Buffer"),
+            size: u64::from(bytes_per_row) * u64::from(height),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            texture.size(),
+        );
+        self.queue.submit([encoder.finish()]);
+
+        buffer.slice(..).map_async(wgpu::MapMode::Read, |result| {
+            result.expect("failed to map fallback readback buffer");
+        });
+        device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .expect("failed to wait for fallback readback");
+
+        let pixmap = {
+            let data = buffer.slice(..).get_mapped_range();
+            let stride = u16::try_from(bytes_per_row / 4)
+                .expect("rows of a pixmap are at most `u16::MAX` pixels wide");
+            PixmapView::new(bytemuck::cast_slice(&data), width, height, stride).to_pixmap()
+        };
+        buffer.unmap();
+
+        Ok(pixmap)
+    }
+}
@@ -0,0 +1,472 @@
+// Copyright 2025 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Caching of rasterized glyphs in the image atlas.
+
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+use vello_common::fearless_simd::Level;
+use vello_common::glyph::{OutlineGlyph, OutlineGlyphKey};
+use vello_common::kurbo::{Affine, Rect, Shape};
+use vello_common::paint::{ImageId, PremulColor};
+use vello_common::peniko::Fill;
+use vello_common::peniko::color::PremulRgba8;
+use vello_common::pixmap::Pixmap;
+use vello_common::strip::Strip;
+use vello_common::strip_generator::{StripGenerator, StripStorage};
+use vello_common::tile::Tile;
+
+/// The number of subpixel positions per pixel that glyphs are rasterized at, horizontally and
+/// vertically.
+const SUBPIXEL_STEPS: f64 = 4.0;
+/// The largest width or height in pixels of a glyph that is cached. Larger glyphs are drawn
+/// as paths, as they are rarely repeated often enough to be worth the space in the atlas.
+const MAX_GLYPH_SIZE: u16 = 128;
+/// The number of [`GlyphAtlas::maintain`] calls after which an unused glyph is evicted.
+const MAX_ENTRY_AGE: u32 = 64;
+
+/// A cache of rasterized glyphs that are drawn from the image atlas of the renderer.
+///
+/// Without an atlas, every glyph is flattened and rasterized into strips whenever it is
+/// drawn. With an atlas, an outline glyph that is filled with a solid color is rasterized
+/// only once for each combination of glyph, transform (apart from the translation), color
+/// and subpixel position, in steps of a quarter pixel. The rasterized glyphs are uploaded
+/// into the image atlas of the renderer, after which drawing them is as cheap as drawing an
+/// image, so the CPU cost of text that stays the same between frames no longer depends on the
+/// complexity of its glyphs.
+///
+/// Since the scene is built without access to the renderer, a glyph is drawn as a path the
+/// first time it is seen, and only the following scenes use the atlas once the glyph has been
+/// uploaded by `Renderer::upload_glyphs` (or `WebGlRenderer::upload_glyphs`). Glyphs that
+/// haven't been used for a while, for example because the text was scaled, are evicted from
+/// the atlas, and glyphs at the new scale are rasterized again.
+///
+/// The atlas is enabled with [`Scene::set_glyph_atlas`](crate::Scene::set_glyph_atlas), and
+/// must always be used with the same renderer, whose image ids it holds.
+#[derive(Debug)]
+pub struct GlyphAtlas {
+    entries: HashMap<GlyphAtlasKey, GlyphAtlasEntry>,
+    /// The keys of the entries whose pixmaps still have to be uploaded.
+    pending: Vec<GlyphAtlasKey>,
+    /// Uploaded images of evicted entries, which still have to be destroyed.
+    evicted: Vec<ImageId>,
+    strip_generator: StripGenerator,
+    strip_storage: StripStorage,
+    serial: u32,
+}
+
+/// Statistics about the contents of a [`GlyphAtlas`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GlyphAtlasStats {
+    /// The number of glyphs that are uploaded into the image atlas.
+    pub uploaded: usize,
+    /// The number of rasterized glyphs that are waiting to be uploaded.
+    pub pending: usize,
+    /// The number of pixels that the uploaded and pending glyphs occupy.
+    pub pixels: usize,
+}
+
+/// The result of looking up a glyph in the [`GlyphAtlas`].
+#[derive(Debug)]
+pub(crate) enum AtlasGlyph {
+    /// The glyph is in the image atlas, and covers `rect` in device space when drawn with
+    /// the image's top left corner at the rectangle's origin.
+    Uploaded { image: ImageId, rect: Rect },
+    /// The glyph doesn't cover any pixels.
+    Empty,
+    /// The glyph isn't uploaded yet, or is too large for the atlas, and must be drawn as a path.
+    Missing,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+struct GlyphAtlasKey {
+    outline: OutlineGlyphKey,
+    /// The bits of the linear part of the glyph transform.
+    linear: [u64; 4],
+    /// The subpixel position, in steps of `1 / SUBPIXEL_STEPS` pixels.
+    subpixel: [u8; 2],
+    color: [u8; 4],
+}
+
+#[derive(Debug)]
+struct GlyphAtlasEntry {
+    /// The offset of the top left corner of the glyph's pixmap from the glyph's origin,
+    /// rounded down to whole pixels.
+    offset: [i32; 2],
+    width: u16,
+    height: u16,
+    state: EntryState,
+    serial: u32,
+}
+
+#[derive(Debug)]
+enum EntryState {
+    Pending(Pixmap),
+    Uploaded(ImageId),
+    Empty,
+}
+
+impl Default for GlyphAtlas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GlyphAtlas {
+    /// Create a new, empty glyph atlas.
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            pending: Vec::new(),
+            evicted: Vec::new(),
+            strip_generator: StripGenerator::new(
+                MAX_GLYPH_SIZE,
+                MAX_GLYPH_SIZE,
+                Level::try_detect().unwrap_or(Level::fallback()),
+            ),
+            strip_storage: StripStorage::default(),
+            serial: 0,
+        }
+    }
+
+    /// Evict all glyphs.
+    ///
+    /// Their images are destroyed by the next upload of the renderer.
+    pub fn clear(&mut self) {
+        for (_, entry) in self.entries.drain() {
+            if let EntryState::Uploaded(image) = entry.state {
+                self.evicted.push(image);
+            }
+        }
+        self.pending.clear();
+    }
+
+    /// Return the number of cached glyphs and the pixels they occupy.
+    pub fn stats(&self) -> GlyphAtlasStats {
+        let mut stats = GlyphAtlasStats::default();
+        for entry in self.entries.values() {
+            match entry.state {
+                EntryState::Pending(_) => stats.pending += 1,
+                EntryState::Uploaded(_) => stats.uploaded += 1,
+                EntryState::Empty => continue,
+            }
+            stats.pixels += usize::from(entry.width) * usize::from(entry.height);
+        }
+        stats
+    }
+
+    pub(crate) fn set_level(&mut self, level: Level) {
+        self.strip_generator.set_level(level);
+    }
+
+    /// Evict the glyphs that weren't drawn since the last `MAX_ENTRY_AGE` calls.
+    ///
+    /// Should be called once per scene.
+    pub(crate) fn maintain(&mut self) {
+        let serial = self.serial;
+        self.serial = self.serial.wrapping_add(1);
+        let evicted = &mut self.evicted;
+        let mut evicted_pending = false;
+        self.entries.retain(|_, entry| {
+            if serial.wrapping_sub(entry.serial) <= MAX_ENTRY_AGE {
+                return true;
+            }
+            match entry.state {
+                EntryState::Uploaded(image) => evicted.push(image),
+                EntryState::Pending(_) => evicted_pending = true,
+                EntryState::Empty => {}
+            }
+            false
+        });
+        if evicted_pending {
+            let entries = &self.entries;
+            self.pending.retain(|key| entries.contains_key(key));
+        }
+    }
+
+    /// Look up a glyph filled with `color`, rasterizing it if it isn't cached yet.
+    pub(crate) fn get_or_insert(
+        &mut self,
+        glyph: &OutlineGlyph<'_>,
+        transform: Affine,
+        color: PremulColor,
+    ) -> AtlasGlyph {
+        let [a, b, c, d, e, f] = transform.as_coeffs();
+        let (x, subpixel_x) = quantize(e);
+        let (y, subpixel_y) = quantize(f);
+        let key = GlyphAtlasKey {
+            outline: glyph.key,
+            linear: [a.to_bits(), b.to_bits(), c.to_bits(), d.to_bits()],
+            subpixel: [subpixel_x, subpixel_y],
+            color: color.as_premul_rgba8().to_u8_array(),
+        };
+
+        let entry = match self.entries.get_mut(&key) {
+            Some(entry) => entry,
+            None => {
+                let local_transform = Affine::new([
+                    a,
+                    b,
+                    c,
+                    d,
+                    f64::from(subpixel_x) / SUBPIXEL_STEPS,
+                    f64::from(subpixel_y) / SUBPIXEL_STEPS,
+                ]);
+                let Some(entry) = self.rasterize(glyph, local_transform, color) else {
+                    return AtlasGlyph::Missing;
+                };
+                if matches!(entry.state, EntryState::Pending(_)) {
+                    self.pending.push(key);
+                }
+                self.entries.entry(key).insert(entry).into_mut()
+            }
+        };
+        entry.serial = self.serial;
+
+        match entry.state {
+            EntryState::Uploaded(image) => {
+                let x0 = f64::from(x.saturating_add(entry.offset[0]));
+                let y0 = f64::from(y.saturating_add(entry.offset[1]));
+                AtlasGlyph::Uploaded {
+                    image,
+                    rect: Rect::new(
+                        x0,
+                        y0,
+                        x0 + f64::from(entry.width),
+                        y0 + f64::from(entry.height),
+                    ),
+                }
+            }
+            EntryState::Empty => AtlasGlyph::Empty,
+            EntryState::Pending(_) => AtlasGlyph::Missing,
+        }
+    }
+
+    /// Take the images of the evicted glyphs, which should be destroyed.
+    ///
+    /// This and [`GlyphAtlas::upload_pending`] are used by `Renderer::upload_glyphs`, and
+    /// allow renderers for other graphics APIs to maintain the atlas.
+    pub fn drain_evicted(&mut self) -> impl Iterator<Item = ImageId> + '_ {
+        self.evicted.drain(..)
+    }
+
+    /// Upload the rasterized glyphs that are waiting to be uploaded with `upload`, which
+    /// returns the id of the uploaded image.
+    pub fn upload_pending(&mut self, mut upload: impl FnMut(&Pixmap) -> ImageId) {
+        for key in self.pending.drain(..) {
+            let Some(entry) = self.entries.get_mut(&key) else {
+                continue;
+            };
+            if let EntryState::Pending(pixmap) = &entry.state {
+                entry.state = EntryState::Uploaded(upload(pixmap));
+            }
+        }
+    }
+
+    /// Rasterize a glyph, whose subpixel position is already part of `transform`.
+    ///
+    /// Returns `None` if the glyph is too large to be cached.
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "The size is checked against `MAX_GLYPH_SIZE`, and the offset saturates"
+    )]
+    fn rasterize(
+        &mut self,
+        glyph: &OutlineGlyph<'_>,
+        transform: Affine,
+        color: PremulColor,
+    ) -> Option<GlyphAtlasEntry> {
+        let bbox = transform.transform_rect_bbox(glyph.path.bounding_box());
+        if bbox.is_zero_area() || !bbox.is_finite() {
+            return Some(GlyphAtlasEntry {
+                offset: [0, 0],
+                width: 0,
+                height: 0,
+                state: EntryState::Empty,
+                serial: self.serial,
+            });
+        }
+
+        let x0 = bbox.x0.floor();
+        let y0 = bbox.y0.floor();
+        let width = bbox.x1.ceil() - x0;
+        let height = bbox.y1.ceil() - y0;
+        let max_size = f64::from(MAX_GLYPH_SIZE);
+        if width > max_size || height > max_size {
+            return None;
+        }
+        let (width, height) = (width as u16, height as u16);
+
+        self.strip_generator.reset();
+        self.strip_generator.generate_filled_path(
+            glyph.path,
+            Fill::NonZero,
+            transform.then_translate((-x0, -y0).into()),
+            None,
+            &mut self.strip_storage,
+            None,
+        );
+        let mut pixmap = Pixmap::new(width, height);
+        fill_coverage(
+            &mut pixmap,
+            &self.strip_storage.strips,
+            &self.strip_storage.alphas,
+            color.as_premul_rgba8(),
+        );
+
+        Some(GlyphAtlasEntry {
+            offset: [x0 as i32, y0 as i32],
+            width,
+            height,
+            state: EntryState::Pending(pixmap),
+            serial: self.serial,
+        })
+    }
+}
+
+/// Split a coordinate into whole pixels and the subpixel step closest to the remainder.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "Coordinates that are too large to be drawn saturate"
+)]
+fn quantize(coordinate: f64) -> (i32, u8) {
+    let steps = (coordinate * SUBPIXEL_STEPS).round() as i64;
+    let subpixel_steps = SUBPIXEL_STEPS as i64;
+    let pixels = steps.div_euclid(subpixel_steps);
+    let subpixel = steps.rem_euclid(subpixel_steps);
+    (
+        pixels.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32,
+        subpixel as u8,
+    )
+}
+
+/// Write `color`, scaled by the coverage of the strips, into `pixmap`.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "The strips lie within the glyph's pixmap, which is at most `MAX_GLYPH_SIZE` wide"
+)]
+fn fill_coverage(pixmap: &mut Pixmap, strips: &[Strip], alphas: &[u8], color: PremulRgba8) {
+    let width = pixmap.width();
+    let height = pixmap.height();
+    let data = pixmap.data_mut();
+    let mut set = |x: u16, y: u16, alpha: u8| {
+        if x < width && y < height && alpha != 0 {
+            let scale = |c: u8| ((u16::from(c) * u16::from(alpha) + 127) / 255) as u8;
+            data[usize::from(y) * usize::from(width) + usize::from(x)] = PremulRgba8 {
+                r: scale(color.r),
+                g: scale(color.g),
+                b: scale(color.b),
+                a: scale(color.a),
+            };
+        }
+    };
+
+    for pair in strips.windows(2) {
+        let (strip, next_strip) = (pair[0], pair[1]);
+        let col = strip.alpha_idx() / u32::from(Tile::HEIGHT);
+        let next_col = next_strip.alpha_idx() / u32::from(Tile::HEIGHT);
+        let strip_width = next_col.saturating_sub(col);
+
+        for i in 0..strip_width {
+            let alpha_idx = ((col + i) * u32::from(Tile::HEIGHT)) as usize;
+            let x = strip.x.saturating_add(i as u16);
+            for (row, alpha) in alphas[alpha_idx..][..usize::from(Tile::HEIGHT)]
+                .iter()
+                .enumerate()
+            {
+                set(x, strip.y + row as u16, *alpha);
+            }
+        }
+
+        if next_strip.fill_gap() && strip.strip_y() == next_strip.strip_y() {
+            let x0 = strip.x.saturating_add(strip_width as u16);
+            for x in x0..next_strip.x.min(width) {
+                for row in 0..Tile::HEIGHT {
+                    set(x, strip.y + row, u8::MAX);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EntryState, quantize};
+    use crate::Scene;
+    use alloc::sync::Arc;
+    use vello_common::glyph::Glyph;
+    use vello_common::kurbo::Affine;
+    use vello_common::peniko::color::palette::css::REBECCA_PURPLE;
+    use vello_common::peniko::{Blob, FontData};
+    use vello_common::pixmap::Pixmap;
+
+    #[test]
+    fn quantize_rounds_to_nearest_subpixel_step() {
+        assert_eq!(quantize(10.0), (10, 0));
+        assert_eq!(quantize(10.3), (10, 1));
+        assert_eq!(quantize(10.9), (11, 0));
+        assert_eq!(quantize(-0.25), (-1, 3));
+    }
+
+    #[test]
+    fn rasterized_glyphs_match_vello_cpu() {
+        const ROBOTO: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+        let font = FontData::new(Blob::new(Arc::new(ROBOTO)), 0);
+        let transform = Affine::translate((20.75, 40.0)).pre_rotate(0.2);
+        let glyph = Glyph {
+            id: 43,
+            x: 0.0,
+            y: 0.0,
+        };
+
+        let mut scene = Scene::new(64, 64);
+        scene.set_glyph_atlas(Some(super::GlyphAtlas::new()));
+        scene.set_transform(transform);
+        scene.set_paint(REBECCA_PURPLE);
+        scene
+            .glyph_run(&font)
+            .font_size(24.0)
+            .fill_glyphs(core::iter::once(glyph));
+
+        let mut ctx = vello_cpu::RenderContext::new(64, 64);
+        ctx.set_transform(transform);
+        ctx.set_paint(REBECCA_PURPLE);
+        ctx.glyph_run(&font)
+            .font_size(24.0)
+            .fill_glyphs(core::iter::once(glyph));
+        ctx.flush();
+        let mut expected = Pixmap::new(64, 64);
+        ctx.render_to_pixmap(&mut expected);
+
+        let glyph_atlas = scene.glyph_atlas().unwrap();
+        let [entry] = glyph_atlas.entries.values().collect::<alloc::vec::Vec<_>>()[..] else {
+            panic!("the glyph is cached once");
+        };
+        let EntryState::Pending(pixmap) = &entry.state else {
+            panic!("the glyph waits for being uploaded");
+        };
+        let mut covered = 0;
+        for y in 0..64 {
+            for x in 0..64 {
+                let atlas_x = i32::from(x) - 20 - entry.offset[0];
+                let atlas_y = i32::from(y) - 40 - entry.offset[1];
+                let actual = u16::try_from(atlas_x)
+                    .ok()
+                    .zip(u16::try_from(atlas_y).ok())
+                    .filter(|(x, y)| *x < pixmap.width() && *y < pixmap.height())
+                    .map(|(x, y)| pixmap.sample(x, y).to_u8_array())
+                    .unwrap_or_default();
+                let expected = expected.sample(x, y).to_u8_array();
+                covered += usize::from(expected[3] != 0);
+                assert!(
+                    actual
+                        .iter()
+                        .zip(expected)
+                        .all(|(actual, expected)| actual.abs_diff(expected) <= 1),
+                    "pixel ({x}, {y}) is {actual:?} in the atlas, but {expected:?} in vello_cpu"
+                );
+            }
+        }
+        assert!(covered > 50);
+    }
+}
@@ -9,10 +9,11 @@ use vello_common::encode::{EncodedGradient, GradientCacheKey};
 use vello_common::fearless_simd::{Level, Simd, dispatch};
 use vello_common::peniko::color::cache_key::CacheKey;
 
-/// Number of bytes per texel in the gradient texture.
-/// Gradient textures use `Rgba8Unorm` format (4 bytes per texel).
-/// This constant is used to convert between byte offsets and texel indices.
+/// Number of bytes per texel in `Rgba8Unorm` gradient textures.
 const BYTES_PER_TEXEL: u32 = 4;
+/// Number of bytes per texel in `Rgba32Float` gradient textures, see
+/// [`GradientRampCache::new`].
+const BYTES_PER_FLOAT_TEXEL: u32 = 16;
 
 #[derive(Debug)]
 pub(crate) struct GradientRampCache {
@@ -28,6 +29,8 @@ pub(crate) struct GradientRampCache {
     retained_count: u32,
     /// SIMD level used for gradient LUT generation.
     level: Level,
+    /// Whether the luts have 32-bit float channels rather than 8-bit ones.
+    float_luts: bool,
     /// Scratch space for maintaining the cache.
     scratch: ScratchSpace,
 }
@@ -53,7 +56,10 @@ struct ScratchSpace {
 
 impl GradientRampCache {
     /// Create a new gradient ramp cache with the specified retained count.
-    pub(crate) fn new(retained_count: u32, level: Level) -> Self {
+    ///
+    /// If `float_luts` is set, the luts are packed with 32-bit float channels for an `Rgba32Float`
+    /// texture, and with 8-bit channels for an `Rgba8Unorm` texture otherwise.
+    pub(crate) fn new(retained_count: u32, level: Level, float_luts: bool) -> Self {
         Self {
             epoch: 0,
             cache: HashMap::new(),
@@ -61,6 +67,7 @@ impl GradientRampCache {
             has_changed: false,
             retained_count,
             level,
+            float_luts,
             scratch: ScratchSpace::default(),
         }
     }
@@ -80,8 +87,9 @@ impl GradientRampCache {
         }
 
         // Generate new gradient LUT.
-        let lut_start = self.luts.len() as u32 / BYTES_PER_TEXEL;
-        let width = dispatch!(self.level, simd => generate_gradient_lut_impl(simd, gradient, &mut self.luts))
+        let lut_start = self.luts.len() as u32 / self.bytes_per_texel();
+        let float_luts = self.float_luts;
+        let width = dispatch!(self.level, simd => generate_gradient_lut_impl(simd, gradient, float_luts, &mut self.luts))
             as u32;
         let cached_ramp = CachedRamp { width, lut_start };
         self.has_changed = true;
@@ -100,6 +108,15 @@ impl GradientRampCache {
         self.evict(entries_to_remove_count);
     }
 
+    /// Get the number of bytes per texel of the packed luts.
+    pub(crate) fn bytes_per_texel(&self) -> u32 {
+        if self.float_luts {
+            BYTES_PER_FLOAT_TEXEL
+        } else {
+            BYTES_PER_TEXEL
+        }
+    }
+
     /// Get the size of the packed luts.
     pub(crate) fn luts_size(&self) -> usize {
         self.luts.len()
@@ -187,11 +204,12 @@ impl GradientRampCache {
         ramps_to_remove.sort_by_key(|(_, ramp)| ramp.lut_start);
 
         // Convert to byte ranges for easier processing
+        let bytes_per_texel = self.bytes_per_texel();
         let mut ranges_to_remove = ramps_to_remove
             .iter()
             .map(|(_, ramp)| {
-                let start = (ramp.lut_start * BYTES_PER_TEXEL) as usize;
-                let end = start + (ramp.width * BYTES_PER_TEXEL) as usize;
+                let start = (ramp.lut_start * bytes_per_texel) as usize;
+                let end = start + (ramp.width * bytes_per_texel) as usize;
                 (start, end)
             })
             .peekable();
@@ -270,13 +288,19 @@ pub(crate) struct CachedRamp {
 fn generate_gradient_lut_impl<S: Simd>(
     simd: S,
     gradient: &EncodedGradient,
+    float_luts: bool,
     output: &mut Vec<u8>,
 ) -> usize {
-    let lut = gradient.u8_lut(simd);
-    let bytes: &[u8] = bytemuck::cast_slice(lut.lut());
+    let (bytes, width): (&[u8], _) = if float_luts {
+        let lut = gradient.f32_lut(simd);
+        (bytemuck::cast_slice(lut.lut()), lut.width())
+    } else {
+        let lut = gradient.u8_lut(simd);
+        (bytemuck::cast_slice(lut.lut()), lut.width())
+    };
     output.reserve(bytes.len());
     output.extend_from_slice(bytes);
-    lut.width()
+    width
 }
 
 #[cfg(test)]
@@ -343,7 +367,7 @@ mod tests {
 
     #[test]
     fn test_cache_empty() {
-        let mut cache = GradientRampCache::new(5, Level::fallback());
+        let mut cache = GradientRampCache::new(5, Level::fallback(), false);
         cache.maintain();
 
         assert_eq!(cache.cache.len(), 0);
@@ -354,7 +378,7 @@ mod tests {
 
     #[test]
     fn test_unique_entry_creation() {
-        let mut cache = GradientRampCache::new(5, Level::fallback());
+        let mut cache = GradientRampCache::new(5, Level::fallback(), false);
         insert_entries(&mut cache, 4);
         cache.maintain();
 
@@ -365,7 +389,7 @@ mod tests {
 
     #[test]
     fn test_no_eviction_under_limit() {
-        let mut cache = GradientRampCache::new(5, Level::fallback());
+        let mut cache = GradientRampCache::new(5, Level::fallback(), false);
         insert_entries(&mut cache, 4);
         cache.maintain();
 
@@ -374,7 +398,7 @@ mod tests {
 
     #[test]
     fn test_no_eviction_at_limit() {
-        let mut cache = GradientRampCache::new(5, Level::fallback());
+        let mut cache = GradientRampCache::new(5, Level::fallback(), false);
         insert_entries(&mut cache, 5);
         cache.maintain();
 
@@ -383,7 +407,7 @@ mod tests {
 
     #[test]
     fn test_eviction_over_limit() {
-        let mut cache = GradientRampCache::new(5, Level::fallback());
+        let mut cache = GradientRampCache::new(5, Level::fallback(), false);
         insert_entries(&mut cache, 10);
         cache.maintain();
 
@@ -393,7 +417,7 @@ mod tests {
 
     #[test]
     fn test_lut_compaction_and_offset_updates() {
-        let mut cache = GradientRampCache::new(2, Level::fallback());
+        let mut cache = GradientRampCache::new(2, Level::fallback(), false);
 
         // Start from 1 to keep LUT sizes consistent, making it easier to test LUT size
         // before and after eviction.
@@ -434,9 +458,37 @@ mod tests {
         assert_eq!(cache.luts.len(), (total_width * BYTES_PER_TEXEL) as usize);
     }
 
+    #[test]
+    fn test_float_luts() {
+        let mut cache = GradientRampCache::new(2, Level::fallback(), false);
+        let mut float_cache = GradientRampCache::new(2, Level::fallback(), true);
+        for i in 1..5 {
+            let gradient = create_encoded_gradient(create_gradient(i as f32 / 10.0));
+            assert_eq!(
+                cache.get_or_create_ramp(&gradient),
+                float_cache.get_or_create_ramp(&gradient)
+            );
+        }
+        cache.maintain();
+        float_cache.maintain();
+
+        // Float luts have the same layout, with four bytes per channel.
+        assert_eq!(float_cache.luts_size(), cache.luts_size() * 4);
+        for (key, (ramp, _)) in &cache.cache {
+            assert_eq!(float_cache.cache[key].0.lut_start, ramp.lut_start);
+        }
+        let floats = float_cache
+            .luts
+            .chunks_exact(4)
+            .map(|bytes| f32::from_ne_bytes(bytes.try_into().unwrap()));
+        for (value, &byte) in floats.zip(&cache.luts) {
+            assert!((value * 255.0 - f32::from(byte)).abs() <= 0.5);
+        }
+    }
+
     #[test]
     fn test_correct_lru_eviction() {
-        let mut cache = GradientRampCache::new(3, Level::fallback());
+        let mut cache = GradientRampCache::new(3, Level::fallback(), false);
 
         // Insert 3 gradients to fill the cache
         let gradient1 = create_gradient(0.1);
@@ -493,7 +545,7 @@ mod tests {
 
     #[test]
     fn test_take_and_restore_luts() {
-        let mut cache = GradientRampCache::new(5, Level::fallback());
+        let mut cache = GradientRampCache::new(5, Level::fallback(), false);
 
         let gradient1 = create_gradient(0.1);
         let gradient2 = create_gradient(0.2);
@@ -526,7 +578,7 @@ mod tests {
 
     #[test]
     fn test_lut_start_invalidation() {
-        let mut cache = GradientRampCache::new(2, Level::fallback());
+        let mut cache = GradientRampCache::new(2, Level::fallback(), false);
 
         let gradient_1 = create_encoded_gradient(create_gradient(0.1));
         let gradient_2 = create_encoded_gradient(create_gradient(0.2));
@@ -1,6 +1,7 @@
 // Copyright 2025 the Vello Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use crate::mipmap::mip_chain_size;
 use crate::multi_atlas::{AtlasConfig, AtlasError, AtlasId, MultiAtlasManager};
 use alloc::vec::Vec;
 use guillotiere::AllocId;
@@ -17,6 +18,10 @@ pub(crate) struct ImageResource {
     pub(crate) atlas_id: AtlasId,
     /// The offset of the image within its atlas.
     pub(crate) offset: [u16; 2],
+    /// The number of mipmap levels stored next to the image, see [`crate::mipmap`].
+    pub(crate) mip_levels: u32,
+    /// The size of the atlas allocation, which also holds the mipmap levels.
+    pub(crate) allocated_size: [u16; 2],
     /// The atlas allocation ID for deallocation.
     atlas_alloc_id: AllocId,
 }
@@ -60,12 +65,38 @@ impl ImageCache {
     }
 
     /// Allocate an image in the cache.
+    pub(crate) fn allocate(&mut self, width: u32, height: u32) -> Result<ImageId, AtlasError> {
+        self.allocate_region(width, height, (width, height), 0)
+    }
+
+    /// Allocate an image together with its mipmap levels in the cache.
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "u16 is enough for the width/height"
+    )]
+    pub(crate) fn allocate_with_mipmaps(
+        &mut self,
+        width: u32,
+        height: u32,
+    ) -> Result<ImageId, AtlasError> {
+        let (chain_width, chain_height, mip_levels) = mip_chain_size(width as u16, height as u16);
+        self.allocate_region(width, height, (chain_width, chain_height), mip_levels)
+    }
+
     #[expect(
         clippy::cast_possible_truncation,
         reason = "u16 is enough for the offset and width/height"
     )]
-    pub(crate) fn allocate(&mut self, width: u32, height: u32) -> Result<ImageId, AtlasError> {
-        let atlas_alloc = self.atlas_manager.try_allocate(width, height)?;
+    fn allocate_region(
+        &mut self,
+        width: u32,
+        height: u32,
+        (allocated_width, allocated_height): (u32, u32),
+        mip_levels: u32,
+    ) -> Result<ImageId, AtlasError> {
+        let atlas_alloc = self
+            .atlas_manager
+            .try_allocate(allocated_width, allocated_height)?;
 
         let slot_idx = self.free_idxs.pop().unwrap_or_else(|| {
             // No free slots, append to vector
@@ -84,6 +115,8 @@ impl ImageCache {
                 atlas_alloc.allocation.rectangle.min.x as u16,
                 atlas_alloc.allocation.rectangle.min.y as u16,
             ],
+            mip_levels,
+            allocated_size: [allocated_width as u16, allocated_height as u16],
             atlas_alloc_id: atlas_alloc.allocation.id,
         };
         self.slots[slot_idx] = Some(image_resource);
@@ -100,8 +133,8 @@ impl ImageCache {
                 .deallocate(
                     image_resource.atlas_id,
                     image_resource.atlas_alloc_id,
-                    image_resource.width as u32,
-                    image_resource.height as u32,
+                    u32::from(image_resource.allocated_size[0]),
+                    u32::from(image_resource.allocated_size[1]),
                 )
                 .unwrap();
             self.free_idxs.push(index);
@@ -145,6 +178,29 @@ mod tests {
         assert_eq!(resource.offset, [0, 0]);
     }
 
+    #[test]
+    fn test_insert_image_with_mipmaps() {
+        let mut cache = ImageCache::new_with_config(AtlasConfig {
+            atlas_size: (ATLAS_SIZE, ATLAS_SIZE),
+            ..Default::default()
+        });
+
+        let id = cache.allocate_with_mipmaps(100, 60).unwrap();
+
+        let resource = cache.get(id).unwrap();
+        assert_eq!((resource.width, resource.height), (100, 60));
+        // 7 levels from 50x30 down to 1x1, stacked to the right of the image.
+        assert_eq!(resource.mip_levels, 7);
+        assert_eq!(resource.allocated_size, [150, 61]);
+
+        let stats = cache.atlas_manager().atlas_stats();
+        assert_eq!(stats[0].1.allocated_area, 150 * 61);
+        // The whole allocation is freed again.
+        cache.deallocate(id).unwrap();
+        let stats = cache.atlas_manager().atlas_stats();
+        assert_eq!(stats[0].1.allocated_area, 0);
+    }
+
     #[test]
     fn test_insert_multiple_images() {
         let mut cache = ImageCache::new_with_config(AtlasConfig {
@@ -24,6 +24,7 @@
 //!
 //! - `wgpu` (enabled by default): Enables the GPU rendering backend via wgpu and includes the required sparse shaders.
 //! - `wgpu_default` (enabled by default): Enables wgpu with its default hardware backends (such as Vulkan, Metal, and DX12).
+//! - `cpu_fallback`: Enables the `fallback` module, which renders to pixmaps with Vello CPU if no GPU is available.
 //! - `webgl`: Enables the WebGL rendering backend for browser support, using GLSL shaders for compatibility.
 //!
 //! If you need to customize the set of enabled wgpu features, disable this crate's default features then enable its `wgpu` feature.
@@ -36,7 +37,8 @@
 //!
 //! - `Scene`: Manages the render context and path processing on the CPU
 //! - `Renderer` or `WebGlRenderer`: Handles GPU resource management and executes draw operations
-//! - `Scheduler`: Manages and schedules draw operations on the renderer.
+//! - `Scheduler`: Manages and schedules draw operations on the renderer. It is exposed as
+//!   `StripScheduler`, so that renderers for other graphics APIs can be built on top of it.
 //!
 //! See the individual module documentation for more details on usage and implementation.
 
@@ -44,26 +46,37 @@
 
 extern crate alloc;
 
+mod glyph_atlas;
 mod gradient_cache;
 mod image_cache;
+mod mipmap;
 mod multi_atlas;
 mod render;
 mod scene;
-#[cfg(any(all(target_arch = "wasm32", feature = "webgl"), feature = "wgpu"))]
 mod schedule;
 
 pub mod api;
+#[cfg(feature = "cpu_fallback")]
+pub mod fallback;
 pub mod util;
 
+pub use glyph_atlas::{GlyphAtlas, GlyphAtlasStats};
 pub use multi_atlas::{AllocationStrategy, AtlasConfig};
 #[cfg(feature = "wgpu")]
-pub use render::{AtlasWriter, RenderTargetConfig, Renderer};
-pub use render::{Config, GpuStrip, RenderSize};
+pub use render::{
+    AtlasWriter, ClipSlotCapture, PassKind, PassTiming, RenderTargetConfig, Renderer,
+};
+pub use render::{ClipSlotUsage, Config, GpuSlotClear, GpuStrip, RenderSize, SlotParity};
 #[cfg(all(target_arch = "wasm32", feature = "webgl"))]
 pub use render::{WebGlAtlasWriter, WebGlRenderer, WebGlTextureWithDimensions};
-pub use scene::{RenderSettings, Scene};
+pub use scene::{FrozenScene, RenderSettings, RenderStateSnapshot, Scene};
+pub use schedule::{LoadOp, RendererBackend, StripScheduler};
 pub use util::DimensionConstraints;
+pub use vello_common::glyph::GlyphCacheStats;
 pub use vello_common::pixmap::Pixmap;
+#[cfg(debug_assertions)]
+pub use vello_common::strip_generator::AllocStats;
+pub use vello_common::strip_generator::{BufferCapacities, TileStats};
 
 use thiserror::Error;
 
@@ -73,11 +86,37 @@ pub enum RenderError {
     /// No slots available for rendering.
     ///
     /// This error is likely to occur if a scene has an extreme number of nested layers
-    /// (clipping, blending, masks, or opacity layers).
+    /// (clipping, blending, masks, or opacity layers). Set [`RenderSettings::lenient_slot_allocation`] to draw such scenes approximately
+    /// instead.
     ///
     /// TODO: Consider supporting more than a single column of slots in slot textures.
     #[error("No slots available for rendering")]
     SlotsExhausted,
+    /// The scene nests more layers than allowed by
+    /// [`RenderSettings::max_layer_depth`](crate::RenderSettings::max_layer_depth).
+    #[error("The scene nests more layers than the configured maximum")]
+    LayerDepthExceeded,
+    /// The scene needs more alpha values than the renderer can address.
+    ///
+    /// Paths whose alpha values would exceed
+    /// [`Strip::MAX_ALPHA_IDX`](vello_common::strip::Strip::MAX_ALPHA_IDX) are dropped while
+    /// the scene is built, and the alpha texture can only be addressed up to a column of
+    /// `2^24`, i.e. `2^26` alpha values. Splitting the scene into several smaller ones avoids
+    /// this.
+    #[error("The scene needs more alpha values than can be addressed")]
+    AlphasExhausted,
+    /// The scene was built with a different
+    /// [`RenderSettings::wide_tile_width`](crate::RenderSettings::wide_tile_width) than the
+    /// renderer.
+    #[error(
+        "The scene's wide tile width of {scene} pixels doesn't match the renderer's {renderer}"
+    )]
+    WideTileWidthMismatch {
+        /// The wide tile width of the scene.
+        scene: u16,
+        /// The wide tile width of the renderer.
+        renderer: u16,
+    },
     // TODO: Consider expanding `RenderError` to replace some `.unwrap` and `.expect`.
 }
 
@@ -0,0 +1,183 @@
+// Copyright 2025 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Mipmaps for images that are drawn smaller than their size.
+//!
+//! The mipmap levels of an image are stored in the same atlas allocation as the image itself:
+//! the image is at the top left, and its downsampled levels are stacked on top of each other
+//! to the right of it. Each level is half as large as the previous one, rounded up, so images
+//! whose sizes aren't powers of two are supported, down to a level of a single pixel.
+
+use alloc::vec::Vec;
+use vello_common::peniko::color::PremulRgba8;
+use vello_common::pixmap::Pixmap;
+
+/// The size of the atlas allocation for an image of the given size together with its mipmap
+/// levels, and the number of levels below the image itself.
+pub(crate) fn mip_chain_size(width: u16, height: u16) -> (u32, u32, u32) {
+    let (width, height) = (u32::from(width), u32::from(height));
+    let mut levels = 0;
+    let mut column_height = 0;
+    for (_, level_height) in level_sizes(width, height) {
+        levels += 1;
+        column_height += level_height;
+    }
+
+    if levels == 0 {
+        return (width, height, 0);
+    }
+    (width + width.div_ceil(2), height.max(column_height), levels)
+}
+
+/// Build the mipmap chain of `image`, laid out as it's stored in the atlas, and return it
+/// together with the number of levels below the image itself.
+///
+/// If the chain would be too large for a pixmap, the image is returned without mipmaps.
+pub(crate) fn build_mip_chain(image: &Pixmap) -> (Pixmap, u32) {
+    let (width, height) = (image.width(), image.height());
+    let (chain_width, chain_height, levels) = mip_chain_size(width, height);
+    let (Ok(chain_width), Ok(chain_height)) =
+        (u16::try_from(chain_width), u16::try_from(chain_height))
+    else {
+        return (image.clone(), 0);
+    };
+
+    let mut chain = Pixmap::new(chain_width, chain_height);
+    let chain_width = usize::from(chain_width);
+    let mut copy_level = |data: &[PremulRgba8], x: usize, y: usize, width: usize| {
+        for (row, pixels) in data.chunks_exact(width).enumerate() {
+            let start = (y + row) * chain_width + x;
+            chain.data_mut()[start..start + width].copy_from_slice(pixels);
+        }
+    };
+    copy_level(image.data(), 0, 0, usize::from(width));
+
+    let mut previous = (image.data().to_vec(), u32::from(width), u32::from(height));
+    let mut y = 0;
+    for (level_width, level_height) in level_sizes(u32::from(width), u32::from(height)) {
+        let level = downsample(
+            &previous.0,
+            previous.1,
+            previous.2,
+            level_width,
+            level_height,
+        );
+        copy_level(&level, usize::from(width), y, level_width as usize);
+        y += level_height as usize;
+        previous = (level, level_width, level_height);
+    }
+
+    (chain, levels)
+}
+
+/// The sizes of the mipmap levels below an image of the given size.
+fn level_sizes(width: u32, height: u32) -> impl Iterator<Item = (u32, u32)> {
+    let has_levels = width > 0 && height > 0;
+    core::iter::successors(Some((width, height)), move |&(width, height)| {
+        (has_levels && (width > 1 || height > 1)).then(|| (width.div_ceil(2), height.div_ceil(2)))
+    })
+    .skip(1)
+}
+
+/// Downsample a level by averaging each block of 2x2 pixels.
+///
+/// At the right and bottom edges of levels with an odd size, the blocks are cut off, and only
+/// the pixels inside the level are averaged.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "The average of `u8` channels fits in a `u8`."
+)]
+fn downsample(
+    data: &[PremulRgba8],
+    width: u32,
+    height: u32,
+    out_width: u32,
+    out_height: u32,
+) -> Vec<PremulRgba8> {
+    let (width, height) = (width as usize, height as usize);
+    let mut out = Vec::with_capacity(out_width as usize * out_height as usize);
+    for y in 0..out_height as usize {
+        for x in 0..out_width as usize {
+            let mut sum = [0_u32; 4];
+            let mut count = 0;
+            for sy in 2 * y..(2 * y + 2).min(height) {
+                for sx in 2 * x..(2 * x + 2).min(width) {
+                    let pixel = data[sy * width + sx];
+                    for (sum, channel) in sum.iter_mut().zip([pixel.r, pixel.g, pixel.b, pixel.a]) {
+                        *sum += u32::from(channel);
+                    }
+                    count += 1;
+                }
+            }
+
+            let [r, g, b, a] = sum.map(|sum| ((sum + count / 2) / count) as u8);
+            out.push(PremulRgba8 { r, g, b, a });
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_mip_chain, mip_chain_size};
+    use vello_common::peniko::color::PremulRgba8;
+    use vello_common::pixmap::Pixmap;
+
+    const BLACK: PremulRgba8 = PremulRgba8 {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+    const WHITE: PremulRgba8 = PremulRgba8 {
+        r: 255,
+        g: 255,
+        b: 255,
+        a: 255,
+    };
+
+    #[test]
+    fn chain_sizes_include_all_levels() {
+        // 8x8, 4x4, 2x2, 1x1.
+        assert_eq!(mip_chain_size(8, 8), (12, 8, 3));
+        // 5x3, 3x2, 2x1, 1x1: the levels are taller than the image.
+        assert_eq!(mip_chain_size(5, 3), (8, 4, 3));
+        // 1x4, 1x2, 1x1.
+        assert_eq!(mip_chain_size(1, 4), (2, 4, 2));
+        assert_eq!(mip_chain_size(1, 1), (1, 1, 0));
+        assert_eq!(mip_chain_size(0, 7), (0, 7, 0));
+    }
+
+    #[test]
+    fn checkerboard_averages_to_gray() {
+        let (width, height) = (5, 3);
+        let mut image = Pixmap::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                image.set_pixel(x, y, if (x + y) % 2 == 0 { WHITE } else { BLACK });
+            }
+        }
+
+        let (chain, levels) = build_mip_chain(&image);
+        assert_eq!((chain.width(), chain.height(), levels), (8, 4, 3));
+        for y in 0..height {
+            for x in 0..width {
+                assert_eq!(chain.sample(x, y), image.sample(x, y));
+            }
+        }
+
+        // The full 2x2 blocks of the first level average to gray.
+        let gray = |pixel: PremulRgba8| (pixel.r, pixel.a);
+        assert_eq!(gray(chain.sample(5, 0)), (128, 255));
+        assert_eq!(gray(chain.sample(6, 0)), (128, 255));
+        // The blocks at the odd right and bottom edges only average the pixels of the image.
+        assert_eq!(gray(chain.sample(7, 0)), (128, 255));
+        assert_eq!(gray(chain.sample(5, 1)), (128, 255));
+        assert_eq!(gray(chain.sample(7, 1)), (255, 255));
+        // The second and third levels follow below the first one.
+        assert_eq!(gray(chain.sample(5, 2)).1, 255);
+        assert_eq!(gray(chain.sample(6, 2)).1, 255);
+        assert_eq!(gray(chain.sample(5, 3)).1, 255);
+        assert_eq!(chain.sample(6, 3).a, 0);
+    }
+}
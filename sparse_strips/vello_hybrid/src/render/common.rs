@@ -9,6 +9,8 @@
 )]
 
 use bytemuck::{Pod, Zeroable};
+use vello_common::encode::EncodedBlurredRoundedRectangle;
+use vello_common::kurbo::Affine;
 
 // GPU paint structure sizes in texels (1 texel = 16 bytes for RGBA32Uint texture format).
 pub(crate) const GPU_ENCODED_IMAGE_SIZE_TEXELS: u32 = (size_of::<GpuEncodedImage>() / 16) as u32;
@@ -17,6 +19,8 @@ pub(crate) const GPU_LINEAR_GRADIENT_SIZE_TEXELS: u32 =
 pub(crate) const GPU_RADIAL_GRADIENT_SIZE_TEXELS: u32 =
     (size_of::<GpuRadialGradient>() / 16) as u32;
 pub(crate) const GPU_SWEEP_GRADIENT_SIZE_TEXELS: u32 = (size_of::<GpuSweepGradient>() / 16) as u32;
+pub(crate) const GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS: u32 =
+    (size_of::<GpuBlurredRoundedRect>() / 16) as u32;
 
 /// Dimensions of the rendering target.
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -42,6 +46,29 @@ pub struct Config {
     pub alphas_tex_width_bits: u32,
 }
 
+/// Identifies one of the two intermediate slot textures used for clipping and blending.
+///
+/// Nested layers alternate between the two textures, so the parity of a layer's depth
+/// determines which texture its contents are drawn into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotParity {
+    /// The slot texture at index 0, used by even layer depths.
+    Even,
+    /// The slot texture at index 1, used by odd layer depths.
+    Odd,
+}
+
+/// Records that a slot was claimed during the last render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClipSlotUsage {
+    /// Index of the slot within its texture.
+    ///
+    /// Slot `n` occupies the rows `n * Tile::HEIGHT..(n + 1) * Tile::HEIGHT` of the texture.
+    pub slot: usize,
+    /// The scheduling round in which the slot was claimed.
+    pub round: usize,
+}
+
 /// Represents a GPU strip for rendering.
 ///
 /// This struct corresponds to the `StripInstance` struct in the shader.
@@ -65,6 +92,44 @@ pub struct GpuStrip {
     pub paint: u32,
 }
 
+impl GpuStrip {
+    /// The number of `u32` vertex attributes that make up a strip instance.
+    ///
+    /// `x`/`y` and `width`/`dense_width` are each packed into a single attribute, so there is one
+    /// attribute per field of `StripInstance`, at locations `0..VERTEX_ATTRIBUTES` and byte offsets
+    /// `4 * location`.
+    pub const VERTEX_ATTRIBUTES: usize = size_of::<Self>() / size_of::<u32>();
+}
+
+const _: () = assert!(
+    size_of::<GpuStrip>().is_multiple_of(size_of::<u32>()),
+    "`GpuStrip` must consist of whole `u32` vertex attributes"
+);
+
+/// A slot to clear, and the color to clear it to.
+///
+/// This struct corresponds to the `SlotClear` struct in `clear_slots.wgsl`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Zeroable, Pod)]
+pub struct GpuSlotClear {
+    /// Index of the slot within its texture.
+    pub slot: u32,
+    /// The premultiplied color to clear the slot to, packed as by [`PremulRgba8::to_u32`].
+    ///
+    /// This is `0` for slots that are cleared to transparent, which is the case for all slots
+    /// except those of layers pushed with `Scene::push_backdrop_layer`.
+    ///
+    /// [`PremulRgba8::to_u32`]: vello_common::color::PremulRgba8::to_u32
+    pub color: u32,
+}
+
+impl GpuSlotClear {
+    /// Clear `slot` to transparent.
+    pub const fn transparent(slot: u32) -> Self {
+        Self { slot, color: 0 }
+    }
+}
+
 /// Different types of GPU encoded paints.
 #[derive(Debug)]
 pub(crate) enum GpuEncodedPaint {
@@ -76,6 +141,8 @@ pub(crate) enum GpuEncodedPaint {
     RadialGradient(GpuRadialGradient),
     /// An encoded sweep gradient.
     SweepGradient(GpuSweepGradient),
+    /// An encoded blurred, rounded rectangle.
+    BlurredRoundedRect(GpuBlurredRoundedRect),
 }
 
 impl GpuEncodedPaint {
@@ -87,6 +154,7 @@ impl GpuEncodedPaint {
             Self::LinearGradient(paint) => bytemuck::bytes_of(paint),
             Self::RadialGradient(paint) => bytemuck::bytes_of(paint),
             Self::SweepGradient(paint) => bytemuck::bytes_of(paint),
+            Self::BlurredRoundedRect(paint) => bytemuck::bytes_of(paint),
         }
     }
 
@@ -120,8 +188,12 @@ pub(crate) struct GpuEncodedImage {
     pub image_offset: u32,
     /// Transform matrix [a, b, c, d, tx, ty].
     pub transform: [f32; 6],
+    /// The number of mipmap levels stored next to the image in the atlas.
+    pub mip_levels: u32,
+    /// The maximum number of samples for anisotropic filtering, 1 if it's disabled.
+    pub max_anisotropy: u32,
     /// Padding for 16-byte alignment.
-    pub _padding: [u32; 3],
+    pub _padding: [u32; 1],
 }
 
 /// GPU encoded linear gradient data.
@@ -188,6 +260,64 @@ pub(crate) struct GpuSweepGradient {
     pub _padding: [u32; 2],
 }
 
+/// GPU encoded blurred, rounded rectangle data.
+/// Align to 16 bytes for `RGBA32Uint` alignment.
+///
+/// See `EncodedBlurredRoundedRectangle` in `vello_common` for the meaning of the blur parameters.
+#[repr(C, align(16))]
+#[derive(Debug, Clone, Copy, Zeroable, Pod)]
+#[allow(dead_code, reason = "Clippy fails when --no-default-features")]
+pub(crate) struct GpuBlurredRoundedRect {
+    /// Transform matrix [a, b, c, d, tx, ty] from view space to rectangle space.
+    pub transform: [f32; 6],
+    /// Exponent of the superellipse used to approximate the blurred corners.
+    pub exponent: f32,
+    /// Reciprocal of `exponent`.
+    pub recip_exponent: f32,
+    /// Scale factor applied to the resulting alpha.
+    pub scale: f32,
+    /// Reciprocal of the standard deviation.
+    pub std_dev_inv: f32,
+    /// The smaller of the rectangle's width and height.
+    pub min_edge: f32,
+    /// Adjusted width of the rectangle.
+    pub w: f32,
+    /// Adjusted height of the rectangle.
+    pub h: f32,
+    /// Width of the rectangle.
+    pub width: f32,
+    /// Height of the rectangle.
+    pub height: f32,
+    /// Corner radius adjusted for the blur.
+    pub r1: f32,
+    /// Premultiplied color [r, g, b, a].
+    pub color: [f32; 4],
+}
+
+impl GpuBlurredRoundedRect {
+    /// Encode a blurred, rounded rectangle for the GPU.
+    pub(crate) fn new(rect: &EncodedBlurredRoundedRectangle) -> Self {
+        // `vello_cpu` evaluates the rectangle at pixel corners, while fragments are shaded at
+        // pixel centers.
+        let transform = rect.transform * Affine::translate((-0.5, -0.5));
+
+        Self {
+            transform: transform.as_coeffs().map(|x| x as f32),
+            exponent: rect.exponent,
+            recip_exponent: rect.recip_exponent,
+            scale: rect.scale,
+            std_dev_inv: rect.std_dev_inv,
+            min_edge: rect.min_edge,
+            w: rect.w,
+            h: rect.h,
+            width: rect.width,
+            height: rect.height,
+            r1: rect.r1,
+            color: rect.color.as_premul_f32().components,
+        }
+    }
+}
+
 // Constants for packing extend_mode and texture_width.
 const EXTEND_MODE_MASK: u32 = 1 << 30;
 const TEXTURE_WIDTH_MASK: u32 = !EXTEND_MODE_MASK;
@@ -250,6 +380,17 @@ pub(crate) fn pack_image_params(
     (atlas_index << 6) | (extend_y << 4) | (extend_x << 2) | quality
 }
 
+/// The largest number of samples that anisotropic filtering takes for a pixel.
+const MAX_ANISOTROPY: u16 = 16;
+
+/// Clamp the [`max_anisotropy`](crate::RenderSettings::max_anisotropy) requested in the settings
+/// to what the device supports, where a device without support for anisotropic filtering
+/// supports 1.
+#[allow(dead_code, reason = "Clippy fails when --no-default-features")]
+pub(crate) fn clamped_max_anisotropy(requested: u16, supported: u16) -> u32 {
+    u32::from(requested.clamp(1, supported.clamp(1, MAX_ANISOTROPY)))
+}
+
 #[cfg(all(target_arch = "wasm32", feature = "webgl", feature = "wgpu"))]
 pub(crate) fn maybe_warn_about_webgl_feature_conflict() {
     use core::sync::atomic::{AtomicBool, Ordering};
@@ -270,3 +411,20 @@ For optimal performance and binary size on web targets, use only the dedicated W
     not(all(target_arch = "wasm32", feature = "webgl", feature = "wgpu"))
 ))]
 pub(crate) fn maybe_warn_about_webgl_feature_conflict() {}
+
+#[cfg(test)]
+mod tests {
+    use super::clamped_max_anisotropy;
+
+    #[test]
+    fn max_anisotropy_is_clamped_to_device_support() {
+        assert_eq!(clamped_max_anisotropy(16, 16), 16);
+        assert_eq!(clamped_max_anisotropy(64, 16), 16);
+        assert_eq!(clamped_max_anisotropy(8, 4), 4);
+        // Devices without support for anisotropic filtering fall back to trilinear filtering.
+        assert_eq!(clamped_max_anisotropy(16, 1), 1);
+        assert_eq!(clamped_max_anisotropy(16, 0), 1);
+        assert_eq!(clamped_max_anisotropy(0, 16), 1);
+        assert_eq!(clamped_max_anisotropy(u16::MAX, u16::MAX), 16);
+    }
+}
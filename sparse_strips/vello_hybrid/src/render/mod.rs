@@ -14,9 +14,9 @@ mod webgl;
 #[cfg(feature = "wgpu")]
 mod wgpu;
 
-pub use common::{Config, GpuStrip, RenderSize};
+pub use common::{ClipSlotUsage, Config, GpuSlotClear, GpuStrip, RenderSize, SlotParity};
 
 #[cfg(all(target_arch = "wasm32", feature = "webgl"))]
 pub use webgl::{WebGlAtlasWriter, WebGlRenderer, WebGlTextureWithDimensions};
 #[cfg(feature = "wgpu")]
-pub use wgpu::{AtlasWriter, RenderTargetConfig, Renderer};
+pub use wgpu::{AtlasWriter, ClipSlotCapture, PassKind, PassTiming, RenderTargetConfig, Renderer};
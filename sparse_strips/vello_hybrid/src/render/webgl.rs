@@ -21,21 +21,23 @@ only break in edge cases, and some of them are also only related to conversions
 )]
 
 use crate::{
-    AtlasConfig, GpuStrip, RenderError, RenderSettings, RenderSize,
+    AtlasConfig, GlyphAtlas, GpuSlotClear, GpuStrip, RenderError, RenderSettings, RenderSize,
     gradient_cache::GradientRampCache,
     image_cache::{ImageCache, ImageResource},
+    mipmap::build_mip_chain,
     multi_atlas::AtlasId,
     render::{
         Config,
         common::{
-            GPU_ENCODED_IMAGE_SIZE_TEXELS, GPU_LINEAR_GRADIENT_SIZE_TEXELS,
-            GPU_RADIAL_GRADIENT_SIZE_TEXELS, GPU_SWEEP_GRADIENT_SIZE_TEXELS, GpuEncodedImage,
+            GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS, GPU_ENCODED_IMAGE_SIZE_TEXELS,
+            GPU_LINEAR_GRADIENT_SIZE_TEXELS, GPU_RADIAL_GRADIENT_SIZE_TEXELS,
+            GPU_SWEEP_GRADIENT_SIZE_TEXELS, GpuBlurredRoundedRect, GpuEncodedImage,
             GpuEncodedPaint, GpuLinearGradient, GpuRadialGradient, GpuSweepGradient,
-            pack_image_offset, pack_image_params, pack_image_size, pack_radial_kind_and_swapped,
-            pack_texture_width_and_extend_mode,
+            clamped_max_anisotropy, pack_image_offset, pack_image_params, pack_image_size,
+            pack_radial_kind_and_swapped, pack_texture_width_and_extend_mode,
         },
     },
-    scene::Scene,
+    scene::{FrozenScene, Scene, SceneView},
     schedule::{LoadOp, RendererBackend, Scheduler, SchedulerState},
 };
 
@@ -45,7 +47,6 @@ use alloc::vec::Vec;
 use bytemuck::{Pod, Zeroable};
 use core::fmt::Debug;
 use vello_common::{
-    coarse::WideTile,
     encode::{EncodedGradient, EncodedKind, EncodedPaint, MAX_GRADIENT_LUT_SIZE, RadialKind},
     kurbo::Affine,
     paint::ImageSource,
@@ -75,6 +76,22 @@ fn get_max_texture_dimension_2d(gl: &WebGl2RenderingContext) -> u32 {
         .unwrap() as u32
 }
 
+/// Query the WebGL context for the maximum anisotropy of texture filtering, which is 1 if the
+/// `EXT_texture_filter_anisotropic` extension isn't supported.
+fn get_max_anisotropy(gl: &WebGl2RenderingContext) -> u16 {
+    /// `MAX_TEXTURE_MAX_ANISOTROPY_EXT` from `EXT_texture_filter_anisotropic`.
+    const MAX_TEXTURE_MAX_ANISOTROPY_EXT: u32 = 0x84FF;
+
+    match gl.get_extension("EXT_texture_filter_anisotropic") {
+        Ok(Some(_)) => gl
+            .get_parameter(MAX_TEXTURE_MAX_ANISOTROPY_EXT)
+            .ok()
+            .and_then(|max| max.as_f64())
+            .map_or(1, |max| max as u16),
+        _ => 1,
+    }
+}
+
 /// Vello Hybrid's WebGL2 Renderer.
 #[derive(Debug)]
 pub struct WebGlRenderer {
@@ -94,6 +111,8 @@ pub struct WebGlRenderer {
     paint_idxs: Vec<u32>,
     /// Gradient cache for storing gradient ramps.
     gradient_cache: GradientRampCache,
+    /// The maximum number of samples for anisotropic filtering of mipmapped images.
+    max_anisotropy: u32,
 }
 
 impl WebGlRenderer {
@@ -105,6 +124,10 @@ impl WebGlRenderer {
     /// Creates a new WebGL2 renderer with specific settings.
     pub fn new_with(canvas: &web_sys::HtmlCanvasElement, settings: RenderSettings) -> Self {
         super::common::maybe_warn_about_webgl_feature_conflict();
+        assert!(
+            !settings.linear_blending,
+            "linear blending is not supported by the WebGL renderer"
+        );
 
         // The WebGL context must be created with anti-aliasing disabled such that we can blit the
         // view framebuffer onto the default framebuffer. This technique is required for the code
@@ -149,24 +172,79 @@ impl WebGlRenderer {
         // and the maximum gradient LUT size - worst case scenario.
         let max_gradient_cache_size =
             max_texture_dimension_2d * max_texture_dimension_2d / MAX_GRADIENT_LUT_SIZE as u32;
-        let gradient_cache = GradientRampCache::new(max_gradient_cache_size, settings.level);
+        let gradient_cache = GradientRampCache::new(max_gradient_cache_size, settings.level, false);
+        let max_anisotropy =
+            clamped_max_anisotropy(settings.max_anisotropy, get_max_anisotropy(&gl));
 
         Self {
-            programs: WebGlPrograms::new(gl.clone(), &image_cache, total_slots),
-            scheduler: Scheduler::new(total_slots),
+            programs: WebGlPrograms::new(
+                gl.clone(),
+                &image_cache,
+                total_slots,
+                settings.checked_wide_tile_width(),
+            ),
+            scheduler: Scheduler::new(total_slots, &settings),
             scheduler_state: SchedulerState::default(),
             gl,
             image_cache,
             encoded_paints: Vec::new(),
             paint_idxs: Vec::new(),
             gradient_cache,
+            max_anisotropy,
         }
     }
 
+    /// Resize the renderer for a drawing buffer of `width` by `height` pixels.
+    ///
+    /// This only updates the state that depends on the size of the drawing buffer, and keeps
+    /// the programs, textures and caches, so it is much cheaper than creating a new renderer
+    /// when the canvas is resized. The canvas's drawing buffer itself must be resized by the
+    /// caller.
+    ///
+    /// [`WebGlRenderer::render`] performs the same update if it is passed a different
+    /// [`RenderSize`], so calling this is not required, but allows doing the work when the
+    /// resize happens.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let max_texture_dimension_2d = self.programs.resources.max_texture_dimension_2d;
+        self.programs.maybe_update_config_buffer(
+            &self.gl,
+            max_texture_dimension_2d,
+            &RenderSize { width, height },
+        );
+    }
+
+    /// Returns the number of clip slots in each of the two slot textures.
+    ///
+    /// This depends on the context, as the slot textures are as high as its
+    /// `MAX_TEXTURE_SIZE` allows, with each slot being [`Tile::HEIGHT`] pixels high.
+    pub fn slot_count(&self) -> usize {
+        self.scheduler.total_slots()
+    }
+
     /// Render `scene` using WebGL2
     ///
     /// This method creates GPU resources as needed and schedules potentially multiple draw calls.
     pub fn render(&mut self, scene: &Scene, render_size: &RenderSize) -> Result<(), RenderError> {
+        scene.with_view(|scene| self.render_view(&scene, render_size))
+    }
+
+    /// Render a [`FrozenScene`] using WebGL2.
+    ///
+    /// This behaves like [`WebGlRenderer::render`], but takes a scene snapshot created by
+    /// [`Scene::freeze`], so that the original scene can be reused while this one renders.
+    pub fn render_frozen(
+        &mut self,
+        scene: &FrozenScene,
+        render_size: &RenderSize,
+    ) -> Result<(), RenderError> {
+        self.render_view(&scene.view(), render_size)
+    }
+
+    fn render_view(
+        &mut self,
+        scene: &SceneView<'_>,
+        render_size: &RenderSize,
+    ) -> Result<(), RenderError> {
         debug_assert_eq!(
             RenderSize {
                 width: self.gl.drawing_buffer_width() as u32,
@@ -176,7 +254,7 @@ impl WebGlRenderer {
             "Render size must match drawing buffer size"
         );
 
-        self.prepare_gpu_encoded_paints(&scene.encoded_paints);
+        self.prepare_gpu_encoded_paints(scene.encoded_paints);
         // TODO: For the time being, we upload the entire alpha buffer as one big chunk. As a future
         // refinement, we could have a bounded alpha buffer, and break draws when the alpha
         // buffer fills.
@@ -184,7 +262,7 @@ impl WebGlRenderer {
             &self.gl,
             &mut self.gradient_cache,
             &self.encoded_paints,
-            &mut scene.strip_storage.borrow_mut().alphas,
+            scene.alphas,
             render_size,
             &self.paint_idxs,
         );
@@ -271,9 +349,48 @@ impl WebGlRenderer {
         &mut self,
         writer: &T,
     ) -> vello_common::paint::ImageId {
+        let image_id = self
+            .image_cache
+            .allocate(writer.width(), writer.height())
+            .unwrap();
+        self.write_image(image_id, writer);
+
+        image_id
+    }
+
+    /// Upload an image together with its mipmaps to cache and atlas. Returns the `ImageId`.
+    ///
+    /// This is the WebGL analogue of the wgpu Renderer's `upload_image_with_mipmaps` method.
+    /// The mipmaps are generated on the CPU and stored next to the image in the atlas. Drawing
+    /// the image smaller than its size with [`ImageQuality::Medium`] or [`ImageQuality::High`]
+    /// samples it trilinearly from the two closest mipmap levels, while [`ImageQuality::Low`]
+    /// still samples the full-size image.
+    ///
+    /// [`ImageQuality::Low`]: vello_common::peniko::ImageQuality::Low
+    /// [`ImageQuality::Medium`]: vello_common::peniko::ImageQuality::Medium
+    /// [`ImageQuality::High`]: vello_common::peniko::ImageQuality::High
+    pub fn upload_image_with_mipmaps(&mut self, pixmap: &Pixmap) -> vello_common::paint::ImageId {
+        let (chain, mip_levels) = build_mip_chain(pixmap);
+        let (width, height) = (u32::from(pixmap.width()), u32::from(pixmap.height()));
+        let image_id = if mip_levels > 0 {
+            self.image_cache.allocate_with_mipmaps(width, height)
+        } else {
+            self.image_cache.allocate(width, height)
+        }
+        .unwrap();
+        self.write_image(image_id, &chain);
+
+        image_id
+    }
+
+    /// Write the pixels of `writer` to the atlas allocation of `image_id`.
+    fn write_image<T: WebGlAtlasWriter>(
+        &mut self,
+        image_id: vello_common::paint::ImageId,
+        writer: &T,
+    ) {
         let width = writer.width();
         let height = writer.height();
-        let image_id = self.image_cache.allocate(width, height).unwrap();
         let image_resource = self
             .image_cache
             .get(image_id)
@@ -294,8 +411,18 @@ impl WebGlRenderer {
             width,
             height,
         );
+    }
 
-        image_id
+    /// Upload the glyphs that were rasterized into `glyph_atlas` while building scenes, and
+    /// destroy the images of the glyphs it evicted.
+    ///
+    /// Call this after building a scene with the atlas, before rendering it, so that the
+    /// following scenes can draw these glyphs from the image atlas.
+    pub fn upload_glyphs(&mut self, glyph_atlas: &mut GlyphAtlas) {
+        for image_id in glyph_atlas.drain_evicted() {
+            self.destroy_image(image_id);
+        }
+        glyph_atlas.upload_pending(|pixmap| self.upload_image(pixmap));
     }
 
     /// Destroy an image from the cache and clear the allocated slot in the atlas.
@@ -307,8 +434,8 @@ impl WebGlRenderer {
                     image_resource.offset[0] as u32,
                     image_resource.offset[1] as u32,
                 ],
-                image_resource.width as u32,
-                image_resource.height as u32,
+                u32::from(image_resource.allocated_size[0]),
+                u32::from(image_resource.allocated_size[1]),
             );
         }
     }
@@ -386,11 +513,11 @@ impl WebGlRenderer {
                     self.encoded_paints[encoded_paint_idx] = gpu_gradient;
                     current_idx += gradient_size_texels;
                 }
-                EncodedPaint::BlurredRoundedRect(_blurred_rect) => {
-                    // TODO: Blurred rounded rectangles are not yet supported
-                    log::warn!(
-                        "Blurred rounded rectangles are not yet supported in sparse strips hybrid renderer"
+                EncodedPaint::BlurredRoundedRect(blurred_rect) => {
+                    self.encoded_paints[encoded_paint_idx] = GpuEncodedPaint::BlurredRoundedRect(
+                        GpuBlurredRoundedRect::new(blurred_rect),
                     );
+                    current_idx += GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS;
                 }
             }
         }
@@ -418,7 +545,9 @@ impl WebGlRenderer {
             image_size,
             image_offset,
             transform,
-            _padding: [0, 0, 0],
+            mip_levels: image_resource.mip_levels,
+            max_anisotropy: self.max_anisotropy,
+            _padding: [0],
         })
     }
 
@@ -512,6 +641,8 @@ struct WebGlPrograms {
     render_size: RenderSize,
     /// Scratch buffer for staging encoded paints texture data.
     encoded_paints_data: Vec<u8>,
+    /// Scratch buffer for staging alpha texture data.
+    alphas_data: Vec<u8>,
 }
 
 /// Uniform locations for `strip_program`.
@@ -569,8 +700,8 @@ struct WebGlResources {
     /// Config buffer for rendering wide tile commands into a slot texture.
     slot_config_buffer: WebGlBuffer,
 
-    /// Buffer for slot indices used in `clear_slots`.
-    clear_slot_indices_buffer: WebGlBuffer,
+    /// Buffer for the [`GpuSlotClear`]s used in `clear_slots`.
+    slot_clears_buffer: WebGlBuffer,
     /// VAO for clear slots program.
     clear_vao: WebGlVertexArrayObject,
     /// Config buffer for clear program.
@@ -589,6 +720,9 @@ struct WebGlResources {
     /// Cached result from querying `WebGl2RenderingContext::MAX_TEXTURE_SIZE` which is a blocking
     /// WebGL call.
     max_texture_dimension_2d: u32,
+
+    /// Width of the slot textures, which is the width of a wide tile.
+    slot_width: u16,
 }
 
 /// Config for the clear slots pipeline.
@@ -607,7 +741,12 @@ struct ClearSlotsConfig {
 
 impl WebGlPrograms {
     /// Creates programs and initializes resources.
-    fn new(gl: WebGl2RenderingContext, image_cache: &ImageCache, slot_count: usize) -> Self {
+    fn new(
+        gl: WebGl2RenderingContext,
+        image_cache: &ImageCache,
+        slot_count: usize,
+        slot_width: u16,
+    ) -> Self {
         let strip_program = create_shader_program(
             &gl,
             render_strips::VERTEX_SOURCE,
@@ -622,7 +761,7 @@ impl WebGlPrograms {
         let strip_uniforms = get_strip_uniforms(&gl, &strip_program);
         let clear_uniforms = get_clear_uniforms(&gl, &clear_program);
 
-        let resources = create_webgl_resources(&gl, image_cache, slot_count);
+        let resources = create_webgl_resources(&gl, image_cache, slot_count, slot_width);
 
         initialize_strip_vao(&gl, &resources);
         initialize_clear_vao(&gl, &resources);
@@ -646,6 +785,7 @@ impl WebGlPrograms {
                 height: 0,
             },
             encoded_paints_data,
+            alphas_data: Vec::new(),
         }
     }
 
@@ -655,7 +795,7 @@ impl WebGlPrograms {
         gl: &WebGl2RenderingContext,
         gradient_cache: &mut GradientRampCache,
         encoded_paints: &[GpuEncodedPaint],
-        alphas: &mut Vec<u8>,
+        alphas: &[u8],
         render_size: &RenderSize,
         paint_idxs: &[u32],
     ) {
@@ -835,7 +975,7 @@ impl WebGlPrograms {
             // Update slot config buffer.
             {
                 let slot_config = Config {
-                    width: u32::from(WideTile::WIDTH),
+                    width: u32::from(self.resources.slot_width),
                     height: u32::from(Tile::HEIGHT) * total_slots,
                     strip_height: u32::from(Tile::HEIGHT),
                     alphas_tex_width_bits: max_texture_dimension_2d.trailing_zeros(),
@@ -857,7 +997,7 @@ impl WebGlPrograms {
             // TODO: This can be done once, and doesn't need to be done on every `prepare` call.
             {
                 let clear_config = ClearSlotsConfig {
-                    slot_width: u32::from(WideTile::WIDTH),
+                    slot_width: u32::from(self.resources.slot_width),
                     slot_height: u32::from(Tile::HEIGHT),
                     texture_height: u32::from(Tile::HEIGHT) * total_slots,
                     _padding: 0,
@@ -898,7 +1038,7 @@ impl WebGlPrograms {
     }
 
     /// Upload alpha data to the texture.
-    fn upload_alpha_texture(&mut self, gl: &WebGl2RenderingContext, alphas: &mut Vec<u8>) {
+    fn upload_alpha_texture(&mut self, gl: &WebGl2RenderingContext, alphas: &[u8]) {
         if alphas.is_empty() {
             return;
         }
@@ -907,10 +1047,10 @@ impl WebGlPrograms {
         let alpha_texture_height = self.resources.alpha_texture_height;
         let total_size = alpha_texture_width as usize * alpha_texture_height as usize * 16;
 
-        let original_len = alphas.len();
-
-        // Temporarily pad the length of the alphas to the texture size before uploading.
-        alphas.resize(total_size, 0);
+        // Pad the alphas to the texture size before uploading.
+        self.alphas_data.clear();
+        self.alphas_data.extend_from_slice(alphas);
+        self.alphas_data.resize(total_size, 0);
 
         gl.active_texture(WebGl2RenderingContext::TEXTURE0);
         gl.bind_texture(
@@ -919,7 +1059,7 @@ impl WebGlPrograms {
         );
 
         // Pack alpha values into RGBA uint32 texture
-        let alpha_data_as_u32 = bytemuck::cast_slice::<u8, u32>(alphas);
+        let alpha_data_as_u32 = bytemuck::cast_slice::<u8, u32>(&self.alphas_data);
         let packed_array = js_sys::Uint32Array::from(alpha_data_as_u32);
 
         gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
@@ -934,9 +1074,6 @@ impl WebGlPrograms {
             Some(&packed_array),
         )
         .unwrap();
-
-        // Truncate back to the original size.
-        alphas.truncate(original_len);
     }
 
     /// Upload encoded paints to the texture.
@@ -1360,6 +1497,7 @@ fn create_webgl_resources(
     gl: &WebGl2RenderingContext,
     image_cache: &ImageCache,
     slot_count: usize,
+    slot_width: u16,
 ) -> WebGlResources {
     let strip_vao = gl.create_vertex_array().unwrap();
     let clear_vao = gl.create_vertex_array().unwrap();
@@ -1367,7 +1505,7 @@ fn create_webgl_resources(
     let strips_buffer = gl.create_buffer().unwrap();
     let view_config_buffer = gl.create_buffer().unwrap();
     let slot_config_buffer = gl.create_buffer().unwrap();
-    let clear_slot_indices_buffer = gl.create_buffer().unwrap();
+    let slot_clears_buffer = gl.create_buffer().unwrap();
     let clear_config_buffer = gl.create_buffer().unwrap();
 
     // Create and configure alpha texture.
@@ -1493,8 +1631,8 @@ fn create_webgl_resources(
 
     // Create slot textures and framebuffers.
     let slot_textures: [WebGlTexture; 2] = [
-        create_slot_texture(gl, slot_count),
-        create_slot_texture(gl, slot_count),
+        create_slot_texture(gl, slot_count, slot_width),
+        create_slot_texture(gl, slot_count, slot_width),
     ];
 
     let slot_framebuffers: [WebGlFramebuffer; 2] = [
@@ -1516,7 +1654,7 @@ fn create_webgl_resources(
         gradient_texture_height: 0,
         view_config_buffer,
         slot_config_buffer,
-        clear_slot_indices_buffer,
+        slot_clears_buffer,
         clear_vao,
         clear_config_buffer,
         slot_textures,
@@ -1524,6 +1662,7 @@ fn create_webgl_resources(
         view_texture,
         view_framebuffer,
         max_texture_dimension_2d,
+        slot_width,
     }
 }
 
@@ -1581,7 +1720,11 @@ fn create_atlas_texture_array(
 }
 
 /// Create a texture for slot rendering.
-fn create_slot_texture(gl: &WebGl2RenderingContext, slot_count: usize) -> WebGlTexture {
+fn create_slot_texture(
+    gl: &WebGl2RenderingContext,
+    slot_count: usize,
+    slot_width: u16,
+) -> WebGlTexture {
     let texture = gl.create_texture().unwrap();
     gl.active_texture(WebGl2RenderingContext::TEXTURE0);
     gl.bind_texture(WebGl2RenderingContext::TEXTURE_2D, Some(&texture));
@@ -1615,7 +1758,7 @@ fn create_slot_texture(gl: &WebGl2RenderingContext, slot_count: usize) -> WebGlT
         WebGl2RenderingContext::TEXTURE_2D,
         0,
         WebGl2RenderingContext::RGBA8 as i32,
-        u32::from(WideTile::WIDTH) as i32,
+        i32::from(slot_width),
         (u32::from(Tile::HEIGHT) * slot_count as u32) as i32,
         0,
         WebGl2RenderingContext::RGBA,
@@ -1655,12 +1798,11 @@ fn initialize_strip_vao(gl: &WebGl2RenderingContext, resources: &WebGlResources)
     );
 
     let stride = size_of::<GpuStrip>() as i32;
-    debug_assert_eq!(stride, 20, "expected stride of 20");
 
     // Configure attributes.
-    for i in 0..5 {
+    for i in 0..GpuStrip::VERTEX_ATTRIBUTES {
         let location = i as u32;
-        let offset = i * 4;
+        let offset = (i * size_of::<u32>()) as i32;
 
         gl.enable_vertex_attrib_array(location);
         gl.vertex_attrib_i_pointer_with_i32(
@@ -1682,20 +1824,25 @@ fn initialize_clear_vao(gl: &WebGl2RenderingContext, resources: &WebGlResources)
     gl.bind_vertex_array(Some(&resources.clear_vao));
     gl.bind_buffer(
         WebGl2RenderingContext::ARRAY_BUFFER,
-        Some(&resources.clear_slot_indices_buffer),
+        Some(&resources.slot_clears_buffer),
     );
 
-    // Configure attributes.
-    let slot_idx_loc = 0;
-    gl.enable_vertex_attrib_array(slot_idx_loc);
-    gl.vertex_attrib_i_pointer_with_i32(
-        slot_idx_loc,
-        1,
-        WebGl2RenderingContext::UNSIGNED_INT,
-        4,
-        0,
-    );
-    gl.vertex_attrib_divisor(slot_idx_loc, 1);
+    let stride = size_of::<GpuSlotClear>() as i32;
+
+    // Configure attributes: the slot index at location 0 and its clear color at location 1.
+    for location in 0..2 {
+        let offset = (location as usize * size_of::<u32>()) as i32;
+
+        gl.enable_vertex_attrib_array(location);
+        gl.vertex_attrib_i_pointer_with_i32(
+            location,
+            1,
+            WebGl2RenderingContext::UNSIGNED_INT,
+            stride,
+            offset,
+        );
+        gl.vertex_attrib_divisor(location, 1);
+    }
 
     gl.bind_vertex_array(None);
 }
@@ -1751,8 +1898,8 @@ impl WebGlRendererContext<'_> {
                 / u32::from(Tile::HEIGHT)) as usize;
             // Set viewport to match slot texture.
             let height = u32::from(Tile::HEIGHT) * total_slots as u32;
-            self.gl
-                .viewport(0, 0, i32::from(WideTile::WIDTH), height as i32);
+            let slot_width = i32::from(self.programs.resources.slot_width);
+            self.gl.viewport(0, 0, slot_width, height as i32);
 
             // Use slot config buffer for rendering to a slot texture.
             self.gl.bind_buffer_base(
@@ -1847,8 +1994,8 @@ impl WebGlRendererContext<'_> {
     }
 
     /// Clear specific slots from a slot texture.
-    fn do_clear_slots_render_pass(&mut self, ix: usize, slot_indices: &[u32]) {
-        if slot_indices.is_empty() {
+    fn do_clear_slots_render_pass(&mut self, ix: usize, slot_clears: &[GpuSlotClear]) {
+        if slot_clears.is_empty() {
             return;
         }
 
@@ -1859,12 +2006,12 @@ impl WebGlRendererContext<'_> {
         // Upload slot indices.
         self.gl.bind_buffer(
             WebGl2RenderingContext::ARRAY_BUFFER,
-            Some(&self.programs.resources.clear_slot_indices_buffer),
+            Some(&self.programs.resources.slot_clears_buffer),
         );
-        let slot_indices_data = bytemuck::cast_slice(slot_indices);
+        let slot_clears_data = bytemuck::cast_slice(slot_clears);
         self.gl.buffer_data_with_u8_array(
             WebGl2RenderingContext::ARRAY_BUFFER,
-            slot_indices_data,
+            slot_clears_data,
             WebGl2RenderingContext::STATIC_DRAW,
         );
 
@@ -1877,8 +2024,8 @@ impl WebGlRendererContext<'_> {
         let total_slots: usize =
             (self.programs.resources.max_texture_dimension_2d / u32::from(Tile::HEIGHT)) as usize;
         let height = u32::from(Tile::HEIGHT) * total_slots as u32;
-        self.gl
-            .viewport(0, 0, i32::from(WideTile::WIDTH), height as i32);
+        let slot_width = i32::from(self.programs.resources.slot_width);
+        self.gl.viewport(0, 0, slot_width, height as i32);
 
         // Setup clear program.
         self.gl.use_program(Some(&self.programs.clear_program));
@@ -1899,7 +2046,7 @@ impl WebGlRendererContext<'_> {
             WebGl2RenderingContext::TRIANGLE_STRIP,
             0,
             4,
-            slot_indices.len() as i32,
+            slot_clears.len() as i32,
         );
 
         self.gl.enable(WebGl2RenderingContext::BLEND);
@@ -1911,7 +2058,7 @@ impl WebGlRendererContext<'_> {
 
 impl RendererBackend for WebGlRendererContext<'_> {
     /// Clear specific slots in a texture
-    fn clear_slots(&mut self, texture_index: usize, slots: &[u32]) {
+    fn clear_slots(&mut self, texture_index: usize, slots: &[GpuSlotClear]) {
         self.do_clear_slots_render_pass(texture_index, slots);
     }
 
@@ -20,42 +20,45 @@ only break in edge cases, and some of them are also only related to conversions
 
 use alloc::vec::Vec;
 use alloc::{sync::Arc, vec};
-use core::{fmt::Debug, num::NonZeroU64};
+use core::{fmt::Debug, num::NonZeroU64, ops::Range, time::Duration};
 
 use crate::AtlasConfig;
 use crate::multi_atlas::AtlasId;
 use crate::{
-    GpuStrip, RenderError, RenderSettings, RenderSize,
+    ClipSlotUsage, GlyphAtlas, GpuSlotClear, GpuStrip, RenderError, RenderSettings, RenderSize,
+    SlotParity,
     gradient_cache::GradientRampCache,
     image_cache::{ImageCache, ImageResource},
+    mipmap::build_mip_chain,
     render::{
         Config,
         common::{
-            GPU_ENCODED_IMAGE_SIZE_TEXELS, GPU_LINEAR_GRADIENT_SIZE_TEXELS,
-            GPU_RADIAL_GRADIENT_SIZE_TEXELS, GPU_SWEEP_GRADIENT_SIZE_TEXELS, GpuEncodedImage,
+            GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS, GPU_ENCODED_IMAGE_SIZE_TEXELS,
+            GPU_LINEAR_GRADIENT_SIZE_TEXELS, GPU_RADIAL_GRADIENT_SIZE_TEXELS,
+            GPU_SWEEP_GRADIENT_SIZE_TEXELS, GpuBlurredRoundedRect, GpuEncodedImage,
             GpuEncodedPaint, GpuLinearGradient, GpuRadialGradient, GpuSweepGradient,
-            pack_image_offset, pack_image_params, pack_image_size, pack_radial_kind_and_swapped,
-            pack_texture_width_and_extend_mode,
+            clamped_max_anisotropy, pack_image_offset, pack_image_params, pack_image_size,
+            pack_radial_kind_and_swapped, pack_texture_width_and_extend_mode,
         },
     },
-    scene::Scene,
+    scene::{FrozenScene, Scene, SceneView},
     schedule::{LoadOp, RendererBackend, Scheduler, SchedulerState},
 };
 use bytemuck::{Pod, Zeroable};
 use vello_common::{
-    coarse::WideTile,
     encode::{EncodedGradient, EncodedKind, EncodedPaint, MAX_GRADIENT_LUT_SIZE, RadialKind},
     kurbo::Affine,
     paint::ImageSource,
     peniko,
+    peniko::color::PremulRgba8,
     pixmap::Pixmap,
     tile::Tile,
 };
 use wgpu::{
     BindGroup, BindGroupLayout, BlendState, Buffer, ColorTargetState, ColorWrites, CommandEncoder,
-    Device, Extent3d, PipelineCompilationOptions, Queue, RenderPassColorAttachment,
-    RenderPassDescriptor, RenderPipeline, Texture, TextureView, TextureViewDescriptor,
-    util::DeviceExt,
+    ComputePipeline, Device, Extent3d, PipelineCompilationOptions, QuerySet, Queue,
+    RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline, Texture, TextureView,
+    TextureViewDescriptor, util::DeviceExt,
 };
 
 /// Placeholder value for uninitialized GPU encoded paints.
@@ -69,6 +72,15 @@ const GPU_PAINT_PLACEHOLDER: GpuEncodedPaint = GpuEncodedPaint::LinearGradient(G
 #[derive(Debug)]
 pub struct RenderTargetConfig {
     /// Format of the rendering target
+    ///
+    /// Both RGBA and BGRA channel orders are supported, as well as their sRGB variants. For the
+    /// latter, colors are converted such that they aren't encoded to sRGB twice.
+    ///
+    /// [`Rgba16Float`](wgpu::TextureFormat::Rgba16Float) is also supported, for example for HDR
+    /// or wide-gamut surfaces. Linear colors are stored in it, and the final composition into it
+    /// is blended in linear space. Solid colors are drawn with 16 bits per color channel and
+    /// gradients are interpolated between 32-bit float ramp entries, so they don't band. The
+    /// intermediate results of layers keep 8 bits per channel.
     pub format: wgpu::TextureFormat,
     /// Width of the rendering target
     pub width: u32,
@@ -76,7 +88,68 @@ pub struct RenderTargetConfig {
     pub height: u32,
 }
 
+/// The contents of one of the two clip-slot textures, captured for debugging.
+///
+/// See [`Renderer::capture_clip_slots`].
+#[derive(Debug)]
+pub struct ClipSlotCapture {
+    /// Which of the two slot textures this capture belongs to.
+    pub parity: SlotParity,
+    /// The contents of the whole slot texture.
+    ///
+    /// The pixmap is [`RenderSettings::wide_tile_width`] pixels wide and contains every slot
+    /// stacked vertically, each [`Tile::HEIGHT`] pixels high. If the slot texture is higher than
+    /// `u16::MAX` pixels, only the slots that fit into a pixmap are captured.
+    pub pixmap: Pixmap,
+    /// The slots that were claimed in this texture during the last render, in claim order.
+    ///
+    /// Slots are reused across rounds, so the same slot may appear more than once. The pixmap
+    /// only holds what was last drawn into each slot. This is only recorded if
+    /// [`RenderSettings::record_clip_slots`] is set, and is empty otherwise.
+    pub slots: Vec<ClipSlotUsage>,
+}
+
+impl ClipSlotCapture {
+    /// Returns the pixels of a single slot in row-major order, or `None` if the texture has
+    /// no slot with that index.
+    pub fn slot_pixels(&self, slot: usize) -> Option<&[PremulRgba8]> {
+        let slot_size = usize::from(self.pixmap.width()) * usize::from(Tile::HEIGHT);
+        let start = slot.checked_mul(slot_size)?;
+        self.pixmap.data().get(start..start.checked_add(slot_size)?)
+    }
+}
+
+/// The kind of pass a [`PassTiming`] was measured for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PassKind {
+    /// A pass clearing slots in one of the slot textures.
+    ClearSlots,
+    /// A pass rendering strips into one of the slot textures.
+    Clip,
+    /// A pass rendering strips into the render target.
+    Target,
+}
+
+/// The GPU time spent in a single pass of a render.
+///
+/// See [`Renderer::take_timings`].
+#[derive(Debug, Clone, Copy)]
+pub struct PassTiming {
+    /// What the pass did.
+    pub kind: PassKind,
+    /// The time between the start and the end of the pass on the GPU.
+    pub duration: Duration,
+}
+
 /// Vello Hybrid's Renderer.
+///
+/// If the device supports storage buffers and compute shaders (e.g. WebGPU and native backends),
+/// strips are read from a storage buffer and slots are cleared with a compute shader. On devices
+/// which don't (e.g. WebGL2), strips are uploaded as instance vertex attributes and slots are
+/// cleared with a render pass instead.
+///
+/// If the device has [`wgpu::Features::TIMESTAMP_QUERY`] enabled, the GPU time of each pass is
+/// measured as well, see [`Renderer::take_timings`].
 #[derive(Debug)]
 pub struct Renderer {
     /// Programs for rendering.
@@ -93,6 +166,10 @@ pub struct Renderer {
     paint_idxs: Vec<u32>,
     /// Gradient cache for storing gradient ramps.
     gradient_cache: GradientRampCache,
+    /// The maximum number of samples for anisotropic filtering of mipmapped images.
+    max_anisotropy: u32,
+    /// The timestamps of the renders since the last call to `take_timings`.
+    pending_timings: Vec<PendingTimings>,
 }
 
 impl Renderer {
@@ -106,8 +183,28 @@ impl Renderer {
         device: &Device,
         render_target_config: &RenderTargetConfig,
         settings: RenderSettings,
+    ) -> Self {
+        Self::new_with_pipeline_cache(device, render_target_config, settings, None)
+    }
+
+    /// Creates a new renderer with specific settings, compiling its pipelines with
+    /// `pipeline_cache`.
+    ///
+    /// Sharing a cache between renderers (or restoring it from the data of a previous run with
+    /// [`wgpu::Device::create_pipeline_cache`]) lets backends that support
+    /// [`wgpu::Features::PIPELINE_CACHE`] skip recompiling the shaders.
+    pub fn new_with_pipeline_cache(
+        device: &Device,
+        render_target_config: &RenderTargetConfig,
+        settings: RenderSettings,
+        pipeline_cache: Option<wgpu::PipelineCache>,
     ) -> Self {
         super::common::maybe_warn_about_webgl_feature_conflict();
+        assert!(
+            !settings.linear_blending || stores_linear_colors(render_target_config.format),
+            "linear blending requires an sRGB or float render target format, got {:?}",
+            render_target_config.format
+        );
 
         let max_texture_dimension_2d = device.limits().max_texture_dimension_2d;
         let total_slots = (max_texture_dimension_2d / u32::from(Tile::HEIGHT)) as usize;
@@ -116,23 +213,67 @@ impl Renderer {
         // and the maximum gradient LUT size - worst case scenario.
         let max_gradient_cache_size =
             max_texture_dimension_2d * max_texture_dimension_2d / MAX_GRADIENT_LUT_SIZE as u32;
-        let gradient_cache = GradientRampCache::new(max_gradient_cache_size, settings.level);
+        let gradient_cache = GradientRampCache::new(
+            max_gradient_cache_size,
+            settings.level,
+            has_float_colors(render_target_config.format),
+        );
+
+        let mut programs = Programs::new(
+            device,
+            &image_cache,
+            render_target_config,
+            total_slots,
+            settings.checked_wide_tile_width(),
+            settings.linear_blending,
+            pipeline_cache,
+        );
+        if !settings.lazy_pipelines {
+            programs.ensure_pipelines(device);
+        }
 
         Self {
-            programs: Programs::new(device, &image_cache, render_target_config, total_slots),
-            scheduler: Scheduler::new(total_slots),
+            programs,
+            scheduler: Scheduler::new(total_slots, &settings)
+                .with_high_precision_colors(has_float_colors(render_target_config.format)),
             scheduler_state: SchedulerState::default(),
             image_cache,
             gradient_cache,
             encoded_paints: Vec::new(),
             paint_idxs: Vec::new(),
+            pending_timings: Vec::new(),
+            // Images are filtered in the shader, so every device can sample them anisotropically.
+            max_anisotropy: clamped_max_anisotropy(settings.max_anisotropy, u16::MAX),
         }
     }
 
+    /// Resize the renderer for a render target of `width` by `height` pixels.
+    ///
+    /// This only updates the state that depends on the size of the render target, and keeps
+    /// the pipelines, textures and caches, so it is much cheaper than creating a new renderer
+    /// when the surface is resized. The render target must keep the format passed in
+    /// [`RenderTargetConfig`]; if the format changes, a new renderer has to be created instead.
+    ///
+    /// [`Renderer::render`] performs the same update if it is passed a different [`RenderSize`],
+    /// so calling this is not required, but allows doing the work when the resize happens.
+    pub fn resize(&mut self, queue: &Queue, width: u32, height: u32) {
+        let max_texture_dimension_2d = self.programs.resources.alphas_texture.width();
+        self.programs.maybe_update_config_buffer(
+            queue,
+            max_texture_dimension_2d,
+            &RenderSize { width, height },
+        );
+    }
+
     /// Render `scene` into the provided command encoder.
     ///
     /// This method creates GPU resources as needed and schedules potentially multiple
     /// render passes.
+    ///
+    /// The render target isn't cleared, and the passes rendering into it are scissored to the
+    /// union bounds of the scene's draws. Pixels outside the geometry of the scene keep their
+    /// previous contents, so a scene confined to a small region of a large surface only costs
+    /// fragment work there.
     pub fn render(
         &mut self,
         scene: &Scene,
@@ -142,7 +283,36 @@ impl Renderer {
         render_size: &RenderSize,
         view: &TextureView,
     ) -> Result<(), RenderError> {
-        self.prepare_gpu_encoded_paints(&scene.encoded_paints);
+        scene.with_view(|scene| self.render_view(&scene, device, queue, encoder, render_size, view))
+    }
+
+    /// Render a [`FrozenScene`] into the provided command encoder.
+    ///
+    /// This behaves like [`Renderer::render`], but takes a scene snapshot created by
+    /// [`Scene::freeze`], so that the original scene can be reused while this one renders.
+    pub fn render_frozen(
+        &mut self,
+        scene: &FrozenScene,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        render_size: &RenderSize,
+        view: &TextureView,
+    ) -> Result<(), RenderError> {
+        self.render_view(&scene.view(), device, queue, encoder, render_size, view)
+    }
+
+    fn render_view(
+        &mut self,
+        scene: &SceneView<'_>,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        render_size: &RenderSize,
+        view: &TextureView,
+    ) -> Result<(), RenderError> {
+        self.programs.ensure_pipelines(device);
+        self.prepare_gpu_encoded_paints(scene.encoded_paints);
         // TODO: For the time being, we upload the entire alpha buffer as one big chunk. As a future
         // refinement, we could have a bounded alpha buffer, and break draws when the alpha
         // buffer fills.
@@ -151,7 +321,7 @@ impl Renderer {
             queue,
             &mut self.gradient_cache,
             &self.encoded_paints,
-            &mut scene.strip_storage.borrow_mut().alphas,
+            scene.alphas,
             render_size,
             &self.paint_idxs,
         );
@@ -169,11 +339,170 @@ impl Renderer {
             scene,
             &self.paint_idxs,
         );
+        self.pending_timings.extend(junk.encode_pending_passes());
         self.gradient_cache.maintain();
 
         result
     }
 
+    /// Returns the number of clip slots in each of the two slot textures.
+    ///
+    /// This depends on the device, as the slot textures are as high as its
+    /// `max_texture_dimension_2d` limit allows, with each slot being [`Tile::HEIGHT`] pixels high.
+    pub fn slot_count(&self) -> usize {
+        self.scheduler.total_slots()
+    }
+
+    /// Read back the contents of the two clip-slot textures.
+    ///
+    /// This is intended for debugging clipping and compositing issues. It should be called
+    /// after the command encoder passed to [`Renderer::render`] has been submitted, and blocks
+    /// until the GPU has finished copying the textures.
+    ///
+    /// The captures are ordered by texture index, i.e. even parity first. The slots claimed
+    /// during the render are only listed if [`RenderSettings::record_clip_slots`] is set.
+    pub fn capture_clip_slots(&self, device: &Device, queue: &Queue) -> [ClipSlotCapture; 2] {
+        let slot_usage = self.scheduler.slot_usage();
+        let textures = &self.programs.resources.slot_textures;
+        let width = u16::try_from(textures[0].width())
+            .expect("slot textures are one wide tile wide, which fits in a `u16`");
+        // Devices with a `max_texture_dimension_2d` limit above `u16::MAX` have slot textures
+        // that are higher than a pixmap can be, so only capture the slots that fit.
+        let height =
+            u16::try_from(textures[0].height()).unwrap_or(u16::MAX / Tile::HEIGHT * Tile::HEIGHT);
+        let bytes_per_row =
+            (u32::from(width) * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Capture Clip Slots"),
+        });
+        let buffers: [Buffer; 2] = core::array::from_fn(|i| {
+            let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Clip Slot Readback Buffer"),
+                size: u64::from(bytes_per_row) * u64::from(height),
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            });
+            encoder.copy_texture_to_buffer(
+                wgpu::TexelCopyTextureInfo {
+                    texture: &textures[i],
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                },
+                wgpu::TexelCopyBufferInfo {
+                    buffer: &buffer,
+                    layout: wgpu::TexelCopyBufferLayout {
+                        offset: 0,
+                        bytes_per_row: Some(bytes_per_row),
+                        rows_per_image: None,
+                    },
+                },
+                wgpu::Extent3d {
+                    width: width.into(),
+                    height: height.into(),
+                    depth_or_array_layers: 1,
+                },
+            );
+            buffer
+        });
+        queue.submit([encoder.finish()]);
+
+        for buffer in &buffers {
+            buffer.slice(..).map_async(wgpu::MapMode::Read, |result| {
+                result.expect("failed to map clip slot readback buffer");
+            });
+        }
+        device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .expect("failed to wait for clip slot readback");
+
+        let swap_red_blue = matches!(
+            textures[0].format(),
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+
+        core::array::from_fn(|i| {
+            let mut pixmap = Pixmap::new(width, height);
+            for (row, dst) in buffers[i]
+                .slice(..)
+                .get_mapped_range()
+                .chunks_exact(bytes_per_row as usize)
+                .zip(
+                    pixmap
+                        .data_as_u8_slice_mut()
+                        .chunks_exact_mut(usize::from(width) * 4),
+                )
+            {
+                dst.copy_from_slice(&row[..usize::from(width) * 4]);
+            }
+            buffers[i].unmap();
+
+            if swap_red_blue {
+                for pixel in pixmap.data_mut() {
+                    core::mem::swap(&mut pixel.r, &mut pixel.b);
+                }
+            }
+
+            ClipSlotCapture {
+                parity: if i == 0 {
+                    SlotParity::Even
+                } else {
+                    SlotParity::Odd
+                },
+                pixmap,
+                slots: slot_usage[i].clone(),
+            }
+        })
+    }
+
+    /// Read back the GPU time spent in each pass of the renders since the last call.
+    ///
+    /// Passes are timed if the device has [`wgpu::Features::TIMESTAMP_QUERY`] enabled; otherwise
+    /// this always returns an empty list. The timings are listed in the order the passes were
+    /// encoded, and are kept until they are taken, so this should be called regularly while
+    /// profiling.
+    ///
+    /// Like [`Renderer::capture_clip_slots`], this should be called after the command encoders
+    /// passed to [`Renderer::render`] have been submitted, and blocks until the GPU has finished
+    /// them.
+    pub fn take_timings(&mut self, device: &Device, queue: &Queue) -> Vec<PassTiming> {
+        let pending_timings = core::mem::take(&mut self.pending_timings);
+        if pending_timings.is_empty() {
+            return Vec::new();
+        }
+
+        for pending in &pending_timings {
+            pending
+                .readback_buffer
+                .slice(..)
+                .map_async(wgpu::MapMode::Read, |result| {
+                    result.expect("failed to map timestamp readback buffer");
+                });
+        }
+        device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .expect("failed to wait for timestamp readback");
+
+        let nanoseconds_per_tick = f64::from(queue.get_timestamp_period());
+        let mut timings = Vec::new();
+        for pending in &pending_timings {
+            let data = pending.readback_buffer.slice(..).get_mapped_range();
+            let timestamps: &[u64] = bytemuck::cast_slice(&data);
+            for (kind, pass_timestamps) in pending.kinds.iter().zip(timestamps.chunks_exact(2)) {
+                let ticks = pass_timestamps[1].saturating_sub(pass_timestamps[0]);
+                timings.push(PassTiming {
+                    kind: *kind,
+                    duration: Duration::from_nanos(
+                        (ticks as f64 * nanoseconds_per_tick).round() as u64
+                    ),
+                });
+            }
+        }
+
+        timings
+    }
+
     /// Upload image to cache and atlas in one step. Returns the `ImageId`.
     ///
     /// It's used when an image is not already in the cache.
@@ -189,9 +518,61 @@ impl Renderer {
         encoder: &mut CommandEncoder,
         writer: &T,
     ) -> vello_common::paint::ImageId {
+        let image_id = self
+            .image_cache
+            .allocate(writer.width(), writer.height())
+            .unwrap();
+        self.write_image(device, queue, encoder, image_id, writer);
+
+        image_id
+    }
+
+    /// Upload an image together with its mipmaps to cache and atlas. Returns the `ImageId`.
+    ///
+    /// The mipmaps are downsampled versions of the image, generated on the CPU and stored next
+    /// to the image in the atlas, which takes up to half as much space again. When the image
+    /// is drawn smaller than its size with [`ImageQuality::Medium`] or [`ImageQuality::High`],
+    /// it's sampled trilinearly from the two closest mipmap levels, which avoids the aliasing
+    /// of sampling the full-size image. [`ImageQuality::Low`] still samples the full-size
+    /// image, so whether to use the mipmaps can be chosen per draw.
+    ///
+    /// Each level is half as large as the previous one, rounded up, so the size of the image
+    /// doesn't need to be a power of two.
+    ///
+    /// [`ImageQuality::Low`]: vello_common::peniko::ImageQuality::Low
+    /// [`ImageQuality::Medium`]: vello_common::peniko::ImageQuality::Medium
+    /// [`ImageQuality::High`]: vello_common::peniko::ImageQuality::High
+    pub fn upload_image_with_mipmaps(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        pixmap: &Pixmap,
+    ) -> vello_common::paint::ImageId {
+        let (chain, mip_levels) = build_mip_chain(pixmap);
+        let (width, height) = (u32::from(pixmap.width()), u32::from(pixmap.height()));
+        let image_id = if mip_levels > 0 {
+            self.image_cache.allocate_with_mipmaps(width, height)
+        } else {
+            self.image_cache.allocate(width, height)
+        }
+        .unwrap();
+        self.write_image(device, queue, encoder, image_id, &chain);
+
+        image_id
+    }
+
+    /// Write the pixels of `writer` to the atlas allocation of `image_id`.
+    fn write_image<T: AtlasWriter>(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        image_id: vello_common::paint::ImageId,
+        writer: &T,
+    ) {
         let width = writer.width();
         let height = writer.height();
-        let image_id = self.image_cache.allocate(width, height).unwrap();
         let image_resource = self
             .image_cache
             .get(image_id)
@@ -218,8 +599,6 @@ impl Renderer {
             width,
             height,
         );
-
-        image_id
     }
 
     /// Destroy an image from the cache and clear the allocated slot in the atlas.
@@ -240,16 +619,34 @@ impl Renderer {
                     image_resource.offset[0] as u32,
                     image_resource.offset[1] as u32,
                 ],
-                image_resource.width as u32,
-                image_resource.height as u32,
+                u32::from(image_resource.allocated_size[0]),
+                u32::from(image_resource.allocated_size[1]),
             );
         }
     }
 
+    /// Upload the glyphs that were rasterized into `glyph_atlas` while building scenes, and
+    /// destroy the images of the glyphs it evicted.
+    ///
+    /// Call this after building a scene with the atlas, before rendering it, so that the
+    /// following scenes can draw these glyphs from the image atlas.
+    pub fn upload_glyphs(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        glyph_atlas: &mut GlyphAtlas,
+    ) {
+        for image_id in glyph_atlas.drain_evicted() {
+            self.destroy_image(device, queue, encoder, image_id);
+        }
+        glyph_atlas.upload_pending(|pixmap| self.upload_image(device, queue, encoder, pixmap));
+    }
+
     /// Clear a specific region of the atlas texture.
     fn clear_atlas_region(
         &mut self,
-        _device: &Device,
+        device: &Device,
         _queue: &Queue,
         encoder: &mut CommandEncoder,
         atlas_id: AtlasId,
@@ -257,6 +654,7 @@ impl Renderer {
         width: u32,
         height: u32,
     ) {
+        self.programs.ensure_pipelines(device);
         // Create a texture view for the specific atlas layer
         let layer_view =
             self.programs
@@ -295,7 +693,7 @@ impl Renderer {
         // Set scissor rectangle to limit clearing to specific region
         render_pass.set_scissor_rect(offset[0], offset[1], width, height);
         // Use atlas clear pipeline to render transparent pixels
-        render_pass.set_pipeline(&self.programs.atlas_clear_pipeline);
+        render_pass.set_pipeline(&self.programs.pipelines().atlas_clear_pipeline);
         // Draw fullscreen quad
         render_pass.draw(0..4, 0..1);
     }
@@ -333,11 +731,11 @@ impl Renderer {
                     self.encoded_paints[encoded_paint_idx] = gradient_paint;
                     current_idx += gradient_size_texels;
                 }
-                EncodedPaint::BlurredRoundedRect(_blurred_rect) => {
-                    // TODO: Blurred rounded rectangles are not yet supported
-                    log::warn!(
-                        "Blurred rounded rectangles are not yet supported in sparse strips hybrid renderer"
+                EncodedPaint::BlurredRoundedRect(blurred_rect) => {
+                    self.encoded_paints[encoded_paint_idx] = GpuEncodedPaint::BlurredRoundedRect(
+                        GpuBlurredRoundedRect::new(blurred_rect),
                     );
+                    current_idx += GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS;
                 }
             }
         }
@@ -365,7 +763,9 @@ impl Renderer {
             image_size,
             image_offset,
             transform,
-            _padding: [0, 0, 0],
+            mip_levels: image_resource.mip_levels,
+            max_anisotropy: self.max_anisotropy,
+            _padding: [0],
         })
     }
 
@@ -445,8 +845,14 @@ impl Renderer {
 /// Defines the GPU resources and pipelines for rendering.
 #[derive(Debug)]
 struct Programs {
-    /// Pipeline for rendering wide tile commands.
-    strip_pipeline: RenderPipeline,
+    /// The pipelines, once they have been compiled.
+    pipelines: Option<Pipelines>,
+    /// Format of the view texture.
+    view_format: wgpu::TextureFormat,
+    /// Whether colors are blended in linear space, see [`RenderSettings::linear_blending`].
+    linear_blending: bool,
+    /// Cache to compile the pipelines with.
+    pipeline_cache: Option<wgpu::PipelineCache>,
     /// Bind group layout for strip draws
     strip_bind_group_layout: BindGroupLayout,
     /// Bind group layout for encoded paints
@@ -455,16 +861,89 @@ struct Programs {
     gradient_bind_group_layout: BindGroupLayout,
     /// Bind group layout for atlas textures
     atlas_bind_group_layout: BindGroupLayout,
-    /// Pipeline for clearing slots in slot textures.
-    clear_pipeline: RenderPipeline,
-    /// Pipeline for clearing atlas regions.
-    atlas_clear_pipeline: RenderPipeline,
+    /// Bind group layout for clearing slots with a render pass.
+    clear_bind_group_layout: BindGroupLayout,
+    /// Resources for clearing slots with a compute shader, if supported by the device.
+    clear_compute: Option<ClearCompute>,
+    /// Whether strips are read from a storage buffer rather than from vertex attributes.
+    storage_strips: bool,
+    /// Whether passes are timed with timestamp queries.
+    timestamp_queries: bool,
     /// GPU resources for rendering (created during prepare)
     resources: GpuResources,
     /// Dimensions of the rendering target
     render_size: RenderSize,
     /// Scratch buffer for staging encoded paints texture data.
     encoded_paints_data: Vec<u8>,
+    /// Scratch buffer for padding the last, partially filled row of the alpha texture.
+    alphas_tail: Vec<u8>,
+    /// The strips of all passes in `pending_passes`, uploaded in one go once the scene has been
+    /// scheduled.
+    pending_strips: Vec<GpuStrip>,
+    /// The slots (and their clear colors) of all clear passes in `pending_passes`.
+    pending_slot_clears: Vec<GpuSlotClear>,
+    /// The passes requested by the scheduler during the current render, in order.
+    pending_passes: Vec<PendingPass>,
+}
+
+/// A pass requested by the scheduler, which is only encoded once all strips of the render are
+/// known and have been uploaded.
+#[derive(Debug)]
+enum PendingPass {
+    /// Clear the slots `pending_slot_clears[slots]` in slot texture `ix`.
+    ClearSlots { ix: usize, slots: Range<usize> },
+    /// Render the strips `pending_strips[strips]` to the view or slot texture `ix`.
+    Strips {
+        ix: usize,
+        strips: Range<u32>,
+        load: wgpu::LoadOp<wgpu::Color>,
+    },
+}
+
+impl PendingPass {
+    fn kind(&self) -> PassKind {
+        match self {
+            Self::ClearSlots { .. } => PassKind::ClearSlots,
+            Self::Strips { ix: 2, .. } => PassKind::Target,
+            Self::Strips { .. } => PassKind::Clip,
+        }
+    }
+}
+
+/// The timestamps written by the passes of a single render, which are read back in
+/// [`Renderer::take_timings`].
+#[derive(Debug)]
+struct PendingTimings {
+    /// Holds a start and an end timestamp for each pass.
+    readback_buffer: Buffer,
+    /// The kind of each pass.
+    kinds: Vec<PassKind>,
+}
+
+/// The render and compute pipelines of the renderer.
+#[derive(Debug)]
+struct Pipelines {
+    /// Pipeline for rendering wide tile commands.
+    strip_pipeline: RenderPipeline,
+    /// Pipeline for rendering wide tile commands into the view texture, if it can't use
+    /// `strip_pipeline`.
+    view_pipeline: Option<RenderPipeline>,
+    /// Pipeline for clearing slots in slot textures.
+    clear_pipeline: RenderPipeline,
+    /// Pipeline running `cs_main` from `clear_slots.wgsl`, if slots are cleared with a compute
+    /// shader.
+    clear_compute_pipeline: Option<ComputePipeline>,
+    /// Pipeline for clearing atlas regions.
+    atlas_clear_pipeline: RenderPipeline,
+}
+
+/// The resources for clearing slots with a compute shader.
+#[derive(Debug)]
+struct ClearCompute {
+    /// Bind group layout for the compute pipeline.
+    bind_group_layout: BindGroupLayout,
+    /// Config buffer shared with the render pipeline for clearing slots.
+    config_buffer: Buffer,
 }
 
 /// Contains all GPU resources needed for rendering
@@ -495,9 +974,11 @@ struct GpuResources {
     slot_config_buffer: Buffer,
 
     /// Buffer for slot indices used in `clear_slots`
-    clear_slot_indices_buffer: Buffer,
+    slot_clears_buffer: Buffer,
     // Bind groups for rendering with clip buffers
     slot_bind_groups: [BindGroup; 3],
+    /// Slot textures
+    slot_textures: [Texture; 2],
     /// Slot texture views
     slot_texture_views: [TextureView; 2],
 
@@ -507,6 +988,77 @@ struct GpuResources {
 
 const SIZE_OF_CONFIG: NonZeroU64 = NonZeroU64::new(size_of::<Config>() as u64).unwrap();
 
+/// The initial number of strips the strips storage buffer can hold.
+const INITIAL_STRIPS_CAPACITY: u64 = 1024;
+
+/// The number of slot columns cleared by a single workgroup of `cs_main` in `clear_slots.wgsl`.
+const CLEAR_SLOTS_WORKGROUP_WIDTH: u32 = 64;
+
+/// Whether `device` can read strips from a storage buffer in the vertex shader.
+///
+/// This is not the case on WebGL2.
+fn supports_storage_strips(device: &Device) -> bool {
+    device.limits().max_storage_buffers_per_shader_stage > 0
+}
+
+/// The format of the slot textures for a view texture of `view_format`.
+///
+/// By default, the slot textures hold sRGB-encoded values, so that blending and compositing happen
+/// on the same values as in `vello_cpu`. They are never sRGB then, and use 8 bits per channel for
+/// float view textures too, so that they can be cleared with a compute shader.
+///
+/// With [linear blending](RenderSettings::linear_blending), the slot textures are sRGB, so that
+/// colors are blended in linear space and decoded when they are loaded.
+fn slot_format(view_format: wgpu::TextureFormat, linear_blending: bool) -> wgpu::TextureFormat {
+    match view_format {
+        _ if linear_blending => wgpu::TextureFormat::Rgba8UnormSrgb,
+        wgpu::TextureFormat::Rgba16Float => wgpu::TextureFormat::Rgba8Unorm,
+        format => format.remove_srgb_suffix(),
+    }
+}
+
+/// Whether view textures of `view_format` store linear colors, in which case the sRGB-encoded
+/// colors of strips need to be decoded before they are written to the view.
+fn stores_linear_colors(view_format: wgpu::TextureFormat) -> bool {
+    view_format.is_srgb() || view_format == wgpu::TextureFormat::Rgba16Float
+}
+
+/// Whether view textures of `view_format` have float channels, in which case solid colors and
+/// gradient ramps are drawn with more than 8 bits per channel.
+fn has_float_colors(view_format: wgpu::TextureFormat) -> bool {
+    view_format == wgpu::TextureFormat::Rgba16Float
+}
+
+/// The format of the gradient texture for a view texture of `view_format`.
+fn gradient_format(view_format: wgpu::TextureFormat) -> wgpu::TextureFormat {
+    if has_float_colors(view_format) {
+        wgpu::TextureFormat::Rgba32Float
+    } else {
+        wgpu::TextureFormat::Rgba8Unorm
+    }
+}
+
+/// Whether `device` can clear slots in textures of `format` with a compute shader.
+fn supports_compute_clear(device: &Device, format: wgpu::TextureFormat) -> bool {
+    let limits = device.limits();
+    limits.max_compute_workgroups_per_dimension > 0
+        && limits.max_storage_buffers_per_shader_stage > 0
+        && limits.max_storage_textures_per_shader_stage > 0
+        // The storage texture format is fixed in `clear_slots.wgsl`.
+        && format == wgpu::TextureFormat::Rgba8Unorm
+}
+
+/// The height of the alpha texture to allocate when it needs to hold `required_height` rows.
+///
+/// Rounding up to the next power of two leaves some headroom, so that scenes whose alpha data
+/// grows gradually over many frames only replace the texture (and rebuild the strip bind groups)
+/// a logarithmic number of times.
+fn grown_alphas_texture_height(required_height: u32, max_texture_dimension_2d: u32) -> u32 {
+    required_height
+        .next_power_of_two()
+        .min(max_texture_dimension_2d)
+}
+
 /// Config for the clear slots pipeline
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
@@ -523,7 +1075,7 @@ struct ClearSlotsConfig {
 
 impl GpuStrip {
     /// Vertex attributes for the strip
-    pub fn vertex_attributes() -> [wgpu::VertexAttribute; 5] {
+    pub fn vertex_attributes() -> [wgpu::VertexAttribute; Self::VERTEX_ATTRIBUTES] {
         wgpu::vertex_attr_array![
             0 => Uint32,
             1 => Uint32,
@@ -540,42 +1092,62 @@ impl Programs {
         image_cache: &ImageCache,
         render_target_config: &RenderTargetConfig,
         slot_count: usize,
+        slot_width: u16,
+        linear_blending: bool,
+        pipeline_cache: Option<wgpu::PipelineCache>,
     ) -> Self {
+        let storage_strips = supports_storage_strips(device);
+        let slot_format = slot_format(render_target_config.format, linear_blending);
+        let compute_clear = supports_compute_clear(device, slot_format);
+
+        let mut strip_bind_group_layout_entries = vec![
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Uint,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+        ];
+        if storage_strips {
+            strip_bind_group_layout_entries.push(wgpu::BindGroupLayoutEntry {
+                binding: 3,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            });
+        }
         let strip_bind_group_layout =
             device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 label: Some("Strip Bind Group Layout"),
-                entries: &[
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 0,
-                        visibility: wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Texture {
-                            sample_type: wgpu::TextureSampleType::Uint,
-                            view_dimension: wgpu::TextureViewDimension::D2,
-                            multisampled: false,
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 1,
-                        visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Buffer {
-                            ty: wgpu::BufferBindingType::Uniform,
-                            has_dynamic_offset: false,
-                            min_binding_size: None,
-                        },
-                        count: None,
-                    },
-                    wgpu::BindGroupLayoutEntry {
-                        binding: 2,
-                        visibility: wgpu::ShaderStages::FRAGMENT,
-                        ty: wgpu::BindingType::Texture {
-                            sample_type: wgpu::TextureSampleType::Float { filterable: false },
-                            view_dimension: wgpu::TextureViewDimension::D2,
-                            multisampled: false,
-                        },
-                        count: None,
-                    },
-                ],
+                entries: &strip_bind_group_layout_entries,
             });
 
         let atlas_bind_group_layout =
@@ -615,7 +1187,8 @@ impl Programs {
                     binding: 0,
                     visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Texture {
-                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        // Gradient ramps are only loaded, and `Rgba32Float` isn't filterable.
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
                         view_dimension: wgpu::TextureViewDimension::D2,
                         multisampled: false,
                     },
@@ -639,168 +1212,39 @@ impl Programs {
                 }],
             });
 
-        let strip_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Strip Shader"),
-            source: wgpu::ShaderSource::Wgsl(vello_sparse_shaders::wgsl::RENDER_STRIPS.into()),
-        });
-
-        let clear_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Clear Slots Shader"),
-            source: wgpu::ShaderSource::Wgsl(vello_sparse_shaders::wgsl::CLEAR_SLOTS.into()),
-        });
-
-        let strip_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Strip Pipeline Layout"),
-                bind_group_layouts: &[
-                    &strip_bind_group_layout,
-                    &atlas_bind_group_layout,
-                    &encoded_paints_bind_group_layout,
-                    &gradient_bind_group_layout,
-                ],
-                push_constant_ranges: &[],
-            });
-
-        let clear_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Clear Slots Pipeline Layout"),
-                bind_group_layouts: &[&clear_bind_group_layout],
-                push_constant_ranges: &[],
-            });
-
-        let strip_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Strip Pipeline"),
-            layout: Some(&strip_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &strip_shader,
-                entry_point: Some("vs_main"),
-                buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: size_of::<GpuStrip>() as u64,
-                    step_mode: wgpu::VertexStepMode::Instance,
-                    attributes: &GpuStrip::vertex_attributes(),
-                }],
-                compilation_options: PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &strip_shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(ColorTargetState {
-                    format: render_target_config.format,
-                    blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
-                    write_mask: ColorWrites::ALL,
-                })],
-                compilation_options: PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleStrip,
-                ..Default::default()
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
-
-        let clear_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Clear Slots Pipeline"),
-            layout: Some(&clear_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &clear_shader,
-                entry_point: Some("vs_main"),
-                buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: size_of::<u32>() as u64,
-                    step_mode: wgpu::VertexStepMode::Instance,
-                    attributes: &[wgpu::VertexAttribute {
-                        format: wgpu::VertexFormat::Uint32,
-                        offset: 0,
-                        shader_location: 0,
-                    }],
-                }],
-                compilation_options: PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &clear_shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(ColorTargetState {
-                    format: render_target_config.format,
-                    // No blending needed for clearing
-                    blend: None,
-                    write_mask: ColorWrites::ALL,
-                })],
-                compilation_options: PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleStrip,
-                ..Default::default()
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
-
-        // Create atlas clear pipeline
-        let atlas_clear_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Atlas Clear Pipeline Layout"),
-                bind_group_layouts: &[],
-                push_constant_ranges: &[],
-            });
-        let atlas_clear_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Atlas Clear Pipeline"),
-            layout: Some(&atlas_clear_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &clear_shader,
-                // Use a different vertex shader entry point
-                entry_point: Some("vs_main_fullscreen"),
-                buffers: &[],
-                compilation_options: PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &clear_shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(ColorTargetState {
-                    format: wgpu::TextureFormat::Rgba8Unorm,
-                    blend: None,
-                    write_mask: ColorWrites::ALL,
-                })],
-                compilation_options: PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleStrip,
-                ..Default::default()
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
-
-        let slot_texture_views: [TextureView; 2] = core::array::from_fn(|_| {
-            device
-                .create_texture(&wgpu::TextureDescriptor {
-                    label: Some("Slot Texture"),
-                    size: Extent3d {
-                        width: u32::from(WideTile::WIDTH),
-                        height: u32::from(Tile::HEIGHT) * slot_count as u32,
-                        depth_or_array_layers: 1,
-                    },
-                    mip_level_count: 1,
-                    sample_count: 1,
-                    dimension: wgpu::TextureDimension::D2,
-                    format: render_target_config.format,
-                    usage: wgpu::TextureUsages::TEXTURE_BINDING
+        let slot_textures: [Texture; 2] = core::array::from_fn(|_| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Slot Texture"),
+                size: Extent3d {
+                    width: u32::from(slot_width),
+                    height: u32::from(Tile::HEIGHT) * slot_count as u32,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: slot_format,
+                usage: if compute_clear {
+                    wgpu::TextureUsages::TEXTURE_BINDING
                         | wgpu::TextureUsages::RENDER_ATTACHMENT
-                        | wgpu::TextureUsages::COPY_SRC,
-                    view_formats: &[],
-                })
-                .create_view(&TextureViewDescriptor::default())
+                        | wgpu::TextureUsages::COPY_SRC
+                        | wgpu::TextureUsages::STORAGE_BINDING
+                } else {
+                    wgpu::TextureUsages::TEXTURE_BINDING
+                        | wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::COPY_SRC
+                },
+                view_formats: &[],
+            })
+        });
+        let slot_texture_views: [TextureView; 2] = core::array::from_fn(|i| {
+            slot_textures[i].create_view(&TextureViewDescriptor::default())
         });
 
         let clear_config_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Clear Slots Config"),
             contents: bytemuck::bytes_of(&ClearSlotsConfig {
-                slot_width: u32::from(WideTile::WIDTH),
+                slot_width: u32::from(slot_width),
                 slot_height: u32::from(Tile::HEIGHT),
                 texture_height: u32::from(Tile::HEIGHT) * slot_count as u32,
                 _padding: 0,
@@ -815,15 +1259,18 @@ impl Programs {
                 resource: clear_config_buffer.as_entire_binding(),
             }],
         });
-        let clear_slot_indices_buffer = Self::create_clear_slot_indices_buffer(
+        let clear_compute =
+            compute_clear.then(|| Self::create_clear_compute(device, clear_config_buffer));
+        let slot_clears_buffer = Self::create_slot_clears_buffer(
             device,
-            slot_count as u64 * size_of::<u32>() as u64,
+            slot_count as u64 * size_of::<GpuSlotClear>() as u64,
+            clear_compute.is_some(),
         );
 
         let slot_config_buffer = Self::create_config_buffer(
             device,
             &RenderSize {
-                width: u32::from(WideTile::WIDTH),
+                width: u32::from(slot_width),
                 height: u32::from(Tile::HEIGHT) * slot_count as u32,
             },
             device.limits().max_texture_dimension_2d,
@@ -882,6 +1329,7 @@ impl Programs {
         const INITIAL_GRADIENT_TEXTURE_HEIGHT: u32 = 1;
         let gradient_texture = Self::create_gradient_texture(
             device,
+            gradient_format(render_target_config.format),
             max_texture_dimension_2d,
             INITIAL_GRADIENT_TEXTURE_HEIGHT,
         );
@@ -891,64 +1339,365 @@ impl Programs {
             &gradient_texture.create_view(&TextureViewDescriptor::default()),
         );
 
-        let slot_bind_groups = Self::create_strip_bind_groups(
-            device,
-            &strip_bind_group_layout,
-            &alphas_texture.create_view(&TextureViewDescriptor::default()),
-            &slot_config_buffer,
-            &view_config_buffer,
-            &slot_texture_views,
-        );
+        let strips_buffer = Self::create_strips_buffer(
+            device,
+            INITIAL_STRIPS_CAPACITY * size_of::<GpuStrip>() as u64,
+            storage_strips,
+        );
+        let slot_bind_groups = Self::create_strip_bind_groups(
+            device,
+            &strip_bind_group_layout,
+            &alphas_texture.create_view(&TextureViewDescriptor::default()),
+            &slot_config_buffer,
+            &view_config_buffer,
+            &slot_texture_views,
+            storage_strips.then_some(&strips_buffer),
+        );
+
+        let resources = GpuResources {
+            strips_buffer,
+            slot_clears_buffer,
+            slot_textures,
+            slot_texture_views,
+            slot_config_buffer,
+            slot_bind_groups,
+            clear_bind_group,
+            alphas_texture,
+            atlas_texture_array,
+            atlas_texture_array_view,
+            atlas_bind_group,
+            encoded_paints_texture,
+            encoded_paints_bind_group,
+            gradient_texture,
+            gradient_bind_group,
+            view_config_buffer,
+        };
+
+        Self {
+            pipelines: None,
+            view_format: render_target_config.format,
+            linear_blending,
+            pipeline_cache,
+            strip_bind_group_layout,
+            encoded_paints_bind_group_layout,
+            gradient_bind_group_layout,
+            atlas_bind_group_layout,
+            resources,
+            encoded_paints_data,
+            alphas_tail: Vec::new(),
+            pending_strips: Vec::new(),
+            pending_slot_clears: Vec::new(),
+            pending_passes: Vec::new(),
+            render_size: RenderSize {
+                width: render_target_config.width,
+                height: render_target_config.height,
+            },
+            clear_bind_group_layout,
+            clear_compute,
+            storage_strips,
+            timestamp_queries: device.features().contains(wgpu::Features::TIMESTAMP_QUERY),
+        }
+    }
+
+    /// Create the resources for clearing slots with a compute shader.
+    ///
+    /// Only valid if [`supports_compute_clear`] returns `true` for the device.
+    fn create_clear_compute(device: &Device, config_buffer: Buffer) -> ClearCompute {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Clear Slots Compute Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: wgpu::TextureFormat::Rgba8Unorm,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        ClearCompute {
+            bind_group_layout,
+            config_buffer,
+        }
+    }
+
+    /// Compile the pipelines, unless that already happened.
+    ///
+    /// This needs to be called before encoding any pass, since the pipelines may be compiled
+    /// lazily, see [`RenderSettings::lazy_pipelines`].
+    fn ensure_pipelines(&mut self, device: &Device) {
+        if self.pipelines.is_none() {
+            self.pipelines = Some(self.create_pipelines(device));
+        }
+    }
+
+    /// The compiled pipelines.
+    ///
+    /// Panics if [`Programs::ensure_pipelines`] hasn't been called yet.
+    fn pipelines(&self) -> &Pipelines {
+        self.pipelines
+            .as_ref()
+            .expect("pipelines are compiled before encoding passes")
+    }
+
+    /// Compile the shaders and create the pipelines.
+    fn create_pipelines(&self, device: &Device) -> Pipelines {
+        let pipeline_cache = self.pipeline_cache.as_ref();
+        let slot_format = slot_format(self.view_format, self.linear_blending);
+
+        let strip_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Strip Shader"),
+            source: wgpu::ShaderSource::Wgsl(vello_sparse_shaders::wgsl::RENDER_STRIPS.into()),
+        });
+
+        let clear_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Clear Slots Shader"),
+            source: wgpu::ShaderSource::Wgsl(vello_sparse_shaders::wgsl::CLEAR_SLOTS.into()),
+        });
+
+        let strip_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Strip Pipeline Layout"),
+                bind_group_layouts: &[
+                    &self.strip_bind_group_layout,
+                    &self.atlas_bind_group_layout,
+                    &self.encoded_paints_bind_group_layout,
+                    &self.gradient_bind_group_layout,
+                ],
+                push_constant_ranges: &[],
+            });
+
+        let clear_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Clear Slots Pipeline Layout"),
+                bind_group_layouts: &[&self.clear_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let strip_vertex_buffers = [wgpu::VertexBufferLayout {
+            array_stride: size_of::<GpuStrip>() as u64,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &GpuStrip::vertex_attributes(),
+        }];
+        let create_strip_pipeline = |label, format, fragment_entry_point| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&strip_pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &strip_shader,
+                    entry_point: Some(if self.storage_strips {
+                        "vs_main_storage"
+                    } else {
+                        "vs_main"
+                    }),
+                    buffers: if self.storage_strips {
+                        &[]
+                    } else {
+                        &strip_vertex_buffers
+                    },
+                    compilation_options: PipelineCompilationOptions::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &strip_shader,
+                    entry_point: Some(fragment_entry_point),
+                    targets: &[Some(ColorTargetState {
+                        format,
+                        blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                        write_mask: ColorWrites::ALL,
+                    })],
+                    compilation_options: PipelineCompilationOptions::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: pipeline_cache,
+            })
+        };
+        let (strip_pipeline, view_pipeline) = if self.linear_blending {
+            // Slots and the view both store linear colors, and colors loaded from slots are
+            // decoded already, so only the colors of paints need to be decoded.
+            (
+                create_strip_pipeline("Strip Pipeline", slot_format, "fs_main_linear"),
+                Some(create_strip_pipeline(
+                    "View Strip Pipeline",
+                    self.view_format,
+                    if has_float_colors(self.view_format) {
+                        "fs_main_linear_float"
+                    } else {
+                        "fs_main_linear"
+                    },
+                )),
+            )
+        } else {
+            // Only the view texture can store linear colors (see `slot_format`), so only it needs
+            // a pipeline which decodes the sRGB-encoded colors of strips.
+            (
+                create_strip_pipeline("Strip Pipeline", slot_format, "fs_main"),
+                stores_linear_colors(self.view_format).then(|| {
+                    create_strip_pipeline(
+                        "Linear View Strip Pipeline",
+                        self.view_format,
+                        if has_float_colors(self.view_format) {
+                            "fs_main_float"
+                        } else {
+                            "fs_main_srgb"
+                        },
+                    )
+                }),
+            )
+        };
+
+        let clear_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Clear Slots Pipeline"),
+            layout: Some(&clear_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &clear_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: size_of::<GpuSlotClear>() as u64,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![0 => Uint32, 1 => Uint32],
+                }],
+                compilation_options: PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &clear_shader,
+                // Clear colors are sRGB-encoded, like the colors of strips.
+                entry_point: Some(if self.linear_blending {
+                    "fs_main_linear"
+                } else {
+                    "fs_main"
+                }),
+                targets: &[Some(ColorTargetState {
+                    format: slot_format,
+                    // No blending needed for clearing
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: pipeline_cache,
+        });
+
+        // Create atlas clear pipeline
+        let atlas_clear_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Atlas Clear Pipeline Layout"),
+                bind_group_layouts: &[],
+                push_constant_ranges: &[],
+            });
+        let atlas_clear_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Atlas Clear Pipeline"),
+            layout: Some(&atlas_clear_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &clear_shader,
+                // Use a different vertex shader entry point
+                entry_point: Some("vs_main_fullscreen"),
+                buffers: &[],
+                compilation_options: PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &clear_shader,
+                entry_point: Some("fs_main_fullscreen"),
+                targets: &[Some(ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: pipeline_cache,
+        });
 
-        let resources = GpuResources {
-            strips_buffer: Self::create_strips_buffer(device, 0),
-            clear_slot_indices_buffer,
-            slot_texture_views,
-            slot_config_buffer,
-            slot_bind_groups,
-            clear_bind_group,
-            alphas_texture,
-            atlas_texture_array,
-            atlas_texture_array_view,
-            atlas_bind_group,
-            encoded_paints_texture,
-            encoded_paints_bind_group,
-            gradient_texture,
-            gradient_bind_group,
-            view_config_buffer,
-        };
+        let clear_compute_pipeline = self.clear_compute.as_ref().map(|clear_compute| {
+            let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Clear Slots Compute Pipeline Layout"),
+                bind_group_layouts: &[&clear_compute.bind_group_layout],
+                push_constant_ranges: &[],
+            });
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("Clear Slots Compute Pipeline"),
+                layout: Some(&pipeline_layout),
+                module: &clear_shader,
+                entry_point: Some("cs_main"),
+                compilation_options: PipelineCompilationOptions::default(),
+                cache: pipeline_cache,
+            })
+        });
 
-        Self {
+        Pipelines {
             strip_pipeline,
-            strip_bind_group_layout,
-            encoded_paints_bind_group_layout,
-            gradient_bind_group_layout,
-            atlas_bind_group_layout,
-            resources,
-            encoded_paints_data,
-            render_size: RenderSize {
-                width: render_target_config.width,
-                height: render_target_config.height,
-            },
+            view_pipeline,
             clear_pipeline,
+            clear_compute_pipeline,
             atlas_clear_pipeline,
         }
     }
 
-    fn create_strips_buffer(device: &Device, required_strips_size: u64) -> Buffer {
+    fn create_strips_buffer(device: &Device, required_strips_size: u64, storage: bool) -> Buffer {
         device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Strips Buffer"),
             size: required_strips_size,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            usage: if storage {
+                wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST
+            } else {
+                wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST
+            },
             mapped_at_creation: false,
         })
     }
 
-    fn create_clear_slot_indices_buffer(device: &Device, required_size: u64) -> Buffer {
+    fn create_slot_clears_buffer(device: &Device, required_size: u64, storage: bool) -> Buffer {
         device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Slot Indices Buffer"),
+            label: Some("Slot Clears Buffer"),
             size: required_size,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            usage: if storage {
+                wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST
+            } else {
+                wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST
+            },
             mapped_at_creation: false,
         })
     }
@@ -1074,7 +1823,12 @@ impl Programs {
         })
     }
 
-    fn create_gradient_texture(device: &Device, width: u32, height: u32) -> Texture {
+    fn create_gradient_texture(
+        device: &Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Texture {
         device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Gradient Texture"),
             size: Extent3d {
@@ -1085,7 +1839,7 @@ impl Programs {
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
+            format,
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             view_formats: &[],
         })
@@ -1113,6 +1867,7 @@ impl Programs {
         strip_config_buffer: &Buffer,
         config_buffer: &Buffer,
         strip_texture_views: &[TextureView],
+        strips_buffer: Option<&Buffer>,
     ) -> [BindGroup; 3] {
         [
             Self::create_strip_bind_group(
@@ -1121,6 +1876,7 @@ impl Programs {
                 alphas_texture_view,
                 strip_config_buffer,
                 &strip_texture_views[1],
+                strips_buffer,
             ),
             Self::create_strip_bind_group(
                 device,
@@ -1128,6 +1884,7 @@ impl Programs {
                 alphas_texture_view,
                 strip_config_buffer,
                 &strip_texture_views[0],
+                strips_buffer,
             ),
             Self::create_strip_bind_group(
                 device,
@@ -1135,6 +1892,7 @@ impl Programs {
                 alphas_texture_view,
                 config_buffer,
                 &strip_texture_views[1],
+                strips_buffer,
             ),
         ]
     }
@@ -1145,27 +1903,52 @@ impl Programs {
         alphas_texture_view: &TextureView,
         config_buffer: &Buffer,
         strip_texture_view: &TextureView,
+        strips_buffer: Option<&Buffer>,
     ) -> BindGroup {
+        let mut entries = vec![
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(alphas_texture_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: config_buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: wgpu::BindingResource::TextureView(strip_texture_view),
+            },
+        ];
+        if let Some(strips_buffer) = strips_buffer {
+            entries.push(wgpu::BindGroupEntry {
+                binding: 3,
+                resource: strips_buffer.as_entire_binding(),
+            });
+        }
         device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Strip Bind Group"),
             layout: strip_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(alphas_texture_view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: config_buffer.as_entire_binding(),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: wgpu::BindingResource::TextureView(strip_texture_view),
-                },
-            ],
+            entries: &entries,
         })
     }
 
+    /// Recreate the strip bind groups after the alpha texture or the strips storage buffer
+    /// changed.
+    fn update_strip_bind_groups(&mut self, device: &Device) {
+        self.resources.slot_bind_groups = Self::create_strip_bind_groups(
+            device,
+            &self.strip_bind_group_layout,
+            &self
+                .resources
+                .alphas_texture
+                .create_view(&TextureViewDescriptor::default()),
+            &self.resources.slot_config_buffer,
+            &self.resources.view_config_buffer,
+            &self.resources.slot_texture_views,
+            self.storage_strips.then_some(&self.resources.strips_buffer),
+        );
+    }
+
     /// Prepare GPU buffers for rendering, given alphas.
     ///
     /// Specifically, updates the alpha texture with `alphas` and the config buffer when
@@ -1176,7 +1959,7 @@ impl Programs {
         queue: &Queue,
         gradient_cache: &mut GradientRampCache,
         encoded_paints: &[GpuEncodedPaint],
-        alphas: &mut Vec<u8>,
+        alphas: &[u8],
         new_render_size: &RenderSize,
         paint_idxs: &[u32],
     ) {
@@ -1222,22 +2005,12 @@ impl Programs {
             let alphas_texture = Self::create_alphas_texture(
                 device,
                 max_texture_dimension_2d,
-                required_alpha_height,
+                grown_alphas_texture_height(required_alpha_height, max_texture_dimension_2d),
             );
             self.resources.alphas_texture = alphas_texture;
 
             // Since the alpha texture has changed, we need to update the clip bind groups.
-            self.resources.slot_bind_groups = Self::create_strip_bind_groups(
-                device,
-                &self.strip_bind_group_layout,
-                &self
-                    .resources
-                    .alphas_texture
-                    .create_view(&TextureViewDescriptor::default()),
-                &self.resources.slot_config_buffer,
-                &self.resources.view_config_buffer,
-                &self.resources.slot_texture_views,
-            );
+            self.update_strip_bind_groups(device);
         }
     }
 
@@ -1290,7 +2063,7 @@ impl Programs {
         max_texture_dimension_2d: u32,
         gradient_cache: &GradientRampCache,
     ) {
-        let gradient_pixels = (gradient_cache.luts_size() / 4) as u32; // 4 bytes per RGBA8 pixel
+        let gradient_pixels = gradient_cache.luts_size() as u32 / gradient_cache.bytes_per_texel();
         let required_gradient_height = gradient_pixels.div_ceil(max_texture_dimension_2d);
         debug_assert!(
             self.resources.gradient_texture.width() == max_texture_dimension_2d,
@@ -1304,6 +2077,7 @@ impl Programs {
             );
             let gradient_texture = Self::create_gradient_texture(
                 device,
+                self.resources.gradient_texture.format(),
                 max_texture_dimension_2d,
                 required_gradient_height,
             );
@@ -1419,44 +2193,40 @@ impl Programs {
     }
 
     /// Upload alpha data to the texture.
-    fn upload_alpha_texture(&mut self, queue: &Queue, alphas: &mut Vec<u8>) {
+    fn upload_alpha_texture(&mut self, queue: &Queue, alphas: &[u8]) {
         if alphas.is_empty() {
             return;
         }
 
-        let texture_width = self.resources.alphas_texture.width();
-        let texture_height = self.resources.alphas_texture.height();
-        let total_size = texture_width as usize * texture_height as usize * 16;
-
-        let original_len = alphas.len();
+        // 16 bytes per RGBA32Uint texel (4 u32s × 4 bytes each), which is equivalent to
+        // a bit shift of 4.
+        let bytes_per_row = self.resources.alphas_texture.width() << 4;
+        let full_rows = alphas.len() / bytes_per_row as usize;
+        let (full, tail) = alphas.split_at(full_rows * bytes_per_row as usize);
 
-        // Temporarily pad the length of the alphas to the texture size before uploading.
-        alphas.resize(total_size, 0);
-
-        queue.write_texture(
-            wgpu::TexelCopyTextureInfo {
-                texture: &self.resources.alphas_texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            alphas,
-            wgpu::TexelCopyBufferLayout {
-                offset: 0,
-                // 16 bytes per RGBA32Uint texel (4 u32s × 4 bytes each), which is equivalent to
-                // a bit shift of 4.
-                bytes_per_row: Some(texture_width << 4),
-                rows_per_image: Some(texture_height),
-            },
-            Extent3d {
-                width: texture_width,
-                height: texture_height,
-                depth_or_array_layers: 1,
-            },
-        );
+        if full_rows > 0 {
+            write_alpha_rows(
+                queue,
+                &self.resources.alphas_texture,
+                full,
+                0,
+                full_rows as u32,
+            );
+        }
 
-        // Truncate back to the original size.
-        alphas.truncate(original_len);
+        if !tail.is_empty() {
+            // Only whole rows can be uploaded, so pad the last row with zeros.
+            self.alphas_tail.clear();
+            self.alphas_tail.extend_from_slice(tail);
+            self.alphas_tail.resize(bytes_per_row as usize, 0);
+            write_alpha_rows(
+                queue,
+                &self.resources.alphas_texture,
+                &self.alphas_tail,
+                full_rows as u32,
+                1,
+            );
+        }
     }
 
     /// Upload encoded paints to the texture.
@@ -1496,7 +2266,9 @@ impl Programs {
 
         // Upload the gradient LUT data
         if !gradient_cache.is_empty() {
-            let total_capacity = (gradient_texture_width * gradient_texture_height * 4) as usize;
+            let bytes_per_texel = gradient_cache.bytes_per_texel();
+            let total_capacity =
+                (gradient_texture_width * gradient_texture_height * bytes_per_texel) as usize;
 
             // Take ownership of the luts to avoid copying, then resize for texture padding
             let mut luts = gradient_cache.take_luts();
@@ -1513,8 +2285,7 @@ impl Programs {
                 &luts,
                 wgpu::TexelCopyBufferLayout {
                     offset: 0,
-                    // 4 bytes per RGBA8 pixel
-                    bytes_per_row: Some(gradient_texture_width << 2),
+                    bytes_per_row: Some(gradient_texture_width * bytes_per_texel),
                     rows_per_image: Some(gradient_texture_height),
                 },
                 Extent3d {
@@ -1530,22 +2301,121 @@ impl Programs {
         }
     }
 
-    /// Upload the strip data by creating and assigning a new `self.resources.strips_buffer`.
-    fn upload_strips(&mut self, device: &Device, queue: &Queue, strips: &[GpuStrip]) {
-        let required_strips_size = size_of_val(strips) as u64;
-        self.resources.strips_buffer = Self::create_strips_buffer(device, required_strips_size);
+    /// Upload `self.pending_strips` to `self.resources.strips_buffer`.
+    ///
+    /// The strips are written to a mapped staging buffer and copied by `encoder`, so the copy is
+    /// ordered after any earlier render recorded into the same encoder that still reads from the
+    /// strips buffer. The strips buffer is only reallocated when it runs out of space.
+    fn upload_strips(&mut self, device: &Device, encoder: &mut CommandEncoder) {
+        if self.pending_strips.is_empty() {
+            return;
+        }
+
+        let required_strips_size = size_of_val(self.pending_strips.as_slice()) as u64;
+        if required_strips_size > self.resources.strips_buffer.size() {
+            // Render passes recorded earlier keep using the old buffer.
+            self.resources.strips_buffer = Self::create_strips_buffer(
+                device,
+                required_strips_size.next_power_of_two(),
+                self.storage_strips,
+            );
+            if self.storage_strips {
+                self.update_strip_bind_groups(device);
+            }
+        }
+
+        let staging_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Strips Staging Buffer"),
+            contents: bytemuck::cast_slice(&self.pending_strips),
+            usage: wgpu::BufferUsages::COPY_SRC,
+        });
+        encoder.copy_buffer_to_buffer(
+            &staging_buffer,
+            0,
+            &self.resources.strips_buffer,
+            0,
+            required_strips_size,
+        );
+    }
+
+    /// Upload the slots to clear and their colors by creating and assigning a new
+    /// `self.resources.slot_clears_buffer`.
+    fn upload_slot_clears(&mut self, device: &Device, queue: &Queue, slot_clears: &[GpuSlotClear]) {
+        let size = size_of_val(slot_clears) as u64;
+        // TODO: We currently allocate a new buffer for each clear. A more efficient
+        // approach would be to re-use buffers or slices of a larger buffer.
+        self.resources.slot_clears_buffer =
+            Self::create_slot_clears_buffer(device, size, self.clear_compute.is_some());
         // TODO: Consider using a staging belt to avoid an extra staging buffer allocation.
         let mut buffer = queue
             .write_buffer_with(
-                &self.resources.strips_buffer,
+                &self.resources.slot_clears_buffer,
                 0,
-                required_strips_size.try_into().unwrap(),
+                size.try_into().unwrap(),
             )
             .expect("Capacity handled in creation");
-        buffer.copy_from_slice(bytemuck::cast_slice(strips));
+        buffer.copy_from_slice(bytemuck::cast_slice(slot_clears));
+    }
+}
+
+/// Write whole rows of alpha data to the alpha texture, starting at row `first_row`.
+fn write_alpha_rows(queue: &Queue, texture: &Texture, data: &[u8], first_row: u32, rows: u32) {
+    let texture_width = texture.width();
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d {
+                x: 0,
+                y: first_row,
+                z: 0,
+            },
+            aspect: wgpu::TextureAspect::All,
+        },
+        data,
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(texture_width << 4),
+            rows_per_image: Some(rows),
+        },
+        Extent3d {
+            width: texture_width,
+            height: rows,
+            depth_or_array_layers: 1,
+        },
+    );
+}
+
+/// Write the start and end timestamps of a render pass to `query_set` at `index` and `index + 1`.
+fn render_pass_timestamp_writes(
+    (query_set, index): (&QuerySet, u32),
+) -> wgpu::RenderPassTimestampWrites<'_> {
+    wgpu::RenderPassTimestampWrites {
+        query_set,
+        beginning_of_pass_write_index: Some(index),
+        end_of_pass_write_index: Some(index + 1),
     }
 }
 
+/// Get the union bounds `[x0, y0, x1, y1]` of `strips` in the target they are drawn to, clamped
+/// to a target of `width` by `height` pixels.
+fn strip_bounds(strips: &[GpuStrip], width: u32, height: u32) -> [u32; 4] {
+    let [x0, y0, x1, y1] =
+        strips
+            .iter()
+            .fold([u32::MAX, u32::MAX, 0, 0], |[x0, y0, x1, y1], strip| {
+                let (x, y) = (u32::from(strip.x), u32::from(strip.y));
+                [
+                    x0.min(x),
+                    y0.min(y),
+                    x1.max(x + u32::from(strip.width)),
+                    y1.max(y + u32::from(Tile::HEIGHT)),
+                ]
+            });
+    let (x1, y1) = (x1.min(width), y1.min(height));
+    [x0.min(x1), y0.min(y1), x1, y1]
+}
+
 /// A struct containing references to the many objects needed to get work
 /// scheduled onto the GPU.
 struct RendererContext<'a> {
@@ -1557,20 +2427,100 @@ struct RendererContext<'a> {
 }
 
 impl RendererContext<'_> {
-    /// Render the strips to either the view or a slot texture (depending on `ix`).
+    /// Upload the strips of all passes requested by the scheduler and encode the passes.
+    ///
+    /// Returns the timestamps of the passes if they are timed.
+    fn encode_pending_passes(&mut self) -> Option<PendingTimings> {
+        self.programs.upload_strips(self.device, self.encoder);
+
+        let passes = core::mem::take(&mut self.programs.pending_passes);
+        let slot_clears = core::mem::take(&mut self.programs.pending_slot_clears);
+        let timestamp_count = 2 * u32::try_from(passes.len()).unwrap();
+        let query_set = (self.programs.timestamp_queries && !passes.is_empty()).then(|| {
+            self.device.create_query_set(&wgpu::QuerySetDescriptor {
+                label: Some("Pass Timestamps"),
+                ty: wgpu::QueryType::Timestamp,
+                count: timestamp_count,
+            })
+        });
+        for (i, pass) in passes.iter().enumerate() {
+            let timestamps = query_set
+                .as_ref()
+                .map(|query_set| (query_set, 2 * i as u32));
+            match pass {
+                PendingPass::ClearSlots { ix, slots } => {
+                    let slot_clears = &slot_clears[slots.clone()];
+                    if self.programs.clear_compute.is_some() {
+                        self.do_clear_slots_compute_pass(*ix, slot_clears, timestamps);
+                    } else {
+                        self.do_clear_slots_render_pass(*ix, slot_clears, timestamps);
+                    }
+                }
+                PendingPass::Strips { ix, strips, load } => {
+                    self.do_strip_render_pass(strips.clone(), *ix, *load, timestamps);
+                }
+            }
+        }
+        let timings = query_set.map(|query_set| PendingTimings {
+            readback_buffer: self.resolve_timestamps(&query_set, timestamp_count),
+            kinds: passes.iter().map(PendingPass::kind).collect(),
+        });
+
+        // Return the allocations for reuse.
+        self.programs.pending_passes = passes;
+        self.programs.pending_passes.clear();
+        self.programs.pending_slot_clears = slot_clears;
+        self.programs.pending_slot_clears.clear();
+        self.programs.pending_strips.clear();
+
+        timings
+    }
+
+    /// Copy the `count` timestamps written to `query_set` into a new buffer that can be read
+    /// back.
+    fn resolve_timestamps(&mut self, query_set: &QuerySet, count: u32) -> Buffer {
+        let size = u64::from(count) * u64::from(wgpu::QUERY_SIZE);
+        let resolve_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Timestamp Resolve Buffer"),
+            size,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Timestamp Readback Buffer"),
+            size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        self.encoder
+            .resolve_query_set(query_set, 0..count, &resolve_buffer, 0);
+        self.encoder
+            .copy_buffer_to_buffer(&resolve_buffer, 0, &readback_buffer, 0, size);
+        readback_buffer
+    }
+
+    /// Render the strips at `strips` in the strips buffer to either the view or a slot texture
+    /// (depending on `ix`).
     fn do_strip_render_pass(
         &mut self,
-        strips: &[GpuStrip],
+        strips: Range<u32>,
         ix: usize,
         load: wgpu::LoadOp<wgpu::Color>,
+        timestamps: Option<(&QuerySet, u32)>,
     ) {
         debug_assert!(ix < 3, "Invalid texture index");
-        if strips.is_empty() {
-            return;
-        }
-        // TODO: We currently allocate a new strips buffer for each render pass. A more efficient
-        // approach would be to re-use buffers or slices of a larger buffer.
-        self.programs.upload_strips(self.device, self.queue, strips);
+
+        // Restrict the final pass to the bounds of its strips, so that a scene confined to a small
+        // region of a large surface only costs fragment work there. The scissor rect doesn't
+        // apply to the load operation, which would still clear the whole view.
+        let scissor = (ix == 2).then(|| {
+            let target = self.view.texture();
+            strip_bounds(
+                &self.programs.pending_strips[strips.start as usize..strips.end as usize],
+                target.width(),
+                target.height(),
+            )
+        });
 
         let mut render_pass = self.encoder.begin_render_pass(&RenderPassDescriptor {
             label: Some("Render to Texture Pass"),
@@ -1589,39 +2539,41 @@ impl RendererContext<'_> {
             })],
             depth_stencil_attachment: None,
             occlusion_query_set: None,
-            timestamp_writes: None,
+            timestamp_writes: timestamps.map(render_pass_timestamp_writes),
         });
-        render_pass.set_pipeline(&self.programs.strip_pipeline);
+        let pipelines = self.programs.pipelines();
+        let pipeline = match &pipelines.view_pipeline {
+            Some(view_pipeline) if ix == 2 => view_pipeline,
+            _ => &pipelines.strip_pipeline,
+        };
+        render_pass.set_pipeline(pipeline);
         render_pass.set_bind_group(0, &self.programs.resources.slot_bind_groups[ix], &[]);
         render_pass.set_bind_group(1, &self.programs.resources.atlas_bind_group, &[]);
         render_pass.set_bind_group(2, &self.programs.resources.encoded_paints_bind_group, &[]);
         render_pass.set_bind_group(3, &self.programs.resources.gradient_bind_group, &[]);
-        render_pass.set_vertex_buffer(0, self.programs.resources.strips_buffer.slice(..));
-        render_pass.draw(0..4, 0..u32::try_from(strips.len()).unwrap());
+        if let Some([x0, y0, x1, y1]) = scissor {
+            render_pass.set_scissor_rect(x0, y0, x1 - x0, y1 - y0);
+        }
+        if !self.programs.storage_strips {
+            render_pass.set_vertex_buffer(0, self.programs.resources.strips_buffer.slice(..));
+        }
+        render_pass.draw(0..4, strips);
     }
 
     /// Clear specific slots from a slot texture.
-    fn do_clear_slots_render_pass(&mut self, ix: usize, slot_indices: &[u32]) {
-        if slot_indices.is_empty() {
+    fn do_clear_slots_render_pass(
+        &mut self,
+        ix: usize,
+        slot_clears: &[GpuSlotClear],
+        timestamps: Option<(&QuerySet, u32)>,
+    ) {
+        if slot_clears.is_empty() {
             return;
         }
 
-        let resources = &mut self.programs.resources;
-        let size = size_of_val(slot_indices) as u64;
-        // TODO: We currently allocate a new strips buffer for each render pass. A more efficient
-        // approach would be to re-use buffers or slices of a larger buffer.
-        resources.clear_slot_indices_buffer =
-            Programs::create_clear_slot_indices_buffer(self.device, size);
-        // TODO: Consider using a staging belt to avoid an extra staging buffer allocation.
-        let mut buffer = self
-            .queue
-            .write_buffer_with(
-                &resources.clear_slot_indices_buffer,
-                0,
-                size.try_into().unwrap(),
-            )
-            .expect("Capacity handled in creation");
-        buffer.copy_from_slice(bytemuck::cast_slice(slot_indices));
+        self.programs
+            .upload_slot_clears(self.device, self.queue, slot_clears);
+        let resources = &self.programs.resources;
 
         {
             let mut render_pass = self.encoder.begin_render_pass(&RenderPassDescriptor {
@@ -1638,31 +2590,116 @@ impl RendererContext<'_> {
                 })],
                 depth_stencil_attachment: None,
                 occlusion_query_set: None,
-                timestamp_writes: None,
+                timestamp_writes: timestamps.map(render_pass_timestamp_writes),
             });
 
-            render_pass.set_pipeline(&self.programs.clear_pipeline);
+            render_pass.set_pipeline(&self.programs.pipelines().clear_pipeline);
             render_pass.set_bind_group(0, &resources.clear_bind_group, &[]);
-            render_pass.set_vertex_buffer(0, resources.clear_slot_indices_buffer.slice(..));
-            render_pass.draw(0..4, 0..u32::try_from(slot_indices.len()).unwrap());
+            render_pass.set_vertex_buffer(0, resources.slot_clears_buffer.slice(..));
+            render_pass.draw(0..4, 0..u32::try_from(slot_clears.len()).unwrap());
+        }
+    }
+
+    /// Clear specific slots from a slot texture with a compute shader.
+    fn do_clear_slots_compute_pass(
+        &mut self,
+        ix: usize,
+        slot_clears: &[GpuSlotClear],
+        timestamps: Option<(&QuerySet, u32)>,
+    ) {
+        if slot_clears.is_empty() {
+            return;
         }
+
+        self.programs
+            .upload_slot_clears(self.device, self.queue, slot_clears);
+        let resources = &self.programs.resources;
+        let clear_compute = self
+            .programs
+            .clear_compute
+            .as_ref()
+            .expect("Only called if the device supports compute clears");
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Clear Slots Compute Bind Group"),
+            layout: &clear_compute.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: clear_compute.config_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: resources.slot_clears_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&resources.slot_texture_views[ix]),
+                },
+            ],
+        });
+
+        let mut compute_pass = self
+            .encoder
+            .begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Clear Slots Compute Pass"),
+                timestamp_writes: timestamps.map(|(query_set, index)| {
+                    wgpu::ComputePassTimestampWrites {
+                        query_set,
+                        beginning_of_pass_write_index: Some(index),
+                        end_of_pass_write_index: Some(index + 1),
+                    }
+                }),
+            });
+        compute_pass.set_pipeline(
+            self.programs
+                .pipelines()
+                .clear_compute_pipeline
+                .as_ref()
+                .expect("Created along with `clear_compute`"),
+        );
+        compute_pass.set_bind_group(0, &bind_group, &[]);
+        compute_pass.dispatch_workgroups(
+            resources.slot_textures[ix].width() / CLEAR_SLOTS_WORKGROUP_WIDTH,
+            u32::try_from(slot_clears.len()).unwrap(),
+            1,
+        );
     }
 }
 
 impl RendererBackend for RendererContext<'_> {
-    /// Execute the render pass for clearing slots.
-    fn clear_slots(&mut self, texture_index: usize, slots: &[u32]) {
-        self.do_clear_slots_render_pass(texture_index, slots);
+    /// Request a pass for clearing slots.
+    fn clear_slots(&mut self, texture_index: usize, slots: &[GpuSlotClear]) {
+        if slots.is_empty() {
+            return;
+        }
+
+        let slot_clears = &mut self.programs.pending_slot_clears;
+        let start = slot_clears.len();
+        slot_clears.extend_from_slice(slots);
+        self.programs.pending_passes.push(PendingPass::ClearSlots {
+            ix: texture_index,
+            slots: start..slot_clears.len(),
+        });
     }
 
-    /// Execute the render pass for rendering strips.
+    /// Request a pass for rendering strips.
     fn render_strips(&mut self, strips: &[GpuStrip], target_index: usize, load_op: LoadOp) {
-        let wgpu_load_op = match load_op {
+        if strips.is_empty() {
+            return;
+        }
+
+        let load = match load_op {
             LoadOp::Load => wgpu::LoadOp::Load,
             LoadOp::Clear => wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
         };
-
-        self.do_strip_render_pass(strips, target_index, wgpu_load_op);
+        let pending_strips = &mut self.programs.pending_strips;
+        let start = u32::try_from(pending_strips.len()).unwrap();
+        pending_strips.extend_from_slice(strips);
+        self.programs.pending_passes.push(PendingPass::Strips {
+            ix: target_index,
+            strips: start..u32::try_from(pending_strips.len()).unwrap(),
+            load,
+        });
     }
 }
 
@@ -1819,3 +2856,58 @@ impl AtlasWriter for Arc<Pixmap> {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{grown_alphas_texture_height, strip_bounds};
+    use crate::GpuStrip;
+
+    #[test]
+    fn alphas_texture_grows_with_headroom() {
+        let max_texture_dimension_2d = 8192;
+        let mut height = 1;
+        let mut replacements = 0;
+        // Simulate a scene whose alpha data grows by a bit more than a row every frame.
+        for frame in 1..=5000_u32 {
+            let required_height = frame * 3 / 2;
+            if required_height > height {
+                height = grown_alphas_texture_height(required_height, max_texture_dimension_2d);
+                replacements += 1;
+            }
+            assert!(height >= required_height);
+            assert!(height <= max_texture_dimension_2d);
+        }
+
+        assert_eq!(height, max_texture_dimension_2d);
+        assert!(
+            replacements <= max_texture_dimension_2d.ilog2(),
+            "alpha texture was replaced {replacements} times"
+        );
+    }
+
+    #[test]
+    fn strip_bounds_are_clamped_to_the_target() {
+        let strip = |x: u16, y: u16, width: u16| GpuStrip {
+            x,
+            y,
+            width,
+            dense_width: 0,
+            col_idx: 0,
+            payload: 0,
+            paint: 0,
+        };
+        let strips = [
+            strip(600, 280, 4),
+            strip(580, 300, 44),
+            strip(1020, 508, 256),
+        ];
+
+        assert_eq!(strip_bounds(&strips, 4096, 4096), [580, 280, 1276, 512]);
+        assert_eq!(strip_bounds(&strips, 1024, 510), [580, 280, 1024, 510]);
+        // Strips outside of the target result in empty bounds.
+        assert_eq!(
+            strip_bounds(&strips[2..], 1000, 500),
+            [1000, 500, 1000, 500]
+        );
+    }
+}
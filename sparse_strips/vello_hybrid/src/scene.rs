@@ -6,27 +6,43 @@
 use alloc::vec;
 use alloc::vec::Vec;
 use core::cell::RefCell;
+use vello_common::blurred_rounded_rect::BlurredRoundedRectangle;
 use vello_common::clip::ClipContext;
-use vello_common::coarse::{MODE_HYBRID, Wide};
+use vello_common::coarse::{MODE_HYBRID, Wide, WideTile};
+use vello_common::colr::{ColrPainter, ColrRenderer, context_color};
 use vello_common::encode::{EncodeExt, EncodedPaint};
 use vello_common::fearless_simd::Level;
 use vello_common::filter_effects::Filter;
-use vello_common::glyph::{GlyphCaches, GlyphRenderer, GlyphRunBuilder, GlyphType, PreparedGlyph};
-use vello_common::kurbo::{Affine, BezPath, Cap, Join, Rect, Shape, Stroke};
+use vello_common::flatten::{DEFAULT_TOLERANCE, wrap_dash_offset};
+use vello_common::glyph::{
+    GlyphCacheStats, GlyphCaches, GlyphRenderer, GlyphRunBuilder, GlyphType, OutlineGlyph,
+    PreparedGlyph,
+};
+use vello_common::kurbo::{
+    Affine, BezPath, Cap, Circle, Ellipse, Join, PathEl, Point, Rect, RoundedRect,
+    RoundedRectRadii, Shape, Stroke, Vec2,
+};
 use vello_common::mask::Mask;
-use vello_common::paint::{Paint, PaintType};
+use vello_common::paint::{Image, ImageId, ImageSource, Paint, PaintType, PremulColor};
 use vello_common::peniko::FontData;
 use vello_common::peniko::color::palette::css::BLACK;
-use vello_common::peniko::{BlendMode, Compose, Fill, Mix};
-use vello_common::recording::{PushLayerCommand, Recordable, Recorder, Recording, RenderCommand};
+use vello_common::peniko::color::{AlphaColor, Srgb};
+use vello_common::peniko::{BlendMode, Compose, Fill, ImageQuality, ImageSampler, Mix};
+use vello_common::recording::{
+    DisplayList, PushLayerCommand, Recordable, Recorder, Recording, RenderCommand,
+};
 use vello_common::render_graph::RenderGraph;
 use vello_common::strip::Strip;
-use vello_common::strip_generator::{GenerationMode, StripGenerator, StripStorage};
+#[cfg(debug_assertions)]
+use vello_common::strip_generator::AllocStats;
+use vello_common::strip_generator::{
+    BufferCapacities, GenerationMode, StripGenerator, StripStorage, TileStats,
+};
+use vello_common::tile::Tile;
+use vello_common::util::{extract_scales, preserves_axis_alignment};
 
 use crate::AtlasConfig;
-
-/// Default tolerance for curve flattening
-pub(crate) const DEFAULT_TOLERANCE: f64 = 0.1;
+use crate::glyph_atlas::{AtlasGlyph, GlyphAtlas};
 
 /// Settings to apply to the render context.
 #[derive(Copy, Clone, Debug)]
@@ -45,6 +61,96 @@ pub struct RenderSettings {
     /// Adjusting these settings can affect memory usage and rendering performance
     /// depending on your application's image usage patterns.
     pub atlas_config: AtlasConfig,
+    /// Whether to compile the GPU pipelines on first use rather than when creating the renderer.
+    ///
+    /// This makes creating a renderer cheaper, for example to show something else while the
+    /// GPU is warming up, at the cost of the first render (or image upload) waiting for the
+    /// compilation. Only the `wgpu` renderer supports this; the WebGL renderer always compiles
+    /// its programs up front.
+    pub lazy_pipelines: bool,
+    /// Whether to blend colors in linear space rather than on sRGB-encoded colors.
+    ///
+    /// This is physically more accurate, for example for anti-aliased edges and semi-transparent
+    /// paints, but differs from `vello_cpu` and from how most other renderers and browsers blend.
+    /// The intermediate results of layers keep 8 bits per channel, stored sRGB-encoded.
+    ///
+    /// The render target format must store linear colors, i.e. be an sRGB or float format. Only
+    /// the `wgpu` renderer supports this; creating a WebGL renderer with it panics.
+    pub linear_blending: bool,
+    /// The maximum number of layers that can be nested, or `None` for no limit.
+    ///
+    /// Every kind of layer counts towards the depth, including clip, blend, opacity, mask and
+    /// filter layers, but clip paths pushed with [`Scene::push_clip_path`] don't. Layers
+    /// pushed beyond the limit are ignored (together with their matching pops), and rendering
+    /// the scene fails with [`RenderError::LayerDepthExceeded`](crate::RenderError::LayerDepthExceeded).
+    ///
+    /// Deeply nested layers need many slots in the slot textures, so limiting the depth
+    /// protects against generated or untrusted content with unbounded nesting.
+    pub max_layer_depth: Option<usize>,
+    /// Whether to draw layers approximately instead of failing when the slot textures run out
+    /// of slots.
+    ///
+    /// Every nested layer of a wide tile needs its own slot, so a single wide tile with more
+    /// nested layers than there are slots can't be rendered exactly. By default, rendering then
+    /// fails with [`RenderError::SlotsExhausted`](crate::RenderError::SlotsExhausted). If this
+    /// is set, the innermost layers of such a tile are flattened instead: their contents are
+    /// drawn without being clipped, blended or made transparent, and a warning is logged.
+    pub lenient_slot_allocation: bool,
+    /// Whether to assign slots in the same order in every frame.
+    ///
+    /// By default, the slots freed during a frame are reused in the following frames in the
+    /// order they were freed in, so the slots that the layers of a scene are drawn into depend
+    /// on the previously rendered scenes. If this is set, slot allocation starts from the
+    /// lowest slot index in every frame, so rendering the same scene always uses the same
+    /// slots. This is useful for comparing captures of the slot textures across runs.
+    pub deterministic_slot_allocation: bool,
+    /// Whether to record which slots are claimed while rendering a scene.
+    ///
+    /// The recorded slots are listed in the captures of `Renderer::capture_clip_slots`, to find
+    /// the slots that are worth inspecting when debugging clipping and compositing issues.
+    /// Recording them costs an allocation per claimed slot, so it is off by default, in which
+    /// case the captures list no slots.
+    pub record_clip_slots: bool,
+    /// The width of a wide tile in pixels.
+    ///
+    /// Wide tiles are the unit in which the coarse draw commands of a scene are scheduled,
+    /// and the slot textures used for clipping and blending are as wide as one wide tile.
+    /// Wider tiles mean fewer, larger draws and more memory per slot, while narrower ones
+    /// reduce the area that a small clipped or blended region occupies. Apart from the
+    /// rounding of colors composited through layers, it doesn't change the rendered output.
+    ///
+    /// It must be a multiple of 64 between 64 and 2048, and the scene and the renderer that
+    /// renders it must use the same width, or rendering fails with
+    /// [`RenderError::WideTileWidthMismatch`](crate::RenderError::WideTileWidthMismatch).
+    /// Defaults to [`WideTile::WIDTH`].
+    pub wide_tile_width: u16,
+    /// The maximum number of samples taken along the longer axis of a pixel when drawing a
+    /// mipmapped image under a non-uniform transform.
+    ///
+    /// Mipmapped images (see `upload_image_with_mipmaps`) are sampled trilinearly. When the
+    /// transform squashes the image much more in one direction than the other, a single
+    /// trilinear sample either aliases along one axis or blurs along the other. Anisotropic
+    /// filtering instead averages up to this many samples along the squashed axis, from a
+    /// sharper mipmap level. Values are clamped to between 1, which disables it, and 16.
+    ///
+    /// The WebGL renderer falls back to trilinear filtering if the device doesn't support the
+    /// `EXT_texture_filter_anisotropic` extension, and clamps the value to the maximum it
+    /// supports. The `wgpu` renderer takes the samples in its shader rather than through a
+    /// hardware sampler, so it doesn't depend on `DownlevelFlags::ANISOTROPIC_FILTERING` and
+    /// supports anisotropic filtering on every adapter. Defaults to 16.
+    pub max_anisotropy: u16,
+}
+
+impl RenderSettings {
+    /// Return [`RenderSettings::wide_tile_width`], checking that it is valid.
+    pub(crate) fn checked_wide_tile_width(&self) -> u16 {
+        assert!(
+            self.wide_tile_width.is_multiple_of(64) && (64..=2048).contains(&self.wide_tile_width),
+            "wide tile width must be a multiple of 64 between 64 and 2048, got {}",
+            self.wide_tile_width
+        );
+        self.wide_tile_width
+    }
 }
 
 impl Default for RenderSettings {
@@ -52,6 +158,14 @@ impl Default for RenderSettings {
         Self {
             level: Level::try_detect().unwrap_or(Level::fallback()),
             atlas_config: AtlasConfig::default(),
+            lazy_pipelines: false,
+            linear_blending: false,
+            max_layer_depth: None,
+            lenient_slot_allocation: false,
+            deterministic_slot_allocation: false,
+            record_clip_slots: false,
+            wide_tile_width: WideTile::WIDTH,
+            max_anisotropy: 16,
         }
     }
 }
@@ -74,6 +188,33 @@ struct RenderState {
     pub(crate) fill_rule: Fill,
     /// Blend mode for compositing.
     pub(crate) blend_mode: BlendMode,
+    /// Whether the paint is visible (e.g., alpha > 0).
+    pub(crate) paint_visible: bool,
+}
+
+/// A snapshot of the render state of a [`Scene`], returned by [`Scene::current_state`].
+///
+/// This is meant for debugging and for asserting the state before a draw, changing it
+/// doesn't affect the scene.
+#[derive(Debug, Clone)]
+pub struct RenderStateSnapshot {
+    /// The current transform applied to geometry.
+    pub transform: Affine,
+    /// The current paint (solid color, gradient, or image).
+    pub paint: PaintType,
+    /// The current transform applied to paint coordinates.
+    pub paint_transform: Affine,
+    /// The current stroke settings.
+    pub stroke: Stroke,
+    /// The current fill rule.
+    pub fill_rule: Fill,
+    /// The current blend mode.
+    pub blend_mode: BlendMode,
+    /// The number of clip paths pushed with [`Scene::push_clip_path`] or
+    /// [`Scene::push_clip_rect`] that haven't been popped yet.
+    pub clip_depth: usize,
+    /// The number of layers that haven't been popped yet.
+    pub layer_depth: usize,
 }
 
 /// A render context for hybrid CPU/GPU rendering.
@@ -89,10 +230,17 @@ pub struct Scene {
     /// Wide coarse rasterizer for generating binned draw commands.
     pub(crate) wide: Wide<MODE_HYBRID>,
     clip_context: ClipContext,
+    /// Device-space bounding boxes of the active clip paths, intersected with each other.
+    clip_path_bounds: Vec<Rect>,
+    /// Device-space bounding boxes of the clips of each active layer, intersected with the
+    /// clips of their parent layers. `None` if neither the layer nor its parents clip.
+    layer_clip_bounds: Vec<Option<Rect>>,
     pub(crate) paint: PaintType,
     /// Transform applied to paint coordinates.
     pub(crate) paint_transform: Affine,
     pub(crate) aliasing_threshold: Option<u8>,
+    /// Tolerance for flattening curves, in device space.
+    pub(crate) tolerance: f64,
     /// Storage for encoded gradient and image paint data.
     pub(crate) encoded_paints: Vec<EncodedPaint>,
     /// Whether the current paint is visible (e.g., alpha > 0).
@@ -111,8 +259,24 @@ pub struct Scene {
     pub(crate) strip_storage: RefCell<StripStorage>,
     /// Cache for rasterized glyphs to improve text rendering performance.
     pub(crate) glyph_caches: Option<GlyphCaches>,
+    /// Cache of rasterized glyphs that are drawn from the image atlas, if enabled.
+    glyph_atlas: Option<GlyphAtlas>,
     /// Dependency graph for managing layer rendering order and filter effects.
     pub(crate) render_graph: RenderGraph,
+    /// Temporary path buffer to avoid repeated allocations.
+    temp_path: BezPath,
+    /// Render states pushed by [`Scene::save`].
+    saved_states: Vec<RenderState>,
+    /// The maximum number of nested layers, see [`RenderSettings::max_layer_depth`].
+    max_layer_depth: Option<usize>,
+    /// The number of currently pushed layers that were ignored for exceeding
+    /// `max_layer_depth`.
+    ignored_layers: usize,
+    /// Whether a layer was ignored for exceeding `max_layer_depth` since the last reset.
+    layer_depth_exceeded: bool,
+    /// For each pushed layer, the blend mode to restore when it is popped, if it is an
+    /// isolated layer.
+    layer_blend_modes: Vec<Option<BlendMode>>,
 }
 
 impl Scene {
@@ -128,13 +292,20 @@ impl Scene {
         Self {
             width,
             height,
-            wide: Wide::<MODE_HYBRID>::new(width, height),
+            wide: Wide::<MODE_HYBRID>::new_with_tile_width(
+                width,
+                height,
+                settings.checked_wide_tile_width(),
+            ),
             clip_context: ClipContext::new(),
+            clip_path_bounds: vec![],
+            layer_clip_bounds: vec![],
             aliasing_threshold: None,
+            tolerance: DEFAULT_TOLERANCE,
             paint: render_state.paint,
             paint_transform: render_state.paint_transform,
             encoded_paints: vec![],
-            paint_visible: true,
+            paint_visible: render_state.paint_visible,
             stroke: render_state.stroke,
             strip_generator: StripGenerator::new(width, height, settings.level),
             strip_storage: RefCell::new(StripStorage::default()),
@@ -142,10 +313,45 @@ impl Scene {
             fill_rule: render_state.fill_rule,
             blend_mode: render_state.blend_mode,
             glyph_caches: Some(GlyphCaches::default()),
+            glyph_atlas: None,
             render_graph,
+            temp_path: BezPath::new(),
+            saved_states: vec![],
+            max_layer_depth: settings.max_layer_depth,
+            ignored_layers: 0,
+            layer_depth_exceeded: false,
+            layer_blend_modes: vec![],
         }
     }
 
+    /// Create a new render context with the given width and height in pixels, reserving
+    /// `capacities` in the buffers used for generating strips.
+    ///
+    /// See [`Scene::reserve`].
+    pub fn with_capacity(width: u16, height: u16, capacities: BufferCapacities) -> Self {
+        let mut scene = Self::new(width, height);
+        scene.reserve(capacities);
+        scene
+    }
+
+    /// Reserve capacity in the buffers used for generating strips.
+    ///
+    /// As [`Scene::reset`] retains the capacity of the buffers, frames whose paths stay within
+    /// the reserved sizes don't allocate in these buffers, not even the first one. Paths
+    /// exceeding them grow the buffers once, which then keep the larger capacity for the
+    /// following frames. In debug builds, [`Scene::alloc_stats`] reports which buffers had to grow.
+    ///
+    /// The lines and tiles are needed for one path at a time, so they should fit the most
+    /// complex path of a frame, while the strips and alphas accumulate over the whole frame.
+    /// Other buffers, for example those holding the draw commands of the wide tiles, aren't
+    /// reserved, but they also keep their capacity across frames.
+    pub fn reserve(&mut self, capacities: BufferCapacities) {
+        self.strip_generator.reserve(capacities);
+        self.strip_storage
+            .borrow_mut()
+            .reserve(capacities.strip_buf, capacities.alphas);
+    }
+
     /// Create default rendering state.
     fn default_render_state() -> RenderState {
         let transform = Affine::IDENTITY;
@@ -167,6 +373,7 @@ impl Scene {
             paint_transform,
             stroke,
             blend_mode,
+            paint_visible: true,
         }
     }
 
@@ -192,12 +399,12 @@ impl Scene {
 
     /// Fill a path with the current paint and fill rule.
     pub fn fill_path(&mut self, path: &BezPath) {
-        if !self.paint_visible {
+        if !self.draw_has_effect() || !self.may_be_visible(path.bounding_box(), 0.0) {
             return;
         }
 
         let paint = self.encode_current_paint();
-        self.fill_path_with(
+        self.generate_filled_path(
             path,
             self.transform,
             self.fill_rule,
@@ -206,13 +413,29 @@ impl Scene {
         );
     }
 
+    /// Check whether any part of a shape with the given `bounds`, grown by `expansion` in
+    /// path coordinates, could end up inside the scene and the active clips.
+    ///
+    /// This allows skipping the flattening and tiling of geometry that is off-screen or
+    /// clipped away entirely.
+    fn may_be_visible(&self, bounds: Rect, expansion: f64) -> bool {
+        let bounds = self
+            .transform
+            .transform_rect_bbox(bounds.inflate(expansion, expansion));
+        let viewport = Rect::new(0.0, 0.0, f64::from(self.width), f64::from(self.height));
+        let visible = self
+            .clip_bounds()
+            .map_or(viewport, |clip| clip.intersect(viewport));
+        !visible.intersect(bounds).is_zero_area()
+    }
+
     /// Build strips for a filled path with the given properties.
     ///
     /// This is the internal implementation that generates strips from a path
     /// and submits them to the coarse rasterizer. The path is first converted
     /// to strips by the strip generator, then the strips are processed by the
     /// wide coarse rasterizer to generate binned draw commands.
-    fn fill_path_with(
+    fn generate_filled_path(
         &mut self,
         path: &BezPath,
         transform: Affine,
@@ -230,12 +453,12 @@ impl Scene {
             strip_storage,
             self.clip_context.get(),
         );
-        wide.generate(
+        generate_wide_cmds(
+            wide,
+            &mut self.render_graph,
             &strip_storage.strips,
             paint,
             self.blend_mode,
-            0,
-            None,
             &self.encoded_paints,
         );
     }
@@ -245,6 +468,11 @@ impl Scene {
     /// See the explanation in the [clipping](https://github.com/linebender/vello/tree/main/sparse_strips/vello_cpu/examples)
     /// example for how this method differs from `push_clip_layer`.
     pub fn push_clip_path(&mut self, path: &BezPath) {
+        let bounds = intersect_clip_bounds(
+            self.clip_path_bounds.last().copied(),
+            self.transform.transform_rect_bbox(path.bounding_box()),
+        );
+        self.clip_path_bounds.push(bounds);
         self.clip_context.push_clip(
             path,
             &mut self.strip_generator,
@@ -254,22 +482,106 @@ impl Scene {
         );
     }
 
+    /// Push a new clip rectangle to the clip stack.
+    ///
+    /// This is the same as calling [`Scene::push_clip_path`] with the outline of the
+    /// rectangle, but if the current transform keeps the rectangle axis-aligned, its strips
+    /// are computed directly instead of flattening and tiling the outline. Like any clip
+    /// path, it is applied when generating the strips of each draw, so unlike
+    /// [`Scene::push_clip_layer`], no clip slots are needed to render it. Nested clip
+    /// rectangles are intersected into a single rectangle, see
+    /// [`ClipContext::push_clip_rect`](vello_common::clip::ClipContext::push_clip_rect).
+    ///
+    /// The clip rectangle must be popped with [`Scene::pop_clip_path`].
+    pub fn push_clip_rect(&mut self, rect: &Rect) {
+        let bounds = intersect_clip_bounds(
+            self.clip_path_bounds.last().copied(),
+            self.transform.transform_rect_bbox(*rect),
+        );
+        self.clip_path_bounds.push(bounds);
+        self.clip_context.push_clip_rect(
+            rect,
+            &mut self.strip_generator,
+            self.transform,
+            self.aliasing_threshold,
+        );
+    }
+
     /// Pop a clip path from the clip stack.
     ///
     /// Note that unlike `push_clip_layer`, it is permissible to have pending
     /// pushed clip paths before finishing the rendering operation.
     pub fn pop_clip_path(&mut self) {
+        self.clip_path_bounds.pop();
         self.clip_context.pop_clip();
     }
 
+    /// Get a conservative bounding box of the area that is not clipped away, in device space.
+    ///
+    /// This is the intersection of the bounding boxes of all active clip paths and clip
+    /// layers, so anything outside of it is guaranteed to be invisible. Returns `None` if
+    /// no clip is active.
+    pub fn clip_bounds(&self) -> Option<Rect> {
+        let layer_bounds = self.layer_clip_bounds.last().copied().flatten();
+        match self.clip_path_bounds.last() {
+            Some(&path_bounds) => Some(intersect_clip_bounds(layer_bounds, path_bounds)),
+            None => layer_bounds,
+        }
+    }
+
+    /// Whether drawing with the current paint and blend mode can change the output.
+    ///
+    /// A fully transparent solid paint leaves the destination untouched when composited with
+    /// source-over, but it still clears the covered area with compose modes such as
+    /// [`Compose::Copy`] or [`Compose::SrcIn`].
+    fn draw_has_effect(&self) -> bool {
+        self.paint_visible || self.blend_mode.compose != Compose::SrcOver
+    }
+
     /// Stroke a path with the current paint and stroke settings.
     pub fn stroke_path(&mut self, path: &BezPath) {
-        if !self.paint_visible {
+        if !self.draw_has_effect()
+            || !self.may_be_visible(path.bounding_box(), stroke_expansion(&self.stroke))
+        {
             return;
         }
 
         let paint = self.encode_current_paint();
-        self.stroke_path_with(path, self.transform, paint, self.aliasing_threshold);
+        self.generate_stroked_path(path, self.transform, paint, self.aliasing_threshold);
+    }
+
+    /// Fill a path with the given paint and fill rule.
+    ///
+    /// Unlike [`Scene::fill_path`], this doesn't use or modify the current paint and fill
+    /// rule, so draws with different paints can be interleaved without having to restore the
+    /// previous paint afterwards. The current transform, paint transform, blend mode and clips
+    /// still apply.
+    pub fn fill_path_with(&mut self, path: &BezPath, paint: &PaintType, fill_rule: Fill) {
+        // Simulate a `save` and `restore` of the fill rule and paint around the path.
+        let old_fill_rule = core::mem::replace(&mut self.fill_rule, fill_rule);
+        let old_paint = self.paint.clone();
+        let old_paint_visible = self.paint_visible;
+        self.set_paint(paint.clone());
+        self.fill_path(path);
+        self.paint = old_paint;
+        self.paint_visible = old_paint_visible;
+        self.fill_rule = old_fill_rule;
+    }
+
+    /// Stroke a path with the given paint and stroke settings.
+    ///
+    /// Unlike [`Scene::stroke_path`], this doesn't use or modify the current paint and stroke
+    /// settings. The current transform, paint transform, blend mode and clips still apply.
+    pub fn stroke_path_with(&mut self, path: &BezPath, paint: &PaintType, stroke: &Stroke) {
+        // Simulate a `save` and `restore` of the stroke and paint around the path.
+        let old_stroke = core::mem::replace(&mut self.stroke, stroke.clone());
+        let old_paint = self.paint.clone();
+        let old_paint_visible = self.paint_visible;
+        self.set_paint(paint.clone());
+        self.stroke_path(path);
+        self.paint = old_paint;
+        self.paint_visible = old_paint_visible;
+        self.stroke = old_stroke;
     }
 
     /// Build strips for a stroked path with the given properties.
@@ -278,7 +590,7 @@ impl Scene {
     /// and submits them to the coarse rasterizer. The path is first stroked and
     /// converted to strips by the strip generator, then the strips are processed by
     /// the wide coarse rasterizer to generate binned draw commands.
-    fn stroke_path_with(
+    fn generate_stroked_path(
         &mut self,
         path: &BezPath,
         transform: Affine,
@@ -297,12 +609,12 @@ impl Scene {
             self.clip_context.get(),
         );
 
-        wide.generate(
+        generate_wide_cmds(
+            wide,
+            &mut self.render_graph,
             &strip_storage.strips,
             paint,
             self.blend_mode,
-            0,
-            None,
             &self.encoded_paints,
         );
     }
@@ -316,20 +628,296 @@ impl Scene {
     /// the coverage is bigger than the threshold (between 0 and 255), otherwise
     /// it will not be painted at all.
     ///
+    /// Like the paint, the threshold applies to all following fills, strokes and glyphs until it
+    /// is changed again, so aliased and anti-aliased paths can be mixed in the same scene. Clip
+    /// paths use the threshold that is set when they are pushed, independently of their content.
+    ///
     /// Note that there is no performance benefit to disabling anti-aliasing and
     /// this functionality is simply provided for compatibility.
     pub fn set_aliasing_threshold(&mut self, aliasing_threshold: Option<u8>) {
         self.aliasing_threshold = aliasing_threshold;
     }
 
-    /// Fill a rectangle with the current paint and fill rule.
+    /// Set the tolerance used for flattening curves.
+    ///
+    /// This is the maximum distance between a curve and the line segments approximating it.
+    /// Paths are flattened after applying the current transform, so the tolerance is given
+    /// in device space (pixels), and zooming in automatically refines curves. Smaller values
+    /// result in smoother curves at the cost of generating more line segments.
+    ///
+    /// The default is [`DEFAULT_TOLERANCE`](vello_common::flatten::DEFAULT_TOLERANCE).
+    /// Non-positive or non-finite values are ignored.
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        if tolerance > 0.0 && tolerance.is_finite() {
+            self.tolerance = tolerance;
+            self.strip_generator.set_tolerance(tolerance);
+        }
+    }
+
+    /// Get the tolerance used for flattening curves, in device space.
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    /// Set the SIMD level used for generating strips.
+    ///
+    /// By default, the level is taken from [`RenderSettings`], which detects the best
+    /// level supported by the current CPU. Pinning a level, for example to
+    /// [`Level::fallback`] to force scalar code, can help reproduce rendering differences
+    /// between platforms.
+    pub fn set_simd_level(&mut self, level: Level) {
+        self.strip_generator.set_level(level);
+    }
+
+    /// Get the SIMD level used for generating strips.
+    pub fn simd_level(&self) -> Level {
+        self.strip_generator.level()
+    }
+
+    /// Fill a rectangle with the current paint.
+    ///
+    /// Rectangles that stay axis-aligned under the current transform are rasterized directly,
+    /// which is considerably cheaper than filling them as a path.
     pub fn fill_rect(&mut self, rect: &Rect) {
-        self.fill_path(&rect.to_path(DEFAULT_TOLERANCE));
+        if !self.draw_has_effect() || !self.may_be_visible(*rect, 0.0) {
+            return;
+        }
+
+        let paint = self.encode_current_paint();
+        let strip_storage = &mut self.strip_storage.borrow_mut();
+        self.strip_generator.generate_filled_rect(
+            rect,
+            self.transform,
+            self.aliasing_threshold,
+            strip_storage,
+            self.clip_context.get(),
+        );
+        generate_wide_cmds(
+            &mut self.wide,
+            &mut self.render_graph,
+            &strip_storage.strips,
+            paint,
+            self.blend_mode,
+            &self.encoded_paints,
+        );
     }
 
     /// Stroke a rectangle with the current paint and stroke settings.
     pub fn stroke_rect(&mut self, rect: &Rect) {
-        self.stroke_path(&rect.to_path(DEFAULT_TOLERANCE));
+        let path = self.rect_to_temp_path(rect);
+        self.stroke_path(&path);
+        self.temp_path = path;
+    }
+
+    /// Write the outline of a rectangle into the temporary path buffer and take it out of
+    /// the scene.
+    ///
+    /// The buffer should be put back after use, so that its allocation can be reused.
+    fn rect_to_temp_path(&mut self, rect: &Rect) -> BezPath {
+        let mut path = core::mem::take(&mut self.temp_path);
+        path.truncate(0);
+        push_rect(&mut path, rect);
+        path
+    }
+
+    /// Fill multiple rectangles with the current paint.
+    ///
+    /// The result is the same as calling [`Scene::fill_rect`] for each rectangle in order,
+    /// batching is purely an optimization: if the rectangles don't stay axis-aligned and no
+    /// two of them touch the same pixel, they are filled as a single path, so they are
+    /// flattened, tiled and converted to strips in one go. Axis-aligned rectangles already
+    /// take a fast path that doesn't need any of that, and overlapping ones need to be
+    /// composited one after another, so they are filled on their own.
+    pub fn fill_rects(&mut self, rects: &[Rect]) {
+        if preserves_axis_alignment(&self.transform) || rects_share_pixels(rects, self.transform) {
+            for rect in rects {
+                self.fill_rect(rect);
+            }
+            return;
+        }
+
+        let mut path = core::mem::take(&mut self.temp_path);
+        path.truncate(0);
+        for rect in rects {
+            push_rect(&mut path, rect);
+        }
+        // Match the fill rule `fill_rect` uses for rectangles that aren't axis-aligned.
+        // Since the rectangles don't overlap, the winding number is never more than 1, so
+        // this has no effect on the coverage.
+        let old_fill_rule = core::mem::replace(&mut self.fill_rule, Fill::NonZero);
+        self.fill_path(&path);
+        self.fill_rule = old_fill_rule;
+        self.temp_path = path;
+    }
+
+    /// Fill a circle with the current paint.
+    ///
+    /// Circles with a radius that isn't positive are not drawn.
+    pub fn fill_circle(&mut self, center: Point, radius: f64) {
+        if radius > 0.0 {
+            let path = self.shape_to_temp_path(&Circle::new(center, radius));
+            self.fill_path(&path);
+            self.temp_path = path;
+        }
+    }
+
+    /// Stroke a circle with the current paint and stroke settings.
+    ///
+    /// Circles with a radius that isn't positive are not drawn.
+    pub fn stroke_circle(&mut self, center: Point, radius: f64) {
+        if radius > 0.0 {
+            let path = self.shape_to_temp_path(&Circle::new(center, radius));
+            self.stroke_path(&path);
+            self.temp_path = path;
+        }
+    }
+
+    /// Fill an axis-aligned ellipse with the current paint.
+    ///
+    /// Ellipses with a radius that isn't positive are not drawn.
+    pub fn fill_ellipse(&mut self, center: Point, radii: Vec2) {
+        if radii.x > 0.0 && radii.y > 0.0 {
+            let path = self.shape_to_temp_path(&Ellipse::new(center, radii, 0.0));
+            self.fill_path(&path);
+            self.temp_path = path;
+        }
+    }
+
+    /// Stroke an axis-aligned ellipse with the current paint and stroke settings.
+    ///
+    /// Ellipses with a radius that isn't positive are not drawn.
+    pub fn stroke_ellipse(&mut self, center: Point, radii: Vec2) {
+        if radii.x > 0.0 && radii.y > 0.0 {
+            let path = self.shape_to_temp_path(&Ellipse::new(center, radii, 0.0));
+            self.stroke_path(&path);
+            self.temp_path = path;
+        }
+    }
+
+    /// Fill a rounded rectangle with the current paint.
+    ///
+    /// The radii of the corners are clamped to half of the shorter side of the rectangle.
+    /// If all radii are zero, this is the same as [`Scene::fill_rect`].
+    pub fn fill_rounded_rect(&mut self, rect: &Rect, radii: impl Into<RoundedRectRadii>) {
+        let rounded_rect = RoundedRect::from_rect(*rect, radii);
+        if rounded_rect.radii() == RoundedRectRadii::default() {
+            self.fill_rect(rect);
+        } else {
+            let path = self.shape_to_temp_path(&rounded_rect);
+            self.fill_path(&path);
+            self.temp_path = path;
+        }
+    }
+
+    /// Stroke a rounded rectangle with the current paint and stroke settings.
+    ///
+    /// The radii of the corners are clamped to half of the shorter side of the rectangle.
+    /// If all radii are zero, this is the same as [`Scene::stroke_rect`].
+    pub fn stroke_rounded_rect(&mut self, rect: &Rect, radii: impl Into<RoundedRectRadii>) {
+        let rounded_rect = RoundedRect::from_rect(*rect, radii);
+        if rounded_rect.radii() == RoundedRectRadii::default() {
+            self.stroke_rect(rect);
+        } else {
+            let path = self.shape_to_temp_path(&rounded_rect);
+            self.stroke_path(&path);
+            self.temp_path = path;
+        }
+    }
+
+    /// Write the outline of a shape into the temporary path buffer and take it out of
+    /// the scene, like [`Scene::rect_to_temp_path`].
+    ///
+    /// Curved shapes are approximated with Béziers, which is done in user space, so the
+    /// tolerance of the scene is scaled down by the largest scale of the current transform.
+    fn shape_to_temp_path(&mut self, shape: &impl Shape) -> BezPath {
+        let (max_scale, _) = extract_scales(&self.transform);
+        let tolerance = self.tolerance / f64::from(max_scale).max(1.);
+
+        let mut path = core::mem::take(&mut self.temp_path);
+        path.truncate(0);
+        path.extend(shape.path_elements(tolerance));
+        path
+    }
+
+    /// Fill a blurred rectangle with the given corner radius and standard deviation.
+    ///
+    /// The blur is evaluated analytically per pixel, so this is much cheaper than blurring
+    /// a rounded rectangle with a filter layer.
+    ///
+    /// Note that this only works properly if the current paint is set to a solid color.
+    /// If not, it will fall back to using black as the fill color.
+    pub fn fill_blurred_rounded_rect(&mut self, rect: &Rect, radius: f32, std_dev: f32) {
+        let color = match self.paint {
+            PaintType::Solid(s) => s,
+            // Fallback to black when attempting to blur a rectangle with an image/gradient paint
+            _ => BLACK,
+        };
+
+        if !self.draw_has_effect() {
+            return;
+        }
+
+        let blurred_rect = BlurredRoundedRectangle {
+            rect: *rect,
+            color,
+            radius,
+            std_dev,
+        };
+
+        // The actual rectangle we paint needs to be larger so that the blurring effect
+        // is not cut off.
+        // The impulse response of a gaussian filter is infinite.
+        // For performance reason we cut off the filter at some extent where the response is close to zero.
+        let kernel_size = 2.5 * std_dev;
+        let inflated_rect = rect.inflate(f64::from(kernel_size), f64::from(kernel_size));
+        let transform = self.transform * self.paint_transform;
+
+        let paint = blurred_rect.encode_into(&mut self.encoded_paints, transform);
+        let path = self.rect_to_temp_path(&inflated_rect);
+        self.generate_filled_path(
+            &path,
+            self.transform,
+            Fill::NonZero,
+            paint,
+            self.aliasing_threshold,
+        );
+        self.temp_path = path;
+    }
+
+    /// Draw the `src` rectangle of an image into the axis-aligned `dst` rectangle.
+    ///
+    /// `src` is in image pixels and is stretched to fill the destination, which may scale it
+    /// differently horizontally and vertically. A scene only refers to images by their id, and
+    /// their sizes are only known to the renderer they were uploaded to, so the source
+    /// rectangle can't default to the whole image. Use `Rect::new(0.0, 0.0, width, height)` to
+    /// draw all of it.
+    ///
+    /// The image is sampled with `sampler`, so parts of `src` outside of the image are filled
+    /// according to its extend modes. Like for image paints, [`ImageQuality::Medium`] is lowered
+    /// to [`ImageQuality::Low`] when the image is copied to whole pixels without scaling. This
+    /// applies the current transform, but ignores the current paint and paint transform.
+    pub fn draw_image(&mut self, image: ImageId, dst: Rect, src: Rect, sampler: ImageSampler) {
+        let (dst, src) = (dst.abs(), src.abs());
+        if src.is_zero_area() || dst.is_zero_area() {
+            return;
+        }
+
+        let paint_transform = Affine::translate((dst.x0, dst.y0))
+            * Affine::scale_non_uniform(dst.width() / src.width(), dst.height() / src.height())
+            * Affine::translate((-src.x0, -src.y0));
+
+        // Simulate a `save` and `restore` of the paint and paint transform around the image.
+        let old_paint_transform = core::mem::replace(&mut self.paint_transform, paint_transform);
+        let old_paint = self.paint.clone();
+        let old_paint_visible = self.paint_visible;
+        self.set_paint(Image {
+            image: ImageSource::OpaqueId(image),
+            sampler,
+        });
+        self.fill_rect(&dst);
+        self.paint = old_paint;
+        self.paint_transform = old_paint_transform;
+        self.paint_visible = old_paint_visible;
     }
 
     /// Creates a builder for drawing a run of glyphs that have the same attributes.
@@ -337,9 +925,103 @@ impl Scene {
         GlyphRunBuilder::new(font.clone(), self.transform, self)
     }
 
+    /// Clear the caches of glyph outlines and hinting instances, releasing their memory.
+    ///
+    /// This is useful for reclaiming memory, for example after the used fonts changed. The
+    /// caches are populated again by the following glyph runs, so it is safe to call this at any
+    /// point between drawing commands.
+    pub fn clear_glyph_caches(&mut self) {
+        self.glyph_caches = Some(GlyphCaches::default());
+    }
+
+    /// Set the atlas that glyphs are drawn from, or `None` to rasterize every glyph into strips.
+    ///
+    /// See [`GlyphAtlas`] for how the atlas is filled. Call `Renderer::upload_glyphs` (or
+    /// `WebGlRenderer::upload_glyphs`) with [`Scene::glyph_atlas_mut`] after building each
+    /// scene and before rendering it. To stop using an atlas without leaking its images in the
+    /// renderer, [`clear`](GlyphAtlas::clear) it and upload it once more before removing it.
+    pub fn set_glyph_atlas(&mut self, glyph_atlas: Option<GlyphAtlas>) {
+        self.glyph_atlas = glyph_atlas;
+        if let Some(glyph_atlas) = &mut self.glyph_atlas {
+            glyph_atlas.set_level(self.strip_generator.level());
+        }
+    }
+
+    /// Get the atlas that glyphs are drawn from, if any.
+    pub fn glyph_atlas(&self) -> Option<&GlyphAtlas> {
+        self.glyph_atlas.as_ref()
+    }
+
+    /// Get the atlas that glyphs are drawn from mutably, for example to upload it.
+    pub fn glyph_atlas_mut(&mut self) -> Option<&mut GlyphAtlas> {
+        self.glyph_atlas.as_mut()
+    }
+
+    /// Fill an outline glyph with the current paint from the glyph atlas, if possible.
+    ///
+    /// Returns `false` if the glyph must be drawn as a path instead.
+    fn fill_atlas_glyph(&mut self, glyph: &OutlineGlyph<'_>, transform: Affine) -> bool {
+        // The atlas stores anti-aliased glyphs with their color baked in.
+        let PaintType::Solid(color) = self.paint else {
+            return false;
+        };
+        let Some(glyph_atlas) = &mut self.glyph_atlas else {
+            return false;
+        };
+        if self.aliasing_threshold.is_some() {
+            return false;
+        }
+
+        let (image, rect) =
+            match glyph_atlas.get_or_insert(glyph, transform, PremulColor::from_alpha_color(color))
+            {
+                AtlasGlyph::Uploaded { image, rect } => (image, rect),
+                AtlasGlyph::Empty => return true,
+                AtlasGlyph::Missing => return false,
+            };
+
+        // The rectangle is aligned to whole pixels, so the image is copied without filtering.
+        let old_transform = core::mem::replace(&mut self.transform, Affine::IDENTITY);
+        let old_paint_transform = core::mem::replace(
+            &mut self.paint_transform,
+            Affine::translate((rect.x0, rect.y0)),
+        );
+        let old_paint = core::mem::replace(
+            &mut self.paint,
+            PaintType::Image(Image {
+                image: ImageSource::OpaqueId(image),
+                sampler: ImageSampler::new().with_quality(ImageQuality::Low),
+            }),
+        );
+        self.fill_rect(&rect);
+        self.paint = old_paint;
+        self.paint_transform = old_paint_transform;
+        self.transform = old_transform;
+
+        true
+    }
+
+    /// Return the number of entries in the caches of glyph outlines and hinting instances, and
+    /// an estimate of the memory used by them.
+    pub fn glyph_cache_stats(&self) -> GlyphCacheStats {
+        self.glyph_caches
+            .as_ref()
+            .map(GlyphCaches::stats)
+            .unwrap_or_default()
+    }
+
     /// Push a new layer with the given properties.
     ///
     /// Only `clip_path` is supported for now.
+    ///
+    /// By default, the contents of the layer are blended as a whole with the backdrop using
+    /// `blend_mode`. If `isolate` is set, the contents are instead blended with each other
+    /// using `blend_mode` on a transparent background, like with `isolation: isolate` in CSS,
+    /// and the layer is composited onto the backdrop using source-over. The blend mode set with
+    /// [`Scene::set_blend_mode`] is then replaced by `blend_mode` until the layer is popped.
+    ///
+    /// If this would nest more layers than [`RenderSettings::max_layer_depth`] allows, the
+    /// layer is ignored and rendering the scene will fail.
     // TODO: Implement filter integration.
     pub fn push_layer(
         &mut self,
@@ -348,6 +1030,29 @@ impl Scene {
         opacity: Option<f32>,
         mask: Option<Mask>,
         filter: Option<Filter>,
+        isolate: bool,
+    ) {
+        if self.ignore_pushed_layer() {
+            return;
+        }
+
+        if isolate {
+            self.push_unignored_layer(clip_path, None, opacity, mask, filter);
+            let outer = core::mem::replace(&mut self.blend_mode, blend_mode.unwrap_or_default());
+            *self.layer_blend_modes.last_mut().unwrap() = Some(outer);
+        } else {
+            self.push_unignored_layer(clip_path, blend_mode, opacity, mask, filter);
+        }
+    }
+
+    /// Push a layer that must not be ignored for exceeding `max_layer_depth`.
+    fn push_unignored_layer(
+        &mut self,
+        clip_path: Option<&BezPath>,
+        blend_mode: Option<BlendMode>,
+        opacity: Option<f32>,
+        mask: Option<Mask>,
+        filter: Option<Filter>,
     ) {
         if filter.is_some() {
             unimplemented!("Filter effects are not yet supported in vello_hybrid");
@@ -355,6 +1060,16 @@ impl Scene {
 
         let mut strip_storage = self.strip_storage.borrow_mut();
 
+        self.layer_blend_modes.push(None);
+        let parent_bounds = self.layer_clip_bounds.last().copied().flatten();
+        self.layer_clip_bounds.push(match clip_path {
+            Some(c) => Some(intersect_clip_bounds(
+                parent_bounds,
+                self.transform.transform_rect_bbox(c.bounding_box()),
+            )),
+            None => parent_bounds,
+        });
+
         let clip = if let Some(c) = clip_path {
             self.strip_generator.generate_filled_path(
                 c,
@@ -388,41 +1103,122 @@ impl Scene {
         );
     }
 
+    /// Check whether a layer that is about to be pushed has to be ignored because it exceeds
+    /// the maximum layer depth, and record it as ignored if so.
+    fn ignore_pushed_layer(&mut self) -> bool {
+        if self.ignored_layers > 0
+            || self
+                .max_layer_depth
+                .is_some_and(|max| self.layer_clip_bounds.len() >= max)
+        {
+            self.ignored_layers += 1;
+            self.layer_depth_exceeded = true;
+            return true;
+        }
+
+        false
+    }
+
+    /// Push a clip layer whose clip strips were generated when preparing a recording.
+    fn push_cached_clip_layer(&mut self, clip_path: &BezPath, clip_strips: &[Strip]) {
+        if self.ignore_pushed_layer() {
+            return;
+        }
+
+        self.layer_blend_modes.push(None);
+        let parent_bounds = self.layer_clip_bounds.last().copied().flatten();
+        self.layer_clip_bounds.push(Some(intersect_clip_bounds(
+            parent_bounds,
+            self.transform.transform_rect_bbox(clip_path.bounding_box()),
+        )));
+        self.wide.push_layer(
+            0,
+            Some(clip_strips),
+            BlendMode::new(Mix::Normal, Compose::SrcOver),
+            None,
+            1.,
+            None,
+            self.transform,
+            &mut self.render_graph,
+            0,
+        );
+    }
+
     /// Push a new clip layer.
     ///
     /// See the explanation in the [clipping](https://github.com/linebender/vello/tree/main/sparse_strips/vello_cpu/examples)
     /// example for how this method differs from `push_clip_path`.
     pub fn push_clip_layer(&mut self, path: &BezPath) {
-        self.push_layer(Some(path), None, None, None, None);
+        self.push_layer(Some(path), None, None, None, None, false);
     }
 
     /// Push a new blend layer.
     pub fn push_blend_layer(&mut self, blend_mode: BlendMode) {
-        self.push_layer(None, Some(blend_mode), None, None, None);
+        self.push_layer(None, Some(blend_mode), None, None, None, false);
     }
 
     /// Push a new opacity layer.
     pub fn push_opacity_layer(&mut self, opacity: f32) {
-        self.push_layer(None, None, Some(opacity), None, None);
+        self.push_layer(None, None, Some(opacity), None, None, false);
+    }
+
+    /// Push a new isolated layer, whose contents are blended with each other using
+    /// `blend_mode`.
+    ///
+    /// See [`Scene::push_layer`] for how this differs from [`Scene::push_blend_layer`].
+    pub fn push_isolated_layer(&mut self, blend_mode: BlendMode) {
+        self.push_layer(None, Some(blend_mode), None, None, None, true);
     }
 
     /// Push a new mask layer.
     ///
     /// Note that masks are not yet supported in `vello_hybrid`.
     pub fn push_mask_layer(&mut self, mask: Mask) {
-        self.push_layer(None, None, None, Some(mask), None);
+        self.push_layer(None, None, None, Some(mask), None, false);
     }
 
     /// Push a new filter layer.
     ///
     /// Note that filters are currently ignored in `vello_hybrid`.
     pub fn push_filter_layer(&mut self, filter: Filter) {
-        self.push_layer(None, None, None, None, Some(filter));
+        self.push_layer(None, None, None, None, Some(filter), false);
+    }
+
+    /// Push a new layer whose contents start out as `backdrop` instead of being transparent.
+    ///
+    /// This is useful for effects that need to operate on a known, non-transparent backdrop.
+    /// The slots of the layer are cleared to the backdrop color, so it covers the whole layer
+    /// and is clipped by the currently active clips; the layer is composited using source-over
+    /// once it is popped.
+    pub fn push_backdrop_layer(&mut self, backdrop: AlphaColor<Srgb>) {
+        if self.ignore_pushed_layer() {
+            return;
+        }
+
+        let backdrop = backdrop.premultiply().to_rgba8();
+        if backdrop.a == 0 {
+            self.push_unignored_layer(None, None, None, None, None);
+            return;
+        }
+
+        self.layer_blend_modes.push(None);
+        self.layer_clip_bounds
+            .push(self.layer_clip_bounds.last().copied().flatten());
+        self.wide.push_backdrop_layer(0, backdrop);
     }
 
     /// Pop the last pushed layer.
     pub fn pop_layer(&mut self) {
+        if self.ignored_layers > 0 {
+            self.ignored_layers -= 1;
+            return;
+        }
+
         self.wide.pop_layer(&mut self.render_graph);
+        self.layer_clip_bounds.pop();
+        if let Some(Some(blend_mode)) = self.layer_blend_modes.pop() {
+            self.blend_mode = blend_mode;
+        }
     }
 
     /// Set the blend mode for subsequent rendering operations.
@@ -435,6 +1231,17 @@ impl Scene {
         self.stroke = stroke;
     }
 
+    /// Set the dash offset of the current stroke, wrapped into a single period of its dash
+    /// pattern.
+    ///
+    /// This animates the dashes along a path, for example for "marching ants", without
+    /// rebuilding the path: keep increasing `dash_offset` from frame to frame, and the dashes
+    /// move continuously, also when the offset wraps around. The start and end caps of the
+    /// stroke are applied to both ends of every dash.
+    pub fn set_dash_offset(&mut self, dash_offset: f64) {
+        self.stroke.dash_offset = wrap_dash_offset(dash_offset, &self.stroke.dash_pattern);
+    }
+
     /// Set the paint for subsequent rendering operations.
     // TODO: This API is not final. Supporting images from a pixmap is explicitly out of scope.
     //       Instead images should be passed via a backend-agnostic opaque id, and be hydrated at
@@ -476,6 +1283,38 @@ impl Scene {
         self.transform = Affine::IDENTITY;
     }
 
+    /// Save the current render state, so that it can be restored with [`Scene::restore`].
+    ///
+    /// The render state consists of the transform, paint, paint transform, stroke, fill rule
+    /// and blend mode. Calls to `save` and `restore` can be nested.
+    pub fn save(&mut self) {
+        let state = self.copy_current_state();
+        self.saved_states.push(state);
+    }
+
+    /// Restore the render state that was saved by the matching call to [`Scene::save`].
+    ///
+    /// Calling this method without a matching `save` has no effect.
+    pub fn restore(&mut self) {
+        if let Some(state) = self.saved_states.pop() {
+            self.restore_state(state);
+        }
+    }
+
+    /// Return a snapshot of the current render state, including the clip and layer depth.
+    pub fn current_state(&self) -> RenderStateSnapshot {
+        RenderStateSnapshot {
+            transform: self.transform,
+            paint: self.paint.clone(),
+            paint_transform: self.paint_transform,
+            stroke: self.stroke.clone(),
+            fill_rule: self.fill_rule,
+            blend_mode: self.blend_mode,
+            clip_depth: self.clip_path_bounds.len(),
+            layer_depth: self.layer_clip_bounds.len() + self.ignored_layers,
+        }
+    }
+
     /// Apply filter to the current paint (affects next drawn element)
     pub fn set_filter_effect(&mut self, _filter: Filter) {
         unimplemented!("Filter effects integration with Scene")
@@ -487,10 +1326,18 @@ impl Scene {
     }
 
     /// Reset scene to default values.
+    ///
+    /// The buffers of the scene keep their capacity, so that rendering frames of similar
+    /// complexity doesn't allocate. Use [`Scene::shrink_to_fit`] to release that memory.
     pub fn reset(&mut self) {
         self.wide.reset();
         self.strip_generator.reset();
         self.clip_context.reset();
+        self.clip_path_bounds.clear();
+        self.layer_clip_bounds.clear();
+        self.layer_blend_modes.clear();
+        self.ignored_layers = 0;
+        self.layer_depth_exceeded = false;
         self.strip_storage.borrow_mut().clear();
         self.encoded_paints.clear();
 
@@ -501,27 +1348,483 @@ impl Scene {
         self.paint = render_state.paint;
         self.stroke = render_state.stroke;
         self.blend_mode = render_state.blend_mode;
+        self.paint_visible = render_state.paint_visible;
+        self.saved_states.clear();
 
         self.glyph_caches.as_mut().unwrap().maintain();
+        if let Some(glyph_atlas) = &mut self.glyph_atlas {
+            glyph_atlas.maintain();
+        }
     }
 
-    /// Get the width of the render context.
-    pub fn width(&self) -> u16 {
-        self.width
+    /// Return how many tiles, strips, alpha bytes and wide tiles the scene produced since the
+    /// last [`Scene::reset`].
+    ///
+    /// This helps to understand why a scene is slow to render, for example because a complex
+    /// path generates a large number of tiles.
+    pub fn tile_stats(&self) -> TileStats {
+        let wide_tiles = self
+            .wide
+            .tiles()
+            .iter()
+            .filter(|tile| !tile.cmds.is_empty())
+            .count();
+
+        TileStats {
+            wide_tiles: u32::try_from(wide_tiles).unwrap_or(u32::MAX),
+            ..self.strip_generator.tile_stats()
+        }
     }
 
-    /// Get the height of the render context.
+    /// Return how often the buffers for generating strips had to grow since the last
+    /// [`Scene::reset`].
+    ///
+    /// This helps to catch per-frame allocations: after rendering a frame once to warm up the
+    /// buffers, rendering frames of similar complexity should report no growth at all. Note
+    /// that [`Scene::freeze`] hands the strip buffers over to the frozen scene, so the next
+    /// frame has to allocate them again.
+    ///
+    /// This is only available in debug builds (`debug_assertions`).
+    #[cfg(debug_assertions)]
+    pub fn alloc_stats(&self) -> AllocStats {
+        self.strip_generator.alloc_stats()
+    }
+
+    /// Release the capacity of the scene's buffers that is not currently in use.
+    ///
+    /// Calling this after [`Scene::reset`] frees most of the memory held by the scene,
+    /// for example after rendering an unusually complex frame.
+    pub fn shrink_to_fit(&mut self) {
+        self.wide.shrink_to_fit();
+        self.strip_generator.shrink_to_fit();
+        self.clip_context.shrink_to_fit();
+        self.clip_path_bounds.shrink_to_fit();
+        self.layer_clip_bounds.shrink_to_fit();
+        self.layer_blend_modes.shrink_to_fit();
+        self.strip_storage.borrow_mut().shrink_to_fit();
+        self.encoded_paints.shrink_to_fit();
+        self.saved_states.shrink_to_fit();
+    }
+
+    /// Get the width of the render context.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Get the height of the render context.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Get the wide tiles of the scene, together with their coarse draw commands.
+    ///
+    /// This is a read-only view of the intermediate representation that the renderer
+    /// consumes, which is useful for debugging or for implementing alternative backends.
+    /// The commands of each tile are stored in [`WideTile::cmds`](vello_common::coarse::WideTile::cmds)
+    /// and reference their shared attributes in [`Wide::attrs`].
+    pub fn wide(&self) -> &Wide<MODE_HYBRID> {
+        &self.wide
+    }
+
+    /// Get the encoded gradient and image paints referenced by the scene.
+    pub fn encoded_paints(&self) -> &[EncodedPaint] {
+        &self.encoded_paints
+    }
+
+    /// Take the render data of the scene out into an immutable [`FrozenScene`].
+    ///
+    /// The frozen scene owns its coarse commands, encoded paints, strips and alphas, so
+    /// it can be handed to another thread and rendered there, while this scene is reset
+    /// (as if by [`Scene::reset`]) and can immediately be used to build the next frame.
+    pub fn freeze(&mut self) -> FrozenScene {
+        let empty_wide = Wide::<MODE_HYBRID>::new_with_tile_width(
+            self.width,
+            self.height,
+            self.wide.tile_width(),
+        );
+        let wide = core::mem::replace(&mut self.wide, empty_wide);
+        let encoded_paints = core::mem::take(&mut self.encoded_paints);
+        let layer_depth_exceeded = self.layer_depth_exceeded;
+        let (strips, alphas, alphas_exhausted) = {
+            let mut strip_storage = self.strip_storage.borrow_mut();
+            (
+                core::mem::take(&mut strip_storage.strips),
+                core::mem::take(&mut strip_storage.alphas),
+                strip_storage.alphas_exhausted,
+            )
+        };
+        self.reset();
+
+        FrozenScene {
+            width: self.width,
+            height: self.height,
+            wide,
+            encoded_paints,
+            strips,
+            alphas,
+            layer_depth_exceeded,
+            alphas_exhausted,
+        }
+    }
+
+    /// Borrow the data that a renderer needs to draw the scene.
+    pub(crate) fn with_view<R>(&self, f: impl FnOnce(SceneView<'_>) -> R) -> R {
+        let strip_storage = self.strip_storage.borrow();
+        f(SceneView {
+            wide: &self.wide,
+            encoded_paints: &self.encoded_paints,
+            alphas: &strip_storage.alphas,
+            layer_depth_exceeded: self.layer_depth_exceeded,
+            alphas_exhausted: strip_storage.alphas_exhausted,
+        })
+    }
+
+    /// Replay a display list, with all of its transforms composed with `root_transform`.
+    ///
+    /// The commands are drawn starting from the current paint, fill rule and stroke of the
+    /// scene, which are restored once the display list has been replayed. Layers that are
+    /// left open by the display list are popped at the end, and pops that don't match a
+    /// layer pushed by the display list are ignored, so layers pushed before calling this
+    /// method are never affected.
+    pub fn replay(&mut self, display_list: &DisplayList, root_transform: Affine) {
+        let saved_state = self.copy_current_state();
+        self.transform = root_transform;
+        let mut depth = 0_usize;
+
+        for command in display_list.commands() {
+            match command {
+                RenderCommand::FillPath(path) => self.fill_path(path),
+                RenderCommand::StrokePath(path) => self.stroke_path(path),
+                RenderCommand::FillRect(rect) => self.fill_rect(rect),
+                RenderCommand::StrokeRect(rect) => self.stroke_rect(rect),
+                RenderCommand::FillOutlineGlyph((path, glyph_transform)) => {
+                    let paint = self.encode_current_paint();
+                    self.generate_filled_path(
+                        path,
+                        root_transform * *glyph_transform,
+                        Fill::NonZero,
+                        paint,
+                        self.aliasing_threshold,
+                    );
+                }
+                RenderCommand::StrokeOutlineGlyph((path, glyph_transform)) => {
+                    let paint = self.encode_current_paint();
+                    self.generate_stroked_path(
+                        path,
+                        root_transform * *glyph_transform,
+                        paint,
+                        self.aliasing_threshold,
+                    );
+                }
+                RenderCommand::StrokeOutlineGlyphWith((path, glyph_transform, stroke, paint)) => {
+                    // Simulate a `save` and `restore` of the stroke and paint around the glyph.
+                    let old_stroke = core::mem::replace(&mut self.stroke, stroke.clone());
+                    let old_paint = self.paint.clone();
+                    self.set_paint(paint.clone());
+                    let paint = self.encode_current_paint();
+                    self.generate_stroked_path(
+                        path,
+                        root_transform * *glyph_transform,
+                        paint,
+                        self.aliasing_threshold,
+                    );
+                    self.set_paint(old_paint);
+                    self.stroke = old_stroke;
+                }
+                RenderCommand::SetTransform(transform) => {
+                    self.set_transform(root_transform * *transform);
+                }
+                RenderCommand::SetPaint(paint) => self.set_paint(paint.clone()),
+                RenderCommand::SetPaintTransform(transform) => self.set_paint_transform(*transform),
+                RenderCommand::ResetPaintTransform => self.reset_paint_transform(),
+                RenderCommand::SetFillRule(fill_rule) => self.set_fill_rule(*fill_rule),
+                RenderCommand::SetStroke(stroke) => self.set_stroke(stroke.clone()),
+                RenderCommand::SetFilterEffect(filter) => self.set_filter_effect(filter.clone()),
+                RenderCommand::ResetFilterEffect => self.reset_filter_effect(),
+                RenderCommand::PushLayer(PushLayerCommand {
+                    clip_path,
+                    blend_mode,
+                    opacity,
+                    mask,
+                    filter,
+                }) => {
+                    self.push_layer(
+                        clip_path.as_ref(),
+                        *blend_mode,
+                        *opacity,
+                        mask.clone(),
+                        filter.clone(),
+                        false,
+                    );
+                    depth += 1;
+                }
+                RenderCommand::PushClip(clip_path) => {
+                    self.push_clip_layer(clip_path);
+                    depth += 1;
+                }
+                RenderCommand::PopLayer => {
+                    if depth > 0 {
+                        self.pop_layer();
+                        depth -= 1;
+                    }
+                }
+            }
+        }
+
+        for _ in 0..depth {
+            self.pop_layer();
+        }
+        self.restore_state(saved_state);
+    }
+
+    /// Draw the contents of another scene on top of the contents of this scene, moved by
+    /// `transform`.
+    ///
+    /// This allows building independent parts of a scene on different threads and merging them
+    /// afterwards. Unlike [`Scene::replay`], the already rasterized commands of `other` are reused
+    /// as is, so the current state of this scene, like its transform and clip paths, doesn't
+    /// apply to them. Parts of `other` that are moved outside of this scene are dropped.
+    ///
+    /// If the alphas of both scenes together don't fit into the alpha buffer, nothing is
+    /// appended and rendering the scene fails with
+    /// [`RenderError::AlphasExhausted`](crate::RenderError::AlphasExhausted).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `transform` isn't a translation by whole pixels horizontally and by a multiple
+    /// of the tile height vertically, or if either scene has layers that haven't been popped
+    /// yet. Also panics if `other` contains layers with destructive blend modes and the
+    /// horizontal offset isn't a multiple of the wide tile width.
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "Offsets that are truncated are rejected by the assertion below"
+    )]
+    pub fn append(&mut self, other: &Self, transform: Affine) {
+        let [a, b, c, d, e, f] = transform.as_coeffs();
+        let (dx, dy) = (e as i32, f as i32);
+        assert!(
+            a == 1.0
+                && b == 0.0
+                && c == 0.0
+                && d == 1.0
+                && f64::from(dx) == e
+                && f64::from(dy) == f
+                && dy % i32::from(Tile::HEIGHT) == 0,
+            "only translations by whole pixels horizontally and whole tiles vertically are supported"
+        );
+
+        let strip_storage = self.strip_storage.get_mut();
+        let other_strip_storage = other.strip_storage.borrow();
+        strip_storage.alphas_exhausted |= other_strip_storage.alphas_exhausted;
+        let Some(alpha_offset) = strip_storage.extend_alphas(&other_strip_storage.alphas) else {
+            return;
+        };
+
+        let paint_offset = self.encoded_paints.len();
+        self.encoded_paints
+            .extend(other.encoded_paints.iter().map(|paint| {
+                let mut paint = paint.clone();
+                paint.translate(Vec2::new(e, f));
+                paint
+            }));
+        self.layer_depth_exceeded |= other.layer_depth_exceeded;
+        self.wide
+            .append(&other.wide, dx, dy, paint_offset, alpha_offset);
+    }
+}
+
+/// An immutable snapshot of the render data of a [`Scene`], created by [`Scene::freeze`].
+///
+/// Unlike a [`Scene`], a frozen scene has no interior mutability and is `Send + Sync`, so
+/// it can be rendered on a different thread than the one building the next frame.
+#[derive(Debug)]
+pub struct FrozenScene {
+    width: u16,
+    height: u16,
+    wide: Wide<MODE_HYBRID>,
+    encoded_paints: Vec<EncodedPaint>,
+    strips: Vec<Strip>,
+    alphas: Vec<u8>,
+    layer_depth_exceeded: bool,
+    alphas_exhausted: bool,
+}
+
+impl FrozenScene {
+    /// Get the width of the frozen scene.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Get the height of the frozen scene.
     pub fn height(&self) -> u16 {
         self.height
     }
+
+    /// Get the wide tiles of the frozen scene, together with their coarse draw commands.
+    pub fn wide(&self) -> &Wide<MODE_HYBRID> {
+        &self.wide
+    }
+
+    /// Get the encoded gradient and image paints referenced by the frozen scene.
+    pub fn encoded_paints(&self) -> &[EncodedPaint] {
+        &self.encoded_paints
+    }
+
+    /// Get the strips of the frozen scene.
+    pub fn strips(&self) -> &[Strip] {
+        &self.strips
+    }
+
+    /// Get the alpha values referenced by the strips and commands of the frozen scene.
+    pub fn alphas(&self) -> &[u8] {
+        &self.alphas
+    }
+
+    /// Borrow the data that a renderer needs to draw the scene.
+    pub(crate) fn view(&self) -> SceneView<'_> {
+        SceneView {
+            wide: &self.wide,
+            encoded_paints: &self.encoded_paints,
+            alphas: &self.alphas,
+            layer_depth_exceeded: self.layer_depth_exceeded,
+            alphas_exhausted: self.alphas_exhausted,
+        }
+    }
+}
+
+/// The data of a [`Scene`] or [`FrozenScene`] that is consumed by the renderers.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SceneView<'a> {
+    /// Wide tiles with their coarse draw commands.
+    pub(crate) wide: &'a Wide<MODE_HYBRID>,
+    /// Encoded gradient and image paints referenced by the commands.
+    pub(crate) encoded_paints: &'a [EncodedPaint],
+    /// Alpha values referenced by the commands.
+    pub(crate) alphas: &'a [u8],
+    /// Whether layers were ignored for exceeding [`RenderSettings::max_layer_depth`].
+    pub(crate) layer_depth_exceeded: bool,
+    /// Whether geometry was dropped because its alphas didn't fit into the alpha buffer.
+    pub(crate) alphas_exhausted: bool,
+}
+
+/// Get how far the outline of a stroke can extend beyond its path, in path coordinates.
+fn stroke_expansion(stroke: &Stroke) -> f64 {
+    let mut scale = 1.0_f64;
+    if stroke.join == Join::Miter {
+        scale = scale.max(stroke.miter_limit);
+    }
+    if stroke.start_cap == Cap::Square || stroke.end_cap == Cap::Square {
+        scale = scale.max(core::f64::consts::SQRT_2);
+    }
+    0.5 * stroke.width * scale
+}
+
+/// Get the range of the cached strips of the geometry or clip command with index `range_index`
+/// in a prepared recording.
+fn cached_strip_range(
+    strip_start_indices: &[usize],
+    range_index: usize,
+    strip_count: usize,
+) -> core::ops::Range<usize> {
+    assert!(
+        range_index < strip_start_indices.len(),
+        "Strip range index out of bounds: range_index={}, strip_start_indices.len()={}",
+        range_index,
+        strip_start_indices.len()
+    );
+    let start = strip_start_indices[range_index];
+    let end = strip_start_indices
+        .get(range_index + 1)
+        .copied()
+        .unwrap_or(strip_count);
+    assert!(
+        start <= end && end <= strip_count,
+        "Invalid strip range: start={start}, end={end}, strip_count={strip_count}"
+    );
+    start..end
+}
+
+/// Generate the wide tile commands for drawing `strips` with `paint` and `blend_mode`.
+///
+/// The blend modes of single draws are ignored when rendering, only the blend modes of layers
+/// are applied when compositing them. So draws that mix with their backdrop, like the ones in
+/// isolated layers, are wrapped in a layer of their own that is blended using `blend_mode`.
+fn generate_wide_cmds(
+    wide: &mut Wide<MODE_HYBRID>,
+    render_graph: &mut RenderGraph,
+    strips: &[Strip],
+    paint: Paint,
+    blend_mode: BlendMode,
+    encoded_paints: &[EncodedPaint],
+) {
+    if blend_mode.mix == Mix::Normal || blend_mode.compose != Compose::SrcOver {
+        wide.generate(strips, paint, blend_mode, 0, None, encoded_paints);
+        return;
+    }
+
+    wide.push_layer(
+        0,
+        None,
+        blend_mode,
+        None,
+        1.,
+        None,
+        Affine::IDENTITY,
+        render_graph,
+        0,
+    );
+    wide.generate(
+        strips,
+        paint,
+        BlendMode::new(Mix::Normal, Compose::SrcOver),
+        0,
+        None,
+        encoded_paints,
+    );
+    wide.pop_layer(render_graph);
+}
+
+/// Intersect the bounds of a clip with the bounds of its parent clip, if there is one.
+fn intersect_clip_bounds(parent: Option<Rect>, bounds: Rect) -> Rect {
+    parent.map_or(bounds, |parent| parent.intersect(bounds))
+}
+
+/// Append the outline of a rectangle to a path.
+fn push_rect(path: &mut BezPath, rect: &Rect) {
+    path.push(PathEl::MoveTo(Point::new(rect.x0, rect.y0)));
+    path.push(PathEl::LineTo(Point::new(rect.x1, rect.y0)));
+    path.push(PathEl::LineTo(Point::new(rect.x1, rect.y1)));
+    path.push(PathEl::LineTo(Point::new(rect.x0, rect.y1)));
+    path.push(PathEl::ClosePath);
+}
+
+/// Whether any two of the rectangles touch the same pixel after being transformed.
+fn rects_share_pixels(rects: &[Rect], transform: Affine) -> bool {
+    let mut bboxes = rects
+        .iter()
+        .map(|rect| transform.transform_rect_bbox(*rect).expand())
+        .collect::<Vec<_>>();
+    bboxes.sort_unstable_by(|a, b| a.y0.total_cmp(&b.y0));
+
+    // Only the rectangles starting above the bottom edge of `a` can share rows with it.
+    bboxes.iter().enumerate().any(|(i, a)| {
+        bboxes[i + 1..]
+            .iter()
+            .take_while(|b| b.y0 < a.y1)
+            .any(|b| b.x0 < a.x1 && a.x0 < b.x1)
+    })
 }
 
 impl GlyphRenderer for Scene {
     fn fill_glyph(&mut self, prepared_glyph: PreparedGlyph<'_>) {
         match prepared_glyph.glyph_type {
             GlyphType::Outline(glyph) => {
+                if self.fill_atlas_glyph(&glyph, prepared_glyph.transform) {
+                    return;
+                }
+
                 let paint = self.encode_current_paint();
-                self.fill_path_with(
+                self.generate_filled_path(
                     glyph.path,
                     prepared_glyph.transform,
                     Fill::NonZero,
@@ -529,8 +1832,37 @@ impl GlyphRenderer for Scene {
                     self.aliasing_threshold,
                 );
             }
-            GlyphType::Bitmap(_) => {}
-            GlyphType::Colr(_) => {}
+            GlyphType::Bitmap(_) => {
+                // Bitmap glyphs would first need to be uploaded to the image atlas, which
+                // requires access to the renderer, so they are not supported yet.
+            }
+            GlyphType::Colr(glyph) => {
+                // Unlike Vello CPU, we don't render into an intermediate pixmap (which we
+                // couldn't sample from without uploading it), but paint each layer of the
+                // glyph directly into the scene. Save the state and restore it afterwards.
+                let old_transform = self.transform;
+                let old_paint_transform = self.paint_transform;
+                let old_paint = self.paint.clone();
+                let context_color = context_color(&old_paint);
+
+                let area = glyph.area;
+                self.set_transform(prepared_glyph.transform);
+                // Isolate the glyph, so that its blend layers composite against the glyph's
+                // own layers and not against the content behind it.
+                self.push_clip_layer(&area.to_path(0.1));
+                ColrPainter::new(
+                    glyph,
+                    context_color,
+                    &mut ColrGlyphPainter { scene: self, area },
+                )
+                .paint();
+                self.pop_layer();
+
+                // Restore the state.
+                self.set_paint(old_paint);
+                self.paint_transform = old_paint_transform;
+                self.transform = old_transform;
+            }
         }
     }
 
@@ -538,18 +1870,36 @@ impl GlyphRenderer for Scene {
         match prepared_glyph.glyph_type {
             GlyphType::Outline(glyph) => {
                 let paint = self.encode_current_paint();
-                self.stroke_path_with(
+                self.generate_stroked_path(
                     glyph.path,
                     prepared_glyph.transform,
                     paint,
                     self.aliasing_threshold,
                 );
             }
-            GlyphType::Bitmap(_) => {}
-            GlyphType::Colr(_) => {}
+            GlyphType::Bitmap(_) | GlyphType::Colr(_) => {
+                // The definitions of COLR and bitmap glyphs can't meaningfully support being stroked.
+                // (COLR's imaging model only has fills)
+                self.fill_glyph(prepared_glyph);
+            }
         }
     }
 
+    fn stroke_glyph_with(
+        &mut self,
+        prepared_glyph: PreparedGlyph<'_>,
+        stroke: &Stroke,
+        paint: &PaintType,
+    ) {
+        // Simulate a `save` and `restore` of the stroke and paint around the glyph.
+        let old_stroke = core::mem::replace(&mut self.stroke, stroke.clone());
+        let old_paint = self.paint.clone();
+        self.set_paint(paint.clone());
+        self.stroke_glyph(prepared_glyph);
+        self.set_paint(old_paint);
+        self.stroke = old_stroke;
+    }
+
     fn take_glyph_caches(&mut self) -> GlyphCaches {
         self.glyph_caches.take().unwrap_or_default()
     }
@@ -559,6 +1909,43 @@ impl GlyphRenderer for Scene {
     }
 }
 
+/// Paints the layers of a COLR glyph into a [`Scene`].
+///
+/// Fills cover `area`, the region of the glyph in the coordinate system of its
+/// intermediate pixmap, instead of the whole scene.
+struct ColrGlyphPainter<'a> {
+    scene: &'a mut Scene,
+    area: Rect,
+}
+
+impl ColrRenderer for ColrGlyphPainter<'_> {
+    fn push_clip_layer(&mut self, clip: &BezPath) {
+        self.scene.push_clip_layer(clip);
+    }
+
+    fn push_blend_layer(&mut self, blend_mode: BlendMode) {
+        self.scene.push_blend_layer(blend_mode);
+    }
+
+    fn fill_solid(&mut self, color: AlphaColor<Srgb>) {
+        self.scene.set_paint(color);
+        self.scene.fill_rect(&self.area);
+    }
+
+    fn fill_gradient(&mut self, gradient: vello_common::peniko::Gradient) {
+        self.scene.set_paint(gradient);
+        self.scene.fill_rect(&self.area);
+    }
+
+    fn set_paint_transform(&mut self, affine: Affine) {
+        self.scene.set_paint_transform(affine);
+    }
+
+    fn pop_layer(&mut self) {
+        self.scene.pop_layer();
+    }
+}
+
 impl Recordable for Scene {
     fn record<F>(&mut self, recording: &mut Recording, f: F)
     where
@@ -578,7 +1965,9 @@ impl Recordable for Scene {
 
     fn execute_recording(&mut self, recording: &Recording) {
         let (cached_strips, cached_alphas) = recording.get_cached_strips();
-        let adjusted_strips = self.prepare_cached_strips(cached_strips, cached_alphas);
+        let Some(adjusted_strips) = self.prepare_cached_strips(cached_strips, cached_alphas) else {
+            return;
+        };
 
         // Use pre-calculated strip start indices from when we generated the cache
         let strip_start_indices = recording.get_strip_start_indices();
@@ -600,6 +1989,18 @@ impl Recordable for Scene {
                     );
                     range_index += 1;
                 }
+                RenderCommand::StrokeOutlineGlyphWith((_, _, _, paint)) => {
+                    // Simulate a `save` and `restore` of the paint around the glyph.
+                    let old_paint = self.paint.clone();
+                    self.set_paint(paint.clone());
+                    self.process_geometry_command(
+                        strip_start_indices,
+                        range_index,
+                        &adjusted_strips,
+                    );
+                    self.set_paint(old_paint);
+                    range_index += 1;
+                }
                 RenderCommand::SetPaint(paint) => {
                     self.set_paint(paint.clone());
                 }
@@ -637,8 +2038,15 @@ impl Recordable for Scene {
                         *opacity,
                         mask.clone(),
                         filter.clone(),
+                        false,
                     );
                 }
+                RenderCommand::PushClip(clip_path) => {
+                    let range =
+                        cached_strip_range(strip_start_indices, range_index, adjusted_strips.len());
+                    self.push_cached_clip_layer(clip_path, &adjusted_strips[range]);
+                    range_index += 1;
+                }
                 RenderCommand::PopLayer => {
                     self.pop_layer();
                 }
@@ -694,9 +2102,8 @@ impl Scene {
                     strip_start_indices.push(start_index);
                 }
                 RenderCommand::FillRect(rect) => {
-                    self.strip_generator.generate_filled_path(
-                        rect.to_path(DEFAULT_TOLERANCE),
-                        self.fill_rule,
+                    self.strip_generator.generate_filled_rect(
+                        rect,
                         self.transform,
                         self.aliasing_threshold,
                         &mut strip_storage,
@@ -706,7 +2113,7 @@ impl Scene {
                 }
                 RenderCommand::StrokeRect(rect) => {
                     self.strip_generator.generate_stroked_path(
-                        rect.to_path(DEFAULT_TOLERANCE),
+                        rect.to_path(self.tolerance),
                         &self.stroke,
                         self.transform,
                         self.aliasing_threshold,
@@ -737,6 +2144,17 @@ impl Scene {
                     );
                     strip_start_indices.push(start_index);
                 }
+                RenderCommand::StrokeOutlineGlyphWith((path, glyph_transform, stroke, _)) => {
+                    self.strip_generator.generate_stroked_path(
+                        path,
+                        stroke,
+                        *glyph_transform,
+                        self.aliasing_threshold,
+                        &mut strip_storage,
+                        None,
+                    );
+                    strip_start_indices.push(start_index);
+                }
                 RenderCommand::SetTransform(transform) => {
                     self.transform = *transform;
                 }
@@ -746,6 +2164,17 @@ impl Scene {
                 RenderCommand::SetStroke(stroke) => {
                     self.stroke = stroke.clone();
                 }
+                RenderCommand::PushClip(clip_path) => {
+                    self.strip_generator.generate_filled_path(
+                        clip_path,
+                        self.fill_rule,
+                        self.transform,
+                        self.aliasing_threshold,
+                        &mut strip_storage,
+                        None,
+                    );
+                    strip_start_indices.push(start_index);
+                }
 
                 _ => {}
             }
@@ -762,61 +2191,58 @@ impl Scene {
         range_index: usize,
         adjusted_strips: &[Strip],
     ) {
-        assert!(
-            range_index < strip_start_indices.len(),
-            "Strip range index out of bounds: range_index={}, strip_start_indices.len()={}",
-            range_index,
-            strip_start_indices.len()
-        );
-        let start = strip_start_indices[range_index];
-        let end = strip_start_indices
-            .get(range_index + 1)
-            .copied()
-            .unwrap_or(adjusted_strips.len());
-        let count = end - start;
-        if count == 0 {
+        let range = cached_strip_range(strip_start_indices, range_index, adjusted_strips.len());
+        if range.is_empty() {
             // There are no strips to generate.
             return;
         }
-        assert!(
-            start < adjusted_strips.len() && count > 0,
-            "Invalid strip range: start={start}, end={end}, count={count}"
-        );
         let paint = self.encode_current_paint();
-        self.wide.generate(
-            &adjusted_strips[start..end],
+        generate_wide_cmds(
+            &mut self.wide,
+            &mut self.render_graph,
+            &adjusted_strips[range],
             paint,
             self.blend_mode,
-            0,
-            None,
             &self.encoded_paints,
         );
     }
 
     /// Prepare cached strips for rendering by adjusting alpha indices and extending alpha buffer.
-    #[expect(
-        clippy::cast_possible_truncation,
-        reason = "Alphas length conversion is safe in this case"
-    )]
+    ///
+    /// Returns `None` if the cached alphas don't fit into the alpha buffer.
     fn prepare_cached_strips(
         &mut self,
         cached_strips: &[Strip],
         cached_alphas: &[u8],
-    ) -> Vec<Strip> {
+    ) -> Option<Vec<Strip>> {
         let mut strip_storage = self.strip_storage.borrow_mut();
-        // Calculate offset for alpha indices based on current buffer size.
-        let alpha_offset = strip_storage.alphas.len() as u32;
-        // Extend current alpha buffer with cached alphas.
-        strip_storage.alphas.extend(cached_alphas);
+        // Extend current alpha buffer with cached alphas, and calculate the offset for alpha
+        // indices based on its previous size.
+        let alpha_offset = strip_storage.extend_alphas(cached_alphas)?;
         // Create adjusted strips with corrected alpha indices
-        cached_strips
-            .iter()
-            .map(move |strip| {
-                let mut adjusted_strip = *strip;
-                adjusted_strip.set_alpha_idx(adjusted_strip.alpha_idx() + alpha_offset);
-                adjusted_strip
-            })
-            .collect()
+        Some(
+            cached_strips
+                .iter()
+                .map(move |strip| {
+                    let mut adjusted_strip = *strip;
+                    adjusted_strip.set_alpha_idx(adjusted_strip.alpha_idx() + alpha_offset);
+                    adjusted_strip
+                })
+                .collect(),
+        )
+    }
+
+    /// Copy the current rendering state.
+    fn copy_current_state(&self) -> RenderState {
+        RenderState {
+            paint: self.paint.clone(),
+            paint_transform: self.paint_transform,
+            transform: self.transform,
+            fill_rule: self.fill_rule,
+            blend_mode: self.blend_mode,
+            stroke: self.stroke.clone(),
+            paint_visible: self.paint_visible,
+        }
     }
 
     /// Save current rendering state.
@@ -828,6 +2254,7 @@ impl Scene {
             fill_rule: self.fill_rule,
             blend_mode: self.blend_mode,
             stroke: core::mem::take(&mut self.stroke),
+            paint_visible: self.paint_visible,
         }
     }
 
@@ -839,5 +2266,1091 @@ impl Scene {
         self.transform = state.transform;
         self.fill_rule = state.fill_rule;
         self.blend_mode = state.blend_mode;
+        self.paint_visible = state.paint_visible;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vello_common::coarse::{
+        Cmd, CmdAlphaFill, CmdClipAlphaFill, CmdClipFill, CmdFill, LayerKind,
+    };
+    use vello_common::peniko::Extend;
+    use vello_common::peniko::color::palette::css::{BLUE, RED};
+
+    fn star(center: Point, radius: f64) -> BezPath {
+        let mut path = BezPath::new();
+        for i in 0..7 {
+            let angle = f64::from(i * 3) * core::f64::consts::TAU / 7.0;
+            let p = center + radius * Point::new(angle.cos(), angle.sin()).to_vec2();
+            if i == 0 {
+                path.move_to(p);
+            } else {
+                path.line_to(p);
+            }
+        }
+        path.close_path();
+        path
+    }
+
+    fn render_strips(level: Level) -> StripStorage {
+        let mut scene = Scene::new(200, 150);
+        scene.set_simd_level(level);
+
+        scene.set_paint(RED);
+        scene.fill_path(&star(Point::new(70.0, 70.0), 60.0));
+        scene.set_fill_rule(Fill::EvenOdd);
+        scene.set_transform(Affine::rotate(0.3).then_translate((90.0, 10.0).into()));
+        scene.fill_path(&star(Point::new(50.0, 50.0), 45.5));
+        scene.set_paint(BLUE);
+        scene.set_stroke(Stroke::new(3.5));
+        scene.stroke_path(&Circle::new((40.0, 40.0), 33.3).to_path(0.1));
+
+        scene.strip_storage.into_inner()
+    }
+
+    #[test]
+    fn simd_level_does_not_affect_strips() {
+        let scalar = render_strips(Level::fallback());
+        let detected = render_strips(Level::try_detect().unwrap_or(Level::fallback()));
+
+        assert!(!scalar.alphas.is_empty());
+        // The geometry must match exactly, while alphas may differ by one between levels.
+        assert_eq!(scalar.strips, detected.strips);
+        assert_eq!(scalar.alphas.len(), detected.alphas.len());
+        let max_diff = scalar
+            .alphas
+            .iter()
+            .zip(&detected.alphas)
+            .map(|(a, b)| a.abs_diff(*b))
+            .max()
+            .unwrap_or(0);
+        assert!(max_diff <= 1, "alphas differ by {max_diff}");
+    }
+
+    #[test]
+    fn transparent_fill_in_clip_is_drawn_as_color() {
+        let clip = Rect::new(0.0, 0.0, 100.0, 4.0).to_path(0.1);
+        let rect = Rect::new(0.0, 0.0, 256.0, 4.0);
+        let transparent = BLUE.with_alpha(0.0);
+
+        let mut scene = Scene::new(256, 4);
+        scene.push_clip_layer(&clip);
+        scene.set_paint(transparent);
+        scene.fill_rect(&rect);
+        scene.pop_layer();
+        // With source-over, a transparent paint has no effect and is culled.
+        assert!(scene.wide().attrs.fill.is_empty());
+
+        scene.reset();
+        scene.push_clip_layer(&clip);
+        scene.set_paint(transparent);
+        scene.set_blend_mode(BlendMode::new(Mix::Normal, Compose::Copy));
+        scene.fill_rect(&rect);
+        scene.pop_layer();
+
+        let wide = scene.wide();
+        assert_eq!(wide.attrs.fill.len(), 1);
+        assert!(matches!(
+            wide.attrs.fill[0].paint,
+            Paint::Solid(color) if color.as_premul_rgba8().to_u32() == 0
+        ));
+        assert!(
+            wide.get(0, 0)
+                .cmds
+                .iter()
+                .any(|cmd| matches!(cmd, Cmd::Fill(fill) if fill.attrs_idx == 0))
+        );
+    }
+
+    #[test]
+    fn transparent_blurred_rect_clears_with_destructive_compose() {
+        let rect = Rect::new(10.0, 0.0, 50.0, 4.0);
+
+        let mut scene = Scene::new(256, 4);
+        scene.set_paint(BLUE.with_alpha(0.0));
+        scene.fill_blurred_rounded_rect(&rect, 2.0, 2.0);
+        assert!(scene.wide().attrs.fill.is_empty());
+
+        scene.set_blend_mode(BlendMode::new(Mix::Normal, Compose::Copy));
+        scene.fill_blurred_rounded_rect(&rect, 2.0, 2.0);
+        assert_eq!(scene.wide().attrs.fill.len(), 1);
+    }
+
+    #[test]
+    fn clip_layer_commands() {
+        let mut scene = Scene::new(256, 4);
+        scene.push_clip_layer(&Rect::new(8.0, 0.0, 100.0, 4.0).to_path(0.1));
+        scene.fill_rect(&Rect::new(0.0, 0.0, 256.0, 4.0));
+        scene.pop_layer();
+
+        let wide = scene.wide();
+        assert_eq!((wide.width_tiles(), wide.height_tiles()), (1, 1));
+        assert_eq!(
+            wide.get(0, 0).cmds,
+            [
+                Cmd::PushBuf(LayerKind::Clip(0)),
+                Cmd::AlphaFill(CmdAlphaFill {
+                    x: 0,
+                    width: 4,
+                    alpha_offset: 0,
+                    attrs_idx: 0,
+                }),
+                Cmd::Fill(CmdFill {
+                    x: 4,
+                    width: 248,
+                    attrs_idx: 0,
+                }),
+                Cmd::AlphaFill(CmdAlphaFill {
+                    x: 252,
+                    width: 4,
+                    alpha_offset: 16,
+                    attrs_idx: 0,
+                }),
+                Cmd::ClipStrip(CmdClipAlphaFill {
+                    x: 8,
+                    width: 4,
+                    alpha_offset: 0,
+                    attrs_idx: 0,
+                }),
+                Cmd::ClipFill(CmdClipFill { x: 12, width: 88 }),
+                Cmd::ClipStrip(CmdClipAlphaFill {
+                    x: 100,
+                    width: 4,
+                    alpha_offset: 16,
+                    attrs_idx: 0,
+                }),
+                Cmd::PopBuf,
+            ]
+        );
+        assert_eq!(wide.attrs.fill.len(), 1);
+        assert_eq!(wide.attrs.clip.len(), 1);
+    }
+
+    fn wide_cmds(scene: &Scene) -> Vec<&[Cmd]> {
+        scene
+            .wide()
+            .tiles
+            .iter()
+            .map(|tile| tile.cmds.as_slice())
+            .collect()
+    }
+
+    #[test]
+    fn replay_composes_root_transform() {
+        let clip = Circle::new((40.0, 40.0), 30.0).to_path(0.1);
+        let rect = Rect::new(10.0, 10.0, 70.0, 50.0);
+
+        let mut recording = Recording::new();
+        Scene::new(1, 1).record(&mut recording, |ctx| {
+            ctx.push_clip_layer(&clip);
+            ctx.set_paint(RED);
+            ctx.set_transform(Affine::rotate(0.2));
+            ctx.fill_rect(&rect);
+            ctx.pop_layer();
+        });
+        let display_list = DisplayList::from(recording);
+
+        let mut snapshots = vec![];
+        for root_transform in [
+            Affine::IDENTITY,
+            Affine::translate((300.5, 20.0)) * Affine::scale(1.5),
+        ] {
+            let mut replayed = Scene::new(600, 200);
+            replayed.set_paint(BLUE);
+            replayed.replay(&display_list, root_transform);
+
+            let mut direct = Scene::new(600, 200);
+            direct.set_transform(root_transform);
+            direct.push_clip_layer(&clip);
+            direct.set_paint(RED);
+            direct.set_transform(root_transform * Affine::rotate(0.2));
+            direct.fill_rect(&rect);
+            direct.pop_layer();
+
+            assert_eq!(wide_cmds(&replayed), wide_cmds(&direct));
+            assert_eq!(replayed.strip_storage, direct.strip_storage);
+            assert_eq!(replayed.wide.attrs.fill, direct.wide.attrs.fill);
+            // The state of the scene is restored after replaying.
+            assert_eq!(replayed.transform, Affine::IDENTITY);
+            assert!(matches!(replayed.paint, PaintType::Solid(color) if color == BLUE));
+
+            snapshots.push(replayed.strip_storage.into_inner());
+        }
+        assert_ne!(snapshots[0], snapshots[1]);
+    }
+
+    #[test]
+    fn replay_balances_layers() {
+        let clip = Rect::new(0.0, 0.0, 50.0, 50.0).to_path(0.1);
+
+        let mut recording = Recording::new();
+        Scene::new(1, 1).record(&mut recording, |ctx| {
+            ctx.pop_layer();
+            ctx.push_clip_layer(&clip);
+            ctx.push_clip_layer(&clip);
+            ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+            ctx.pop_layer();
+        });
+        let display_list = DisplayList::from(recording);
+
+        let mut scene = Scene::new(100, 100);
+        scene.replay(&display_list, Affine::IDENTITY);
+        assert!(!scene.wide().has_layers());
+
+        // Unmatched pops must not pop layers that were pushed outside of the display list.
+        scene.push_clip_layer(&clip);
+        scene.replay(&display_list, Affine::IDENTITY);
+        assert!(scene.wide().has_layers());
+        scene.pop_layer();
+        assert!(!scene.wide().has_layers());
+    }
+
+    #[test]
+    fn cached_clip_layer_matches_direct_drawing() {
+        let clip = Circle::new((50.0, 50.0), 30.0).to_path(0.1);
+        let rect = Rect::new(0.0, 0.0, 60.0, 100.0);
+
+        let mut scene = Scene::new(100, 100);
+        let mut recording = Recording::new();
+        scene.record(&mut recording, |ctx| {
+            ctx.set_transform(Affine::translate((5.0, 0.0)));
+            ctx.push_clip_layer(&clip);
+            ctx.set_paint(RED);
+            ctx.fill_rect(&rect);
+            ctx.pop_layer();
+        });
+        assert!(
+            recording
+                .commands()
+                .iter()
+                .any(|command| matches!(command, RenderCommand::PushClip(_)))
+        );
+        scene.prepare_recording(&mut recording);
+        scene.execute_recording(&recording);
+
+        let mut expected = Scene::new(100, 100);
+        expected.set_transform(Affine::translate((5.0, 0.0)));
+        expected.push_clip_layer(&clip);
+        expected.set_paint(RED);
+        expected.fill_rect(&rect);
+        expected.pop_layer();
+
+        assert_eq!(wide_cmds(&scene), wide_cmds(&expected));
+        assert_eq!(
+            scene.strip_storage.borrow().alphas,
+            expected.strip_storage.borrow().alphas
+        );
+        assert!(!scene.wide().has_layers());
+    }
+
+    #[test]
+    fn fill_circle_matches_path() {
+        let center = Point::new(60.0, 40.0);
+        let transform = Affine::scale(2.5);
+
+        let mut scene = Scene::new(300, 200);
+        scene.set_transform(transform);
+        scene.fill_circle(center, 20.0);
+        scene.stroke_ellipse(center, Vec2::new(30.0, 10.0));
+
+        let tolerance = scene.tolerance() / 2.5;
+        let mut expected = Scene::new(300, 200);
+        expected.set_transform(transform);
+        expected.fill_path(&Circle::new(center, 20.0).to_path(tolerance));
+        expected.stroke_path(&Ellipse::new(center, (30.0, 10.0), 0.0).to_path(tolerance));
+
+        assert_eq!(wide_cmds(&scene), wide_cmds(&expected));
+        assert_eq!(scene.strip_storage, expected.strip_storage);
+    }
+
+    #[test]
+    fn non_positive_radius_is_no_op() {
+        let mut scene = Scene::new(100, 100);
+        scene.fill_circle(Point::new(50.0, 50.0), 0.0);
+        scene.stroke_circle(Point::new(50.0, 50.0), -10.0);
+        scene.fill_ellipse(Point::new(50.0, 50.0), Vec2::new(10.0, 0.0));
+        scene.stroke_ellipse(Point::new(50.0, 50.0), Vec2::new(-10.0, 10.0));
+
+        assert!(wide_cmds(&scene).iter().all(|cmds| cmds.is_empty()));
+        assert!(scene.strip_storage.borrow().strips.is_empty());
+    }
+
+    #[test]
+    fn rounded_rect_radii() {
+        let rect = Rect::new(10.0, 20.0, 90.0, 60.0);
+        let draw = |f: &dyn Fn(&mut Scene)| {
+            let mut scene = Scene::new(100, 100);
+            f(&mut scene);
+            scene
+        };
+
+        // Zero radii draw a plain rectangle.
+        let rounded = draw(&|scene| {
+            scene.fill_rounded_rect(&rect, 0.0);
+            scene.stroke_rounded_rect(&rect, 0.0);
+        });
+        let plain = draw(&|scene| {
+            scene.fill_rect(&rect);
+            scene.stroke_rect(&rect);
+        });
+        assert_eq!(wide_cmds(&rounded), wide_cmds(&plain));
+        assert_eq!(rounded.strip_storage, plain.strip_storage);
+
+        // Radii are clamped to half of the shorter side, per corner.
+        let oversized = draw(&|scene| scene.fill_rounded_rect(&rect, (100.0, 5.0, 30.0, 0.0)));
+        let clamped = draw(&|scene| scene.fill_rounded_rect(&rect, (20.0, 5.0, 20.0, 0.0)));
+        assert_eq!(wide_cmds(&oversized), wide_cmds(&clamped));
+        assert_eq!(oversized.strip_storage, clamped.strip_storage);
+        assert_ne!(oversized.strip_storage, plain.strip_storage);
+    }
+
+    /// Compute the coverage of each pixel by compositing the fill commands of all wide
+    /// tiles on top of each other, ignoring their paints.
+    fn coverage(scene: &Scene) -> Vec<f32> {
+        wide_coverage(
+            scene.wide(),
+            &scene.strip_storage.borrow().alphas,
+            scene.width,
+            scene.height,
+        )
+    }
+
+    fn wide_coverage(wide: &Wide<MODE_HYBRID>, alphas: &[u8], width: u16, height: u16) -> Vec<f32> {
+        let (width, height) = (usize::from(width), usize::from(height));
+        let mut coverage = vec![0.0_f32; width * height];
+
+        for tile in &wide.tiles {
+            for cmd in &tile.cmds {
+                let (x, cmd_width, alpha_idx) = match cmd {
+                    Cmd::Fill(fill) => (fill.x, fill.width, None),
+                    Cmd::AlphaFill(fill) => {
+                        let attrs = &wide.attrs.fill[fill.attrs_idx as usize];
+                        (fill.x, fill.width, Some(attrs.alpha_idx(fill.alpha_offset)))
+                    }
+                    _ => panic!("unexpected command {cmd:?}"),
+                };
+                for col in 0..usize::from(cmd_width) {
+                    for row in 0..usize::from(Tile::HEIGHT) {
+                        let alpha = alpha_idx.map_or(1.0, |idx| {
+                            let idx = idx as usize + col * usize::from(Tile::HEIGHT) + row;
+                            f32::from(alphas[idx]) / 255.0
+                        });
+                        let px = usize::from(tile.x + x) + col;
+                        let py = usize::from(tile.y) + row;
+                        if px < width && py < height {
+                            let cov = &mut coverage[py * width + px];
+                            *cov += alpha * (1.0 - *cov);
+                        }
+                    }
+                }
+            }
+        }
+
+        coverage
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn steady_state_frames_do_not_allocate() {
+        let draw = |scene: &mut Scene| {
+            scene.set_paint(RED);
+            scene.fill_path(&star(Point::new(70.0, 70.0), 60.0));
+            scene.push_clip_layer(&Circle::new((100.0, 60.0), 40.0).to_path(0.1));
+            scene.set_stroke(Stroke::new(3.5));
+            scene.stroke_path(&Circle::new((90.0, 70.0), 33.3).to_path(0.1));
+            scene.pop_layer();
+        };
+
+        let mut scene = Scene::new(200, 150);
+        draw(&mut scene);
+        let warmup = scene.alloc_stats();
+        assert!(warmup.line_buf > 0 && warmup.tiles > 0);
+        assert!(warmup.strip_buf > 0 && warmup.alphas > 0);
+
+        for _ in 0..3 {
+            scene.reset();
+            draw(&mut scene);
+            assert_eq!(scene.alloc_stats(), AllocStats::default());
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn reserved_capacities_avoid_allocations_in_the_first_frame() {
+        let draw = |scene: &mut Scene| {
+            scene.set_paint(RED);
+            scene.fill_path(&star(Point::new(70.0, 70.0), 60.0));
+            scene.push_clip_layer(&Circle::new((100.0, 60.0), 40.0).to_path(0.1));
+            scene.set_stroke(Stroke::new(3.5));
+            scene.stroke_path(&Circle::new((90.0, 70.0), 33.3).to_path(0.1));
+            scene.pop_layer();
+        };
+        let capacities = BufferCapacities {
+            line_buf: 1024,
+            tiles: 1024,
+            strip_buf: 1024,
+            alphas: 64 * 1024,
+        };
+
+        let mut scene = Scene::with_capacity(200, 150, capacities);
+        for _ in 0..3 {
+            draw(&mut scene);
+            assert_eq!(scene.alloc_stats(), AllocStats::default());
+            scene.reset();
+        }
+
+        // Exceeding the reserved capacities grows the buffers once.
+        let mut scene = Scene::with_capacity(200, 150, BufferCapacities::default());
+        draw(&mut scene);
+        assert_ne!(scene.alloc_stats(), AllocStats::default());
+        scene.reset();
+        draw(&mut scene);
+        assert_eq!(scene.alloc_stats(), AllocStats::default());
+    }
+
+    #[test]
+    fn animated_dash_offsets_move_dashes_continuously() {
+        let outline = Rect::new(20.5, 20.5, 180.5, 120.5).to_path(0.1);
+        let dashes_at = |dash_offset: f64| {
+            let mut scene = Scene::new(200, 150);
+            scene.set_stroke(Stroke::new(4.0).with_dashes(0.0, [6.0, 4.0]));
+            scene.set_dash_offset(dash_offset);
+            scene.stroke_path(&outline);
+            (scene.stroke.dash_offset, coverage(&scene))
+        };
+
+        // Moving the 52 dashes of the outline by a quarter pixel moves both of their ends, which
+        // changes the coverage of a quarter of the stroke width at each.
+        let max_change = 52.0 * 2.0 * 0.25 * 4.0 * 1.5;
+        let (_, first) = dashes_at(0.0);
+        let mut previous = first.clone();
+        for step in 1..=40 {
+            let (dash_offset, coverage) = dashes_at(f64::from(step) * 0.25);
+            assert!((0.0..10.0).contains(&dash_offset));
+            let change: f32 = coverage
+                .iter()
+                .zip(&previous)
+                .map(|(a, b)| (a - b).abs())
+                .sum();
+            assert!(change > 0.0 && change < max_change, "step {step}: {change}");
+            previous = coverage;
+        }
+
+        // A full period later, including when the offset has grown large, the dashes are back
+        // where they started.
+        assert_eq!(previous, first);
+        assert_eq!(dashes_at(1e6).1, first);
+    }
+
+    #[test]
+    fn draw_image_maps_source_rect_onto_destination() {
+        let image = ImageId::new(7);
+        // Samples the image at the pixel centers of the corners of `dst`.
+        let corners = |scene: &Scene, dst: Rect| {
+            let [EncodedPaint::Image(encoded)] = scene.encoded_paints() else {
+                panic!("expected a single image paint");
+            };
+            assert!(matches!(encoded.source, ImageSource::OpaqueId(id) if id == image));
+            [
+                (dst.x0, dst.y0),
+                (dst.x1 - 1.0, dst.y0),
+                (dst.x0, dst.y1 - 1.0),
+                (dst.x1 - 1.0, dst.y1 - 1.0),
+            ]
+            .map(|p| {
+                let p = encoded.transform * Point::from(p);
+                (p.x.floor(), p.y.floor())
+            })
+        };
+        let covered = |scene: &Scene| {
+            let coverage = coverage(scene);
+            let mut bounds = (usize::MAX, usize::MAX, 0, 0);
+            for (i, _) in coverage.iter().enumerate().filter(|(_, c)| **c > 0.5) {
+                let (x, y) = (i % 100, i / 100);
+                bounds = (
+                    bounds.0.min(x),
+                    bounds.1.min(y),
+                    bounds.2.max(x + 1),
+                    bounds.3.max(y + 1),
+                );
+            }
+            bounds
+        };
+
+        // A 4x2 source rectangle stretched non-uniformly onto 40x10 pixels.
+        let sampler = ImageSampler::new();
+        let mut scene = Scene::new(100, 100);
+        let dst = Rect::new(10.0, 20.0, 50.0, 30.0);
+        scene.draw_image(image, dst, Rect::new(2.0, 4.0, 6.0, 6.0), sampler);
+        assert_eq!(
+            corners(&scene, dst),
+            [(2.0, 4.0), (5.0, 4.0), (2.0, 5.0), (5.0, 5.0)]
+        );
+        assert_eq!(covered(&scene), (10, 20, 50, 30));
+
+        // Flipped rectangles are normalized.
+        let mut scene = Scene::new(100, 100);
+        scene.draw_image(
+            image,
+            Rect::new(50.0, 30.0, 10.0, 20.0),
+            Rect::new(16.0, 8.0, 0.0, 0.0),
+            sampler,
+        );
+        assert_eq!(
+            corners(&scene, dst),
+            [(0.0, 0.0), (15.0, 0.0), (0.0, 7.0), (15.0, 7.0)]
+        );
+        assert_eq!(covered(&scene), (10, 20, 50, 30));
+
+        // The sampler is passed through, and an empty rectangle draws nothing.
+        let mut scene = Scene::new(100, 100);
+        let sampler = sampler
+            .with_quality(ImageQuality::High)
+            .with_extend(Extend::Repeat);
+        scene.draw_image(image, dst, Rect::new(-16.0, 0.0, 16.0, 8.0), sampler);
+        let [EncodedPaint::Image(encoded)] = scene.encoded_paints() else {
+            panic!("expected a single image paint");
+        };
+        assert_eq!(encoded.sampler, sampler);
+        scene.draw_image(image, dst, Rect::new(4.0, 0.0, 4.0, 8.0), sampler);
+        assert_eq!(scene.encoded_paints().len(), 1);
+
+        // The current paint is left untouched.
+        let mut scene = Scene::new(100, 100);
+        scene.set_paint(RED);
+        scene.draw_image(image, dst, Rect::new(0.0, 0.0, 16.0, 8.0), sampler);
+        assert!(matches!(scene.paint, PaintType::Solid(color) if color == RED));
+        assert_eq!(scene.paint_transform, Affine::IDENTITY);
+    }
+
+    #[test]
+    fn tile_stats_reflect_path_complexity() {
+        let mut rect = Scene::new(300, 300);
+        rect.fill_path(&Rect::new(20.0, 20.0, 280.0, 280.0).to_path(0.1));
+        let rect_stats = rect.tile_stats();
+
+        // A star-shaped polygon with 1000 vertices covering roughly the same area.
+        let mut polygon = BezPath::new();
+        for i in 0..1000 {
+            let angle = f64::from(i) * core::f64::consts::TAU / 1000.0;
+            let radius = if i % 2 == 0 { 130.0 } else { 110.0 };
+            let point = (150.0 + radius * angle.cos(), 150.0 + radius * angle.sin());
+            if i == 0 {
+                polygon.move_to(point);
+            } else {
+                polygon.line_to(point);
+            }
+        }
+        polygon.close_path();
+
+        let mut star = Scene::new(300, 300);
+        star.fill_path(&polygon);
+        let star_stats = star.tile_stats();
+
+        // Each row of the rectangle has one strip per edge, covering a single tile each.
+        assert_eq!(rect_stats.tiles, 2 * 65);
+        assert_eq!(rect_stats.strips, 2 * 65 + 1);
+        assert_eq!(rect_stats.alpha_bytes, 2 * 65 * 16);
+        assert_eq!(rect_stats.wide_tiles, 2 * 65);
+
+        // The polygon touches a similar number of strips and wide tiles, but each of its edges
+        // generates its own tiles and its jagged outline covers many more alphas.
+        assert!(star_stats.tiles > 50 * rect_stats.tiles);
+        assert!(star_stats.alpha_bytes > 5 * rect_stats.alpha_bytes);
+        assert!(star_stats.strips <= rect_stats.strips);
+        assert!(star_stats.wide_tiles <= rect_stats.wide_tiles);
+
+        star.reset();
+        assert_eq!(star.tile_stats(), TileStats::default());
+    }
+
+    #[test]
+    fn fill_rects_matches_individual_fills() {
+        // Rotated rectangles with fractional edges that are close to, but don't share any
+        // pixels, so they can be batched.
+        let rects = (0..40)
+            .map(|i| {
+                let x = f64::from(i % 8) * 40.0 + 8.3;
+                let y = f64::from(i / 8) * 24.0 + 4.6;
+                Rect::new(x, y, x + 35.5, y + 17.2)
+            })
+            .collect::<Vec<_>>();
+        let transform = Affine::rotate_about(0.02, Point::new(170.0, 65.0));
+        assert!(!rects_share_pixels(&rects, transform));
+
+        let mut individual = Scene::new(340, 130);
+        individual.set_transform(transform);
+        for rect in &rects {
+            individual.fill_rect(rect);
+        }
+        let mut batched = Scene::new(340, 130);
+        batched.set_transform(transform);
+        batched.set_fill_rule(Fill::EvenOdd);
+        batched.fill_rects(&rects);
+        assert_eq!(batched.fill_rule, Fill::EvenOdd);
+
+        assert!(coverage(&individual).iter().any(|c| *c > 0.0 && *c < 1.0));
+        assert_eq!(coverage(&batched), coverage(&individual));
+    }
+
+    #[test]
+    fn fill_rects_composites_overlapping_rects_separately() {
+        let rects = [
+            Rect::new(10.5, 10.5, 60.0, 40.0),
+            Rect::new(30.0, 20.0, 90.5, 70.5),
+        ];
+        for transform in [
+            Affine::IDENTITY,
+            Affine::rotate_about(0.1, Point::new(50.0, 40.0)),
+        ] {
+            let mut individual = Scene::new(100, 80);
+            individual.set_transform(transform);
+            individual.set_paint(RED.with_alpha(0.5));
+            for rect in &rects {
+                individual.fill_rect(rect);
+            }
+            let mut batched = Scene::new(100, 80);
+            batched.set_transform(transform);
+            batched.set_paint(RED.with_alpha(0.5));
+            batched.fill_rects(&rects);
+
+            // The overlap is drawn by a command of each rectangle, so the translucent paint
+            // is composited twice there.
+            for (tile, expected) in batched.wide().tiles.iter().zip(&individual.wide().tiles) {
+                assert_eq!(tile.cmds, expected.cmds);
+            }
+            assert_eq!(
+                batched.strip_storage.borrow().alphas,
+                individual.strip_storage.borrow().alphas
+            );
+        }
+    }
+
+    #[test]
+    fn freeze_decouples_from_source() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FrozenScene>();
+
+        let mut scene = Scene::new(200, 150);
+        scene.set_paint(RED);
+        scene.fill_path(&star(Point::new(70.0, 70.0), 60.0));
+        scene.push_clip_layer(&Circle::new((100.0, 60.0), 40.0).to_path(0.1));
+        scene.fill_rect(&Rect::new(50.5, 20.5, 150.5, 100.5));
+        scene.pop_layer();
+        let expected_alphas = scene.strip_storage.borrow().alphas.clone();
+        let expected_commands = wide_cmds(&scene)
+            .iter()
+            .map(|cmds| cmds.len())
+            .collect::<Vec<_>>();
+
+        let frozen = scene.freeze();
+        assert!(wide_cmds(&scene).iter().all(|cmds| cmds.is_empty()));
+        assert!(scene.strip_storage.borrow().alphas.is_empty());
+
+        // Reusing the source scene must not affect the frozen one.
+        scene.set_paint(BLUE);
+        scene.fill_rect(&Rect::new(0.0, 0.0, 200.0, 150.0));
+        scene.reset();
+        scene.fill_path(&star(Point::new(100.0, 75.0), 70.0));
+
+        assert_eq!((frozen.width(), frozen.height()), (200, 150));
+        assert_eq!(frozen.alphas(), expected_alphas.as_slice());
+        assert_eq!(
+            frozen
+                .wide()
+                .tiles
+                .iter()
+                .map(|tile| tile.cmds.len())
+                .collect::<Vec<_>>(),
+            expected_commands
+        );
+    }
+
+    #[test]
+    fn save_restore_nests() {
+        let mut scene = Scene::new(100, 100);
+        scene.set_transform(Affine::translate((10.0, 0.0)));
+        scene.set_paint(RED);
+        scene.save();
+
+        scene.set_transform(Affine::scale(2.0));
+        scene.set_paint(BLUE.with_alpha(0.0));
+        scene.set_stroke(Stroke::new(5.0));
+        scene.set_fill_rule(Fill::EvenOdd);
+        scene.set_blend_mode(BlendMode::new(Mix::Multiply, Compose::SrcOver));
+        scene.save();
+
+        scene.set_transform(Affine::IDENTITY);
+        scene.set_paint(RED);
+        scene.restore();
+        assert_eq!(scene.transform, Affine::scale(2.0));
+        assert!(!scene.paint_visible);
+
+        scene.restore();
+        assert_eq!(scene.transform, Affine::translate((10.0, 0.0)));
+        assert!(matches!(scene.paint, PaintType::Solid(color) if color == RED));
+        assert!(scene.paint_visible);
+        assert_eq!(scene.stroke.width, 1.0);
+        assert_eq!(scene.fill_rule, Fill::NonZero);
+        assert_eq!(scene.blend_mode, BlendMode::default());
+
+        // Unbalanced restores are ignored.
+        scene.restore();
+        assert_eq!(scene.transform, Affine::translate((10.0, 0.0)));
+    }
+
+    #[test]
+    fn one_shot_paints_leave_state_untouched() {
+        let mut scene = Scene::new(100, 100);
+        scene.set_paint(RED);
+        scene.set_transform(Affine::translate((50.0, 0.0)));
+
+        let rect = Rect::new(0.0, 0.0, 40.0, 40.0).to_path(0.1);
+        scene.fill_path_with(&rect, &BLUE.into(), Fill::EvenOdd);
+        scene.stroke_path_with(&rect, &BLUE.with_alpha(0.5).into(), &Stroke::new(4.0));
+        // Outside of the viewport once the current transform is applied.
+        scene.fill_path_with(
+            &Rect::new(60.0, 0.0, 100.0, 40.0).to_path(0.1),
+            &BLUE.into(),
+            Fill::NonZero,
+        );
+        scene.fill_path(&rect);
+
+        assert!(matches!(scene.paint, PaintType::Solid(color) if color == RED));
+        assert!(scene.paint_visible);
+        assert_eq!(scene.fill_rule, Fill::NonZero);
+        assert_eq!(scene.stroke.width, 1.0);
+
+        let paints = scene
+            .wide()
+            .attrs
+            .fill
+            .iter()
+            .map(|attrs| attrs.paint.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paints,
+            [BLUE.into(), BLUE.with_alpha(0.5).into(), RED.into()]
+        );
+    }
+
+    #[test]
+    fn current_state_snapshot() {
+        let mut scene = Scene::new(100, 100);
+        let gradient = vello_common::peniko::Gradient::new_linear((0.0, 0.0), (100.0, 0.0))
+            .with_stops([RED, BLUE].as_slice());
+        scene.set_transform(Affine::translate((10.0, 0.0)));
+        scene.set_paint(gradient.clone());
+        scene.set_paint_transform(Affine::scale(2.0));
+        scene.set_stroke(Stroke::new(3.0));
+        scene.set_fill_rule(Fill::EvenOdd);
+        scene.set_blend_mode(BlendMode::new(Mix::Multiply, Compose::SrcOver));
+        scene.push_clip_path(&Rect::new(0.0, 0.0, 50.0, 50.0).to_path(0.1));
+        scene.push_clip_rect(&Rect::new(0.0, 0.0, 40.0, 40.0));
+        scene.push_opacity_layer(0.5);
+
+        let state = scene.current_state();
+        assert_eq!(state.transform, Affine::translate((10.0, 0.0)));
+        assert!(matches!(
+            state.paint,
+            PaintType::Gradient(ref g) if *g == gradient
+        ));
+        assert_eq!(state.paint_transform, Affine::scale(2.0));
+        assert_eq!(state.stroke.width, 3.0);
+        assert_eq!(state.fill_rule, Fill::EvenOdd);
+        assert_eq!(
+            state.blend_mode,
+            BlendMode::new(Mix::Multiply, Compose::SrcOver)
+        );
+        assert_eq!(state.clip_depth, 2);
+        assert_eq!(state.layer_depth, 1);
+
+        scene.pop_layer();
+        scene.pop_clip_path();
+        scene.set_paint(RED);
+        let state = scene.current_state();
+        assert!(matches!(state.paint, PaintType::Solid(color) if color == RED));
+        assert_eq!(state.clip_depth, 1);
+        assert_eq!(state.layer_depth, 0);
+    }
+
+    #[test]
+    fn clip_bounds_intersect() {
+        let mut scene = Scene::new(200, 200);
+        assert_eq!(scene.clip_bounds(), None);
+
+        scene.push_clip_layer(&Rect::new(10.0, 20.0, 110.0, 120.0).to_path(0.1));
+        scene.push_opacity_layer(0.5);
+        scene.set_transform(Affine::translate((50.0, 50.0)));
+        scene.push_clip_layer(&Rect::new(0.0, 0.0, 100.0, 100.0).to_path(0.1));
+        assert_eq!(
+            scene.clip_bounds(),
+            Some(Rect::new(50.0, 50.0, 110.0, 120.0))
+        );
+
+        // Non-rectangular clips contribute their bounding box.
+        scene.reset_transform();
+        scene.push_clip_path(&Circle::new((100.0, 100.0), 20.0).to_path(0.1));
+        let bounds = scene.clip_bounds().unwrap();
+        let expected = Rect::new(80.0, 80.0, 110.0, 120.0);
+        for (actual, wanted) in [
+            (bounds.x0, expected.x0),
+            (bounds.y0, expected.y0),
+            (bounds.x1, expected.x1),
+            (bounds.y1, expected.y1),
+        ] {
+            assert!((actual - wanted).abs() < 1e-6, "{bounds:?} != {expected:?}");
+        }
+
+        scene.pop_clip_path();
+        scene.pop_layer();
+        assert_eq!(
+            scene.clip_bounds(),
+            Some(Rect::new(10.0, 20.0, 110.0, 120.0))
+        );
+        scene.pop_layer();
+        scene.pop_layer();
+        assert_eq!(scene.clip_bounds(), None);
+    }
+
+    #[test]
+    fn off_screen_draws_are_culled() {
+        let mut scene = Scene::new(100, 100);
+        scene.set_stroke(Stroke::new(4.0));
+        for i in 0..1000 {
+            let x = 150.0 + f64::from(i);
+            scene.fill_rect(&Rect::new(x, -50.0, x + 20.0, -30.0));
+            scene.stroke_rect(&Rect::new(x, 10.0, x + 20.0, 30.0));
+        }
+        assert!(scene.strip_storage.borrow().strips.is_empty());
+        assert!(wide_cmds(&scene).iter().all(|cmds| cmds.is_empty()));
+
+        // Geometry outside of the clip bounds is culled as well.
+        scene.push_clip_layer(&Rect::new(0.0, 0.0, 50.0, 50.0).to_path(0.1));
+        let num_cmds = |scene: &Scene| {
+            wide_cmds(scene)
+                .iter()
+                .map(|cmds| cmds.len())
+                .sum::<usize>()
+        };
+        let before = num_cmds(&scene);
+        scene.fill_rect(&Rect::new(60.0, 60.0, 80.0, 80.0));
+        assert_eq!(num_cmds(&scene), before);
+        scene.pop_layer();
+
+        // The miter of a stroke can reach into the scene even if its path doesn't.
+        scene.set_stroke(
+            Stroke::new(4.0)
+                .with_join(Join::Miter)
+                .with_miter_limit(10.0),
+        );
+        let mut spike = BezPath::new();
+        spike.move_to((-20.0, 45.0));
+        spike.line_to((-3.0, 50.0));
+        spike.line_to((-20.0, 55.0));
+        scene.stroke_path(&spike);
+        assert!(!scene.strip_storage.borrow().strips.is_empty());
+    }
+
+    #[test]
+    fn opaque_tiles_become_sparse_fills() {
+        let outer = Rect::new(0.0, 0.0, 512.0, 64.0);
+        let mut reference = Scene::new(600, 100);
+        reference.fill_rect(&outer);
+
+        // The edges of the inner rectangle only cover tiles that are already fully covered
+        // by the outer one, so they shouldn't need any alphas.
+        let mut path = outer.to_path(0.1);
+        path.extend(Rect::new(100.5, 10.5, 200.5, 50.5).path_elements(0.1));
+        let mut scene = Scene::new(600, 100);
+        scene.fill_path(&path);
+
+        assert_eq!(coverage(&scene), coverage(&reference));
+        assert_eq!(
+            scene.strip_storage.borrow().alphas.len(),
+            reference.strip_storage.borrow().alphas.len()
+        );
+    }
+
+    #[test]
+    fn appended_scene_matches_direct_build() {
+        let star = star(Point::new(70.0, 50.0), 40.0);
+        let circle = Circle::new((240.0, 40.0), 30.5).to_path(0.1);
+        let offset = Affine::translate((256.0, 8.0));
+
+        let mut expected = Scene::new(600, 100);
+        expected.set_paint(RED);
+        expected.fill_path(&star);
+        expected.set_transform(offset);
+        expected.push_opacity_layer(0.5);
+        expected.set_paint(BLUE);
+        expected.fill_path(&circle);
+        expected.pop_layer();
+
+        let mut other = Scene::new(300, 100);
+        other.push_opacity_layer(0.5);
+        other.set_paint(BLUE);
+        other.fill_path(&circle);
+        other.pop_layer();
+
+        let mut scene = Scene::new(600, 100);
+        scene.set_paint(RED);
+        scene.fill_path(&star);
+        scene.append(&other, offset);
+
+        let (wide, expected_wide) = (scene.wide(), expected.wide());
+        assert_eq!(wide.attrs.fill, expected_wide.attrs.fill);
+        for (tile, expected_tile) in wide.tiles().iter().zip(expected_wide.tiles()) {
+            assert_eq!(tile.cmds, expected_tile.cmds);
+        }
+        assert_eq!(
+            scene.strip_storage.borrow().alphas,
+            expected.strip_storage.borrow().alphas
+        );
+    }
+
+    #[test]
+    fn appended_scene_keeps_backdrop_layers() {
+        let mut other = Scene::new(256, 4);
+        other.push_backdrop_layer(BLUE);
+        other.fill_rect(&Rect::new(16.0, 0.0, 48.0, 4.0));
+        other.pop_layer();
+
+        let mut scene = Scene::new(512, 4);
+        scene.push_backdrop_layer(RED);
+        scene.fill_rect(&Rect::new(16.0, 0.0, 48.0, 4.0));
+        scene.pop_layer();
+        scene.append(&other, Affine::translate((256.0, 0.0)));
+
+        // Each layer carries its own backdrop over.
+        let (red, blue) = (RED.premultiply().to_rgba8(), BLUE.premultiply().to_rgba8());
+        let wide = scene.wide();
+        assert_eq!(
+            wide.get(0, 0).cmds[0],
+            Cmd::PushBuf(LayerKind::Backdrop(0, red))
+        );
+        assert_eq!(
+            wide.get(1, 0).cmds[0],
+            Cmd::PushBuf(LayerKind::Backdrop(0, red))
+        );
+        assert!(
+            wide.get(1, 0)
+                .cmds
+                .contains(&Cmd::PushBuf(LayerKind::Backdrop(0, blue)))
+        );
+    }
+
+    #[test]
+    fn appended_scene_is_split_at_wide_tile_boundaries() {
+        let mut other = Scene::new(256, 4);
+        other.fill_rect(&Rect::new(10.5, 0.0, 250.0, 4.0));
+
+        let mut scene = Scene::new(512, 8);
+        scene.append(&other, Affine::translate((200.0, 4.0)));
+
+        let wide = scene.wide();
+        assert!(wide.get(0, 0).cmds.is_empty() && wide.get(1, 0).cmds.is_empty());
+        assert_eq!(
+            wide.get(0, 1).cmds,
+            [
+                Cmd::AlphaFill(CmdAlphaFill {
+                    x: 208,
+                    width: 4,
+                    alpha_offset: 0,
+                    attrs_idx: 0,
+                }),
+                Cmd::Fill(CmdFill {
+                    x: 212,
+                    width: 44,
+                    attrs_idx: 0,
+                }),
+            ]
+        );
+        assert_eq!(
+            wide.get(1, 1).cmds,
+            [
+                Cmd::Fill(CmdFill {
+                    x: 0,
+                    width: 192,
+                    attrs_idx: 0,
+                }),
+                Cmd::AlphaFill(CmdAlphaFill {
+                    x: 192,
+                    width: 4,
+                    alpha_offset: 16,
+                    attrs_idx: 0,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn glyph_atlas_draws_uploaded_glyphs_as_images() {
+        use vello_common::glyph::Glyph;
+        use vello_common::paint::ImageId;
+        use vello_common::peniko::{Blob, FontData};
+        use vello_common::pixmap::Pixmap;
+
+        const ROBOTO: &[u8] = include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+        let font = FontData::new(Blob::new(alloc::sync::Arc::new(ROBOTO)), 0);
+        let glyphs = (40..60).map(|id| Glyph {
+            id,
+            x: (id - 40) as f32 * 11.25,
+            y: 0.0,
+        });
+        let draw = |scene: &mut Scene, font_size: f32| {
+            scene.set_transform(Affine::translate((4.5, 30.0)));
+            scene.set_paint(BLUE);
+            scene
+                .glyph_run(&font)
+                .font_size(font_size)
+                .fill_glyphs(glyphs.clone());
+        };
+
+        let mut scene = Scene::new(256, 64);
+        scene.set_glyph_atlas(Some(GlyphAtlas::new()));
+        draw(&mut scene, 16.0);
+        // Glyphs that haven't been uploaded yet are drawn as paths.
+        let stats = scene.glyph_atlas().unwrap().stats();
+        assert_eq!((stats.uploaded, stats.pending), (0, 20));
+        assert!(scene.encoded_paints().is_empty());
+        let path_tiles = scene.tile_stats().tiles;
+
+        let mut next_id = 0;
+        let mut upload = |_: &Pixmap| {
+            next_id += 1;
+            ImageId::new(next_id)
+        };
+        scene.glyph_atlas_mut().unwrap().upload_pending(&mut upload);
+        assert_eq!(scene.glyph_atlas().unwrap().stats().uploaded, 20);
+
+        scene.reset();
+        draw(&mut scene, 16.0);
+        assert_eq!(scene.glyph_atlas().unwrap().stats().pending, 0);
+        assert!(scene.tile_stats().tiles < path_tiles / 2);
+        let images = scene
+            .encoded_paints()
+            .iter()
+            .map(|paint| match paint {
+                EncodedPaint::Image(image) => match image.source {
+                    ImageSource::OpaqueId(id) => id.as_u32(),
+                    ImageSource::Pixmap(_) => panic!("glyphs are drawn from the image atlas"),
+                },
+                _ => panic!("glyphs are drawn as images"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(images, (1..=20).collect::<Vec<_>>());
+
+        // After the text changes its size, the old glyphs are evicted eventually.
+        for _ in 0..70 {
+            scene.reset();
+            draw(&mut scene, 20.0);
+            scene.glyph_atlas_mut().unwrap().upload_pending(&mut upload);
+        }
+        let glyph_atlas = scene.glyph_atlas_mut().unwrap();
+        assert_eq!(glyph_atlas.drain_evicted().count(), 20);
+        assert_eq!(glyph_atlas.stats().uploaded, 20);
     }
 }
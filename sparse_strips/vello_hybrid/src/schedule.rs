@@ -176,7 +176,10 @@
 only break in edge cases, and some of them are also only related to conversions from f64 to f32."
 )]
 
-use crate::{GpuStrip, RenderError, Scene};
+use crate::scene::SceneView;
+use crate::{
+    ClipSlotUsage, FrozenScene, GpuSlotClear, GpuStrip, RenderError, RenderSettings, Scene,
+};
 use alloc::collections::VecDeque;
 use alloc::vec::Vec;
 use vello_common::coarse::{CommandAttrs, MODE_HYBRID};
@@ -184,7 +187,7 @@ use vello_common::peniko::{BlendMode, Compose, Mix};
 use vello_common::{
     coarse::{Cmd, LayerKind, WideTile},
     encode::EncodedPaint,
-    paint::{ImageSource, Paint},
+    paint::{ImageSource, Paint, PremulColor},
     tile::Tile,
 };
 
@@ -198,16 +201,36 @@ const PAINT_TYPE_IMAGE: u32 = 1;
 const PAINT_TYPE_LINEAR_GRADIENT: u32 = 2;
 const PAINT_TYPE_RADIAL_GRADIENT: u32 = 3;
 const PAINT_TYPE_SWEEP_GRADIENT: u32 = 4;
+const PAINT_TYPE_BLURRED_ROUNDED_RECT: u32 = 5;
+const PAINT_TYPE_SOLID_HIGH_PRECISION: u32 = 6;
+
+/// The number of alphas that the strips of a scene can address.
+///
+/// The vertex shader turns the alpha column of a strip, [`GpuStrip::col_idx`], into an `f32`
+/// texture coordinate, which can only represent whole columns exactly up to `2^24`. This also
+/// keeps the alpha texture within the minimum `max_texture_dimension_2d` of 2048 required by
+/// WebGL2 and `wgpu`.
+const MAX_ALPHAS: usize = (1 << 24) * Tile::HEIGHT as usize;
 
 // The sentinel tile index representing the surface.
 const SENTINEL_SLOT_IDX: usize = usize::MAX;
 
 /// Trait for abstracting the renderer backend from the scheduler.
-pub(crate) trait RendererBackend {
-    /// Clear specific slots in a texture.
-    fn clear_slots(&mut self, texture_index: usize, slots: &[u32]);
-
-    /// Execute a render pass for strips.
+///
+/// The scheduler emits the draw batches of a scene through this trait, in the order in which
+/// they must be executed. Targets are identified by their index: `0` and `1` are the two slot
+/// textures used for clipping and blending (see [`SlotParity`](crate::SlotParity)), and `2` is
+/// the render target. The slot textures are [`Tile::HEIGHT`] pixels high per slot and
+/// [`RenderSettings::wide_tile_width`] pixels wide.
+///
+/// Implementing this trait together with [`StripScheduler`] allows rendering scenes with
+/// graphics APIs other than `wgpu` and WebGL.
+pub trait RendererBackend {
+    /// Clear the given slots of the slot texture at `texture_index` to their colors.
+    fn clear_slots(&mut self, texture_index: usize, slots: &[GpuSlotClear]);
+
+    /// Draw `strips` into the target at `target_index`, which is first cleared if `load_op` is
+    /// [`LoadOp::Clear`].
     fn render_strips(&mut self, strips: &[GpuStrip], target_index: usize, load_op: LoadOp);
 }
 
@@ -215,18 +238,86 @@ pub(crate) trait RendererBackend {
 /// start of a render pass:
 ///  - `LoadOp::Load` is equivalent to `wgpu::LoadOp::Load`
 ///  - `LoadOp::Clear` is equivalent `wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT)`
-#[derive(Debug, PartialEq)]
-pub(crate) enum LoadOp {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadOp {
+    /// Keep the current contents of the target.
     Load,
+    /// Clear the whole target to transparent.
     Clear,
 }
 
+/// Schedules the coarse draw commands of a scene into batches of [`GpuStrip`]s.
+///
+/// This is the part of the renderers that is independent of the graphics API. The strips are
+/// drawn with the same shaders as the `wgpu` renderer uses, see `render_strips.wgsl`.
+#[derive(Debug)]
+pub struct StripScheduler {
+    scheduler: Scheduler,
+    state: SchedulerState,
+}
+
+impl StripScheduler {
+    /// Create a new scheduler for slot textures with `slot_count` slots each.
+    ///
+    /// Only [`RenderSettings::lenient_slot_allocation`],
+    /// [`RenderSettings::deterministic_slot_allocation`] and
+    /// [`RenderSettings::wide_tile_width`] are used from the `settings`.
+    pub fn new(slot_count: usize, settings: &RenderSettings) -> Self {
+        Self {
+            scheduler: Scheduler::new(slot_count, settings),
+            state: SchedulerState::default(),
+        }
+    }
+
+    /// Schedule the draw batches of `scene` into `backend`.
+    ///
+    /// Gradient, image and blurred rectangle paints are referenced by the strips through
+    /// `paint_idxs`, which must contain the index of the encoded data of each of the scene's
+    /// [encoded paints](Scene::encoded_paints) in the backend's paint texture.
+    pub fn schedule(
+        &mut self,
+        scene: &Scene,
+        paint_idxs: &[u32],
+        backend: &mut impl RendererBackend,
+    ) -> Result<(), RenderError> {
+        scene.with_view(|view| {
+            self.scheduler
+                .do_scene(&mut self.state, backend, &view, paint_idxs)
+        })
+    }
+
+    /// Schedule the draw batches of a [`FrozenScene`] into `backend`.
+    ///
+    /// See [`StripScheduler::schedule`].
+    pub fn schedule_frozen(
+        &mut self,
+        scene: &FrozenScene,
+        paint_idxs: &[u32],
+        backend: &mut impl RendererBackend,
+    ) -> Result<(), RenderError> {
+        self.scheduler
+            .do_scene(&mut self.state, backend, &scene.view(), paint_idxs)
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct Scheduler {
     /// Index of the current round
     round: usize,
     /// The total number of slots in each slot texture.
     total_slots: usize,
+    /// The width of the slot textures, which is the width of the wide tiles of the scenes
+    /// that can be rendered.
+    slot_width: u16,
+    /// Whether to flatten layers that can't get a slot instead of failing, see
+    /// [`RenderSettings::lenient_slot_allocation`](crate::RenderSettings::lenient_slot_allocation).
+    lenient_slot_allocation: bool,
+    /// Whether to claim slots starting from the lowest index in every frame, see
+    /// [`RenderSettings::deterministic_slot_allocation`].
+    deterministic_slot_allocation: bool,
+    /// Whether to record the claimed slots in `slot_usage`, see
+    /// [`RenderSettings::record_clip_slots`].
+    record_clip_slots: bool,
     /// The slots that are free to use in each slot texture.
     free: [Vec<usize>; 2],
     /// Rounds are enqueued on push clip commands and dequeued on flush.
@@ -234,6 +325,24 @@ pub(crate) struct Scheduler {
     /// A pool of `Round` objects that can be reused, so that we can reduce
     /// the number of allocations.
     round_pool: RoundPool,
+    /// The slots claimed in each slot texture during the last call to `do_scene`, if
+    /// `record_clip_slots` is set.
+    slot_usage: [Vec<ClipSlotUsage>; 2],
+    /// The last strip that solid fills at the start of the following wide tile may be merged
+    /// into. See [`Scheduler::push_surface_fill`].
+    surface_fill_run: Option<SurfaceFillRun>,
+    /// Whether solid colors are packed with 16 bits per color channel, for float render targets.
+    high_precision_colors: bool,
+}
+
+/// A solid fill ending at the right edge of its wide tile, which is extended to the right while
+/// the following wide tiles start with the same fill.
+#[derive(Debug, Clone, Copy)]
+struct SurfaceFillRun {
+    /// The round whose surface draw contains the strip.
+    round: usize,
+    /// The index of the strip within that draw.
+    strip_idx: usize,
 }
 
 #[derive(Debug, Default)]
@@ -270,7 +379,10 @@ struct Round {
     /// Slots that will be freed after drawing into the two slot textures [0, 1].
     free: [Vec<usize>; 2],
     /// Slots that will be cleared in the two slot textures (0, 1) before drawing this round.
-    clear: [Vec<u32>; 2],
+    ///
+    /// Most slots are cleared to transparent, but those of layers with a backdrop are cleared
+    /// to the color of the backdrop.
+    clear: [Vec<GpuSlotClear>; 2],
 }
 
 impl Round {
@@ -314,11 +426,20 @@ impl SchedulerState {
 #[derive(Debug, Default)]
 struct TileState {
     stack: Vec<TileEl>,
+    /// The round and the length of its surface draw when processing of the tile started.
+    surface_draw_start: (usize, usize),
+    /// Whether a fill of the tile was merged into the surface fill run of the previous tile.
+    extends_surface_fill_run: bool,
+    /// The number of currently pushed layers that are flattened into the top of `stack`, since
+    /// no slots were left for them.
+    flattened_layers: usize,
 }
 
 impl TileState {
     fn clear(&mut self) {
         self.stack.clear();
+        self.extends_surface_fill_run = false;
+        self.flattened_layers = 0;
     }
 }
 
@@ -360,14 +481,18 @@ enum AnnotatedCmd {
     PopBuf,
     /// A `Cmd::PushBuf` that will be blended into by the layer above it. Will need a
     /// `temporary_slot` created for it to enable compositing.
-    PushBufWithTemporarySlot,
+    ///
+    /// Contains the index of the wrapped command, or `None` for the extra layer that prevents
+    /// blending directly into the surface.
+    PushBufWithTemporarySlot(Option<usize>),
 }
 
 impl AnnotatedCmd {
     fn as_cmd<'a>(&self, cmds: &'a [Cmd]) -> Option<&'a Cmd> {
         match self {
             Self::Identity(idx) => Some(&cmds[*idx]),
-            Self::PushBufWithTemporarySlot => Some(&Cmd::PushBuf(LayerKind::Regular(0))),
+            Self::PushBufWithTemporarySlot(Some(idx)) => Some(&cmds[*idx]),
+            Self::PushBufWithTemporarySlot(None) => Some(&Cmd::PushBuf(LayerKind::Regular(0))),
             Self::PushBuf => Some(&Cmd::PushBuf(LayerKind::Regular(0))),
             Self::SrcOverNormalBlend => Some(&Cmd::Blend(BlendMode {
                 mix: Mix::Normal,
@@ -441,22 +566,73 @@ impl Draw {
 }
 
 impl Scheduler {
-    pub(crate) fn new(total_slots: usize) -> Self {
+    pub(crate) fn new(total_slots: usize, settings: &RenderSettings) -> Self {
         let free0: Vec<_> = (0..total_slots).collect();
         let free1 = free0.clone();
         let free: [Vec<usize>; 2] = [free0, free1];
         Self {
             round: 0,
             total_slots,
+            slot_width: settings.checked_wide_tile_width(),
+            lenient_slot_allocation: settings.lenient_slot_allocation,
+            deterministic_slot_allocation: settings.deterministic_slot_allocation,
+            record_clip_slots: settings.record_clip_slots,
             free,
             rounds_queue: VecDeque::new(),
             round_pool: RoundPool::default(),
+            slot_usage: [Vec::new(), Vec::new()],
+            surface_fill_run: None,
+            high_precision_colors: false,
         }
     }
 
+    /// Pack solid colors with 16 bits per color channel instead of 8, see
+    /// `PAINT_TYPE_SOLID_HIGH_PRECISION` in `render_strips.wgsl`.
+    #[cfg(feature = "wgpu")]
+    pub(crate) fn with_high_precision_colors(mut self, high_precision_colors: bool) -> Self {
+        self.high_precision_colors = high_precision_colors;
+        self
+    }
+
+    /// The number of slots in each of the two slot textures.
+    pub(crate) fn total_slots(&self) -> usize {
+        self.total_slots
+    }
+
+    /// The slots claimed in each slot texture (0, 1) during the last rendered scene.
+    #[cfg(feature = "wgpu")]
+    pub(crate) fn slot_usage(&self) -> &[Vec<ClipSlotUsage>; 2] {
+        &self.slot_usage
+    }
+
+    /// Flush rounds until a slot is free in `texture`, and also in the other slot texture if
+    /// `with_temporary_slot` is set.
+    ///
+    /// Returns `false` if there are not enough free slots even after flushing all rounds.
+    fn wait_for_free_slots<R: RendererBackend>(
+        &mut self,
+        texture: usize,
+        with_temporary_slot: bool,
+        renderer: &mut R,
+    ) -> bool {
+        while self.free[texture].is_empty()
+            || (with_temporary_slot && self.free[1 - texture].is_empty())
+        {
+            if self.rounds_queue.is_empty() {
+                return false;
+            }
+            self.flush(renderer);
+        }
+
+        true
+    }
+
+    /// Claim a free slot in `texture`, which is cleared to the packed premultiplied `color`
+    /// in the current round.
     fn claim_free_slot<R: RendererBackend>(
         &mut self,
         texture: usize,
+        color: u32,
         renderer: &mut R,
     ) -> Result<ClaimedSlot, RenderError> {
         while self.free[texture].is_empty() {
@@ -474,9 +650,19 @@ impl Scheduler {
             _ => panic!("invalid slot texture"),
         };
 
+        if self.record_clip_slots {
+            self.slot_usage[texture].push(ClipSlotUsage {
+                slot: slot_ix,
+                round: self.round,
+            });
+        }
+
         // Since the slot was claimed, it needs to be cleared in the given round.
         let round = self.get_round(self.round);
-        round.clear[slot.get_texture()].push(slot.get_idx() as u32);
+        round.clear[slot.get_texture()].push(GpuSlotClear {
+            slot: slot.get_idx() as u32,
+            color,
+        });
 
         Ok(slot)
     }
@@ -485,17 +671,45 @@ impl Scheduler {
         &mut self,
         state: &mut SchedulerState,
         renderer: &mut R,
-        scene: &Scene,
+        scene: &SceneView<'_>,
         paint_idxs: &[u32],
     ) -> Result<(), RenderError> {
+        if scene.layer_depth_exceeded {
+            return Err(RenderError::LayerDepthExceeded);
+        }
+        // Every alpha column referenced by a strip ends within the alphas, so this bounds the
+        // `col_idx` of all strips.
+        if scene.alphas_exhausted || scene.alphas.len() > MAX_ALPHAS {
+            return Err(RenderError::AlphasExhausted);
+        }
+        if scene.wide.tile_width() != self.slot_width {
+            return Err(RenderError::WideTileWidthMismatch {
+                scene: scene.wide.tile_width(),
+                renderer: self.slot_width,
+            });
+        }
+
         let wide_tiles_per_row = scene.wide.width_tiles();
         let wide_tiles_per_col = scene.wide.height_tiles();
 
+        for usage in &mut self.slot_usage {
+            usage.clear();
+        }
+        if self.deterministic_slot_allocation {
+            // All slots are free between frames. Since slots are claimed from the end of the
+            // free lists, order them such that the lowest index is claimed first.
+            for free in &mut self.free {
+                free.clear();
+                free.extend((0..self.total_slots).rev());
+            }
+        }
+        self.surface_fill_run = None;
+
         // Left to right, top to bottom iteration over wide tiles.
         for wide_tile_row in 0..wide_tiles_per_col {
             for wide_tile_col in 0..wide_tiles_per_row {
                 let wide_tile = scene.wide.get(wide_tile_col, wide_tile_row);
-                let wide_tile_x = wide_tile_col * WideTile::WIDTH;
+                let wide_tile_x = wide_tile_col * self.slot_width;
                 let wide_tile_y = wide_tile_row * Tile::HEIGHT;
 
                 state.clear();
@@ -552,9 +766,10 @@ impl Scheduler {
                 // duplicate slots in the clear list. Duplicates signal an inefficiency in
                 // scheduling.
                 if i != 2 {
-                    for (idx, &slot) in round.clear[i].iter().enumerate() {
+                    for (idx, clear) in round.clear[i].iter().enumerate() {
+                        let slot = clear.slot;
                         assert!(
-                            !round.clear[i][..idx].contains(&slot),
+                            !round.clear[i][..idx].iter().any(|clear| clear.slot == slot),
                             "Duplicate slot {slot} found in round.clear[{i}]",
                         );
                     }
@@ -564,12 +779,15 @@ impl Scheduler {
                 if i == 2 {
                     // We're rendering to the view, don't clear.
                     LoadOp::Load
-                } else if round.clear[i].len() + self.free[i].len() == self.total_slots {
-                    // All slots are either unoccupied or need to be cleared. Simply clear the slots
-                    // via a load operation.
+                } else if round.clear[i].len() + self.free[i].len() == self.total_slots
+                    && round.clear[i].iter().all(|clear| clear.color == 0)
+                {
+                    // All slots are either unoccupied or need to be cleared to transparent. Simply
+                    // clear the slots via a load operation.
                     LoadOp::Clear
                 } else {
-                    // Some slots need to be preserved, so only clear the dirty slots.
+                    // Some slots need to be preserved or cleared to the backdrop of their layer, so
+                    // clear each dirty slot on its own.
                     renderer.clear_slots(i, round.clear[i].as_slice());
                     LoadOp::Load
                 }
@@ -601,6 +819,66 @@ impl Scheduler {
         &mut self.get_round(el_round).draws[texture_idx]
     }
 
+    /// The number of strips in the surface draw of round `el_round`.
+    fn surface_draw_len(&self, el_round: usize) -> usize {
+        self.rounds_queue
+            .get(el_round.saturating_sub(self.round))
+            .map_or(0, |round| round.draws[2].0.len())
+    }
+
+    /// Push a fill of the wide tile at `wide_tile_x` to the surface draw of round `el_round`.
+    ///
+    /// Large solid backgrounds are split into one fill per wide tile by coarse rasterization. To
+    /// avoid drawing them with one strip per wide tile, a solid fill that ends at the right edge
+    /// of its wide tile starts a run, and the first fills of the following wide tiles are merged
+    /// into it as long as they have the same color and touch it. Moving those fills forward in
+    /// the draw is fine, since nothing drawn in between overlaps their wide tile.
+    fn push_surface_fill(
+        &mut self,
+        tile_state: &mut TileState,
+        el_round: usize,
+        strip: GpuStrip,
+        wide_tile_x: u16,
+    ) {
+        let is_solid = strip.dense_width == 0
+            && (strip.paint == (COLOR_SOURCE_PAYLOAD << 30) | (PAINT_TYPE_SOLID << 27)
+                || strip.paint >> 27
+                    == (COLOR_SOURCE_PAYLOAD << 3) | PAINT_TYPE_SOLID_HIGH_PRECISION);
+        if !is_solid {
+            self.draw_mut(el_round, 2).push(strip);
+            return;
+        }
+
+        // Whether nothing of this wide tile has been drawn to the surface in this round yet.
+        let is_leading = el_round >= self.round
+            && tile_state.surface_draw_start == (el_round, self.surface_draw_len(el_round));
+        if is_leading
+            && let Some(run) = self.surface_fill_run
+            && run.round == el_round
+        {
+            let prev = &mut self.draw_mut(el_round, 2).0[run.strip_idx];
+            if prev.y == strip.y
+                && u32::from(prev.x) + u32::from(prev.width) == u32::from(strip.x)
+                && prev.payload == strip.payload
+                && prev.paint == strip.paint
+            {
+                prev.width += strip.width;
+                tile_state.extends_surface_fill_run = true;
+                return;
+            }
+        }
+
+        let ends_at_tile_edge = u32::from(strip.x) + u32::from(strip.width)
+            == u32::from(wide_tile_x) + u32::from(self.slot_width);
+        if ends_at_tile_edge && !tile_state.extends_surface_fill_run {
+            self.surface_fill_run = Some(SurfaceFillRun {
+                round: el_round,
+                strip_idx: self.surface_draw_len(el_round),
+            });
+        }
+        self.draw_mut(el_round, 2).push(strip);
+    }
+
     // Find the appropriate round for rendering.
     #[inline(always)]
     fn get_round(&mut self, el_round: usize) -> &mut Round {
@@ -618,7 +896,7 @@ impl Scheduler {
         tile: &WideTile<MODE_HYBRID>,
         wide_tile_x: u16,
         wide_tile_y: u16,
-        scene: &Scene,
+        scene: &SceneView<'_>,
         idxs: &[u32],
     ) {
         // Sentinel `TileEl` to indicate the end of the stack where we draw all
@@ -631,21 +909,24 @@ impl Scheduler {
             round: self.round,
             opacity: 1.,
         });
+        tile_state.surface_draw_start = (self.round, self.surface_draw_len(self.round));
         {
             // If the background has a non-zero alpha then we need to render it.
             let bg = tile.bg.as_premul_rgba8().to_u32();
             if has_non_zero_alpha(bg) {
-                let (payload, paint) = Self::process_paint(
+                let (payload, paint) = self.process_paint(
                     &Paint::Solid(tile.bg),
                     scene,
                     (wide_tile_x, wide_tile_y),
                     idxs,
                 );
 
-                let draw = self.draw_mut(self.round, 2);
-                draw.push(
-                    GpuStripBuilder::at_surface(wide_tile_x, wide_tile_y, WideTile::WIDTH)
+                self.push_surface_fill(
+                    tile_state,
+                    self.round,
+                    GpuStripBuilder::at_surface(wide_tile_x, wide_tile_y, self.slot_width)
                         .paint(payload, paint),
+                    wide_tile_x,
                 );
             }
         }
@@ -659,13 +940,14 @@ impl Scheduler {
         &mut self,
         state: &mut SchedulerState,
         renderer: &mut R,
-        scene: &Scene,
+        scene: &SceneView<'_>,
         wide_tile_x: u16,
         wide_tile_y: u16,
         wide_tile_cmds: &[Cmd],
         paint_idxs: &[u32],
         attrs: &CommandAttrs,
     ) -> Result<(), RenderError> {
+        let slot_width = self.slot_width;
         for annotated_cmd in &state.annotated_commands {
             // Note: this starts at 1 (for the final target)
             let depth = state.tile_state.stack.len();
@@ -673,14 +955,31 @@ impl Scheduler {
                 continue;
             };
 
+            // The contents of flattened layers are drawn directly into the top of the stack, so
+            // only the commands that push, pop, clip or composite them are skipped.
+            if state.tile_state.flattened_layers > 0 {
+                match cmd {
+                    Cmd::PushBuf(_) => {
+                        state.tile_state.flattened_layers += 1;
+                        continue;
+                    }
+                    Cmd::PopBuf => {
+                        state.tile_state.flattened_layers -= 1;
+                        continue;
+                    }
+                    Cmd::ClipFill(_) | Cmd::ClipStrip(_) | Cmd::Opacity(_) | Cmd::Blend(_) => {
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
             match cmd {
                 Cmd::Fill(fill) => {
                     let el = state.tile_state.stack.last_mut().unwrap();
-                    let draw = self.draw_mut(el.round, el.get_draw_texture(depth));
-
                     let fill_attrs = &attrs.fill[fill.attrs_idx as usize];
                     let (scene_strip_x, scene_strip_y) = (wide_tile_x + fill.x, wide_tile_y);
-                    let (payload, paint) = Self::process_paint(
+                    let (payload, paint) = self.process_paint(
                         &fill_attrs.paint,
                         scene,
                         (scene_strip_x, scene_strip_y),
@@ -698,17 +997,22 @@ impl Scheduler {
                         GpuStripBuilder::at_slot(slot_idx, fill.x, fill.width)
                     };
 
-                    draw.push(gpu_strip_builder.paint(payload, paint));
+                    let strip = gpu_strip_builder.paint(payload, paint);
+                    if depth == 1 {
+                        let el_round = el.round;
+                        self.push_surface_fill(&mut state.tile_state, el_round, strip, wide_tile_x);
+                    } else {
+                        let draw = self.draw_mut(el.round, el.get_draw_texture(depth));
+                        draw.push(strip);
+                    }
                 }
                 Cmd::AlphaFill(alpha_fill) => {
                     let el = state.tile_state.stack.last_mut().unwrap();
-                    let draw = self.draw_mut(el.round, el.get_draw_texture(depth));
-
                     let fill_attrs = &attrs.fill[alpha_fill.attrs_idx as usize];
                     let alpha_idx = fill_attrs.alpha_idx(alpha_fill.alpha_offset);
                     let col_idx = alpha_idx / u32::from(Tile::HEIGHT);
                     let (scene_strip_x, scene_strip_y) = (wide_tile_x + alpha_fill.x, wide_tile_y);
-                    let (payload, paint) = Self::process_paint(
+                    let (payload, paint) = self.process_paint(
                         &fill_attrs.paint,
                         scene,
                         (scene_strip_x, scene_strip_y),
@@ -726,14 +1030,15 @@ impl Scheduler {
                         GpuStripBuilder::at_slot(slot_idx, alpha_fill.x, alpha_fill.width)
                     };
 
+                    let draw = self.draw_mut(el.round, el.get_draw_texture(depth));
                     draw.push(
                         gpu_strip_builder
                             .with_sparse(alpha_fill.width, col_idx)
                             .paint(payload, paint),
                     );
                 }
-                Cmd::PushBuf(_layer_id) => {
-                    // TODO: Handle layer_id for filter effects when implemented.
+                Cmd::PushBuf(layer_kind) => {
+                    // TODO: Handle filtered layers when filter effects are implemented.
                     // `wgpu` does not allow reading/writing from the same slot texture. This means
                     // that to represent the binary function `Blend(src_tile, dest_tile)` we need
                     // both slots being blended to be on the same texture. This is accomplished as
@@ -755,7 +1060,7 @@ impl Scheduler {
                             let el_round = tos.round + usize::from(next_round);
                             let draw = self.draw_mut(el_round, temp_slot.get_texture());
                             draw.push(
-                                GpuStripBuilder::at_slot(temp_slot.get_idx(), 0, WideTile::WIDTH)
+                                GpuStripBuilder::at_slot(temp_slot.get_idx(), 0, slot_width)
                                     .copy_from_slot(tos.dest_slot.get_idx(), 0xFF),
                             );
 
@@ -770,32 +1075,61 @@ impl Scheduler {
                             // results into dest slot again.
                             let dest_slot = tos.dest_slot;
                             let round = self.get_round(el_round);
-                            round.clear[temp_slot.get_texture()].push(temp_slot.get_idx() as u32);
+                            round.clear[temp_slot.get_texture()]
+                                .push(GpuSlotClear::transparent(temp_slot.get_idx() as u32));
                             debug_assert_ne!(
                                 dest_slot.get_idx(),
                                 SENTINEL_SLOT_IDX,
                                 "surface cannot be read"
                             );
                             let round1 = self.get_round(tos.round);
-                            round1.clear[dest_slot.get_texture()].push(dest_slot.get_idx() as u32);
+                            round1.clear[dest_slot.get_texture()]
+                                .push(GpuSlotClear::transparent(dest_slot.get_idx() as u32));
                         }
                     }
 
-                    // Push a new tile.
                     let ix = depth % 2;
-                    let slot = self.claim_free_slot(ix, renderer)?;
-                    let temporary_slot =
-                        if matches!(annotated_cmd, AnnotatedCmd::PushBufWithTemporarySlot) {
-                            let temp_slot = self.claim_free_slot((ix + 1) % 2, renderer)?;
-                            debug_assert_ne!(
-                                slot.get_texture(),
-                                temp_slot.get_texture(),
-                                "slot and temporary slot must be on opposite textures."
-                            );
-                            TemporarySlot::Valid(temp_slot)
-                        } else {
-                            TemporarySlot::None
-                        };
+                    let with_temporary_slot =
+                        matches!(annotated_cmd, AnnotatedCmd::PushBufWithTemporarySlot(_));
+                    if !self.wait_for_free_slots(ix, with_temporary_slot, renderer) {
+                        if !self.lenient_slot_allocation {
+                            return Err(RenderError::SlotsExhausted);
+                        }
+
+                        log::warn!(
+                            "No slots left for a layer at depth {depth} of the wide tile at \
+                            ({wide_tile_x}, {wide_tile_y}), drawing its contents without \
+                            clipping or blending it"
+                        );
+                        self.flatten_layer(&mut state.tile_state, depth);
+                        continue;
+                    }
+
+                    // The contents of the layer are drawn into the temporary slot if it has one,
+                    // so that is the slot that starts out with the backdrop.
+                    let backdrop = match layer_kind {
+                        LayerKind::Backdrop(_, backdrop) => backdrop.to_u32(),
+                        _ => 0,
+                    };
+                    let (dest_color, temp_color) = if with_temporary_slot {
+                        (0, backdrop)
+                    } else {
+                        (backdrop, 0)
+                    };
+
+                    // Push a new tile.
+                    let slot = self.claim_free_slot(ix, dest_color, renderer)?;
+                    let temporary_slot = if with_temporary_slot {
+                        let temp_slot = self.claim_free_slot((ix + 1) % 2, temp_color, renderer)?;
+                        debug_assert_ne!(
+                            slot.get_texture(),
+                            temp_slot.get_texture(),
+                            "slot and temporary slot must be on opposite textures."
+                        );
+                        TemporarySlot::Valid(temp_slot)
+                    } else {
+                        TemporarySlot::None
+                    };
                     state.tile_state.stack.push(TileEl {
                         dest_slot: slot,
                         temporary_slot,
@@ -866,7 +1200,7 @@ impl Scheduler {
                     if let TemporarySlot::Valid(temp_slot) = nos.temporary_slot {
                         let draw = self.draw_mut(round, nos.dest_slot.get_texture());
                         draw.push(
-                            GpuStripBuilder::at_slot(nos.dest_slot.get_idx(), 0, WideTile::WIDTH)
+                            GpuStripBuilder::at_slot(nos.dest_slot.get_idx(), 0, slot_width)
                                 .copy_from_slot(temp_slot.get_idx(), 0xFF),
                         );
                     }
@@ -909,7 +1243,7 @@ impl Scheduler {
                     if let TemporarySlot::Valid(temp_slot) = nos.temporary_slot {
                         let draw = self.draw_mut(round, nos.dest_slot.get_texture());
                         draw.push(
-                            GpuStripBuilder::at_slot(nos.dest_slot.get_idx(), 0, WideTile::WIDTH)
+                            GpuStripBuilder::at_slot(nos.dest_slot.get_idx(), 0, slot_width)
                                 .copy_from_slot(temp_slot.get_idx(), 0xFF),
                         );
                     }
@@ -968,9 +1302,9 @@ impl Scheduler {
                     );
 
                     let gpu_strip_builder = if depth <= 2 {
-                        GpuStripBuilder::at_surface(wide_tile_x, wide_tile_y, WideTile::WIDTH)
+                        GpuStripBuilder::at_surface(wide_tile_x, wide_tile_y, slot_width)
                     } else {
-                        GpuStripBuilder::at_slot(nos.dest_slot.get_idx(), 0, WideTile::WIDTH)
+                        GpuStripBuilder::at_slot(nos.dest_slot.get_idx(), 0, slot_width)
                     };
 
                     if let TemporarySlot::Valid(temp_slot) = nos.temporary_slot {
@@ -1016,21 +1350,54 @@ impl Scheduler {
         Ok(())
     }
 
+    /// Flatten a layer that can't get a slot into the top of the stack, so that its contents are
+    /// drawn without being clipped or composited.
+    fn flatten_layer(&mut self, tile_state: &mut TileState, depth: usize) {
+        tile_state.flattened_layers += 1;
+
+        // All rounds were flushed while looking for a free slot, so the layers on the stack
+        // are ready in the current round.
+        for el in &mut tile_state.stack {
+            el.round = el.round.max(self.round);
+        }
+        self.get_round(self.round);
+
+        // While a layer still expects to be blended into, it is drawn into its temporary slot,
+        // and only the blend writes the result to its destination slot. Since that blend is
+        // skipped now, copy what was drawn so far to the destination slot instead.
+        let slot_width = self.slot_width;
+        let tos = tile_state.stack.last_mut().unwrap();
+        if let TemporarySlot::Valid(temp_slot) = tos.temporary_slot {
+            debug_assert!(depth > 1, "the surface has no temporary slot");
+            let el_round = tos.round + 1;
+            self.draw_mut(el_round, tos.dest_slot.get_texture()).push(
+                GpuStripBuilder::at_slot(tos.dest_slot.get_idx(), 0, slot_width)
+                    .copy_from_slot(temp_slot.get_idx(), 0xFF),
+            );
+            tos.round = el_round;
+            tos.temporary_slot.invalidate();
+        }
+    }
+
     /// Process a paint and return (`payload`, `paint`)
     #[inline(always)]
     fn process_paint(
+        &self,
         paint: &Paint,
-        scene: &Scene,
+        scene: &SceneView<'_>,
         (scene_strip_x, scene_strip_y): (u16, u16),
         paint_idxs: &[u32],
     ) -> (u32, u32) {
         match paint {
+            Paint::Solid(color) if self.high_precision_colors => {
+                let (payload, blue_alpha) = pack_high_precision_color(color);
+                let paint_packed = (COLOR_SOURCE_PAYLOAD << 30)
+                    | (PAINT_TYPE_SOLID_HIGH_PRECISION << 27)
+                    | blue_alpha;
+                (payload, paint_packed)
+            }
             Paint::Solid(color) => {
                 let rgba = color.as_premul_rgba8().to_u32();
-                debug_assert!(
-                    has_non_zero_alpha(rgba),
-                    "Color fields with 0 alpha are reserved for clipping"
-                );
                 let paint_packed = (COLOR_SOURCE_PAYLOAD << 30) | (PAINT_TYPE_SOLID << 27);
                 (rgba, paint_packed)
             }
@@ -1064,6 +1431,14 @@ impl Scheduler {
                             ((scene_strip_y as u32) << 16) | (scene_strip_x as u32);
                         (scene_strip_xy, paint_packed)
                     }
+                    Some(EncodedPaint::BlurredRoundedRect(_)) => {
+                        let paint_packed = (COLOR_SOURCE_PAYLOAD << 30)
+                            | (PAINT_TYPE_BLURRED_ROUNDED_RECT << 27)
+                            | (paint_idx & 0x07FFFFFF);
+                        let scene_strip_xy =
+                            ((scene_strip_y as u32) << 16) | (scene_strip_x as u32);
+                        (scene_strip_xy, paint_packed)
+                    }
 
                     _ => unimplemented!("Unsupported paint type"),
                 }
@@ -1162,6 +1537,23 @@ impl GpuStripBuilder {
     }
 }
 
+/// Pack a premultiplied color with 16 bits per color channel and 8 bits for alpha.
+///
+/// Returns the payload holding red and green, and the paint bits holding blue and alpha, see
+/// `PAINT_TYPE_SOLID_HIGH_PRECISION` in `render_strips.wgsl`.
+fn pack_high_precision_color(color: &PremulColor) -> (u32, u32) {
+    let [r, g, b, a] = color.as_premul_f32().components;
+    let alpha = (a * 255.0 + 0.5) as u32;
+    // Premultiply with the rounded alpha, so that the color channels never exceed it.
+    let scale = if a > 0.0 {
+        alpha as f32 / 255.0 / a
+    } else {
+        0.0
+    };
+    let unorm16 = |c: f32| ((c * scale).clamp(0.0, 1.0) * 65535.0 + 0.5) as u32;
+    ((unorm16(g) << 16) | unorm16(r), (alpha << 16) | unorm16(b))
+}
+
 #[inline(always)]
 fn has_non_zero_alpha(rgba: u32) -> bool {
     rgba >= 0x1_00_00_00
@@ -1212,7 +1604,12 @@ fn prepare_cmds(cmds: &[Cmd], state: &mut SchedulerState) {
                 if needs_to_read_dest && pointer_to_push_buf_stack.len() >= 2 {
                     let push_buf_idx =
                         pointer_to_push_buf_stack[pointer_to_push_buf_stack.len() - 2];
-                    annotated_commands[push_buf_idx] = AnnotatedCmd::PushBufWithTemporarySlot;
+                    annotated_commands[push_buf_idx] = match annotated_commands[push_buf_idx] {
+                        AnnotatedCmd::Identity(idx) => {
+                            AnnotatedCmd::PushBufWithTemporarySlot(Some(idx))
+                        }
+                        _ => AnnotatedCmd::PushBufWithTemporarySlot(None),
+                    };
                 }
             }
             _ => {}
@@ -1223,7 +1620,7 @@ fn prepare_cmds(cmds: &[Cmd], state: &mut SchedulerState) {
 
     if matches!(
         annotated_commands[0],
-        AnnotatedCmd::PushBufWithTemporarySlot
+        AnnotatedCmd::PushBufWithTemporarySlot(_)
     ) {
         // We need to wrap the draw commands with an extra layer - preventing blending directly into
         // the surface.
@@ -1234,3 +1631,376 @@ fn prepare_cmds(cmds: &[Cmd], state: &mut SchedulerState) {
         annotated_commands[0] = AnnotatedCmd::Empty;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RenderSettings, Scene};
+    use alloc::vec;
+    use vello_common::kurbo::{Rect, Shape};
+    use vello_common::peniko::color::palette::css::{BLUE, GRAY, RED};
+    use vello_common::peniko::color::{AlphaColor, Srgb};
+
+    /// A backend that records the strips drawn to the surface.
+    #[derive(Default)]
+    struct RecordingBackend {
+        surface_strips: Vec<GpuStrip>,
+    }
+
+    impl RendererBackend for RecordingBackend {
+        fn clear_slots(&mut self, _texture_index: usize, _slots: &[GpuSlotClear]) {}
+
+        fn render_strips(&mut self, strips: &[GpuStrip], target_index: usize, _load_op: LoadOp) {
+            if target_index == 2 {
+                self.surface_strips.extend_from_slice(strips);
+            }
+        }
+    }
+
+    fn schedule(
+        scene: &Scene,
+        total_slots: usize,
+        lenient_slot_allocation: bool,
+    ) -> Result<Vec<GpuStrip>, RenderError> {
+        let settings = RenderSettings {
+            lenient_slot_allocation,
+            ..RenderSettings::default()
+        };
+        let mut scheduler = Scheduler::new(total_slots, &settings);
+        let mut backend = RecordingBackend::default();
+        scene.with_view(|view| {
+            let paint_idxs = vec![0; view.encoded_paints.len() + 1];
+            scheduler.do_scene(
+                &mut SchedulerState::default(),
+                &mut backend,
+                &view,
+                &paint_idxs,
+            )
+        })?;
+        Ok(backend.surface_strips)
+    }
+
+    fn surface_strips(scene: &Scene) -> Vec<GpuStrip> {
+        schedule(scene, 64, false).unwrap()
+    }
+
+    #[test]
+    fn solid_background_is_drawn_with_one_strip_per_row() {
+        // Not a multiple of the wide tile width, so the last wide tile is only partially covered.
+        let (width, height) = (2000_u16, 64_u16);
+        let mut scene = Scene::new(width, height);
+        scene.set_paint(BLUE);
+        scene.fill_rect(&Rect::new(0.0, 0.0, f64::from(width), f64::from(height)));
+        scene.set_paint(RED.with_alpha(0.5));
+        scene.fill_rect(&Rect::new(300.0, 8.0, 1200.0, 20.0));
+
+        let strips = surface_strips(&scene);
+        let is_background = |strip: &GpuStrip| {
+            strip.payload == BLUE.premultiply().to_rgba8().to_u32() && strip.dense_width == 0
+        };
+        let background = strips
+            .iter()
+            .enumerate()
+            .filter(|(_, strip)| is_background(strip))
+            .collect::<Vec<_>>();
+
+        assert_eq!(background.len(), usize::from(height / Tile::HEIGHT));
+        for (row, (idx, strip)) in background.iter().enumerate() {
+            let y = row as u16 * Tile::HEIGHT;
+            // The left and right edges of the background are drawn as alpha fills.
+            assert_eq!(
+                (strip.x, strip.y, strip.width),
+                (Tile::WIDTH, y, width - 2 * Tile::WIDTH)
+            );
+            // Everything it overlaps must still be drawn on top of the background.
+            let overlaps = |other: &GpuStrip| {
+                other.y == y && other.x < strip.x + strip.width && strip.x < other.x + other.width
+            };
+            assert!(
+                strips[..*idx]
+                    .iter()
+                    .all(|other| !overlaps(other) || is_background(other)),
+                "row {row} draws over its background"
+            );
+        }
+    }
+
+    #[test]
+    fn high_precision_solid_background_is_merged() {
+        let (width, height) = (2000_u16, 16_u16);
+        let mut scene = Scene::new(width, height);
+        // A color between two 8-bit values.
+        let color = AlphaColor::<Srgb>::new([0.3, 0.5004, 1.0, 0.6]);
+        scene.set_paint(color);
+        scene.fill_rect(&Rect::new(0.0, 0.0, f64::from(width), f64::from(height)));
+
+        let mut scheduler = Scheduler::new(64, &RenderSettings::default());
+        scheduler.high_precision_colors = true;
+        let mut backend = RecordingBackend::default();
+        scene
+            .with_view(|view| {
+                scheduler.do_scene(&mut SchedulerState::default(), &mut backend, &view, &[])
+            })
+            .unwrap();
+
+        let background = backend
+            .surface_strips
+            .iter()
+            .filter(|strip| strip.dense_width == 0)
+            .collect::<Vec<_>>();
+        assert_eq!(background.len(), usize::from(height / Tile::HEIGHT));
+        for strip in background {
+            assert_eq!(strip.paint >> 27, PAINT_TYPE_SOLID_HIGH_PRECISION);
+            let channels = [
+                strip.payload & 0xffff,
+                strip.payload >> 16,
+                strip.paint & 0xffff,
+            ];
+            for (channel, expected) in channels.into_iter().zip(color.premultiply().components) {
+                assert!((channel as f32 / 65535.0 - expected).abs() < 1e-4);
+            }
+            assert_eq!((strip.paint >> 16) & 0xff, 153);
+        }
+    }
+
+    #[test]
+    fn exceeding_max_layer_depth_is_an_error() {
+        let settings = RenderSettings {
+            max_layer_depth: Some(8),
+            ..RenderSettings::default()
+        };
+        let draw_nested = |scene: &mut Scene, depth: usize| {
+            for i in 0..depth {
+                let inset = i as f64;
+                scene.push_clip_layer(&Rect::new(inset, inset, 100.0, 40.0).to_path(0.1));
+            }
+            scene.set_paint(BLUE);
+            scene.fill_rect(&Rect::new(0.0, 0.0, 100.0, 40.0));
+            for _ in 0..depth {
+                scene.pop_layer();
+            }
+        };
+
+        let mut scene = Scene::new_with(100, 40, settings);
+        draw_nested(&mut scene, 8);
+        assert!(schedule(&scene, 64, false).is_ok());
+
+        // Layers beyond the limit are ignored, and so are their pops, so drawing can continue
+        // at the outer layers.
+        draw_nested(&mut scene, 20);
+        scene.push_clip_layer(&Rect::new(0.0, 0.0, 50.0, 40.0).to_path(0.1));
+        scene.fill_rect(&Rect::new(0.0, 0.0, 100.0, 40.0));
+        scene.pop_layer();
+        assert!(matches!(
+            schedule(&scene, 64, false),
+            Err(RenderError::LayerDepthExceeded)
+        ));
+
+        scene.reset();
+        draw_nested(&mut scene, 8);
+        assert!(schedule(&scene, 64, false).is_ok());
+    }
+
+    #[test]
+    fn exhausting_the_alphas_is_an_error() {
+        let rect = Rect::new(10.5, 10.5, 50.5, 30.5);
+        let mut scene = Scene::new(100, 40);
+        scene.fill_rect(&rect);
+        assert!(schedule(&scene, 64, false).is_ok());
+
+        // Geometry was dropped because the alpha buffer was full.
+        scene.strip_storage.borrow_mut().alphas_exhausted = true;
+        assert!(matches!(
+            schedule(&scene, 64, false),
+            Err(RenderError::AlphasExhausted)
+        ));
+
+        // All alphas can still be addressed.
+        scene.reset();
+        scene
+            .strip_storage
+            .borrow_mut()
+            .alphas
+            .resize(MAX_ALPHAS, 0);
+        assert!(schedule(&scene, 64, false).is_ok());
+
+        // The alphas of the rectangle start in a column the shader can't address exactly.
+        scene.fill_rect(&rect);
+        assert!(matches!(
+            schedule(&scene, 64, false),
+            Err(RenderError::AlphasExhausted)
+        ));
+    }
+
+    #[test]
+    fn lenient_slot_allocation_flattens_layers_without_slots() {
+        let draw_nested = |scene: &mut Scene, blend_mode: BlendMode| {
+            for i in 0..20 {
+                let inset = f64::from(i) * 3.0;
+                scene.push_layer(
+                    Some(&Rect::new(inset, 0.5, 256.0, 3.5).to_path(0.1)),
+                    Some(blend_mode),
+                    None,
+                    None,
+                    None,
+                    false,
+                );
+                scene.set_paint(BLUE);
+                scene.fill_rect(&Rect::new(0.0, 0.0, 256.0 - inset, 4.0));
+            }
+            for _ in 0..20 {
+                scene.pop_layer();
+            }
+        };
+
+        for blend_mode in [
+            BlendMode::default(),
+            BlendMode::new(Mix::Multiply, Compose::SrcOver),
+        ] {
+            let mut scene = Scene::new(256, 4);
+            draw_nested(&mut scene, blend_mode);
+
+            assert!(matches!(
+                schedule(&scene, 4, false),
+                Err(RenderError::SlotsExhausted)
+            ));
+            let strips = schedule(&scene, 4, true).unwrap();
+            assert!(!strips.is_empty(), "nothing was drawn for {blend_mode:?}");
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Batch {
+        Clear(usize, Vec<GpuSlotClear>),
+        Strips(usize, LoadOp, usize),
+    }
+
+    /// A backend that records the batches it is asked to execute.
+    #[derive(Default)]
+    struct MockBackend(Vec<Batch>);
+
+    impl RendererBackend for MockBackend {
+        fn clear_slots(&mut self, texture_index: usize, slots: &[GpuSlotClear]) {
+            self.0.push(Batch::Clear(texture_index, slots.to_vec()));
+        }
+
+        fn render_strips(&mut self, strips: &[GpuStrip], target_index: usize, load_op: LoadOp) {
+            self.0
+                .push(Batch::Strips(target_index, load_op, strips.len()));
+        }
+    }
+
+    #[test]
+    fn strip_scheduler_emits_batches_for_custom_backends() {
+        // A single wide tile with a clipped fill.
+        let mut scene = Scene::new(256, 4);
+        scene.push_clip_layer(&Rect::new(10.5, 0.5, 200.5, 3.5).to_path(0.1));
+        scene.set_paint(BLUE);
+        scene.fill_rect(&Rect::new(0.0, 0.0, 256.0, 4.0));
+        scene.pop_layer();
+
+        let mut scheduler = StripScheduler::new(8, &RenderSettings::default());
+        let mut backend = MockBackend::default();
+        scheduler.schedule(&scene, &[0], &mut backend).unwrap();
+        assert_eq!(
+            backend.0,
+            [
+                // Nothing is drawn into the even slot texture, but it is still cleared.
+                Batch::Clear(0, vec![]),
+                // The layer is drawn into a slot of the odd slot texture...
+                Batch::Strips(1, LoadOp::Clear, 3),
+                // ... and composited onto the render target through the clip, whose rows are
+                // all partially covered and thus form a single strip.
+                Batch::Strips(2, LoadOp::Load, 1),
+            ]
+        );
+
+        // The scheduler can be reused, and schedules frozen scenes the same way.
+        let batches = core::mem::take(&mut backend.0);
+        let frozen = scene.freeze();
+        scheduler
+            .schedule_frozen(&frozen, &[0], &mut backend)
+            .unwrap();
+        assert_eq!(backend.0, batches);
+    }
+
+    #[test]
+    fn backdrop_layers_clear_their_slots_to_the_backdrop() {
+        // A clip layer and a backdrop layer, whose slots are both claimed in the odd slot texture
+        // in the first round.
+        let mut scene = Scene::new(256, 4);
+        scene.push_clip_layer(&Rect::new(10.5, 0.5, 200.5, 3.5).to_path(0.1));
+        scene.set_paint(BLUE);
+        scene.fill_rect(&Rect::new(0.0, 0.0, 256.0, 4.0));
+        scene.pop_layer();
+        scene.push_backdrop_layer(GRAY);
+        scene.pop_layer();
+
+        let mut scheduler = StripScheduler::new(8, &RenderSettings::default());
+        let mut backend = MockBackend::default();
+        scheduler.schedule(&scene, &[0], &mut backend).unwrap();
+        let gray = GRAY.premultiply().to_rgba8().to_u32();
+        assert_eq!(
+            backend.0,
+            [
+                Batch::Clear(0, vec![]),
+                // Clearing the whole texture would lose the backdrop, so the slots are cleared on
+                // their own.
+                Batch::Clear(
+                    1,
+                    vec![
+                        GpuSlotClear::transparent(7),
+                        GpuSlotClear {
+                            slot: 6,
+                            color: gray
+                        }
+                    ]
+                ),
+                Batch::Strips(1, LoadOp::Load, 3),
+                // The clipped fill and the backdrop are composited onto the render target.
+                Batch::Strips(2, LoadOp::Load, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn deterministic_slot_allocation_is_independent_of_previous_frames() {
+        let nested_clips = |depth: usize| {
+            let mut scene = Scene::new(600, 12);
+            for i in 0..depth {
+                let inset = i as f64 + 0.5;
+                scene.push_clip_layer(&Rect::new(inset, inset, 600.0 - inset, 12.0).to_path(0.1));
+            }
+            scene.set_paint(BLUE);
+            scene.fill_rect(&Rect::new(0.0, 0.0, 600.0, 12.0));
+            for _ in 0..depth {
+                scene.pop_layer();
+            }
+            scene
+        };
+        let (scene, other_scene) = (nested_clips(3), nested_clips(7));
+        let slot_usage = |deterministic_slot_allocation: bool| {
+            let settings = RenderSettings {
+                deterministic_slot_allocation,
+                record_clip_slots: true,
+                ..RenderSettings::default()
+            };
+            let mut scheduler = StripScheduler::new(16, &settings);
+            let mut backend = RecordingBackend::default();
+            let mut usage = vec![];
+            for scene in [&scene, &other_scene, &scene] {
+                scheduler.schedule(scene, &[0], &mut backend).unwrap();
+                usage.push(scheduler.scheduler.slot_usage.clone());
+            }
+            usage
+        };
+
+        let usage = slot_usage(true);
+        assert_eq!(usage[0], usage[2]);
+        // The lowest slots are claimed first.
+        assert!(usage[0].iter().all(|usage| usage[0].slot == 0));
+        // Without it, the slots freed by the previous frames are reused first.
+        let usage = slot_usage(false);
+        assert_ne!(usage[0], usage[2]);
+    }
+}
@@ -25,13 +25,50 @@ impl DimensionConstraints {
         }
     }
 
+    /// Limit the maximum dimensions to `max_texture_dimension_2d`, for example the
+    /// `max_texture_dimension_2d` limit of a wgpu device or the `MAX_TEXTURE_SIZE` of a WebGL context.
+    ///
+    /// The maximum is also limited to [`u16::MAX`], the largest size supported by scenes.
+    /// Minimum dimensions larger than the new maximum are lowered to it.
+    pub fn with_max_texture_dimension(mut self, max_texture_dimension_2d: u32) -> Self {
+        let max = f64::from(max_texture_dimension_2d.min(u32::from(u16::MAX)));
+        let clamp_range = |range: &RangeInclusive<f64>| {
+            let end = range.end().min(max);
+            range.start().min(end)..=end
+        };
+        self.width_range = clamp_range(&self.width_range);
+        self.height_range = clamp_range(&self.height_range);
+        self
+    }
+
     /// Calculate dimensions while preserving aspect ratio within constraints
     ///
     /// Some viewboxes could never fit inside this constraint. For example, if the constraint for both axes
     /// is 100.0..=2000.0, if `original_width` is `2.` and `original_height` is `1000.`, there is clearly
     /// no way for that to fit within the constraints.
     /// In these cases, this method clamps to within the ranges (respecting the constraints but losing the aspect ratio).
+    ///
+    /// See [`calculate_dimensions_and_scale`](Self::calculate_dimensions_and_scale) to also get the
+    /// scale factor which was applied.
     pub fn calculate_dimensions(&self, original_width: f64, original_height: f64) -> (f64, f64) {
+        let (width, height, _) =
+            self.calculate_dimensions_and_scale(original_width, original_height);
+        (width, height)
+    }
+
+    /// Calculate dimensions like [`calculate_dimensions`](Self::calculate_dimensions), and also
+    /// return the uniform scale factor to apply to the content, as `(width, height, scale)`.
+    ///
+    /// Content of the original size scaled by `scale` always fits within the returned dimensions.
+    /// If the aspect ratio can't be preserved, one of the dimensions is larger than the scaled
+    /// content, rather than the content being cut off.
+    ///
+    /// Zero-size (or negative) inputs are treated as being one unit in size.
+    pub fn calculate_dimensions_and_scale(
+        &self,
+        original_width: f64,
+        original_height: f64,
+    ) -> (f64, f64, f64) {
         // Ensure we have non-zero input dimensions
         let original_width = original_width.max(1.0);
         let original_height = original_height.max(1.0);
@@ -41,26 +78,23 @@ impl DimensionConstraints {
         let min_height = *self.height_range.start();
         let max_height = *self.height_range.end();
 
-        let (width, height) = if original_width > max_width || original_height > max_height {
+        // The largest scale at which the content still fits within the maximum limits.
+        let max_ratio = (max_width / original_width).min(max_height / original_height);
+        let ratio = if original_width > max_width || original_height > max_height {
             // Scale down if dimensions exceed maximum limits
-            let width_ratio = max_width / original_width;
-            let height_ratio = max_height / original_height;
-            let ratio = width_ratio.min(height_ratio);
-
-            ((original_width * ratio), (original_height * ratio))
+            max_ratio
         } else if original_width < min_width || original_height < min_height {
-            // Scale up if dimensions are below minimum limits
+            // Scale up if dimensions are below minimum limits, but not beyond the maximum limits
             let width_ratio = min_width / original_width;
             let height_ratio = min_height / original_height;
-            let ratio = width_ratio.max(height_ratio);
-
-            ((original_width * ratio), (original_height * ratio))
+            width_ratio.max(height_ratio).min(max_ratio)
         } else {
-            (original_width, original_height)
+            1.0
         };
         (
-            width.clamp(min_width, max_width),
-            height.clamp(min_height, max_height),
+            (original_width * ratio).clamp(min_width, max_width),
+            (original_height * ratio).clamp(min_height, max_height),
+            ratio,
         )
     }
 
@@ -128,4 +162,46 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn oversized_dimensions_respect_max_texture_dimension() {
+        let constraints = DimensionConstraints::new(100., 100., 10_000., 10_000.)
+            .with_max_texture_dimension(2048);
+        assert_eq!(constraints.width_range, 100.0..=2048.0);
+
+        let (width, height, scale) = constraints.calculate_dimensions_and_scale(8_000., 4_000.);
+        assert_eq!((width, height), (2048., 1024.));
+        assert_eq!(scale, 0.256);
+
+        // Limits above what scenes support are capped, and the minimum follows the maximum.
+        let constraints = DimensionConstraints::new(100_000., 10., 200_000., 200_000.)
+            .with_max_texture_dimension(u32::MAX);
+        assert_eq!(constraints.width_range, 65535.0..=65535.0);
+        assert_eq!(constraints.height_range, 10.0..=65535.0);
+    }
+
+    #[test]
+    fn scaled_content_fits_within_dimensions() {
+        let constraints = DimensionConstraints::default();
+        for [test_width, test_height] in [
+            [2., 1_000.],
+            [1_000., 2.],
+            [1., 100_000.],
+            [0., 0.],
+            [3_000., 50.],
+        ] {
+            let (width, height, scale) =
+                constraints.calculate_dimensions_and_scale(test_width, test_height);
+            assert!(constraints.width_range.contains(&width));
+            assert!(constraints.height_range.contains(&height));
+            assert!(test_width.max(1.) * scale <= width);
+            assert!(test_height.max(1.) * scale <= height);
+        }
+
+        // Zero-size inputs are treated as a unit square.
+        assert_eq!(
+            constraints.calculate_dimensions_and_scale(0., 0.),
+            (100., 100., 100.)
+        );
+    }
 }
@@ -0,0 +1,188 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests that a [`Scene`] reuses its buffers across frames.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use vello_common::kurbo::{Affine, BezPath, Circle, Point, Rect, Shape, Stroke};
+use vello_common::paint::{Image, ImageId, ImageSource};
+use vello_common::peniko::color::palette::css::{BLUE, GREEN, RED, YELLOW};
+use vello_common::peniko::{Extend, Fill, Gradient, ImageQuality, ImageSampler};
+use vello_hybrid::Scene;
+
+/// An allocator that counts the allocations made by the current thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+// SAFETY: All calls are forwarded to the system allocator.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        // SAFETY: Upheld by the caller.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: Upheld by the caller.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        // SAFETY: Upheld by the caller.
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+fn draw_frame(scene: &mut Scene, paths: &[BezPath], frame: u32) {
+    scene.reset();
+    // Animate the scene, while keeping its complexity stable.
+    let offset = f64::from(frame % 8);
+    scene.set_transform(Affine::translate((offset, offset * 0.5)));
+
+    scene.set_paint(RED);
+    scene.fill_path(&paths[0]);
+    scene.push_clip_path(&paths[1]);
+    scene.set_paint(GREEN);
+    scene.set_fill_rule(Fill::EvenOdd);
+    scene.fill_rect(&Rect::new(20.0, 20.0, 180.0, 120.0));
+    scene.pop_clip_path();
+    scene.set_paint(BLUE);
+    scene.set_stroke(Stroke::new(4.0));
+    scene.stroke_path(&paths[2]);
+}
+
+#[test]
+fn steady_state_frames_do_not_allocate() {
+    let paths = [
+        Circle::new((70.0, 60.0), 50.0).to_path(0.1),
+        Circle::new((120.0, 80.0), 45.0).to_path(0.1),
+        Rect::new(10.0, 10.0, 190.0, 140.0)
+            .to_rounded_rect(12.0)
+            .to_path(0.1),
+    ];
+    let mut scene = Scene::new(200, 150);
+
+    // Let the buffers grow to their steady-state size.
+    for frame in 0..8 {
+        draw_frame(&mut scene, &paths, frame);
+    }
+
+    let before = allocations();
+    for frame in 0..8 {
+        draw_frame(&mut scene, &paths, frame);
+    }
+    assert_eq!(allocations() - before, 0);
+
+    // Shrinking releases the buffers, so they need to be allocated again.
+    scene.shrink_to_fit();
+    let before = allocations();
+    draw_frame(&mut scene, &paths, 0);
+    assert_ne!(allocations() - before, 0);
+}
+
+/// The number of frames drawn by [`warm_allocations`] after warming up.
+const WARM_FRAMES: u32 = 8;
+
+/// Draw 8 frames to let the buffers grow to their steady-state size, and return the number of
+/// allocations made while drawing [`WARM_FRAMES`] more frames.
+fn warm_allocations(mut draw_frame: impl FnMut(&mut Scene, u32)) -> usize {
+    let mut scene = Scene::new(200, 150);
+    for frame in 0..8 {
+        scene.reset();
+        draw_frame(&mut scene, frame);
+    }
+
+    let before = allocations();
+    for frame in 0..WARM_FRAMES {
+        scene.reset();
+        draw_frame(&mut scene, frame);
+    }
+    allocations() - before
+}
+
+#[test]
+fn steady_state_gradient_frames_stay_within_budget() {
+    // Encoding a gradient allocates the color ranges of its stops, which are dropped with the
+    // encoded paints when the scene is reset. Apart from that, drawing gradients shouldn't
+    // allocate.
+    const GRADIENTS_PER_FRAME: usize = 3;
+
+    let circle = Circle::new((100.0, 75.0), 60.0).to_path(0.1);
+    let allocations = warm_allocations(|scene, frame| {
+        let offset = f64::from(frame % 8) * 4.0;
+        let stops = [RED, YELLOW, GREEN, BLUE];
+        scene.set_paint(
+            Gradient::new_linear(Point::new(offset, 0.0), Point::new(200.0 - offset, 150.0))
+                .with_stops(stops),
+        );
+        scene.fill_rect(&Rect::new(0.0, 0.0, 200.0, 70.0));
+        scene.set_paint(
+            Gradient::new_radial(Point::new(100.0 - offset, 75.0), 60.0)
+                .with_stops(stops)
+                .with_extend(Extend::Reflect),
+        );
+        scene.fill_path(&circle);
+        scene.set_paint(Gradient::new_sweep(Point::new(100.0, 75.0), 0.0, 270.0).with_stops(stops));
+        scene.set_stroke(Stroke::new(6.0));
+        scene.stroke_path(&circle);
+    });
+    let budget = GRADIENTS_PER_FRAME * WARM_FRAMES as usize;
+    assert!(
+        allocations <= budget,
+        "{allocations} allocations exceed the budget of {budget}"
+    );
+}
+
+#[test]
+fn steady_state_image_frames_do_not_allocate() {
+    let circle = Circle::new((100.0, 75.0), 60.0).to_path(0.1);
+    let allocations = warm_allocations(|scene, frame| {
+        let image = |id, quality| Image {
+            image: ImageSource::OpaqueId(ImageId::new(id)),
+            sampler: ImageSampler {
+                x_extend: Extend::Repeat,
+                y_extend: Extend::Pad,
+                quality,
+                alpha: 1.0,
+            },
+        };
+        scene.set_paint_transform(Affine::rotate(f64::from(frame) * 0.1));
+        scene.set_paint(image(0, ImageQuality::Low));
+        scene.fill_rect(&Rect::new(0.0, 0.0, 200.0, 70.0));
+        scene.set_paint(image(1, ImageQuality::High));
+        scene.push_clip_path(&circle);
+        scene.fill_rect(&Rect::new(20.0, 20.0, 180.0, 140.0));
+        scene.pop_clip_path();
+    });
+    assert_eq!(allocations, 0);
+}
+
+#[test]
+fn culled_draws_do_not_allocate() {
+    let mut scene = Scene::new(200, 150);
+    scene.set_paint(RED);
+    scene.set_stroke(Stroke::new(4.0));
+    // The first stroked rectangle allocates the buffer that rectangles are written into.
+    scene.stroke_rect(&Rect::new(-30.0, -30.0, -10.0, -10.0));
+
+    let before = allocations();
+    for i in 0..1000 {
+        let x = 250.0 + f64::from(i);
+        scene.fill_rect(&Rect::new(x, 10.0, x + 20.0, 30.0));
+        scene.stroke_rect(&Rect::new(x, 40.0, x + 20.0, 60.0));
+    }
+    assert_eq!(allocations() - before, 0);
+    assert!(scene.wide().tiles.iter().all(|tile| tile.cmds.is_empty()));
+}
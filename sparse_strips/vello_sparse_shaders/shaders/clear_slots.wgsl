@@ -1,7 +1,7 @@
 // Copyright 2025 the Vello Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-// This vertex shader clears specific slots in slot textures to transparent pixels.
+// This vertex shader clears specific slots in slot textures to a color (usually transparent).
 
 // Assumes this texture consists of a single column of slots of `config.slot_height`, 
 // numbering from 0 to `texture_height / slot_height - 1` from top to bottom.
@@ -17,6 +17,19 @@ struct Config {
     _padding: u32,
 }
 
+// A slot to clear, matching `GpuSlotClear`.
+struct SlotClear {
+    // The index of the slot.
+    index: u32,
+    // The premultiplied RGBA8 color to clear the slot to, packed with red in the lowest byte.
+    color: u32,
+}
+
+struct ClearVertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) @interpolate(flat) color: u32,
+}
+
 @group(0) @binding(0)
 var<uniform> config: Config;
 
@@ -24,7 +37,8 @@ var<uniform> config: Config;
 fn vs_main(
     @builtin(vertex_index) vertex_index: u32,
     @location(0) index: u32,
-) -> @builtin(position) vec4<f32> {
+    @location(1) color: u32,
+) -> ClearVertexOutput {
     // Map vertex_index (0-3) to quad corners:
     // 0 → (0,0), 1 → (1,0), 2 → (0,1), 3 → (1,1)
     let x = f32(vertex_index & 1u);
@@ -41,7 +55,10 @@ fn vs_main(
     let ndc_x = pix_x * 2.0 / f32(config.slot_width) - 1.0;
     let ndc_y = 1.0 - pix_y * 2.0 / f32(config.texture_height);
     
-    return vec4<f32>(ndc_x, ndc_y, 0.0, 1.0);
+    var out: ClearVertexOutput;
+    out.position = vec4<f32>(ndc_x, ndc_y, 0.0, 1.0);
+    out.color = color;
+    return out;
 }
 
 // This vertex shader is used for clearing atlas regions.
@@ -65,8 +82,53 @@ fn vs_main_fullscreen(
     return vec4<f32>(x, y, 0.0, 1.0);
 }
 
+// The slots to clear, and the slot texture to clear them in. These are only used by `cs_main`,
+// which is not available on WebGL2.
+@group(0) @binding(1)
+var<storage, read> slot_clears: array<SlotClear>;
+
+@group(0) @binding(2)
+var slot_texture: texture_storage_2d<rgba8unorm, write>;
+
+// This compute shader clears the same slots as `vs_main`, without needing a render pass.
+//
+// Each workgroup clears 64 columns of one slot, so it must be dispatched with
+// `slot_width / 64` workgroups in x and one workgroup per slot in y. The workgroup height
+// matches `config.slot_height`.
+@compute @workgroup_size(64, 4)
+fn cs_main(
+    @builtin(workgroup_id) workgroup_id: vec3<u32>,
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+) {
+    let x = workgroup_id.x * 64u + local_id.x;
+    let slot_clear = slot_clears[workgroup_id.y];
+    let y = slot_clear.index * config.slot_height + local_id.y;
+    textureStore(slot_texture, vec2<u32>(x, y), unpack4x8unorm(slot_clear.color));
+}
+
 @fragment
-fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
-    // Clear with transparent pixels
+fn fs_main(in: ClearVertexOutput) -> @location(0) vec4<f32> {
+    return unpack4x8unorm(in.color);
+}
+
+// The fragment shader paired with `vs_main_fullscreen`, clearing to transparent pixels.
+@fragment
+fn fs_main_fullscreen(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
     return vec4<f32>(0.0, 0.0, 0.0, 0.0);
-} 
+}
+
+// Like `fs_main`, but for slot textures which store linear colors.
+@fragment
+fn fs_main_linear(in: ClearVertexOutput) -> @location(0) vec4<f32> {
+    let color = unpack4x8unorm(in.color);
+    if color.a == 0.0 {
+        return color;
+    }
+    return vec4<f32>(srgb_to_linear(color.rgb / color.a) * color.a, color.a);
+}
+
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+    let low = color / 12.92;
+    let high = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(high, low, color <= vec3<f32>(0.04045));
+}
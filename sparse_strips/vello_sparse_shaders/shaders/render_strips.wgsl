@@ -37,6 +37,8 @@ const PAINT_TYPE_IMAGE: u32 = 1u;
 const PAINT_TYPE_LINEAR_GRADIENT: u32 = 2u;
 const PAINT_TYPE_RADIAL_GRADIENT: u32 = 3u;
 const PAINT_TYPE_SWEEP_GRADIENT: u32 = 4u;
+const PAINT_TYPE_BLURRED_ROUNDED_RECT: u32 = 5u;
+const PAINT_TYPE_SOLID_HIGH_PRECISION: u32 = 6u;
 
 // Paint texture index mask (extracts lower 27 bits from paint field).
 const PAINT_TEXTURE_INDEX_MASK: u32 = 0x07FFFFFFu; 
@@ -59,6 +61,7 @@ const RADIAL_GRADIENT_TYPE_FOCAL: u32 = 2u;
 // Mathematical constants.
 const PI: f32 = 3.1415926535897932384626433832795028;
 const TWO_PI: f32 = 2.0 * PI;
+const FRAC_2_SQRT_PI: f32 = 1.12837916709551257389615890312154517;
 // Tolerance for nearly zero comparisons (matching vello_cpu implementation).
 // Note: This must match SCALAR_NEARLY_ZERO in vello_common/src/math.rs
 // @see {@link https://github.com/linebender/vello/blob/748ba4c7a8973f642f778591b09658d8ee6e1132/sparse_strips/vello_common/src/math.rs#L21}
@@ -117,10 +120,12 @@ struct Config {
 //   - Bits 0-29:  Usage depends on color_source:
 //
 //     When color_source = 0 (COLOR_SOURCE_PAYLOAD):
-//       - Bits 27-29: `paint_type` (0 = solid, 1 = image, 2 = linear_gradient, 3 = radial_gradient, 4 = sweep_gradient)
+//       - Bits 27-29: `paint_type` (0 = solid, 1 = image, 2 = linear_gradient, 3 = radial_gradient, 4 = sweep_gradient,
+//         5 = blurred_rounded_rect, 6 = solid_high_precision)
 //       - Bits 0-26: 
 //         - If paint_type = 0: unused
-//         - If paint_type >= 1: `paint_texture_idx`
+//         - If paint_type = 6: blue (bits 0-15) and alpha (bits 16-23) of the color
+//         - Otherwise: `paint_texture_idx`
 //
 //     When color_source = 1 (COLOR_SOURCE_SLOT):
 //       - Bits 0-7: opacity (0-255)
@@ -137,12 +142,18 @@ struct Config {
 // ├── paint_type = 0 (PAINT_TYPE_SOLID) - Solid color rendering
 // │   └── payload = [r, g, b, a] RGBA (packed as u8s)
 // │
+// ├── paint_type = 6 (PAINT_TYPE_SOLID_HIGH_PRECISION) - Solid color rendering, for float render targets
+// │   ├── payload = [r, g] (packed as u16s)
+// │   ├── bits 0-15 = b (u16)
+// │   └── bits 16-23 = a (u8)
+// │
 // ├── paint_type = 1 (PAINT_TYPE_IMAGE) - Image rendering
 // │   └── payload = packed image parameters
 // │
 // ├── paint_type = 2 (PAINT_TYPE_LINEAR_GRADIENT) - Linear gradient rendering
 // ├── paint_type = 3 (PAINT_TYPE_RADIAL_GRADIENT) - Radial gradient (with kind discriminator)
-// └── paint_type = 4 (PAINT_TYPE_SWEEP_GRADIENT) - Sweep gradient rendering
+// ├── paint_type = 4 (PAINT_TYPE_SWEEP_GRADIENT) - Sweep gradient rendering
+// └── paint_type = 5 (PAINT_TYPE_BLURRED_ROUNDED_RECT) - Blurred, rounded rectangle rendering
 //     ├── payload = [x, y] scene coordinates (packed as u16s)
 //     └── bits 0-27 = paint_texture_idx
 //
@@ -208,11 +219,39 @@ var encoded_paints_texture: texture_2d<u32>;
 @group(3) @binding(0)
 var gradient_texture: texture_2d<f32>;
 
+// The same data as `StripInstance`, as laid out in the strips storage buffer.
+struct StripData {
+    xy: u32,
+    widths: u32,
+    col_idx: u32,
+    payload: u32,
+    paint: u32,
+}
+
+// Only used by `vs_main_storage`, which is not available on WebGL2.
+@group(0) @binding(3)
+var<storage, read> strips: array<StripData>;
+
 @vertex
 fn vs_main(
     @builtin(vertex_index) in_vertex_index: u32,
     instance: StripInstance,
 ) -> VertexOutput {
+    return strip_vertex(in_vertex_index, instance);
+}
+
+// Like `vs_main`, but reads the strip from a storage buffer instead of from instance attributes.
+@vertex
+fn vs_main_storage(
+    @builtin(vertex_index) in_vertex_index: u32,
+    @builtin(instance_index) instance_index: u32,
+) -> VertexOutput {
+    let strip = strips[instance_index];
+    let instance = StripInstance(strip.xy, strip.widths, strip.col_idx, strip.payload, strip.paint);
+    return strip_vertex(in_vertex_index, instance);
+}
+
+fn strip_vertex(in_vertex_index: u32, instance: StripInstance) -> VertexOutput {
     var out: VertexOutput;
     // Map vertex_index (0-3) to quad corners:
     // 0 → (0,0), 1 → (1,0), 2 → (0,1), 3 → (1,1)
@@ -252,8 +291,8 @@ fn vs_main(
                 + encoded_image.transform.zw * f32(scene_strip_y)
                 + encoded_image.transform.xy * x * f32(width)
                 + encoded_image.transform.zw * y * f32(config.strip_height);
-        } else if paint_type == PAINT_TYPE_LINEAR_GRADIENT || paint_type == PAINT_TYPE_RADIAL_GRADIENT || paint_type == PAINT_TYPE_SWEEP_GRADIENT {
-            // Use view coordinates for gradient transform (always in global view space)
+        } else if paint_type == PAINT_TYPE_LINEAR_GRADIENT || paint_type == PAINT_TYPE_RADIAL_GRADIENT || paint_type == PAINT_TYPE_SWEEP_GRADIENT || paint_type == PAINT_TYPE_BLURRED_ROUNDED_RECT {
+            // Use view coordinates for gradient and blurred rectangle transforms (always in global view space)
             out.sample_xy = vec2<f32>(
                 f32(scene_strip_x) + x * f32(width),
                 f32(scene_strip_y) + y * f32(config.strip_height)
@@ -278,6 +317,62 @@ var clip_input_texture: texture_2d<f32>;
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return strip_fragment(in);
+}
+
+// Like `fs_main`, but for render targets storing linear colors. Colors are premultiplied and
+// sRGB-encoded, so they are converted to linear space here. sRGB render targets encode them back
+// to sRGB when they are stored, while float render targets store them as they are.
+@fragment
+fn fs_main_srgb(in: VertexOutput) -> @location(0) vec4<f32> {
+    return premul_srgb_to_linear(strip_fragment(in));
+}
+
+// Like `fs_main_srgb`, but for float render targets, whose gradient ramps have float channels and
+// are interpolated.
+@fragment
+fn fs_main_float(in: VertexOutput) -> @location(0) vec4<f32> {
+    interpolate_gradient_ramps = true;
+    return premul_srgb_to_linear(strip_fragment(in));
+}
+
+// Like `fs_main`, but for blending in linear space. Slots are sRGB textures then, so colors loaded
+// from them are linear already, and only the colors of paints are converted to linear space.
+@fragment
+fn fs_main_linear(in: VertexOutput) -> @location(0) vec4<f32> {
+    return linear_fragment(in);
+}
+
+// Like `fs_main_linear`, but for float render targets, see `fs_main_float`.
+@fragment
+fn fs_main_linear_float(in: VertexOutput) -> @location(0) vec4<f32> {
+    interpolate_gradient_ramps = true;
+    return linear_fragment(in);
+}
+
+fn linear_fragment(in: VertexOutput) -> vec4<f32> {
+    let color = strip_fragment(in);
+    let color_source = (in.paint >> 30u) & 0x3u;
+    if color_source != COLOR_SOURCE_PAYLOAD {
+        return color;
+    }
+    return premul_srgb_to_linear(color);
+}
+
+fn premul_srgb_to_linear(color: vec4<f32>) -> vec4<f32> {
+    if color.a == 0.0 {
+        return color;
+    }
+    return vec4<f32>(srgb_to_linear(color.rgb / color.a) * color.a, color.a);
+}
+
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+    let low = color / 12.92;
+    let high = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(high, low, color <= vec3<f32>(0.04045));
+}
+
+fn strip_fragment(in: VertexOutput) -> vec4<f32> {
     let x = u32(floor(in.tex_coord.x));
     var alpha = 1.0;
     // This if condition essentially checks whether the current pixel lies within a strip or a sparse
@@ -320,6 +415,11 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         // in.payload encodes a color for PAINT_TYPE_SOLID or sample_xy for PAINT_TYPE_IMAGE
         if paint_type == PAINT_TYPE_SOLID {
             final_color = alpha * unpack4x8unorm(in.payload);
+        } else if paint_type == PAINT_TYPE_SOLID_HIGH_PRECISION {
+            let rg = unpack2x16unorm(in.payload);
+            let b = f32(in.paint & 0xffffu) * (1.0 / 65535.0);
+            let a = f32((in.paint >> 16u) & 0xffu) * (1.0 / 255.0);
+            final_color = alpha * vec4<f32>(rg, b, a);
         } else if paint_type == PAINT_TYPE_IMAGE {
             let paint_tex_idx = in.paint & PAINT_TEXTURE_INDEX_MASK;
             let encoded_image = unpack_encoded_image(paint_tex_idx);
@@ -334,8 +434,49 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
                 extend_mode(local_xy.x + offset, encoded_image.extend_modes.x, image_size.x),
                 extend_mode(local_xy.y + offset, encoded_image.extend_modes.y, image_size.y)
             );
+            // How far one pixel on screen moves in the image horizontally and vertically. The
+            // level of detail is the number of times the image is halved on screen along the
+            // axis where it's squashed the most.
+            let axis_x = encoded_image.transform.xy;
+            let axis_y = encoded_image.transform.zw;
+            let major_axis = select(axis_y, axis_x, length(axis_x) >= length(axis_y));
+            let major_length = length(major_axis);
+            let minor_length = min(length(axis_x), length(axis_y));
+            let lod = log2(major_length);
             
-            if encoded_image.quality == IMAGE_QUALITY_HIGH {
+            if encoded_image.mip_levels > 0u && encoded_image.quality != IMAGE_QUALITY_LOW && lod > 0.0 {
+                // If the image is squashed much more along one axis than the other, average
+                // several samples along that axis from a sharper level instead of blurring the
+                // other axis.
+                let anisotropy = major_length / max(minor_length, 1e-6);
+                let samples = u32(ceil(min(anisotropy, f32(encoded_image.max_anisotropy))));
+                if samples > 1u {
+                    let sample_color = anisotropic_sample(
+                        atlas_texture_array,
+                        local_xy + offset,
+                        i32(encoded_image.atlas_index),
+                        image_offset,
+                        image_size,
+                        encoded_image.extend_modes,
+                        encoded_image.mip_levels,
+                        major_axis,
+                        samples,
+                    );
+                    final_color = alpha * sample_color;
+                } else {
+                    let sample_color = trilinear_sample(
+                        atlas_texture_array,
+                        extended_xy,
+                        i32(encoded_image.atlas_index),
+                        image_offset,
+                        image_size,
+                        encoded_image.extend_modes,
+                        encoded_image.mip_levels,
+                        lod,
+                    );
+                    final_color = alpha * sample_color;
+                }
+            } else if encoded_image.quality == IMAGE_QUALITY_HIGH {
                 let final_xy = image_offset + extended_xy;
                 let sample_color = bicubic_sample(
                     atlas_texture_array,
@@ -456,10 +597,26 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
                 true
             );
             final_color = alpha * gradient_color;
+        } else if paint_type == PAINT_TYPE_BLURRED_ROUNDED_RECT {
+            let paint_tex_idx = in.paint & PAINT_TEXTURE_INDEX_MASK;
+            let blurred_rect = unpack_blurred_rounded_rect(paint_tex_idx);
+
+            // Calculate fragment position in the space of the rectangle
+            let fragment_pos = in.sample_xy;
+            let rect_pos = vec2<f32>(
+                blurred_rect.transform[0] * fragment_pos.x +
+                blurred_rect.transform[2] * fragment_pos.y +
+                blurred_rect.transform[4],
+                blurred_rect.transform[1] * fragment_pos.x +
+                blurred_rect.transform[3] * fragment_pos.y +
+                blurred_rect.transform[5]
+            );
+
+            final_color = alpha * blurred_rounded_rect_alpha(rect_pos, blurred_rect) * blurred_rect.color;
         }
     } else if color_source == COLOR_SOURCE_SLOT {
-        // in.payload encodes a slot in the source clip texture
-        let clip_x = u32(in.position.x) & 0xFFu;
+        // in.payload encodes a slot in the source clip texture, which is one wide tile wide
+        let clip_x = u32(in.position.x) % textureDimensions(clip_input_texture).x;
         let clip_y = (u32(in.position.y) & 3) + in.payload * config.strip_height;
         let clip_in_color = textureLoad(clip_input_texture, vec2(clip_x, clip_y), 0);
 
@@ -475,7 +632,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         // Read source color from slot
         let src_slot = in.payload & 0xFFFFu;
         let dest_slot = (in.payload >> 16u) & 0xFFFFu;
-        let clip_x = u32(in.position.x) & 0xFFu;
+        let clip_x = u32(in.position.x) % textureDimensions(clip_input_texture).x;
         let src_y = (u32(in.position.y) & 3u) + src_slot * config.strip_height;
         let src_color = textureLoad(clip_input_texture, vec2(clip_x, src_y), 0);
         
@@ -780,6 +937,10 @@ struct EncodedImage {
     /// Translation offset for 2D affine transformation.
     /// Contains [tx, ty] representing the translation component.
     translate: vec2<f32>,
+    /// The number of mipmap levels stored next to the image in the atlas.
+    mip_levels: u32,
+    /// The maximum number of samples for anisotropic filtering, 1 if it's disabled.
+    max_anisotropy: u32,
 }
 
 // Unpack encoded image from the encoded paints texture.
@@ -801,6 +962,8 @@ fn unpack_encoded_image(paint_tex_idx: u32) -> EncodedImage {
         bitcast<f32>(texel1.y), bitcast<f32>(texel1.z)
     );
     let translate = vec2<f32>(bitcast<f32>(texel1.w), bitcast<f32>(texel2.x));
+    let mip_levels = texel2.y;
+    let max_anisotropy = texel2.z;
 
     return EncodedImage(
         quality, 
@@ -809,7 +972,9 @@ fn unpack_encoded_image(paint_tex_idx: u32) -> EncodedImage {
         image_offset,
         atlas_index,
         transform,
-        translate
+        translate,
+        mip_levels,
+        max_anisotropy
     );
 }
 
@@ -878,6 +1043,100 @@ fn bilinear_sample(
     return mix(mix(a, b, uv_frac.y), mix(c, d, uv_frac.y), uv_frac.x);
 }
 
+// Trilinear filtering
+//
+// Trilinear filtering consists of bilinearly sampling the two mipmap levels closest to the level
+// of detail and interpolating between them. `coords` are relative to the full-size image.
+fn trilinear_sample(
+    tex: texture_2d_array<f32>,
+    coords: vec2<f32>,
+    atlas_idx: i32,
+    image_offset: vec2<f32>,
+    image_size: vec2<f32>,
+    extend_modes: vec2<u32>,
+    mip_levels: u32,
+    lod: f32,
+) -> vec4<f32> {
+    let clamped_lod = clamp(lod, 0.0, f32(mip_levels));
+    let level0 = u32(floor(clamped_lod));
+    let level1 = min(level0 + 1u, mip_levels);
+    let a = mip_level_sample(tex, coords, atlas_idx, image_offset, image_size, extend_modes, level0);
+    let b = mip_level_sample(tex, coords, atlas_idx, image_offset, image_size, extend_modes, level1);
+    return mix(a, b, fract(clamped_lod));
+}
+
+// Anisotropic filtering
+//
+// Anisotropic filtering consists of taking several trilinear samples spread along the longer
+// axis of the footprint of a pixel in the image and averaging them. Each sample only covers
+// its part of the footprint, so it's taken from a sharper level than a single trilinear sample
+// would be. `coords` are relative to the full-size image, before applying the extend modes.
+fn anisotropic_sample(
+    tex: texture_2d_array<f32>,
+    coords: vec2<f32>,
+    atlas_idx: i32,
+    image_offset: vec2<f32>,
+    image_size: vec2<f32>,
+    extend_modes: vec2<u32>,
+    mip_levels: u32,
+    major_axis: vec2<f32>,
+    samples: u32,
+) -> vec4<f32> {
+    let lod = log2(length(major_axis) / f32(samples));
+    var sum = vec4(0.0);
+    for (var i = 0u; i < samples; i += 1u) {
+        let sample_xy = coords + major_axis * ((f32(i) + 0.5) / f32(samples) - 0.5);
+        let extended_xy = vec2<f32>(
+            extend_mode(sample_xy.x, extend_modes.x, image_size.x),
+            extend_mode(sample_xy.y, extend_modes.y, image_size.y)
+        );
+        sum += trilinear_sample(
+            tex,
+            extended_xy,
+            atlas_idx,
+            image_offset,
+            image_size,
+            extend_modes,
+            mip_levels,
+            lod,
+        );
+    }
+    return sum / f32(samples);
+}
+
+// Bilinearly sample a single mipmap level. Level 0 is the image itself, and the levels below it
+// are stacked on top of each other to the right of the image in the atlas, each half as large as
+// the previous one, rounded up.
+fn mip_level_sample(
+    tex: texture_2d_array<f32>,
+    coords: vec2<f32>,
+    atlas_idx: i32,
+    image_offset: vec2<f32>,
+    image_size: vec2<f32>,
+    extend_modes: vec2<u32>,
+    level: u32,
+) -> vec4<f32> {
+    var level_offset = image_offset;
+    var level_size = image_size;
+    if level > 0u {
+        level_offset.x += image_size.x;
+        level_size = ceil(level_size * 0.5);
+        for (var i = 1u; i < level; i += 1u) {
+            level_offset.y += level_size.y;
+            level_size = ceil(level_size * 0.5);
+        }
+    }
+    let level_xy = coords * level_size / image_size;
+    return bilinear_sample(
+        tex,
+        level_offset + level_xy - vec2(0.5),
+        atlas_idx,
+        level_offset,
+        level_size,
+        extend_modes,
+    );
+}
+
 // Bicubic filtering using Mitchell filter with B=1/3, C=1/3
 //
 // Cubic resampling consists of sampling the 16 surrounding pixels of the target point and
@@ -1006,21 +1265,33 @@ fn xy_to_unit_angle(x: f32, y: f32) -> f32 {
     return phi;
 }
 
+// Whether `sample_gradient_lut` interpolates between neighboring entries of the gradient ramps
+// rather than picking the nearest one below. Only set by the entry points for float render targets,
+// so that gradients drawn into them don't band.
+var<private> interpolate_gradient_ramps: bool = false;
+
 // Sample from the gradient texture at calculated position.
 fn sample_gradient_lut(t_value: f32, extend_mode: u32, gradient_start: u32, texture_width: u32, is_valid: bool) -> vec4<f32> {
     // Apply extend mode to t_value
     let clamped_t = extend_mode_normalized(t_value, extend_mode);
     // Convert t_value to texture coordinate
-    let t_offset = select(texture_width, u32(clamped_t * f32(texture_width - 1u)), is_valid);
-    // Calculate absolute position in flat gradient texture
-    let flat_coord = gradient_start + t_offset;
+    let position = clamped_t * f32(texture_width - 1u);
+    let t_offset = select(texture_width, u32(position), is_valid);
+    let gradient_color = load_gradient_lut(gradient_start + t_offset);
+    if !interpolate_gradient_ramps || !is_valid {
+        return gradient_color;
+    }
+    let next_color = load_gradient_lut(gradient_start + min(t_offset + 1u, texture_width - 1u));
+    return mix(gradient_color, next_color, fract(position));
+}
+
+// Load the entry at `flat_coord` in the flat gradient texture.
+fn load_gradient_lut(flat_coord: u32) -> vec4<f32> {
     // Convert flat coordinate to 2D texture coordinate
     let gradient_tex_width = textureDimensions(gradient_texture).x;
     let tex_x = flat_coord % gradient_tex_width;
     let tex_y = flat_coord / gradient_tex_width;
-    // Sample from the gradient texture at calculated position
-    let gradient_color = textureLoad(gradient_texture, vec2<u32>(tex_x, tex_y), 0);
-    return gradient_color;
+    return textureLoad(gradient_texture, vec2<u32>(tex_x, tex_y), 0);
 }
 
 struct LinearGradient {
@@ -1250,6 +1521,100 @@ fn unpack_sweep_gradient(paint_tex_idx: u32) -> SweepGradient {
     );
 }
 
+struct BlurredRoundedRect {
+    /// Transform from view space to the space of the rectangle.
+    transform: array<f32, 6>,
+    /// Exponent of the superellipse approximating the blurred corners.
+    exponent: f32,
+    /// Reciprocal of `exponent`.
+    recip_exponent: f32,
+    /// Scale factor applied to the resulting alpha.
+    scale: f32,
+    /// Reciprocal of the standard deviation.
+    std_dev_inv: f32,
+    /// The smaller of the rectangle's width and height.
+    min_edge: f32,
+    /// Adjusted width of the rectangle.
+    w: f32,
+    /// Adjusted height of the rectangle.
+    h: f32,
+    /// Width of the rectangle.
+    width: f32,
+    /// Height of the rectangle.
+    height: f32,
+    /// Corner radius adjusted for the blur.
+    r1: f32,
+    /// Premultiplied color.
+    color: vec4<f32>,
+}
+
+// Unpack a blurred, rounded rectangle from the encoded paints texture.
+fn unpack_blurred_rounded_rect(paint_tex_idx: u32) -> BlurredRoundedRect {
+    let texel0 = textureLoad(encoded_paints_texture, vec2<u32>(paint_tex_idx, 0), 0);
+    let texel1 = textureLoad(encoded_paints_texture, vec2<u32>(paint_tex_idx + 1u, 0), 0);
+    let texel2 = textureLoad(encoded_paints_texture, vec2<u32>(paint_tex_idx + 2u, 0), 0);
+    let texel3 = textureLoad(encoded_paints_texture, vec2<u32>(paint_tex_idx + 3u, 0), 0);
+    let texel4 = textureLoad(encoded_paints_texture, vec2<u32>(paint_tex_idx + 4u, 0), 0);
+
+    let transform = array<f32, 6>(
+        bitcast<f32>(texel0.x), bitcast<f32>(texel0.y), bitcast<f32>(texel0.z),
+        bitcast<f32>(texel0.w), bitcast<f32>(texel1.x), bitcast<f32>(texel1.y)
+    );
+
+    return BlurredRoundedRect(
+        transform,
+        bitcast<f32>(texel1.z),
+        bitcast<f32>(texel1.w),
+        bitcast<f32>(texel2.x),
+        bitcast<f32>(texel2.y),
+        bitcast<f32>(texel2.z),
+        bitcast<f32>(texel2.w),
+        bitcast<f32>(texel3.x),
+        bitcast<f32>(texel3.y),
+        bitcast<f32>(texel3.z),
+        bitcast<f32>(texel3.w),
+        bitcast<vec4<f32>>(texel4),
+    );
+}
+
+// Compute the coverage of a blurred, rounded rectangle at the given position.
+//
+// This matches the implementation in `vello_cpu`, which is adapted from
+// <https://git.sr.ht/~raph/blurrr/tree/master/src/distfield.rs>.
+fn blurred_rounded_rect_alpha(pos: vec2<f32>, r: BlurredRoundedRect) -> f32 {
+    let y = pos.y + 0.5 - 0.5 * r.height;
+    let y0 = abs(y) - (0.5 * r.h - r.r1);
+    let y1 = max(y0, 0.0);
+
+    let x = pos.x + 0.5 - 0.5 * r.width;
+    let x0 = abs(x) - (0.5 * r.w - r.r1);
+    let x1 = max(x0, 0.0);
+
+    let d_pos = pow_non_negative(
+        pow_non_negative(x1, r.exponent) + pow_non_negative(y1, r.exponent),
+        r.recip_exponent
+    );
+    let d_neg = min(max(x0, y0), 0.0);
+    let d = d_pos + d_neg - r.r1;
+
+    return r.scale * (erf7(r.std_dev_inv * (r.min_edge + d)) - erf7(r.std_dev_inv * d));
+}
+
+// `pow` is undefined for a base of zero on some platforms, so handle it explicitly.
+fn pow_non_negative(x: f32, y: f32) -> f32 {
+    return select(pow(x, y), 0.0, x <= 0.0);
+}
+
+// Approximate the error function.
+// See <https://raphlinus.github.io/audio/2018/09/05/sigmoid.html>.
+fn erf7(x_in: f32) -> f32 {
+    // Clamp `x`, because for large `x` the terms here become `inf`.
+    let x = clamp(x_in, -10.0, 10.0) * FRAC_2_SQRT_PI;
+    let xx = x * x;
+    let p = x + ((0.0104 * xx + 0.03395) * xx + 0.24295) * (x * xx);
+    return p / sqrt(1.0 + p * p);
+}
+
 // Unpack texture_width and extend_mode from packed field.
 // Returns (texture_width, extend_mode).
 fn unpack_texture_width_and_extend_mode(packed: u32) -> vec2<u32> {
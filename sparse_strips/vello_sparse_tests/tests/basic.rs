@@ -3,14 +3,22 @@
 
 //! Tests for basic functionality.
 
+use crate::render_all_backends;
 use crate::renderer::Renderer;
-use crate::util::{circular_star, crossed_line_star, layout_glyphs_roboto, miter_stroke_2};
+use crate::util::{
+    assert_renders_match, available_levels, circular_star, crossed_line_star, layout_glyphs_roboto,
+    miter_stroke_2,
+};
 use std::f64::consts::PI;
+use std::ops::Range;
 use vello_common::coarse::Cmd;
 use vello_common::color::palette::css::{
     BEIGE, BLUE, DARK_BLUE, GREEN, LIME, MAROON, REBECCA_PURPLE, RED, TRANSPARENT,
 };
-use vello_common::kurbo::{Affine, BezPath, Circle, Join, Point, Rect, Shape, Stroke};
+use vello_common::color::{AlphaColor, Srgb};
+use vello_common::kurbo::{
+    self, Affine, BezPath, Cap, Circle, Ellipse, Join, Line, Point, Rect, Shape, Stroke, StrokeOpts,
+};
 use vello_common::peniko::Fill;
 use vello_cpu::color::palette::css::BLACK;
 use vello_cpu::{Glyph, Level, Pixmap, RenderContext, RenderMode, RenderSettings};
@@ -175,6 +183,18 @@ fn filling_evenodd_rule(ctx: &mut impl Renderer) {
     ctx.fill_path(&star);
 }
 
+/// Like `filling_evenodd_rule`, but stretched so that both the filled interior and the
+/// central hole span multiple wide tiles, which are filled by sparse fill commands.
+#[vello_test(width = 600, height = 200)]
+fn filling_evenodd_rule_wide(ctx: &mut impl Renderer) {
+    let star = crossed_line_star();
+
+    ctx.set_paint(MAROON);
+    ctx.set_fill_rule(Fill::EvenOdd);
+    ctx.set_transform(Affine::scale_non_uniform(6.0, 2.0));
+    ctx.fill_path(&star);
+}
+
 #[vello_test(width = 30, height = 20)]
 fn filled_aligned_rect(ctx: &mut impl Renderer) {
     let rect = Rect::new(1.0, 1.0, 29.0, 19.0);
@@ -361,6 +381,25 @@ fn strip_inscribed_rect(ctx: &mut impl Renderer) {
     ctx.fill_rect(&rect);
 }
 
+/// Draw rows of strips with distinct colors and anti-aliased edges, so that the position, alpha
+/// column and color of each strip instance of `vello_hybrid` have to reach the shader intact.
+#[vello_test(width = 64, height = 16)]
+fn strip_instances_with_distinct_colors(ctx: &mut impl Renderer) {
+    let colors: [AlphaColor<Srgb>; 4] = [
+        AlphaColor::from_rgba8(0x12, 0x34, 0x56, 0xff),
+        AlphaColor::from_rgba8(0xfe, 0xdc, 0xba, 0xff),
+        AlphaColor::from_rgba8(0x80, 0x00, 0x40, 0xff),
+        AlphaColor::from_rgba8(0x00, 0xa0, 0xff, 0xff),
+    ];
+    for (row, color) in colors.iter().enumerate() {
+        let y = 4.0 * row as f64;
+        ctx.set_paint(*color);
+        // The half-pixel edges produce strips with partial coverage, while the pixels in between
+        // are drawn as sparse fills.
+        ctx.fill_rect(&Rect::new(4.5, y, 59.5, y + 4.0));
+    }
+}
+
 #[vello_test(width = 5, height = 8)]
 fn filled_vertical_hairline_rect(ctx: &mut impl Renderer) {
     let rect = Rect::new(2.25, 0.0, 2.75, 8.0);
@@ -377,6 +416,14 @@ fn filled_vertical_hairline_rect_2(ctx: &mut impl Renderer) {
     ctx.fill_rect(&rect);
 }
 
+/// Fill an ellipse whose interior spans several wide tiles, so that most of it is drawn with
+/// sparse fills between its strips.
+#[vello_test(width = 1024, height = 256)]
+fn filled_large_ellipse(ctx: &mut impl Renderer) {
+    ctx.set_paint(LIME);
+    ctx.fill_path(&Ellipse::new((512.0, 128.0), (502.0, 118.0), 0.0).to_path(0.1));
+}
+
 #[vello_test]
 fn oversized_star(ctx: &mut impl Renderer) {
     // Create a star path that extends beyond the render context boundaries
@@ -547,3 +594,426 @@ fn composite_to_pixmap_at_offset() {
         "composite_to_pixmap_at_offset result should match direct rendering"
     );
 }
+
+/// Geometry far left of the viewport is culled during tiling, but its winding must still be
+/// accounted for. Compare against the same shape with its left part clipped at the viewport edge.
+#[test]
+fn shape_far_left_of_viewport_matches_clipped() {
+    let render = |path: &BezPath, fill_rule: Fill| {
+        render_all_backends!(100, 100, |ctx| {
+            ctx.set_paint(REBECCA_PURPLE);
+            ctx.set_fill_rule(fill_rule);
+            ctx.fill_path(path);
+        })
+    };
+
+    let with_left_edge = |left: &[(f64, f64)]| {
+        let mut path = BezPath::new();
+        path.move_to((left[0].0, 5.5));
+        path.line_to((40.0, 5.5));
+        path.curve_to((95.0, 30.0), (95.0, 70.0), (40.0, 94.25));
+        for point in left {
+            path.line_to(*point);
+        }
+        path.close_path();
+        path
+    };
+
+    // The left edges of the shape span the same rows, so they cover every visible pixel of
+    // those rows the same way.
+    let far_left = with_left_edge(&[(-3000.0, 94.25), (-2500.0, 60.0), (-3000.0, 5.5)]);
+    let clipped = with_left_edge(&[(0.0, 94.25), (0.0, 5.5)]);
+
+    for fill_rule in [Fill::NonZero, Fill::EvenOdd] {
+        assert_renders_match(
+            &render(&far_left, fill_rule),
+            &render(&clipped, fill_rule),
+            &format!("{fill_rule:?}: shape extending left of the viewport vs. clipped shape"),
+        );
+    }
+}
+
+/// Vertical edges produce infinite slopes, and edges collinear with a pixel edge produce NaNs in
+/// the coverage calculation. Check that they don't drop or bleed into neighboring columns.
+#[test]
+fn vertical_edges_cover_exact_columns() {
+    const WIDTH: u16 = 300;
+    const HEIGHT: u16 = 24;
+
+    let rects = |xs: &[(f64, f64)]| {
+        let mut path = BezPath::new();
+        for &(x0, x1) in xs {
+            path.extend(&Rect::new(x0, 2.0, x1, 22.0).to_path(0.1));
+        }
+        path
+    };
+    // Thin vertical strokes, like table borders and dividers.
+    let vertical_stroke = |x: f64| {
+        kurbo::stroke(
+            Line::new((x, 2.0), (x, 22.0)).path_elements(0.1),
+            &Stroke::new(1.0).with_caps(Cap::Butt),
+            &StrokeOpts::default(),
+            0.1,
+        )
+    };
+
+    // The covered columns and their alpha, for each row from 2 to 22.
+    type Columns = &'static [(Range<u16>, u8)];
+    let cases: [(&str, BezPath, Fill, Columns); 11] = [
+        (
+            "pixel boundary",
+            rects(&[(10.0, 11.0)]),
+            Fill::NonZero,
+            &[(10..11, 255)],
+        ),
+        (
+            "left tile boundary",
+            rects(&[(8.0, 9.0)]),
+            Fill::NonZero,
+            &[(8..9, 255)],
+        ),
+        (
+            "right tile boundary",
+            rects(&[(15.0, 16.0)]),
+            Fill::NonZero,
+            &[(15..16, 255)],
+        ),
+        (
+            "wide tile boundary",
+            rects(&[(255.0, 257.0)]),
+            Fill::NonZero,
+            &[(255..257, 255)],
+        ),
+        (
+            "straddling tile boundary",
+            rects(&[(3.5, 4.5)]),
+            Fill::NonZero,
+            &[(3..5, 128)],
+        ),
+        (
+            "stroke centered on pixel",
+            vertical_stroke(10.5),
+            Fill::NonZero,
+            &[(10..11, 255)],
+        ),
+        (
+            "stroke on tile boundary",
+            vertical_stroke(12.0),
+            Fill::NonZero,
+            &[(11..13, 128)],
+        ),
+        (
+            "coincident edges with opposite winding",
+            rects(&[(4.0, 8.0), (8.0, 12.0)]),
+            Fill::NonZero,
+            &[(4..12, 255)],
+        ),
+        (
+            "coincident edges with opposite winding, even-odd",
+            rects(&[(6.0, 8.0), (8.0, 10.0)]),
+            Fill::EvenOdd,
+            &[(6..10, 255)],
+        ),
+        ("zero width", rects(&[(8.0, 8.0)]), Fill::NonZero, &[]),
+        (
+            "coincident edges with the same winding, even-odd",
+            rects(&[(8.0, 10.0), (8.0, 10.0)]),
+            Fill::EvenOdd,
+            &[],
+        ),
+    ];
+
+    for (name, path, fill_rule, columns) in &cases {
+        let renders = render_all_backends!(WIDTH, HEIGHT, |ctx| {
+            ctx.set_paint(BLACK);
+            ctx.set_fill_rule(*fill_rule);
+            ctx.fill_path(path);
+        });
+
+        for (backend, pixmap) in &renders {
+            for y in 0..HEIGHT {
+                for x in 0..WIDTH {
+                    let expected = columns
+                        .iter()
+                        .find(|(range, _)| (2..22).contains(&y) && range.contains(&x))
+                        .map_or(0, |(_, alpha)| *alpha);
+                    let alpha = pixmap.sample(x, y).a;
+                    assert!(
+                        alpha.abs_diff(expected) <= backend.tolerance(),
+                        "{name} ({backend:?}): coverage {alpha} instead of {expected} at ({x}, {y})"
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Strips must be identical for all SIMD levels, while alphas may differ by at most 1 due to
+/// fused multiply-adds.
+#[test]
+fn strips_match_across_simd_levels() {
+    use vello_common::strip_generator::{StripGenerator, StripStorage};
+
+    let paths = [
+        (crossed_line_star(), Fill::EvenOdd, Affine::IDENTITY),
+        (
+            crossed_line_star(),
+            Fill::NonZero,
+            Affine::scale_non_uniform(2.9, 1.7),
+        ),
+        (
+            circular_star(Point::new(50.0, 50.0), 13, 20.0, 45.0),
+            Fill::NonZero,
+            Affine::rotate(0.3),
+        ),
+        (
+            Circle::new((100.3, 100.7), 90.2).to_path(0.1),
+            Fill::NonZero,
+            Affine::IDENTITY,
+        ),
+        (
+            Ellipse::new((150.0, 80.0), (140.0, 30.0), 0.7).to_path(0.1),
+            Fill::EvenOdd,
+            Affine::skew(0.3, 0.1),
+        ),
+    ];
+
+    let generate = |level: Level, path: &BezPath, fill_rule: Fill, transform: Affine| {
+        let mut generator = StripGenerator::new(300, 200, level);
+        let mut storage = StripStorage::default();
+        generator.generate_filled_path(path, fill_rule, transform, None, &mut storage, None);
+        storage
+    };
+
+    for (path, fill_rule, transform) in &paths {
+        let reference = generate(Level::fallback(), path, *fill_rule, *transform);
+
+        for level in available_levels() {
+            let storage = generate(level, path, *fill_rule, *transform);
+            assert_eq!(storage.strips, reference.strips, "{level:?}: strips differ");
+            assert_eq!(storage.alphas.len(), reference.alphas.len());
+            let max_diff = storage
+                .alphas
+                .iter()
+                .zip(&reference.alphas)
+                .map(|(a, b)| a.abs_diff(*b))
+                .max()
+                .unwrap_or(0);
+            assert!(max_diff <= 1, "{level:?}: alphas differ by {max_diff}");
+        }
+    }
+}
+
+/// Display P3 red is outside of the sRGB gamut, so it is clipped to sRGB red. The premultiplied
+/// components of semi-transparent wide-gamut colors and gradients must not exceed their alpha.
+#[test]
+fn wide_gamut_colors_are_clipped_to_srgb() {
+    use vello_common::color::{AlphaColor, ColorSpaceTag, DisplayP3};
+    use vello_common::peniko::Gradient;
+
+    let p3_red = AlphaColor::<DisplayP3>::new([1.0, 0.0, 0.0, 0.5]);
+    let p3_green = AlphaColor::<DisplayP3>::new([0.0, 1.0, 0.0, 0.5]);
+
+    let renders = render_all_backends!(64, 8, |ctx| {
+        ctx.set_paint(p3_red);
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 64.0, 4.0));
+        ctx.set_paint(
+            Gradient::new_linear((0.0, 0.0), (64.0, 0.0))
+                .with_interpolation_cs(ColorSpaceTag::DisplayP3)
+                .with_stops([p3_red, p3_green]),
+        );
+        ctx.fill_rect(&Rect::new(0.0, 4.0, 64.0, 8.0));
+    });
+
+    let expected = RED.with_alpha(0.5).premultiply().to_rgba8().to_u8_array();
+    for (backend, pixmap) in &renders {
+        for x in 0..64 {
+            let actual = pixmap.sample(x, 0).to_u8_array();
+            assert!(
+                actual
+                    .iter()
+                    .zip(expected)
+                    .all(|(a, e)| a.abs_diff(e) <= backend.tolerance()),
+                "{backend:?}: {actual:?} instead of {expected:?} at ({x}, 0)"
+            );
+            for y in 0..8 {
+                let pixel = pixmap.sample(x, y);
+                assert!(
+                    pixel.r <= pixel.a && pixel.g <= pixel.a && pixel.b <= pixel.a,
+                    "{backend:?}: invalid premultiplied color {pixel:?} at ({x}, {y})"
+                );
+            }
+        }
+    }
+}
+
+/// The aliasing threshold applies per draw, so aliased and anti-aliased paths can be mixed in one
+/// scene. Clip paths keep the threshold that was set when they were pushed.
+#[test]
+fn aliasing_threshold_applies_per_draw() {
+    let is_aliased = |alpha: u8| alpha == 0 || alpha == 255;
+
+    // An aliased rotated rect next to an anti-aliased circle.
+    let renders = render_all_backends!(100, 50, |ctx| {
+        ctx.set_paint(BLACK);
+        ctx.set_aliasing_threshold(Some(128));
+        ctx.set_transform(Affine::rotate_about(0.3, Point::new(25.0, 25.0)));
+        ctx.fill_rect(&Rect::new(10.0, 10.0, 40.0, 40.0));
+        ctx.set_aliasing_threshold(None);
+        ctx.set_transform(Affine::IDENTITY);
+        ctx.fill_path(&Circle::new((75.0, 25.0), 20.0).to_path(0.1));
+    });
+
+    for (backend, pixmap) in &renders {
+        let alphas = |xs: Range<u16>| {
+            (0..50).flat_map(move |y| xs.clone().map(move |x| pixmap.sample(x, y).a))
+        };
+        assert!(
+            alphas(0..50).all(is_aliased),
+            "{backend:?}: rect is anti-aliased"
+        );
+        assert!(
+            alphas(50..100).any(|a| !is_aliased(a)),
+            "{backend:?}: circle isn't anti-aliased"
+        );
+    }
+
+    // An aliased clip around anti-aliased content with edges at half pixels.
+    let renders = render_all_backends!(50, 50, |ctx| {
+        ctx.set_paint(BLACK);
+        ctx.set_aliasing_threshold(Some(128));
+        ctx.push_clip_layer(&Circle::new((25.0, 25.0), 20.0).to_path(0.1));
+        ctx.set_aliasing_threshold(None);
+        ctx.fill_rect(&Rect::new(10.5, 0.0, 39.5, 50.0));
+        ctx.pop_layer();
+    });
+
+    for (backend, pixmap) in &renders {
+        let is_half_covered = |alpha: u8| alpha.abs_diff(128) <= backend.tolerance();
+        for y in 0..50 {
+            for x in 0..50 {
+                let alpha = pixmap.sample(x, y).a;
+                if x == 10 || x == 39 {
+                    assert!(
+                        alpha == 0 || is_half_covered(alpha),
+                        "{backend:?}: wrong edge coverage at ({x}, {y})"
+                    );
+                } else {
+                    assert!(
+                        is_aliased(alpha),
+                        "{backend:?}: clip is anti-aliased at ({x}, {y})"
+                    );
+                }
+            }
+        }
+        for x in [10, 39] {
+            assert!(is_half_covered(pixmap.sample(x, 25).a), "{backend:?}");
+        }
+    }
+}
+
+/// Filling empty paths, points, zero-length lines and other paths without any area doesn't
+/// panic or draw anything.
+#[test]
+fn degenerate_paths_draw_nothing() {
+    let mut paths = vec![BezPath::new()];
+    let mut path = BezPath::new();
+    path.move_to((10.5, 7.25));
+    paths.push(path.clone());
+    path.line_to((10.5, 7.25));
+    paths.push(path.clone());
+    path.quad_to((10.5, 7.25), (10.5, 7.25));
+    path.close_path();
+    paths.push(path);
+    let mut path = BezPath::new();
+    path.move_to((8.0, 4.0));
+    path.close_path();
+    path.move_to((20.0, 12.0));
+    path.move_to((40.0, 16.0));
+    paths.push(path);
+    let mut path = BezPath::new();
+    path.move_to((2.0, 3.0));
+    path.line_to((30.5, 17.25));
+    path.line_to((16.25, 10.125));
+    path.close_path();
+    paths.push(path);
+
+    let renders = render_all_backends!(40, 20, |ctx| {
+        ctx.set_paint(BLUE);
+        for path in &paths {
+            for fill_rule in [Fill::NonZero, Fill::EvenOdd] {
+                ctx.set_fill_rule(fill_rule);
+                ctx.fill_path(path);
+            }
+        }
+    });
+
+    for (backend, pixmap) in &renders {
+        assert!(
+            pixmap.data().iter().all(|pixel| pixel.a == 0),
+            "{backend:?}"
+        );
+    }
+}
+
+/// Segments with non-finite coordinates are dropped, while the rest of the path is still drawn.
+#[test]
+fn non_finite_segments_are_dropped() {
+    let render = |path: &BezPath| {
+        render_all_backends!(60, 60, |ctx| {
+            ctx.set_paint(BLUE);
+            ctx.fill_path(path);
+            ctx.set_paint(RED);
+            ctx.set_stroke(Stroke::new(3.0));
+            ctx.stroke_path(path);
+        })
+    };
+
+    let mut expected = BezPath::new();
+    expected.move_to((10.5, 10.25));
+    expected.line_to((50.0, 15.0));
+    expected.line_to((45.75, 50.0));
+    expected.line_to((10.0, 40.0));
+    expected.close_path();
+
+    let mut path = BezPath::new();
+    path.move_to((10.5, 10.25));
+    path.line_to((50.0, 15.0));
+    path.curve_to((f64::NAN, 20.0), (55.0, 40.0), (45.75, 50.0));
+    path.line_to((f64::INFINITY, 45.0));
+    path.line_to((10.0, 40.0));
+    path.close_path();
+
+    assert_renders_match(&render(&path), &render(&expected), "non-finite segments");
+}
+
+/// Geometry far outside of the viewport is clipped at the viewport edges, without its coordinates
+/// wrapping around into the visible area.
+#[test]
+fn huge_coordinates_are_clipped_to_viewport() {
+    let render = |far: f64| {
+        render_all_backends!(100, 100, |ctx| {
+            let mut triangle = BezPath::new();
+            triangle.move_to((10.0, 10.0));
+            triangle.line_to((far, far));
+            triangle.line_to((10.0, far));
+            triangle.close_path();
+            ctx.set_paint(BLUE);
+            ctx.fill_path(&triangle);
+
+            let mut line = BezPath::new();
+            line.move_to((40.0, 20.0));
+            line.line_to((far, 20.0));
+            line.move_to((60.0, 60.0));
+            line.line_to((60.0, -far));
+            ctx.set_paint(RED);
+            ctx.set_stroke(Stroke::new(4.0));
+            ctx.stroke_path(&line);
+        })
+    };
+
+    let expected = render(1000.0);
+    for far in [1e5, 1e9, 1e15, 1e30, 1e40, 1e100] {
+        assert_renders_match(&render(far), &expected, &format!("{far}"));
+    }
+}
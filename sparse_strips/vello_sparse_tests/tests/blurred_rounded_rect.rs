@@ -3,7 +3,10 @@
 
 use crate::renderer::Renderer;
 use vello_common::color::palette::css::REBECCA_PURPLE;
+use vello_common::filter_effects::{EdgeMode, Filter, FilterPrimitive};
 use vello_common::kurbo::{Affine, Point, Rect};
+use vello_common::pixmap::Pixmap;
+use vello_cpu::{Level, RenderContext, RenderMode, RenderSettings};
 use vello_dev_macros::vello_test;
 
 fn rect_with(ctx: &mut impl Renderer, radius: f32, std_dev: f32, affine: Affine) {
@@ -43,6 +46,11 @@ fn blurred_rounded_rect_large_std_dev(ctx: &mut impl Renderer) {
     rect_with(ctx, 0.0, 20.0, Affine::IDENTITY);
 }
 
+#[vello_test]
+fn blurred_rounded_rect_huge_std_dev(ctx: &mut impl Renderer) {
+    rect_with(ctx, 10.0, 200.0, Affine::IDENTITY);
+}
+
 #[vello_test]
 fn blurred_rounded_rect_with_radius(ctx: &mut impl Renderer) {
     rect_with(ctx, 10.0, 10.0, Affine::IDENTITY);
@@ -62,3 +70,51 @@ fn blurred_rounded_rect_with_transform(ctx: &mut impl Renderer) {
         Affine::rotate_about(45.0_f64.to_radians(), Point::new(50.0, 50.0)),
     );
 }
+
+/// The analytic blur is an approximation, so bound how far it deviates from blurring a crisp
+/// rectangle with a gaussian blur filter.
+#[test]
+fn blurred_rounded_rect_matches_filter_blur() {
+    const STD_DEV: f32 = 5.0;
+    // The filter blur is itself approximated by downsampling, and the two mostly disagree on how
+    // sharp the transition at the rectangle's edges is.
+    const MAX_DIFF: u8 = 24;
+
+    let rect = Rect::new(20.0, 20.0, 80.0, 80.0);
+    let settings = RenderSettings {
+        level: Level::fallback(),
+        num_threads: 0,
+        render_mode: RenderMode::OptimizeQuality,
+    };
+
+    let mut analytic = RenderContext::new_with(100, 100, settings);
+    analytic.set_paint(REBECCA_PURPLE);
+    analytic.fill_blurred_rounded_rect(&rect, 0.0, STD_DEV);
+    analytic.flush();
+    let mut analytic_pixmap = Pixmap::new(100, 100);
+    analytic.render_to_pixmap(&mut analytic_pixmap);
+
+    let mut filtered = RenderContext::new_with(100, 100, settings);
+    filtered.push_filter_layer(Filter::from_primitive(FilterPrimitive::GaussianBlur {
+        std_deviation: (STD_DEV, STD_DEV),
+        edge_mode: EdgeMode::None,
+    }));
+    filtered.set_paint(REBECCA_PURPLE);
+    filtered.fill_rect(&rect);
+    filtered.pop_layer();
+    filtered.flush();
+    let mut filtered_pixmap = Pixmap::new(100, 100);
+    filtered.render_to_pixmap(&mut filtered_pixmap);
+
+    let max_diff = analytic_pixmap
+        .data_as_u8_slice()
+        .iter()
+        .zip(filtered_pixmap.data_as_u8_slice())
+        .map(|(a, b)| a.abs_diff(*b))
+        .max()
+        .unwrap();
+    assert!(
+        max_diff <= MAX_DIFF,
+        "analytic blur deviates from filter blur by {max_diff}"
+    );
+}
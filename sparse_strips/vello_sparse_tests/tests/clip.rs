@@ -8,9 +8,9 @@ use crate::util::{circular_star, crossed_line_star, stops_green_blue};
 use std::f64::consts::PI;
 use vello_common::coarse::WideTile;
 use vello_common::color::palette::css::{
-    BLACK, BLUE, DARK_BLUE, DARK_GREEN, GREEN, REBECCA_PURPLE, RED,
+    BLACK, BLUE, DARK_BLUE, DARK_GREEN, GREEN, LIME, REBECCA_PURPLE, RED,
 };
-use vello_common::kurbo::{Affine, BezPath, Circle, Point, Rect, Shape, Stroke};
+use vello_common::kurbo::{Affine, BezPath, Circle, Ellipse, Point, Rect, Shape, Stroke};
 use vello_common::peniko::Color;
 use vello_common::peniko::Fill;
 use vello_common::tile::Tile;
@@ -315,6 +315,16 @@ fn draw_clipping_outline(ctx: &mut impl Renderer, path: &BezPath) {
     ctx.stroke_path(path);
 }
 
+/// Fill the viewport through a clip path whose interior spans several wide tiles, so that most
+/// of the clip is applied with sparse fills between its strips.
+#[vello_test(width = 1024, height = 256)]
+fn clip_large_ellipse(ctx: &mut impl Renderer) {
+    ctx.push_clip_layer(&Ellipse::new((512.0, 128.0), (502.0, 118.0), 0.0).to_path(0.1));
+    ctx.set_paint(LIME);
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 1024.0, 256.0));
+    ctx.pop_layer();
+}
+
 // See <https://github.com/linebender/vello/issues/917>
 #[vello_test(no_ref)]
 fn clip_exceeding_viewport(ctx: &mut impl Renderer) {
@@ -484,3 +494,152 @@ fn clip_with_sweep_gradient_fill(ctx: &mut impl Renderer) {
 
     ctx.pop_layer();
 }
+
+/// The number of clip slots of `vello_hybrid` should fill the height the device allows.
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn hybrid_slot_count_matches_device_limits() {
+    use vello_hybrid::RenderTargetConfig;
+
+    let (device, _queue) = crate::hybrid::hybrid_device();
+    let renderer = vello_hybrid::Renderer::new(
+        &device,
+        &RenderTargetConfig {
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            width: 100,
+            height: 100,
+        },
+    );
+
+    let expected = device.limits().max_texture_dimension_2d / u32::from(Tile::HEIGHT);
+    assert_eq!(renderer.slot_count(), expected as usize);
+}
+
+/// Render a nested clip with `vello_hybrid` and check that both clip-slot textures were used.
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn clip_nested_capture_clip_slots() {
+    use crate::hybrid::{hybrid_device, render_scene};
+    use vello_hybrid::{RenderSettings, RenderTargetConfig, Scene, SlotParity};
+
+    let width = WideTile::WIDTH;
+    let height = Tile::HEIGHT * 4;
+
+    let mut scene = Scene::new(width, height);
+    scene.push_clip_layer(&Circle::new((40.0, 8.0), 30.0).to_path(0.1));
+    scene.push_clip_layer(&Rect::new(20.0, 2.0, 60.0, 14.0).to_path(0.1));
+    scene.set_paint(REBECCA_PURPLE);
+    scene.fill_rect(&Rect::new(0.0, 0.0, f64::from(width), f64::from(height)));
+    scene.pop_layer();
+    scene.pop_layer();
+
+    let (device, queue) = hybrid_device();
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let mut renderer = vello_hybrid::Renderer::new_with(
+        &device,
+        &RenderTargetConfig {
+            format,
+            width: width.into(),
+            height: height.into(),
+        },
+        RenderSettings {
+            record_clip_slots: true,
+            ..RenderSettings::default()
+        },
+    );
+    render_scene(&mut renderer, &device, &queue, &scene, format);
+
+    let captures = renderer.capture_clip_slots(&device, &queue);
+    assert_eq!(
+        usize::from(captures[0].pixmap.height()),
+        renderer.slot_count() * usize::from(Tile::HEIGHT)
+    );
+    assert_eq!(captures[0].parity, SlotParity::Even);
+    assert_eq!(captures[1].parity, SlotParity::Odd);
+    assert!(captures[0].slot_pixels(renderer.slot_count()).is_none());
+
+    for capture in &captures {
+        assert!(
+            !capture.slots.is_empty(),
+            "no slots were claimed in the {:?} texture",
+            capture.parity
+        );
+        assert!(
+            capture
+                .slots
+                .iter()
+                .filter_map(|usage| capture.slot_pixels(usage.slot))
+                .any(|pixels| pixels.iter().any(|p| p.a != 0)),
+            "all claimed slots in the {:?} texture are empty",
+            capture.parity
+        );
+    }
+}
+
+/// Clipping to a rectangle with `push_clip_rect` intersects the strips of each draw, so no clip
+/// slots are claimed, for axis-aligned as well as rotated and nested rectangles.
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn clip_rect_claims_no_clip_slots() {
+    use crate::hybrid::{hybrid_device, render_scene};
+    use vello_hybrid::{RenderSettings, RenderTargetConfig, Scene};
+
+    let (width, height) = (WideTile::WIDTH, 32);
+    let clip = Rect::new(20.0, 8.0, 60.0, 24.0);
+    let (device, queue) = hybrid_device();
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+
+    let cases = [
+        (Affine::IDENTITY, 1),
+        (Affine::IDENTITY, 10),
+        (Affine::rotate_about(0.3, (40.0, 16.0)), 1),
+    ];
+    for (transform, depth) in cases {
+        let mut scene = Scene::new(width, height);
+        scene.set_transform(transform);
+        // Nested rectangles that all contain `clip`, so their intersection is `clip`.
+        for i in (0..depth).rev() {
+            scene.push_clip_rect(&clip.inflate(f64::from(i), f64::from(i)));
+        }
+        scene.reset_transform();
+        scene.set_paint(REBECCA_PURPLE);
+        scene.fill_rect(&Rect::new(0.0, 0.0, f64::from(width), f64::from(height)));
+        for _ in 0..depth {
+            scene.pop_clip_path();
+        }
+
+        let mut renderer = vello_hybrid::Renderer::new_with(
+            &device,
+            &RenderTargetConfig {
+                format,
+                width: width.into(),
+                height: height.into(),
+            },
+            RenderSettings {
+                record_clip_slots: true,
+                ..RenderSettings::default()
+            },
+        );
+        let pixels = render_scene(&mut renderer, &device, &queue, &scene, format);
+
+        for capture in renderer.capture_clip_slots(&device, &queue) {
+            assert!(
+                capture.slots.is_empty(),
+                "slots were claimed in the {:?} texture with {transform:?} at depth {depth}",
+                capture.parity
+            );
+        }
+
+        if transform == Affine::IDENTITY {
+            let purple = REBECCA_PURPLE.to_rgba8().to_u8_array();
+            for y in 0..height {
+                for x in 0..width {
+                    let idx = (usize::from(y) * usize::from(width) + usize::from(x)) * 4;
+                    let inside = clip.contains(Point::new(f64::from(x) + 0.5, f64::from(y) + 0.5));
+                    let expected = if inside { purple } else { [0; 4] };
+                    assert_eq!(pixels[idx..idx + 4], expected, "wrong pixel at ({x}, {y})");
+                }
+            }
+        }
+    }
+}
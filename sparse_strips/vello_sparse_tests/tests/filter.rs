@@ -7,9 +7,9 @@ use crate::util::circular_star;
 use crate::{renderer::Renderer, util::layout_glyphs_roboto};
 use vello_common::color::AlphaColor;
 use vello_common::color::palette::css::{
-    BLACK, PURPLE, REBECCA_PURPLE, ROYAL_BLUE, SEA_GREEN, TOMATO, VIOLET,
+    BLACK, PURPLE, REBECCA_PURPLE, ROYAL_BLUE, SEA_GREEN, TOMATO, VIOLET, WHITE,
 };
-use vello_common::filter_effects::{EdgeMode, Filter, FilterPrimitive};
+use vello_common::filter_effects::{EdgeMode, Filter, FilterPrimitive, MorphologyOperator};
 use vello_common::kurbo::{Affine, BezPath, Circle, Point, Rect, Shape, Stroke};
 use vello_common::peniko::{BlendMode, Compose, Mix};
 use vello_cpu::color::palette::css::{BLUE, GREEN, RED};
@@ -55,7 +55,7 @@ fn filter_flood_star(ctx: &mut impl Renderer) {
 #[vello_test(skip_hybrid, skip_multithreaded)]
 fn filter_gaussian_blur_no_decimation(ctx: &mut impl Renderer) {
     let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 2.0,
+        std_deviation: (2.0, 2.0),
         edge_mode: EdgeMode::None,
     });
     let rect = Rect::new(20.0, 20.0, 80.0, 80.0).to_path(0.1);
@@ -71,7 +71,7 @@ fn filter_gaussian_blur_no_decimation(ctx: &mut impl Renderer) {
 #[vello_test(skip_hybrid, skip_multithreaded)]
 fn filter_gaussian_blur_with_decimation(ctx: &mut impl Renderer) {
     let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 4.0,
+        std_deviation: (4.0, 4.0),
         edge_mode: EdgeMode::None,
     });
     let rect = Rect::new(20.0, 20.0, 80.0, 80.0).to_path(0.1);
@@ -82,6 +82,22 @@ fn filter_gaussian_blur_with_decimation(ctx: &mut impl Renderer) {
     ctx.pop_layer();
 }
 
+/// Test Gaussian blur with a zero standard deviation on the y axis.
+/// The square should only be smeared horizontally.
+#[vello_test(skip_hybrid, skip_multithreaded)]
+fn filter_gaussian_blur_horizontal_only(ctx: &mut impl Renderer) {
+    let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
+        std_deviation: (8.0, 0.0),
+        edge_mode: EdgeMode::None,
+    });
+    let rect = Rect::new(30.0, 30.0, 70.0, 70.0).to_path(0.1);
+
+    ctx.push_filter_layer(filter);
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.fill_path(&rect);
+    ctx.pop_layer();
+}
+
 /// Test drop shadow filter on text glyph.
 /// Creates a blurred, offset shadow beneath the original graphic.
 #[vello_test(skip_hybrid, skip_multithreaded)]
@@ -92,7 +108,7 @@ fn filter_drop_shadow(ctx: &mut impl Renderer) {
     let filter = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: 16.0,
         dy: 8.0,
-        std_deviation: 2.0,
+        std_deviation: (2.0, 2.0),
         color: REBECCA_PURPLE,
         edge_mode: EdgeMode::None,
     });
@@ -131,7 +147,7 @@ fn filter_drop_shadow_corners(ctx: &mut impl Renderer) {
     let filter_tl = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: -shadow_offset,
         dy: -shadow_offset,
-        std_deviation: shadow_blur,
+        std_deviation: (shadow_blur, shadow_blur),
         color: AlphaColor::from_rgba8(0, 0, 0, 180),
         edge_mode: EdgeMode::Duplicate,
     });
@@ -142,7 +158,7 @@ fn filter_drop_shadow_corners(ctx: &mut impl Renderer) {
     let filter_tc = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: 0.0,
         dy: -shadow_offset,
-        std_deviation: shadow_blur,
+        std_deviation: (shadow_blur, shadow_blur),
         color: AlphaColor::from_rgba8(0, 0, 0, 180),
         edge_mode: EdgeMode::Duplicate,
     });
@@ -153,7 +169,7 @@ fn filter_drop_shadow_corners(ctx: &mut impl Renderer) {
     let filter_tr = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: shadow_offset,
         dy: -shadow_offset,
-        std_deviation: shadow_blur,
+        std_deviation: (shadow_blur, shadow_blur),
         color: AlphaColor::from_rgba8(0, 0, 0, 180),
         edge_mode: EdgeMode::Duplicate,
     });
@@ -164,7 +180,7 @@ fn filter_drop_shadow_corners(ctx: &mut impl Renderer) {
     let filter_lc = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: -shadow_offset,
         dy: 0.0,
-        std_deviation: shadow_blur,
+        std_deviation: (shadow_blur, shadow_blur),
         color: AlphaColor::from_rgba8(0, 0, 0, 180),
         edge_mode: EdgeMode::Duplicate,
     });
@@ -175,7 +191,7 @@ fn filter_drop_shadow_corners(ctx: &mut impl Renderer) {
     let filter_c = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: shadow_offset,
         dy: shadow_offset,
-        std_deviation: shadow_blur,
+        std_deviation: (shadow_blur, shadow_blur),
         color: AlphaColor::from_rgba8(0, 0, 0, 180),
         edge_mode: EdgeMode::Duplicate,
     });
@@ -191,7 +207,7 @@ fn filter_drop_shadow_corners(ctx: &mut impl Renderer) {
     let filter_rc = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: shadow_offset,
         dy: 0.0,
-        std_deviation: shadow_blur,
+        std_deviation: (shadow_blur, shadow_blur),
         color: AlphaColor::from_rgba8(0, 0, 0, 180),
         edge_mode: EdgeMode::Duplicate,
     });
@@ -202,7 +218,7 @@ fn filter_drop_shadow_corners(ctx: &mut impl Renderer) {
     let filter_bl = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: -shadow_offset,
         dy: shadow_offset,
-        std_deviation: shadow_blur,
+        std_deviation: (shadow_blur, shadow_blur),
         color: AlphaColor::from_rgba8(0, 0, 0, 180),
         edge_mode: EdgeMode::Duplicate,
     });
@@ -213,7 +229,7 @@ fn filter_drop_shadow_corners(ctx: &mut impl Renderer) {
     let filter_bc = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: 0.0,
         dy: shadow_offset,
-        std_deviation: shadow_blur,
+        std_deviation: (shadow_blur, shadow_blur),
         color: AlphaColor::from_rgba8(0, 0, 0, 180),
         edge_mode: EdgeMode::Duplicate,
     });
@@ -224,7 +240,7 @@ fn filter_drop_shadow_corners(ctx: &mut impl Renderer) {
     let filter_br = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: shadow_offset,
         dy: shadow_offset,
-        std_deviation: shadow_blur,
+        std_deviation: (shadow_blur, shadow_blur),
         color: AlphaColor::from_rgba8(0, 0, 0, 180),
         edge_mode: EdgeMode::Duplicate,
     });
@@ -241,12 +257,12 @@ fn filter_set_effect(ctx: &mut impl Renderer) {
     let filter_drop_shadow = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: 2.0,
         dy: 2.0,
-        std_deviation: 4.0,
+        std_deviation: (4.0, 4.0),
         color: AlphaColor::from_rgba8(0, 0, 0, 255),
         edge_mode: EdgeMode::None,
     });
     let filter_gaussian_blur = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 2.0,
+        std_deviation: (2.0, 2.0),
         edge_mode: EdgeMode::None,
     });
 
@@ -294,12 +310,12 @@ fn filter_varying_depths_clips_and_compositions(ctx: &mut impl Renderer) {
     let filter_drop_shadow = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: 2.0,
         dy: 2.0,
-        std_deviation: 4.0,
+        std_deviation: (4.0, 4.0),
         color: AlphaColor::from_rgba8(0, 0, 0, 255),
         edge_mode: EdgeMode::None,
     });
     let filter_gaussian_blur = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 2.0,
+        std_deviation: (2.0, 2.0),
         edge_mode: EdgeMode::None,
     });
 
@@ -704,14 +720,14 @@ fn filter_varying_depths_clips_and_compositions(ctx: &mut impl Renderer) {
     top = y;
     {
         ctx.push_filter_layer(Filter::from_primitive(FilterPrimitive::GaussianBlur {
-            std_deviation: 2.0,
+            std_deviation: (2.0, 2.0),
             edge_mode: EdgeMode::None,
         }));
         ctx.set_paint(ROYAL_BLUE);
         ctx.fill_rect(&Rect::from_points((left, top), (left + width, top + width)));
         {
             ctx.push_filter_layer(Filter::from_primitive(FilterPrimitive::GaussianBlur {
-                std_deviation: 2.0,
+                std_deviation: (2.0, 2.0),
                 edge_mode: EdgeMode::None,
             }));
             ctx.set_paint(PURPLE);
@@ -720,7 +736,7 @@ fn filter_varying_depths_clips_and_compositions(ctx: &mut impl Renderer) {
             ctx.fill_rect(&Rect::from_points((left, top), (left + width, top + width)));
             {
                 ctx.push_filter_layer(Filter::from_primitive(FilterPrimitive::GaussianBlur {
-                    std_deviation: 2.0,
+                    std_deviation: (2.0, 2.0),
                     edge_mode: EdgeMode::None,
                 }));
                 ctx.set_paint(VIOLET);
@@ -729,7 +745,7 @@ fn filter_varying_depths_clips_and_compositions(ctx: &mut impl Renderer) {
                 ctx.fill_rect(&Rect::from_points((left, top), (left + width, top + width)));
                 {
                     ctx.push_filter_layer(Filter::from_primitive(FilterPrimitive::GaussianBlur {
-                        std_deviation: 2.0,
+                        std_deviation: (2.0, 2.0),
                         edge_mode: EdgeMode::None,
                     }));
                     ctx.set_paint(SEA_GREEN);
@@ -739,7 +755,7 @@ fn filter_varying_depths_clips_and_compositions(ctx: &mut impl Renderer) {
                     {
                         ctx.push_filter_layer(Filter::from_primitive(
                             FilterPrimitive::GaussianBlur {
-                                std_deviation: 2.0,
+                                std_deviation: (2.0, 2.0),
                                 edge_mode: EdgeMode::None,
                             },
                         ));
@@ -767,7 +783,7 @@ fn filter_varying_depths_clips_and_compositions(ctx: &mut impl Renderer) {
 #[vello_test(skip_hybrid, skip_multithreaded)]
 fn filter_rotated_blur(ctx: &mut impl Renderer) {
     let filter_gaussian_blur = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 4.0,
+        std_deviation: (4.0, 4.0),
         edge_mode: EdgeMode::None,
     });
     let center = Point::new(50.0, 50.0);
@@ -833,7 +849,7 @@ fn filter_rotated_blur(ctx: &mut impl Renderer) {
 #[vello_test(skip_hybrid, skip_multithreaded)]
 fn filter_gaussian_blur_zero(ctx: &mut impl Renderer) {
     let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 0.0,
+        std_deviation: (0.0, 0.0),
         edge_mode: EdgeMode::None,
     });
     let rect = Rect::new(25.0, 25.0, 75.0, 75.0).to_path(0.1);
@@ -850,7 +866,7 @@ fn filter_drop_shadow_fractional_offset(ctx: &mut impl Renderer) {
     let filter = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: 2.5,
         dy: 3.7,
-        std_deviation: 1.0,
+        std_deviation: (1.0, 1.0),
         color: AlphaColor::from_rgba8(0, 0, 0, 180),
         edge_mode: EdgeMode::None,
     });
@@ -868,7 +884,7 @@ fn filter_drop_shadow_zero_offset(ctx: &mut impl Renderer) {
     let filter = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: 0.0,
         dy: 0.0,
-        std_deviation: 4.0,
+        std_deviation: (4.0, 4.0),
         color: AlphaColor::from_rgba8(0, 0, 0, 180),
         edge_mode: EdgeMode::None,
     });
@@ -921,7 +937,7 @@ fn filter_offset(ctx: &mut impl Renderer) {
 #[vello_test(skip_hybrid, skip_multithreaded)]
 fn filter_transformed_blur(ctx: &mut impl Renderer) {
     let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 3.0,
+        std_deviation: (3.0, 3.0),
         edge_mode: EdgeMode::None,
     });
 
@@ -943,7 +959,7 @@ fn filter_transformed_blur(ctx: &mut impl Renderer) {
 #[vello_test(skip_hybrid, skip_multithreaded)]
 fn filter_empty_layers(ctx: &mut impl Renderer) {
     let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 4.0,
+        std_deviation: (4.0, 4.0),
         edge_mode: EdgeMode::None,
     });
 
@@ -960,13 +976,13 @@ fn filter_empty_layers(ctx: &mut impl Renderer) {
 #[vello_test(skip_hybrid, skip_multithreaded)]
 fn filter_nested_layers(ctx: &mut impl Renderer) {
     let blur = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 2.0,
+        std_deviation: (2.0, 2.0),
         edge_mode: EdgeMode::None,
     });
     let shadow = Filter::from_primitive(FilterPrimitive::DropShadow {
         dx: 12.0,
         dy: 12.0,
-        std_deviation: 4.0,
+        std_deviation: (4.0, 4.0),
         color: AlphaColor::from_rgba8(0, 0, 0, 180),
         edge_mode: EdgeMode::None,
     });
@@ -983,7 +999,7 @@ fn filter_nested_layers(ctx: &mut impl Renderer) {
 #[vello_test(skip_hybrid, skip_multithreaded)]
 fn filter_extreme_blur(ctx: &mut impl Renderer) {
     let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 20.0,
+        std_deviation: (20.0, 20.0),
         edge_mode: EdgeMode::None,
     });
     let rect = Rect::new(25.0, 25.0, 75.0, 75.0).to_path(0.1);
@@ -998,7 +1014,7 @@ fn filter_extreme_blur(ctx: &mut impl Renderer) {
 #[vello_test(skip_hybrid, skip_multithreaded)]
 fn filter_transparent_shapes(ctx: &mut impl Renderer) {
     let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 3.0,
+        std_deviation: (3.0, 3.0),
         edge_mode: EdgeMode::None,
     });
 
@@ -1023,7 +1039,7 @@ fn filter_stroked_paths(ctx: &mut impl Renderer) {
     use vello_common::kurbo::{Cap, Join, Stroke};
 
     let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 2.0,
+        std_deviation: (2.0, 2.0),
         edge_mode: EdgeMode::None,
     });
 
@@ -1054,7 +1070,7 @@ fn filter_stroked_paths(ctx: &mut impl Renderer) {
 #[vello_test(skip_hybrid, skip_multithreaded)]
 fn issue_filter_canvas_boundaries(ctx: &mut impl Renderer) {
     let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 5.0,
+        std_deviation: (5.0, 5.0),
         edge_mode: EdgeMode::None,
     });
 
@@ -1089,26 +1105,24 @@ fn issue_filter_canvas_boundaries(ctx: &mut impl Renderer) {
 
 pub(crate) fn blur_with_edge_mode(ctx: &mut impl Renderer, edge_mode: EdgeMode) {
     let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
-        std_deviation: 6.0,
+        std_deviation: (6.0, 6.0),
         edge_mode,
     });
 
-    let step = 256.0 / 3.0;
+    let width = f64::from(ctx.width());
+    let height = f64::from(ctx.height());
+    let step = width / 3.0;
 
     ctx.push_filter_layer(filter);
     ctx.set_paint(RED);
-    ctx.fill_rect(&Rect::new(0.0, 0.0, step, 100.0));
+    ctx.fill_rect(&Rect::new(0.0, 0.0, step, height));
     ctx.set_paint(BLUE);
-    ctx.fill_rect(&Rect::new(step, 0.0, 2.0 * step, 100.0));
+    ctx.fill_rect(&Rect::new(step, 0.0, 2.0 * step, height));
     ctx.set_paint(GREEN);
-    ctx.fill_rect(&Rect::new(2.0 * step, 0.0, 3.0 * step, 100.0));
+    ctx.fill_rect(&Rect::new(2.0 * step, 0.0, 3.0 * step, height));
     ctx.pop_layer();
 }
 
-// TODO: Currently, these tests have a width/height that is a multiple of a wide tile,
-// because edge modes currently don't handle other widths/heights correctly. Once that is
-// fixed, we should change the tests back to 100x100 to exercise that path as well.
-
 #[vello_test(skip_hybrid, skip_multithreaded, width = 256, height = 100)]
 fn filter_gaussian_blur_edge_mode_duplicate(ctx: &mut impl Renderer) {
     blur_with_edge_mode(ctx, EdgeMode::Duplicate);
@@ -1123,3 +1137,261 @@ fn filter_gaussian_blur_edge_mode_wrap(ctx: &mut impl Renderer) {
 fn filter_gaussian_blur_edge_mode_mirror(ctx: &mut impl Renderer) {
     blur_with_edge_mode(ctx, EdgeMode::Mirror);
 }
+
+// The filtered region does not end on a wide tile boundary here, so the edge mode must be
+// applied at the edge of the render target rather than at the (invisible) tile padding.
+
+#[vello_test(skip_hybrid, skip_multithreaded, width = 100, height = 98)]
+fn filter_gaussian_blur_edge_mode_duplicate_unaligned(ctx: &mut impl Renderer) {
+    blur_with_edge_mode(ctx, EdgeMode::Duplicate);
+}
+
+#[vello_test(skip_hybrid, skip_multithreaded, width = 100, height = 98)]
+fn filter_gaussian_blur_edge_mode_wrap_unaligned(ctx: &mut impl Renderer) {
+    blur_with_edge_mode(ctx, EdgeMode::Wrap);
+}
+
+#[vello_test(skip_hybrid, skip_multithreaded, width = 100, height = 98)]
+fn filter_gaussian_blur_edge_mode_mirror_unaligned(ctx: &mut impl Renderer) {
+    blur_with_edge_mode(ctx, EdgeMode::Mirror);
+}
+
+// A solid rectangle filling the whole filter region must stay solid under `EdgeMode::Duplicate`,
+// even when the blur radius exceeds the region size.
+#[vello_test(skip_hybrid, skip_multithreaded, width = 37, height = 5)]
+fn filter_gaussian_blur_edge_mode_duplicate_large_radius(ctx: &mut impl Renderer) {
+    ctx.push_filter_layer(Filter::from_primitive(FilterPrimitive::GaussianBlur {
+        std_deviation: (20.0, 20.0),
+        edge_mode: EdgeMode::Duplicate,
+    }));
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 37.0, 5.0));
+    ctx.pop_layer();
+}
+
+/// Draw a group of differently colored shapes.
+fn color_matrix_shapes(ctx: &mut impl Renderer) {
+    ctx.set_paint(TOMATO);
+    ctx.fill_rect(&Rect::new(10.0, 10.0, 55.0, 55.0));
+    ctx.set_paint(ROYAL_BLUE);
+    ctx.fill_path(&Circle::new((65.0, 40.0), 25.0).to_path(0.1));
+    ctx.set_paint(SEA_GREEN.with_alpha(0.7));
+    ctx.fill_path(&circular_star(Point::new(45.0, 68.0), 5, 12.0, 28.0));
+}
+
+#[vello_test(skip_hybrid, skip_multithreaded)]
+fn filter_color_matrix_saturate(ctx: &mut impl Renderer) {
+    ctx.push_filter_layer(Filter::from_primitive(FilterPrimitive::saturate(0.0)));
+    color_matrix_shapes(ctx);
+    ctx.pop_layer();
+}
+
+#[vello_test(skip_hybrid, skip_multithreaded)]
+fn filter_color_matrix_hue_rotate(ctx: &mut impl Renderer) {
+    ctx.push_filter_layer(Filter::from_primitive(FilterPrimitive::hue_rotate(120.0)));
+    color_matrix_shapes(ctx);
+    ctx.pop_layer();
+}
+
+#[vello_test(skip_hybrid, skip_multithreaded)]
+fn filter_color_matrix_luminance_to_alpha(ctx: &mut impl Renderer) {
+    ctx.push_filter_layer(Filter::from_primitive(FilterPrimitive::luminance_to_alpha()));
+    // Un-premultiplying nearly transparent anti-aliased pixels is very imprecise, and
+    // `luminanceToAlpha` turns that imprecision into alpha. Draw onto an opaque backdrop
+    // so that the result does not depend on it.
+    ctx.set_paint(WHITE);
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+    color_matrix_shapes(ctx);
+    ctx.pop_layer();
+}
+
+/// Desaturating a group should turn every pixel into the luma of its original color.
+#[test]
+fn filter_color_matrix_desaturate_matches_luma() {
+    use vello_common::pixmap::Pixmap;
+    use vello_cpu::{RenderContext, RenderMode, RenderSettings};
+
+    let settings = RenderSettings {
+        num_threads: 0,
+        render_mode: RenderMode::OptimizeSpeed,
+        ..Default::default()
+    };
+    let mut ctx = RenderContext::new_with(100, 100, settings);
+    ctx.push_filter_layer(Filter::from_primitive(FilterPrimitive::saturate(0.0)));
+    color_matrix_shapes(&mut ctx);
+    ctx.pop_layer();
+    ctx.flush();
+    let mut pixmap = Pixmap::new(100, 100);
+    ctx.render_to_pixmap(&mut pixmap);
+
+    // The center of the tomato square is not covered by any other shape.
+    let [r, g, b, _] = TOMATO.to_rgba8().to_u8_array();
+    let luma = (0.213 * f32::from(r) + 0.715 * f32::from(g) + 0.072 * f32::from(b)).round() as u8;
+    let sampled = pixmap.sample(20, 20);
+    assert_eq!(sampled.a, 255);
+    for channel in [sampled.r, sampled.g, sampled.b] {
+        assert!(
+            channel.abs_diff(luma) <= 1,
+            "expected luma {luma}, got {sampled:?}"
+        );
+    }
+}
+
+fn blur_then_drop_shadow() -> [FilterPrimitive; 2] {
+    [
+        FilterPrimitive::GaussianBlur {
+            std_deviation: (2.0, 2.0),
+            edge_mode: EdgeMode::None,
+        },
+        FilterPrimitive::DropShadow {
+            dx: 6.0,
+            dy: 6.0,
+            std_deviation: (3.0, 3.0),
+            color: BLACK.with_alpha(0.6),
+            edge_mode: EdgeMode::None,
+        },
+    ]
+}
+
+/// Test a filter chaining a blur into a drop shadow.
+#[vello_test(skip_hybrid, skip_multithreaded)]
+fn filter_chained_blur_drop_shadow(ctx: &mut impl Renderer) {
+    ctx.push_filter_layer(Filter::from_primitives(blur_then_drop_shadow()));
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.fill_path(&circular_star(Point::new(45.0, 45.0), 5, 14.0, 30.0));
+    ctx.pop_layer();
+}
+
+/// A chain of primitives in one filter should match nesting a filter layer per primitive.
+#[test]
+fn filter_chained_matches_nested_layers() {
+    use vello_common::pixmap::Pixmap;
+    use vello_cpu::{RenderContext, RenderMode, RenderSettings};
+
+    let render = |nested: bool| {
+        let settings = RenderSettings {
+            num_threads: 0,
+            render_mode: RenderMode::OptimizeSpeed,
+            ..Default::default()
+        };
+        let mut ctx = RenderContext::new_with(100, 100, settings);
+        let [blur, drop_shadow] = blur_then_drop_shadow();
+        if nested {
+            ctx.push_filter_layer(Filter::from_primitive(drop_shadow));
+            ctx.push_filter_layer(Filter::from_primitive(blur));
+        } else {
+            ctx.push_filter_layer(Filter::from_primitives([blur, drop_shadow]));
+        }
+        ctx.set_paint(REBECCA_PURPLE);
+        ctx.fill_path(&circular_star(Point::new(45.0, 45.0), 5, 14.0, 30.0));
+        ctx.pop_layer();
+        if nested {
+            ctx.pop_layer();
+        }
+        ctx.flush();
+        let mut pixmap = Pixmap::new(100, 100);
+        ctx.render_to_pixmap(&mut pixmap);
+        pixmap
+    };
+
+    let chained = render(false);
+    let nested = render(true);
+    for y in 0..100 {
+        for x in 0..100 {
+            assert_eq!(
+                chained.sample(x, y),
+                nested.sample(x, y),
+                "pixel ({x}, {y}) differs between chained and nested filters"
+            );
+        }
+    }
+}
+
+fn morphology_star(ctx: &mut impl Renderer, operator: MorphologyOperator) {
+    ctx.push_filter_layer(Filter::from_primitive(FilterPrimitive::Morphology {
+        operator,
+        radius: (3.0, 3.0),
+    }));
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.fill_path(&circular_star(Point::new(50.0, 50.0), 5, 16.0, 34.0));
+    ctx.pop_layer();
+}
+
+/// Test dilating a star, which thickens its arms.
+#[vello_test(skip_hybrid, skip_multithreaded)]
+fn filter_morphology_dilate_star(ctx: &mut impl Renderer) {
+    morphology_star(ctx, MorphologyOperator::Dilate);
+}
+
+/// Test eroding a star, which thins its arms.
+#[vello_test(skip_hybrid, skip_multithreaded)]
+fn filter_morphology_erode_star(ctx: &mut impl Renderer) {
+    morphology_star(ctx, MorphologyOperator::Erode);
+}
+
+/// Test a blur confined to bounds that are tighter than the blurred content, which
+/// crops the result at the edge of the bounds.
+#[vello_test(skip_hybrid, skip_multithreaded)]
+fn filter_gaussian_blur_bounds(ctx: &mut impl Renderer) {
+    let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
+        std_deviation: (4.0, 4.0),
+        edge_mode: EdgeMode::None,
+    })
+    .with_bounds(Rect::new(20.0, 20.0, 70.0, 60.0));
+
+    ctx.push_filter_layer(filter);
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.fill_rect(&Rect::new(10.0, 30.0, 90.0, 70.0));
+    ctx.pop_layer();
+}
+
+/// Test that filter bounds are affected by the transform in place.
+#[vello_test(skip_hybrid, skip_multithreaded)]
+fn filter_gaussian_blur_bounds_transformed(ctx: &mut impl Renderer) {
+    let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
+        std_deviation: (2.0, 2.0),
+        edge_mode: EdgeMode::None,
+    })
+    .with_bounds(Rect::new(10.0, 10.0, 35.0, 35.0));
+
+    ctx.set_transform(Affine::translate((5.0, 5.0)).then_scale(2.0));
+    ctx.push_filter_layer(filter);
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.fill_path(&Circle::new((25.0, 25.0), 15.0).to_path(0.1));
+    ctx.pop_layer();
+}
+
+/// Content outside of the filter bounds is clipped, while content inside is kept.
+#[test]
+fn filter_bounds_crop_outside() {
+    use vello_common::pixmap::Pixmap;
+    use vello_cpu::{RenderContext, RenderMode, RenderSettings};
+
+    let settings = RenderSettings {
+        num_threads: 0,
+        render_mode: RenderMode::OptimizeSpeed,
+        ..Default::default()
+    };
+    let mut ctx = RenderContext::new_with(100, 100, settings);
+    let filter = Filter::from_primitive(FilterPrimitive::Offset { dx: 0.0, dy: 0.0 })
+        .with_bounds(Rect::new(20.0, 30.0, 60.0, 70.0));
+    ctx.push_filter_layer(filter);
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+    ctx.pop_layer();
+    ctx.flush();
+    let mut pixmap = Pixmap::new(100, 100);
+    ctx.render_to_pixmap(&mut pixmap);
+
+    for y in 0..100 {
+        for x in 0..100 {
+            let inside = (20..60).contains(&x) && (30..70).contains(&y);
+            let alpha = pixmap.sample(x, y).a;
+            assert_eq!(
+                alpha,
+                if inside { 255 } else { 0 },
+                "pixel ({x}, {y}) has unexpected alpha"
+            );
+        }
+    }
+}
@@ -9,9 +9,9 @@ use crate::util::layout_glyphs_apple_color_emoji;
 use crate::util::{layout_glyphs_noto_cbtf, layout_glyphs_noto_colr, layout_glyphs_roboto};
 use std::iter;
 use std::sync::Arc;
-use vello_common::color::palette::css::{BLACK, BLUE, GREEN, REBECCA_PURPLE};
-use vello_common::glyph::Glyph;
-use vello_common::kurbo::Affine;
+use vello_common::color::palette::css::{BLACK, BLUE, GREEN, LIME, ORANGE, REBECCA_PURPLE};
+use vello_common::glyph::{Glyph, GlyphRunBuilder, YAxis};
+use vello_common::kurbo::{Affine, Stroke};
 use vello_common::peniko::{Blob, FontData};
 use vello_dev_macros::vello_test;
 
@@ -67,6 +67,28 @@ fn glyphs_stroked_unhinted(ctx: &mut impl Renderer) {
         .stroke_glyphs(glyphs.into_iter());
 }
 
+/// Outlined text, stroked behind the fill at a few stroke widths. The stroke and paint of the
+/// outline must not replace the current ones, which the fill uses.
+#[vello_test(width = 230, height = 180)]
+fn glyphs_outlined(ctx: &mut impl Renderer) {
+    let font_size: f32 = 50_f32;
+    let (font, glyphs) = layout_glyphs_roboto("Vello", font_size);
+
+    ctx.set_paint(REBECCA_PURPLE);
+    for (i, width) in [1.0, 4.0, 8.0].into_iter().enumerate() {
+        ctx.set_transform(Affine::translate((
+            10.0 + 20.0 * i as f64,
+            50.0 + 55.0 * i as f64,
+        )));
+        ctx.glyph_run(&font)
+            .font_size(font_size)
+            .stroke_glyphs_with(&Stroke::new(width), ORANGE, glyphs.iter().copied());
+        ctx.glyph_run(&font)
+            .font_size(font_size)
+            .fill_glyphs(glyphs.iter().copied());
+    }
+}
+
 #[vello_test(width = 300, height = 70)]
 fn glyphs_skewed(ctx: &mut impl Renderer) {
     let font_size: f32 = 50_f32;
@@ -81,6 +103,40 @@ fn glyphs_skewed(ctx: &mut impl Renderer) {
         .fill_glyphs(glyphs.into_iter());
 }
 
+/// Upright text above the same text with a synthetic oblique style, which shears the glyphs
+/// about their baseline.
+#[vello_test(width = 300, height = 130)]
+fn glyphs_synthetic_oblique(ctx: &mut impl Renderer) {
+    let font_size: f32 = 50_f32;
+    let (font, glyphs) = layout_glyphs_roboto("Hello, world!", font_size);
+
+    ctx.set_paint(REBECCA_PURPLE.with_alpha(0.5));
+    for (i, angle) in [0.0, 14_f64.to_radians()].into_iter().enumerate() {
+        ctx.set_transform(Affine::translate((0., 50.0 + 60.0 * i as f64)));
+        ctx.glyph_run(&font)
+            .font_size(font_size)
+            .synthetic_oblique(angle)
+            .fill_glyphs(glyphs.iter().copied());
+    }
+}
+
+/// Regular text above the same text with a synthetic bold style. The advances are not
+/// adjusted, so the emboldened glyphs are set more tightly.
+#[vello_test(width = 300, height = 130)]
+fn glyphs_synthetic_bold(ctx: &mut impl Renderer) {
+    let font_size: f32 = 50_f32;
+    let (font, glyphs) = layout_glyphs_roboto("Hello, world!", font_size);
+
+    ctx.set_paint(REBECCA_PURPLE.with_alpha(0.5));
+    for (i, strength) in [0.0, 0.04].into_iter().enumerate() {
+        ctx.set_transform(Affine::translate((0., 50.0 + 60.0 * i as f64)));
+        ctx.glyph_run(&font)
+            .font_size(font_size)
+            .synthetic_bold(strength)
+            .fill_glyphs(glyphs.iter().copied());
+    }
+}
+
 #[vello_test(width = 300, height = 70)]
 fn glyphs_skewed_unhinted(ctx: &mut impl Renderer) {
     let font_size: f32 = 50_f32;
@@ -394,3 +450,212 @@ fn render_colr_noto_with_transform(ctx: &mut impl Renderer, transform: Affine) {
         .hint(false)
         .fill_glyphs(glyphs.into_iter());
 }
+
+/// Check that COLR glyphs, which Vello CPU renders through an intermediate pixmap and Vello
+/// Hybrid paints layer by layer, look the same on both backends.
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn colr_glyphs_match_across_backends() {
+    use crate::hybrid::{hybrid_device, render_scene};
+    use vello_common::pixmap::Pixmap;
+    use vello_cpu::RenderContext;
+    use vello_hybrid::{RenderTargetConfig, Scene};
+
+    let (width, height) = (250_u16, 70_u16);
+    let font_size: f32 = 50_f32;
+    let (font, glyphs) = layout_glyphs_noto_colr("✅👀🎉🤠", font_size);
+    let transform = Affine::translate((0., 50.));
+
+    let mut ctx = RenderContext::new(width, height);
+    ctx.set_transform(transform);
+    ctx.glyph_run(&font)
+        .font_size(font_size)
+        .hint(false)
+        .fill_glyphs(glyphs.clone().into_iter());
+    ctx.flush();
+    let mut pixmap = Pixmap::new(width, height);
+    ctx.render_to_pixmap(&mut pixmap);
+
+    let mut scene = Scene::new(width, height);
+    scene.set_transform(transform);
+    scene
+        .glyph_run(&font)
+        .font_size(font_size)
+        .hint(false)
+        .fill_glyphs(glyphs.into_iter());
+    let (device, queue) = hybrid_device();
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let mut renderer = vello_hybrid::Renderer::new(
+        &device,
+        &RenderTargetConfig {
+            format,
+            width: width.into(),
+            height: height.into(),
+        },
+    );
+    let pixels = render_scene(&mut renderer, &device, &queue, &scene, format);
+
+    // Vello CPU resamples the intermediate pixmap, so edges differ slightly. Averaging keeps
+    // the check tolerant of that while still catching misplaced or missing layers (a shift by
+    // a single pixel already yields a mean difference above 10).
+    let cpu = pixmap.data_as_u8_slice();
+    let total_diff: u64 = cpu
+        .iter()
+        .zip(&pixels)
+        .map(|(a, b)| u64::from(a.abs_diff(*b)))
+        .sum();
+    let mean_diff = total_diff as f64 / cpu.len() as f64;
+    assert!(
+        mean_diff < 6.0,
+        "COLR glyphs differ between backends, mean channel difference is {mean_diff}"
+    );
+}
+
+/// Check that clearing the glyph caches empties them and that the next glyph run fills them
+/// again.
+#[test]
+fn glyph_caches_can_be_cleared() {
+    use vello_common::glyph::GlyphCacheStats;
+
+    let font_size: f32 = 50_f32;
+    let (font, glyphs) = layout_glyphs_roboto("Hello, world!", font_size);
+    let check =
+        |draw: &mut dyn FnMut(), clear: &mut dyn FnMut(), stats: &dyn Fn() -> GlyphCacheStats| {
+            assert_eq!(stats(), GlyphCacheStats::default());
+            draw();
+            let filled = stats();
+            assert!(filled.outlines > 0, "no outlines were cached: {filled:?}");
+            assert_eq!(filled.hinting_instances, 1);
+            assert!(filled.approx_bytes > 0);
+
+            clear();
+            assert_eq!(stats(), GlyphCacheStats::default());
+            draw();
+            assert_eq!(stats(), filled);
+        };
+
+    let ctx = core::cell::RefCell::new(vello_cpu::RenderContext::new(300, 70));
+    check(
+        &mut || {
+            ctx.borrow_mut()
+                .glyph_run(&font)
+                .font_size(font_size)
+                .hint(true)
+                .fill_glyphs(glyphs.clone().into_iter());
+        },
+        &mut || ctx.borrow_mut().clear_glyph_caches(),
+        &|| ctx.borrow().glyph_cache_stats(),
+    );
+
+    let scene = core::cell::RefCell::new(vello_hybrid::Scene::new(300, 70));
+    check(
+        &mut || {
+            scene
+                .borrow_mut()
+                .glyph_run(&font)
+                .font_size(font_size)
+                .hint(true)
+                .fill_glyphs(glyphs.clone().into_iter());
+        },
+        &mut || scene.borrow_mut().clear_glyph_caches(),
+        &|| scene.borrow().glyph_cache_stats(),
+    );
+}
+
+/// Render a single glyph of the COLR test font, configuring the run with `configure`.
+fn render_colr_test_glyph(
+    id: u32,
+    configure: impl FnOnce(
+        GlyphRunBuilder<'_, vello_cpu::RenderContext>,
+    ) -> GlyphRunBuilder<'_, vello_cpu::RenderContext>,
+) -> vello_cpu::Pixmap {
+    const TEST_FONT: &[u8] =
+        include_bytes!("../../../examples/assets/colr_test_glyphs/test_glyphs-glyf_colr_1.ttf");
+    let font = FontData::new(Blob::new(Arc::new(TEST_FONT)), 0);
+
+    let mut ctx = vello_cpu::RenderContext::new(40, 40);
+    ctx.set_paint(BLACK);
+    ctx.set_transform(Affine::translate((0., 32.)));
+    configure(ctx.glyph_run(&font).font_size(32.0).hint(false)).fill_glyphs(iter::once(Glyph {
+        id,
+        x: 0.0,
+        y: 0.0,
+    }));
+    ctx.flush();
+    let mut pixmap = vello_cpu::Pixmap::new(40, 40);
+    ctx.render_to_pixmap(&mut pixmap);
+    pixmap
+}
+
+/// Layers of a COLR glyph that refer to the foreground color use the color of the glyph run.
+#[test]
+fn colr_glyph_with_foreground_color() {
+    // Glyph 154 is a square filled with the foreground color.
+    let pixmap = render_colr_test_glyph(154, |run| run.foreground_color(LIME));
+    assert_eq!(pixmap.sample(14, 12).to_u8_array(), [0, 255, 0, 255]);
+}
+
+/// Selecting a palette changes the colors of a COLR glyph, and palettes the font doesn't have
+/// fall back to the default palette.
+#[test]
+fn colr_glyph_with_palette() {
+    // Glyph 12 is filled with palette colors.
+    let default = render_colr_test_glyph(12, |run| run);
+    let second = render_colr_test_glyph(12, |run| run.palette(1));
+    let missing = render_colr_test_glyph(12, |run| run.palette(u16::MAX));
+    assert_ne!(default.data_as_u8_slice(), second.data_as_u8_slice());
+    assert_eq!(default.data_as_u8_slice(), missing.data_as_u8_slice());
+}
+
+/// Render "Hello" on two lines, in a y-down coordinate system or in a y-up one that is flipped
+/// onto the pixmap.
+fn render_glyphs_with_y_axis(y_axis: YAxis, hint: bool) -> vello_cpu::Pixmap {
+    let font_size: f32 = 30_f32;
+    let (font, glyphs) = layout_glyphs_roboto("Hello", font_size);
+    let (width, height) = (120, 90);
+    let mut ctx = vello_cpu::RenderContext::new(width, height);
+
+    // The second line is placed below the first one, and the glyphs are slanted to the right
+    // like italic text, which requires the opposite skew when the y-axis points up.
+    let mut line_offset = 35.0;
+    let mut skew = -0.2;
+    let transform = match y_axis {
+        YAxis::Down => Affine::translate((5.0, 35.0)),
+        YAxis::Up => {
+            line_offset = -line_offset;
+            skew = -skew;
+            Affine::translate((0.0, f64::from(height)))
+                * Affine::FLIP_Y
+                * Affine::translate((5.0, 55.0))
+        }
+    };
+    let glyphs = glyphs.iter().copied().chain(glyphs.iter().map(|g| Glyph {
+        y: g.y + line_offset,
+        ..*g
+    }));
+
+    ctx.set_transform(transform);
+    ctx.set_paint(BLACK);
+    ctx.glyph_run(&font)
+        .font_size(font_size)
+        .hint(hint)
+        .glyph_transform(Affine::skew(skew, 0.0))
+        .y_axis(y_axis)
+        .fill_glyphs(glyphs);
+    ctx.flush();
+    let mut pixmap = vello_cpu::Pixmap::new(width, height);
+    ctx.render_to_pixmap(&mut pixmap);
+    pixmap
+}
+
+/// Glyphs drawn in a y-up coordinate system aren't flipped a second time, and look the same as
+/// in a y-down coordinate system.
+#[test]
+fn glyphs_y_axis_up() {
+    for hint in [false, true] {
+        let down = render_glyphs_with_y_axis(YAxis::Down, hint);
+        let up = render_glyphs_with_y_axis(YAxis::Up, hint);
+        assert!(down.data().iter().any(|p| p.a != 0));
+        assert_eq!(down.data(), up.data(), "hint: {hint}");
+    }
+}
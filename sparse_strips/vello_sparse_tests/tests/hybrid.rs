@@ -0,0 +1,1100 @@
+// Copyright 2025 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests for the `wgpu` renderer of `vello_hybrid`, covering what the `Renderer` trait doesn't
+//! expose, such as render target formats, renderer settings and scene snapshots.
+
+use crate::util::{circular_star, layout_glyphs_roboto};
+use vello_common::coarse::WideTile;
+use vello_common::color::palette::css::{
+    BEIGE, BLACK, BLUE, DARK_BLUE, GREEN, LIME, MAROON, REBECCA_PURPLE, RED,
+};
+use vello_common::kurbo::{Affine, Circle, Line, Point, Rect, Shape, Stroke};
+use vello_hybrid::Scene;
+
+/// Create a `wgpu` device for the `vello_hybrid` tests.
+pub(crate) fn hybrid_device() -> (wgpu::Device, wgpu::Queue) {
+    hybrid_device_with_features(wgpu::Features::empty()).unwrap()
+}
+
+/// Create a `wgpu` device with `features` enabled, or `None` if the adapter doesn't support them.
+pub(crate) fn hybrid_device_with_features(
+    features: wgpu::Features,
+) -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::default(),
+        force_fallback_adapter: false,
+        compatible_surface: None,
+    }))
+    .expect("Failed to find an appropriate adapter");
+    if !adapter.features().contains(features) {
+        return None;
+    }
+    let device = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        required_features: features,
+        ..Default::default()
+    }))
+    .expect("Failed to create device");
+    Some(device)
+}
+
+/// Create a `width` by `height` render target of `format`, let `render` draw into it and read
+/// back its pixels.
+pub(crate) fn render_hybrid_pixels(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    width: u16,
+    height: u16,
+    format: wgpu::TextureFormat,
+    render: impl FnOnce(&mut wgpu::CommandEncoder, &vello_hybrid::RenderSize, &wgpu::TextureView),
+) -> Vec<u8> {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Render Target"),
+        size: wgpu::Extent3d {
+            width: width.into(),
+            height: height.into(),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let render_size = vello_hybrid::RenderSize {
+        width: width.into(),
+        height: height.into(),
+    };
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    render(&mut encoder, &render_size, &view);
+
+    let bytes_per_pixel = format
+        .block_copy_size(None)
+        .expect("render target formats are copyable");
+    let bytes_per_row = (u32::from(width) * bytes_per_pixel).next_multiple_of(256);
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Output Buffer"),
+        size: u64::from(bytes_per_row) * u64::from(height),
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: None,
+            },
+        },
+        texture.size(),
+    );
+    queue.submit([encoder.finish()]);
+
+    buffer.slice(..).map_async(wgpu::MapMode::Read, |result| {
+        result.expect("Failed to map texture for reading");
+    });
+    device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+
+    buffer
+        .slice(..)
+        .get_mapped_range()
+        .chunks_exact(bytes_per_row as usize)
+        .flat_map(|row| row[..(u32::from(width) * bytes_per_pixel) as usize].to_vec())
+        .collect::<Vec<u8>>()
+}
+
+/// Render `scene` with `renderer` into a new render target of `format` and read back its pixels.
+pub(crate) fn render_scene(
+    renderer: &mut vello_hybrid::Renderer,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    scene: &Scene,
+    format: wgpu::TextureFormat,
+) -> Vec<u8> {
+    render_hybrid_pixels(
+        device,
+        queue,
+        scene.width(),
+        scene.height(),
+        format,
+        |encoder, size, view| {
+            renderer
+                .render(scene, device, queue, encoder, size, view)
+                .unwrap();
+        },
+    )
+}
+
+/// Render a frozen `vello_hybrid` scene after its source scene has been reset and reused, and
+/// check that it matches rendering the scene directly.
+#[test]
+fn frozen_scene_renders_after_source_reset() {
+    use vello_hybrid::RenderTargetConfig;
+
+    let (width, height) = (100_u16, 60_u16);
+    let draw = |scene: &mut Scene| {
+        scene.set_paint(REBECCA_PURPLE);
+        scene.fill_path(&circular_star(Point::new(50.0, 30.0), 5, 10.0, 28.0));
+        scene.push_clip_layer(&Rect::new(0.0, 0.0, 50.0, 60.0).to_path(0.1));
+        scene.set_paint(GREEN);
+        scene.fill_rect(&Rect::new(20.0, 20.0, 80.0, 40.0));
+        scene.pop_layer();
+    };
+
+    let (device, queue) = hybrid_device();
+    let mut renderer = vello_hybrid::Renderer::new(
+        &device,
+        &RenderTargetConfig {
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            width: width.into(),
+            height: height.into(),
+        },
+    );
+
+    let mut scene = Scene::new(width, height);
+    draw(&mut scene);
+    let expected = render_scene(
+        &mut renderer,
+        &device,
+        &queue,
+        &scene,
+        wgpu::TextureFormat::Rgba8Unorm,
+    );
+
+    scene.reset();
+    draw(&mut scene);
+    let frozen = scene.freeze();
+    // Reuse the source scene for a different frame before rendering the frozen one.
+    scene.set_paint(RED);
+    scene.fill_rect(&Rect::new(0.0, 0.0, 100.0, 60.0));
+
+    let actual = render_hybrid_pixels(
+        &device,
+        &queue,
+        width,
+        height,
+        wgpu::TextureFormat::Rgba8Unorm,
+        |encoder, size, view| {
+            renderer
+                .render_frozen(&frozen, &device, &queue, encoder, size, view)
+                .unwrap();
+        },
+    );
+
+    assert!(
+        expected.iter().any(|&byte| byte != 0),
+        "nothing was rendered"
+    );
+    assert_eq!(actual, expected);
+}
+
+/// Resize a `vello_hybrid` renderer up and down, and check that each render matches the
+/// output of a renderer created for that size.
+#[test]
+fn resized_renderer_matches_new_renderer() {
+    use vello_hybrid::RenderTargetConfig;
+
+    let (device, queue) = hybrid_device();
+    let new_renderer = |width: u16, height: u16| {
+        vello_hybrid::Renderer::new(
+            &device,
+            &RenderTargetConfig {
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                width: width.into(),
+                height: height.into(),
+            },
+        )
+    };
+    let scene = |width: u16, height: u16| {
+        let mut scene = Scene::new(width, height);
+        let (w, h) = (f64::from(width), f64::from(height));
+        scene.set_paint(REBECCA_PURPLE);
+        scene.fill_path(&circular_star(
+            Point::new(w / 2.0, h / 2.0),
+            5,
+            h / 6.0,
+            h / 2.0,
+        ));
+        scene.set_paint(GREEN);
+        scene.fill_rect(&Rect::new(w / 5.0, h / 3.0, w * 4.0 / 5.0, h * 2.0 / 3.0));
+        scene
+    };
+
+    let mut renderer = new_renderer(100, 60);
+    for (width, height) in [(100, 60), (300, 180), (40, 24), (100, 60)] {
+        renderer.resize(&queue, width.into(), height.into());
+        let scene = scene(width, height);
+        let actual = render_scene(
+            &mut renderer,
+            &device,
+            &queue,
+            &scene,
+            wgpu::TextureFormat::Rgba8Unorm,
+        );
+        let mut fresh_renderer = new_renderer(width, height);
+        let expected = render_scene(
+            &mut fresh_renderer,
+            &device,
+            &queue,
+            &scene,
+            wgpu::TextureFormat::Rgba8Unorm,
+        );
+
+        assert!(
+            expected.iter().any(|&byte| byte != 0),
+            "nothing was rendered at {width}x{height}"
+        );
+        assert_eq!(actual, expected, "output differs at {width}x{height}");
+    }
+}
+
+/// Render the same scene into `vello_hybrid` render targets with different channel orders and
+/// sRGB encoding, and check that they store the same colors.
+#[test]
+fn render_target_formats_store_same_colors() {
+    use vello_hybrid::RenderTargetConfig;
+
+    let (width, height) = (64_u16, 32_u16);
+    // Only use pixel-aligned, opaque geometry, since blending on sRGB render targets happens in
+    // linear space, which changes the result of partially covered pixels.
+    let mut scene = Scene::new(width, height);
+    scene.set_paint(REBECCA_PURPLE);
+    scene.fill_rect(&Rect::new(0.0, 0.0, 64.0, 32.0));
+    scene.set_paint(LIME);
+    scene.fill_rect(&Rect::new(8.0, 8.0, 40.0, 24.0));
+    scene.push_clip_layer(&Rect::new(24.0, 0.0, 64.0, 32.0).to_path(0.1));
+    scene.set_paint(MAROON);
+    scene.fill_rect(&Rect::new(32.0, 4.0, 56.0, 28.0));
+    scene.pop_layer();
+
+    let (device, queue) = hybrid_device();
+    let render = |format| {
+        let mut renderer = vello_hybrid::Renderer::new(
+            &device,
+            &RenderTargetConfig {
+                format,
+                width: width.into(),
+                height: height.into(),
+            },
+        );
+        render_scene(&mut renderer, &device, &queue, &scene, format)
+    };
+
+    let rgba = render(wgpu::TextureFormat::Rgba8Unorm);
+    let mut bgra = render(wgpu::TextureFormat::Bgra8Unorm);
+    for pixel in bgra.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    // The stored bytes of an sRGB texture are the sRGB-encoded colors, so they should match the
+    // other formats up to rounding.
+    let srgb = render(wgpu::TextureFormat::Rgba8UnormSrgb);
+
+    assert_eq!(
+        &rgba[..4],
+        &REBECCA_PURPLE.premultiply().to_rgba8().to_u8_array()
+    );
+    assert_eq!(bgra, rgba);
+    for (i, (&actual, &expected)) in srgb.iter().zip(&rgba).enumerate() {
+        assert!(
+            actual.abs_diff(expected) <= 1,
+            "sRGB byte {i} is {actual}, expected {expected}"
+        );
+    }
+}
+
+/// Push `vello_hybrid` layers with gray and yellow backdrops inside clip layers and check that
+/// each backdrop is composited, both where its layer has content and where it doesn't.
+#[test]
+fn backdrop_layer_is_composited() {
+    use vello_common::color::palette::css::{GRAY, YELLOW};
+    use vello_hybrid::RenderTargetConfig;
+
+    // Make the scene wider than a single wide tile, so that some wide tiles of the backdrop layer
+    // have no content of their own.
+    let (width, height) = (320_u16, 8_u16);
+    let mut scene = Scene::new(width, height);
+    scene.set_paint(BLUE);
+    scene.fill_rect(&Rect::new(0.0, 0.0, 320.0, 8.0));
+    scene.push_clip_layer(&Rect::new(0.0, 0.0, 300.0, 8.0).to_path(0.1));
+    scene.push_backdrop_layer(GRAY);
+    scene.set_paint(LIME);
+    scene.fill_rect(&Rect::new(8.0, 0.0, 16.0, 8.0));
+    scene.pop_layer();
+    scene.pop_layer();
+    // The slots of this layer are cleared in the same round as those of the gray one.
+    scene.push_clip_layer(&Rect::new(300.0, 0.0, 310.0, 8.0).to_path(0.1));
+    scene.push_backdrop_layer(YELLOW);
+    scene.pop_layer();
+    scene.pop_layer();
+
+    let (device, queue) = hybrid_device();
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let mut renderer = vello_hybrid::Renderer::new(
+        &device,
+        &RenderTargetConfig {
+            format,
+            width: width.into(),
+            height: height.into(),
+        },
+    );
+    let pixels = render_scene(&mut renderer, &device, &queue, &scene, format);
+
+    let pixel = |x: usize| {
+        let idx = x * 4;
+        <[u8; 4]>::try_from(&pixels[idx..idx + 4]).unwrap()
+    };
+    let gray = GRAY.to_rgba8().to_u8_array();
+    assert_eq!(pixel(0), gray);
+    assert_eq!(pixel(12), LIME.to_rgba8().to_u8_array());
+    assert_eq!(pixel(100), gray);
+    assert_eq!(pixel(280), gray);
+    assert_eq!(pixel(305), YELLOW.to_rgba8().to_u8_array());
+    assert_eq!(pixel(315), BLUE.to_rgba8().to_u8_array());
+}
+
+/// Check that every pass of a render with a blend layer is timed when the device supports
+/// timestamp queries.
+#[test]
+fn render_passes_are_timed() {
+    use vello_common::peniko::{BlendMode, Compose, Mix};
+    use vello_hybrid::{PassKind, RenderTargetConfig};
+
+    let Some((device, queue)) = hybrid_device_with_features(wgpu::Features::TIMESTAMP_QUERY) else {
+        return;
+    };
+
+    // The nested blend layer needs a temporary slot, so some slots are cleared while others are
+    // still in use.
+    let (width, height) = (512_u16, 64_u16);
+    let mut scene = Scene::new(width, height);
+    scene.set_paint(BLUE);
+    scene.fill_rect(&Rect::new(0.0, 0.0, 512.0, 64.0));
+    scene.push_clip_layer(&Circle::new((256.0, 32.0), 200.0).to_path(0.1));
+    scene.push_clip_layer(&Circle::new((240.0, 32.0), 100.0).to_path(0.1));
+    scene.push_blend_layer(BlendMode::new(Mix::Multiply, Compose::SrcOver));
+    scene.set_paint(LIME);
+    scene.fill_path(&circular_star(Point::new(256.0, 32.0), 13, 10.0, 60.0));
+    scene.pop_layer();
+    scene.pop_layer();
+    scene.pop_layer();
+
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let mut renderer = vello_hybrid::Renderer::new(
+        &device,
+        &RenderTargetConfig {
+            format,
+            width: width.into(),
+            height: height.into(),
+        },
+    );
+    render_scene(&mut renderer, &device, &queue, &scene, format);
+
+    let timings = renderer.take_timings(&device, &queue);
+    for kind in [PassKind::ClearSlots, PassKind::Clip, PassKind::Target] {
+        assert!(
+            timings.iter().any(|timing| timing.kind == kind),
+            "no {kind:?} pass was timed"
+        );
+    }
+    for timing in &timings {
+        assert!(!timing.duration.is_zero(), "{timing:?} took no time");
+    }
+    assert!(renderer.take_timings(&device, &queue).is_empty());
+}
+
+/// Check that renderers sharing a pipeline cache fill it and render correctly from it.
+#[test]
+fn renderers_share_pipeline_cache() {
+    use vello_hybrid::{RenderSettings, RenderTargetConfig};
+
+    let Some((device, queue)) = hybrid_device_with_features(wgpu::Features::PIPELINE_CACHE) else {
+        return;
+    };
+    // SAFETY: The cache starts out empty, so there is no data that could be invalid.
+    let pipeline_cache = unsafe {
+        device.create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+            label: Some("Renderer Pipeline Cache"),
+            data: None,
+            fallback: true,
+        })
+    };
+
+    let (width, height) = (16_u16, 16_u16);
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let config = RenderTargetConfig {
+        format,
+        width: width.into(),
+        height: height.into(),
+    };
+    let new_renderer = || {
+        vello_hybrid::Renderer::new_with_pipeline_cache(
+            &device,
+            &config,
+            RenderSettings::default(),
+            Some(pipeline_cache.clone()),
+        )
+    };
+    let _first = new_renderer();
+    assert!(
+        pipeline_cache
+            .get_data()
+            .is_some_and(|data| !data.is_empty()),
+        "the pipelines of the first renderer were not cached"
+    );
+
+    let mut second = new_renderer();
+    let mut scene = Scene::new(width, height);
+    scene.set_paint(LIME);
+    scene.fill_rect(&Rect::new(0.0, 0.0, 16.0, 16.0));
+    let pixels = render_scene(&mut second, &device, &queue, &scene, format);
+    assert_eq!(pixels[..4], LIME.to_rgba8().to_u8_array());
+}
+
+/// Check that a renderer whose pipelines are compiled lazily renders correctly the first time.
+#[test]
+fn lazy_pipelines_are_compiled_on_first_render() {
+    use vello_hybrid::{RenderSettings, RenderTargetConfig};
+
+    let (width, height) = (16_u16, 16_u16);
+    let mut scene = Scene::new(width, height);
+    scene.set_paint(BLUE);
+    scene.fill_rect(&Rect::new(0.0, 0.0, 16.0, 16.0));
+    scene.push_clip_layer(&Rect::new(8.0, 0.0, 16.0, 16.0).to_path(0.1));
+    scene.set_paint(LIME);
+    scene.fill_rect(&Rect::new(0.0, 0.0, 16.0, 16.0));
+    scene.pop_layer();
+
+    let (device, queue) = hybrid_device();
+    let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+    let mut renderer = vello_hybrid::Renderer::new_with(
+        &device,
+        &RenderTargetConfig {
+            format,
+            width: width.into(),
+            height: height.into(),
+        },
+        RenderSettings {
+            lazy_pipelines: true,
+            ..RenderSettings::default()
+        },
+    );
+    let pixels = render_scene(&mut renderer, &device, &queue, &scene, format);
+
+    assert_eq!(pixels[..4], BLUE.to_rgba8().to_u8_array());
+    assert_eq!(pixels[12 * 4..13 * 4], LIME.to_rgba8().to_u8_array());
+}
+
+/// Convert the bits of an IEEE 754 half-precision float to an `f32`.
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 == 0 { 1.0 } else { -1.0 };
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let mantissa = f32::from(bits & 0x3ff);
+    sign * match exponent {
+        0 => mantissa * 2_f32.powi(-24),
+        0x1f => f32::INFINITY,
+        _ => (1.0 + mantissa / 1024.0) * 2_f32.powi(exponent - 15),
+    }
+}
+
+/// Render a scene with gradients, anti-aliasing, transparency and clipping into a float and an
+/// sRGB `vello_hybrid` render target, and check that the float target stores the linear colors
+/// which the sRGB target encodes.
+#[test]
+fn float_render_target_stores_linear_colors() {
+    use vello_common::color::ColorSpaceTag;
+    use vello_common::peniko::Gradient;
+    use vello_hybrid::RenderTargetConfig;
+
+    fn linear_to_srgb(value: f32) -> f32 {
+        if value <= 0.003_130_8 {
+            value * 12.92
+        } else {
+            1.055 * value.powf(1.0 / 2.4) - 0.055
+        }
+    }
+
+    let (width, height) = (128_u16, 64_u16);
+    let mut scene = Scene::new(width, height);
+    scene.set_paint(
+        Gradient::new_linear((0.0, 0.0), (128.0, 0.0))
+            .with_interpolation_cs(ColorSpaceTag::Srgb)
+            .with_stops([BLACK, BEIGE]),
+    );
+    scene.fill_rect(&Rect::new(0.0, 0.0, 128.0, 64.0));
+    scene.set_paint(REBECCA_PURPLE.with_alpha(0.6));
+    scene.fill_path(&Circle::new((40.0, 32.0), 27.5).to_path(0.1));
+    scene.push_clip_layer(&circular_star(Point::new(90.0, 32.0), 5, 10.0, 30.0));
+    scene.set_paint(LIME.with_alpha(0.8));
+    scene.fill_rect(&Rect::new(60.0, 0.0, 128.0, 64.0));
+    scene.pop_layer();
+
+    let (device, queue) = hybrid_device();
+    let render = |format| {
+        let mut renderer = vello_hybrid::Renderer::new(
+            &device,
+            &RenderTargetConfig {
+                format,
+                width: width.into(),
+                height: height.into(),
+            },
+        );
+        render_scene(&mut renderer, &device, &queue, &scene, format)
+    };
+
+    let srgb = render(wgpu::TextureFormat::Rgba8UnormSrgb);
+    let float = render(wgpu::TextureFormat::Rgba16Float);
+    assert_eq!(float.len(), srgb.len() * 2);
+
+    for (i, (bytes, &expected)) in float.chunks_exact(2).zip(&srgb).enumerate() {
+        let value = f16_to_f32(u16::from_le_bytes([bytes[0], bytes[1]]));
+        // Alpha isn't sRGB-encoded.
+        let encoded = if i % 4 == 3 {
+            value
+        } else {
+            linear_to_srgb(value)
+        };
+        let actual = encoded * 255.0;
+        // The sRGB render target rounds the colors it blends onto to 8 bits, so overlapping draws
+        // can accumulate slightly more than one unit of difference. The float target also
+        // interpolates between the entries of the gradient ramp, which are about one unit apart.
+        assert!(
+            (actual - f32::from(expected)).abs() <= 3.0,
+            "float channel {i} encodes to {actual}, expected {expected}"
+        );
+    }
+}
+
+/// Render a subtle gradient into a float and an 8-bit `vello_hybrid` render target, and check that
+/// the float target has far more distinct levels, i.e. doesn't band.
+#[test]
+fn float_render_target_reduces_gradient_banding() {
+    use vello_common::color::ColorSpaceTag;
+    use vello_common::peniko::{Color, Gradient};
+    use vello_hybrid::RenderTargetConfig;
+
+    let (width, height) = (1024_u16, 4_u16);
+    let mut scene = Scene::new(width, height);
+    scene.set_paint(
+        Gradient::new_linear((0.0, 0.0), (1024.0, 0.0))
+            .with_interpolation_cs(ColorSpaceTag::Srgb)
+            .with_stops([Color::from_rgb8(64, 64, 64), Color::from_rgb8(80, 80, 80)]),
+    );
+    scene.fill_rect(&Rect::new(0.0, 0.0, 1024.0, 4.0));
+
+    let (device, queue) = hybrid_device();
+    let render = |format| {
+        let mut renderer = vello_hybrid::Renderer::new(
+            &device,
+            &RenderTargetConfig {
+                format,
+                width: width.into(),
+                height: height.into(),
+            },
+        );
+        render_scene(&mut renderer, &device, &queue, &scene, format)
+    };
+    // The number of distinct values of the red channel in the first row, which increases
+    // monotonically.
+    let levels = |pixels: &[u8], bytes_per_channel: usize| {
+        let pixel_size = 4 * bytes_per_channel;
+        let mut reds = pixels[..usize::from(width) * pixel_size]
+            .chunks_exact(pixel_size)
+            .map(|pixel| &pixel[..bytes_per_channel])
+            .collect::<Vec<_>>();
+        reds.dedup();
+        reds.len()
+    };
+
+    let unorm_levels = levels(&render(wgpu::TextureFormat::Rgba8Unorm), 1);
+    let float_levels = levels(&render(wgpu::TextureFormat::Rgba16Float), 2);
+    assert!(
+        unorm_levels <= 17,
+        "the 8-bit target has {unorm_levels} levels"
+    );
+    assert!(
+        float_levels > 10 * unorm_levels,
+        "the float target has {float_levels} levels, the 8-bit target {unorm_levels}"
+    );
+}
+
+/// Fill a float `vello_hybrid` render target with a color that lies between two 8-bit values, and
+/// check that it isn't rounded to 8 bits.
+#[test]
+fn float_render_target_keeps_solid_color_precision() {
+    use vello_common::peniko::Color;
+    use vello_hybrid::RenderTargetConfig;
+
+    let (width, height) = (64_u16, 8_u16);
+    let mut scene = Scene::new(width, height);
+    scene.set_paint(Color::new([0.3, 0.3, 0.3, 1.0]));
+    scene.fill_rect(&Rect::new(0.0, 0.0, 64.0, 8.0));
+    scene.set_paint(Color::new([0.3, 0.3, 0.3, 0.5]));
+    scene.fill_path(&Circle::new((32.0, 4.0), 3.5).to_path(0.1));
+
+    let (device, queue) = hybrid_device();
+    let format = wgpu::TextureFormat::Rgba16Float;
+    let mut renderer = vello_hybrid::Renderer::new(
+        &device,
+        &RenderTargetConfig {
+            format,
+            width: width.into(),
+            height: height.into(),
+        },
+    );
+    let pixels = render_scene(&mut renderer, &device, &queue, &scene, format);
+
+    // 0.3 in linear space, while the closest 8-bit value 77 / 255 is about 0.0713.
+    let expected = ((0.3_f32 + 0.055) / 1.055).powf(2.4);
+    for (i, bytes) in pixels.chunks_exact(2).enumerate() {
+        let value = f16_to_f32(u16::from_le_bytes([bytes[0], bytes[1]]));
+        let expected = if i % 4 == 3 { 1.0 } else { expected };
+        assert!(
+            (value - expected).abs() < 2e-4,
+            "float channel {i} is {value}, expected {expected}"
+        );
+    }
+}
+
+/// Blend half-transparent white over black with `vello_hybrid`, both directly into the render
+/// target and within nested clip layers, and check that linear blending lightens the result.
+#[test]
+fn linear_blending_blends_in_linear_space() {
+    use vello_common::color::palette::css::WHITE;
+    use vello_hybrid::{RenderSettings, RenderTargetConfig};
+
+    let (width, height) = (64_u16, 16_u16);
+    let mut scene = Scene::new(width, height);
+    let draw = |scene: &mut Scene| {
+        scene.set_paint(BLACK);
+        scene.fill_rect(&Rect::new(0.0, 0.0, 64.0, 16.0));
+        scene.set_paint(WHITE.with_alpha(0.5));
+        scene.fill_rect(&Rect::new(0.0, 0.0, 64.0, 16.0));
+    };
+    draw(&mut scene);
+    // The right half is drawn into slots, and composited through another slot.
+    let clip = Rect::new(32.0, 0.0, 64.0, 16.0).to_path(0.1);
+    scene.push_clip_layer(&clip);
+    scene.push_clip_layer(&clip);
+    draw(&mut scene);
+    scene.pop_layer();
+    scene.pop_layer();
+
+    let (device, queue) = hybrid_device();
+    let render = |format, linear_blending| {
+        let mut renderer = vello_hybrid::Renderer::new_with(
+            &device,
+            &RenderTargetConfig {
+                format,
+                width: width.into(),
+                height: height.into(),
+            },
+            RenderSettings {
+                linear_blending,
+                ..RenderSettings::default()
+            },
+        );
+        render_scene(&mut renderer, &device, &queue, &scene, format)
+    };
+
+    // 50% gray, encoded in sRGB.
+    let srgb_blended = render(wgpu::TextureFormat::Rgba8Unorm, false);
+    // 50% gray in linear space, which is lighter once encoded in sRGB.
+    let linear_blended = render(wgpu::TextureFormat::Rgba8UnormSrgb, true);
+
+    for (pixels, expected) in [(&srgb_blended, 128), (&linear_blended, 188)] {
+        for (i, pixel) in pixels.chunks_exact(4).enumerate() {
+            for &channel in &pixel[..3] {
+                assert!(
+                    channel.abs_diff(expected) <= 1,
+                    "pixel {i} is {pixel:?}, expected a gray of {expected}"
+                );
+            }
+            assert_eq!(pixel[3], 255);
+        }
+    }
+}
+
+/// Only the pixels covered by the scene are written to the render target, the rest of it keeps
+/// its previous contents, both inside and outside the scissor rect of the final pass.
+#[test]
+fn render_only_touches_drawn_region() {
+    use vello_hybrid::RenderTargetConfig;
+
+    let (width, height) = (1024_u16, 512_u16);
+    let circle = Circle::new((600.5, 300.25), 20.0);
+    let bounds = circle.bounding_box();
+    let mut scene = Scene::new(width, height);
+    scene.set_paint(BLUE);
+    scene.fill_path(&circle.to_path(0.1));
+
+    let (device, queue) = hybrid_device();
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let mut renderer = vello_hybrid::Renderer::new(
+        &device,
+        &RenderTargetConfig {
+            format,
+            width: width.into(),
+            height: height.into(),
+        },
+    );
+    let pixels = render_hybrid_pixels(
+        &device,
+        &queue,
+        width,
+        height,
+        format,
+        |encoder, size, view| {
+            // Fill the target with opaque red before rendering the scene over it.
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::RED),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+            renderer
+                .render(&scene, &device, &queue, encoder, size, view)
+                .unwrap();
+        },
+    );
+
+    let red = RED.to_rgba8().to_u8_array();
+    let blue = BLUE.to_rgba8().to_u8_array();
+    for y in 0..height {
+        for x in 0..width {
+            let idx = (usize::from(y) * usize::from(width) + usize::from(x)) * 4;
+            let pixel = &pixels[idx..idx + 4];
+            let (px, py) = (f64::from(x), f64::from(y));
+            let center = Point::new(px + 0.5, py + 0.5);
+            if Rect::new(px, py, px + 1.0, py + 1.0)
+                .intersect(bounds)
+                .is_zero_area()
+            {
+                assert_eq!(
+                    pixel, red,
+                    "pixel ({x}, {y}) outside the circle was changed"
+                );
+            } else if (center - circle.center).hypot() < circle.radius - 1.0 {
+                assert_eq!(
+                    pixel, blue,
+                    "pixel ({x}, {y}) inside the circle wasn't drawn"
+                );
+            }
+        }
+    }
+}
+
+/// Appending independently built scenes renders the same as drawing their contents directly.
+#[test]
+fn appended_scenes_match_direct_build() {
+    use vello_common::color::ColorSpaceTag;
+    use vello_common::peniko::Gradient;
+    use vello_hybrid::RenderTargetConfig;
+
+    fn draw_panel(scene: &mut Scene) {
+        scene.set_paint(
+            Gradient::new_linear((0.0, 0.0), (300.0, 0.0))
+                .with_interpolation_cs(ColorSpaceTag::Srgb)
+                .with_stops([BLACK, BEIGE]),
+        );
+        scene.fill_rect(&Rect::new(10.0, 10.0, 290.0, 110.0));
+        scene.push_clip_layer(&circular_star(Point::new(150.0, 60.0), 5, 20.0, 50.0));
+        scene.set_paint(REBECCA_PURPLE.with_alpha(0.6));
+        scene.fill_path(&Circle::new((130.0, 60.0), 45.5).to_path(0.1));
+        scene.pop_layer();
+        scene.push_opacity_layer(0.5);
+        scene.set_paint(LIME);
+        scene.fill_path(&Circle::new((250.0, 80.0), 25.25).to_path(0.1));
+        scene.pop_layer();
+    }
+
+    let (width, height) = (640_u16, 160_u16);
+    let offsets = [
+        Affine::translate((301.0, 12.0)),
+        Affine::translate((-30.0, 40.0)),
+    ];
+
+    let mut expected = Scene::new(width, height);
+    expected.set_paint(DARK_BLUE);
+    expected.fill_rect(&Rect::new(0.0, 0.0, 640.0, 160.0));
+    for offset in offsets {
+        expected.set_transform(offset);
+        draw_panel(&mut expected);
+    }
+
+    let mut panel = Scene::new(300, 120);
+    draw_panel(&mut panel);
+    let mut scene = Scene::new(width, height);
+    scene.set_paint(DARK_BLUE);
+    scene.fill_rect(&Rect::new(0.0, 0.0, 640.0, 160.0));
+    for offset in offsets {
+        scene.append(&panel, offset);
+    }
+
+    let (device, queue) = hybrid_device();
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let mut renderer = vello_hybrid::Renderer::new(
+        &device,
+        &RenderTargetConfig {
+            format,
+            width: width.into(),
+            height: height.into(),
+        },
+    );
+    let mut render = |scene: &Scene| render_scene(&mut renderer, &device, &queue, scene, format);
+
+    assert_eq!(render(&scene), render(&expected));
+}
+
+/// Render a scene with clip, blend and opacity layers that span several wide tiles with
+/// different wide tile widths, and check that the output doesn't depend on it.
+#[test]
+fn wide_tile_width_does_not_change_output() {
+    use vello_common::peniko::{BlendMode, Compose, Mix};
+    use vello_hybrid::{RenderError, RenderSettings, RenderTargetConfig};
+
+    let (width, height) = (600_u16, 80_u16);
+    let draw = |scene: &mut Scene| {
+        scene.set_paint(DARK_BLUE);
+        scene.fill_rect(&Rect::new(0.0, 0.0, 600.0, 80.0));
+        scene.set_paint(LIME);
+        scene.fill_path(&Circle::new((300.3, 40.7), 36.0).to_path(0.1));
+        scene.set_paint(RED);
+        scene.set_stroke(Stroke::new(3.0));
+        scene.stroke_path(&Line::new((10.0, 70.0), (590.0, 10.0)).to_path(0.1));
+
+        scene.push_blend_layer(BlendMode::new(Mix::Multiply, Compose::SrcOver));
+        scene.set_paint(BEIGE);
+        scene.fill_rect(&Rect::new(60.25, 10.5, 420.75, 70.5));
+        scene.pop_layer();
+
+        scene.push_clip_layer(&Circle::new((200.5, 40.0), 120.0).to_path(0.1));
+        scene.push_opacity_layer(0.5);
+        scene.set_paint(MAROON);
+        scene.fill_rect(&Rect::new(250.0, 0.0, 500.0, 40.0));
+        scene.pop_layer();
+        scene.pop_layer();
+    };
+
+    let (device, queue) = hybrid_device();
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let target_config = RenderTargetConfig {
+        format,
+        width: width.into(),
+        height: height.into(),
+    };
+    let render = |wide_tile_width| {
+        let settings = RenderSettings {
+            wide_tile_width,
+            ..RenderSettings::default()
+        };
+        let mut scene = Scene::new_with(width, height, settings);
+        draw(&mut scene);
+        let mut renderer = vello_hybrid::Renderer::new_with(&device, &target_config, settings);
+        render_scene(&mut renderer, &device, &queue, &scene, format)
+    };
+
+    // Wide tiles that are fully inside a clip path skip the clip buffer, and thus one
+    // quantization to 8 bits. Which tiles that are depends on their width, so colors composited
+    // through clip layers may differ by one.
+    let expected = render(WideTile::WIDTH);
+    for wide_tile_width in [64, 128, 192, 512] {
+        let pixels = render(wide_tile_width);
+        assert!(
+            pixels
+                .iter()
+                .zip(&expected)
+                .all(|(actual, expected)| actual.abs_diff(*expected) <= 1),
+            "rendering with {wide_tile_width} pixel wide tiles changes the output"
+        );
+    }
+
+    // A scene has to be rendered by a renderer with the same wide tile width.
+    let mut scene = Scene::new_with(
+        width,
+        height,
+        RenderSettings {
+            wide_tile_width: 128,
+            ..RenderSettings::default()
+        },
+    );
+    draw(&mut scene);
+    let mut renderer = vello_hybrid::Renderer::new(&device, &target_config);
+    let mut result = Ok(());
+    render_hybrid_pixels(
+        &device,
+        &queue,
+        width,
+        height,
+        format,
+        |encoder, size, view| {
+            result = renderer.render(&scene, &device, &queue, encoder, size, view);
+        },
+    );
+    assert!(matches!(
+        result,
+        Err(RenderError::WideTileWidthMismatch {
+            scene: 128,
+            renderer: 256,
+        })
+    ));
+}
+
+/// Render text with a glyph atlas over several frames, and check that its glyphs are uploaded,
+/// drawn from the atlas with the same result as paths, and evicted once they are unused.
+#[test]
+fn glyph_atlas_uploads_and_evicts_glyphs() {
+    use vello_common::color::palette::css::WHITE;
+    use vello_hybrid::{GlyphAtlas, RenderTargetConfig};
+
+    let (width, height) = (400_u16, 100_u16);
+    let (font, glyphs) = layout_glyphs_roboto(
+        "The quick brown fox jumps\nover the lazy dog, again and again.",
+        18.0,
+    );
+    let draw = |scene: &mut Scene, scale: f64| {
+        scene.set_paint(WHITE);
+        scene.fill_rect(&Rect::new(0.0, 0.0, f64::from(width), f64::from(height)));
+        scene.set_transform(Affine::translate((10.25, 30.0)) * Affine::scale(scale));
+        scene.set_paint(DARK_BLUE);
+        scene
+            .glyph_run(&font)
+            .font_size(18.0)
+            .fill_glyphs(glyphs.iter().copied());
+        scene.reset_transform();
+    };
+
+    let (device, queue) = hybrid_device();
+    let format = wgpu::TextureFormat::Rgba8Unorm;
+    let target_config = RenderTargetConfig {
+        format,
+        width: width.into(),
+        height: height.into(),
+    };
+    let mut renderer = vello_hybrid::Renderer::new(&device, &target_config);
+    let mut render = |scene: &mut Scene| {
+        render_hybrid_pixels(
+            &device,
+            &queue,
+            width,
+            height,
+            format,
+            |encoder, size, view| {
+                if let Some(glyph_atlas) = scene.glyph_atlas_mut() {
+                    renderer.upload_glyphs(&device, &queue, encoder, glyph_atlas);
+                }
+                renderer
+                    .render(scene, &device, &queue, encoder, size, view)
+                    .unwrap();
+            },
+        )
+    };
+
+    let mut scene = Scene::new(width, height);
+    draw(&mut scene, 1.0);
+    let expected = render(&mut scene);
+    let path_tiles = scene.tile_stats().tiles;
+
+    // The first frame rasterizes the glyphs for the atlas, but still draws them as paths.
+    scene.reset();
+    scene.set_glyph_atlas(Some(GlyphAtlas::new()));
+    draw(&mut scene, 1.0);
+    let pending = scene.glyph_atlas().unwrap().stats().pending;
+    assert!(pending > 0);
+    assert_eq!(render(&mut scene), expected);
+    let stats = scene.glyph_atlas().unwrap().stats();
+    assert_eq!((stats.uploaded, stats.pending), (pending, 0));
+
+    // The following frames draw the uploaded glyphs as images, without rasterizing them.
+    scene.reset();
+    draw(&mut scene, 1.0);
+    assert!(scene.tile_stats().tiles < path_tiles / 4);
+    assert_eq!(scene.glyph_atlas().unwrap().stats(), stats);
+    render(&mut scene);
+
+    // Scaled glyphs are rasterized again, and the unused ones are evicted eventually.
+    scene.reset();
+    draw(&mut scene, 1.5);
+    render(&mut scene);
+    let both_stats = scene.glyph_atlas().unwrap().stats();
+    for _ in 0..70 {
+        scene.reset();
+        draw(&mut scene, 1.5);
+        render(&mut scene);
+    }
+    let scaled_stats = scene.glyph_atlas().unwrap().stats();
+    assert_eq!(scaled_stats.uploaded, both_stats.uploaded - stats.uploaded);
+    assert_eq!(scaled_stats.pixels, both_stats.pixels - stats.pixels);
+}
+
+/// The CPU fallback renders the same as the `wgpu` renderer, up to rounding.
+#[test]
+fn cpu_fallback_matches_gpu() {
+    use vello_common::color::ColorSpaceTag;
+    use vello_common::peniko::Gradient;
+    use vello_common::recording::{Recordable, Recording};
+    use vello_hybrid::fallback::FallbackRenderer;
+
+    let instance = wgpu::Instance::default();
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::default(),
+        force_fallback_adapter: false,
+        compatible_surface: None,
+    }))
+    .expect("Failed to find an appropriate adapter");
+    let mut gpu = pollster::block_on(FallbackRenderer::new(Some(&adapter)));
+    let mut cpu = FallbackRenderer::new_cpu();
+    assert!(!gpu.is_cpu());
+    assert!(cpu.is_cpu());
+
+    let mut render = |renderer: &mut FallbackRenderer| {
+        let mut scene = renderer.create_scene(300, 120);
+        let mut recording = Recording::new();
+        scene.record(&mut recording, |ctx| {
+            ctx.set_paint(DARK_BLUE);
+            ctx.fill_path(&circular_star(Point::new(60.0, 60.0), 5, 20.0, 50.0));
+            ctx.set_paint(
+                Gradient::new_linear((120.0, 0.0), (280.0, 0.0))
+                    .with_interpolation_cs(ColorSpaceTag::Srgb)
+                    .with_stops([BLACK, BEIGE]),
+            );
+            ctx.set_stroke(Stroke::new(6.5));
+            ctx.stroke_path(&Circle::new((200.0, 60.0), 40.25).to_path(0.1));
+            ctx.push_clip_layer(&Rect::new(150.0, 30.0, 250.0, 70.0).to_path(0.1));
+            ctx.set_paint(REBECCA_PURPLE.with_alpha(0.6));
+            ctx.fill_path(&Circle::new((220.0, 50.0), 35.5).to_path(0.1));
+            ctx.pop_layer();
+        });
+        scene.prepare_recording(&mut recording);
+        scene.execute_recording(&recording);
+        renderer.render_to_pixmap(&mut scene).unwrap()
+    };
+
+    let expected = render(&mut gpu);
+    let actual = render(&mut cpu);
+    for (i, (a, e)) in actual
+        .data_as_u8_slice()
+        .iter()
+        .zip(expected.data_as_u8_slice())
+        .enumerate()
+    {
+        assert!(
+            a.abs_diff(*e) <= 1,
+            "channel {i} differs: {a} on the CPU, {e} on the GPU"
+        );
+    }
+}
@@ -562,3 +562,56 @@ fn image_with_multiple_clip_layers(ctx: &mut impl Renderer) {
     ctx.pop_layer();
     ctx.pop_layer();
 }
+
+/// Load images with different channel layouts through the `image` crate, draw them as an image
+/// paint and convert the result back, which should reproduce the original image.
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn image_crate_round_trip() {
+    use vello_cpu::{Level, Pixmap, RenderContext, RenderMode, RenderSettings};
+
+    let settings = RenderSettings {
+        level: Level::try_detect().unwrap_or(Level::fallback()),
+        num_threads: 0,
+        render_mode: RenderMode::OptimizeQuality,
+    };
+
+    for name in [
+        "rgb_image_10x10",
+        "rgba_image_10x10",
+        "luma_image_10x10",
+        "lumaa_image_10x10",
+    ] {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join(format!("tests/assets/{name}.png"));
+        let original = ::image::open(path).unwrap();
+        let pixmap = Pixmap::from_image(&original).unwrap();
+        let (width, height) = (pixmap.width(), pixmap.height());
+
+        let mut ctx = RenderContext::new_with(width, height, settings);
+        ctx.set_paint(Image {
+            image: ImageSource::Pixmap(Arc::new(pixmap)),
+            sampler: ImageSampler::default(),
+        });
+        ctx.fill_rect(&Rect::new(0.0, 0.0, f64::from(width), f64::from(height)));
+        ctx.flush();
+        let mut rendered = Pixmap::new(width, height);
+        ctx.render_to_pixmap(&mut rendered);
+
+        let expected = original.to_rgba8();
+        let actual = rendered.to_image();
+        assert_eq!(actual.dimensions(), expected.dimensions());
+        for (a, e) in actual.pixels().zip(expected.pixels()) {
+            assert_eq!(a[3], e[3], "alpha differs in {name}");
+            if e[3] == 0 {
+                continue;
+            }
+            // Premultiplying quantizes the color channels, more so the lower the alpha.
+            let tolerance = 1 + 255 / i16::from(e[3]);
+            for c in 0..3 {
+                let diff = (i16::from(a[c]) - i16::from(e[c])).abs();
+                assert!(diff <= tolerance, "{name}: {a:?} != {e:?}");
+            }
+        }
+    }
+}
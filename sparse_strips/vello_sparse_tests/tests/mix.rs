@@ -6,7 +6,7 @@ use crate::renderer::Renderer;
 use smallvec::smallvec;
 use vello_common::color::palette::css::{BLUE, LIME, MAGENTA, ORANGE, RED, YELLOW};
 use vello_common::color::{AlphaColor, DynamicColor, Srgb};
-use vello_common::kurbo::{Affine, Point, Rect};
+use vello_common::kurbo::{Affine, Circle, Point, Rect, Shape};
 use vello_common::paint::{Image, ImageSource};
 use vello_common::peniko::{
     BlendMode, Color, ColorStop, ColorStops, Compose, Extend, Gradient, ImageQuality, Mix,
@@ -198,6 +198,19 @@ fn mix_saturation_with_solid(ctx: &mut impl Renderer) {
     mix_solid(ctx, Mix::Saturation);
 }
 
+// An opaque fill covering whole wide tiles must not replace the content below it unless it is
+// drawn with source-over. The whole image should be (122, 85, 0).
+#[vello_test(width = 256, height = 8, cpu_u8_tolerance = 1, hybrid_tolerance = 1)]
+fn mix_multiply_opaque_fill_over_content(ctx: &mut impl Renderer) {
+    let rect = Rect::new(0.0, 0.0, 256.0, 8.0);
+    ctx.set_paint(AlphaColor::<Srgb>::from_rgba8(122, 85, 73, 255));
+    ctx.fill_rect(&rect);
+
+    ctx.set_blend_mode(BlendMode::new(Mix::Multiply, Compose::SrcOver));
+    ctx.set_paint(AlphaColor::<Srgb>::from_rgba8(255, 255, 0, 255));
+    ctx.fill_rect(&rect);
+}
+
 // Currently `vello_hybrid` does not support gradients. This test ensure mix is tested in
 // `vello_hybrid` by using solid colors.
 #[vello_test(width = 80, height = 160)]
@@ -297,3 +310,77 @@ fn mix_non_isolated_soft_light(ctx: &mut impl Renderer) {
 fn mix_non_isolated_color_dodge(ctx: &mut impl Renderer) {
     mix_non_isolated(ctx, Mix::ColorDodge);
 }
+
+/// Fill the background with a gradient that the groups of the isolation tests are drawn on.
+fn isolation_backdrop(ctx: &mut impl Renderer) {
+    let gradient = Gradient {
+        kind: LinearGradientPosition {
+            start: Point::new(0.0, 0.0),
+            end: Point::new(100.0, 0.0),
+        }
+        .into(),
+        stops: ColorStops(smallvec![
+            ColorStop {
+                offset: 0.0,
+                color: DynamicColor::from_alpha_color(YELLOW),
+            },
+            ColorStop {
+                offset: 1.0,
+                color: DynamicColor::from_alpha_color(BLUE),
+            },
+        ]),
+        ..Default::default()
+    };
+    ctx.set_paint(gradient);
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+}
+
+fn fill_circle(ctx: &mut impl Renderer, center: (f64, f64), color: AlphaColor<Srgb>) {
+    ctx.set_paint(color);
+    ctx.fill_path(&Circle::new(center, 25.0).to_path(0.1));
+}
+
+fn multiply_group(ctx: &mut impl Renderer, isolated: bool) {
+    isolation_backdrop(ctx);
+
+    let multiply = BlendMode::new(Mix::Multiply, Compose::SrcOver);
+    if isolated {
+        ctx.push_isolated_layer(multiply);
+    } else {
+        ctx.push_blend_layer(multiply);
+    }
+    fill_circle(ctx, (35.0, 50.0), ORANGE);
+    fill_circle(ctx, (65.0, 50.0), LIME);
+    ctx.pop_layer();
+}
+
+// The whole group is multiplied with the gradient.
+#[vello_test]
+fn mix_multiply_group(ctx: &mut impl Renderer) {
+    multiply_group(ctx, false);
+}
+
+// The circles are only multiplied with each other where they overlap, and the group is drawn
+// over the gradient as is.
+#[vello_test]
+fn mix_isolated_multiply_group(ctx: &mut impl Renderer) {
+    multiply_group(ctx, true);
+}
+
+#[vello_test]
+fn mix_nested_isolated_groups(ctx: &mut impl Renderer) {
+    isolation_backdrop(ctx);
+
+    // The screen group is isolated from the gradient, but the multiply group that it is drawn
+    // into as a whole isn't.
+    ctx.push_isolated_layer(BlendMode::new(Mix::Screen, Compose::SrcOver));
+    fill_circle(ctx, (50.0, 35.0), RED);
+    ctx.push_blend_layer(BlendMode::new(Mix::Multiply, Compose::SrcOver));
+    fill_circle(ctx, (35.0, 65.0), ORANGE);
+    fill_circle(ctx, (65.0, 65.0), MAGENTA);
+    ctx.pop_layer();
+    ctx.pop_layer();
+
+    // The blend mode that was set before the isolated group applies again.
+    fill_circle(ctx, (80.0, 20.0), LIME.with_alpha(0.5));
+}
@@ -13,6 +13,8 @@
 //!   - If it cannot be classified cleanly, for now you can just put it into `basic.rs` which
 //!     currently holds a bunch of different kinds of tests.
 //!   - Tests for bugs should go into `issues.rs`.
+//!   - Tests that need the `wgpu` renderer of `vello_hybrid` directly, e.g. to check render
+//!     targets or renderer settings, should go into `hybrid.rs`.
 //!   - For test naming, try to put the "topic" of the test at the start of the name instead of
 //!     the end. For example, if your test case is about blend modes, `blend_mode_hard_light` is
 //!     better than `hard_light_blend_mode`. This makes it easier to inspect the reference
@@ -37,6 +39,8 @@ mod compose;
 mod filter;
 mod glyph;
 mod gradient;
+#[cfg(not(target_arch = "wasm32"))]
+mod hybrid;
 mod image;
 mod issues;
 mod layer;
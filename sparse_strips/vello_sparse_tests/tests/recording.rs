@@ -7,7 +7,7 @@ use vello_common::color::palette::css::{
     REBECCA_PURPLE,
 };
 use vello_common::kurbo::BezPath;
-use vello_common::kurbo::{Affine, Rect};
+use vello_common::kurbo::{Affine, Rect, Stroke};
 use vello_common::recording::Recording;
 use vello_dev_macros::vello_test;
 
@@ -119,6 +119,34 @@ fn glyph_recording_outside_transform(ctx: &mut impl Renderer) {
     ctx.execute_recording(&recording);
 }
 
+/// Same as `glyphs_outlined`, but recorded. The outline carries its own stroke and paint, so
+/// the fill must still use the ones that are current in the recording.
+#[vello_test(width = 230, height = 180)]
+fn recording_glyphs_outlined(ctx: &mut impl Renderer) {
+    let font_size: f32 = 50_f32;
+    let (font, glyphs) = layout_glyphs_roboto("Vello", font_size);
+
+    let mut recording = Recording::new();
+    ctx.record(&mut recording, |ctx| {
+        ctx.set_paint(REBECCA_PURPLE);
+        for (i, width) in [1.0, 4.0, 8.0].into_iter().enumerate() {
+            ctx.set_transform(Affine::translate((
+                10.0 + 20.0 * i as f64,
+                50.0 + 55.0 * i as f64,
+            )));
+            ctx.glyph_run(&font)
+                .font_size(font_size)
+                .stroke_glyphs_with(&Stroke::new(width), ORANGE, glyphs.iter().copied());
+            ctx.glyph_run(&font)
+                .font_size(font_size)
+                .fill_glyphs(glyphs.iter().copied());
+        }
+    });
+
+    ctx.prepare_recording(&mut recording);
+    ctx.execute_recording(&recording);
+}
+
 #[vello_test(width = 50, height = 50)]
 fn recording_is_executed_at_recorded_transform(ctx: &mut impl Renderer) {
     ctx.set_transform(Affine::translate((10., 10.)));
@@ -215,6 +243,40 @@ fn recording_can_be_cleared(ctx: &mut impl Renderer) {
     ctx.execute_recording(&recording);
 }
 
+/// A clipped group drawn from a recording on the left and directly on the right, which must
+/// look the same.
+#[vello_test(width = 100, height = 50)]
+fn recording_clip_layer(ctx: &mut impl Renderer) {
+    fn triangle() -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to((25.0, 2.0));
+        path.line_to((2.0, 48.0));
+        path.line_to((48.0, 48.0));
+        path.close_path();
+        path
+    }
+
+    let mut recording = Recording::new();
+    ctx.record(&mut recording, |ctx| {
+        ctx.push_clip_layer(&triangle());
+        ctx.set_paint(ORANGE);
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 50.0, 30.0));
+        ctx.set_paint(REBECCA_PURPLE);
+        ctx.fill_rect(&Rect::new(0.0, 30.0, 50.0, 50.0));
+        ctx.pop_layer();
+    });
+    ctx.prepare_recording(&mut recording);
+    ctx.execute_recording(&recording);
+
+    ctx.set_transform(Affine::translate((50.0, 0.0)));
+    ctx.push_clip_layer(&triangle());
+    ctx.set_paint(ORANGE);
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 50.0, 30.0));
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.fill_rect(&Rect::new(0.0, 30.0, 50.0, 50.0));
+    ctx.pop_layer();
+}
+
 #[vello_test(width = 50, height = 50)]
 fn recording_is_executed_with_multiple_transforms(ctx: &mut impl Renderer) {
     ctx.set_transform(Affine::translate((15., 15.)));
@@ -45,6 +45,7 @@ pub(crate) trait Renderer: Sized {
     fn push_clip_layer(&mut self, path: &BezPath);
     fn push_clip_path(&mut self, path: &BezPath);
     fn push_blend_layer(&mut self, blend_mode: BlendMode);
+    fn push_isolated_layer(&mut self, blend_mode: BlendMode);
     fn push_opacity_layer(&mut self, opacity: f32);
     fn push_mask_layer(&mut self, mask: Mask);
     fn push_filter_layer(&mut self, filter: Filter);
@@ -120,7 +121,7 @@ impl Renderer for RenderContext {
         mask: Option<Mask>,
         filter: Option<Filter>,
     ) {
-        Self::push_layer(self, clip_path, blend_mode, opacity, mask, filter);
+        Self::push_layer(self, clip_path, blend_mode, opacity, mask, filter, false);
     }
 
     fn flush(&mut self) {
@@ -139,6 +140,10 @@ impl Renderer for RenderContext {
         Self::push_blend_layer(self, blend_mode);
     }
 
+    fn push_isolated_layer(&mut self, blend_mode: BlendMode) {
+        Self::push_isolated_layer(self, blend_mode);
+    }
+
     fn push_opacity_layer(&mut self, opacity: f32) {
         Self::push_opacity_layer(self, opacity);
     }
@@ -317,8 +322,8 @@ impl Renderer for HybridRenderer {
         self.scene.fill_rect(rect);
     }
 
-    fn fill_blurred_rounded_rect(&mut self, _: &Rect, _: f32, _: f32) {
-        unimplemented!()
+    fn fill_blurred_rounded_rect(&mut self, rect: &Rect, radius: f32, std_dev: f32) {
+        self.scene.fill_blurred_rounded_rect(rect, radius, std_dev);
     }
 
     fn stroke_rect(&mut self, rect: &Rect) {
@@ -338,7 +343,7 @@ impl Renderer for HybridRenderer {
         filter: Option<Filter>,
     ) {
         self.scene
-            .push_layer(clip, blend_mode, opacity, mask, filter);
+            .push_layer(clip, blend_mode, opacity, mask, filter, false);
     }
 
     fn flush(&mut self) {}
@@ -353,11 +358,16 @@ impl Renderer for HybridRenderer {
 
     fn push_blend_layer(&mut self, blend_mode: BlendMode) {
         self.scene
-            .push_layer(None, Some(blend_mode), None, None, None);
+            .push_layer(None, Some(blend_mode), None, None, None, false);
+    }
+
+    fn push_isolated_layer(&mut self, blend_mode: BlendMode) {
+        self.scene.push_isolated_layer(blend_mode);
     }
 
     fn push_opacity_layer(&mut self, opacity: f32) {
-        self.scene.push_layer(None, None, Some(opacity), None, None);
+        self.scene
+            .push_layer(None, None, Some(opacity), None, None, false);
     }
 
     fn push_mask_layer(&mut self, _: Mask) {
@@ -400,8 +410,8 @@ impl Renderer for HybridRenderer {
         self.scene.set_transform(transform);
     }
 
-    fn set_blend_mode(&mut self, _: BlendMode) {
-        unimplemented!()
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.scene.set_blend_mode(blend_mode);
     }
 
     fn set_aliasing_threshold(&mut self, aliasing_threshold: Option<u8>) {
@@ -621,8 +631,8 @@ impl Renderer for HybridRenderer {
         self.scene.fill_path(path);
     }
 
-    fn set_blend_mode(&mut self, _: BlendMode) {
-        unimplemented!()
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.scene.set_blend_mode(blend_mode);
     }
 
     fn stroke_path(&mut self, path: &BezPath) {
@@ -633,8 +643,8 @@ impl Renderer for HybridRenderer {
         self.scene.fill_rect(rect);
     }
 
-    fn fill_blurred_rounded_rect(&mut self, _: &Rect, _: f32, _: f32) {
-        unimplemented!()
+    fn fill_blurred_rounded_rect(&mut self, rect: &Rect, radius: f32, std_dev: f32) {
+        self.scene.fill_blurred_rounded_rect(rect, radius, std_dev);
     }
 
     fn stroke_rect(&mut self, rect: &Rect) {
@@ -658,7 +668,7 @@ impl Renderer for HybridRenderer {
         filter: Option<Filter>,
     ) {
         self.scene
-            .push_layer(clip, blend_mode, opacity, mask, filter);
+            .push_layer(clip, blend_mode, opacity, mask, filter, false);
     }
 
     fn flush(&mut self) {}
@@ -668,11 +678,17 @@ impl Renderer for HybridRenderer {
     }
 
     fn push_blend_layer(&mut self, mode: BlendMode) {
-        self.scene.push_layer(None, Some(mode), None, None, None);
+        self.scene
+            .push_layer(None, Some(mode), None, None, None, false);
+    }
+
+    fn push_isolated_layer(&mut self, blend_mode: BlendMode) {
+        self.scene.push_isolated_layer(blend_mode);
     }
 
     fn push_opacity_layer(&mut self, opacity: f32) {
-        self.scene.push_layer(None, None, Some(opacity), None, None);
+        self.scene
+            .push_layer(None, None, Some(opacity), None, None, false);
     }
 
     fn push_mask_layer(&mut self, _: Mask) {
@@ -154,12 +154,148 @@ pub(crate) fn get_ctx<T: Renderer>(
     ctx
 }
 
+/// All SIMD levels supported by the current machine, starting with the fallback level.
+pub(crate) fn available_levels() -> Vec<Level> {
+    #[allow(unused_mut, reason = "only mutated on some architectures")]
+    let mut levels = vec![Level::fallback()];
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.2") {
+            levels.push(Level::Sse4_2(unsafe {
+                vello_common::fearless_simd::Sse4_2::new_unchecked()
+            }));
+        }
+        if std::arch::is_x86_feature_detected!("avx2") && std::arch::is_x86_feature_detected!("fma")
+        {
+            levels.push(Level::Avx2(unsafe {
+                vello_common::fearless_simd::Avx2::new_unchecked()
+            }));
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    levels.extend(
+        Level::try_detect()
+            .and_then(|level| level.as_neon())
+            .map(Level::Neon),
+    );
+
+    #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+    levels.extend(
+        Level::try_detect()
+            .and_then(|level| level.as_wasm_simd128())
+            .map(Level::WasmSimd128),
+    );
+
+    levels
+}
+
 pub(crate) fn render_pixmap(ctx: &impl Renderer) -> Pixmap {
     let mut pixmap = Pixmap::new(ctx.width(), ctx.height());
     ctx.render_to_pixmap(&mut pixmap);
     pixmap
 }
 
+/// A renderer configuration that [`render_all_backends`] draws with.
+#[derive(Debug)]
+pub(crate) struct Backend {
+    /// Whether this is `vello_hybrid` rather than `vello_cpu`.
+    pub(crate) hybrid: bool,
+    level: Level,
+    num_threads: u16,
+    render_mode: RenderMode,
+}
+
+impl Backend {
+    /// The maximum difference per channel from exact results that this backend may produce.
+    pub(crate) fn tolerance(&self) -> u8 {
+        if self.hybrid { 1 } else { 0 }
+    }
+
+    pub(crate) fn create<T: Renderer>(&self, width: u16, height: u16) -> T {
+        T::new(
+            width,
+            height,
+            self.num_threads,
+            self.level,
+            self.render_mode,
+        )
+    }
+}
+
+/// `vello_cpu` at every available SIMD level, with both render modes and with and without
+/// multithreading, followed by `vello_hybrid`.
+pub(crate) fn all_backends() -> Vec<Backend> {
+    let mut backends = Vec::new();
+    for level in available_levels() {
+        for num_threads in [0, 3] {
+            for render_mode in [RenderMode::OptimizeQuality, RenderMode::OptimizeSpeed] {
+                backends.push(Backend {
+                    hybrid: false,
+                    level,
+                    num_threads,
+                    render_mode,
+                });
+            }
+        }
+    }
+    backends.push(Backend {
+        hybrid: true,
+        level: Level::fallback(),
+        num_threads: 0,
+        render_mode: RenderMode::OptimizeSpeed,
+    });
+    backends
+}
+
+/// Draw on a transparent background with every backend in [`all_backends`], returning each
+/// backend together with the image it rendered.
+///
+/// The drawing is expanded once per renderer type, so it can use the whole [`Renderer`] API.
+#[macro_export]
+macro_rules! render_all_backends {
+    ($width:expr, $height:expr, |$ctx:ident| $draw:expr) => {{
+        let mut renders = Vec::new();
+        for backend in $crate::util::all_backends() {
+            let pixmap = if backend.hybrid {
+                let mut ctx = backend.create::<$crate::renderer::HybridRenderer>($width, $height);
+                {
+                    let $ctx = &mut ctx;
+                    $draw;
+                }
+                $crate::renderer::Renderer::flush(&mut ctx);
+                $crate::util::render_pixmap(&ctx)
+            } else {
+                let mut ctx = backend.create::<vello_cpu::RenderContext>($width, $height);
+                {
+                    let $ctx = &mut ctx;
+                    $draw;
+                }
+                $crate::renderer::Renderer::flush(&mut ctx);
+                $crate::util::render_pixmap(&ctx)
+            };
+            renders.push((backend, pixmap));
+        }
+        renders
+    }};
+}
+
+/// Check that two drawings rendered with [`render_all_backends`] are identical on each backend.
+pub(crate) fn assert_renders_match(
+    actual: &[(Backend, Pixmap)],
+    expected: &[(Backend, Pixmap)],
+    description: &str,
+) {
+    assert_eq!(actual.len(), expected.len());
+    for ((backend, actual), (_, expected)) in actual.iter().zip(expected) {
+        assert!(
+            actual.data() == expected.data(),
+            "{description} ({backend:?}): renders differ"
+        );
+    }
+}
+
 pub(crate) fn miter_stroke_2() -> Stroke {
     Stroke {
         width: 2.0,